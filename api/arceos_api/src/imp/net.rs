@@ -47,8 +47,8 @@ pub fn ax_tcp_bind(socket: &AxTcpSocketHandle, addr: SocketAddr) -> AxResult {
     socket.0.bind(addr)
 }
 
-pub fn ax_tcp_listen(socket: &mut AxTcpSocketHandle, _backlog: usize) -> AxResult {
-    socket.0.listen()
+pub fn ax_tcp_listen(socket: &mut AxTcpSocketHandle, backlog: usize) -> AxResult {
+    socket.0.listen(backlog)
 }
 
 pub fn ax_tcp_accept(socket: &AxTcpSocketHandle) -> AxResult<(AxTcpSocketHandle, SocketAddr)> {
@@ -261,8 +261,9 @@ pub mod net {
         pub fn ax_tcp_connect(handle: &AxTcpSocketHandle, addr: SocketAddr) -> AxResult;
         /// Binds the TCP socket to the given address and port.
         pub fn ax_tcp_bind(socket: &AxTcpSocketHandle, addr: SocketAddr) -> AxResult;
-        /// Starts listening on the bound address and port.
-        pub fn ax_tcp_listen(socket: &mut AxTcpSocketHandle, _backlog: usize) -> AxResult;
+        /// Starts listening on the bound address and port, queueing at most
+        /// `backlog` pending connections.
+        pub fn ax_tcp_listen(socket: &mut AxTcpSocketHandle, backlog: usize) -> AxResult;
         /// Accepts a new connection on the TCP socket.
         ///
         /// This function will block the calling thread until a new TCP connection
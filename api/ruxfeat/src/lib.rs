@@ -28,6 +28,7 @@
 //!     - `sched_fifo`: Use the FIFO cooperative scheduler.
 //!     - `sched_rr`: Use the Round-robin preemptive scheduler.
 //!     - `sched_cfs`: Use the Completely Fair Scheduler (CFS) preemptive scheduler.
+//!     - `sched_prio`: Use the static priority preemptive scheduler, with `SCHED_FIFO` and `SCHED_RR`.
 //! - Upperlayer stacks (fs, net, display, rtc)
 //!     - `fs`: Enable file system support.
 //!     - `myfs`: Allow users to define their custom filesystems to override the default.
@@ -39,11 +39,12 @@ typedef struct {{
 
     fn gen_pthread_mutex(out_file: &str) -> std::io::Result<()> {
         // TODO: generate size and initial content automatically.
+        // An `axsync::Mutex<()>` followed by the owner, the type and the recursion count.
         let (mutex_size, mutex_init) = if cfg!(feature = "multitask") {
             if cfg!(feature = "smp") {
-                (6, "{0, 8, 0, 0, 0, 0}") // core::mem::transmute::<_, [usize; 6]>(axsync::Mutex::new(()))
+                (8, "{0, 8, 0, 0, 0, 0, 0, 0}") // core::mem::transmute::<_, [usize; 8]>(PthreadMutex::new(0))
             } else {
-                (5, "{8, 0, 0, 0, 0}") // core::mem::transmute::<_, [usize; 5]>(axsync::Mutex::new(()))
+                (7, "{8, 0, 0, 0, 0, 0, 0}") // core::mem::transmute::<_, [usize; 7]>(PthreadMutex::new(0))
             }
         } else {
             (1, "{0}")
@@ -68,11 +69,72 @@ typedef struct {{
         Ok(())
     }
 
+    fn gen_pthread_rwlock(out_file: &str) -> std::io::Result<()> {
+        // Same layout as the mutex: a wait queue followed by two `u32` counters.
+        let (rwlock_size, rwlock_init) = if cfg!(feature = "multitask") {
+            if cfg!(feature = "smp") {
+                (6, "{0, 8, 0, 0, 0, 0}")
+            } else {
+                (5, "{8, 0, 0, 0, 0}")
+            }
+        } else {
+            (1, "{0}")
+        };
+
+        let mut output = Vec::new();
+        writeln!(
+            output,
+            "// Generated by ruxos_posix_api/build.rs, DO NOT edit!"
+        )?;
+        writeln!(
+            output,
+            r#"
+typedef struct {{
+    long __l[{rwlock_size}];
+}} pthread_rwlock_t;
+
+#define PTHREAD_RWLOCK_INITIALIZER {{ .__l = {rwlock_init}}}
+"#
+        )?;
+        std::fs::write(out_file, output)?;
+        Ok(())
+    }
+
+    fn gen_pthread_barrier(out_file: &str) -> std::io::Result<()> {
+        // A wait queue followed by three `u32` fields.
+        let barrier_size = if cfg!(feature = "multitask") {
+            if cfg!(feature = "smp") {
+                7
+            } else {
+                6
+            }
+        } else {
+            1
+        };
+
+        let mut output = Vec::new();
+        writeln!(
+            output,
+            "// Generated by ruxos_posix_api/build.rs, DO NOT edit!"
+        )?;
+        writeln!(
+            output,
+            r#"
+typedef struct {{
+    long __l[{barrier_size}];
+}} pthread_barrier_t;
+"#
+        )?;
+        std::fs::write(out_file, output)?;
+        Ok(())
+    }
+
     fn gen_c_to_rust_bindings(in_file: &str, out_file: &str) {
         println!("cargo:rerun-if-changed={in_file}");
 
         let allow_types = [
             "stat",
+            "statfs",
             "size_t",
             "ssize_t",
             "off_t",
@@ -81,19 +143,29 @@ typedef struct {{
             "fd_set",
             "timeval",
             "itimerval",
+            "itimerspec",
+            "signalfd_siginfo",
             "pthread_t",
             "pthread_attr_t",
             "pthread_mutex_t",
             "pthread_mutexattr_t",
+            "pthread_rwlock_t",
+            "pthread_rwlockattr_t",
+            "pthread_barrier_t",
+            "pthread_barrierattr_t",
             "pthread_key_t",
             "pollfd",
             "nfds_t",
             "epoll_event",
             "iovec",
             "clockid_t",
+            "cpu_set_t",
+            "sched_param",
             "rlimit",
+            "rusage",
             "aibuf",
             "msghdr",
+            "cmsghdr",
             "pthread_cond_t",
             "pthread_condattr_t",
             "sysinfo",
@@ -106,6 +178,7 @@ typedef struct {{
             "stack_t",
             "ino_t",
             "dirent",
+            "open_how",
         ];
         let allow_vars = [
             "O_.*",
@@ -114,22 +187,44 @@ typedef struct {{
             "IPPROTO_.*",
             "FD_.*",
             "F_.*",
+            "FALLOC_FL_.*",
             "_SC_.*",
             "EPOLL_CTL_.*",
             "EPOLL.*",
+            "POLL.*",
             "RLIMIT_.*",
+            "RUSAGE_.*",
+            "WNOHANG",
+            "WUNTRACED",
+            "WCONTINUED",
             "EAI_.*",
             "MAXADDRS",
             "ITIMER_.*",
+            "TFD_.*",
+            "SFD_.*",
+            "CLOCK_.*",
             "SIG.*",
             "EINVAL",
             "CLONE_.*",
             "AT_.*",
+            "RESOLVE_.*",
             "MAP_.+",
             "PROT_.+",
             "MS_.+",
+            "MNT_.+",
+            "UMOUNT_NOFOLLOW",
             "MREMAP_.+",
+            "MADV_.+",
             "GRND_.*",
+            "PTHREAD_BARRIER_SERIAL_THREAD",
+            "PTHREAD_MUTEX_.*",
+            "MSG_.*",
+            "SOL_SOCKET",
+            "SCM_.*",
+            "SO_.*",
+            "SHUT_.*",
+            "IP_.*",
+            "TCP_NODELAY",
         ];
 
         #[derive(Debug)]
@@ -137,7 +232,7 @@ typedef struct {{
 
         impl bindgen::callbacks::ParseCallbacks for MyCallbacks {
             fn include_file(&self, fname: &str) {
-                if !fname.contains("ax_pthread_mutex.h") {
+                if !fname.contains("ax_pthread_") {
                     println!("cargo:rerun-if-changed={}", fname);
                 }
             }
@@ -166,5 +261,7 @@ typedef struct {{
 
     gen_pthread_mutex("../../ulib/ruxlibc/include/ax_pthread_mutex.h").unwrap();
     gen_pthread_cond("../../ulib/ruxlibc/include/ax_pthread_cond.h").unwrap();
+    gen_pthread_rwlock("../../ulib/ruxlibc/include/ax_pthread_rwlock.h").unwrap();
+    gen_pthread_barrier("../../ulib/ruxlibc/include/ax_pthread_barrier.h").unwrap();
     gen_c_to_rust_bindings("ctypes.h", "src/ctypes_gen.rs");
 }
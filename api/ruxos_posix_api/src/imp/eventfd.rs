@@ -0,0 +1,194 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use alloc::sync::Arc;
+use core::ffi::{c_int, c_uint};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use axerrno::{LinuxError, LinuxResult};
+use axio::PollState;
+use ruxfdtable::{FileLike, RuxStat};
+#[cfg(feature = "multitask")]
+use ruxtask::WaitQueue;
+
+use super::fd_ops::add_file_like;
+use crate::{ctypes, sys_fcntl};
+
+const EFD_SEMAPHORE: c_uint = 1;
+const EFD_NONBLOCK: c_uint = ctypes::O_NONBLOCK;
+const EFD_CLOEXEC: c_uint = ctypes::O_CLOEXEC;
+
+/// The largest value the counter may hold.
+const EFD_MAX: u64 = u64::MAX - 1;
+
+pub struct EventFd {
+    value: AtomicU64,
+    semaphore: bool,
+    nonblocking: AtomicBool,
+    #[cfg(feature = "multitask")]
+    wq: WaitQueue,
+}
+
+impl EventFd {
+    pub fn new(initval: u64, semaphore: bool, nonblocking: bool) -> Self {
+        Self {
+            value: AtomicU64::new(initval),
+            semaphore,
+            nonblocking: AtomicBool::new(nonblocking),
+            #[cfg(feature = "multitask")]
+            wq: WaitQueue::new(),
+        }
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.nonblocking.load(Ordering::Acquire)
+    }
+
+    /// Takes the whole counter, or 1 in semaphore mode. Returns `None` if it is zero.
+    fn try_take(&self) -> Option<u64> {
+        let semaphore = self.semaphore;
+        self.value
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |v| match v {
+                0 => None,
+                _ if semaphore => Some(v - 1),
+                _ => Some(0),
+            })
+            .ok()
+            .map(|old| if semaphore { 1 } else { old })
+    }
+
+    /// Adds `add` to the counter. Returns `false` if it would exceed `EFD_MAX`.
+    fn try_add(&self, add: u64) -> bool {
+        self.value
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |v| {
+                (add <= EFD_MAX - v).then_some(v + add)
+            })
+            .is_ok()
+    }
+
+    /// Blocks the current task until `ready` returns true.
+    fn wait_until(&self, ready: impl Fn() -> bool) {
+        #[cfg(feature = "multitask")]
+        self.wq.wait_until(ready);
+        #[cfg(not(feature = "multitask"))]
+        while !ready() {
+            crate::sys_sched_yield();
+        }
+    }
+
+    fn notify(&self) {
+        #[cfg(feature = "multitask")]
+        self.wq.notify_all(true);
+    }
+}
+
+impl FileLike for EventFd {
+    fn read(&self, buf: &mut [u8]) -> LinuxResult<usize> {
+        if buf.len() < core::mem::size_of::<u64>() {
+            return Err(LinuxError::EINVAL);
+        }
+        let ret = loop {
+            if let Some(ret) = self.try_take() {
+                break ret;
+            }
+            if self.is_nonblocking() {
+                return Err(LinuxError::EAGAIN);
+            }
+            self.wait_until(|| self.value.load(Ordering::Acquire) > 0);
+        };
+        self.notify();
+        buf[..8].copy_from_slice(&ret.to_ne_bytes());
+        Ok(8)
+    }
+
+    fn write(&self, buf: &[u8]) -> LinuxResult<usize> {
+        if buf.len() < core::mem::size_of::<u64>() {
+            return Err(LinuxError::EINVAL);
+        }
+        let add = u64::from_ne_bytes(buf[..8].try_into().unwrap());
+        if add == u64::MAX {
+            return Err(LinuxError::EINVAL);
+        }
+        // The counter must not exceed `EFD_MAX`, block until a reader drains it.
+        while !self.try_add(add) {
+            if self.is_nonblocking() {
+                return Err(LinuxError::EAGAIN);
+            }
+            self.wait_until(|| add <= EFD_MAX - self.value.load(Ordering::Acquire));
+        }
+        self.notify();
+        Ok(8)
+    }
+
+    fn flush(&self) -> LinuxResult {
+        Ok(())
+    }
+
+    fn stat(&self) -> LinuxResult<RuxStat> {
+        let st_mode = 0o600u32; // anonymous inode, rw-------
+        Ok(RuxStat::from(ctypes::stat {
+            st_ino: 1,
+            st_nlink: 1,
+            st_mode,
+            st_uid: 1000,
+            st_gid: 1000,
+            st_blksize: 4096,
+            ..Default::default()
+        }))
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn core::any::Any + Send + Sync> {
+        self
+    }
+
+    fn poll(&self) -> LinuxResult<PollState> {
+        let value = self.value.load(Ordering::Acquire);
+        Ok(PollState {
+            readable: value > 0,
+            writable: value < EFD_MAX,
+            ..Default::default()
+        })
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> LinuxResult {
+        self.nonblocking.store(nonblocking, Ordering::Release);
+        Ok(())
+    }
+}
+
+/// Create a file descriptor for event notification
+///
+/// Return the new file descriptor if succeed
+pub fn sys_eventfd(initval: c_uint, flags: c_int) -> c_int {
+    debug!("sys_eventfd <= initval: {}, flags: {:#x}", initval, flags);
+    syscall_body!(sys_eventfd, {
+        let flags = flags as c_uint;
+        if flags & !(EFD_SEMAPHORE | EFD_NONBLOCK | EFD_CLOEXEC) != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+
+        let efd = EventFd::new(
+            initval as u64,
+            flags & EFD_SEMAPHORE != 0,
+            flags & EFD_NONBLOCK != 0,
+        );
+        let fd = add_file_like(Arc::new(efd))? as c_int;
+        if flags & EFD_CLOEXEC != 0 {
+            sys_fcntl(fd, ctypes::F_SETFD as _, ctypes::FD_CLOEXEC as _);
+        }
+        Ok(fd)
+    })
+}
+
+/// `eventfd2` syscall, used by musl
+///
+/// Return the new file descriptor if succeed
+pub fn sys_eventfd2(initval: c_uint, flags: c_int) -> c_int {
+    sys_eventfd(initval, flags)
+}
@@ -1,5 +1,6 @@
-use crate::{ctypes::kstat, *};
+use crate::*;
 use alloc::{vec, vec::Vec};
+use axerrno::{LinuxError, LinuxResult};
 use core::ptr::null_mut;
 
 #[derive(Debug)]
@@ -16,33 +17,21 @@ impl ElfProg {
     /// read elf from `path`, and copy LOAD segments to a alloacated memory
     ///
     /// and load interp, if needed.
-    pub fn new(filepath: &str) -> Self {
+    pub fn new(filepath: &str) -> LinuxResult<Self> {
         debug!("sys_execve: new elf prog: {filepath}");
 
-        // open file
-        let fd = sys_open(filepath.as_ptr() as _, ctypes::O_RDWR as _, 0);
-
-        // get file size
-        let mut buf = ctypes::kstat {
-            ..Default::default()
-        };
-        sys_fstat(fd, &mut buf as *const kstat as *mut _);
-        let filesize = buf.st_size as usize;
-
         // read file
-        let mut file = vec![0u8; filesize];
-        sys_read(fd, file.as_mut_ptr() as *mut _, filesize);
-        debug!("sys_execve: read file size 0x{filesize:x}");
-        sys_close(fd);
+        let file = ruxfs::api::read(filepath)?;
+        debug!("sys_execve: read file size 0x{:x}", file.len());
 
         // parse elf
         let file = elf::ElfBytes::<elf::endian::AnyEndian>::minimal_parse(&file)
-            .expect("parse ELF failed");
+            .map_err(|_| LinuxError::ENOEXEC)?;
+        let segs = file.segments().ok_or(LinuxError::ENOEXEC)?;
 
         // get program's LOAD mem size
         let mut min_addr = 0;
         let mut max_addr = 0;
-        let segs = file.segments().unwrap();
         for seg in segs {
             if seg.p_type == elf::abi::PT_LOAD {
                 min_addr = min_addr.min(seg.p_vaddr);
@@ -59,7 +48,7 @@ impl ElfProg {
         // copy LOAD segments
         for seg in segs {
             if seg.p_type == elf::abi::PT_LOAD {
-                let data = file.segment_data(&seg).unwrap();
+                let data = file.segment_data(&seg).map_err(|_| LinuxError::ENOEXEC)?;
                 let dst = (seg.p_vaddr as usize + base) as *mut u8;
                 unsafe { dst.copy_from_nonoverlapping(data.as_ptr(), data.len()) };
             }
@@ -73,34 +62,34 @@ impl ElfProg {
 
         // parse interpreter
         let mut interp_path = vec![];
-        for seg in file.segments().unwrap() {
+        for seg in segs {
             if seg.p_type == elf::abi::PT_INTERP {
-                let data = file.segment_data(&seg).unwrap().to_vec();
+                let data = file
+                    .segment_data(&seg)
+                    .map_err(|_| LinuxError::ENOEXEC)?
+                    .to_vec();
                 interp_path = data;
                 break;
             }
         }
 
-        // get address of .text for debugging
-        let text_section_addr = base
-            + file
-                .section_header_by_name(".text")
-                .unwrap()
-                .unwrap()
-                .sh_offset as usize;
-        debug!(
-            "sys_execve: loaded ELF in 0x{:x}, .text is 0x{:x}",
-            base, text_section_addr
-        );
+        // get address of .text for debugging, the stripped files have none
+        if let Ok(Some(text)) = file.section_header_by_name(".text") {
+            debug!(
+                "sys_execve: loaded ELF in 0x{:x}, .text is 0x{:x}",
+                base,
+                base + text.sh_offset as usize
+            );
+        }
 
         // create retval
-        Self {
+        Ok(Self {
             base,
             entry,
             interp_path,
             phent: file.ehdr.e_phentsize as usize,
             phnum: file.ehdr.e_phnum as usize,
             phdr,
-        }
+        })
     }
 }
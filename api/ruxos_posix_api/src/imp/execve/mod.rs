@@ -2,22 +2,71 @@ mod auxv;
 mod load_elf;
 mod stack;
 
-use alloc::vec;
-use core::ffi::c_char;
+use alloc::{vec, vec::Vec};
+use core::ffi::{c_char, c_int, CStr};
+
+use axerrno::LinuxResult;
 
 use crate::{
     config,
     imp::stat::{sys_getgid, sys_getuid},
-    sys_getegid, sys_geteuid, sys_random,
+    sys_getegid, sys_geteuid,
     utils::char_ptr_to_str,
 };
 
 /// int execve(const char *pathname, char *const argv[], char *const envp[] );
-pub fn sys_execve(pathname: *const c_char, argv: usize, envp: usize) -> ! {
+///
+/// The program runs in the calling task, in the address space shared with
+/// the others: its segments are loaded in newly mapped memory, and it starts
+/// on a new stack where `pathname` and the strings of `argv` and `envp` are
+/// copied. So a parent suspended by `vfork`, e.g. in `posix_spawn`, resumes
+/// and may free them once they are copied.
+///
+/// It only returns on errors, e.g. `ENOENT` if the file does not exist, or
+/// `ENOEXEC` if it is not an ELF file.
+#[allow(unreachable_code)]
+pub fn sys_execve(pathname: *const c_char, argv: usize, envp: usize) -> c_int {
+    debug!(
+        "sys_execve <= pathname: {:?}, argv: {:#x}, envp: {:#x}",
+        char_ptr_to_str(pathname),
+        argv,
+        envp
+    );
+    syscall_body!(sys_execve, {
+        let (stack, sp, entry) = load_program(pathname, argv, envp)?;
+
+        // The fd table is shared with the parent of a child created by
+        // `vfork`, which still uses its close-on-exec files.
+        #[cfg(feature = "multitask")]
+        if ruxtask::current().in_main_program() {
+            super::fd_ops::close_cloexec_fds();
+        }
+        #[cfg(not(feature = "multitask"))]
+        super::fd_ops::close_cloexec_fds();
+
+        #[cfg(feature = "multitask")]
+        ruxtask::current().notify_exec();
+
+        // the program never returns, nor frees its stack
+        core::mem::forget(stack);
+        set_sp_and_jmp(sp, entry);
+        Ok(0)
+    })
+}
+
+/// Loads the program at `pathname` and its interpreter, if any, and creates
+/// its stack with the arguments `argv` and the environment `envp`.
+///
+/// Returns the stack, the address of its top, and the address to jump to.
+fn load_program(
+    pathname: *const c_char,
+    argv: usize,
+    envp: usize,
+) -> LinuxResult<(stack::Stack, usize, usize)> {
     use auxv::*;
 
-    let path = char_ptr_to_str(pathname).unwrap();
-    let prog = load_elf::ElfProg::new(path);
+    let path = char_ptr_to_str(pathname)?;
+    let prog = load_elf::ElfProg::new(path)?;
 
     // get entry
     let mut entry = prog.entry;
@@ -25,8 +74,8 @@ pub fn sys_execve(pathname: *const c_char, argv: usize, envp: usize) -> ! {
     // if interp is needed
     let mut at_base = 0;
     if !prog.interp_path.is_empty() {
-        let interp_path = char_ptr_to_str(prog.interp_path.as_ptr() as _).unwrap();
-        let interp_prog = load_elf::ElfProg::new(interp_path);
+        let interp_path = char_ptr_to_str(prog.interp_path.as_ptr() as _)?;
+        let interp_prog = load_elf::ElfProg::new(interp_path)?;
         entry = interp_prog.entry;
         at_base = interp_prog.base;
         debug!("sys_execve: INTERP base is {:x}", at_base);
@@ -37,9 +86,15 @@ pub fn sys_execve(pathname: *const c_char, argv: usize, envp: usize) -> ! {
 
     // non 8B info
     stack.push(&[0u8; 32], 16);
-    let rand = unsafe { [sys_random(), sys_random()] };
+    let mut rand = [0u8; 16];
+    chacha_rng::fill_bytes(&mut rand);
     let p_rand = stack.push(&rand, 16);
 
+    // strings, as the caller may free them
+    let execfn = stack.push(unsafe { CStr::from_ptr(pathname) }.to_bytes_with_nul(), 1);
+    let env_vec = unsafe { push_strings(&mut stack, envp) };
+    let arg_vec = unsafe { push_strings(&mut stack, argv) };
+
     // auxv
     // TODO: vdso
     let auxv = vec![
@@ -74,7 +129,7 @@ pub fn sys_execve(pathname: *const c_char, argv: usize, envp: usize) -> ! {
         AT_SECURE,
         0,
         AT_EXECFN,
-        pathname as usize,
+        execfn,
         AT_RANDOM,
         p_rand,
         AT_SYSINFO_EHDR,
@@ -85,28 +140,6 @@ pub fn sys_execve(pathname: *const c_char, argv: usize, envp: usize) -> ! {
         0,
     ];
 
-    // handle envs and args
-    let mut env_vec = vec![];
-    let mut arg_vec = vec![];
-
-    let mut envp = envp as *const usize;
-    unsafe {
-        while *envp != 0 {
-            env_vec.push(*envp);
-            envp = envp.add(1);
-        }
-        env_vec.push(0);
-    }
-
-    let mut argv = argv as *const usize;
-    unsafe {
-        while *argv != 0 {
-            arg_vec.push(*argv);
-            argv = argv.add(1);
-        }
-        arg_vec.push(0);
-    }
-
     // push
     stack.push(&auxv, 16);
     stack.push(&env_vec, 8);
@@ -119,7 +152,20 @@ pub fn sys_execve(pathname: *const c_char, argv: usize, envp: usize) -> ! {
         prog.entry
     );
 
-    set_sp_and_jmp(sp, entry);
+    Ok((stack, sp, entry))
+}
+
+/// Copies the strings of the null-terminated array `ptrs`, which may be
+/// null, onto `stack`, and returns their addresses followed by a null one.
+unsafe fn push_strings(stack: &mut stack::Stack, ptrs: usize) -> Vec<usize> {
+    let mut addrs = vec![];
+    let mut ptr = ptrs as *const *const c_char;
+    while !ptr.is_null() && !(*ptr).is_null() {
+        addrs.push(stack.push(CStr::from_ptr(*ptr).to_bytes_with_nul(), 1));
+        ptr = ptr.add(1);
+    }
+    addrs.push(0);
+    addrs
 }
 
 fn set_sp_and_jmp(sp: usize, entry: usize) -> ! {
@@ -11,7 +11,7 @@ use alloc::sync::Arc;
 use core::ffi::c_int;
 
 use axerrno::{LinuxError, LinuxResult};
-use ruxfdtable::{FileLike, RuxStat, RuxTimeSpec, FD_TABLE, RUX_FILE_LIMIT};
+use ruxfdtable::{FileLike, RuxStat, RuxTimeSpec, FD_TABLE};
 
 use super::stdio::{stdin, stdout};
 use crate::ctypes;
@@ -144,7 +144,17 @@ pub fn get_file_like(fd: c_int) -> LinuxResult<Arc<dyn FileLike>> {
 
 pub fn add_file_like(f: Arc<dyn FileLike>) -> LinuxResult<c_int> {
     let _exec = *MUST_EXEC;
-    Ok(FD_TABLE.write().add(f).ok_or(LinuxError::EMFILE)? as c_int)
+    let limit = super::resources::nofile_limit();
+    let mut table = FD_TABLE.write();
+    let fd = table.add(f).ok_or(LinuxError::EMFILE)?;
+    if fd >= limit {
+        // the lowest free fd is beyond the RLIMIT_NOFILE soft limit
+        let f = table.remove(fd);
+        drop(table);
+        drop(f);
+        return Err(LinuxError::EMFILE);
+    }
+    Ok(fd as c_int)
 }
 
 pub fn close_file_like(fd: c_int) -> LinuxResult {
@@ -153,10 +163,21 @@ pub fn close_file_like(fd: c_int) -> LinuxResult {
         .write()
         .remove(fd as usize)
         .ok_or(LinuxError::EBADF)?;
+    ruxfdtable::reset_stats(fd as usize);
+    ruxfdtable::set_cloexec(fd as usize, false);
     drop(f);
     Ok(())
 }
 
+/// Closes the file descriptors with the close-on-exec flag, when the program
+/// is replaced by `execve`.
+pub fn close_cloexec_fds() {
+    let _exec = *MUST_EXEC;
+    for fd in ruxfdtable::cloexec_fds() {
+        close_file_like(fd as c_int).ok();
+    }
+}
+
 /// Close a file by `fd`.
 pub fn sys_close(fd: c_int) -> c_int {
     debug!("sys_close <= {}", fd);
@@ -166,44 +187,90 @@ pub fn sys_close(fd: c_int) -> c_int {
     syscall_body!(sys_close, close_file_like(fd).map(|_| 0))
 }
 
-fn dup_fd(old_fd: c_int) -> LinuxResult<c_int> {
+/// Duplicates `old_fd` on the lowest free fd, which is closed on exec if
+/// `cloexec` is set.
+fn dup_fd(old_fd: c_int, cloexec: bool) -> LinuxResult<c_int> {
     let f = get_file_like(old_fd)?;
     let new_fd = add_file_like(f)?;
+    ruxfdtable::set_cloexec(new_fd as usize, cloexec);
+    Ok(new_fd)
+}
+
+/// Duplicates `old_fd` on `new_fd`, closing it first if it is open. The new
+/// fd is closed on exec if `cloexec` is set.
+fn dup2_fd(old_fd: c_int, new_fd: c_int, cloexec: bool) -> LinuxResult<c_int> {
+    if new_fd < 0 || new_fd as usize >= super::resources::nofile_limit() {
+        return Err(LinuxError::EBADF);
+    }
+    let f = get_file_like(old_fd)?;
+    close_file_like(new_fd).ok();
+    FD_TABLE
+        .write()
+        .add_at(new_fd as usize, f)
+        .ok_or(LinuxError::EMFILE)?;
+    ruxfdtable::set_cloexec(new_fd as usize, cloexec);
     Ok(new_fd)
 }
 
 /// Duplicate a file descriptor.
 pub fn sys_dup(old_fd: c_int) -> c_int {
     debug!("sys_dup <= {}", old_fd);
-    syscall_body!(sys_dup, dup_fd(old_fd))
+    syscall_body!(sys_dup, dup_fd(old_fd, false))
 }
 
 /// Duplicate a file descriptor, but it uses the file descriptor number specified in `new_fd`.
 ///
-/// TODO: `dup2` should forcibly close new_fd if it is already opened.
+/// `new_fd` is closed first if it is open, and is not closed on exec.
 pub fn sys_dup2(old_fd: c_int, new_fd: c_int) -> c_int {
     debug!("sys_dup2 <= old_fd: {}, new_fd: {}", old_fd, new_fd);
     syscall_body!(sys_dup2, {
         if old_fd == new_fd {
-            let r = sys_fcntl(old_fd, ctypes::F_GETFD as _, 0);
-            if r >= 0 {
-                return Ok(old_fd);
-            } else {
-                return Ok(r);
-            }
+            // only checks that `old_fd` is open, keeping its flags
+            get_file_like(old_fd)?;
+            return Ok(old_fd);
         }
-        if new_fd as usize >= RUX_FILE_LIMIT {
-            return Err(LinuxError::EBADF);
-        }
-        close_file_like(new_fd)?;
+        dup2_fd(old_fd, new_fd, false)
+    })
+}
 
-        let f = get_file_like(old_fd)?;
-        FD_TABLE
-            .write()
-            .add_at(new_fd as usize, f)
-            .ok_or(LinuxError::EMFILE)?;
+/// Truncate or extend the file referenced by `fd` to `length` bytes.
+///
+/// The extended part reads as zeros. It fails with `EINVAL` if the file can
+/// not be resized, like sockets and pipes.
+pub fn sys_ftruncate(fd: c_int, length: ctypes::off_t) -> c_int {
+    debug!("sys_ftruncate <= fd: {}, length: {}", fd, length);
+    syscall_body!(sys_ftruncate, {
+        if length < 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        get_file_like(fd)?.truncate(length as u64)?;
+        Ok(0)
+    })
+}
 
-        Ok(new_fd)
+/// Allocate the storage of the `len` bytes at `offset` of the file referenced
+/// by `fd`, extending it with zeros if it ends before.
+///
+/// With `FALLOC_FL_KEEP_SIZE` in `mode`, the file is not extended. It fails
+/// with `ENOSPC` if the filesystem is full, and with `EOPNOTSUPP` for the
+/// other modes.
+pub fn sys_fallocate(fd: c_int, mode: c_int, offset: ctypes::off_t, len: ctypes::off_t) -> c_int {
+    debug!(
+        "sys_fallocate <= fd: {}, mode: {:#x}, offset: {}, len: {}",
+        fd, mode, offset, len
+    );
+    syscall_body!(sys_fallocate, {
+        if offset < 0 || len <= 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        let file = get_file_like(fd)?;
+        match mode as u32 {
+            0 => file.fallocate(offset as u64, len as u64)?,
+            // The files can not have storage beyond their end.
+            ctypes::FALLOC_FL_KEEP_SIZE => {}
+            _ => return Err(LinuxError::EOPNOTSUPP),
+        }
+        Ok(0)
     })
 }
 
@@ -215,33 +282,23 @@ pub fn sys_dup3(old_fd: c_int, new_fd: c_int, flags: c_int) -> c_int {
         old_fd, new_fd, flags
     );
     syscall_body!(sys_dup3, {
-        if old_fd == new_fd {
+        if old_fd == new_fd || flags as u32 & !ctypes::O_CLOEXEC != 0 {
             return Err(LinuxError::EINVAL);
         }
-        sys_dup2(old_fd, new_fd);
-        if flags as u32 & ctypes::O_CLOEXEC != 0 {
-            sys_fcntl(
-                new_fd,
-                ctypes::F_SETFD as c_int,
-                ctypes::FD_CLOEXEC as usize,
-            );
-        }
-        Ok(new_fd)
+        dup2_fd(old_fd, new_fd, flags as u32 & ctypes::O_CLOEXEC != 0)
     })
 }
 
 /// Manipulate file descriptor.
 ///
-/// TODO: `SET/GET` command is ignored, hard-code stdin/stdout
+/// The only flag of a file descriptor (`F_GETFD` and `F_SETFD`) is
+/// `FD_CLOEXEC`, the status flags (`F_SETFL`) are limited to `O_NONBLOCK`.
 pub fn sys_fcntl(fd: c_int, cmd: c_int, arg: usize) -> c_int {
     debug!("sys_fcntl <= fd: {} cmd: {} arg: {}", fd, cmd, arg);
     syscall_body!(sys_fcntl, {
         match cmd as u32 {
-            ctypes::F_DUPFD => dup_fd(fd),
-            ctypes::F_DUPFD_CLOEXEC => {
-                // TODO: Change fd flags
-                dup_fd(fd)
-            }
+            ctypes::F_DUPFD => dup_fd(fd, false),
+            ctypes::F_DUPFD_CLOEXEC => dup_fd(fd, true),
             ctypes::F_SETFL => {
                 if fd == 0 || fd == 1 || fd == 2 {
                     return Ok(0);
@@ -263,15 +320,17 @@ pub fn sys_fcntl(fd: c_int, cmd: c_int, arg: usize) -> c_int {
                 }
                 Ok(flags as c_int)
             }
-            ctypes::F_SETFD => {
-                if arg == 0 || arg == 1 || arg == 2 {
-                    return Ok(0);
+            ctypes::F_GETFD => {
+                get_file_like(fd)?;
+                if ruxfdtable::is_cloexec(fd as usize) {
+                    Ok(ctypes::FD_CLOEXEC as c_int)
+                } else {
+                    Ok(0)
                 }
-                FD_TABLE
-                    .write()
-                    .add_at(arg, get_file_like(fd)?)
-                    .ok_or(LinuxError::EMFILE)?;
-                let _ = close_file_like(fd);
+            }
+            ctypes::F_SETFD => {
+                get_file_like(fd)?;
+                ruxfdtable::set_cloexec(fd as usize, arg & ctypes::FD_CLOEXEC as usize != 0);
                 Ok(0)
             }
             _ => {
@@ -8,21 +8,51 @@
  */
 
 use alloc::{borrow::Cow, string::String, sync::Arc};
-use core::ffi::{c_char, c_int, c_long, c_void, CStr};
+use core::ffi::{c_char, c_int, c_long, c_ulong, c_void, CStr};
+use core::mem::size_of;
 
-use axerrno::{LinuxError, LinuxResult};
+use axerrno::{AxError, LinuxError, LinuxResult};
 use axio::{PollState, SeekFrom};
-use axsync::Mutex;
+use axsync::{Mutex, MutexGuard};
 use ruxfdtable::{FileLike, RuxStat};
 use ruxfs::{
     api::set_current_dir,
-    fops::{DirEntry, OpenOptions},
+    fops::{DirEntry, FileAttr, OpenOptions},
 };
 
 use super::fd_ops::get_file_like;
 use crate::ctypes;
+use crate::utils::char_ptr_to_str;
 use alloc::vec::Vec;
 
+/// Owner of the files on the filesystems which do not record it, which is the
+/// only user.
+const DEFAULT_OWNER: u32 = 1000;
+
+/// Converts the attributes of a file to a `stat` structure.
+fn attr_to_stat(metadata: &FileAttr) -> ctypes::stat {
+    let ty = metadata.file_type() as u8;
+    let perm = metadata.perm().bits() as u32;
+    let st_mode = ((ty as u32) << 12) | perm;
+
+    // Inode of files, for musl dynamic linker.
+    // WARN: there will be collision for files with the same size.
+    // TODO: implement real inode.
+    let st_ino = metadata.size() + st_mode as u64;
+
+    ctypes::stat {
+        st_ino,
+        st_nlink: metadata.nlink() as _,
+        st_mode,
+        st_uid: metadata.uid().unwrap_or(DEFAULT_OWNER),
+        st_gid: metadata.gid().unwrap_or(DEFAULT_OWNER),
+        st_size: metadata.size() as _,
+        st_blocks: metadata.blocks() as _,
+        st_blksize: 512,
+        ..Default::default()
+    }
+}
+
 pub struct File {
     pub(crate) inner: Mutex<ruxfs::fops::File>,
 }
@@ -44,45 +74,50 @@ impl File {
             .downcast::<Self>()
             .map_err(|_| LinuxError::EINVAL)
     }
+
+    /// Locks the file to read, write or seek it, which fails with `EBADF` if
+    /// only its path is open.
+    fn lock_io(&self) -> LinuxResult<MutexGuard<'_, ruxfs::fops::File>> {
+        let file = self.inner.lock();
+        if file.is_path() {
+            return Err(LinuxError::EBADF);
+        }
+        Ok(file)
+    }
 }
 
 impl FileLike for File {
     fn read(&self, buf: &mut [u8]) -> LinuxResult<usize> {
-        Ok(self.inner.lock().read(buf)?)
+        Ok(self.lock_io()?.read(buf)?)
     }
 
     fn write(&self, buf: &[u8]) -> LinuxResult<usize> {
-        Ok(self.inner.lock().write(buf)?)
+        Ok(self.lock_io()?.write(buf)?)
     }
 
     fn flush(&self) -> LinuxResult {
-        Ok(self.inner.lock().flush()?)
+        Ok(self.lock_io()?.flush()?)
     }
 
-    fn stat(&self) -> LinuxResult<RuxStat> {
-        let metadata = self.inner.lock().get_attr()?;
-        let ty = metadata.file_type() as u8;
-        let perm = metadata.perm().bits() as u32;
-        let st_mode = ((ty as u32) << 12) | perm;
+    fn truncate(&self, size: u64) -> LinuxResult {
+        Ok(self.lock_io()?.truncate(size)?)
+    }
 
-        // Inode of files, for musl dynamic linker.
-        // WARN: there will be collision for files with the same size.
-        // TODO: implement real inode.
-        let st_ino = metadata.size() + st_mode as u64;
+    fn fallocate(&self, offset: u64, len: u64) -> LinuxResult {
+        Ok(self.lock_io()?.fallocate(offset, len)?)
+    }
 
-        let res = RuxStat::from(ctypes::stat {
-            st_ino,
-            st_nlink: 1,
-            st_mode,
-            st_uid: 1000,
-            st_gid: 1000,
-            st_size: metadata.size() as _,
-            st_blocks: metadata.blocks() as _,
-            st_blksize: 512,
-            ..Default::default()
-        });
+    fn pos(&self) -> u64 {
+        // not `lock`, as the file may be the one reading its own `fdinfo`
+        self.inner
+            .try_lock()
+            .and_then(|mut file| file.seek(SeekFrom::Current(0)).ok())
+            .unwrap_or(0)
+    }
 
-        Ok(res)
+    fn stat(&self) -> LinuxResult<RuxStat> {
+        let metadata = self.inner.lock().get_attr()?;
+        Ok(RuxStat::from(attr_to_stat(&metadata)))
     }
 
     fn into_any(self: Arc<Self>) -> Arc<dyn core::any::Any + Send + Sync> {
@@ -90,10 +125,11 @@ impl FileLike for File {
     }
 
     fn poll(&self) -> LinuxResult<PollState> {
-        Ok(PollState {
-            readable: true,
-            writable: true,
-        })
+        Ok(self.lock_io()?.poll()?)
+    }
+
+    fn ioctl(&self, cmd: usize, arg: usize) -> LinuxResult<c_int> {
+        Ok(self.lock_io()?.ioctl(cmd, arg)? as _)
     }
 
     fn set_nonblocking(&self, _nonblocking: bool) -> LinuxResult {
@@ -126,10 +162,16 @@ impl Directory {
 
 impl FileLike for Directory {
     fn read(&self, _buf: &mut [u8]) -> LinuxResult<usize> {
+        if self.inner.lock().is_path() {
+            return Err(LinuxError::EBADF);
+        }
         Err(LinuxError::EACCES)
     }
 
     fn write(&self, _buf: &[u8]) -> LinuxResult<usize> {
+        if self.inner.lock().is_path() {
+            return Err(LinuxError::EBADF);
+        }
         Err(LinuxError::EACCES)
     }
 
@@ -144,10 +186,10 @@ impl FileLike for Directory {
         let st_mode = ((ty as u32) << 12) | perm;
         Ok(RuxStat::from(ctypes::stat {
             st_ino: 1,
-            st_nlink: 1,
+            st_nlink: metadata.nlink() as _,
             st_mode,
-            st_uid: 1000,
-            st_gid: 1000,
+            st_uid: metadata.uid().unwrap_or(DEFAULT_OWNER),
+            st_gid: metadata.gid().unwrap_or(DEFAULT_OWNER),
             st_size: metadata.size() as _,
             st_blocks: metadata.blocks() as _,
             st_blksize: 512,
@@ -163,6 +205,7 @@ impl FileLike for Directory {
         Ok(PollState {
             readable: true,
             writable: true,
+            ..Default::default()
         })
     }
 
@@ -172,9 +215,14 @@ impl FileLike for Directory {
 }
 
 /// Convert open flags to [`OpenOptions`].
-fn flags_to_options(flags: c_int, _mode: ctypes::mode_t) -> OpenOptions {
+fn flags_to_options(flags: c_int, mode: ctypes::mode_t) -> OpenOptions {
     let flags = flags as u32;
     let mut options = OpenOptions::new();
+    if flags & ctypes::O_PATH != 0 {
+        // the access mode and the other flags are ignored
+        options.path(true);
+        return options;
+    }
     match flags & 0b11 {
         ctypes::O_RDONLY => options.read(true),
         ctypes::O_WRONLY => options.write(true),
@@ -192,9 +240,16 @@ fn flags_to_options(flags: c_int, _mode: ctypes::mode_t) -> OpenOptions {
     if flags & ctypes::O_CREAT != 0 {
         options.create(true);
     }
-    if flags & ctypes::O_EXEC != 0 {
+    if flags & ctypes::O_EXCL != 0 {
         options.create_new(true);
     }
+    if flags & ctypes::O_TMPFILE == ctypes::O_TMPFILE {
+        options.tmpfile(true);
+    }
+    if flags & ctypes::O_DIRECT != 0 {
+        options.direct(true);
+    }
+    options.mode(mode);
     options
 }
 
@@ -207,8 +262,14 @@ pub fn sys_open(filename: *const c_char, flags: c_int, mode: ctypes::mode_t) ->
     debug!("sys_open <= {:?} {:#o} {:#o}", filename, flags, mode);
     syscall_body!(sys_open, {
         let options = flags_to_options(flags, mode);
-        let file = ruxfs::fops::File::open(&filename?, &options)?;
-        File::new(file).add_to_fd_table()
+        let fd = if (flags as u32) & ctypes::O_PATH != 0 {
+            open_path_at(ctypes::AT_FDCWD, &filename?, &options)?
+        } else {
+            let file = ruxfs::fops::File::open(&filename?, &options)?;
+            File::new(file).add_to_fd_table()?
+        };
+        set_cloexec(fd, flags);
+        Ok(fd)
     })
 }
 
@@ -219,28 +280,124 @@ pub fn sys_openat(fd: usize, path: *const c_char, flags: c_int, mode: ctypes::mo
     debug!("sys_openat <= {}, {:?}, {:#o} {:#o}", fd, path, flags, mode);
     syscall_body!(sys_openat, {
         let options = flags_to_options(flags, mode);
-        if (flags as u32) & ctypes::O_DIRECTORY != 0 {
-            let dir = if fd == ctypes::AT_FDCWD {
-                ruxfs::fops::Directory::open_dir(&path?, &options)?
-            } else {
-                Directory::from_fd(fd)?
-                    .inner
-                    .lock()
-                    .open_dir_at(&path?, &options)?
-            };
-            Directory::new(dir).add_to_fd_table()
+        open_at(fd, &path?, flags, &options).inspect(|&fd| set_cloexec(fd, flags))
+    })
+}
+
+/// Open a file under a specific dir like `sys_openat`, with the flags, the
+/// mode and the restrictions on the resolution of the path in `how`.
+///
+/// Only `RESOLVE_BENEATH` and `RESOLVE_NO_SYMLINKS` are supported, and
+/// `RESOLVE_NO_MAGICLINKS` which has no effect as there are no magic links.
+/// The other `RESOLVE_*` flags fail with `EINVAL`.
+pub fn sys_openat2(
+    fd: c_int,
+    path: *const c_char,
+    how: *const ctypes::open_how,
+    size: ctypes::size_t,
+) -> c_int {
+    // the path stays relative to `fd`, to check that it does not leave it
+    let path = char_ptr_to_str(path);
+    debug!(
+        "sys_openat2 <= {}, {:?}, {:#x}, {}",
+        fd, path, how as usize, size
+    );
+    syscall_body!(sys_openat2, {
+        if how.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        if size < size_of::<ctypes::open_how>() {
+            return Err(LinuxError::EINVAL);
+        }
+        // the fields of newer versions of the struct are not supported
+        let extra = unsafe {
+            core::slice::from_raw_parts(
+                how.cast::<u8>().add(size_of::<ctypes::open_how>()),
+                size - size_of::<ctypes::open_how>(),
+            )
+        };
+        if extra.iter().any(|&b| b != 0) {
+            return Err(LinuxError::E2BIG);
+        }
+        let how = unsafe { how.read() };
+        let supported =
+            ctypes::RESOLVE_BENEATH | ctypes::RESOLVE_NO_SYMLINKS | ctypes::RESOLVE_NO_MAGICLINKS;
+        if how.flags > u32::MAX as u64 || how.resolve & !(supported as u64) != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        let flags = how.flags as c_int;
+        // the mode is only given for the new files
+        let creates = (flags as u32) & ctypes::O_CREAT != 0
+            || (flags as u32) & ctypes::O_TMPFILE == ctypes::O_TMPFILE;
+        if how.mode > 0o7777 || (how.mode != 0 && !creates) {
+            return Err(LinuxError::EINVAL);
+        }
+        let mut options = flags_to_options(flags, how.mode as ctypes::mode_t);
+        options.resolve_beneath(how.resolve & ctypes::RESOLVE_BENEATH as u64 != 0);
+        options.resolve_no_symlinks(how.resolve & ctypes::RESOLVE_NO_SYMLINKS as u64 != 0);
+        open_at(fd, path?, flags, &options).inspect(|&fd| set_cloexec(fd, flags))
+    })
+}
+
+/// Marks the new file descriptor `fd` to be closed on exec if `flags` has
+/// `O_CLOEXEC`.
+fn set_cloexec(fd: c_int, flags: c_int) {
+    ruxfdtable::set_cloexec(fd as usize, (flags as u32) & ctypes::O_CLOEXEC != 0);
+}
+
+/// Opens `path` under the directory `fd` with `options`, as a directory if
+/// `flags` has `O_DIRECTORY`, and inserts it into the file descriptor table.
+fn open_at(fd: c_int, path: &str, flags: c_int, options: &OpenOptions) -> LinuxResult<c_int> {
+    // `O_TMPFILE` includes `O_DIRECTORY`, but opens a file
+    let is_tmpfile = (flags as u32) & ctypes::O_TMPFILE == ctypes::O_TMPFILE;
+    if (flags as u32) & ctypes::O_DIRECTORY != 0 && !is_tmpfile {
+        let dir = if fd == ctypes::AT_FDCWD {
+            ruxfs::fops::Directory::open_dir(path, options)?
         } else {
-            let file = if fd == ctypes::AT_FDCWD {
-                ruxfs::fops::File::open(&path?, &options)?
-            } else {
-                Directory::from_fd(fd)?
-                    .inner
-                    .lock()
-                    .open_file_at(&path?, &options)?
+            Directory::from_fd(fd)?
+                .inner
+                .lock()
+                .open_dir_at(path, options)?
+        };
+        Directory::new(dir).add_to_fd_table()
+    } else if (flags as u32) & ctypes::O_PATH != 0 {
+        open_path_at(fd, path, options)
+    } else {
+        let file = if fd == ctypes::AT_FDCWD {
+            ruxfs::fops::File::open(path, options)?
+        } else {
+            Directory::from_fd(fd)?
+                .inner
+                .lock()
+                .open_file_at(path, options)?
+        };
+        File::new(file).add_to_fd_table()
+    }
+}
+
+/// Opens only the path of a file under the directory `fd` (`O_PATH`), as a
+/// directory if it is one, so that it can be the `dirfd` of the `*at` calls.
+fn open_path_at(fd: c_int, path: &str, options: &OpenOptions) -> LinuxResult<c_int> {
+    let anchor = if fd == ctypes::AT_FDCWD {
+        None
+    } else {
+        Some(Directory::from_fd(fd)?)
+    };
+    let dir = match &anchor {
+        Some(anchor) => anchor.inner.lock().open_dir_at(path, options),
+        None => ruxfs::fops::Directory::open_dir(path, options),
+    };
+    match dir {
+        Ok(dir) => Directory::new(dir).add_to_fd_table(),
+        Err(AxError::NotADirectory) => {
+            let file = match &anchor {
+                Some(anchor) => anchor.inner.lock().open_file_at(path, options)?,
+                None => ruxfs::fops::File::open(path, options)?,
             };
             File::new(file).add_to_fd_table()
         }
-    })
+        Err(e) => Err(e.into()),
+    }
 }
 
 /// Set the position of the file indicated by `fd`.
@@ -258,7 +415,8 @@ pub fn sys_pread64(
             return Err(LinuxError::EFAULT);
         }
         let dst = unsafe { core::slice::from_raw_parts_mut(buf as *mut u8, count) };
-        let size = File::from_fd(fd)?.inner.lock().read_at(pos as u64, dst)?;
+        let size = File::from_fd(fd)?.lock_io()?.read_at(pos as u64, dst)?;
+        ruxfdtable::count_read(fd as usize, size);
         Ok(size as ctypes::ssize_t)
     })
 }
@@ -278,7 +436,8 @@ pub fn sys_pwrite64(
             return Err(LinuxError::EFAULT);
         }
         let src = unsafe { core::slice::from_raw_parts_mut(buf as *mut u8, count) };
-        let size = File::from_fd(fd)?.inner.lock().write_at(pos as u64, src)?;
+        let size = File::from_fd(fd)?.lock_io()?.write_at(pos as u64, src)?;
+        ruxfdtable::count_write(fd as usize, size);
         Ok(size as ctypes::ssize_t)
     })
 }
@@ -295,25 +454,31 @@ pub fn sys_lseek(fd: c_int, offset: ctypes::off_t, whence: c_int) -> ctypes::off
             2 => SeekFrom::End(offset as _),
             _ => return Err(LinuxError::EINVAL),
         };
-        let off = File::from_fd(fd)?.inner.lock().seek(pos)?;
+        let off = File::from_fd(fd)?.lock_io()?.seek(pos)?;
         Ok(off)
     })
 }
 
 /// Synchronize a file's in-core state with storage device
 ///
-/// TODO
+/// The dirty blocks of the disk cache are written back to the device.
 pub unsafe fn sys_fsync(fd: c_int) -> c_int {
     debug!("sys_fsync <= fd: {}", fd);
-    syscall_body!(sys_fsync, Ok(0))
+    syscall_body!(sys_fsync, {
+        get_file_like(fd)?.flush()?;
+        Ok(0)
+    })
 }
 
 /// Synchronize a file's in-core state with storage device
 ///
-/// TODO
+/// The metadata is written back along with the data, as by [`sys_fsync`].
 pub unsafe fn sys_fdatasync(fd: c_int) -> c_int {
     debug!("sys_fdatasync <= fd: {}", fd);
-    syscall_body!(sys_fdatasync, Ok(0))
+    syscall_body!(sys_fdatasync, {
+        get_file_like(fd)?.flush()?;
+        Ok(0)
+    })
 }
 
 /// Get the file metadata by `path` and write into `buf`.
@@ -326,10 +491,7 @@ pub unsafe fn sys_stat(path: *const c_char, buf: *mut core::ffi::c_void) -> c_in
         if buf.is_null() {
             return Err(LinuxError::EFAULT);
         }
-        let mut options = OpenOptions::new();
-        options.read(true);
-        let file = ruxfs::fops::File::open(&path?, &options)?;
-        let st: ctypes::stat = File::new(file).stat()?.into();
+        let st = attr_to_stat(&ruxfs::fops::lookup(&path?)?.get_attr()?);
 
         #[cfg(not(feature = "musl"))]
         {
@@ -357,6 +519,31 @@ pub unsafe fn sys_stat(path: *const c_char, buf: *mut core::ffi::c_void) -> c_in
     })
 }
 
+/// Get the attributes of the filesystem which contains `path` and write
+/// them into `buf`. There are no inode counts nor filesystem IDs.
+///
+/// Return 0 if success.
+pub unsafe fn sys_statfs(path: *const c_char, buf: *mut ctypes::statfs) -> c_int {
+    let path = char_ptr_to_absolute_path(path);
+    debug!("sys_statfs <= {:?} {:#x}", path, buf as usize);
+    syscall_body!(sys_statfs, {
+        if buf.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        let info = ruxfs::fops::statfs(&path?)?;
+        let mut st: ctypes::statfs = unsafe { core::mem::zeroed() };
+        st.f_type = info.fs_type() as _;
+        st.f_bsize = info.block_size() as _;
+        st.f_frsize = info.block_size() as _;
+        st.f_blocks = info.blocks() as _;
+        st.f_bfree = info.blocks_free() as _;
+        st.f_bavail = info.blocks_free() as _;
+        st.f_namelen = 255;
+        unsafe { *buf = st };
+        Ok(0)
+    })
+}
+
 /// retrieve information about the file pointed by `fd`
 pub fn sys_fstat(fd: c_int, kst: *mut core::ffi::c_void) -> c_int {
     debug!("sys_fstat <= {} {:#x}", fd, kst as usize);
@@ -401,37 +588,93 @@ pub fn sys_fstat(fd: c_int, kst: *mut core::ffi::c_void) -> c_int {
 ///
 /// Return 0 if success.
 pub unsafe fn sys_lstat(path: *const c_char, buf: *mut ctypes::stat) -> ctypes::ssize_t {
-    let path = char_ptr_to_absolute_path(path);
-    debug!("sys_lstat <= {:?} {:#x}", path, buf as usize);
-    syscall_body!(sys_lstat, {
+    debug!(
+        "sys_lstat <= {:?} {:#x}",
+        char_ptr_to_absolute_path(path),
+        buf as usize
+    );
+    sys_fstatat(
+        ctypes::AT_FDCWD,
+        path,
+        buf,
+        ctypes::AT_SYMLINK_NOFOLLOW as c_int,
+    ) as _
+}
+
+/// Returns the metadata of the file at `path`, or of the file referred to by
+/// `fd` if `path` is empty and `AT_EMPTY_PATH` is set.
+///
+/// `path` must have been resolved from `fd` by [`char_ptr_to_path_at`].
+fn stat_at(fd: c_int, path: &str, flag: c_int) -> LinuxResult<ctypes::stat> {
+    let flag = flag as u32;
+    if flag & !(ctypes::AT_SYMLINK_NOFOLLOW | ctypes::AT_EMPTY_PATH) != 0 {
+        return Err(LinuxError::EINVAL);
+    }
+    if path.is_empty() {
+        if flag & ctypes::AT_EMPTY_PATH == 0 {
+            return Err(LinuxError::ENOENT);
+        }
+        if fd == ctypes::AT_FDCWD {
+            let cwd = ruxfs::api::current_dir()?;
+            return Ok(attr_to_stat(&ruxfs::fops::lookup(&cwd)?.get_attr()?));
+        }
+        return Ok(get_file_like(fd)?.stat()?.into());
+    }
+    let node = if flag & ctypes::AT_SYMLINK_NOFOLLOW != 0 {
+        ruxfs::fops::lookup_nofollow(path)?
+    } else {
+        ruxfs::fops::lookup(path)?
+    };
+    Ok(attr_to_stat(&node.get_attr()?))
+}
+
+/// Get the metadata of the file at `path` and write into `buf`.
+///
+/// With `AT_SYMLINK_NOFOLLOW`, the metadata of a symbolic link at `path` is
+/// returned instead of that of its target. With `AT_EMPTY_PATH`, an empty
+/// `path` refers to the file `fd` itself.
+///
+/// Return 0 if success.
+pub unsafe fn sys_fstatat(
+    fd: c_int,
+    path: *const c_char,
+    buf: *mut ctypes::stat,
+    flag: c_int,
+) -> c_int {
+    let path = char_ptr_to_path_at(fd, path);
+    debug!(
+        "sys_fstatat <= fd: {}, path: {:?}, flag: {:x}",
+        fd, path, flag
+    );
+    syscall_body!(sys_fstatat, {
         if buf.is_null() {
             return Err(LinuxError::EFAULT);
         }
-        unsafe { *buf = Default::default() }; // TODO
+        let st = stat_at(fd, &path?, flag)?;
+        unsafe { *buf = st };
         Ok(0)
     })
 }
 
 /// `newfstatat` used by A64
+///
+/// The flags are handled like [`sys_fstatat`].
 pub unsafe fn sys_newfstatat(
-    _fd: c_int,
+    fd: c_int,
     path: *const c_char,
     kst: *mut ctypes::kstat,
     flag: c_int,
 ) -> c_int {
-    let path = char_ptr_to_absolute_path(path);
+    let path = char_ptr_to_path_at(fd, path);
     debug!(
         "sys_newfstatat <= fd: {}, path: {:?}, flag: {:x}",
-        _fd, path, flag
+        fd, path, flag
     );
     syscall_body!(sys_newfstatat, {
         if kst.is_null() {
             return Err(LinuxError::EFAULT);
         }
-        let mut options = OpenOptions::new();
-        options.read(true);
-        let file = ruxfs::fops::File::open(&path?, &options)?;
-        let st = File::new(file).stat()?;
+        let st = stat_at(fd, &path?, flag)?;
         unsafe {
             (*kst).st_dev = st.st_dev;
             (*kst).st_ino = st.st_ino;
@@ -482,17 +725,13 @@ pub fn sys_rename(old: *const c_char, new: *const c_char) -> c_int {
 }
 
 /// Rename at certain directory pointed by `oldfd`
-///
-/// TODO: only support `oldfd`, `newfd` equals to AT_FDCWD
 pub fn sys_renameat(oldfd: c_int, old: *const c_char, newfd: c_int, new: *const c_char) -> c_int {
-    let old_path = char_ptr_to_absolute_path(old);
-    let new_path = char_ptr_to_absolute_path(new);
+    let old_path = char_ptr_to_path_at(oldfd, old);
+    let new_path = char_ptr_to_path_at(newfd, new);
     debug!(
         "sys_renameat <= oldfd: {}, old: {:?}, newfd: {}, new: {:?}",
         oldfd, old_path, newfd, new_path
     );
-    assert_eq!(oldfd, ctypes::AT_FDCWD as c_int);
-    assert_eq!(newfd, ctypes::AT_FDCWD as c_int);
     syscall_body!(sys_renameat, {
         ruxfs::api::rename(&old_path?, &new_path?)?;
         Ok(0)
@@ -519,45 +758,229 @@ pub fn sys_unlink(pathname: *const c_char) -> c_int {
     })
 }
 
+/// Truncate or extend the file at `path` to `length` bytes.
+///
+/// The extended part reads as zeros. It fails with `EISDIR` for directories.
+pub fn sys_truncate(path: *const c_char, length: ctypes::off_t) -> c_int {
+    let path = char_ptr_to_absolute_path(path);
+    debug!("sys_truncate <= path: {:?}, length: {}", path, length);
+    syscall_body!(sys_truncate, {
+        if length < 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        ruxfs::api::truncate(&path?, length as u64)?;
+        Ok(0)
+    })
+}
+
 /// deletes a name from the filesystem
 pub fn sys_unlinkat(fd: c_int, pathname: *const c_char, flags: c_int) -> c_int {
+    let path = char_ptr_to_path_at(fd, pathname);
     debug!(
         "sys_unlinkat <= fd: {}, pathname: {:?}, flags: {}",
-        fd,
-        char_ptr_to_absolute_path(pathname),
-        flags
+        fd, path, flags
     );
-    if flags as u32 & ctypes::AT_REMOVEDIR != 0 {
-        return sys_rmdir(pathname);
-    }
-    sys_unlink(pathname)
+    syscall_body!(sys_unlinkat, {
+        if flags as u32 & ctypes::AT_REMOVEDIR != 0 {
+            ruxfs::api::remove_dir(&path?)?;
+        } else {
+            ruxfs::api::remove_file(&path?)?;
+        }
+        Ok(0)
+    })
 }
 
 /// Creates a new, empty directory at the provided path.
+///
+/// Its permission is `mode` without the bits of the umask.
 pub fn sys_mkdir(pathname: *const c_char, mode: ctypes::mode_t) -> c_int {
-    // TODO: implement mode
     syscall_body!(sys_mkdir, {
         let path = char_ptr_to_absolute_path(pathname)?;
-        debug!("sys_mkdir <= path: {:?}, mode: {:?}", path, mode);
-        ruxfs::api::create_dir(&path)?;
+        debug!("sys_mkdir <= path: {:?}, mode: {:#o}", path, mode);
+        ruxfs::api::DirBuilder::new().mode(mode).create(&path)?;
         Ok(0)
     })
 }
 
 /// attempts to create a directory named pathname under directory pointed by `fd`
-///
-/// TODO: currently fd is not used
 pub fn sys_mkdirat(fd: c_int, pathname: *const c_char, mode: ctypes::mode_t) -> c_int {
+    let path = char_ptr_to_path_at(fd, pathname);
     debug!(
         "sys_mkdirat <= fd: {}, pathname: {:?}, mode: {:x?}",
-        fd,
-        char_ptr_to_absolute_path(pathname),
-        mode
+        fd, path, mode
     );
-    sys_mkdir(pathname, mode)
+    syscall_body!(sys_mkdirat, {
+        ruxfs::api::DirBuilder::new().mode(mode).create(&path?)?;
+        Ok(0)
+    })
 }
 
-/// Changes the ownership of the file referred to by the open file descriptor fd
+/// Converts `mode` to the permission bits stored on the nodes.
+///
+/// The set-user-ID, set-group-ID and sticky bits are not supported, and are
+/// dropped.
+fn mode_to_perm(mode: ctypes::mode_t) -> ruxfs::fops::FilePerm {
+    ruxfs::fops::FilePerm::from_bits_truncate(mode as u16)
+}
+
+/// Changes the permission mode of the file at `path`, following it if it is
+/// a symbolic link.
+///
+/// Return `EROFS` if the filesystem does not allow it.
+pub fn sys_chmod(path: *const c_char, mode: ctypes::mode_t) -> c_int {
+    let path = char_ptr_to_absolute_path(path);
+    debug!("sys_chmod <= path: {:?}, mode: {:#o}", path, mode);
+    syscall_body!(sys_chmod, {
+        ruxfs::fops::lookup(&path?)?.setattr(Some(mode_to_perm(mode)), None, None, None)?;
+        Ok(0)
+    })
+}
+
+/// Changes the permission mode of the file referred to by `fd`.
+pub fn sys_fchmod(fd: c_int, mode: ctypes::mode_t) -> c_int {
+    debug!("sys_fchmod <= fd: {}, mode: {:#o}", fd, mode);
+    syscall_body!(sys_fchmod, {
+        let perm = mode_to_perm(mode);
+        if let Ok(file) = File::from_fd(fd) {
+            file.inner.lock().set_perm(perm)?;
+        } else if let Ok(dir) = Directory::from_fd(fd) {
+            dir.inner.lock().set_perm(perm)?;
+        } else {
+            // pipes and sockets are not on a filesystem
+            get_file_like(fd)?;
+            return Err(LinuxError::EINVAL);
+        }
+        Ok(0)
+    })
+}
+
+/// Changes the permission mode of the file at `path`.
+///
+/// The mode of symbolic links can not be changed, so `AT_SYMLINK_NOFOLLOW`
+/// fails with `EOPNOTSUPP` if `path` is one.
+pub fn sys_fchmodat(fd: c_int, path: *const c_char, mode: ctypes::mode_t, flag: c_int) -> c_int {
+    let path = char_ptr_to_path_at(fd, path);
+    debug!(
+        "sys_fchmodat <= fd: {}, path: {:?}, mode: {:#o}, flag: {:x}",
+        fd, path, mode, flag
+    );
+    syscall_body!(sys_fchmodat, {
+        let flag = flag as u32;
+        if flag & !ctypes::AT_SYMLINK_NOFOLLOW != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        let path = path?;
+        let node = if flag & ctypes::AT_SYMLINK_NOFOLLOW != 0 {
+            let node = ruxfs::fops::lookup_nofollow(&path)?;
+            if node.get_attr()?.file_type().is_symlink() {
+                return Err(LinuxError::EOPNOTSUPP);
+            }
+            node
+        } else {
+            ruxfs::fops::lookup(&path)?
+        };
+        node.setattr(Some(mode_to_perm(mode)), None, None, None)?;
+        Ok(0)
+    })
+}
+
+/// Converts an owner ID passed to `chown`, where `-1` leaves it unchanged.
+fn owner_id(id: u32) -> Option<u32> {
+    (id != u32::MAX).then_some(id)
+}
+
+/// Changes the owner of the opened file or directory `fd`.
+fn chown_fd(fd: c_int, uid: ctypes::uid_t, gid: ctypes::gid_t) -> LinuxResult {
+    let (uid, gid) = (owner_id(uid), owner_id(gid));
+    if let Ok(file) = File::from_fd(fd) {
+        file.inner.lock().set_owner(uid, gid)?;
+    } else if let Ok(dir) = Directory::from_fd(fd) {
+        dir.inner.lock().set_owner(uid, gid)?;
+    } else {
+        // pipes and sockets are not on a filesystem
+        get_file_like(fd)?;
+        return Err(LinuxError::EINVAL);
+    }
+    Ok(())
+}
+
+/// Changes the owner of the file at `path`, or of `fd` itself if `path` is
+/// empty and `AT_EMPTY_PATH` is set.
+///
+/// `path` must have been resolved from `fd` by [`char_ptr_to_path_at`].
+fn chown_at(
+    fd: c_int,
+    path: &str,
+    uid: ctypes::uid_t,
+    gid: ctypes::gid_t,
+    flag: c_int,
+) -> LinuxResult {
+    let flag = flag as u32;
+    if flag & !(ctypes::AT_SYMLINK_NOFOLLOW | ctypes::AT_EMPTY_PATH) != 0 {
+        return Err(LinuxError::EINVAL);
+    }
+    if path.is_empty() {
+        if flag & ctypes::AT_EMPTY_PATH == 0 {
+            return Err(LinuxError::ENOENT);
+        }
+        if fd != ctypes::AT_FDCWD {
+            return chown_fd(fd, uid, gid);
+        }
+        let cwd = ruxfs::api::current_dir()?;
+        return chown_at(fd, &cwd, uid, gid, 0);
+    }
+    let node = if flag & ctypes::AT_SYMLINK_NOFOLLOW != 0 {
+        ruxfs::fops::lookup_nofollow(path)?
+    } else {
+        ruxfs::fops::lookup(path)?
+    };
+    node.setattr(None, owner_id(uid), owner_id(gid), None)?;
+    Ok(())
+}
+
+/// Changes the owner and group of the file at `path`, following it if it is
+/// a symbolic link. A `uid` or `gid` of `-1` is left unchanged.
+///
+/// There is no privilege model, so any owner can be set, as if the caller
+/// were privileged.
+pub fn sys_chown(path: *const c_char, uid: ctypes::uid_t, gid: ctypes::gid_t) -> c_int {
+    let path = char_ptr_to_absolute_path(path);
+    debug!("sys_chown <= path: {:?}, uid: {}, gid: {}", path, uid, gid);
+    syscall_body!(sys_chown, {
+        chown_at(ctypes::AT_FDCWD, &path?, uid, gid, 0)?;
+        Ok(0)
+    })
+}
+
+/// Changes the owner and group of the file at `path`, which is the symbolic
+/// link itself if `path` names one.
+///
+/// Like [`sys_chown`], any owner can be set.
+pub fn sys_lchown(path: *const c_char, uid: ctypes::uid_t, gid: ctypes::gid_t) -> c_int {
+    let path = char_ptr_to_absolute_path(path);
+    debug!("sys_lchown <= path: {:?}, uid: {}, gid: {}", path, uid, gid);
+    syscall_body!(sys_lchown, {
+        let flag = ctypes::AT_SYMLINK_NOFOLLOW as c_int;
+        chown_at(ctypes::AT_FDCWD, &path?, uid, gid, flag)?;
+        Ok(0)
+    })
+}
+
+/// Changes the owner and group of the file referred to by `fd`.
+///
+/// Like [`sys_chown`], any owner can be set.
+pub fn sys_fchown(fd: c_int, uid: ctypes::uid_t, gid: ctypes::gid_t) -> c_int {
+    debug!("sys_fchown <= fd: {}, uid: {}, gid: {}", fd, uid, gid);
+    syscall_body!(sys_fchown, {
+        chown_fd(fd, uid, gid)?;
+        Ok(0)
+    })
+}
+
+/// Changes the owner and group of the file at `path`.
+///
+/// `AT_SYMLINK_NOFOLLOW` and `AT_EMPTY_PATH` are handled like
+/// [`sys_fstatat`]. Like [`sys_chown`], any owner can be set.
 pub fn sys_fchownat(
     fd: c_int,
     path: *const c_char,
@@ -565,32 +988,104 @@ pub fn sys_fchownat(
     gid: ctypes::gid_t,
     flag: c_int,
 ) -> c_int {
+    let path = char_ptr_to_path_at(fd, path);
     debug!(
-        "sys_fchownat <= fd: {}, path: {:?}, uid: {}, gid: {}, flag: {}",
-        fd,
-        char_ptr_to_absolute_path(path),
-        uid,
-        gid,
-        flag
+        "sys_fchownat <= fd: {}, path: {:?}, uid: {}, gid: {}, flag: {:x}",
+        fd, path, uid, gid, flag
     );
-    syscall_body!(sys_fchownat, Ok(0))
+    syscall_body!(sys_fchownat, {
+        chown_at(fd, &path?, uid, gid, flag)?;
+        Ok(0)
+    })
+}
+
+/// Creates a hard link `newpath` to the file at `oldpath`.
+///
+/// The symbolic link at `oldpath` is linked itself, unless
+/// `AT_SYMLINK_FOLLOW` is set. With `AT_EMPTY_PATH`, an empty `oldpath`
+/// refers to the file `olddirfd` itself, e.g. one opened with `O_TMPFILE`.
+pub fn sys_linkat(
+    olddirfd: c_int,
+    oldpath: *const c_char,
+    newdirfd: c_int,
+    newpath: *const c_char,
+    flags: c_int,
+) -> c_int {
+    let old = char_ptr_to_path_at(olddirfd, oldpath);
+    let new = char_ptr_to_path_at(newdirfd, newpath);
+    debug!(
+        "sys_linkat <= {} {:?} {} {:?} {:#x}",
+        olddirfd, old, newdirfd, new, flags
+    );
+    syscall_body!(sys_linkat, {
+        let flags = flags as u32;
+        if flags & !(ctypes::AT_SYMLINK_FOLLOW | ctypes::AT_EMPTY_PATH) != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        let (old, new) = (old?, new?);
+        if old.is_empty() {
+            if flags & ctypes::AT_EMPTY_PATH == 0 {
+                return Err(LinuxError::ENOENT);
+            }
+            File::from_fd(olddirfd)?.inner.lock().link(&new)?;
+        } else {
+            let node = if flags & ctypes::AT_SYMLINK_FOLLOW != 0 {
+                ruxfs::fops::lookup(&old)?
+            } else {
+                ruxfs::fops::lookup_nofollow(&old)?
+            };
+            ruxfs::fops::link(&node, &new)?;
+        }
+        Ok(0)
+    })
+}
+
+/// Creates a hard link `newpath` to the file at `oldpath`.
+pub fn sys_link(oldpath: *const c_char, newpath: *const c_char) -> c_int {
+    sys_linkat(ctypes::AT_FDCWD, oldpath, ctypes::AT_FDCWD, newpath, 0)
+}
+
+/// Creates a symbolic link `linkpath` which points to `target`.
+pub fn sys_symlinkat(target: *const c_char, newdirfd: c_int, linkpath: *const c_char) -> c_int {
+    let path = char_ptr_to_path_at(newdirfd, linkpath);
+    debug!(
+        "sys_symlinkat <= target: {:?}, newdirfd: {}, linkpath: {:?}",
+        char_ptr_to_str(target),
+        newdirfd,
+        path
+    );
+    syscall_body!(sys_symlinkat, {
+        // the target is stored as is, and resolved when the link is followed
+        let target = char_ptr_to_str(target)?;
+        ruxfs::fops::symlink(target, &path?)?;
+        Ok(0)
+    })
 }
 
 /// read value of a symbolic link relative to directory file descriptor
-/// TODO: currently only support symlink, so return EINVAL anyway
+///
+/// The target is truncated if `buf` is too small, and it is not terminated
+/// by a null byte. Return `EINVAL` if `pathname` is not a symbolic link.
 pub fn sys_readlinkat(
     fd: c_int,
     pathname: *const c_char,
     buf: *mut c_char,
     bufsize: usize,
 ) -> usize {
-    let path = char_ptr_to_absolute_path(pathname);
+    let path = char_ptr_to_path_at(fd, pathname);
     debug!(
         "sys_readlinkat <= path = {:?}, fd = {:}, buf = {:p}, bufsize = {:}",
         path, fd, buf, bufsize
     );
     syscall_body!(sys_readlinkat, {
-        Err::<usize, LinuxError>(LinuxError::EINVAL)
+        if buf.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        let target = ruxfs::fops::read_link(&path?)?;
+        let len = target.len().min(bufsize);
+        let dst = unsafe { core::slice::from_raw_parts_mut(buf as *mut u8, len) };
+        dst.copy_from_slice(&target.as_bytes()[..len]);
+        Ok(len)
     })
 }
 
@@ -625,6 +1120,9 @@ pub unsafe fn sys_getdents64(
     syscall_body!(sys_getdents64, {
         let expect_entries = count / 280;
         let dir = Directory::from_fd(fd)?;
+        if dir.inner.lock().is_path() {
+            return Err(LinuxError::EBADF);
+        }
         let mut my_dirent: Vec<DirEntry> =
             (0..expect_entries).map(|_| DirEntry::default()).collect();
 
@@ -682,25 +1180,137 @@ pub unsafe fn sys_preadv(
 /// The mode is either the value F_OK, for the existence of the file,
 /// or a mask consisting of the bitwise OR of one or more of R_OK, W_OK, and X_OK, for the read, write, execute permissions.
 pub fn sys_faccessat(dirfd: c_int, pathname: *const c_char, mode: c_int, flags: c_int) -> c_int {
-    let path = char_ptr_to_absolute_path(pathname).unwrap();
+    let path = char_ptr_to_path_at(dirfd, pathname);
     debug!(
-        "sys_faccessat <= dirfd {} path {} mode {} flags {}",
+        "sys_faccessat <= dirfd {} path {:?} mode {} flags {}",
         dirfd, path, mode, flags
     );
     syscall_body!(sys_faccessat, {
         let mut options = OpenOptions::new();
         options.read(true);
-        let _file = ruxfs::fops::File::open(&path, &options)?;
+        let _file = ruxfs::fops::File::open(&path?, &options)?;
         Ok(0)
     })
 }
 
-/// changes the current working directory to the directory specified in path.
+/// Changes the current working directory to the directory at `path`.
+///
+/// It fails with `ENOTDIR` if `path` is not a directory, and with `EACCES`
+/// if it has no search permission.
 pub fn sys_chdir(path: *const c_char) -> c_int {
-    let p = char_ptr_to_absolute_path(path).unwrap();
-    debug!("sys_chdir <= path: {}", p);
+    let path = char_ptr_to_absolute_path(path);
+    debug!("sys_chdir <= path: {:?}", path);
     syscall_body!(sys_chdir, {
-        set_current_dir(&p)?;
+        set_current_dir(&path?)?;
+        Ok(0)
+    })
+}
+
+/// Changes the current working directory to the directory opened as `fd`.
+///
+/// The directory is found again by the path it was opened at, so it fails
+/// with `ENOENT` if it has been removed or renamed since. It fails with
+/// `ENOTDIR` if `fd` is not a directory.
+pub fn sys_fchdir(fd: c_int) -> c_int {
+    debug!("sys_fchdir <= fd: {}", fd);
+    syscall_body!(sys_fchdir, {
+        let dir = match Directory::from_fd(fd) {
+            Ok(dir) => dir,
+            Err(LinuxError::EINVAL) => return Err(LinuxError::ENOTDIR),
+            Err(e) => return Err(e),
+        };
+        let path = String::from(dir.inner.lock().path());
+        set_current_dir(&path)?;
+        Ok(0)
+    })
+}
+
+/// Disassociates parts of the execution context of the process.
+///
+/// Only `CLONE_NEWNS` is supported, which switches the calling thread to a
+/// copy of its mount namespace. The threads and processes it creates later
+/// start in the new namespace, the others keep the previous one.
+pub fn sys_unshare(flags: c_int) -> c_int {
+    debug!("sys_unshare <= flags: {:#x}", flags);
+    syscall_body!(sys_unshare, {
+        if flags as u32 & !ctypes::CLONE_NEWNS != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        if flags as u32 & ctypes::CLONE_NEWNS != 0 {
+            ruxfs::fops::unshare_mounts();
+        }
+        Ok(0)
+    })
+}
+
+/// Mounts a new filesystem of the type `fstype` on the directory `target`,
+/// with the options in the string `data`.
+///
+/// The filesystems are created in memory, so `source` is ignored: they are
+/// `tmpfs`, `proc`, `devtmpfs` and `sysfs`, the other types fail with
+/// `ENODEV`. With `MS_BIND`, the file or directory `source` is made visible
+/// at `target` instead, along with the filesystems mounted below it if
+/// `MS_REC` is also given. `MS_REMOUNT` and `MS_MOVE` are not supported and
+/// fail with `EINVAL`, the other flags are ignored.
+pub fn sys_mount(
+    source: *const c_char,
+    target: *const c_char,
+    fstype: *const c_char,
+    flags: c_ulong,
+    data: *const c_void,
+) -> c_int {
+    let target = char_ptr_to_absolute_path(target);
+    debug!(
+        "sys_mount <= source: {:?}, target: {:?}, fstype: {:?}, flags: {:#x}",
+        char_ptr_to_str(source),
+        target,
+        char_ptr_to_str(fstype),
+        flags
+    );
+    syscall_body!(sys_mount, {
+        let unsupported = ctypes::MS_REMOUNT | ctypes::MS_MOVE;
+        if flags & unsupported as c_ulong != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        let target = target?;
+        if flags & ctypes::MS_BIND as c_ulong != 0 {
+            let source = char_ptr_to_absolute_path(source)?;
+            let recursive = flags & ctypes::MS_REC as c_ulong != 0;
+            ruxfs::bind_mount(&source, &target, recursive)?;
+            return Ok(0);
+        }
+        if !ruxfs::fops::lookup(&target)?.get_attr()?.is_dir() {
+            return Err(LinuxError::ENOTDIR);
+        }
+        let data = if data.is_null() {
+            ""
+        } else {
+            char_ptr_to_str(data as *const c_char)?
+        };
+        ruxfs::mount(char_ptr_to_str(fstype)?, &target, data).map_err(|e| match e {
+            AxError::Unsupported => LinuxError::ENODEV,
+            e => e.into(),
+        })?;
+        Ok(0)
+    })
+}
+
+/// Unmounts the filesystem mounted on `target`.
+///
+/// It fails with `EBUSY` if another filesystem is mounted below it, or if
+/// the current directory is in it, unless `MNT_FORCE` or `MNT_DETACH` is
+/// given. The files which are still open in it remain usable. `MNT_EXPIRE`
+/// is not supported.
+pub fn sys_umount2(target: *const c_char, flags: c_int) -> c_int {
+    let target = char_ptr_to_absolute_path(target);
+    debug!("sys_umount2 <= target: {:?}, flags: {:#x}", target, flags);
+    syscall_body!(sys_umount2, {
+        let supported = ctypes::MNT_FORCE | ctypes::MNT_DETACH | ctypes::UMOUNT_NOFOLLOW;
+        if flags as u32 & !supported != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        let force = flags as u32 & (ctypes::MNT_FORCE | ctypes::MNT_DETACH) != 0;
+        ruxfs::umount(&target?, force)?;
         Ok(0)
     })
 }
@@ -736,3 +1346,25 @@ pub fn char_ptr_to_absolute_path<'a>(ptr: *const c_char) -> LinuxResult<Cow<'a,
         Ok(Cow::Borrowed(path))
     }
 }
+
+/// Gets the path from `ptr`, resolving it from the directory referred to by
+/// `dirfd` if it is relative, like the `*at` syscalls do.
+///
+/// Absolute and empty paths, and all paths if `dirfd` is `AT_FDCWD`, are
+/// handled like [`char_ptr_to_absolute_path`]. Otherwise `dirfd` must be an
+/// opened directory, and the path is resolved from the path at which it was
+/// opened.
+fn char_ptr_to_path_at<'a>(dirfd: c_int, ptr: *const c_char) -> LinuxResult<Cow<'a, str>> {
+    let path = char_ptr_to_str(ptr)?;
+    if dirfd == ctypes::AT_FDCWD || path.is_empty() || path.starts_with('/') {
+        return char_ptr_to_absolute_path(ptr);
+    }
+    let dir = get_file_like(dirfd)?
+        .into_any()
+        .downcast::<Directory>()
+        .map_err(|_| LinuxError::ENOTDIR)?;
+    let dir_path = String::from(dir.inner.lock().path());
+    Ok(Cow::Owned(ruxfs::api::canonicalize(
+        &(dir_path + "/" + path),
+    )?))
+}
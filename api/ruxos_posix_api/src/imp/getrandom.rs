@@ -7,13 +7,17 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-/// If adding `random-hw` to features, rand()/random() will try to use CPU instruction to generate the random.
-/// If CPU doesn't support instructions for random Generation, rand()/random() will return persudo random using LCG algorithm instead;
-/// Without feature `random-hw`, rand()/random() will simply return 64-bit persudo random generated from LCG algorithm by default.
-/// For x86_64, intel's CPU support `rdrand` instruction since IvyBridge, AMD's CPU support `rdrand` since Ryzen.
-/// For aarch64, resigter `rndr` is supported for part of CPU core since ARMv8.5. For more information, you can read: https://developer.arm.com/documentation/ddi0601/2023-06/AArch64-Registers/RNDR--Random-Number?lang=en
-/// We can determine whether the CPU supports this instruction by CPUID(x86_64) or ID_AA64ISAR0_EL1(aarch), which is implement in function `has_rdrand()`.
-/// As of now, riscv64 does not support generating random numbers through instructions.           
+//! Random numbers.
+//!
+//! `getrandom()` reads the kernel CSPRNG of [`chacha_rng`], which the runtime
+//! seeds at boot from the jitter of the timer and from the random number
+//! instruction of the CPU, if any (see [`ruxhal::random`]).
+//!
+//! `rand()`/`random()` are not meant to be secure. With the `random-hw`
+//! feature they return the random number instruction of the CPU if it has
+//! one, otherwise, and by default, 64-bit pseudo random numbers generated by
+//! an LCG.
+
 use core::ffi::{c_int, c_long, c_uint, c_void};
 
 use core::sync::atomic::{AtomicU64, Ordering::SeqCst};
@@ -22,9 +26,6 @@ use crate::ctypes::{size_t, ssize_t};
 
 use axerrno::LinuxError;
 
-#[cfg(all(target_arch = "x86_64", feature = "random-hw"))]
-use core::arch::x86_64::__cpuid;
-
 static SEED: AtomicU64 = AtomicU64::new(0xae_f3);
 
 /// Returns a 32-bit unsigned pseudo random interger using LCG.
@@ -52,63 +53,6 @@ fn srand_lcg(seed: u64) {
     SEED.store(seed - 1, SeqCst);
 }
 
-/// Checking if the CPU core is compatible with hardware random number instructions.
-#[cfg(feature = "random-hw")]
-fn has_rdrand() -> bool {
-    #[cfg(target_arch = "x86_64")]
-    {
-        unsafe { __cpuid(1).ecx & (1 << 30) != 0 }
-    }
-    #[cfg(target_arch = "aarch64")]
-    {
-        let mut id_aa64_isar0_el1: u64;
-        unsafe {
-            core::arch::asm!(
-                "mrs {},ID_AA64ISAR0_EL1",
-                out(reg) id_aa64_isar0_el1
-            )
-        }
-        id_aa64_isar0_el1 & (0b1111 << 60) == 0b0001 << 60
-    }
-    #[cfg(target_arch = "riscv64")]
-    {
-        false
-    }
-}
-
-/// Return 64-bit unsigned random interger using cpu instruction
-#[cfg(feature = "random-hw")]
-fn random_hw() -> u64 {
-    let mut _random: u64;
-
-    #[cfg(target_arch = "x86_64")]
-    {
-        unsafe {
-            core::arch::asm! {
-                "rdrand {0:r}",
-                out(reg) _random
-            }
-        }
-        _random
-    }
-
-    #[cfg(target_arch = "aarch64")]
-    {
-        unsafe {
-            core::arch::asm! {
-                "mrs {}, s3_3_c2_c4_0", // s3_3_c2_c4_0 is register `rndr`
-                out(reg) _random
-            }
-        }
-        _random
-    }
-
-    #[cfg(target_arch = "riscv64")]
-    {
-        panic!("riscv64 has no rdrand instructions")
-    }
-}
-
 /// Sets the seed for the 32-bit random number generator based on LCG.
 #[no_mangle]
 pub unsafe extern "C" fn sys_srand(_seed: c_uint) {
@@ -119,59 +63,54 @@ pub unsafe extern "C" fn sys_srand(_seed: c_uint) {
 #[no_mangle]
 pub unsafe extern "C" fn sys_rand() -> c_int {
     #[cfg(feature = "random-hw")]
-    {
-        match has_rdrand() {
-            true => (random_hw() >> 33) as c_int,
-            false => rand_lcg32() as c_int,
-        }
-    }
-    #[cfg(not(feature = "random-hw"))]
-    {
-        rand_lcg32() as c_int
+    if let Some(random) = ruxhal::random::hw_random() {
+        return (random >> 33) as c_int;
     }
+    rand_lcg32() as c_int
 }
 
 /// Returns a 64-bit unsigned random integer
 #[no_mangle]
 pub unsafe extern "C" fn sys_random() -> c_long {
     #[cfg(feature = "random-hw")]
-    {
-        match has_rdrand() {
-            true => (random_hw() >> 1) as c_long,
-            false => random_lcg64() as c_long,
-        }
-    }
-    #[cfg(not(feature = "random-hw"))]
-    {
-        random_lcg64() as c_long
+    if let Some(random) = ruxhal::random::hw_random() {
+        return (random >> 1) as c_long;
     }
+    random_lcg64() as c_long
 }
 
-/// Fills the buffer pointed to by buf with up to buflen random bytes.
+/// Fills the buffer pointed to by buf with buflen random bytes from the kernel
+/// CSPRNG.
+///
+/// `GRND_RANDOM` makes no difference, as there is a single pool. Until the
+/// CSPRNG is seeded, it waits, or fails with `EAGAIN` with `GRND_NONBLOCK`.
+/// `GRND_INSECURE` never waits.
 pub unsafe extern "C" fn sys_getrandom(buf: *mut c_void, buflen: size_t, flags: c_int) -> ssize_t {
     debug!(
         "sys_getrandom <= buf: {:?}, buflen: {}, flags: {}",
         buf, buflen, flags
     );
     syscall_body!(sys_getrandom, {
-        if buf.is_null() {
-            return Err(LinuxError::EFAULT);
+        use crate::ctypes::{GRND_INSECURE, GRND_NONBLOCK, GRND_RANDOM};
+        let flags = flags as u32;
+        if flags & !(GRND_NONBLOCK | GRND_RANDOM | GRND_INSECURE) != 0
+            || flags & (GRND_RANDOM | GRND_INSECURE) == GRND_RANDOM | GRND_INSECURE
+        {
+            return Err(LinuxError::EINVAL);
         }
-
-        match flags as _ {
-            crate::ctypes::GRND_NONBLOCK => {}
-            crate::ctypes::GRND_RANDOM => {}
-            _ => return Err(LinuxError::EINVAL),
+        if buflen == 0 {
+            return Ok(0);
         }
-        // fill the buffer 8 bytes at a time first, then fill the remaining bytes
-        let buflen_mod = buflen % (core::mem::size_of::<i64>() / core::mem::size_of::<u8>());
-        let buflen_div = buflen / (core::mem::size_of::<i64>() / core::mem::size_of::<u8>());
-        for i in 0..buflen_div {
-            *((buf as *mut u8 as *mut i64).add(i)) = sys_random() as i64;
+        if buf.is_null() {
+            return Err(LinuxError::EFAULT);
         }
-        for i in 0..buflen_mod {
-            *((buf as *mut u8).add(buflen - buflen_mod + i)) = sys_rand() as u8;
+        while flags & GRND_INSECURE == 0 && !chacha_rng::is_seeded() {
+            if flags & GRND_NONBLOCK != 0 {
+                return Err(LinuxError::EAGAIN);
+            }
+            crate::sys_sched_yield();
         }
+        chacha_rng::fill_bytes(core::slice::from_raw_parts_mut(buf as *mut u8, buflen));
         Ok(buflen as ssize_t)
     })
 }
@@ -28,7 +28,9 @@ pub fn sys_read(fd: c_int, buf: *mut c_void, count: usize) -> ctypes::ssize_t {
         let dst = unsafe { core::slice::from_raw_parts_mut(buf as *mut u8, count) };
         #[cfg(feature = "fd")]
         {
-            Ok(get_file_like(fd)?.read(dst)? as ctypes::ssize_t)
+            let len = get_file_like(fd)?.read(dst)?;
+            ruxfdtable::count_read(fd as usize, len);
+            Ok(len as ctypes::ssize_t)
         }
         #[cfg(not(feature = "fd"))]
         match fd {
@@ -51,7 +53,9 @@ pub fn sys_write(fd: c_int, buf: *const c_void, count: usize) -> ctypes::ssize_t
         let src = unsafe { core::slice::from_raw_parts(buf as *const u8, count) };
         #[cfg(feature = "fd")]
         {
-            Ok(get_file_like(fd)?.write(src)? as ctypes::ssize_t)
+            let len = get_file_like(fd)?.write(src)?;
+            ruxfdtable::count_write(fd as usize, len);
+            Ok(len as ctypes::ssize_t)
         }
         #[cfg(not(feature = "fd"))]
         match fd {
@@ -64,6 +68,9 @@ pub fn sys_write(fd: c_int, buf: *const c_void, count: usize) -> ctypes::ssize_t
 
 /// Writes `iocnt` buffers of data described by `iov` to the file associated with the file
 /// descriptor `fd`
+///
+/// The buffers are handed to the file at once, so that a TCP socket can send
+/// them in a single segment.
 pub unsafe fn sys_writev(fd: c_int, iov: *const ctypes::iovec, iocnt: c_int) -> ctypes::ssize_t {
     debug!("sys_writev <= fd: {}, iocnt: {}", fd, iocnt);
     syscall_body!(sys_writev, {
@@ -72,15 +79,30 @@ pub unsafe fn sys_writev(fd: c_int, iov: *const ctypes::iovec, iocnt: c_int) ->
         }
 
         let iovs = unsafe { core::slice::from_raw_parts(iov, iocnt as usize) };
-        let mut ret = 0;
-        for iov in iovs.iter() {
-            if iov.iov_base.is_null() {
-                continue;
+        #[cfg(feature = "fd")]
+        {
+            let bufs: alloc::vec::Vec<&[u8]> = iovs
+                .iter()
+                .filter(|iov| !iov.iov_base.is_null())
+                .map(|iov| unsafe {
+                    core::slice::from_raw_parts(iov.iov_base as *const u8, iov.iov_len)
+                })
+                .collect();
+            let len = get_file_like(fd)?.write_vectored(&bufs)?;
+            ruxfdtable::count_write(fd as usize, len);
+            Ok(len as ctypes::ssize_t)
+        }
+        #[cfg(not(feature = "fd"))]
+        {
+            let mut ret = 0;
+            for iov in iovs.iter() {
+                if iov.iov_base.is_null() {
+                    continue;
+                }
+                ret += sys_write(fd, iov.iov_base, iov.iov_len);
             }
-            ret += sys_write(fd, iov.iov_base, iov.iov_len);
+            Ok(ret)
         }
-
-        Ok(ret)
     })
 }
 /// Reads `iocnt` buffers from the file associated with the file descriptor `fd` into the
@@ -8,39 +8,52 @@
  */
 
 use crate::{ctypes, imp::fd_ops::get_file_like};
-use axerrno::{LinuxError, LinuxResult};
+use axerrno::LinuxError;
 use ruxhal::time::current_time;
 
 use core::{ffi::c_int, time::Duration};
 
-fn poll_all(fds: &mut [ctypes::pollfd]) -> LinuxResult<usize> {
-    let mut events_num = 0;
+/// Sets the `revents` of each entry of `fds`, and returns how many have some.
+///
+/// Negative fds are skipped, and fds which are not open get `POLLNVAL`.
+/// `POLLERR` and `POLLHUP` are reported whether they are requested or not.
+fn poll_all(fds: &mut [ctypes::pollfd]) -> usize {
+    let mut fds_num = 0;
 
     for pollfd_item in fds.iter_mut() {
-        let intfd = pollfd_item.fd;
-        let events = pollfd_item.events;
-        let revents = &mut pollfd_item.revents;
-        match get_file_like(intfd as c_int)?.poll() {
-            Err(_) => {
-                if (events & ctypes::EPOLLERR as i16) != 0 {
-                    *revents |= ctypes::EPOLLERR as i16;
-                    events_num += 1;
-                }
-            }
-            Ok(state) => {
-                if state.readable && (events & ctypes::EPOLLIN as i16 != 0) {
-                    *revents |= ctypes::EPOLLIN as i16;
-                    events_num += 1;
-                }
-
-                if state.writable && (events & ctypes::EPOLLOUT as i16 != 0) {
-                    *revents |= ctypes::EPOLLOUT as i16;
-                    events_num += 1;
+        pollfd_item.revents = 0;
+        if pollfd_item.fd < 0 {
+            continue;
+        }
+        let events = pollfd_item.events as u32 | ctypes::POLLERR | ctypes::POLLHUP;
+        let revents = match get_file_like(pollfd_item.fd) {
+            Err(_) => ctypes::POLLNVAL,
+            Ok(file) => match file.poll() {
+                Err(_) => ctypes::POLLERR,
+                Ok(state) => {
+                    let mut revents = 0;
+                    if state.readable {
+                        revents |= ctypes::POLLIN;
+                    }
+                    if state.writable {
+                        revents |= ctypes::POLLOUT;
+                    }
+                    if state.hangup {
+                        revents |= ctypes::POLLHUP;
+                    }
+                    if state.error {
+                        revents |= ctypes::POLLERR;
+                    }
+                    revents & events
                 }
-            }
+            },
+        };
+        if revents != 0 {
+            pollfd_item.revents = revents as i16;
+            fds_num += 1;
         }
     }
-    Ok(events_num)
+    fds_num
 }
 
 /// `ppoll` used by A64. Currently ignore signal
@@ -61,20 +74,30 @@ pub unsafe fn sys_ppoll(
 }
 
 /// Used to monitor multiple file descriptors for events
+///
+/// Waits until one of `fds` is ready, or for `timeout` milliseconds if it is
+/// not negative, and returns how many are ready, 0 on timeout. It fails with
+/// `EINTR` if a signal is delivered while waiting.
 pub unsafe fn sys_poll(fds: *mut ctypes::pollfd, nfds: ctypes::nfds_t, timeout: c_int) -> c_int {
     debug!("sys_poll <= nfds: {} timeout: {} ms", nfds, timeout);
 
     syscall_body!(sys_poll, {
-        if nfds == 0 {
-            return Err(LinuxError::EINVAL);
+        if nfds > 0 && fds.is_null() {
+            return Err(LinuxError::EFAULT);
         }
-        let fds = core::slice::from_raw_parts_mut(fds, nfds as usize);
+        let fds: &mut [ctypes::pollfd] = if nfds == 0 {
+            &mut []
+        } else {
+            core::slice::from_raw_parts_mut(fds, nfds as usize)
+        };
         let deadline = (!timeout.is_negative())
             .then(|| current_time() + Duration::from_millis(timeout as u64));
+        #[cfg(feature = "signal")]
+        let delivered = ruxruntime::Signal::delivered();
         loop {
             #[cfg(feature = "net")]
             ruxnet::poll_interfaces();
-            let fds_num = poll_all(fds)?;
+            let fds_num = poll_all(fds);
             if fds_num > 0 {
                 return Ok(fds_num as c_int);
             }
@@ -83,6 +106,10 @@ pub unsafe fn sys_poll(fds: *mut ctypes::pollfd, nfds: ctypes::nfds_t, timeout:
                 debug!("    timeout!");
                 return Ok(0);
             }
+            #[cfg(feature = "signal")]
+            if ruxruntime::Signal::delivered() != delivered {
+                return Err(LinuxError::EINTR);
+            }
             crate::sys_sched_yield();
         }
     })
@@ -8,6 +8,7 @@
  */
 
 use core::ffi::{c_int, c_void};
+use core::time::Duration;
 
 use axerrno::{LinuxError, LinuxResult};
 use ruxhal::time::current_time;
@@ -91,11 +92,14 @@ impl FdSets {
                 let fd = i + j;
                 match get_file_like(fd as _)?.poll() {
                     Ok(state) => {
-                        if state.readable && read_bits & bit != 0 {
+                        // a hangup or an error is reported by the call which gets it
+                        let readable = state.readable || state.hangup || state.error;
+                        let writable = state.writable || state.error;
+                        if readable && read_bits & bit != 0 {
                             unsafe { set_fd_set(res_read_fds, fd) };
                             res_num += 1;
                         }
-                        if state.writable && write_bits & bit != 0 {
+                        if writable && write_bits & bit != 0 {
                             unsafe { set_fd_set(res_write_fds, fd) };
                             res_num += 1;
                         }
@@ -117,6 +121,11 @@ impl FdSets {
 }
 
 /// Monitor multiple file descriptors, waiting until one or more of the file descriptors become "ready" for some class of I/O operation
+///
+/// Only the fds below `nfds` are checked, and the sets are updated to contain
+/// the ready ones. Like on Linux, `timeout` is updated to the time remaining.
+/// With no sets, it only sleeps. It fails with `EINTR` if a signal is
+/// delivered while waiting.
 pub unsafe fn sys_select(
     nfds: c_int,
     readfds: *mut ctypes::fd_set,
@@ -133,30 +142,82 @@ pub unsafe fn sys_select(
             return Err(LinuxError::EINVAL);
         }
         let nfds = (nfds as usize).min(FD_SETSIZE);
+        if let Some(t) = unsafe { timeout.as_ref() } {
+            if t.tv_sec < 0 || !(0..1_000_000).contains(&t.tv_usec) {
+                return Err(LinuxError::EINVAL);
+            }
+        }
         let deadline = unsafe { timeout.as_ref().map(|t| current_time() + (*t).into()) };
-        let fd_sets = FdSets::from(nfds, readfds, writefds, exceptfds);
-
-        unsafe {
-            zero_fd_set(readfds, nfds);
-            zero_fd_set(writefds, nfds);
-            zero_fd_set(exceptfds, nfds);
+        let res = if readfds.is_null() && writefds.is_null() && exceptfds.is_null() {
+            sleep(deadline)
+        } else {
+            wait(nfds, readfds, writefds, exceptfds, deadline)
+        };
+        if let (Some(ddl), Some(t)) = (deadline, unsafe { timeout.as_mut() }) {
+            *t = ddl.saturating_sub(current_time()).into();
         }
+        res
+    })
+}
 
-        loop {
-            #[cfg(feature = "net")]
-            ruxnet::poll_interfaces();
-            let res = fd_sets.poll_all(readfds, writefds, exceptfds)?;
-            if res > 0 {
-                return Ok(res);
+/// Sleeps until `deadline`, or forever if there is none.
+fn sleep(deadline: Option<Duration>) -> LinuxResult<c_int> {
+    match deadline {
+        Some(ddl) => {
+            #[cfg(feature = "multitask")]
+            ruxtask::sleep_until(ddl);
+            #[cfg(not(feature = "multitask"))]
+            ruxhal::time::busy_wait_until(ddl);
+            Ok(0)
+        }
+        None => {
+            #[cfg(feature = "signal")]
+            let delivered = ruxruntime::Signal::delivered();
+            loop {
+                #[cfg(feature = "signal")]
+                if ruxruntime::Signal::delivered() != delivered {
+                    return Err(LinuxError::EINTR);
+                }
+                crate::sys_sched_yield();
             }
+        }
+    }
+}
 
-            if deadline.map_or(false, |ddl| current_time() >= ddl) {
-                debug!("    timeout!");
-                return Ok(0);
-            }
-            crate::sys_sched_yield();
+/// Waits until one of the fds in the sets is ready, or until `deadline`.
+unsafe fn wait(
+    nfds: usize,
+    readfds: *mut ctypes::fd_set,
+    writefds: *mut ctypes::fd_set,
+    exceptfds: *mut ctypes::fd_set,
+    deadline: Option<Duration>,
+) -> LinuxResult<c_int> {
+    let fd_sets = FdSets::from(nfds, readfds, writefds, exceptfds);
+
+    zero_fd_set(readfds, nfds);
+    zero_fd_set(writefds, nfds);
+    zero_fd_set(exceptfds, nfds);
+
+    #[cfg(feature = "signal")]
+    let delivered = ruxruntime::Signal::delivered();
+    loop {
+        #[cfg(feature = "net")]
+        ruxnet::poll_interfaces();
+        let res = fd_sets.poll_all(readfds, writefds, exceptfds)?;
+        if res > 0 {
+            return Ok(res as c_int);
         }
-    })
+
+        if deadline.map_or(false, |ddl| current_time() >= ddl) {
+            debug!("    timeout!");
+            return Ok(0);
+        }
+        #[cfg(feature = "signal")]
+        if ruxruntime::Signal::delivered() != delivered {
+            return Err(LinuxError::EINTR);
+        }
+        crate::sys_sched_yield();
+    }
 }
 
 /// allows  a  program  to  monitor  multiple  file descriptors, waiting until one or more of the file descriptors become
@@ -7,7 +7,7 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use crate::{imp::fd_ops::get_file_like, sys_getpgid};
+use crate::imp::fd_ops::get_file_like;
 use axerrno::LinuxError;
 use core::ffi::c_int;
 
@@ -16,8 +16,11 @@ pub const TCGETS: usize = 0x5401;
 pub const TIOCGPGRP: usize = 0x540F;
 pub const TIOCSPGRP: usize = 0x5410;
 pub const TIOCGWINSZ: usize = 0x5413;
+pub const TIOCSWINSZ: usize = 0x5414;
+pub const FIONREAD: usize = 0x541B;
 pub const FIONBIO: usize = 0x5421;
 pub const FIOCLEX: usize = 0x5451;
+pub const SIOCATMARK: usize = 0x8905;
 
 #[derive(Clone, Copy, Default)]
 pub struct ConsoleWinSize {
@@ -27,8 +30,11 @@ pub struct ConsoleWinSize {
     pub ws_ypixel: u16,
 }
 
-/// ioctl implementation,
-/// currently only support fd = 1
+/// ioctl implementation.
+///
+/// `FIONBIO`, `FIONREAD`, `FIOCLEX` and `SIOCATMARK` are handled for any
+/// file, other requests are passed to the file itself, e.g. the terminal ones
+/// to stdio.
 pub fn sys_ioctl(fd: c_int, request: usize, data: usize) -> c_int {
     debug!("sys_ioctl <= fd: {}, request: {}", fd, request);
     syscall_body!(sys_ioctl, {
@@ -39,30 +45,30 @@ pub fn sys_ioctl(fd: c_int, request: usize, data: usize) -> c_int {
                 }
                 Ok(0)
             }
-            TIOCGWINSZ => {
-                let winsize = data as *mut ConsoleWinSize;
-                unsafe {
-                    *winsize = ConsoleWinSize::default();
+            FIONREAD => {
+                let file = get_file_like(fd)?;
+                match file.ioctl(request, data) {
+                    Err(LinuxError::ENOTTY) => {
+                        // Without an exact count, a readable file has at least one byte.
+                        let readable = file.poll()?.readable;
+                        unsafe {
+                            *(data as *mut c_int) = readable as c_int;
+                        }
+                        Ok(0)
+                    }
+                    res => res,
                 }
-                Ok(0)
-            }
-            TCGETS => {
-                debug!("sys_ioctl: tty TCGETS");
-                Ok(0)
             }
-            TIOCSPGRP => {
-                warn!("stdout pretend to be tty");
-                Ok(0)
-            }
-            TIOCGPGRP => {
-                warn!("stdout TIOCGPGRP, pretend to be have a tty process group.");
+            FIOCLEX => Ok(0),
+            #[cfg(feature = "net")]
+            SIOCATMARK => {
+                let at_mark = crate::imp::net::socket_at_mark(fd)?;
                 unsafe {
-                    *(data as *mut u32) = sys_getpgid(0) as _;
+                    *(data as *mut c_int) = at_mark as c_int;
                 }
                 Ok(0)
             }
-            FIOCLEX => Ok(0),
-            _ => Err(LinuxError::EINVAL),
+            _ => get_file_like(fd)?.ioctl(request, data),
         }
     })
 }
@@ -14,7 +14,7 @@ use core::{
     ffi::{c_int, c_void},
     ops::Bound,
 };
-use memory_addr::PAGE_SIZE_4K;
+use memory_addr::{align_up_4k, PAGE_SIZE_4K};
 use ruxhal::{mem::VirtAddr, paging::pte_update_page};
 
 use super::utils::{
@@ -190,6 +190,10 @@ pub fn sys_munmap(start: *mut c_void, len: ctypes::size_t) -> c_int {
 /// Changes the access protections for the calling process's memory pages
 /// containing any part of the address range in the interval [addr, addr+len).  
 /// addr must be aligned to a page boundary.
+///
+/// Return `ENOMEM` if any page in the range is not mapped. The PTEs of pages
+/// already populated are updated and flushed from TLB immediately, while the
+/// others will take the new `prot` when they are faulted in.
 pub fn sys_mprotect(start: *mut c_void, len: ctypes::size_t, prot: c_int) -> c_int {
     debug!(
         "sys_mprotect <= addr: {:p}, len: 0x{:x}, prot: {}",
@@ -252,7 +256,7 @@ pub fn sys_mprotect(start: *mut c_void, len: ctypes::size_t, prot: c_int) -> c_i
                 end - start,
                 counter
             );
-            return Err(LinuxError::EFAULT);
+            return Err(LinuxError::ENOMEM);
         }
 
         // upate PTEs if mprotect is successful.
@@ -317,6 +321,11 @@ pub fn sys_msync(start: *mut c_void, len: ctypes::size_t, flags: c_int) -> c_int
 }
 
 /// Remap a virtual memory address
+///
+/// The mapping is shrunk or grown in place if possible, otherwise it is moved
+/// to a new region with `MREMAP_MAYMOVE`, along with the mapped pages. The
+/// range `[old_addr, old_addr + old_size)` must lie in a single mapping, or
+/// `EFAULT` is returned.
 pub fn sys_mremap(
     old_addr: *mut c_void,
     old_size: ctypes::size_t,
@@ -341,12 +350,16 @@ pub fn sys_mremap(
                 != 0
             || ((flags & ctypes::MREMAP_FIXED != 0 || flags & ctypes::MREMAP_DONTUNMAP != 0)
                 && (flags & ctypes::MREMAP_MAYMOVE == 0))
+            || (flags & ctypes::MREMAP_FIXED != 0
+                && !VirtAddr::from(new_addr as usize).is_aligned(PAGE_SIZE_4K))
         {
             return Err(LinuxError::EINVAL);
         }
 
         let old_start = old_vaddr.as_usize();
-        let old_end = old_start + old_size;
+        let old_end = old_start + align_up_4k(old_size);
+        let new_size = align_up_4k(new_size);
+        let old_size = old_end - old_start;
 
         let mut consistent_vma: Option<Vma> = None; // structure to verify the consistent in the range of [old_start, old_end)
         let mut post_remove: Vec<usize> = Vec::new(); // vma should be removed if success.
@@ -355,9 +368,13 @@ pub fn sys_mremap(
         // collect and check vma alongside the range of [old_start, old_end).
         let mut node = vma_map.upper_bound_mut(Bound::Included(&old_start));
         while let Some(vma) = node.value_mut() {
-            if vma.start_addr > old_end {
+            if vma.start_addr >= old_end.max(old_start + 1) {
                 break;
             }
+            if vma.end_addr <= old_start {
+                node.move_next();
+                continue;
+            }
             // make sure of consistent_vma is continuous and consistent in both flags and prots.
             if let Some(ref mut inner_vma) = consistent_vma {
                 if inner_vma.end_addr == vma.start_addr
@@ -392,15 +409,25 @@ pub fn sys_mremap(
         }
 
         // check if consistent_vma full match the remapping memory.
-        if consistent_vma.is_none() {
-            return Err(LinuxError::EFAULT);
-        }
-        let mut old_vma = consistent_vma.unwrap();
-        if old_vma.end_addr < old_end {
+        let old_vma = consistent_vma.ok_or(LinuxError::EFAULT)?;
+        if old_vma.start_addr > old_start || old_vma.end_addr < old_end {
             return Err(LinuxError::EFAULT);
         }
+        // the part of `old_vma` mapped at [start, end) after remapping, which
+        // maps the file from the same offset as `old_start`.
+        let remapped_vma = |start: usize, end: usize| {
+            let mut vma = Vma::clone_from(&old_vma, start, end);
+            vma.offset += old_start - old_vma.start_addr;
+            vma
+        };
+        // the part of `old_vma` in [start, end) that keeps its address.
+        let kept_vma = |start: usize, end: usize| {
+            let mut vma = Vma::clone_from(&old_vma, start, end);
+            vma.offset += start - old_vma.start_addr;
+            vma
+        };
 
-        let opt_address = if flags & ctypes::MREMAP_FIXED != 0 || !new_addr.is_null() {
+        let opt_address = if flags & ctypes::MREMAP_FIXED != 0 {
             Some(new_addr as usize)
         } else {
             None
@@ -408,143 +435,156 @@ pub fn sys_mremap(
 
         if flags & ctypes::MREMAP_DONTUNMAP != 0 {
             // find a new region for new_start
-            if let Some(new_start) = find_free_region(&vma_map, opt_address, new_size) {
-                if flags & ctypes::MREMAP_FIXED != 0 && new_addr as usize != new_start {
-                    return Err(LinuxError::ENOMEM);
-                }
-
-                // copy the dirty page.
-                shift_mapped_page(
-                    old_vma.start_addr,
-                    old_vma.end_addr,
-                    new_start - old_vma.start_addr,
-                    true,
-                );
+            let new_start = find_free_region(&vma_map, opt_address, new_size)
+                .filter(|&start| opt_address.is_none() || opt_address == Some(start))
+                .ok_or(LinuxError::ENOMEM)?;
+
+            // move the pages, the old range is kept but refilled on the next fault.
+            shift_mapped_page(old_start, old_end, new_start.wrapping_sub(old_start), false);
+            vma_map.insert(new_start, remapped_vma(new_start, new_start + new_size));
+            return Ok(new_start as *mut c_void);
+        }
 
-                // copy the old to the new.
-                vma_map.insert(
-                    new_start,
-                    Vma::clone_from(&old_vma, new_start, new_start + new_size),
-                );
+        let in_place = flags & ctypes::MREMAP_FIXED == 0 || new_addr as usize == old_start;
+        // shrinking the original address does not require changing the mapped page.
+        if in_place && new_size <= old_size {
+            if new_size < old_size {
+                let new_end = old_start + new_size;
+                // delete the mapped and swapped page outside of new vma.
+                release_pages_mapped(new_end, old_end, true);
+                #[cfg(feature = "fs")]
+                release_pages_swaped(new_end, old_end);
 
-                // Remove the mapping debris and combine them into a large one.(for performance)
                 for key in post_remove {
                     vma_map.remove(&key);
                 }
-                vma_map.insert(old_vma.start_addr, old_vma);
-
-                return Ok(new_start as *mut c_void);
-            } else {
-                return Err(LinuxError::ENOMEM);
-            }
-        }
-
-        // shrinking the original address does not require changing the mapped page.
-        if old_size > new_size && (flags & ctypes::MREMAP_FIXED == 0 || new_addr == old_addr) {
-            let ret = old_vma.start_addr;
-            let new_end = old_start + new_size;
-            if old_vma.end_addr > old_end {
-                vma_map.insert(
-                    old_end,
-                    Vma::clone_from(&old_vma, old_end, old_vma.end_addr),
-                );
+                vma_map.insert(old_vma.start_addr, kept_vma(old_vma.start_addr, new_end));
+                if old_vma.end_addr > old_end {
+                    vma_map.insert(old_end, kept_vma(old_end, old_vma.end_addr));
+                }
             }
-            old_vma.end_addr = new_end;
-
-            // delete the mapped and swapped page outside of new vma.
-            release_pages_mapped(new_end, old_end, false);
-            #[cfg(feature = "fs")]
-            release_pages_swaped(new_end, old_end);
-
-            // vma_map.insert(old_vma.start_addr, old_vma);
-            return Ok(ret as *mut c_void);
+            return Ok(old_start as *mut c_void);
         }
         // expanding the original address does not require changing the mapped page.
-        else if old_size < new_size && (flags & ctypes::MREMAP_FIXED == 0 || new_addr == old_addr)
-        {
-            if old_vma.end_addr != old_end && flags & ctypes::MREMAP_MAYMOVE == 0 {
-                return Err(LinuxError::ENOMEM);
-            }
+        if in_place && old_vma.end_addr == old_end {
             // find the right region to expand them in orignal addr.
             let upper = vma_map
                 .lower_bound(Bound::Included(&old_end))
                 .key()
                 .unwrap_or(&VMA_END);
             if upper - old_end >= new_size - old_size {
-                let ret = old_vma.start_addr;
+                for key in post_remove {
+                    vma_map.remove(&key);
+                }
                 let new_end = old_start + new_size;
-                old_vma.end_addr = new_end;
-                vma_map.insert(old_vma.start_addr, old_vma);
-                return Ok(ret as *mut c_void);
+                vma_map.insert(old_vma.start_addr, kept_vma(old_vma.start_addr, new_end));
+                return Ok(old_start as *mut c_void);
             }
         }
 
         // try to move pages according to `new_addr`.
-        if flags & ctypes::MREMAP_MAYMOVE != 0 {
-            match find_free_region(&vma_map, opt_address, new_size) {
-                Some(vaddr) if vaddr == new_addr as usize || flags & ctypes::MREMAP_FIXED == 0 => {
-                    // Reserve excess memory before and after
-                    if old_vma.start_addr < old_start {
-                        vma_map.insert(
-                            old_vma.start_addr,
-                            Vma::clone_from(&old_vma, old_vma.start_addr, old_start),
-                        );
-                    }
-                    if old_vma.end_addr > old_end {
-                        vma_map.insert(
-                            old_end,
-                            Vma::clone_from(&old_vma, old_end, old_vma.end_addr),
-                        );
-                    }
-
-                    // Shift mapped memory in both `MEM_MAP` and `SWAP_MAP`.
-                    shift_mapped_page(
-                        old_vma.start_addr,
-                        old_vma.end_addr,
-                        vaddr - old_vma.start_addr,
-                        false,
-                    );
-
-                    // Insert the new vma.
-                    vma_map.insert(vaddr, Vma::clone_from(&old_vma, vaddr, vaddr + new_size));
-
-                    // remove the old vma deris.
-                    for key in post_remove {
-                        vma_map.remove(&key);
-                    }
-                    if old_vma.start_addr < old_start {
-                        vma_map.insert(
-                            old_end,
-                            Vma::clone_from(&old_vma, old_vma.start_addr, old_start),
-                        );
-                    }
-                    if old_vma.end_addr > old_end {
-                        vma_map.insert(
-                            old_end,
-                            Vma::clone_from(&old_vma, old_end, old_vma.end_addr),
-                        );
-                    }
-
-                    return Ok(vaddr as *mut c_void);
-                }
-                _ => {
-                    return Err(LinuxError::ENOMEM);
-                }
-            };
+        if flags & ctypes::MREMAP_MAYMOVE == 0 {
+            return Err(LinuxError::ENOMEM);
+        }
+        let new_start = find_free_region(&vma_map, opt_address, new_size)
+            .filter(|&start| opt_address.is_none() || opt_address == Some(start))
+            .ok_or(LinuxError::ENOMEM)?;
+
+        // the pages beyond the new size are released rather than moved.
+        let moved_end = old_end.min(old_start + new_size);
+        if moved_end < old_end {
+            release_pages_mapped(moved_end, old_end, true);
+            #[cfg(feature = "fs")]
+            release_pages_swaped(moved_end, old_end);
+        }
+        // Shift mapped memory in both `MEM_MAP` and `SWAP_MAP`.
+        shift_mapped_page(
+            old_start,
+            moved_end,
+            new_start.wrapping_sub(old_start),
+            false,
+        );
+
+        // remove the old vma and reserve excess memory before and after.
+        for key in post_remove {
+            vma_map.remove(&key);
+        }
+        if old_vma.start_addr < old_start {
+            vma_map.insert(old_vma.start_addr, kept_vma(old_vma.start_addr, old_start));
+        }
+        if old_vma.end_addr > old_end {
+            vma_map.insert(old_end, kept_vma(old_end, old_vma.end_addr));
         }
+        vma_map.insert(new_start, remapped_vma(new_start, new_start + new_size));
 
-        Err(LinuxError::ENOMEM)
+        Ok(new_start as *mut c_void)
     })
 }
 
 /// give advice about use of memory
 /// if success return 0, if error return -1
 ///
-/// TODO: implement this to improve performance.
+/// `MADV_DONTNEED` drops the pages in the range, so that anonymous pages read
+/// back as zero on the next access. `MADV_NORMAL`, `MADV_RANDOM`,
+/// `MADV_SEQUENTIAL`, `MADV_WILLNEED` and `MADV_FREE` are accepted as hints.
+/// Return `ENOMEM` if any page in the range is not mapped.
 pub fn sys_madvise(addr: *mut c_void, len: ctypes::size_t, advice: c_int) -> c_int {
     debug!(
         "sys_madvise <= addr: {:p}, len: {}, advice: {}",
         addr, len, advice
     );
-    syscall_body!(sys_madvise, Ok(0))
+    syscall_body!(sys_madvise, {
+        // transform C-type into rust-type
+        let start = addr as usize;
+        let end = align_up_4k(start + len);
+        if !VirtAddr::from(start).is_aligned(PAGE_SIZE_4K) {
+            return Err(LinuxError::EINVAL);
+        }
+        match advice as u32 {
+            ctypes::MADV_NORMAL
+            | ctypes::MADV_RANDOM
+            | ctypes::MADV_SEQUENTIAL
+            | ctypes::MADV_WILLNEED
+            | ctypes::MADV_FREE
+            | ctypes::MADV_DONTNEED => {}
+            _ => return Err(LinuxError::EINVAL),
+        }
+        if len == 0 {
+            return Ok(0);
+        }
+
+        let vma_map = VMA_MAP.lock();
+        let mut node = vma_map.upper_bound(Bound::Included(&start));
+        let mut counter = 0; // counter to check if all address in [start, start+len) is mapped.
+        while let Some(vma) = node.value() {
+            if vma.start_addr >= end {
+                break;
+            }
+            if let Some((overlapped_start, overlapped_end)) =
+                get_overlap((start, end), (vma.start_addr, vma.end_addr))
+            {
+                counter += overlapped_end - overlapped_start;
+            }
+            node.move_next();
+        }
+        if counter != end - start {
+            error!(
+                "madvise 0x{:x?} but only 0x{:x?} byte inside",
+                end - start,
+                counter
+            );
+            return Err(LinuxError::ENOMEM);
+        }
+        drop(vma_map);
+
+        // Only `MADV_DONTNEED` takes action, the others are just hints. Dropped
+        // pages are refilled with zero (or read from file) on the next fault,
+        // while dirty pages of a shared file mapping are written back first.
+        if advice as u32 == ctypes::MADV_DONTNEED {
+            release_pages_mapped(start, end, true);
+            #[cfg(feature = "fs")]
+            release_pages_swaped(start, end);
+        }
+        Ok(0)
+    })
 }
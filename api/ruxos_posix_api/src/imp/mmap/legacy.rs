@@ -119,11 +119,28 @@ pub fn sys_msync(start: *mut c_void, len: ctypes::size_t, flags: c_int) -> c_int
 /// give advice about use of memory
 /// if success return 0, if error return -1
 ///
-/// TODO: implement this
+/// Without paging the mapped memory can't be released, so `MADV_DONTNEED` only
+/// fills the range with zero. The other advices are ignored.
 pub fn sys_madvise(addr: *mut c_void, len: ctypes::size_t, advice: c_int) -> c_int {
     debug!(
         "sys_madvise <= addr: {:p}, len: {}, advice: {}",
         addr, len, advice
     );
-    syscall_body!(sys_madvise, Ok(0))
+    syscall_body!(sys_madvise, {
+        match advice as u32 {
+            ctypes::MADV_DONTNEED => {
+                if addr.is_null() {
+                    return Err(LinuxError::ENOMEM);
+                }
+                unsafe { (addr as *mut u8).write_bytes(0, len) };
+            }
+            ctypes::MADV_NORMAL
+            | ctypes::MADV_RANDOM
+            | ctypes::MADV_SEQUENTIAL
+            | ctypes::MADV_WILLNEED
+            | ctypes::MADV_FREE => {}
+            _ => return Err(LinuxError::EINVAL),
+        }
+        Ok(0)
+    })
 }
@@ -100,7 +100,7 @@ impl Vma {
             file: vma.file.clone(),
             offset: vma.offset,
             prot: vma.prot,
-            flags: vma.prot,
+            flags: vma.flags,
         }
     }
 }
@@ -325,6 +325,8 @@ pub(crate) fn release_pages_swaped(start: usize, end: usize) {
 
 /// shift mapped the page in both MEM_MAP and SWAPED_MAP.
 /// No page fault here should be guaranteed
+///
+/// `vma_offset` is added with wrapping, so pages can be shifted to a lower address as well.
 pub(crate) fn shift_mapped_page(start: usize, end: usize, vma_offset: usize, copy: bool) {
     let mut memory_map = MEM_MAP.lock();
     used_fs! {
@@ -371,8 +373,13 @@ pub(crate) fn shift_mapped_page(start: usize, end: usize, vma_offset: usize, cop
             }
             (fake_vaddr, flags)
         };
-        do_pte_map(VirtAddr::from(start + vma_offset), fake_vaddr, flags).unwrap();
-        memory_map.insert(start + vma_offset, page_info.clone());
+        do_pte_map(
+            VirtAddr::from(start.wrapping_add(vma_offset)),
+            fake_vaddr,
+            flags,
+        )
+        .unwrap();
+        memory_map.insert(start.wrapping_add(vma_offset), page_info.clone());
     }
 
     used_fs! {
@@ -392,7 +399,7 @@ pub(crate) fn shift_mapped_page(start: usize, end: usize, vma_offset: usize, cop
                 read_from(&SWAP_FILE, rw_buffer.as_mut_ptr(), off_in_swap as u64, PAGE_SIZE_4K);
                 write_into(&SWAP_FILE, rw_buffer.as_mut_ptr(), off_ptr as u64, PAGE_SIZE_4K);
             }
-            swaped_map.insert(start + vma_offset, off_in_swap);
+            swaped_map.insert(start.wrapping_add(vma_offset), off_in_swap);
         }
     }
 }
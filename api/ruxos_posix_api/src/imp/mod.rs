@@ -20,6 +20,8 @@ pub mod sys;
 pub mod task;
 pub mod time;
 
+#[cfg(feature = "fd")]
+pub mod eventfd;
 #[cfg(feature = "fs")]
 pub mod execve;
 #[cfg(feature = "fd")]
@@ -40,6 +42,12 @@ pub mod pipe;
 pub mod pthread;
 #[cfg(feature = "signal")]
 pub mod signal;
+#[cfg(all(feature = "signal", feature = "fd"))]
+pub mod signalfd;
+#[cfg(feature = "fd")]
+pub mod timerfd;
+#[cfg(feature = "net")]
+pub mod unix;
 
 /// Invalid syscall
 pub fn sys_invalid(id: core::ffi::c_int) -> core::ffi::c_int {
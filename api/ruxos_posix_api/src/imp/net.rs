@@ -11,19 +11,27 @@ use alloc::{sync::Arc, vec, vec::Vec};
 use core::ffi::{c_char, c_int, c_void};
 use core::mem::size_of;
 use core::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
+use core::time::Duration;
 
-use axerrno::{LinuxError, LinuxResult};
+use axerrno::{AxError, LinuxError, LinuxResult};
 use axio::PollState;
 use axsync::Mutex;
 use ruxfdtable::{FileLike, RuxStat};
+#[cfg(feature = "smoltcp")]
+use ruxnet::RawSocket;
 use ruxnet::{TcpSocket, UdpSocket};
 
-use crate::ctypes;
+use super::fd_ops::close_file_like;
+use super::unix::{self, UnixSocket};
 use crate::utils::char_ptr_to_str;
+use crate::{ctypes, sys_fcntl};
 
 pub enum Socket {
     Udp(Mutex<UdpSocket>),
     Tcp(Mutex<TcpSocket>),
+    #[cfg(feature = "smoltcp")]
+    Raw(Mutex<RawSocket>),
+    Unix(UnixSocket),
 }
 
 impl Socket {
@@ -38,17 +46,36 @@ impl Socket {
             .map_err(|_| LinuxError::EINVAL)
     }
 
-    fn send(&self, buf: &[u8]) -> LinuxResult<usize> {
+    fn send(&self, buf: &[u8], flags: c_int) -> LinuxResult<usize> {
         match self {
+            Socket::Udp(_) if flags & ctypes::MSG_OOB as c_int != 0 => Err(LinuxError::EOPNOTSUPP),
             Socket::Udp(udpsocket) => Ok(udpsocket.lock().send(buf)?),
+            Socket::Tcp(tcpsocket) if flags & ctypes::MSG_OOB as c_int != 0 => {
+                Ok(tcpsocket.lock().send_urgent(buf)?)
+            }
             Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().send(buf)?),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(_) if flags & ctypes::MSG_OOB as c_int != 0 => Err(LinuxError::EOPNOTSUPP),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => Ok(rawsocket.lock().send(buf)?),
+            Socket::Unix(_) if flags & ctypes::MSG_OOB as c_int != 0 => Err(LinuxError::EOPNOTSUPP),
+            Socket::Unix(unixsocket) => unixsocket.send(buf, flags),
         }
     }
 
     fn recv(&self, buf: &mut [u8], flags: i32) -> LinuxResult<usize> {
         match self {
-            Socket::Udp(udpsocket) => Ok(udpsocket.lock().recv_from(buf).map(|e| e.0)?),
+            Socket::Udp(_) if flags & ctypes::MSG_OOB as c_int != 0 => Err(LinuxError::EOPNOTSUPP),
+            Socket::Udp(udpsocket) => Ok(udpsocket.lock().recv_from_flags(buf, flags)?.0),
             Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().recv(buf, flags)?),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(_) if flags & (ctypes::MSG_OOB | ctypes::MSG_PEEK) as c_int != 0 => {
+                Err(LinuxError::EOPNOTSUPP)
+            }
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => Ok(rawsocket.lock().recv_from_flags(buf, flags)?.0),
+            Socket::Unix(_) if flags & ctypes::MSG_OOB as c_int != 0 => Err(LinuxError::EOPNOTSUPP),
+            Socket::Unix(unixsocket) => unixsocket.recv(buf, flags),
         }
     }
 
@@ -56,20 +83,46 @@ impl Socket {
         match self {
             Socket::Udp(udpsocket) => Ok(udpsocket.lock().poll()?),
             Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().poll()?),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => Ok(rawsocket.lock().poll()?),
+            Socket::Unix(unixsocket) => Ok(unixsocket.poll()),
         }
     }
 
+    /// Returns the address the socket is bound to, the wildcard `0.0.0.0:0`
+    /// if it is not bound yet.
+    ///
+    /// The addresses of `AF_UNIX` sockets are not IP ones, see [`UnixSocket::local_addr`].
     fn local_addr(&self) -> LinuxResult<SocketAddr> {
-        match self {
-            Socket::Udp(udpsocket) => Ok(udpsocket.lock().local_addr()?),
-            Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().local_addr()?),
+        let res = match self {
+            Socket::Udp(udpsocket) => udpsocket.lock().local_addr(),
+            Socket::Tcp(tcpsocket) => tcpsocket.lock().local_addr(),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => rawsocket.lock().local_addr(),
+            Socket::Unix(_) => return Err(LinuxError::EAFNOSUPPORT),
+        };
+        match res {
+            Err(AxError::NotConnected) => {
+                Ok(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)))
+            }
+            res => Ok(res?),
         }
     }
 
     fn peer_addr(&self) -> LinuxResult<SocketAddr> {
         match self {
             Socket::Udp(udpsocket) => Ok(udpsocket.lock().peer_addr()?),
-            Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().peer_addr()?),
+            Socket::Tcp(tcpsocket) => {
+                let addr = tcpsocket.lock().peer_addr()?;
+                // A listening TCP socket has no peer.
+                if addr.port() == 0 {
+                    return Err(LinuxError::ENOTCONN);
+                }
+                Ok(addr)
+            }
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => Ok(rawsocket.lock().peer_addr()?),
+            Socket::Unix(_) => Err(LinuxError::EAFNOSUPPORT),
         }
     }
 
@@ -77,6 +130,9 @@ impl Socket {
         match self {
             Socket::Udp(udpsocket) => Ok(udpsocket.lock().bind(addr)?),
             Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().bind(addr)?),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => Ok(rawsocket.lock().bind(addr)?),
+            Socket::Unix(_) => Err(LinuxError::EAFNOSUPPORT),
         }
     }
 
@@ -84,6 +140,147 @@ impl Socket {
         match self {
             Socket::Udp(udpsocket) => Ok(udpsocket.lock().connect(addr)?),
             Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().connect(addr)?),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => Ok(rawsocket.lock().connect(addr)?),
+            Socket::Unix(_) => Err(LinuxError::EAFNOSUPPORT),
+        }
+    }
+
+    fn disconnect(&self) -> LinuxResult {
+        match self {
+            Socket::Udp(_) | Socket::Unix(_) => Err(LinuxError::EOPNOTSUPP),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(_) => Err(LinuxError::EOPNOTSUPP),
+            Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().reset()?),
+        }
+    }
+
+    /// Sets the timeout of blocking sends and connects, as `SO_SNDTIMEO`.
+    fn set_send_timeout(&self, timeout: Duration) -> LinuxResult {
+        match self {
+            Socket::Tcp(tcpsocket) => tcpsocket.lock().set_send_timeout(Some(timeout)),
+            _ => return Err(LinuxError::ENOPROTOOPT),
+        }
+        Ok(())
+    }
+
+    /// Returns the timeout set by [`set_send_timeout`](Self::set_send_timeout),
+    /// zero for none.
+    fn send_timeout(&self) -> LinuxResult<Duration> {
+        match self {
+            Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().send_timeout().unwrap_or_default()),
+            _ => Err(LinuxError::ENOPROTOOPT),
+        }
+    }
+
+    /// Sets the socket option `optname` at `level` to `val`.
+    ///
+    /// All the `c_int` options supported by [`sys_setsockopt`] are routed here,
+    /// so a new option only needs a branch here and in
+    /// [`get_option`](Self::get_option).
+    fn set_option(&self, level: u32, optname: u32, val: c_int) -> LinuxResult {
+        match (level, optname) {
+            (ctypes::SOL_SOCKET, ctypes::SO_REUSEADDR) => match self {
+                // UDP ports are not checked for conflicts, so they are always reusable.
+                Socket::Udp(_) | Socket::Unix(_) => {}
+                #[cfg(feature = "smoltcp")]
+                Socket::Raw(_) => {}
+                Socket::Tcp(tcpsocket) => tcpsocket.lock().set_reuse_addr(val != 0),
+            },
+            (ctypes::SOL_SOCKET, ctypes::SO_BROADCAST) => match self {
+                Socket::Udp(udpsocket) => udpsocket.lock().set_broadcast(val != 0),
+                _ => return Err(LinuxError::ENOPROTOOPT),
+            },
+            (ctypes::SOL_SOCKET, ctypes::SO_RCVBUF) => match self {
+                Socket::Udp(udpsocket) => udpsocket.lock().set_recv_buffer_size(val.max(0) as _),
+                Socket::Tcp(tcpsocket) => tcpsocket.lock().set_recv_buffer_size(val.max(0) as _),
+                #[cfg(feature = "smoltcp")]
+                Socket::Raw(_) => return Err(LinuxError::ENOPROTOOPT),
+                // The buffers of the Unix sockets have a fixed size.
+                Socket::Unix(_) => {}
+            },
+            (ctypes::SOL_SOCKET, ctypes::SO_SNDBUF) => match self {
+                Socket::Udp(udpsocket) => udpsocket.lock().set_send_buffer_size(val.max(0) as _),
+                Socket::Tcp(tcpsocket) => tcpsocket.lock().set_send_buffer_size(val.max(0) as _),
+                #[cfg(feature = "smoltcp")]
+                Socket::Raw(_) => return Err(LinuxError::ENOPROTOOPT),
+                Socket::Unix(_) => {}
+            },
+            #[cfg(feature = "smoltcp")]
+            (ctypes::IPPROTO_IP, ctypes::IP_HDRINCL) => match self {
+                Socket::Raw(rawsocket) => rawsocket.lock().set_header_included(val != 0),
+                _ => return Err(LinuxError::ENOPROTOOPT),
+            },
+            (ctypes::IPPROTO_TCP, ctypes::TCP_NODELAY) => match self {
+                Socket::Tcp(tcpsocket) => tcpsocket.lock().set_nodelay(val != 0),
+                _ => return Err(LinuxError::ENOPROTOOPT),
+            },
+            _ => return Err(LinuxError::ENOPROTOOPT),
+        }
+        Ok(())
+    }
+
+    /// Gets the value of the socket option `optname` at `level`.
+    fn get_option(&self, level: u32, optname: u32) -> LinuxResult<c_int> {
+        match (level, optname) {
+            (ctypes::SOL_SOCKET, ctypes::SO_REUSEADDR) => match self {
+                Socket::Udp(_) | Socket::Unix(_) => Ok(1),
+                #[cfg(feature = "smoltcp")]
+                Socket::Raw(_) => Ok(1),
+                Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().is_reuse_addr() as _),
+            },
+            (ctypes::SOL_SOCKET, ctypes::SO_BROADCAST) => match self {
+                Socket::Udp(udpsocket) => Ok(udpsocket.lock().is_broadcast() as _),
+                _ => Err(LinuxError::ENOPROTOOPT),
+            },
+            (ctypes::SOL_SOCKET, ctypes::SO_RCVBUF) => match self {
+                Socket::Udp(udpsocket) => Ok(udpsocket.lock().recv_buffer_size() as _),
+                Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().recv_buffer_size() as _),
+                #[cfg(feature = "smoltcp")]
+                Socket::Raw(_) => Err(LinuxError::ENOPROTOOPT),
+                Socket::Unix(_) => Ok(unix::UNIX_BUF_SIZE as _),
+            },
+            (ctypes::SOL_SOCKET, ctypes::SO_SNDBUF) => match self {
+                Socket::Udp(udpsocket) => Ok(udpsocket.lock().send_buffer_size() as _),
+                Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().send_buffer_size() as _),
+                #[cfg(feature = "smoltcp")]
+                Socket::Raw(_) => Err(LinuxError::ENOPROTOOPT),
+                Socket::Unix(_) => Ok(unix::UNIX_BUF_SIZE as _),
+            },
+            (ctypes::SOL_SOCKET, ctypes::SO_TYPE) => Ok(match self {
+                Socket::Udp(_) => ctypes::SOCK_DGRAM,
+                Socket::Tcp(_) | Socket::Unix(_) => ctypes::SOCK_STREAM,
+                #[cfg(feature = "smoltcp")]
+                Socket::Raw(_) => ctypes::SOCK_RAW,
+            } as _),
+            (ctypes::SOL_SOCKET, ctypes::SO_ERROR) => match self {
+                Socket::Udp(_) | Socket::Unix(_) => Ok(0),
+                #[cfg(feature = "smoltcp")]
+                Socket::Raw(_) => Ok(0),
+                Socket::Tcp(tcpsocket) => Ok(tcpsocket
+                    .lock()
+                    .take_error()
+                    .map_or(0, |e| LinuxError::from(e).code())),
+            },
+            #[cfg(feature = "smoltcp")]
+            (ctypes::IPPROTO_IP, ctypes::IP_HDRINCL) => match self {
+                Socket::Raw(rawsocket) => Ok(rawsocket.lock().header_included() as _),
+                _ => Err(LinuxError::ENOPROTOOPT),
+            },
+            (ctypes::IPPROTO_TCP, ctypes::TCP_NODELAY) => match self {
+                Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().is_nodelay() as _),
+                _ => Err(LinuxError::ENOPROTOOPT),
+            },
+            _ => Err(LinuxError::ENOPROTOOPT),
+        }
+    }
+
+    fn at_mark(&self) -> LinuxResult<bool> {
+        match self {
+            Socket::Udp(_) | Socket::Unix(_) => Err(LinuxError::ENOTTY),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(_) => Err(LinuxError::ENOTTY),
+            Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().at_mark()?),
         }
     }
 
@@ -91,36 +288,64 @@ impl Socket {
         match self {
             // diff: must bind before sendto
             Socket::Udp(udpsocket) => Ok(udpsocket.lock().send_to(buf, addr)?),
-            Socket::Tcp(_) => Err(LinuxError::EISCONN),
+            Socket::Tcp(_) | Socket::Unix(_) => Err(LinuxError::EISCONN),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => Ok(rawsocket.lock().send_to(buf, addr)?),
         }
     }
 
-    fn recvfrom(&self, buf: &mut [u8]) -> LinuxResult<(usize, Option<SocketAddr>)> {
+    fn recvfrom(&self, buf: &mut [u8], flags: c_int) -> LinuxResult<(usize, Option<SocketAddr>)> {
         match self {
             // diff: must bind before recvfrom
-            Socket::Udp(udpsocket) => Ok(udpsocket
-                .lock()
-                .recv_from(buf)
-                .map(|res| (res.0, Some(res.1)))?),
-            Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().recv(buf, 0).map(|res| (res, None))?),
+            Socket::Udp(udpsocket) => {
+                let (len, addr) = udpsocket.lock().recv_from_flags(buf, flags)?;
+                Ok((len, Some(addr)))
+            }
+            Socket::Tcp(tcpsocket) => Ok((tcpsocket.lock().recv(buf, flags)?, None)),
+            Socket::Unix(unixsocket) => Ok((unixsocket.recv(buf, flags)?, None)),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(_) if flags & ctypes::MSG_PEEK as c_int != 0 => Err(LinuxError::EOPNOTSUPP),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => {
+                let (len, addr) = rawsocket.lock().recv_from_flags(buf, flags)?;
+                Ok((len, Some(addr)))
+            }
         }
     }
 
-    fn listen(&self) -> LinuxResult {
+    fn listen(&self, backlog: usize) -> LinuxResult {
         match self {
             Socket::Udp(_) => Err(LinuxError::EOPNOTSUPP),
-            Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().listen()?),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(_) => Err(LinuxError::EOPNOTSUPP),
+            Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().listen(backlog)?),
+            Socket::Unix(unixsocket) => unixsocket.listen(backlog),
         }
     }
 
-    fn accept(&self) -> LinuxResult<TcpSocket> {
+    fn is_nonblocking(&self) -> bool {
+        match self {
+            Socket::Udp(udpsocket) => udpsocket.lock().is_nonblocking(),
+            Socket::Tcp(tcpsocket) => tcpsocket.lock().is_nonblocking(),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => rawsocket.lock().is_nonblocking(),
+            Socket::Unix(unixsocket) => unixsocket.is_nonblocking(),
+        }
+    }
+
+    fn accept(&self) -> LinuxResult<Socket> {
         match self {
             Socket::Udp(_) => Err(LinuxError::EOPNOTSUPP),
-            Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().accept()?),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(_) => Err(LinuxError::EOPNOTSUPP),
+            Socket::Tcp(tcpsocket) => Ok(Socket::Tcp(Mutex::new(tcpsocket.lock().accept()?))),
+            Socket::Unix(unixsocket) => Ok(Socket::Unix(unixsocket.accept()?)),
         }
     }
 
-    fn shutdown(&self) -> LinuxResult {
+    /// Shuts down the connection of the socket, only the direction told by
+    /// `how` for `AF_UNIX` sockets and both of them for the others.
+    fn shutdown(&self, how: c_int) -> LinuxResult {
         match self {
             Socket::Udp(udpsocket) => {
                 let udpsocket = udpsocket.lock();
@@ -135,6 +360,16 @@ impl Socket {
                 tcpsocket.shutdown()?;
                 Ok(())
             }
+
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => {
+                let rawsocket = rawsocket.lock();
+                rawsocket.peer_addr()?;
+                rawsocket.shutdown()?;
+                Ok(())
+            }
+
+            Socket::Unix(unixsocket) => unixsocket.shutdown(how),
         }
     }
 }
@@ -145,7 +380,16 @@ impl FileLike for Socket {
     }
 
     fn write(&self, buf: &[u8]) -> LinuxResult<usize> {
-        self.send(buf)
+        self.send(buf, 0)
+    }
+
+    fn write_vectored(&self, bufs: &[&[u8]]) -> LinuxResult<usize> {
+        match self {
+            // queued at once, to go out in as few segments as possible
+            Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().send_vectored(bufs)?),
+            // sent at once, so that UDP and raw sockets send a single datagram
+            _ => self.send(&bufs.concat(), 0),
+        }
     }
 
     ///TODO
@@ -179,6 +423,9 @@ impl FileLike for Socket {
         match self {
             Socket::Udp(udpsocket) => udpsocket.lock().set_nonblocking(nonblock),
             Socket::Tcp(tcpsocket) => tcpsocket.lock().set_nonblocking(nonblock),
+            #[cfg(feature = "smoltcp")]
+            Socket::Raw(rawsocket) => rawsocket.lock().set_nonblocking(nonblock),
+            Socket::Unix(unixsocket) => unixsocket.set_nonblocking(nonblock),
         }
         Ok(())
     }
@@ -208,15 +455,32 @@ impl From<ctypes::sockaddr_in> for SocketAddrV4 {
     }
 }
 
-fn into_sockaddr(addr: SocketAddr) -> (ctypes::sockaddr, ctypes::socklen_t) {
+/// Stores `addr` in the buffer of `*addrlen` bytes at `dst`, truncated if the
+/// buffer is too small, and sets `*addrlen` to the full size of the address.
+unsafe fn write_sockaddr(
+    addr: SocketAddr,
+    dst: *mut ctypes::sockaddr,
+    addrlen: *mut ctypes::socklen_t,
+) -> LinuxResult {
     debug!("    Sockaddr: {}", addr);
-    match addr {
-        SocketAddr::V4(addr) => (
-            unsafe { *(&ctypes::sockaddr_in::from(addr) as *const _ as *const ctypes::sockaddr) },
-            size_of::<ctypes::sockaddr>() as _,
-        ),
+    let sockaddr = match addr {
+        SocketAddr::V4(addr) => ctypes::sockaddr_in::from(addr),
         SocketAddr::V6(_) => panic!("IPv6 is not supported"),
+    };
+    let buf_len = unsafe { *addrlen };
+    if (buf_len as i32) < 0 {
+        return Err(LinuxError::EINVAL);
+    }
+    let len = size_of::<ctypes::sockaddr_in>();
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            &sockaddr as *const _ as *const u8,
+            dst as *mut u8,
+            len.min(buf_len as usize),
+        );
+        *addrlen = len as _;
     }
+    Ok(())
 }
 
 fn from_sockaddr(
@@ -242,46 +506,170 @@ fn from_sockaddr(
 
 /// Create an socket for communication.
 ///
+/// Raw sockets (`SOCK_RAW`), only supported with smoltcp, send and receive
+/// the IPv4 packets of the given protocol, e.g. `IPPROTO_ICMP`. The packets
+/// received include their IPv4 header, and so do those sent with `IP_HDRINCL`
+/// set. Unix domain sockets (`AF_UNIX`) are only of `SOCK_STREAM`.
+///
 /// Return the socket file descriptor.
 pub fn sys_socket(domain: c_int, socktype: c_int, protocol: c_int) -> c_int {
     debug!("sys_socket <= {} {} {}", domain, socktype, protocol);
     let (domain, socktype, protocol) = (domain as u32, socktype as u32, protocol as u32);
-    pub const _SOCK_STREAM_NONBLOCK: u32 = ctypes::SOCK_STREAM | ctypes::SOCK_NONBLOCK;
+    let flags = socktype & (ctypes::SOCK_NONBLOCK | ctypes::SOCK_CLOEXEC);
+    let socktype = socktype & !flags;
     syscall_body!(sys_socket, {
-        match (domain, socktype, protocol) {
+        let socket = match (domain, socktype, protocol) {
             (ctypes::AF_INET, ctypes::SOCK_STREAM, ctypes::IPPROTO_TCP)
             | (ctypes::AF_INET, ctypes::SOCK_STREAM, 0) => {
-                Socket::Tcp(Mutex::new(TcpSocket::new())).add_to_fd_table()
+                Socket::Tcp(Mutex::new(TcpSocket::new()))
             }
             (ctypes::AF_INET, ctypes::SOCK_DGRAM, ctypes::IPPROTO_UDP)
-            | (ctypes::AF_INET, ctypes::SOCK_DGRAM, 0) => {
-                Socket::Udp(Mutex::new(UdpSocket::new())).add_to_fd_table()
+            | (ctypes::AF_INET, ctypes::SOCK_DGRAM, 0) => Socket::Udp(Mutex::new(UdpSocket::new())),
+            (ctypes::AF_INET, ctypes::SOCK_RAW, 0 | ctypes::IPPROTO_RAW) => {
+                // smoltcp only sends the packets of the protocol of the socket.
+                return Err(LinuxError::EPROTONOSUPPORT);
             }
-            (ctypes::AF_INET, _SOCK_STREAM_NONBLOCK, ctypes::IPPROTO_TCP) => {
-                let tcp_socket = TcpSocket::new();
-                tcp_socket.set_nonblocking(true);
-                Socket::Tcp(Mutex::new(tcp_socket)).add_to_fd_table()
+            #[cfg(feature = "smoltcp")]
+            (ctypes::AF_INET, ctypes::SOCK_RAW, protocol) => {
+                let protocol = u8::try_from(protocol).map_err(|_| LinuxError::EPROTONOSUPPORT)?;
+                Socket::Raw(Mutex::new(RawSocket::new(protocol)))
             }
-            _ => Err(LinuxError::EINVAL),
+            // lwIP has no raw sockets.
+            #[cfg(not(feature = "smoltcp"))]
+            (ctypes::AF_INET, ctypes::SOCK_RAW, _) => return Err(LinuxError::EPROTONOSUPPORT),
+            (ctypes::AF_UNIX, ctypes::SOCK_STREAM, 0) => Socket::Unix(UnixSocket::new()),
+            _ => return Err(LinuxError::EINVAL),
+        };
+        socket.set_nonblocking(flags & ctypes::SOCK_NONBLOCK != 0)?;
+        let fd = socket.add_to_fd_table()?;
+        if flags & ctypes::SOCK_CLOEXEC != 0 {
+            sys_fcntl(fd, ctypes::F_SETFD as _, ctypes::FD_CLOEXEC as _);
+        }
+        Ok(fd)
+    })
+}
+
+/// Create a pair of connected sockets, stored in `fds`.
+///
+/// Only the `AF_UNIX` stream sockets can be created in pairs, `SOCK_NONBLOCK`
+/// and `SOCK_CLOEXEC` apply to both of them.
+///
+/// Return 0 if success.
+pub fn sys_socketpair(domain: c_int, socktype: c_int, protocol: c_int, fds: &mut [c_int]) -> c_int {
+    debug!(
+        "sys_socketpair <= {} {} {} {:#x}",
+        domain,
+        socktype,
+        protocol,
+        fds.as_ptr() as usize
+    );
+    let (domain, socktype, protocol) = (domain as u32, socktype as u32, protocol as u32);
+    let flags = socktype & (ctypes::SOCK_NONBLOCK | ctypes::SOCK_CLOEXEC);
+    let socktype = socktype & !flags;
+    syscall_body!(sys_socketpair, {
+        if fds.len() != 2 {
+            return Err(LinuxError::EFAULT);
+        }
+        match (domain, socktype, protocol) {
+            (ctypes::AF_UNIX, ctypes::SOCK_STREAM, 0) => {}
+            (ctypes::AF_INET, _, _) => return Err(LinuxError::EOPNOTSUPP),
+            _ => return Err(LinuxError::EINVAL),
+        }
+
+        let (a, b) = UnixSocket::pair();
+        let nonblocking = flags & ctypes::SOCK_NONBLOCK != 0;
+        a.set_nonblocking(nonblocking);
+        b.set_nonblocking(nonblocking);
+        let fd_a = Socket::Unix(a).add_to_fd_table()?;
+        let fd_b = Socket::Unix(b).add_to_fd_table().inspect_err(|_| {
+            close_file_like(fd_a).ok();
+        })?;
+        if flags & ctypes::SOCK_CLOEXEC != 0 {
+            sys_fcntl(fd_a, ctypes::F_SETFD as _, ctypes::FD_CLOEXEC as _);
+            sys_fcntl(fd_b, ctypes::F_SETFD as _, ctypes::FD_CLOEXEC as _);
         }
+        fds[0] = fd_a;
+        fds[1] = fd_b;
+        Ok(0)
     })
 }
 
-/// `setsockopt`, currently ignored
+/// Set the option `optname` at `level` of a socket to the `c_int` at `optval`,
+/// or to the `timeval` at `optval` for `SO_SNDTIMEO`.
 ///
-/// TODO: implement this
+/// Return 0 if success, or `ENOPROTOOPT` if the option is not supported.
 pub fn sys_setsockopt(
     fd: c_int,
     level: c_int,
     optname: c_int,
-    _optval: *const c_void,
+    optval: *const c_void,
     optlen: ctypes::socklen_t,
 ) -> c_int {
     debug!(
-        "sys_setsockopt <= fd: {}, level: {}, optname: {}, optlen: {}, IGNORED",
+        "sys_setsockopt <= fd: {}, level: {}, optname: {}, optlen: {}",
         fd, level, optname, optlen
     );
-    syscall_body!(sys_setsockopt, Ok(0))
+    syscall_body!(sys_setsockopt, {
+        let socket = Socket::from_fd(fd)?;
+        if optval.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        if (level as u32, optname as u32) == (ctypes::SOL_SOCKET, ctypes::SO_SNDTIMEO) {
+            if (optlen as usize) < size_of::<ctypes::timeval>() {
+                return Err(LinuxError::EINVAL);
+            }
+            let tv = unsafe { *(optval as *const ctypes::timeval) };
+            if tv.tv_sec < 0 || !(0..1_000_000).contains(&tv.tv_usec) {
+                return Err(LinuxError::EDOM);
+            }
+            socket.set_send_timeout(Duration::from(tv))?;
+            return Ok(0);
+        }
+        if (optlen as usize) < size_of::<c_int>() {
+            return Err(LinuxError::EINVAL);
+        }
+        let val = unsafe { *(optval as *const c_int) };
+        socket.set_option(level as u32, optname as u32, val)?;
+        Ok(0)
+    })
+}
+
+/// Get the option `optname` at `level` of a socket, as a `c_int` written to
+/// `optval`, or a `timeval` for `SO_SNDTIMEO`, and its size to `optlen`.
+///
+/// Return 0 if success, or `ENOPROTOOPT` if the option is not supported.
+/// Getting `SO_ERROR` clears the pending error of the socket.
+pub unsafe fn sys_getsockopt(
+    fd: c_int,
+    level: c_int,
+    optname: c_int,
+    optval: *mut c_void,
+    optlen: *mut ctypes::socklen_t,
+) -> c_int {
+    debug!(
+        "sys_getsockopt <= fd: {}, level: {}, optname: {}",
+        fd, level, optname
+    );
+    syscall_body!(sys_getsockopt, {
+        let socket = Socket::from_fd(fd)?;
+        if optval.is_null() || optlen.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        if (level as u32, optname as u32) == (ctypes::SOL_SOCKET, ctypes::SO_SNDTIMEO) {
+            if (*optlen as usize) < size_of::<ctypes::timeval>() {
+                return Err(LinuxError::EINVAL);
+            }
+            *(optval as *mut ctypes::timeval) = socket.send_timeout()?.into();
+            *optlen = size_of::<ctypes::timeval>() as _;
+            return Ok(0);
+        }
+        if (*optlen as usize) < size_of::<c_int>() {
+            return Err(LinuxError::EINVAL);
+        }
+        *(optval as *mut c_int) = socket.get_option(level as u32, optname as u32)?;
+        *optlen = size_of::<c_int>() as _;
+        Ok(0)
+    })
 }
 
 /// Bind a address to a socket.
@@ -297,8 +685,13 @@ pub fn sys_bind(
         socket_fd, socket_addr as usize, addrlen
     );
     syscall_body!(sys_bind, {
-        let addr = from_sockaddr(socket_addr, addrlen)?;
-        Socket::from_fd(socket_fd)?.bind(addr)?;
+        let socket = Socket::from_fd(socket_fd)?;
+        if let Socket::Unix(unixsocket) = &*socket {
+            let addr = unsafe { unix::addr_from_sockaddr(socket_addr, addrlen)? };
+            unixsocket.bind(addr)?;
+        } else {
+            socket.bind(from_sockaddr(socket_addr, addrlen)?)?;
+        }
         Ok(0)
     })
 }
@@ -316,8 +709,21 @@ pub fn sys_connect(
         socket_fd, socket_addr as usize, addrlen
     );
     syscall_body!(sys_connect, {
-        let addr = from_sockaddr(socket_addr, addrlen)?;
-        Socket::from_fd(socket_fd)?.connect(addr)?;
+        // Like Linux, connecting to an `AF_UNSPEC` address dissolves the current
+        // connection, so that the socket can be connected again.
+        let is_unspec = !socket_addr.is_null()
+            && unsafe { (*socket_addr).sa_family } == ctypes::AF_UNSPEC as u16;
+        if is_unspec {
+            Socket::from_fd(socket_fd)?.disconnect()?;
+            return Ok(0);
+        }
+        let socket = Socket::from_fd(socket_fd)?;
+        if let Socket::Unix(unixsocket) = &*socket {
+            let addr = unsafe { unix::addr_from_sockaddr(socket_addr, addrlen)? };
+            unixsocket.connect(&addr)?;
+        } else {
+            socket.connect(from_sockaddr(socket_addr, addrlen)?)?;
+        }
         Ok(0)
     })
 }
@@ -353,12 +759,14 @@ pub fn sys_sendto(
 
 /// Send a message on a socket to the address connected.
 ///
+/// With `MSG_OOB`, the last byte of the message is sent as TCP urgent data.
+///
 /// Return the number of bytes sent if success.
 pub fn sys_send(
     socket_fd: c_int,
     buf_ptr: *const c_void,
     len: ctypes::size_t,
-    flag: c_int, // currently not used
+    flag: c_int,
 ) -> ctypes::ssize_t {
     debug!(
         "sys_sendto <= {} {:#x} {} {}",
@@ -369,18 +777,20 @@ pub fn sys_send(
             return Err(LinuxError::EFAULT);
         }
         let buf = unsafe { core::slice::from_raw_parts(buf_ptr as *const u8, len) };
-        Socket::from_fd(socket_fd)?.send(buf)
+        Socket::from_fd(socket_fd)?.send(buf, flag)
     })
 }
 
 /// Receive a message on a socket and get its source address.
 ///
+/// The same `flag`s as [`sys_recv`] are supported.
+///
 /// Return the number of bytes received if success.
 pub unsafe fn sys_recvfrom(
     socket_fd: c_int,
     buf_ptr: *mut c_void,
     len: ctypes::size_t,
-    flag: c_int, // currently not used
+    flag: c_int,
     socket_addr: *mut ctypes::sockaddr,
     addrlen: *mut ctypes::socklen_t,
 ) -> ctypes::ssize_t {
@@ -399,11 +809,9 @@ pub unsafe fn sys_recvfrom(
         let socket = Socket::from_fd(socket_fd)?;
         let buf = unsafe { core::slice::from_raw_parts_mut(buf_ptr as *mut u8, len) };
 
-        let res = socket.recvfrom(buf)?;
+        let res = socket.recvfrom(buf, flag)?;
         if let Some(addr) = res.1 {
-            unsafe {
-                (*socket_addr, *addrlen) = into_sockaddr(addr);
-            }
+            unsafe { write_sockaddr(addr, socket_addr, addrlen)? };
         }
         Ok(res.0)
     })
@@ -411,12 +819,17 @@ pub unsafe fn sys_recvfrom(
 
 /// Receive a message on a socket.
 ///
+/// With `MSG_OOB`, the TCP urgent byte is received instead. `MSG_PEEK` leaves
+/// the data in the queue, `MSG_DONTWAIT` does not block for this call only,
+/// and `MSG_WAITALL` waits for the whole buffer on TCP sockets. Other flags
+/// are ignored.
+///
 /// Return the number of bytes received if success.
 pub fn sys_recv(
     socket_fd: c_int,
     buf_ptr: *mut c_void,
     len: ctypes::size_t,
-    flag: c_int, // currently not used
+    flag: c_int,
 ) -> ctypes::ssize_t {
     debug!(
         "sys_recv <= {} {:#x} {} {}",
@@ -433,74 +846,187 @@ pub fn sys_recv(
 
 /// Listen for connections on a socket
 ///
+/// At most `backlog` established connections wait to be accepted, a negative
+/// `backlog` means the maximum supported.
+///
 /// Return 0 if success.
-pub fn sys_listen(
-    socket_fd: c_int,
-    backlog: c_int, // currently not used
-) -> c_int {
+pub fn sys_listen(socket_fd: c_int, backlog: c_int) -> c_int {
     debug!("sys_listen <= {} {}", socket_fd, backlog);
     syscall_body!(sys_listen, {
-        Socket::from_fd(socket_fd)?.listen()?;
+        let backlog = usize::try_from(backlog).unwrap_or(usize::MAX);
+        Socket::from_fd(socket_fd)?.listen(backlog)?;
         Ok(0)
     })
 }
 
 /// Accept for connections on a socket
 ///
+/// The accepted socket is in the blocking mode of the listening one.
+///
 /// Return file descriptor for the accepted socket if success.
 pub unsafe fn sys_accept(
     socket_fd: c_int,
     socket_addr: *mut ctypes::sockaddr,
     socket_len: *mut ctypes::socklen_t,
+) -> c_int {
+    sys_accept4(socket_fd, socket_addr, socket_len, 0)
+}
+
+/// Accept for connections on a socket, like [`sys_accept`], with `flags`.
+///
+/// The accepted socket is nonblocking with `SOCK_NONBLOCK`, otherwise in the
+/// blocking mode of the listening one, and is closed on exec with
+/// `SOCK_CLOEXEC`. The peer address is stored at `socket_addr` unless it is
+/// null, truncated to `*socket_len` bytes, and `*socket_len` is set to its
+/// full size.
+///
+/// Return file descriptor for the accepted socket if success.
+pub unsafe fn sys_accept4(
+    socket_fd: c_int,
+    socket_addr: *mut ctypes::sockaddr,
+    socket_len: *mut ctypes::socklen_t,
+    flags: c_int,
 ) -> c_int {
     debug!(
-        "sys_accept <= {} {:#x} {:#x}",
-        socket_fd, socket_addr as usize, socket_len as usize
+        "sys_accept4 <= {} {:#x} {:#x} {}",
+        socket_fd, socket_addr as usize, socket_len as usize, flags
     );
-    syscall_body!(sys_accept, {
-        if socket_addr.is_null() || socket_len.is_null() {
+    syscall_body!(sys_accept4, {
+        let flags = flags as u32;
+        if flags & !(ctypes::SOCK_NONBLOCK | ctypes::SOCK_CLOEXEC) != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        if !socket_addr.is_null() && socket_len.is_null() {
             return Err(LinuxError::EFAULT);
         }
         let socket = Socket::from_fd(socket_fd)?;
         let new_socket = socket.accept()?;
-        let addr = new_socket.peer_addr()?;
-        let new_fd = Socket::add_to_fd_table(Socket::Tcp(Mutex::new(new_socket)))?;
-        unsafe {
-            (*socket_addr, *socket_len) = into_sockaddr(addr);
+        if !socket_addr.is_null() {
+            match &new_socket {
+                Socket::Unix(unixsocket) => unsafe {
+                    let addr = unixsocket.peer_addr()?;
+                    unix::write_sockaddr_un(addr.as_ref(), socket_addr, socket_len)?
+                },
+                _ => unsafe { write_sockaddr(new_socket.peer_addr()?, socket_addr, socket_len)? },
+            }
+        }
+        new_socket
+            .set_nonblocking(flags & ctypes::SOCK_NONBLOCK != 0 || socket.is_nonblocking())?;
+        let fd = new_socket.add_to_fd_table()?;
+        if flags & ctypes::SOCK_CLOEXEC != 0 {
+            sys_fcntl(fd, ctypes::F_SETFD as _, ctypes::FD_CLOEXEC as _);
         }
-        Ok(new_fd)
+        Ok(fd)
     })
 }
 
 /// Shut down a full-duplex connection.
 ///
+/// The `flag` (`SHUT_RD`, `SHUT_WR` or `SHUT_RDWR`) is only honored by the
+/// `AF_UNIX` sockets, the others shut down both directions.
+///
 /// Return 0 if success.
-pub fn sys_shutdown(
-    socket_fd: c_int,
-    flag: c_int, // currently not used
-) -> c_int {
+pub fn sys_shutdown(socket_fd: c_int, flag: c_int) -> c_int {
     debug!("sys_shutdown <= {} {}", socket_fd, flag);
     syscall_body!(sys_shutdown, {
-        Socket::from_fd(socket_fd)?.shutdown()?;
+        Socket::from_fd(socket_fd)?.shutdown(flag)?;
         Ok(0)
     })
 }
 
+/// Tell whether the next byte to read on a TCP socket is the urgent byte.
+///
+/// Return 1 at the mark, 0 otherwise.
+pub fn sys_sockatmark(socket_fd: c_int) -> c_int {
+    debug!("sys_sockatmark <= {}", socket_fd);
+    syscall_body!(sys_sockatmark, { Ok(socket_at_mark(socket_fd)? as c_int) })
+}
+
+pub(crate) fn socket_at_mark(socket_fd: c_int) -> LinuxResult<bool> {
+    Socket::from_fd(socket_fd)?.at_mark()
+}
+
+/// Returns the addresses of a host name.
+///
+/// Numeric addresses are returned as is, and the names listed in
+/// `/etc/hosts` are not queried. The others are resolved by DNS, whose
+/// answers are cached by [`ruxnet::dns_query`].
+fn resolve_host(name: &str) -> LinuxResult<Vec<IpAddr>> {
+    if let Ok(addr) = name.parse::<IpAddr>() {
+        return Ok(vec![addr]);
+    }
+    #[cfg(feature = "fs")]
+    if let Ok(table) = ruxfs::api::read_to_string("/etc/hosts") {
+        let addrs = ruxnet::lookup_hosts(&table, name);
+        if !addrs.is_empty() {
+            return Ok(addrs);
+        }
+    }
+    Ok(ruxnet::dns_query(name)?)
+}
+
+/// Returns the port of the service `name` over `protocol`, e.g. `"tcp"`.
+///
+/// A numeric service is the port itself. Otherwise it is looked up in
+/// `/etc/services`, then among the well-known services.
+fn resolve_service(name: &str, protocol: &str) -> Option<u16> {
+    if let Ok(port) = name.parse::<u16>() {
+        return Some(port);
+    }
+    #[cfg(feature = "fs")]
+    if let Ok(table) = ruxfs::api::read_to_string("/etc/services") {
+        if let Some(port) = ruxnet::lookup_service(&table, name, protocol) {
+            return Some(port);
+        }
+    }
+    ruxnet::lookup_service(ruxnet::WELL_KNOWN_SERVICES, name, protocol)
+}
+
+/// Returns the socket types and protocols allowed by `hints`, with the name
+/// of the protocols in the service table.
+fn socket_types(hints: *const ctypes::addrinfo) -> LinuxResult<Vec<(c_int, c_int, &'static str)>> {
+    const SOCKET_TYPES: [(u32, u32, &str); 2] = [
+        (ctypes::SOCK_STREAM, ctypes::IPPROTO_TCP, "tcp"),
+        (ctypes::SOCK_DGRAM, ctypes::IPPROTO_UDP, "udp"),
+    ];
+    let (family, socktype, protocol) = match unsafe { hints.as_ref() } {
+        Some(hints) => (hints.ai_family, hints.ai_socktype, hints.ai_protocol),
+        None => (0, 0, 0),
+    };
+    if family != ctypes::AF_UNSPEC as c_int && family != ctypes::AF_INET as c_int {
+        return Err(LinuxError::EAFNOSUPPORT);
+    }
+    let types: Vec<_> = SOCKET_TYPES
+        .iter()
+        .map(|&(ty, proto, name)| (ty as c_int, proto as c_int, name))
+        .filter(|&(ty, proto, _)| {
+            (socktype == 0 || socktype == ty) && (protocol == 0 || protocol == proto)
+        })
+        .collect();
+    if types.is_empty() {
+        return Err(LinuxError::ESOCKTNOSUPPORT);
+    }
+    Ok(types)
+}
+
 /// Query addresses for a domain name.
 ///
-/// Only IPv4. Ports are always 0. Ignore servname and hint.
-/// Results' ai_flags and ai_canonname are 0 or NULL.
+/// Only IPv4, the IPv6 addresses found are skipped. The service is a port
+/// number or a name looked up in `/etc/services`, and an unknown one fails
+/// with `ESRCH`. The family, socket type and protocol of the hints are
+/// honored, and there is a result for each address and each socket type, TCP
+/// and UDP by default. Results' ai_flags and ai_canonname are 0 or NULL.
 ///
 /// Return address number if success.
 pub unsafe fn sys_getaddrinfo(
     nodename: *const c_char,
     servname: *const c_char,
-    _hints: *const ctypes::addrinfo,
+    hints: *const ctypes::addrinfo,
     res: *mut *mut ctypes::addrinfo,
 ) -> c_int {
     let name = char_ptr_to_str(nodename);
-    let port = char_ptr_to_str(servname);
-    debug!("sys_getaddrinfo <= {:?} {:?}", name, port);
+    let service = char_ptr_to_str(servname);
+    debug!("sys_getaddrinfo <= {:?} {:?}", name, service);
     syscall_body!(sys_getaddrinfo, {
         if nodename.is_null() && servname.is_null() {
             return Ok(0);
@@ -509,45 +1035,66 @@ pub unsafe fn sys_getaddrinfo(
             return Err(LinuxError::EFAULT);
         }
 
-        let port = port.map_or(0, |p| p.parse::<u16>().unwrap_or(0));
+        let service = if servname.is_null() {
+            None
+        } else {
+            Some(service?)
+        };
+        let services: Vec<(c_int, c_int, u16)> = socket_types(hints)?
+            .into_iter()
+            .filter_map(|(socktype, protocol, proto_name)| {
+                let port = match service {
+                    Some(service) => resolve_service(service, proto_name)?,
+                    None => 0,
+                };
+                Some((socktype, protocol, port))
+            })
+            .collect();
+        if services.is_empty() {
+            return Err(LinuxError::ESRCH);
+        }
+
         let ip_addrs = if let Ok(domain) = name {
-            if let Ok(a) = domain.parse::<IpAddr>() {
-                vec![a]
-            } else {
-                ruxnet::dns_query(domain)?
-            }
+            resolve_host(domain)?
         } else {
             vec![Ipv4Addr::LOCALHOST.into()]
         };
+        let ip_addrs: Vec<Ipv4Addr> = ip_addrs
+            .into_iter()
+            .filter_map(|ip| match ip {
+                IpAddr::V4(ip) => Some(ip),
+                IpAddr::V6(_) => None,
+            })
+            .take(ctypes::MAXADDRS as usize)
+            .collect();
 
-        let len = ip_addrs.len().min(ctypes::MAXADDRS as usize);
+        let len = ip_addrs.len() * services.len();
         if len == 0 {
             return Ok(0);
         }
 
         let mut out: Vec<ctypes::aibuf> = Vec::with_capacity(len);
-        for (i, &ip) in ip_addrs.iter().enumerate().take(len) {
-            let buf = match ip {
-                IpAddr::V4(ip) => ctypes::aibuf {
-                    ai: ctypes::addrinfo {
-                        ai_family: ctypes::AF_INET as _,
-                        // TODO: This is a hard-code part, only return TCP parameters
-                        ai_socktype: ctypes::SOCK_STREAM as _,
-                        ai_protocol: ctypes::IPPROTO_TCP as _,
-                        ai_addrlen: size_of::<ctypes::sockaddr_in>() as _,
-                        ai_addr: core::ptr::null_mut(),
-                        ai_canonname: core::ptr::null_mut(),
-                        ai_next: core::ptr::null_mut(),
-                        ai_flags: 0,
-                    },
-                    sa: ctypes::aibuf_sa {
-                        sin: SocketAddrV4::new(ip, port).into(),
-                    },
-                    slot: i as i16,
-                    lock: [0],
-                    ref_: 0,
+        let entries = ip_addrs
+            .iter()
+            .flat_map(|&ip| services.iter().map(move |&service| (ip, service)));
+        for (i, (ip, (socktype, protocol, port))) in entries.enumerate() {
+            let buf = ctypes::aibuf {
+                ai: ctypes::addrinfo {
+                    ai_family: ctypes::AF_INET as _,
+                    ai_socktype: socktype,
+                    ai_protocol: protocol,
+                    ai_addrlen: size_of::<ctypes::sockaddr_in>() as _,
+                    ai_addr: core::ptr::null_mut(),
+                    ai_canonname: core::ptr::null_mut(),
+                    ai_next: core::ptr::null_mut(),
+                    ai_flags: 0,
                 },
-                _ => panic!("IPv6 is not supported"),
+                sa: ctypes::aibuf_sa {
+                    sin: SocketAddrV4::new(ip, port).into(),
+                },
+                slot: i as i16,
+                lock: [0],
+                ref_: 0,
             };
             out.push(buf);
             out[i].ai.ai_addr =
@@ -578,6 +1125,9 @@ pub unsafe fn sys_freeaddrinfo(res: *mut ctypes::addrinfo) {
 }
 
 /// Get current address to which the socket sockfd is bound.
+///
+/// An unbound socket is bound to `0.0.0.0:0`. The address is truncated if
+/// `addrlen` is too small, and `addrlen` is set to its full size.
 pub unsafe fn sys_getsockname(
     sock_fd: c_int,
     addr: *mut ctypes::sockaddr,
@@ -591,17 +1141,21 @@ pub unsafe fn sys_getsockname(
         if addr.is_null() || addrlen.is_null() {
             return Err(LinuxError::EFAULT);
         }
-        if unsafe { *addrlen } < size_of::<ctypes::sockaddr>() as u32 {
-            return Err(LinuxError::EINVAL);
-        }
-        unsafe {
-            (*addr, *addrlen) = into_sockaddr(Socket::from_fd(sock_fd)?.local_addr()?);
+        let socket = Socket::from_fd(sock_fd)?;
+        match &*socket {
+            Socket::Unix(unixsocket) => unsafe {
+                unix::write_sockaddr_un(unixsocket.local_addr().as_ref(), addr, addrlen)?
+            },
+            _ => unsafe { write_sockaddr(socket.local_addr()?, addr, addrlen)? },
         }
         Ok(0)
     })
 }
 
 /// Get peer address to which the socket sockfd is connected.
+///
+/// Fails with `ENOTCONN` if the socket is not connected. The address is
+/// truncated like with `sys_getsockname`.
 pub unsafe fn sys_getpeername(
     sock_fd: c_int,
     addr: *mut ctypes::sockaddr,
@@ -615,18 +1169,154 @@ pub unsafe fn sys_getpeername(
         if addr.is_null() || addrlen.is_null() {
             return Err(LinuxError::EFAULT);
         }
-        if unsafe { *addrlen } < size_of::<ctypes::sockaddr>() as u32 {
-            return Err(LinuxError::EINVAL);
-        }
-        unsafe {
-            (*addr, *addrlen) = into_sockaddr(Socket::from_fd(sock_fd)?.peer_addr()?);
+        let socket = Socket::from_fd(sock_fd)?;
+        match &*socket {
+            Socket::Unix(unixsocket) => unsafe {
+                unix::write_sockaddr_un(unixsocket.peer_addr()?.as_ref(), addr, addrlen)?
+            },
+            _ => unsafe { write_sockaddr(socket.peer_addr()?, addr, addrlen)? },
         }
         Ok(0)
     })
 }
 
-/// Send a message on a socket to the address connected.
-/// The  message is pointed to by the elements of the array msg.msg_iov.
+/// Maximum number of buffers in the iovec array of a message.
+const UIO_MAXIOV: usize = 1024;
+
+/// Returns the iovec array of `msg`.
+unsafe fn msg_iovecs<'a>(msg: &ctypes::msghdr) -> LinuxResult<&'a [ctypes::iovec]> {
+    let iovlen = msg.msg_iovlen as usize;
+    if iovlen > UIO_MAXIOV {
+        return Err(LinuxError::EMSGSIZE);
+    }
+    if iovlen == 0 {
+        return Ok(&[]);
+    }
+    if msg.msg_iov.is_null() {
+        return Err(LinuxError::EFAULT);
+    }
+    let iovs = unsafe { core::slice::from_raw_parts(msg.msg_iov, iovlen) };
+    if iovs
+        .iter()
+        .any(|iov| iov.iov_base.is_null() && iov.iov_len != 0)
+    {
+        return Err(LinuxError::EFAULT);
+    }
+    Ok(iovs)
+}
+
+/// Rounds `len` up to the alignment of the control messages.
+const fn cmsg_align(len: usize) -> usize {
+    (len + size_of::<usize>() - 1) & !(size_of::<usize>() - 1)
+}
+
+/// Returns the files of the fds passed with `SCM_RIGHTS` in the control
+/// messages sent with `msg`.
+///
+/// The other `SOL_SOCKET` messages fail with `EINVAL`, and the messages of the
+/// other levels, e.g. `IP_PKTINFO`, are ignored.
+unsafe fn cmsg_rights(msg: &ctypes::msghdr) -> LinuxResult<Vec<Arc<dyn FileLike>>> {
+    let mut files = Vec::new();
+    let controllen = msg.msg_controllen as usize;
+    if controllen == 0 {
+        return Ok(files);
+    }
+    if msg.msg_control.is_null() {
+        return Err(LinuxError::EFAULT);
+    }
+    let hdr_len = size_of::<ctypes::cmsghdr>();
+    let mut offset = 0;
+    while controllen - offset >= hdr_len {
+        let cmsg_ptr = unsafe { (msg.msg_control as *const u8).add(offset) };
+        let cmsg = unsafe { (cmsg_ptr as *const ctypes::cmsghdr).read_unaligned() };
+        let len = cmsg.cmsg_len as usize;
+        if len < hdr_len || len > controllen - offset {
+            return Err(LinuxError::EINVAL);
+        }
+        if cmsg.cmsg_level == ctypes::SOL_SOCKET as c_int {
+            if cmsg.cmsg_type != ctypes::SCM_RIGHTS as c_int {
+                debug!("    unsupported control message type {}", cmsg.cmsg_type);
+                return Err(LinuxError::EINVAL);
+            }
+            let data_offset = cmsg_align(hdr_len);
+            let fds = (len.max(data_offset) - data_offset) / size_of::<c_int>();
+            for i in 0..fds {
+                let fd = unsafe {
+                    (cmsg_ptr.add(data_offset) as *const c_int)
+                        .add(i)
+                        .read_unaligned()
+                };
+                files.push(super::fd_ops::get_file_like(fd)?);
+            }
+        }
+        offset = controllen.min(offset + cmsg_align(len));
+    }
+    Ok(files)
+}
+
+/// Adds the files received to the fd table, and stores their fds in an
+/// `SCM_RIGHTS` control message of `msg`.
+///
+/// The files which do not fit in `msg.msg_control`, or can not be added
+/// since the fd table is full, are closed and `MSG_CTRUNC` is set. Returns
+/// the length of the control message.
+unsafe fn put_cmsg_rights(
+    msg: &mut ctypes::msghdr,
+    files: Vec<Arc<dyn FileLike>>,
+    flags: c_int,
+) -> usize {
+    if files.is_empty() {
+        return 0;
+    }
+    let controllen = msg.msg_controllen as usize;
+    let data_offset = cmsg_align(size_of::<ctypes::cmsghdr>());
+    if msg.msg_control.is_null() || controllen < data_offset {
+        msg.msg_flags |= ctypes::MSG_CTRUNC as c_int;
+        return 0;
+    }
+    let max_fds = files
+        .len()
+        .min((controllen - data_offset) / size_of::<c_int>());
+    let cmsg_ptr = msg.msg_control as *mut u8;
+    let mut fds = 0;
+    for file in files.iter().take(max_fds) {
+        // like Linux, keep the fds installed so far if the table gets full
+        let Ok(fd) = super::fd_ops::add_file_like(file.clone()) else {
+            break;
+        };
+        if flags & ctypes::MSG_CMSG_CLOEXEC as c_int != 0 {
+            sys_fcntl(fd, ctypes::F_SETFD as _, ctypes::FD_CLOEXEC as _);
+        }
+        unsafe {
+            (cmsg_ptr.add(data_offset) as *mut c_int)
+                .add(fds)
+                .write_unaligned(fd)
+        };
+        fds += 1;
+    }
+    if fds < files.len() {
+        msg.msg_flags |= ctypes::MSG_CTRUNC as c_int;
+    }
+    if fds == 0 {
+        return 0;
+    }
+    let len = data_offset + fds * size_of::<c_int>();
+    let cmsg = ctypes::cmsghdr {
+        cmsg_len: len as _,
+        cmsg_level: ctypes::SOL_SOCKET as _,
+        cmsg_type: ctypes::SCM_RIGHTS as _,
+        ..Default::default()
+    };
+    unsafe { (cmsg_ptr as *mut ctypes::cmsghdr).write_unaligned(cmsg) };
+    controllen.min(cmsg_align(len))
+}
+
+/// Send a message on a socket.
+///
+/// The data of the buffers of `msg.msg_iov` is gathered and sent at once, so
+/// it makes a single datagram. It is sent to `msg.msg_name` if set, except on
+/// TCP and Unix sockets which send to the address connected. Fds can be
+/// passed with `SCM_RIGHTS` over Unix sockets only.
 ///
 /// Return the number of bytes sent if success.
 pub unsafe fn sys_sendmsg(
@@ -639,27 +1329,95 @@ pub unsafe fn sys_sendmsg(
         if msg.is_null() {
             return Err(LinuxError::EFAULT);
         }
-        let msg = *msg;
-        if msg.msg_iov.is_null() {
+        let msg = unsafe { *msg };
+        let iovs = unsafe { msg_iovecs(&msg)? };
+        let files = unsafe { cmsg_rights(&msg)? };
+        let socket = Socket::from_fd(socket_fd)?;
+
+        let mut buf = Vec::with_capacity(iovs.iter().map(|iov| iov.iov_len).sum());
+        for iov in iovs.iter().filter(|iov| iov.iov_len != 0) {
+            buf.extend_from_slice(unsafe {
+                core::slice::from_raw_parts(iov.iov_base as *const u8, iov.iov_len)
+            });
+        }
+        if !files.is_empty() {
+            match &*socket {
+                Socket::Unix(_) if flags & ctypes::MSG_OOB as c_int != 0 => {
+                    Err(LinuxError::EOPNOTSUPP)
+                }
+                Socket::Unix(unixsocket) => unixsocket.send_with_rights(&buf, flags, files),
+                _ => Err(LinuxError::EINVAL),
+            }
+        } else if msg.msg_name.is_null() || matches!(*socket, Socket::Tcp(_) | Socket::Unix(_)) {
+            socket.send(&buf, flags)
+        } else {
+            let addr = from_sockaddr(msg.msg_name as *const ctypes::sockaddr, msg.msg_namelen)?;
+            socket.sendto(&buf, addr)
+        }
+    })
+}
+
+/// Receive a message on a socket.
+///
+/// The data received is scattered over the buffers of `msg.msg_iov`, and its
+/// source address is stored in `msg.msg_name` if set. The only control
+/// message received is `SCM_RIGHTS`, with the fds passed over Unix sockets,
+/// which are added to the fd table.
+///
+/// Return the number of bytes received if success.
+pub unsafe fn sys_recvmsg(
+    socket_fd: c_int,
+    msg: *mut ctypes::msghdr,
+    flags: c_int,
+) -> ctypes::ssize_t {
+    debug!("sys_recvmsg <= {} {:#x} {}", socket_fd, msg as usize, flags);
+    syscall_body!(sys_recvmsg, {
+        if msg.is_null() {
             return Err(LinuxError::EFAULT);
         }
-        let iovs = core::slice::from_raw_parts(msg.msg_iov, msg.msg_iovlen as usize);
+        let msg = unsafe { &mut *msg };
+        let iovs = unsafe { msg_iovecs(msg)? };
         let socket = Socket::from_fd(socket_fd)?;
-        let mut ret = 0;
 
+        let mut buf = vec![0; iovs.iter().map(|iov| iov.iov_len).sum()];
+        let (len, addr, files) = match &*socket {
+            Socket::Unix(_) if flags & ctypes::MSG_OOB as c_int != 0 => {
+                return Err(LinuxError::EOPNOTSUPP)
+            }
+            Socket::Unix(unixsocket) => {
+                let (len, files) = unixsocket.recv_with_rights(&mut buf, flags)?;
+                (len, None, files)
+            }
+            _ if msg.msg_name.is_null() => (socket.recv(&mut buf, flags)?, None, Vec::new()),
+            _ => {
+                let (len, addr) = socket.recvfrom(&mut buf, flags)?;
+                (len, addr, Vec::new())
+            }
+        };
+        let mut data = &buf[..len];
         for iov in iovs.iter() {
-            if iov.iov_base.is_null() {
-                return Err(LinuxError::EFAULT);
+            let n = data.len().min(iov.iov_len);
+            if n == 0 {
+                break;
             }
-            let buf = core::slice::from_raw_parts(iov.iov_base as *const u8, iov.iov_len);
-            ret += match &socket as &Socket {
-                Socket::Udp(udpsocket) => udpsocket.lock().send_to(
-                    buf,
-                    from_sockaddr(msg.msg_name as *const ctypes::sockaddr, msg.msg_namelen)?,
-                )?,
-                Socket::Tcp(tcpsocket) => tcpsocket.lock().send(buf)?,
-            };
+            unsafe { core::ptr::copy_nonoverlapping(data.as_ptr(), iov.iov_base as *mut u8, n) };
+            data = &data[n..];
         }
-        Ok(ret)
+
+        if !msg.msg_name.is_null() {
+            match addr {
+                Some(addr) => unsafe {
+                    write_sockaddr(
+                        addr,
+                        msg.msg_name as *mut ctypes::sockaddr,
+                        &mut msg.msg_namelen,
+                    )?
+                },
+                None => msg.msg_namelen = 0,
+            }
+        }
+        msg.msg_flags = 0;
+        msg.msg_controllen = unsafe { put_cmsg_rights(msg, files, flags) } as _;
+        Ok(len)
     })
 }
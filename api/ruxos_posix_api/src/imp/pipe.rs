@@ -205,9 +205,14 @@ impl FileLike for Pipe {
 
     fn poll(&self) -> LinuxResult<PollState> {
         let buf = self.buffer.lock();
+        // the other end is closed
+        let closed = self.write_end_close();
         Ok(PollState {
-            readable: self.readable() && buf.available_read() > 0,
+            // reading returns 0 at once once the write end is closed
+            readable: self.readable() && (buf.available_read() > 0 || closed),
             writable: self.writable() && buf.available_write() > 0,
+            hangup: self.readable() && closed,
+            error: self.writable() && closed,
         })
     }
 
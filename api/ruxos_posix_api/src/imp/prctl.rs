@@ -13,6 +13,9 @@ use axerrno::LinuxError;
 
 const ARCH_SET_FS: i32 = 0x1002;
 
+const PR_SET_NAME: c_int = 15;
+const PR_GET_NAME: c_int = 16;
+
 /// set thread state
 pub fn sys_arch_prctl(code: c_int, addr: c_ulong) -> c_int {
     debug!("sys_arch_prctl <= code: {}, addr: {:#x}", code, addr);
@@ -29,11 +32,40 @@ pub fn sys_arch_prctl(code: c_int, addr: c_ulong) -> c_int {
     })
 }
 
-/// TODO: fake implementation for prctl
+/// Operations on the calling thread
+///
+/// `PR_SET_NAME` and `PR_GET_NAME` set and get the name of the thread, which
+/// is truncated to 15 bytes like on Linux. With `PR_GET_NAME`, `arg0` points to
+/// a buffer of 16 bytes.
+///
+/// TODO: other operations are ignored
 pub fn sys_prctl(op: c_int, arg0: c_ulong, arg1: c_ulong, arg2: c_ulong, arg3: c_ulong) -> c_int {
     debug!(
         "sys_prctl <= op: {}, arg0: {}, arg1: {}, arg2: {}, arg3: {}",
         op, arg0, arg1, arg2, arg3
     );
-    syscall_body!(sys_prctl, Ok(0))
+    syscall_body!(sys_prctl, {
+        match op {
+            PR_SET_NAME | PR_GET_NAME if arg0 == 0 => Err(LinuxError::EFAULT),
+            #[cfg(feature = "multitask")]
+            PR_SET_NAME => {
+                let name = unsafe { core::ffi::CStr::from_ptr(arg0 as *const core::ffi::c_char) };
+                let name = name.to_str().map_err(|_| LinuxError::EINVAL)?;
+                ruxtask::current().set_name(name);
+                Ok(0)
+            }
+            #[cfg(feature = "multitask")]
+            PR_GET_NAME => {
+                let buf = arg0 as *mut u8;
+                ruxtask::current().with_name(|name| unsafe {
+                    core::ptr::copy_nonoverlapping(name.as_ptr(), buf, name.len());
+                    *buf.add(name.len()) = 0;
+                });
+                Ok(0)
+            }
+            #[cfg(not(feature = "multitask"))]
+            PR_SET_NAME | PR_GET_NAME => Err(LinuxError::EINVAL),
+            _ => Ok(0),
+        }
+    })
 }
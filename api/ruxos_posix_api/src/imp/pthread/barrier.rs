@@ -0,0 +1,120 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use crate::{ctypes, utils::check_null_mut_ptr};
+
+use axerrno::{LinuxError, LinuxResult};
+use ruxtask::WaitQueue;
+
+use core::ffi::{c_int, c_uint};
+use core::mem::size_of;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+static_assertions::const_assert_eq!(
+    size_of::<PthreadBarrier>(),
+    size_of::<ctypes::pthread_barrier_t>()
+);
+
+/// A reusable barrier.
+///
+/// Every time `count` threads have arrived, the generation number is bumped
+/// and all the waiting threads are released, so that the barrier can be used
+/// again for the next phase.
+#[repr(C)]
+pub struct PthreadBarrier {
+    wq: WaitQueue,
+    count: u32,
+    arrived: AtomicU32,
+    generation: AtomicU32,
+}
+
+impl PthreadBarrier {
+    const fn new(count: u32) -> Self {
+        Self {
+            wq: WaitQueue::new(),
+            count,
+            arrived: AtomicU32::new(0),
+            generation: AtomicU32::new(0),
+        }
+    }
+
+    /// Returns `true` for the last thread arriving at the barrier.
+    fn wait(&self) -> LinuxResult<bool> {
+        let generation = self.generation.load(Ordering::Acquire);
+        if self.arrived.fetch_add(1, Ordering::AcqRel) + 1 == self.count {
+            self.arrived.store(0, Ordering::Relaxed);
+            self.generation.fetch_add(1, Ordering::Release);
+            self.wq.notify_all(true);
+            Ok(true)
+        } else {
+            self.wq
+                .wait_until(|| self.generation.load(Ordering::Acquire) != generation);
+            Ok(false)
+        }
+    }
+}
+
+/// Initialize a barrier which releases the waiting threads once `count`
+/// threads have called `sys_pthread_barrier_wait`.
+pub fn sys_pthread_barrier_init(
+    barrier: *mut ctypes::pthread_barrier_t,
+    _attr: *const ctypes::pthread_barrierattr_t,
+    count: c_uint,
+) -> c_int {
+    debug!(
+        "sys_pthread_barrier_init <= {:#x}, count: {}",
+        barrier as usize, count
+    );
+    syscall_body!(sys_pthread_barrier_init, {
+        check_null_mut_ptr(barrier)?;
+        if count == 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        unsafe {
+            barrier
+                .cast::<PthreadBarrier>()
+                .write(PthreadBarrier::new(count));
+        }
+        Ok(0)
+    })
+}
+
+/// Destroy the given barrier.
+pub fn sys_pthread_barrier_destroy(barrier: *mut ctypes::pthread_barrier_t) -> c_int {
+    debug!("sys_pthread_barrier_destroy <= {:#x}", barrier as usize);
+    syscall_body!(sys_pthread_barrier_destroy, {
+        check_null_mut_ptr(barrier)?;
+        let barrier = barrier.cast::<PthreadBarrier>();
+        unsafe {
+            if (*barrier).arrived.load(Ordering::Relaxed) != 0 {
+                return Err(LinuxError::EBUSY);
+            }
+            barrier.drop_in_place();
+        }
+        Ok(0)
+    })
+}
+
+/// Block the calling thread until the required number of threads have
+/// called it on the given barrier.
+///
+/// Returns `PTHREAD_BARRIER_SERIAL_THREAD` to exactly one of the released
+/// threads and 0 to the others.
+pub fn sys_pthread_barrier_wait(barrier: *mut ctypes::pthread_barrier_t) -> c_int {
+    debug!("sys_pthread_barrier_wait <= {:#x}", barrier as usize);
+    syscall_body!(sys_pthread_barrier_wait, {
+        check_null_mut_ptr(barrier)?;
+        let serial = unsafe { (*barrier.cast::<PthreadBarrier>()).wait()? };
+        if serial {
+            Ok(ctypes::PTHREAD_BARRIER_SERIAL_THREAD)
+        } else {
+            Ok(0)
+        }
+    })
+}
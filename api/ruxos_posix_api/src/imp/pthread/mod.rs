@@ -9,7 +9,7 @@
 
 use alloc::{boxed::Box, collections::BTreeMap, sync::Arc};
 use core::cell::UnsafeCell;
-use core::ffi::{c_int, c_void};
+use core::ffi::{c_char, c_int, c_void, CStr};
 
 use axerrno::{LinuxError, LinuxResult};
 use ruxtask::AxTaskRef;
@@ -17,8 +17,10 @@ use spin::RwLock;
 
 use crate::ctypes;
 
+pub mod barrier;
 pub mod condvar;
 pub mod mutex;
+pub mod rwlock;
 
 pub mod futex;
 
@@ -68,7 +70,7 @@ pub struct Pthread {
 
 impl Pthread {
     fn create(
-        _attr: *const ctypes::pthread_attr_t,
+        attr: *const ctypes::pthread_attr_t,
         start_routine: extern "C" fn(arg: *mut c_void) -> *mut c_void,
         arg: *mut c_void,
     ) -> LinuxResult<ctypes::pthread_t> {
@@ -86,7 +88,18 @@ impl Pthread {
             drop(their_packet);
         };
 
-        let task_inner = ruxtask::spawn(main);
+        let stack_size = if attr.is_null() {
+            ruxconfig::TASK_STACK_SIZE
+        } else {
+            // `_a_stacksize` in `pthread_attr_t`.
+            match unsafe { (*attr).__u.__s[0] } as usize {
+                0 => ruxconfig::TASK_STACK_SIZE,
+                size => size,
+            }
+        };
+        // like Linux, failing to allocate the stack is a lack of resources
+        let task_inner =
+            ruxtask::try_spawn_raw(main, "".into(), stack_size).map_err(|_| LinuxError::EAGAIN)?;
         let tid = task_inner.id().as_u64();
         let thread = Pthread {
             inner: task_inner,
@@ -98,6 +111,9 @@ impl Pthread {
     }
 
     /// Posix create, used by musl libc
+    ///
+    /// A child task is created in a new thread group, and is not runnable
+    /// until it is put into the run queue.
     #[cfg(feature = "musl")]
     fn pcreate(
         _attr: *const ctypes::pthread_attr_t,
@@ -106,6 +122,7 @@ impl Pthread {
         tls: *mut c_void,
         set_tid: core::sync::atomic::AtomicU64,
         tl: core::sync::atomic::AtomicU64,
+        is_child: bool,
     ) -> LinuxResult<(u64, AxTaskRef)> {
         let arg_wrapper = ForceSendSync(arg);
 
@@ -118,7 +135,8 @@ impl Pthread {
             start_routine(arg.0);
         };
 
-        let task_inner = ruxtask::pspawn(main, tls as usize, set_tid, tl);
+        let task_inner = ruxtask::pspawn(main, tls as usize, set_tid, tl, is_child)
+            .map_err(|_| LinuxError::EAGAIN)?;
 
         let tid = task_inner.id().as_u64();
         let thread = Pthread {
@@ -143,7 +161,7 @@ impl Pthread {
     }
 
     #[cfg(feature = "musl")]
-    fn exit_musl(_retcode: usize) -> ! {
+    fn exit_musl(retcode: usize) -> ! {
         let tid = Self::current()
             .expect("fail to get current thread")
             .inner
@@ -154,7 +172,7 @@ impl Pthread {
         TID_TO_PTHREAD.write().remove(&tid);
         debug!("Exit_musl, tid: {}", tid);
         drop(thread);
-        ruxtask::exit(0)
+        ruxtask::exit(retcode as i32)
     }
 
     #[cfg(not(feature = "musl"))]
@@ -179,6 +197,13 @@ impl Pthread {
     }
 }
 
+/// Gets the task of the thread with the given ID.
+pub(crate) fn tid_to_task(tid: u64) -> Option<AxTaskRef> {
+    let threads = TID_TO_PTHREAD.read();
+    let thread = threads.get(&tid)?.0 as *const Pthread;
+    Some(unsafe { (*thread).inner.clone() })
+}
+
 /// Returns the `pthread` struct of current thread.
 pub fn sys_pthread_self() -> ctypes::pthread_t {
     Pthread::current().expect("fail to get current thread") as *const Pthread as _
@@ -239,6 +264,51 @@ pub unsafe fn sys_pthread_join(thread: ctypes::pthread_t, retval: *mut *mut c_vo
     })
 }
 
+/// Sets the name of the given thread.
+///
+/// Like Linux, the name is truncated to 15 bytes.
+pub unsafe fn sys_pthread_setname_np(thread: ctypes::pthread_t, name: *const c_char) -> c_int {
+    debug!("sys_pthread_setname_np <= {:#x}", thread as usize);
+    syscall_body!(sys_pthread_setname_np, {
+        if thread.is_null() || name.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        let name = unsafe { CStr::from_ptr(name) }
+            .to_str()
+            .map_err(|_| LinuxError::EINVAL)?;
+        let thread = unsafe { &*(thread as *const Pthread) };
+        thread.inner.set_name(name);
+        Ok(0)
+    })
+}
+
+/// Stores the NUL-terminated name of the given thread in `buf`.
+///
+/// Returns `ERANGE` if `len` is too small for the name and the terminator.
+pub unsafe fn sys_pthread_getname_np(
+    thread: ctypes::pthread_t,
+    buf: *mut c_char,
+    len: ctypes::size_t,
+) -> c_int {
+    debug!("sys_pthread_getname_np <= {:#x}, {}", thread as usize, len);
+    syscall_body!(sys_pthread_getname_np, {
+        if thread.is_null() || buf.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        let thread = unsafe { &*(thread as *const Pthread) };
+        thread.inner.with_name(|name| {
+            if name.len() >= len as usize {
+                return Err(LinuxError::ERANGE);
+            }
+            unsafe {
+                core::ptr::copy_nonoverlapping(name.as_ptr(), buf as *mut u8, name.len());
+                *buf.add(name.len()) = 0;
+            }
+            Ok(0)
+        })
+    })
+}
+
 #[derive(Clone, Copy)]
 struct ForceSendSync<T>(T);
 
@@ -263,10 +333,20 @@ pub unsafe fn sys_clone(
     );
 
     syscall_body!(sys_clone, {
-        if (flags as u32 & ctypes::CLONE_THREAD) == 0 {
-            debug!("ONLY support thread");
+        // Without `CLONE_THREAD`, the new task is a child that `wait4` can
+        // reap, but it still has to share the address space.
+        let is_child = (flags as u32 & ctypes::CLONE_THREAD) == 0;
+        if is_child && (flags as u32 & ctypes::CLONE_VM) == 0 {
+            debug!("ONLY support thread and CLONE_VM child");
             return Err(LinuxError::EINVAL);
         }
+        // Without `CLONE_SETTLS`, the new task keeps the TLS of the caller,
+        // e.g. the child of `posix_spawn` until it calls `execve`.
+        let tls = if (flags as u32 & ctypes::CLONE_SETTLS) != 0 {
+            tls
+        } else {
+            ruxhal::arch::read_thread_pointer() as *mut c_void
+        };
 
         let func = unsafe {
             core::mem::transmute::<*const (), extern "C" fn(arg: *mut c_void) -> *mut c_void>(
@@ -280,6 +360,12 @@ pub unsafe fn sys_clone(
         } else {
             core::sync::atomic::AtomicU64::new(0)
         };
+        // the other callers than `pthread_create` may leave `ctid` undefined
+        let clear_tid = if (flags as u32 & ctypes::CLONE_CHILD_CLEARTID) != 0 {
+            core::sync::atomic::AtomicU64::new(ctid as _)
+        } else {
+            core::sync::atomic::AtomicU64::new(0)
+        };
 
         let (tid, task_inner) = Pthread::pcreate(
             core::ptr::null(),
@@ -287,7 +373,8 @@ pub unsafe fn sys_clone(
             args,
             tls,
             set_tid,
-            core::sync::atomic::AtomicU64::from(ctid as u64),
+            clear_tid,
+            is_child,
         )?;
 
         // write tid to ptid
@@ -295,7 +382,16 @@ pub unsafe fn sys_clone(
             unsafe { *ptid = tid as c_int };
         }
 
-        ruxtask::put_task(task_inner);
+        if is_child {
+            ruxtask::current().add_child(task_inner.clone());
+        }
+        ruxtask::put_task(task_inner.clone());
+
+        // like `vfork`, the caller is suspended until the child releases the
+        // memory it shares by calling `execve` or exiting
+        if (flags as u32 & ctypes::CLONE_VFORK) != 0 {
+            task_inner.wait_for_exec();
+        }
 
         Ok(tid)
     })
@@ -317,10 +413,20 @@ pub unsafe fn sys_clone(
     );
 
     syscall_body!(sys_clone, {
-        if (flags as u32 & ctypes::CLONE_THREAD) == 0 {
-            debug!("ONLY support thread");
+        // Without `CLONE_THREAD`, the new task is a child that `wait4` can
+        // reap, but it still has to share the address space.
+        let is_child = (flags as u32 & ctypes::CLONE_THREAD) == 0;
+        if is_child && (flags as u32 & ctypes::CLONE_VM) == 0 {
+            debug!("ONLY support thread and CLONE_VM child");
             return Err(LinuxError::EINVAL);
         }
+        // Without `CLONE_SETTLS`, the new task keeps the TLS of the caller,
+        // e.g. the child of `posix_spawn` until it calls `execve`.
+        let tls = if (flags as u32 & ctypes::CLONE_SETTLS) != 0 {
+            tls
+        } else {
+            ruxhal::arch::read_thread_pointer() as *mut c_void
+        };
 
         let func = unsafe {
             core::mem::transmute::<*const (), extern "C" fn(arg: *mut c_void) -> *mut c_void>(
@@ -334,6 +440,12 @@ pub unsafe fn sys_clone(
         } else {
             core::sync::atomic::AtomicU64::new(0)
         };
+        // the other callers than `pthread_create` may leave `ctid` undefined
+        let clear_tid = if (flags as u32 & ctypes::CLONE_CHILD_CLEARTID) != 0 {
+            core::sync::atomic::AtomicU64::new(ctid as _)
+        } else {
+            core::sync::atomic::AtomicU64::new(0)
+        };
 
         let (tid, task_inner) = Pthread::pcreate(
             core::ptr::null(),
@@ -341,7 +453,8 @@ pub unsafe fn sys_clone(
             args,
             tls,
             set_tid,
-            core::sync::atomic::AtomicU64::from(ctid as u64),
+            clear_tid,
+            is_child,
         )?;
 
         // write tid to ptid
@@ -349,7 +462,16 @@ pub unsafe fn sys_clone(
             unsafe { *ptid = tid as c_int };
         }
 
-        ruxtask::put_task(task_inner);
+        if is_child {
+            ruxtask::current().add_child(task_inner.clone());
+        }
+        ruxtask::put_task(task_inner.clone());
+
+        // like `vfork`, the caller is suspended until the child releases the
+        // memory it shares by calling `execve` or exiting
+        if (flags as u32 & ctypes::CLONE_VFORK) != 0 {
+            task_inner.wait_for_exec();
+        }
 
         Ok(tid)
     })
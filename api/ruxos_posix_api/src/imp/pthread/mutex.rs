@@ -14,34 +14,94 @@ use axsync::Mutex;
 
 use core::ffi::c_int;
 use core::mem::{size_of, ManuallyDrop};
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 
 static_assertions::const_assert_eq!(
     size_of::<PthreadMutex>(),
     size_of::<ctypes::pthread_mutex_t>()
 );
 
+/// A mutex of the type `kind`, one of `PTHREAD_MUTEX_NORMAL`,
+/// `PTHREAD_MUTEX_RECURSIVE` and `PTHREAD_MUTEX_ERRORCHECK`.
+///
+/// The owner and the recursion count are only updated by the task holding
+/// the lock, so the other tasks never see their own ID in `owner`.
 #[repr(C)]
-pub struct PthreadMutex(Mutex<()>);
+pub struct PthreadMutex {
+    inner: Mutex<()>,
+    owner: AtomicU64,
+    kind: c_int,
+    count: AtomicU32,
+}
 
 impl PthreadMutex {
-    const fn new() -> Self {
-        Self(Mutex::new(()))
+    const fn new(kind: c_int) -> Self {
+        Self {
+            inner: Mutex::new(()),
+            owner: AtomicU64::new(0),
+            kind,
+            count: AtomicU32::new(0),
+        }
+    }
+
+    fn is_owner(&self) -> bool {
+        self.owner.load(Ordering::Relaxed) == ruxtask::current().id().as_u64()
+    }
+
+    /// Relocks the mutex already held by the current task, or fails.
+    fn relock(&self) -> LinuxResult {
+        match self.kind as u32 {
+            ctypes::PTHREAD_MUTEX_RECURSIVE => {
+                let count = self.count.load(Ordering::Relaxed);
+                if count == u32::MAX {
+                    return Err(LinuxError::EAGAIN);
+                }
+                self.count.store(count + 1, Ordering::Relaxed);
+                Ok(())
+            }
+            _ => Err(LinuxError::EDEADLK),
+        }
+    }
+
+    fn set_owner(&self) {
+        self.owner
+            .store(ruxtask::current().id().as_u64(), Ordering::Relaxed);
+        self.count.store(1, Ordering::Relaxed);
     }
 
     fn lock(&self) -> LinuxResult {
-        let _guard = ManuallyDrop::new(self.0.lock());
+        if self.kind as u32 != ctypes::PTHREAD_MUTEX_NORMAL && self.is_owner() {
+            return self.relock();
+        }
+        let _guard = ManuallyDrop::new(self.inner.lock());
+        self.set_owner();
         Ok(())
     }
 
     fn unlock(&self) -> LinuxResult {
-        unsafe { self.0.force_unlock() };
+        if self.kind as u32 != ctypes::PTHREAD_MUTEX_NORMAL {
+            if !self.is_owner() {
+                return Err(LinuxError::EPERM);
+            }
+            let count = self.count.load(Ordering::Relaxed) - 1;
+            self.count.store(count, Ordering::Relaxed);
+            if count > 0 {
+                return Ok(());
+            }
+        }
+        self.owner.store(0, Ordering::Relaxed);
+        unsafe { self.inner.force_unlock() };
         Ok(())
     }
 
     fn trylock(&self) -> LinuxResult {
-        match self.0.try_lock() {
+        if self.kind as u32 == ctypes::PTHREAD_MUTEX_RECURSIVE && self.is_owner() {
+            return self.relock();
+        }
+        match self.inner.try_lock() {
             Some(mutex_guard) => {
                 let _guard = ManuallyDrop::new(mutex_guard);
+                self.set_owner();
                 Ok(())
             }
             None => Err(LinuxError::EBUSY),
@@ -52,13 +112,21 @@ impl PthreadMutex {
 /// Initialize a mutex.
 pub fn sys_pthread_mutex_init(
     mutex: *mut ctypes::pthread_mutex_t,
-    _attr: *const ctypes::pthread_mutexattr_t,
+    attr: *const ctypes::pthread_mutexattr_t,
 ) -> c_int {
     debug!("sys_pthread_mutex_init <= {:#x}", mutex as usize);
     syscall_body!(sys_pthread_mutex_init, {
         check_null_mut_ptr(mutex)?;
+        // the type is in the low bits of the attributes, like in musl
+        let kind = if attr.is_null() {
+            ctypes::PTHREAD_MUTEX_NORMAL
+        } else {
+            unsafe { (*attr).__attr & 3 }
+        };
         unsafe {
-            mutex.cast::<PthreadMutex>().write(PthreadMutex::new());
+            mutex
+                .cast::<PthreadMutex>()
+                .write(PthreadMutex::new(kind as c_int));
         }
         Ok(0)
     })
@@ -0,0 +1,216 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use crate::{ctypes, utils::check_null_mut_ptr};
+
+use axerrno::{LinuxError, LinuxResult};
+use ruxtask::WaitQueue;
+
+use core::ffi::c_int;
+use core::mem::size_of;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+static_assertions::const_assert_eq!(
+    size_of::<PthreadRwLock>(),
+    size_of::<ctypes::pthread_rwlock_t>()
+);
+
+/// Bit of `state` set while a writer holds the lock, the remaining bits count readers.
+const WRITER_LOCKED: u32 = 1 << 31;
+
+/// A writer-preferring read/write lock.
+///
+/// Blocked readers and writers sleep in the same wait queue and are all woken
+/// up when the lock becomes available. Once a writer is waiting, new readers
+/// are not admitted, so a steady stream of readers can not starve writers.
+#[repr(C)]
+pub struct PthreadRwLock {
+    wq: WaitQueue,
+    state: AtomicU32,
+    writers_waiting: AtomicU32,
+}
+
+impl PthreadRwLock {
+    const fn new() -> Self {
+        Self {
+            wq: WaitQueue::new(),
+            state: AtomicU32::new(0),
+            writers_waiting: AtomicU32::new(0),
+        }
+    }
+
+    fn try_acquire_read(&self) -> bool {
+        if self.writers_waiting.load(Ordering::Acquire) != 0 {
+            return false;
+        }
+        let mut state = self.state.load(Ordering::Relaxed);
+        loop {
+            if state & WRITER_LOCKED != 0 {
+                return false;
+            }
+            match self.state.compare_exchange_weak(
+                state,
+                state + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(s) => state = s,
+            }
+        }
+    }
+
+    fn try_acquire_write(&self) -> bool {
+        self.state
+            .compare_exchange(0, WRITER_LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    fn rdlock(&self) -> LinuxResult {
+        if !self.try_acquire_read() {
+            self.wq.wait_until(|| self.try_acquire_read());
+        }
+        Ok(())
+    }
+
+    fn tryrdlock(&self) -> LinuxResult {
+        if self.try_acquire_read() {
+            Ok(())
+        } else {
+            Err(LinuxError::EBUSY)
+        }
+    }
+
+    fn wrlock(&self) -> LinuxResult {
+        if !self.try_acquire_write() {
+            self.writers_waiting.fetch_add(1, Ordering::AcqRel);
+            self.wq.wait_until(|| self.try_acquire_write());
+            self.writers_waiting.fetch_sub(1, Ordering::AcqRel);
+        }
+        Ok(())
+    }
+
+    fn trywrlock(&self) -> LinuxResult {
+        if self.try_acquire_write() {
+            Ok(())
+        } else {
+            Err(LinuxError::EBUSY)
+        }
+    }
+
+    fn unlock(&self) -> LinuxResult {
+        let state = self.state.load(Ordering::Relaxed);
+        if state == 0 {
+            return Err(LinuxError::EPERM);
+        }
+        if state & WRITER_LOCKED != 0 {
+            self.state.store(0, Ordering::Release);
+            self.wq.notify_all(true);
+        } else if self.state.fetch_sub(1, Ordering::Release) == 1 {
+            // The last reader leaves, let a waiting writer in.
+            self.wq.notify_all(true);
+        }
+        Ok(())
+    }
+}
+
+/// Initialize a read/write lock.
+pub fn sys_pthread_rwlock_init(
+    rwlock: *mut ctypes::pthread_rwlock_t,
+    _attr: *const ctypes::pthread_rwlockattr_t,
+) -> c_int {
+    debug!("sys_pthread_rwlock_init <= {:#x}", rwlock as usize);
+    syscall_body!(sys_pthread_rwlock_init, {
+        check_null_mut_ptr(rwlock)?;
+        unsafe {
+            rwlock.cast::<PthreadRwLock>().write(PthreadRwLock::new());
+        }
+        Ok(0)
+    })
+}
+
+/// Destroy the given read/write lock.
+pub fn sys_pthread_rwlock_destroy(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    debug!("sys_pthread_rwlock_destroy <= {:#x}", rwlock as usize);
+    syscall_body!(sys_pthread_rwlock_destroy, {
+        check_null_mut_ptr(rwlock)?;
+        let lock = rwlock.cast::<PthreadRwLock>();
+        unsafe {
+            if (*lock).state.load(Ordering::Relaxed) != 0 {
+                return Err(LinuxError::EBUSY);
+            }
+            lock.drop_in_place();
+        }
+        Ok(0)
+    })
+}
+
+/// Acquire the given read/write lock for reading.
+///
+/// It blocks while a writer holds the lock or is waiting for it.
+pub fn sys_pthread_rwlock_rdlock(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    debug!("sys_pthread_rwlock_rdlock <= {:#x}", rwlock as usize);
+    syscall_body!(sys_pthread_rwlock_rdlock, {
+        check_null_mut_ptr(rwlock)?;
+        unsafe {
+            (*rwlock.cast::<PthreadRwLock>()).rdlock()?;
+        }
+        Ok(0)
+    })
+}
+
+/// Acquire the given read/write lock for reading like sys_pthread_rwlock_rdlock,
+/// except that it returns EBUSY instead of blocking.
+pub fn sys_pthread_rwlock_tryrdlock(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    debug!("sys_pthread_rwlock_tryrdlock <= {:#x}", rwlock as usize);
+    syscall_body!(sys_pthread_rwlock_tryrdlock, {
+        check_null_mut_ptr(rwlock)?;
+        unsafe {
+            (*rwlock.cast::<PthreadRwLock>()).tryrdlock()?;
+        }
+        Ok(0)
+    })
+}
+
+/// Acquire the given read/write lock for writing.
+pub fn sys_pthread_rwlock_wrlock(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    debug!("sys_pthread_rwlock_wrlock <= {:#x}", rwlock as usize);
+    syscall_body!(sys_pthread_rwlock_wrlock, {
+        check_null_mut_ptr(rwlock)?;
+        unsafe {
+            (*rwlock.cast::<PthreadRwLock>()).wrlock()?;
+        }
+        Ok(0)
+    })
+}
+
+/// Acquire the given read/write lock for writing like sys_pthread_rwlock_wrlock,
+/// except that it returns EBUSY instead of blocking.
+pub fn sys_pthread_rwlock_trywrlock(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    debug!("sys_pthread_rwlock_trywrlock <= {:#x}", rwlock as usize);
+    syscall_body!(sys_pthread_rwlock_trywrlock, {
+        check_null_mut_ptr(rwlock)?;
+        unsafe {
+            (*rwlock.cast::<PthreadRwLock>()).trywrlock()?;
+        }
+        Ok(0)
+    })
+}
+
+/// Release a read or write lock held on the given read/write lock.
+pub fn sys_pthread_rwlock_unlock(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    debug!("sys_pthread_rwlock_unlock <= {:#x}", rwlock as usize);
+    syscall_body!(sys_pthread_rwlock_unlock, {
+        check_null_mut_ptr(rwlock)?;
+        unsafe {
+            (*rwlock.cast::<PthreadRwLock>()).unlock()?;
+        }
+        Ok(0)
+    })
+}
@@ -8,67 +8,84 @@
  */
 
 use crate::ctypes;
-use axerrno::LinuxError;
+use axerrno::{LinuxError, LinuxResult};
+use axsync::Mutex;
 use core::ffi::c_int;
 
+/// No limit on a resource.
+pub const RLIM_INFINITY: ctypes::rlim_t = !0;
+
+const NUM_RLIMITS: usize = ctypes::RLIMIT_NLIMITS as usize;
+
+/// Soft and hard limits of the process, indexed by `RLIMIT_*`.
+///
+/// Only `RLIMIT_NOFILE` is enforced, by the fd table. The other limits, such as
+/// `RLIMIT_STACK` and `RLIMIT_AS`, are stored and reported back, but not applied.
+static RLIMITS: Mutex<[ctypes::rlimit; NUM_RLIMITS]> = Mutex::new(default_rlimits());
+
+const fn default_rlimits() -> [ctypes::rlimit; NUM_RLIMITS] {
+    const fn limit(value: ctypes::rlim_t) -> ctypes::rlimit {
+        ctypes::rlimit {
+            rlim_cur: value,
+            rlim_max: value,
+        }
+    }
+    let mut limits = [limit(RLIM_INFINITY); NUM_RLIMITS];
+    limits[ctypes::RLIMIT_STACK as usize] = limit(ruxconfig::TASK_STACK_SIZE as _);
+    limits[ctypes::RLIMIT_NPROC as usize] = limit(1);
+    #[cfg(feature = "fd")]
+    {
+        limits[ctypes::RLIMIT_NOFILE as usize] = limit(ruxfdtable::RUX_FILE_LIMIT as _);
+    }
+    limits
+}
+
+fn rlimit_index(resource: c_int) -> LinuxResult<usize> {
+    match usize::try_from(resource) {
+        Ok(idx) if idx < NUM_RLIMITS => Ok(idx),
+        _ => Err(LinuxError::EINVAL),
+    }
+}
+
+/// Returns the soft limit of the number of open files, so that new fds are
+/// below it.
+///
+/// Files already open past the limit, as it may have been lowered, stay open.
+#[cfg(feature = "fd")]
+pub(crate) fn nofile_limit() -> usize {
+    let limit = RLIMITS.lock()[ctypes::RLIMIT_NOFILE as usize].rlim_cur;
+    limit.min(ruxfdtable::RUX_FILE_LIMIT as _) as usize
+}
+
+fn get_rlimit(resource: c_int) -> LinuxResult<ctypes::rlimit> {
+    let idx = rlimit_index(resource)?;
+    Ok(RLIMITS.lock()[idx])
+}
+
+fn set_rlimit(resource: c_int, new: ctypes::rlimit) -> LinuxResult {
+    let idx = rlimit_index(resource)?;
+    if new.rlim_cur > new.rlim_max {
+        return Err(LinuxError::EINVAL);
+    }
+    let mut limits = RLIMITS.lock();
+    // Only a privileged process may raise a hard limit, and the fd table can
+    // not grow.
+    if new.rlim_max > limits[idx].rlim_max {
+        return Err(LinuxError::EPERM);
+    }
+    limits[idx] = new;
+    Ok(())
+}
+
 /// Get resource limitations
 ///
-/// TODO: support more resource types
+/// The limits are those of the whole process, `RLIM_INFINITY` by default.
 pub unsafe fn sys_getrlimit(resource: c_int, rlimits: *mut ctypes::rlimit) -> c_int {
     debug!("sys_getrlimit <= {} {:#x}", resource, rlimits as usize);
     syscall_body!(sys_getrlimit, {
-        match resource as u32 {
-            ctypes::RLIMIT_CPU => {}
-            ctypes::RLIMIT_FSIZE => {}
-            ctypes::RLIMIT_DATA => {}
-            ctypes::RLIMIT_STACK => {}
-            ctypes::RLIMIT_CORE => {}
-            ctypes::RLIMIT_RSS => {}
-            ctypes::RLIMIT_NPROC => {}
-            ctypes::RLIMIT_NOFILE => {}
-            ctypes::RLIMIT_MEMLOCK => {}
-            ctypes::RLIMIT_AS => {}
-            ctypes::RLIMIT_LOCKS => {}
-            ctypes::RLIMIT_SIGPENDING => {}
-            ctypes::RLIMIT_MSGQUEUE => {}
-            ctypes::RLIMIT_NICE => {}
-            ctypes::RLIMIT_RTPRIO => {}
-            ctypes::RLIMIT_RTTIME => {}
-            ctypes::RLIMIT_NLIMITS => {}
-            _ => return Err(LinuxError::EINVAL),
-        }
-        if rlimits.is_null() {
-            return Ok(0);
-        }
-        match resource as u32 {
-            ctypes::RLIMIT_CPU => {}
-            ctypes::RLIMIT_FSIZE => {}
-            ctypes::RLIMIT_DATA => {}
-            ctypes::RLIMIT_STACK => unsafe {
-                (*rlimits).rlim_cur = ruxconfig::TASK_STACK_SIZE as _;
-                (*rlimits).rlim_max = ruxconfig::TASK_STACK_SIZE as _;
-            },
-            ctypes::RLIMIT_CORE => {}
-            ctypes::RLIMIT_RSS => {}
-            ctypes::RLIMIT_NPROC => unsafe {
-                (*rlimits).rlim_cur = 1;
-                (*rlimits).rlim_max = 1;
-            },
-            #[cfg(feature = "fd")]
-            ctypes::RLIMIT_NOFILE => unsafe {
-                (*rlimits).rlim_cur = ruxfdtable::RUX_FILE_LIMIT as _;
-                (*rlimits).rlim_max = ruxfdtable::RUX_FILE_LIMIT as _;
-            },
-            ctypes::RLIMIT_MEMLOCK => {}
-            ctypes::RLIMIT_AS => {}
-            ctypes::RLIMIT_LOCKS => {}
-            ctypes::RLIMIT_SIGPENDING => {}
-            ctypes::RLIMIT_MSGQUEUE => {}
-            ctypes::RLIMIT_NICE => {}
-            ctypes::RLIMIT_RTPRIO => {}
-            ctypes::RLIMIT_RTTIME => {}
-            ctypes::RLIMIT_NLIMITS => {}
-            _ => {}
+        let limit = get_rlimit(resource)?;
+        if !rlimits.is_null() {
+            unsafe { *rlimits = limit };
         }
         Ok(0)
     })
@@ -76,34 +93,90 @@ pub unsafe fn sys_getrlimit(resource: c_int, rlimits: *mut ctypes::rlimit) -> c_
 
 /// Set resource limitations
 ///
-/// TODO: support more resource types
+/// The soft limit can be changed up to the hard limit, and the hard limit can
+/// only be lowered, as the caller is not privileged. `RLIMIT_NOFILE` may be
+/// lowered below the highest fd open, which only fails new fds past it.
 pub unsafe fn sys_setrlimit(resource: c_int, rlimits: *const ctypes::rlimit) -> c_int {
     debug!("sys_setrlimit <= {} {:#x}", resource, rlimits as usize);
     syscall_body!(sys_setrlimit, {
-        match resource as u32 {
-            ctypes::RLIMIT_DATA => {}
-            ctypes::RLIMIT_STACK => {}
-            ctypes::RLIMIT_NOFILE => {}
-            _ => return Err(LinuxError::EINVAL),
+        if rlimits.is_null() {
+            return Err(LinuxError::EFAULT);
         }
-        // Currently do not support set resources
+        set_rlimit(resource, unsafe { *rlimits })?;
         Ok(0)
     })
 }
 
 /// set/get resource limitations
+///
+/// The old limit is returned in `old_limit` if the new one is set
+/// successfully.
 pub unsafe fn sys_prlimit64(
-    _pid: ctypes::pid_t,
+    pid: ctypes::pid_t,
     resource: c_int,
     new_limit: *const ctypes::rlimit,
     old_limit: *mut ctypes::rlimit,
 ) -> c_int {
-    debug!("sys_prlimit64 <= resource: {}", resource);
-    if !new_limit.is_null() {
-        return sys_setrlimit(resource, new_limit);
+    debug!("sys_prlimit64 <= pid: {}, resource: {}", pid, resource);
+    syscall_body!(sys_prlimit64, {
+        let old = get_rlimit(resource)?;
+        if !new_limit.is_null() {
+            set_rlimit(resource, unsafe { *new_limit })?;
+        }
+        if !old_limit.is_null() {
+            unsafe { *old_limit = old };
+        }
+        Ok(0)
+    })
+}
+
+/// Returns the CPU time and the voluntary and involuntary context switches
+/// of the process, or of the current thread only.
+pub(crate) fn task_usage(thread: bool) -> (core::time::Duration, u64, u64) {
+    #[cfg(feature = "multitask")]
+    {
+        let usage = if thread {
+            ruxtask::current().usage()
+        } else {
+            ruxtask::total_usage()
+        };
+        (usage.cpu_time, usage.nvcsw, usage.nivcsw)
     }
-    if !old_limit.is_null() {
-        return sys_getrlimit(resource, old_limit);
+    #[cfg(not(feature = "multitask"))]
+    {
+        // The only task has been running since boot.
+        let _ = thread;
+        (ruxhal::time::current_time(), 0, 0)
     }
-    0
+}
+
+/// Get resource usage
+///
+/// `RUSAGE_SELF` reports the usage of all the threads, and `RUSAGE_THREAD`
+/// that of the calling one. All the CPU time is reported as user time, as
+/// there is no distinction between user and kernel mode. The maximum
+/// resident set size is the peak of the heap, shared by all the threads.
+/// There are no child processes, so `RUSAGE_CHILDREN` reports nothing.
+pub unsafe fn sys_getrusage(who: c_int, usage: *mut ctypes::rusage) -> c_int {
+    debug!("sys_getrusage <= {} {:#x}", who, usage as usize);
+    syscall_body!(sys_getrusage, {
+        if usage.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        let mut ru: ctypes::rusage = unsafe { core::mem::zeroed() };
+        if who == ctypes::RUSAGE_SELF as c_int || who == ctypes::RUSAGE_THREAD as c_int {
+            let (cpu_time, nvcsw, nivcsw) = task_usage(who == ctypes::RUSAGE_THREAD as c_int);
+            ru.ru_utime = cpu_time.into();
+            ru.ru_nvcsw = nvcsw as _;
+            ru.ru_nivcsw = nivcsw as _;
+            #[cfg(feature = "alloc")]
+            {
+                ru.ru_maxrss = (axalloc::global_allocator().peak_used_bytes() / 1024) as _;
+            }
+        } else if who != ctypes::RUSAGE_CHILDREN {
+            return Err(LinuxError::EINVAL);
+        }
+        unsafe { *usage = ru };
+        Ok(0)
+    })
 }
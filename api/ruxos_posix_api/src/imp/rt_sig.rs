@@ -12,10 +12,9 @@
 use axerrno::LinuxError;
 
 use crate::ctypes;
-use core::{
-    ffi::c_int,
-    sync::atomic::{AtomicUsize, Ordering},
-};
+use core::ffi::c_int;
+#[cfg(not(feature = "signal"))]
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 enum RTSigprocmaskHow {
     Block = 0,
@@ -35,23 +34,35 @@ impl TryFrom<c_int> for RTSigprocmaskHow {
     }
 }
 
-static mut MASK_TMP: AtomicUsize = AtomicUsize::new(0);
+#[cfg(not(feature = "signal"))]
+static MASK_TMP: AtomicUsize = AtomicUsize::new(0);
 
-fn set_mask(old: *mut usize, new: usize) {
-    unsafe {
-        *old = new;
-    }
+/// `SIGKILL` and `SIGSTOP` can not be blocked.
+const UNBLOCKABLE: usize = (1 << (ctypes::SIGKILL - 1)) | (1 << (ctypes::SIGSTOP - 1));
+
+/// Get the blocked signals, where signal `n` is bit `n - 1` as in `sigset_t`.
+fn blocked_mask() -> usize {
+    #[cfg(feature = "signal")]
+    return (ruxruntime::Signal::blocked(None) >> 1) as usize;
+    #[cfg(not(feature = "signal"))]
+    MASK_TMP.load(Ordering::Acquire)
 }
 
-fn get_mask(mask: *const usize) -> usize {
-    unsafe { *mask }
+fn set_blocked_mask(mask: usize) {
+    let mask = mask & !UNBLOCKABLE;
+    #[cfg(feature = "signal")]
+    ruxruntime::Signal::blocked(Some((mask as u32) << 1));
+    #[cfg(not(feature = "signal"))]
+    MASK_TMP.store(mask, Ordering::Release);
 }
 
 /// Set mask for given thread
+///
+/// The mask is shared by all threads for now.
 pub fn sys_rt_sigprocmask(
     how: c_int,
-    _new_mask: *const usize,
-    _old_mask: *mut usize,
+    new_mask: *const usize,
+    old_mask: *mut usize,
     sigsetsize: usize,
 ) -> c_int {
     debug!(
@@ -60,25 +71,19 @@ pub fn sys_rt_sigprocmask(
     );
 
     syscall_body!(sys_rt_sigprocmask, {
-        if !_old_mask.is_null() {
-            unsafe {
-                let new = MASK_TMP.load(Ordering::Relaxed);
-                set_mask(_old_mask, new);
-            }
+        let old = blocked_mask();
+        if !new_mask.is_null() {
+            let set = unsafe { *new_mask };
+            match how.try_into() {
+                Ok(RTSigprocmaskHow::Block) => set_blocked_mask(old | set),
+                Ok(RTSigprocmaskHow::UnBlock) => set_blocked_mask(old & !set),
+                Ok(RTSigprocmaskHow::SetMask) => set_blocked_mask(set),
+                _ => return Err(LinuxError::EINVAL),
+            };
         }
-
-        if !_new_mask.is_null() {
-            unsafe {
-                let set = get_mask(_new_mask);
-                match how.try_into() {
-                    Ok(RTSigprocmaskHow::Block) => MASK_TMP.fetch_or(set, Ordering::Relaxed),
-                    Ok(RTSigprocmaskHow::UnBlock) => MASK_TMP.fetch_and(!set, Ordering::Relaxed),
-                    Ok(RTSigprocmaskHow::SetMask) => MASK_TMP.swap(set, Ordering::Relaxed),
-                    _ => return Err(LinuxError::EINVAL),
-                };
-            }
+        if !old_mask.is_null() {
+            unsafe { *old_mask = old };
         }
-
         Ok(0)
     })
 }
@@ -61,7 +61,7 @@ pub unsafe fn sys_getitimer(which: c_int, curr_value: *mut ctypes::itimerval) ->
         }
         let mut now: ctypes::timespec = ctypes::timespec::default();
         unsafe {
-            crate::sys_clock_gettime(0, &mut now);
+            crate::sys_clock_gettime(ctypes::CLOCK_MONOTONIC as _, &mut now);
         }
         let now = Duration::from(now);
         if ddl > now {
@@ -86,8 +86,27 @@ pub unsafe fn sys_sigaltstack(
     syscall_body!(sys_sigaltstack, Ok(0))
 }
 
-/// TODO: send a signal to a process
+/// Send a signal to a process
+///
+/// Only the current process can be signaled. The signal is left pending while
+/// it is blocked, otherwise its handler runs before returning. A zero `sig`
+/// only checks that the process exists.
 pub unsafe fn sys_kill(pid: pid_t, sig: c_int) -> c_int {
     debug!("sys_kill <= pid {} sig {}", pid, sig);
-    syscall_body!(sys_kill, Ok(0))
+    syscall_body!(sys_kill, {
+        if !(0..32).contains(&sig) {
+            return Err(LinuxError::EINVAL);
+        }
+        if pid > 0 && pid != crate::sys_getpid() {
+            return Err(LinuxError::ESRCH);
+        }
+        if sig == 0 {
+            return Ok(0);
+        }
+        Signal::signal(sig as i8, true);
+        if Signal::blocked(None) & (1 << sig) == 0 && Signal::take_pending(1 << sig).is_some() {
+            Signal::sigaction(sig as u8, None, None);
+        }
+        Ok(0)
+    })
 }
@@ -0,0 +1,167 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use alloc::sync::Arc;
+use core::ffi::{c_int, c_uint};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use axerrno::{LinuxError, LinuxResult};
+use axio::PollState;
+use ruxfdtable::{FileLike, RuxStat};
+use ruxruntime::Signal;
+
+use super::fd_ops::{add_file_like, get_file_like};
+use crate::{ctypes, sys_fcntl};
+
+const SIGINFO_SIZE: usize = core::mem::size_of::<ctypes::signalfd_siginfo>();
+
+pub struct SignalFd {
+    /// Signals accepted by this fd, where signal `n` is bit `n`.
+    mask: AtomicU32,
+    nonblocking: AtomicBool,
+}
+
+impl SignalFd {
+    pub fn new(mask: u32, nonblocking: bool) -> Self {
+        Self {
+            mask: AtomicU32::new(mask),
+            nonblocking: AtomicBool::new(nonblocking),
+        }
+    }
+
+    fn mask(&self) -> u32 {
+        self.mask.load(Ordering::Acquire)
+    }
+}
+
+impl FileLike for SignalFd {
+    fn read(&self, buf: &mut [u8]) -> LinuxResult<usize> {
+        if buf.len() < SIGINFO_SIZE {
+            return Err(LinuxError::EINVAL);
+        }
+        // Block until the first signal, then take as many as fit without waiting.
+        let mut read_len = 0;
+        for chunk in buf.chunks_exact_mut(SIGINFO_SIZE) {
+            let signum = loop {
+                if let Some(signum) = Signal::take_pending(self.mask()) {
+                    break signum;
+                }
+                if read_len > 0 {
+                    return Ok(read_len);
+                }
+                if self.nonblocking.load(Ordering::Acquire) {
+                    return Err(LinuxError::EAGAIN);
+                }
+                crate::sys_sched_yield(); // TODO: use synconize primitive
+            };
+            let info = ctypes::signalfd_siginfo {
+                ssi_signo: signum as u32,
+                ssi_pid: crate::sys_getpid() as u32,
+                ..Default::default()
+            };
+            // SAFETY: `signalfd_siginfo` is plain old data of `SIGINFO_SIZE` bytes.
+            let bytes = unsafe {
+                core::slice::from_raw_parts(&info as *const _ as *const u8, SIGINFO_SIZE)
+            };
+            chunk.copy_from_slice(bytes);
+            read_len += SIGINFO_SIZE;
+        }
+        Ok(read_len)
+    }
+
+    fn write(&self, _buf: &[u8]) -> LinuxResult<usize> {
+        Err(LinuxError::EINVAL)
+    }
+
+    fn flush(&self) -> LinuxResult {
+        Ok(())
+    }
+
+    fn stat(&self) -> LinuxResult<RuxStat> {
+        let st_mode = 0o600u32; // anonymous inode, rw-------
+        Ok(RuxStat::from(ctypes::stat {
+            st_ino: 1,
+            st_nlink: 1,
+            st_mode,
+            st_uid: 1000,
+            st_gid: 1000,
+            st_blksize: 4096,
+            ..Default::default()
+        }))
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn core::any::Any + Send + Sync> {
+        self
+    }
+
+    fn poll(&self) -> LinuxResult<PollState> {
+        let pending = Signal::signal(-1, true).unwrap_or(0);
+        Ok(PollState {
+            readable: pending & self.mask() != 0,
+            writable: false,
+            ..Default::default()
+        })
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> LinuxResult {
+        self.nonblocking.store(nonblocking, Ordering::Release);
+        Ok(())
+    }
+}
+
+/// Create a file descriptor for accepting signals, or update the mask of an existing one
+///
+/// The signals in `mask` should be blocked by `sigprocmask`, so they stay pending
+/// until they are read from the fd instead of running their handlers.
+pub unsafe fn sys_signalfd4(
+    fd: c_int,
+    mask: *const ctypes::sigset_t,
+    sigsetsize: usize,
+    flags: c_int,
+) -> c_int {
+    debug!(
+        "sys_signalfd4 <= fd: {}, sigsetsize: {}, flags: {:#x}",
+        fd, sigsetsize, flags
+    );
+    syscall_body!(sys_signalfd4, {
+        if mask.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        if sigsetsize != core::mem::size_of::<u64>() {
+            return Err(LinuxError::EINVAL);
+        }
+        let flags = flags as c_uint;
+        if flags & !(ctypes::SFD_NONBLOCK | ctypes::SFD_CLOEXEC) != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        // In `sigset_t` signal `n` is bit `n - 1`, only the first 31 signals are supported.
+        let mask = (unsafe { *(mask as *const u64) } as u32) << 1;
+
+        if fd != -1 {
+            let sfd = get_file_like(fd)?
+                .into_any()
+                .downcast::<SignalFd>()
+                .map_err(|_| LinuxError::EINVAL)?;
+            sfd.mask.store(mask, Ordering::Release);
+            return Ok(fd);
+        }
+
+        let sfd = SignalFd::new(mask, flags & ctypes::SFD_NONBLOCK != 0);
+        let fd = add_file_like(Arc::new(sfd))? as c_int;
+        if flags & ctypes::SFD_CLOEXEC != 0 {
+            sys_fcntl(fd, ctypes::F_SETFD as _, ctypes::FD_CLOEXEC as _);
+        }
+        Ok(fd)
+    })
+}
+
+/// `signalfd` syscall, used by x86_64, which takes no flags
+pub unsafe fn sys_signalfd(fd: c_int, mask: *const ctypes::sigset_t, sigsetsize: usize) -> c_int {
+    sys_signalfd4(fd, mask, sigsetsize, 0)
+}
@@ -12,10 +12,26 @@ use core::ffi::c_int;
 
 /// Set file mode creation mask
 ///
-/// TODO:
+/// The permission bits set in the mask are cleared on the new files and
+/// directories. It is `0o022` by default.
+///
+/// Return the previous mask.
 pub fn sys_umask(mode: ctypes::mode_t) -> ctypes::mode_t {
-    debug!("sys_umask <= mode: {:x}", mode);
-    syscall_body!(sys_umask, Ok(0))
+    debug!("sys_umask <= mode: {:#o}", mode);
+    syscall_body!(sys_umask, {
+        #[cfg(feature = "fs")]
+        {
+            let mask = ruxfs::fops::FilePerm::from_bits_truncate(mode as u16 & 0o777);
+            Ok(ruxfs::fops::set_umask(mask).bits() as ctypes::mode_t)
+        }
+        #[cfg(not(feature = "fs"))]
+        {
+            // no file can be created, the mask is only remembered
+            use core::sync::atomic::{AtomicU32, Ordering};
+            static UMASK: AtomicU32 = AtomicU32::new(0o022);
+            Ok(UMASK.swap(mode & 0o777, Ordering::AcqRel))
+        }
+    })
 }
 
 /// Returns the effective user ID of the calling process
@@ -16,6 +16,7 @@ use {
     alloc::sync::Arc,
     axerrno::{AxError, LinuxError, LinuxResult},
     axio::PollState,
+    core::ffi::c_int,
     core::sync::atomic::{AtomicBool, Ordering},
 };
 
@@ -80,12 +81,15 @@ impl Stdin {
         }
     }
 
-    // Attempt a non-blocking read operation.
+    // Attempt a non-blocking read operation, which fails with `WouldBlock` if
+    // no line is typed yet.
     #[cfg(feature = "fd")]
     fn read_nonblocked(&self, buf: &mut [u8]) -> AxResult<usize> {
         if let Some(mut inner) = self.inner.try_lock() {
-            let read_len = inner.read(buf)?;
-            Ok(read_len)
+            match inner.read(buf)? {
+                0 if !buf.is_empty() => Err(AxError::WouldBlock),
+                read_len => Ok(read_len),
+            }
         } else {
             Err(AxError::WouldBlock)
         }
@@ -128,6 +132,34 @@ pub fn stdout() -> Stdout {
     Stdout { inner: &INSTANCE }
 }
 
+/// Terminal I/O control operations, shared by stdin and stdout.
+#[cfg(feature = "fd")]
+fn console_ioctl(cmd: usize, arg: usize) -> LinuxResult<c_int> {
+    use super::ioctl::*;
+
+    static WINSIZE: Mutex<ConsoleWinSize> = Mutex::new(ConsoleWinSize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    });
+
+    match cmd {
+        TIOCGWINSZ => unsafe { *(arg as *mut ConsoleWinSize) = *WINSIZE.lock() },
+        TIOCSWINSZ => *WINSIZE.lock() = unsafe { *(arg as *const ConsoleWinSize) },
+        TCGETS => debug!("sys_ioctl: tty TCGETS"),
+        TIOCSPGRP => warn!("stdout pretend to be tty"),
+        TIOCGPGRP => {
+            warn!("stdout TIOCGPGRP, pretend to be have a tty process group.");
+            unsafe {
+                *(arg as *mut u32) = crate::sys_getpgid(0) as _;
+            }
+        }
+        _ => return Err(LinuxError::EINVAL),
+    }
+    Ok(0)
+}
+
 #[cfg(feature = "fd")]
 impl ruxfdtable::FileLike for Stdin {
     fn read(&self, buf: &mut [u8]) -> LinuxResult<usize> {
@@ -163,6 +195,7 @@ impl ruxfdtable::FileLike for Stdin {
         Ok(PollState {
             readable: true,
             writable: true,
+            ..Default::default()
         })
     }
 
@@ -170,6 +203,10 @@ impl ruxfdtable::FileLike for Stdin {
         self.nonblocking.store(nonblocking, Ordering::Relaxed);
         Ok(())
     }
+
+    fn ioctl(&self, cmd: usize, arg: usize) -> LinuxResult<c_int> {
+        console_ioctl(cmd, arg)
+    }
 }
 
 #[cfg(feature = "fd")]
@@ -204,10 +241,15 @@ impl ruxfdtable::FileLike for Stdout {
         Ok(PollState {
             readable: true,
             writable: true,
+            ..Default::default()
         })
     }
 
     fn set_nonblocking(&self, _nonblocking: bool) -> LinuxResult {
         Ok(())
     }
+
+    fn ioctl(&self, cmd: usize, arg: usize) -> LinuxResult<c_int> {
+        console_ioctl(cmd, arg)
+    }
 }
@@ -11,6 +11,10 @@ use core::ffi::{c_int, c_long};
 
 use crate::ctypes;
 
+/// bits to shift the load averages in `struct sysinfo`
+#[cfg(feature = "multitask")]
+const SI_LOAD_SHIFT: u64 = 16;
+
 /// Return sysinfo struct
 #[no_mangle]
 pub unsafe extern "C" fn sys_sysinfo(info: *mut ctypes::sysinfo) -> c_int {
@@ -25,11 +29,18 @@ pub unsafe extern "C" fn sys_sysinfo(info: *mut ctypes::sysinfo) -> c_int {
         #[cfg(feature = "multitask")]
         {
             ruxtask::get_avenrun(&mut info_mut.loads);
+            // `loads` is shifted by `SI_LOAD_SHIFT` bits in `struct sysinfo`
+            for load in info_mut.loads.iter_mut() {
+                *load <<= SI_LOAD_SHIFT - ruxtask::FSHIFT;
+            }
         }
 
         info_mut.sharedram = 0;
-        // TODO
         info_mut.bufferram = 0;
+        #[cfg(feature = "fs")]
+        {
+            info_mut.bufferram = ruxfs::fops::cached_bytes() as _;
+        }
 
         info_mut.totalram = 0;
         info_mut.freeram = 0;
@@ -37,10 +48,8 @@ pub unsafe extern "C" fn sys_sysinfo(info: *mut ctypes::sysinfo) -> c_int {
         {
             use core::ffi::c_ulong;
             let allocator = axalloc::global_allocator();
-            info_mut.freeram = (allocator.available_bytes()
-                + allocator.available_pages() * memory_addr::PAGE_SIZE_4K)
-                as c_ulong;
-            info_mut.totalram = info_mut.freeram + allocator.used_bytes() as c_ulong;
+            info_mut.freeram = allocator.free_memory() as c_ulong;
+            info_mut.totalram = allocator.total_memory() as c_ulong;
         }
 
         // TODO
@@ -48,6 +57,10 @@ pub unsafe extern "C" fn sys_sysinfo(info: *mut ctypes::sysinfo) -> c_int {
         info_mut.freeswap = 0;
 
         info_mut.procs = 1;
+        #[cfg(feature = "multitask")]
+        {
+            info_mut.procs = ruxtask::task_count().min(u16::MAX as usize) as _;
+        }
 
         // unused in 64-bit
         info_mut.totalhigh = 0;
@@ -7,8 +7,18 @@
  *   See the Mulan PSL v2 for more details.
  */
 
+use crate::ctypes;
+use axerrno::LinuxError;
 use core::ffi::c_int;
 
+#[cfg(feature = "multitask")]
+use {
+    crate::utils::{check_null_mut_ptr, check_null_ptr},
+    axerrno::LinuxResult,
+    core::mem::size_of,
+    ruxtask::SchedPolicy,
+};
+
 /// Relinquish the CPU, and switches to another task.
 ///
 /// For single-threaded configuration (`multitask` feature is disabled), we just
@@ -49,11 +59,253 @@ pub fn sys_getppid() -> c_int {
     syscall_body!(sys_getppid, Ok(1))
 }
 
+/// Wait for the child `pid` to exit, where -1, 0 or below means any child,
+/// and return its ID.
+///
+/// The exit status is stored in `wstatus` as `WEXITSTATUS` decodes it, and
+/// `rusage` is cleared as the usage of children is not tracked. With
+/// `WNOHANG`, it returns 0 at once if no child has exited yet. Only the tasks
+/// created by `clone` without `CLONE_THREAD` are children, it fails with
+/// `ECHILD` if there is none.
+pub unsafe fn sys_wait4(
+    pid: ctypes::pid_t,
+    wstatus: *mut c_int,
+    options: c_int,
+    rusage: *mut ctypes::rusage,
+) -> ctypes::pid_t {
+    debug!(
+        "sys_wait4 <= {} {:#x} {:#x} {:#x}",
+        pid, wstatus as usize, options, rusage as usize
+    );
+    syscall_body!(sys_wait4, {
+        let valid_options = ctypes::WNOHANG | ctypes::WUNTRACED | ctypes::WCONTINUED;
+        if options & !(valid_options as c_int) != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        #[cfg(not(feature = "multitask"))]
+        {
+            let _ = (pid, wstatus, rusage);
+            Err(LinuxError::ECHILD)
+        }
+        #[cfg(feature = "multitask")]
+        {
+            // there are no process groups, so any child matches a group
+            let id = (pid > 0).then_some(pid as u64);
+            let curr = ruxtask::current();
+            #[cfg(feature = "signal")]
+            let delivered = ruxruntime::Signal::delivered();
+            loop {
+                if let Some((child, exit_code)) = curr.reap_child(id) {
+                    if !wstatus.is_null() {
+                        unsafe { *wstatus = (exit_code & 0xff) << 8 };
+                    }
+                    if !rusage.is_null() {
+                        unsafe { *rusage = core::mem::zeroed() };
+                    }
+                    return Ok(child as ctypes::pid_t);
+                }
+                if !curr.has_child(id) {
+                    return Err(LinuxError::ECHILD);
+                }
+                if options & ctypes::WNOHANG as c_int != 0 {
+                    return Ok(0);
+                }
+                #[cfg(feature = "signal")]
+                if ruxruntime::Signal::delivered() != delivered {
+                    return Err(LinuxError::EINTR);
+                }
+                crate::sys_sched_yield();
+            }
+        }
+    })
+}
+
+/// Gets the task of the given thread ID, where 0 means the calling thread.
+#[cfg(feature = "multitask")]
+fn pid_to_task(pid: ctypes::pid_t) -> LinuxResult<ruxtask::AxTaskRef> {
+    let curr = ruxtask::current();
+    if pid == 0 || pid as u64 == curr.id().as_u64() {
+        return Ok(curr.as_task_ref().clone());
+    }
+    if pid < 0 {
+        return Err(LinuxError::EINVAL);
+    }
+    super::pthread::tid_to_task(pid as u64).ok_or(LinuxError::ESRCH)
+}
+
+/// Set the CPU affinity mask of the thread `pid` to the first `cpusetsize`
+/// bytes of `mask`.
+///
+/// Returns `EINVAL` if none of the CPUs in the mask exists.
+#[cfg(feature = "multitask")]
+pub unsafe fn sys_sched_setaffinity(
+    pid: ctypes::pid_t,
+    cpusetsize: ctypes::size_t,
+    mask: *const ctypes::cpu_set_t,
+) -> c_int {
+    debug!(
+        "sys_sched_setaffinity <= {} {} {:#x}",
+        pid, cpusetsize, mask as usize
+    );
+    syscall_body!(sys_sched_setaffinity, {
+        check_null_ptr(mask)?;
+        let mut bits = [0u8; size_of::<usize>()];
+        let len = bits.len().min(cpusetsize as usize);
+        bits[..len].copy_from_slice(unsafe { core::slice::from_raw_parts(mask.cast(), len) });
+        let task = pid_to_task(pid)?;
+        if !ruxtask::set_affinity(&task, usize::from_ne_bytes(bits)) {
+            return Err(LinuxError::EINVAL);
+        }
+        Ok(0)
+    })
+}
+
+/// Store the CPU affinity mask of the thread `pid` in `mask`, whose size is
+/// `cpusetsize` bytes.
+///
+/// Like the Linux system call, it returns the number of bytes of the mask
+/// stored, the rest of `mask` is cleared.
+#[cfg(feature = "multitask")]
+pub unsafe fn sys_sched_getaffinity(
+    pid: ctypes::pid_t,
+    cpusetsize: ctypes::size_t,
+    mask: *mut ctypes::cpu_set_t,
+) -> c_int {
+    debug!(
+        "sys_sched_getaffinity <= {} {} {:#x}",
+        pid, cpusetsize, mask as usize
+    );
+    syscall_body!(sys_sched_getaffinity, {
+        check_null_mut_ptr(mask)?;
+        let cpusetsize = cpusetsize as usize;
+        if cpusetsize < size_of::<usize>() {
+            return Err(LinuxError::EINVAL);
+        }
+        let bits = pid_to_task(pid)?.cpu_mask().to_ne_bytes();
+        let dst = unsafe { core::slice::from_raw_parts_mut(mask.cast::<u8>(), cpusetsize) };
+        dst.fill(0);
+        dst[..bits.len()].copy_from_slice(&bits);
+        Ok(bits.len() as c_int)
+    })
+}
+
+/// Set the scheduling policy and the static priority of the thread `pid`.
+///
+/// `SCHED_OTHER`, the default policy, takes priority 0, while `SCHED_FIFO`
+/// and `SCHED_RR` take priorities from 1 to 99, which are only supported by
+/// the `sched_prio` scheduler. Other combinations are `EINVAL`.
+#[cfg(feature = "multitask")]
+pub unsafe fn sys_sched_setscheduler(
+    pid: ctypes::pid_t,
+    policy: c_int,
+    param: *const ctypes::sched_param,
+) -> c_int {
+    debug!(
+        "sys_sched_setscheduler <= {} {} {:#x}",
+        pid, policy, param as usize
+    );
+    syscall_body!(sys_sched_setscheduler, {
+        check_null_ptr(param)?;
+        let policy = policy_from_raw(policy)?;
+        let prio =
+            usize::try_from(unsafe { (*param).sched_priority }).map_err(|_| LinuxError::EINVAL)?;
+        let task = pid_to_task(pid)?;
+        if !ruxtask::set_sched_policy(&task, policy, prio) {
+            return Err(LinuxError::EINVAL);
+        }
+        Ok(0)
+    })
+}
+
+/// Get the scheduling policy of the thread `pid`.
+#[cfg(feature = "multitask")]
+pub fn sys_sched_getscheduler(pid: ctypes::pid_t) -> c_int {
+    debug!("sys_sched_getscheduler <= {}", pid);
+    syscall_body!(sys_sched_getscheduler, {
+        let (policy, _) = ruxtask::sched_policy(&pid_to_task(pid)?);
+        Ok(policy as c_int)
+    })
+}
+
+/// Store the static priority of the thread `pid` in `param`.
+#[cfg(feature = "multitask")]
+pub unsafe fn sys_sched_getparam(pid: ctypes::pid_t, param: *mut ctypes::sched_param) -> c_int {
+    debug!("sys_sched_getparam <= {} {:#x}", pid, param as usize);
+    syscall_body!(sys_sched_getparam, {
+        check_null_mut_ptr(param)?;
+        let (_, prio) = ruxtask::sched_policy(&pid_to_task(pid)?);
+        unsafe { (*param).sched_priority = prio as c_int };
+        Ok(0)
+    })
+}
+
+/// Get the highest static priority of the scheduling policy `policy`.
+#[cfg(feature = "multitask")]
+pub fn sys_sched_get_priority_max(policy: c_int) -> c_int {
+    debug!("sys_sched_get_priority_max <= {}", policy);
+    syscall_body!(sys_sched_get_priority_max, {
+        match policy_from_raw(policy)? {
+            SchedPolicy::Normal => Ok(0),
+            _ => Ok(ruxtask::RT_PRIORITY_MAX as c_int),
+        }
+    })
+}
+
+/// Get the lowest static priority of the scheduling policy `policy`.
+#[cfg(feature = "multitask")]
+pub fn sys_sched_get_priority_min(policy: c_int) -> c_int {
+    debug!("sys_sched_get_priority_min <= {}", policy);
+    syscall_body!(sys_sched_get_priority_min, {
+        match policy_from_raw(policy)? {
+            SchedPolicy::Normal => Ok(0),
+            _ => Ok(ruxtask::RT_PRIORITY_MIN as c_int),
+        }
+    })
+}
+
+#[cfg(feature = "multitask")]
+fn policy_from_raw(policy: c_int) -> LinuxResult<SchedPolicy> {
+    u32::try_from(policy)
+        .ok()
+        .and_then(SchedPolicy::try_from_raw)
+        .ok_or(LinuxError::EINVAL)
+}
+
 /// Exit current task
+///
+/// The other threads keep running, the program ends with the last one of them.
 pub fn sys_exit(exit_code: c_int) -> ! {
     debug!("sys_exit <= {}", exit_code);
     #[cfg(feature = "multitask")]
     ruxtask::exit(exit_code);
     #[cfg(not(feature = "multitask"))]
-    ruxhal::misc::terminate();
+    {
+        #[cfg(feature = "fs")]
+        let _ = ruxfs::sync();
+        ruxhal::misc::terminate();
+    }
+}
+
+/// Exit current task and all the other threads of its process, with
+/// `exit_code` as the exit status of the process.
+pub fn sys_exit_group(exit_code: c_int) -> ! {
+    debug!("sys_exit_group <= {}", exit_code);
+    #[cfg(feature = "multitask")]
+    ruxtask::exit_group(exit_code);
+    #[cfg(not(feature = "multitask"))]
+    {
+        #[cfg(feature = "fs")]
+        let _ = ruxfs::sync();
+        ruxhal::misc::terminate();
+    }
+}
+
+/// Exits the current task if another thread of its process called
+/// [`sys_exit_group`].
+///
+/// It is called on the return path of the system calls, where the task holds
+/// no lock.
+pub fn exit_if_group_exiting() {
+    #[cfg(feature = "multitask")]
+    ruxtask::exit_if_group_exiting();
 }
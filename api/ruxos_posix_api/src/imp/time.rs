@@ -7,12 +7,14 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use core::ffi::{c_int, c_long};
+use core::ffi::{c_int, c_long, c_void};
 use core::time::Duration;
 
+use super::resources::task_usage;
 use crate::ctypes;
 
 use axerrno::LinuxError;
+use ruxhal::time::{MICROS_PER_SEC, NANOS_PER_SEC};
 
 impl From<ctypes::timespec> for Duration {
     fn from(ts: ctypes::timespec) -> Self {
@@ -44,32 +46,96 @@ impl From<Duration> for ctypes::timeval {
     }
 }
 
-/// Get clock time since booting
-pub unsafe fn sys_clock_gettime(_clk: ctypes::clockid_t, ts: *mut ctypes::timespec) -> c_int {
+/// Time since booting, which never jumps.
+fn monotonic_nanos() -> u64 {
+    ruxhal::time::current_time_nanos()
+}
+
+/// The time the threads of the process, or the current one, have been running.
+fn cpu_time(thread: bool) -> Duration {
+    task_usage(thread).0
+}
+
+/// Get the time of the given clock.
+///
+/// `CLOCK_MONOTONIC` counts from booting and never jumps, `CLOCK_REALTIME` is
+/// the monotonic time plus the wall-clock time at boot, which can be changed
+/// by `sys_clock_settime`. `CLOCK_PROCESS_CPUTIME_ID` and
+/// `CLOCK_THREAD_CPUTIME_ID` only advance while the threads of the process,
+/// or the calling one, are running.
+pub unsafe fn sys_clock_gettime(clk: ctypes::clockid_t, ts: *mut ctypes::timespec) -> c_int {
     syscall_body!(sys_clock_gettime, {
         if ts.is_null() {
             return Err(LinuxError::EFAULT);
         }
-        let now = ruxhal::time::current_time().into();
+        let nanos = match clk as u32 {
+            ctypes::CLOCK_REALTIME | ctypes::CLOCK_REALTIME_COARSE => {
+                ruxhal::time::wall_time().as_nanos() as u64
+            }
+            ctypes::CLOCK_MONOTONIC
+            | ctypes::CLOCK_MONOTONIC_RAW
+            | ctypes::CLOCK_MONOTONIC_COARSE
+            | ctypes::CLOCK_BOOTTIME => monotonic_nanos(),
+            ctypes::CLOCK_PROCESS_CPUTIME_ID => cpu_time(false).as_nanos() as u64,
+            ctypes::CLOCK_THREAD_CPUTIME_ID => cpu_time(true).as_nanos() as u64,
+            _ => return Err(LinuxError::EINVAL),
+        };
+        let now = Duration::from_nanos(nanos).into();
         unsafe { *ts = now };
         debug!("sys_clock_gettime: {}.{:09}s", now.tv_sec, now.tv_nsec);
         Ok(0)
     })
 }
 
-/// Get clock time since booting
-pub unsafe fn sys_clock_settime(_clk: ctypes::clockid_t, ts: *const ctypes::timespec) -> c_int {
+/// Get the resolution of the given clock.
+///
+/// All the clocks are read from the same hardware counter, so it is the
+/// length of a hardware tick, not the period of the scheduler tick.
+pub unsafe fn sys_clock_getres(clk: ctypes::clockid_t, res: *mut ctypes::timespec) -> c_int {
+    debug!("sys_clock_getres <= {}", clk);
+    syscall_body!(sys_clock_getres, {
+        match clk as u32 {
+            ctypes::CLOCK_REALTIME
+            | ctypes::CLOCK_REALTIME_COARSE
+            | ctypes::CLOCK_MONOTONIC
+            | ctypes::CLOCK_MONOTONIC_RAW
+            | ctypes::CLOCK_MONOTONIC_COARSE
+            | ctypes::CLOCK_BOOTTIME
+            | ctypes::CLOCK_PROCESS_CPUTIME_ID
+            | ctypes::CLOCK_THREAD_CPUTIME_ID => {}
+            _ => return Err(LinuxError::EINVAL),
+        }
+        if !res.is_null() {
+            let nanos = ruxhal::time::clock_resolution_nanos();
+            unsafe { *res = Duration::from_nanos(nanos).into() };
+        }
+        Ok(0)
+    })
+}
+
+/// Set the time of `CLOCK_REALTIME` by adjusting the wall-clock time at boot.
+///
+/// The other clocks can not be set, `CLOCK_MONOTONIC` in particular never jumps.
+pub unsafe fn sys_clock_settime(clk: ctypes::clockid_t, ts: *const ctypes::timespec) -> c_int {
     syscall_body!(sys_clock_setttime, {
         if ts.is_null() {
             return Err(LinuxError::EFAULT);
         }
+        if clk as u32 != ctypes::CLOCK_REALTIME {
+            return Err(LinuxError::EINVAL);
+        }
+        if (*ts).tv_sec < 0 || (*ts).tv_nsec < 0 || (*ts).tv_nsec >= NANOS_PER_SEC as c_long {
+            return Err(LinuxError::EINVAL);
+        }
         let new_tv = Duration::from(*ts);
         debug!(
             "sys_clock_setttime: {}.{:09}s",
             new_tv.as_secs(),
-            new_tv.as_nanos()
+            new_tv.subsec_nanos()
         );
-        ruxhal::time::set_current_time(new_tv);
+        if !ruxhal::time::set_wall_time(new_tv) {
+            return Err(LinuxError::EINVAL);
+        }
         Ok(0)
     })
 }
@@ -110,10 +176,32 @@ pub unsafe fn sys_nanosleep(req: *const ctypes::timespec, rem: *mut ctypes::time
     })
 }
 
-/// Get time of the day, ignore second parameter
-pub unsafe fn sys_gettimeofday(ts: *mut ctypes::timespec, flags: c_int) -> c_int {
-    debug!("sys_gettimeofday <= flags: {}", flags);
-    unsafe { sys_clock_gettime(0, ts) }
+/// Get the wall-clock time, the time zone `tz` is ignored.
+pub unsafe fn sys_gettimeofday(tv: *mut ctypes::timeval, tz: *mut c_void) -> c_int {
+    debug!("sys_gettimeofday <= {:#x}", tz as usize);
+    syscall_body!(sys_gettimeofday, {
+        if !tv.is_null() {
+            unsafe { *tv = ruxhal::time::wall_time().into() };
+        }
+        Ok(0)
+    })
+}
+
+/// Set the wall-clock time, the time zone `tz` is ignored.
+pub unsafe fn sys_settimeofday(tv: *const ctypes::timeval, tz: *const c_void) -> c_int {
+    debug!("sys_settimeofday <= {:#x}", tz as usize);
+    syscall_body!(sys_settimeofday, {
+        if tv.is_null() {
+            return Ok(0);
+        }
+        if (*tv).tv_sec < 0 || (*tv).tv_usec < 0 || (*tv).tv_usec >= MICROS_PER_SEC as c_long {
+            return Err(LinuxError::EINVAL);
+        }
+        if !ruxhal::time::set_wall_time((*tv).into()) {
+            return Err(LinuxError::EINVAL);
+        }
+        Ok(0)
+    })
 }
 
 /// TODO: get process and waited-for child process times
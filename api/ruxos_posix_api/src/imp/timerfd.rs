@@ -0,0 +1,306 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use alloc::sync::Arc;
+use core::ffi::{c_int, c_long, c_uint};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::time::Duration;
+
+use axerrno::{LinuxError, LinuxResult};
+use axio::PollState;
+use axsync::Mutex;
+use ruxfdtable::{FileLike, RuxStat};
+use ruxhal::time::NANOS_PER_SEC;
+#[cfg(feature = "multitask")]
+use ruxtask::WaitQueue;
+
+use super::fd_ops::{add_file_like, get_file_like};
+use crate::{ctypes, sys_fcntl};
+
+struct TimerState {
+    /// The next expiration, in the time base of the timer's clock.
+    deadline: Option<Duration>,
+    interval: Duration,
+    /// Expirations not yet consumed by `read`.
+    expirations: u64,
+}
+
+impl TimerState {
+    /// Accounts for every expiration up to `now` and rearms periodic timers.
+    fn update(&mut self, now: Duration) {
+        let Some(deadline) = self.deadline else {
+            return;
+        };
+        if now < deadline {
+            return;
+        }
+        if self.interval.is_zero() {
+            self.expirations += 1;
+            self.deadline = None;
+        } else {
+            let interval = self.interval.as_nanos();
+            let n = (now - deadline).as_nanos() / interval + 1;
+            self.expirations += n as u64;
+            self.deadline = Some(deadline + Duration::from_nanos((interval * n) as u64));
+        }
+    }
+}
+
+pub struct TimerFd {
+    clock: c_uint,
+    state: Mutex<TimerState>,
+    nonblocking: AtomicBool,
+    /// Bumped whenever the timer is rearmed, to wake up blocked readers.
+    generation: AtomicUsize,
+    #[cfg(feature = "multitask")]
+    wq: WaitQueue,
+}
+
+impl TimerFd {
+    pub fn new(clock: c_uint, nonblocking: bool) -> Self {
+        Self {
+            clock,
+            state: Mutex::new(TimerState {
+                deadline: None,
+                interval: Duration::ZERO,
+                expirations: 0,
+            }),
+            nonblocking: AtomicBool::new(nonblocking),
+            generation: AtomicUsize::new(0),
+            #[cfg(feature = "multitask")]
+            wq: WaitQueue::new(),
+        }
+    }
+
+    /// Current time of the timer's clock.
+    fn now(&self) -> Duration {
+        match self.clock {
+            ctypes::CLOCK_REALTIME => ruxhal::time::wall_time(),
+            _ => ruxhal::time::current_time(),
+        }
+    }
+
+    /// Arms the timer with `new`, or disarms it if `new.it_value` is zero, and
+    /// returns the previous setting.
+    fn set_time(&self, new: &ctypes::itimerspec, abstime: bool) -> ctypes::itimerspec {
+        let now = self.now();
+        let mut state = self.state.lock();
+        let old = Self::get_time_locked(&mut state, now);
+        let value = Duration::from(new.it_value);
+        state.interval = Duration::from(new.it_interval);
+        state.expirations = 0;
+        state.deadline = match (value.is_zero(), abstime) {
+            (true, _) => None,
+            (false, true) => Some(value),
+            (false, false) => Some(now + value),
+        };
+        drop(state);
+        self.generation.fetch_add(1, Ordering::Release);
+        #[cfg(feature = "multitask")]
+        self.wq.notify_all(true);
+        old
+    }
+
+    /// Blocks until `timeout` elapses or the timer is rearmed after `generation`.
+    ///
+    /// The timeout is backed by the alarm of the task timer list, a disarmed
+    /// timer waits without a timeout.
+    fn wait(&self, generation: usize, timeout: Option<Duration>) {
+        #[cfg(feature = "multitask")]
+        {
+            let rearmed = || self.generation.load(Ordering::Acquire) != generation;
+            match timeout {
+                #[cfg(feature = "irq")]
+                Some(dur) => {
+                    self.wq.wait_timeout_until(dur, rearmed);
+                }
+                #[cfg(not(feature = "irq"))]
+                Some(dur) => ruxtask::sleep(dur),
+                None => self.wq.wait_until(rearmed),
+            }
+        }
+        #[cfg(not(feature = "multitask"))]
+        {
+            let _ = generation;
+            match timeout {
+                Some(dur) => ruxhal::time::busy_wait(dur),
+                None => {
+                    crate::sys_sched_yield();
+                }
+            }
+        }
+    }
+
+    fn get_time(&self) -> ctypes::itimerspec {
+        let now = self.now();
+        Self::get_time_locked(&mut self.state.lock(), now)
+    }
+
+    fn get_time_locked(state: &mut TimerState, now: Duration) -> ctypes::itimerspec {
+        state.update(now);
+        let remaining = state
+            .deadline
+            .map_or(Duration::ZERO, |d| d.saturating_sub(now));
+        ctypes::itimerspec {
+            it_interval: state.interval.into(),
+            it_value: remaining.into(),
+        }
+    }
+}
+
+impl FileLike for TimerFd {
+    fn read(&self, buf: &mut [u8]) -> LinuxResult<usize> {
+        if buf.len() < core::mem::size_of::<u64>() {
+            return Err(LinuxError::EINVAL);
+        }
+        loop {
+            let now = self.now();
+            let mut state = self.state.lock();
+            state.update(now);
+            if state.expirations > 0 {
+                let count = core::mem::take(&mut state.expirations);
+                buf[..8].copy_from_slice(&count.to_ne_bytes());
+                return Ok(8);
+            }
+            if self.nonblocking.load(Ordering::Acquire) {
+                return Err(LinuxError::EAGAIN);
+            }
+            let generation = self.generation.load(Ordering::Acquire);
+            let timeout = state.deadline.map(|d| d - now);
+            drop(state);
+            self.wait(generation, timeout);
+        }
+    }
+
+    fn write(&self, _buf: &[u8]) -> LinuxResult<usize> {
+        Err(LinuxError::EINVAL)
+    }
+
+    fn flush(&self) -> LinuxResult {
+        Ok(())
+    }
+
+    fn stat(&self) -> LinuxResult<RuxStat> {
+        let st_mode = 0o600u32; // anonymous inode, rw-------
+        Ok(RuxStat::from(ctypes::stat {
+            st_ino: 1,
+            st_nlink: 1,
+            st_mode,
+            st_uid: 1000,
+            st_gid: 1000,
+            st_blksize: 4096,
+            ..Default::default()
+        }))
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn core::any::Any + Send + Sync> {
+        self
+    }
+
+    fn poll(&self) -> LinuxResult<PollState> {
+        let now = self.now();
+        let mut state = self.state.lock();
+        state.update(now);
+        Ok(PollState {
+            readable: state.expirations > 0,
+            writable: false,
+            ..Default::default()
+        })
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> LinuxResult {
+        self.nonblocking.store(nonblocking, Ordering::Release);
+        Ok(())
+    }
+}
+
+fn timerfd_from_fd(fd: c_int) -> LinuxResult<Arc<TimerFd>> {
+    get_file_like(fd)?
+        .into_any()
+        .downcast::<TimerFd>()
+        .map_err(|_| LinuxError::EINVAL)
+}
+
+fn check_timespec(ts: &ctypes::timespec) -> LinuxResult {
+    if ts.tv_sec < 0 || ts.tv_nsec < 0 || ts.tv_nsec >= NANOS_PER_SEC as c_long {
+        return Err(LinuxError::EINVAL);
+    }
+    Ok(())
+}
+
+/// Create a timer that delivers expirations via a file descriptor
+///
+/// Only `CLOCK_REALTIME` and `CLOCK_MONOTONIC` are supported.
+pub fn sys_timerfd_create(clockid: c_int, flags: c_int) -> c_int {
+    debug!(
+        "sys_timerfd_create <= clockid: {}, flags: {:#x}",
+        clockid, flags
+    );
+    syscall_body!(sys_timerfd_create, {
+        let clockid = clockid as c_uint;
+        if clockid != ctypes::CLOCK_REALTIME && clockid != ctypes::CLOCK_MONOTONIC {
+            return Err(LinuxError::EINVAL);
+        }
+        let flags = flags as c_uint;
+        if flags & !(ctypes::TFD_NONBLOCK | ctypes::TFD_CLOEXEC) != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+
+        let tfd = TimerFd::new(clockid, flags & ctypes::TFD_NONBLOCK != 0);
+        let fd = add_file_like(Arc::new(tfd))? as c_int;
+        if flags & ctypes::TFD_CLOEXEC != 0 {
+            sys_fcntl(fd, ctypes::F_SETFD as _, ctypes::FD_CLOEXEC as _);
+        }
+        Ok(fd)
+    })
+}
+
+/// Arm or disarm the timer referred to by `fd`
+///
+/// `new_value.it_value` is relative to now, or absolute if `TFD_TIMER_ABSTIME` is set.
+/// A zero `it_value` disarms the timer, a nonzero `it_interval` makes it periodic.
+pub unsafe fn sys_timerfd_settime(
+    fd: c_int,
+    flags: c_int,
+    new_value: *const ctypes::itimerspec,
+    old_value: *mut ctypes::itimerspec,
+) -> c_int {
+    debug!("sys_timerfd_settime <= fd: {}, flags: {:#x}", fd, flags);
+    syscall_body!(sys_timerfd_settime, {
+        if new_value.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        if flags as c_uint & !ctypes::TFD_TIMER_ABSTIME != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        let new_value = unsafe { &*new_value };
+        check_timespec(&new_value.it_value)?;
+        check_timespec(&new_value.it_interval)?;
+
+        let tfd = timerfd_from_fd(fd)?;
+        let old = tfd.set_time(new_value, flags as c_uint & ctypes::TFD_TIMER_ABSTIME != 0);
+        if !old_value.is_null() {
+            unsafe { *old_value = old };
+        }
+        Ok(0)
+    })
+}
+
+/// Get the time until the next expiration and the interval of the timer referred to by `fd`
+pub unsafe fn sys_timerfd_gettime(fd: c_int, curr_value: *mut ctypes::itimerspec) -> c_int {
+    debug!("sys_timerfd_gettime <= fd: {}", fd);
+    syscall_body!(sys_timerfd_gettime, {
+        if curr_value.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        let curr = timerfd_from_fd(fd)?.get_time();
+        unsafe { *curr_value = curr };
+        Ok(0)
+    })
+}
@@ -0,0 +1,621 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Unix domain stream sockets (`AF_UNIX`), whose connections carry the bytes
+//! through buffers in memory.
+//!
+//! A socket bound to a path creates a socket node there, and the listening
+//! sockets are registered under the absolute path of their node, where
+//! [`UnixSocket::connect`] finds them. A socket may be bound to a name of the
+//! abstract namespace instead, which has no node, and the listening sockets
+//! are registered under that name in a separate table.
+//!
+//! Files are passed along with the bytes they are sent with, as `SCM_RIGHTS`
+//! does, see [`UnixSocket::send_with_rights`].
+
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::string::String;
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+use core::ffi::c_int;
+use core::mem::size_of;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use axerrno::{LinuxError, LinuxResult};
+use axio::PollState;
+use axsync::Mutex;
+use ruxfdtable::FileLike;
+
+use crate::ctypes;
+
+/// Number of bytes each direction of a connection buffers.
+pub const UNIX_BUF_SIZE: usize = 64 * 1024;
+
+/// The listening sockets, by the absolute path they are bound to.
+static LISTENERS: Mutex<BTreeMap<String, Weak<Listener>>> = Mutex::new(BTreeMap::new());
+
+/// The listening sockets, by the abstract name they are bound to.
+static ABSTRACT_LISTENERS: Mutex<BTreeMap<Vec<u8>, Weak<Listener>>> = Mutex::new(BTreeMap::new());
+
+/// The address of a Unix domain socket.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnixAddr {
+    /// A path of the filesystem.
+    Path(String),
+    /// A name of the abstract namespace, without the leading null byte.
+    Abstract(Vec<u8>),
+}
+
+impl UnixAddr {
+    /// Returns the listening socket registered under this address, which is
+    /// an absolute path or an abstract name.
+    fn listener(&self) -> Option<Weak<Listener>> {
+        match self {
+            Self::Path(path) => LISTENERS.lock().get(path).cloned(),
+            Self::Abstract(name) => ABSTRACT_LISTENERS.lock().get(name).cloned(),
+        }
+    }
+
+    /// Whether a socket listens under this address.
+    fn is_listened(&self) -> bool {
+        self.listener()
+            .is_some_and(|listener| listener.strong_count() > 0)
+    }
+
+    /// Registers `listener` under this address.
+    ///
+    /// Fails with `EADDRINUSE` if another socket listens under it.
+    fn register(&self, listener: Weak<Listener>) -> LinuxResult {
+        fn insert<K: Ord>(
+            listeners: &mut BTreeMap<K, Weak<Listener>>,
+            key: K,
+            listener: Weak<Listener>,
+        ) -> LinuxResult {
+            if listeners
+                .get(&key)
+                .is_some_and(|listener| listener.strong_count() > 0)
+            {
+                return Err(LinuxError::EADDRINUSE);
+            }
+            listeners.insert(key, listener);
+            Ok(())
+        }
+        match self {
+            Self::Path(path) => insert(&mut LISTENERS.lock(), path.clone(), listener),
+            Self::Abstract(name) => insert(&mut ABSTRACT_LISTENERS.lock(), name.clone(), listener),
+        }
+    }
+
+    /// Removes the listening socket registered under this address if it is
+    /// closed, another socket may be listening under it since.
+    fn unregister_closed(&self) {
+        fn remove<K: Ord>(listeners: &mut BTreeMap<K, Weak<Listener>>, key: &K) {
+            if listeners
+                .get(key)
+                .is_some_and(|listener| listener.strong_count() == 0)
+            {
+                listeners.remove(key);
+            }
+        }
+        match self {
+            Self::Path(path) => remove(&mut LISTENERS.lock(), path),
+            Self::Abstract(name) => remove(&mut ABSTRACT_LISTENERS.lock(), name),
+        }
+    }
+}
+
+/// Files passed over a connection.
+struct Rights {
+    /// Offset in the buffer of the first byte sent with the files.
+    offset: usize,
+    files: Vec<Arc<dyn FileLike>>,
+}
+
+/// The bytes sent in one direction of a connection.
+struct Channel {
+    buf: Mutex<VecDeque<u8>>,
+    /// The files passed, by increasing offset. Locked after `buf`.
+    rights: Mutex<VecDeque<Rights>>,
+    /// No more bytes are sent, the reader gets the end of the stream.
+    write_closed: AtomicBool,
+    /// No more bytes are received, the writer gets `EPIPE`.
+    read_closed: AtomicBool,
+}
+
+impl Channel {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            buf: Mutex::new(VecDeque::new()),
+            rights: Mutex::new(VecDeque::new()),
+            write_closed: AtomicBool::new(false),
+            read_closed: AtomicBool::new(false),
+        })
+    }
+}
+
+/// One end of a connection.
+struct Endpoint {
+    rx: Arc<Channel>,
+    tx: Arc<Channel>,
+    /// The address the other end is bound to.
+    peer_addr: Option<UnixAddr>,
+}
+
+impl Endpoint {
+    /// Returns the two ends of a new connection, whose other ends are bound
+    /// to `peer_addrs`.
+    fn pair(peer_addrs: (Option<UnixAddr>, Option<UnixAddr>)) -> (Self, Self) {
+        let (a_to_b, b_to_a) = (Channel::new(), Channel::new());
+        let a = Self {
+            rx: b_to_a.clone(),
+            tx: a_to_b.clone(),
+            peer_addr: peer_addrs.0,
+        };
+        let b = Self {
+            rx: a_to_b,
+            tx: b_to_a,
+            peer_addr: peer_addrs.1,
+        };
+        (a, b)
+    }
+}
+
+impl Drop for Endpoint {
+    fn drop(&mut self) {
+        self.rx.read_closed.store(true, Ordering::Release);
+        self.tx.write_closed.store(true, Ordering::Release);
+    }
+}
+
+/// The connections waiting to be accepted by a listening socket.
+struct Listener {
+    bound: BoundAddr,
+    backlog: Mutex<usize>,
+    pending: Mutex<VecDeque<Endpoint>>,
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        self.bound.key.unregister_closed();
+    }
+}
+
+enum State {
+    Idle,
+    Listening(Arc<Listener>),
+    Connected(Endpoint),
+}
+
+/// The address a socket is bound to, as given and as the listening sockets
+/// are registered under.
+#[derive(Clone)]
+struct BoundAddr {
+    addr: UnixAddr,
+    key: UnixAddr,
+}
+
+pub struct UnixSocket {
+    state: Mutex<State>,
+    bound: Mutex<Option<BoundAddr>>,
+    nonblocking: AtomicBool,
+}
+
+/// Returns the address the socket at `addr` is registered under, the
+/// absolute path for a path.
+fn addr_key(addr: &UnixAddr) -> LinuxResult<UnixAddr> {
+    match addr {
+        #[cfg(feature = "fs")]
+        UnixAddr::Path(path) => Ok(UnixAddr::Path(ruxfs::api::canonicalize(path)?)),
+        _ => Ok(addr.clone()),
+    }
+}
+
+impl UnixSocket {
+    pub fn new() -> Self {
+        Self::with_state(State::Idle, None)
+    }
+
+    fn with_state(state: State, bound: Option<BoundAddr>) -> Self {
+        Self {
+            state: Mutex::new(state),
+            bound: Mutex::new(bound),
+            nonblocking: AtomicBool::new(false),
+        }
+    }
+
+    /// Creates a pair of connected sockets, like `socketpair`.
+    pub fn pair() -> (Self, Self) {
+        let (a, b) = Endpoint::pair((None, None));
+        (
+            Self::with_state(State::Connected(a), None),
+            Self::with_state(State::Connected(b), None),
+        )
+    }
+
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking.load(Ordering::Acquire)
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) {
+        self.nonblocking.store(nonblocking, Ordering::Release);
+    }
+
+    /// Returns the channels the socket receives from and sends to.
+    fn channels(&self) -> LinuxResult<(Arc<Channel>, Arc<Channel>)> {
+        match &*self.state.lock() {
+            State::Connected(endpoint) => Ok((endpoint.rx.clone(), endpoint.tx.clone())),
+            _ => Err(LinuxError::ENOTCONN),
+        }
+    }
+
+    /// Returns the address the socket is bound to, if any.
+    pub fn local_addr(&self) -> Option<UnixAddr> {
+        self.bound.lock().as_ref().map(|bound| bound.addr.clone())
+    }
+
+    /// Returns the address the peer of the socket is bound to, if any.
+    pub fn peer_addr(&self) -> LinuxResult<Option<UnixAddr>> {
+        match &*self.state.lock() {
+            State::Connected(endpoint) => Ok(endpoint.peer_addr.clone()),
+            _ => Err(LinuxError::ENOTCONN),
+        }
+    }
+
+    /// Binds the socket to `addr`. A socket node is created at a path.
+    ///
+    /// Fails with `EADDRINUSE` if the path already exists, or if a socket
+    /// listens under the abstract name.
+    pub fn bind(&self, addr: UnixAddr) -> LinuxResult {
+        let mut bound = self.bound.lock();
+        if bound.is_some() {
+            return Err(LinuxError::EINVAL);
+        }
+        match &addr {
+            #[cfg(feature = "fs")]
+            UnixAddr::Path(path) => {
+                let perm = ruxfs::fops::FilePerm::from_bits_truncate(0o777);
+                ruxfs::fops::create_socket(path, perm).map_err(|e| match e {
+                    axerrno::AxError::AlreadyExists => LinuxError::EADDRINUSE,
+                    e => e.into(),
+                })?
+            }
+            UnixAddr::Abstract(_) if addr.is_listened() => return Err(LinuxError::EADDRINUSE),
+            _ => {}
+        }
+        *bound = Some(BoundAddr {
+            key: addr_key(&addr)?,
+            addr,
+        });
+        Ok(())
+    }
+
+    /// Starts accepting the connections to the address the socket is bound to,
+    /// at most `backlog` waiting to be accepted.
+    pub fn listen(&self, backlog: usize) -> LinuxResult {
+        let mut state = self.state.lock();
+        match &*state {
+            State::Idle => {}
+            State::Listening(listener) => {
+                *listener.backlog.lock() = backlog;
+                return Ok(());
+            }
+            State::Connected(_) => return Err(LinuxError::EINVAL),
+        }
+        let bound = self.bound.lock();
+        let bound = bound.as_ref().ok_or(LinuxError::EINVAL)?;
+        let listener = Arc::new(Listener {
+            bound: bound.clone(),
+            backlog: Mutex::new(backlog),
+            pending: Mutex::new(VecDeque::new()),
+        });
+        bound.key.register(Arc::downgrade(&listener))?;
+        *state = State::Listening(listener);
+        Ok(())
+    }
+
+    /// Accepts a connection, waiting for one unless the socket is
+    /// nonblocking.
+    pub fn accept(&self) -> LinuxResult<UnixSocket> {
+        let listener = match &*self.state.lock() {
+            State::Listening(listener) => listener.clone(),
+            _ => return Err(LinuxError::EINVAL),
+        };
+        loop {
+            if let Some(endpoint) = listener.pending.lock().pop_front() {
+                let bound = listener.bound.clone();
+                return Ok(Self::with_state(State::Connected(endpoint), Some(bound)));
+            }
+            if self.is_nonblocking() {
+                return Err(LinuxError::EAGAIN);
+            }
+            crate::sys_sched_yield(); // TODO: use synconize primitive
+        }
+    }
+
+    /// Connects to the socket listening at `addr`.
+    ///
+    /// Fails with `ENOENT` if there is no such path, and with `ECONNREFUSED`
+    /// if no socket listens there.
+    pub fn connect(&self, addr: &UnixAddr) -> LinuxResult {
+        match &*self.state.lock() {
+            State::Idle => {}
+            State::Listening(_) => return Err(LinuxError::EINVAL),
+            State::Connected(_) => return Err(LinuxError::EISCONN),
+        }
+        #[cfg(feature = "fs")]
+        if let UnixAddr::Path(path) = addr {
+            let node = ruxfs::fops::lookup(path)?;
+            if node.get_attr()?.file_type() != ruxfs::fops::FileType::Socket {
+                return Err(LinuxError::ECONNREFUSED);
+            }
+        }
+        let listener = addr_key(addr)?.listener();
+        let listener = listener.ok_or(LinuxError::ECONNREFUSED)?;
+        loop {
+            // The listening socket may be closed while waiting.
+            let listener = listener.upgrade().ok_or(LinuxError::ECONNREFUSED)?;
+            let mut pending = listener.pending.lock();
+            if pending.len() <= *listener.backlog.lock() {
+                let (local, remote) =
+                    Endpoint::pair((Some(listener.bound.addr.clone()), self.local_addr()));
+                pending.push_back(remote);
+                *self.state.lock() = State::Connected(local);
+                return Ok(());
+            }
+            drop(pending);
+            drop(listener);
+            if self.is_nonblocking() {
+                return Err(LinuxError::EAGAIN);
+            }
+            crate::sys_sched_yield(); // TODO: use synconize primitive
+        }
+    }
+
+    /// Receives bytes from the peer, waiting for some unless the socket is
+    /// nonblocking or `MSG_DONTWAIT` is set. `MSG_PEEK` leaves them to be
+    /// received again.
+    ///
+    /// Returns 0 at the end of the stream.
+    pub fn recv(&self, buf: &mut [u8], flags: c_int) -> LinuxResult<usize> {
+        Ok(self.recv_with_rights(buf, flags)?.0)
+    }
+
+    /// Receives bytes from the peer like [`recv`](Self::recv), and the files
+    /// passed with the first of them, if any.
+    ///
+    /// The bytes sent with files are never received along with the bytes
+    /// sent before them, so that the files come with their own bytes. With
+    /// `MSG_PEEK`, the files are left to be received with the bytes.
+    pub fn recv_with_rights(
+        &self,
+        buf: &mut [u8],
+        flags: c_int,
+    ) -> LinuxResult<(usize, Vec<Arc<dyn FileLike>>)> {
+        let (rx, _) = self.channels()?;
+        let nonblocking = self.is_nonblocking() || flags & ctypes::MSG_DONTWAIT as c_int != 0;
+        loop {
+            let mut data = rx.buf.lock();
+            if !data.is_empty() || buf.is_empty() {
+                let mut rights = rx.rights.lock();
+                // Stop before the bytes sent with the next files.
+                let end = rights
+                    .iter()
+                    .map(|rights| rights.offset)
+                    .find(|&offset| offset > 0)
+                    .unwrap_or(data.len());
+                let len = end.min(buf.len());
+                for (dst, src) in buf[..len].iter_mut().zip(data.iter()) {
+                    *dst = *src;
+                }
+                let mut files = Vec::new();
+                if flags & ctypes::MSG_PEEK as c_int == 0 {
+                    data.drain(..len);
+                    if len > 0 && rights.front().is_some_and(|rights| rights.offset == 0) {
+                        files = rights.pop_front().unwrap().files;
+                    }
+                    for rights in rights.iter_mut() {
+                        rights.offset -= len;
+                    }
+                }
+                return Ok((len, files));
+            }
+            if rx.write_closed.load(Ordering::Acquire) || rx.read_closed.load(Ordering::Acquire) {
+                return Ok(0);
+            }
+            if nonblocking {
+                return Err(LinuxError::EAGAIN);
+            }
+            drop(data);
+            crate::sys_sched_yield(); // TODO: use synconize primitive
+        }
+    }
+
+    /// Sends the bytes of `buf` to the peer, waiting for room in its buffer
+    /// unless the socket is nonblocking or `MSG_DONTWAIT` is set.
+    ///
+    /// Fails with `EPIPE` if the peer does not receive anymore.
+    pub fn send(&self, buf: &[u8], flags: c_int) -> LinuxResult<usize> {
+        self.send_with_rights(buf, flags, Vec::new())
+    }
+
+    /// Sends the bytes of `buf` to the peer like [`send`](Self::send), and
+    /// passes `files` along with them, as `SCM_RIGHTS` does.
+    ///
+    /// The files are received with the first byte of `buf`, they are dropped
+    /// if `buf` is empty or none of its bytes are sent.
+    pub fn send_with_rights(
+        &self,
+        buf: &[u8],
+        flags: c_int,
+        files: Vec<Arc<dyn FileLike>>,
+    ) -> LinuxResult<usize> {
+        let (_, tx) = self.channels()?;
+        let mut files = Some(files).filter(|files| !files.is_empty());
+        let nonblocking = self.is_nonblocking() || flags & ctypes::MSG_DONTWAIT as c_int != 0;
+        let mut sent = 0;
+        loop {
+            if tx.write_closed.load(Ordering::Acquire) || tx.read_closed.load(Ordering::Acquire) {
+                return if sent > 0 {
+                    Ok(sent)
+                } else {
+                    Err(LinuxError::EPIPE)
+                };
+            }
+            let mut data = tx.buf.lock();
+            let len = (UNIX_BUF_SIZE - data.len()).min(buf.len() - sent);
+            if len > 0 {
+                if let Some(files) = files.take() {
+                    let offset = data.len();
+                    tx.rights.lock().push_back(Rights { offset, files });
+                }
+            }
+            data.extend(&buf[sent..sent + len]);
+            sent += len;
+            if sent == buf.len() {
+                return Ok(sent);
+            }
+            if nonblocking {
+                return if sent > 0 {
+                    Ok(sent)
+                } else {
+                    Err(LinuxError::EAGAIN)
+                };
+            }
+            drop(data);
+            crate::sys_sched_yield(); // TODO: use synconize primitive
+        }
+    }
+
+    /// Shuts down the receiving and/or sending direction of the connection,
+    /// as told by `how`, one of the `SHUT_*` values.
+    pub fn shutdown(&self, how: c_int) -> LinuxResult {
+        let (rx, tx) = self.channels()?;
+        let (read, write) = match how as u32 {
+            ctypes::SHUT_RD => (true, false),
+            ctypes::SHUT_WR => (false, true),
+            ctypes::SHUT_RDWR => (true, true),
+            _ => return Err(LinuxError::EINVAL),
+        };
+        if read {
+            rx.read_closed.store(true, Ordering::Release);
+        }
+        if write {
+            tx.write_closed.store(true, Ordering::Release);
+        }
+        Ok(())
+    }
+
+    pub fn poll(&self) -> PollState {
+        match &*self.state.lock() {
+            State::Idle => PollState {
+                readable: false,
+                writable: false,
+                ..Default::default()
+            },
+            State::Listening(listener) => PollState {
+                readable: !listener.pending.lock().is_empty(),
+                writable: false,
+                ..Default::default()
+            },
+            State::Connected(endpoint) => {
+                let (rx, tx) = (&endpoint.rx, &endpoint.tx);
+                let rx_closed = rx.write_closed.load(Ordering::Acquire)
+                    || rx.read_closed.load(Ordering::Acquire);
+                let tx_closed = tx.write_closed.load(Ordering::Acquire)
+                    || tx.read_closed.load(Ordering::Acquire);
+                PollState {
+                    readable: !rx.buf.lock().is_empty() || rx_closed,
+                    // Sending fails at once once the connection is closed.
+                    writable: tx.buf.lock().len() < UNIX_BUF_SIZE || tx_closed,
+                    // Both directions are shut down, by either end.
+                    hangup: rx_closed && tx_closed,
+                    error: false,
+                }
+            }
+        }
+    }
+}
+
+/// Returns the address in the `sockaddr_un` at `addr`, of `addrlen` bytes.
+///
+/// An abstract name starts with a null byte, and takes the rest of the
+/// address, null bytes included.
+pub(crate) unsafe fn addr_from_sockaddr(
+    addr: *const ctypes::sockaddr,
+    addrlen: ctypes::socklen_t,
+) -> LinuxResult<UnixAddr> {
+    if addr.is_null() {
+        return Err(LinuxError::EFAULT);
+    }
+    let path_offset = size_of::<ctypes::sa_family_t>();
+    let addrlen = addrlen as usize;
+    if addrlen <= path_offset || addrlen > size_of::<ctypes::sockaddr_un>() {
+        return Err(LinuxError::EINVAL);
+    }
+    let addr = unsafe { &*(addr as *const ctypes::sockaddr_un) };
+    if addr.sun_family != ctypes::AF_UNIX as ctypes::sa_family_t {
+        return Err(LinuxError::EINVAL);
+    }
+    let path = unsafe {
+        core::slice::from_raw_parts(addr.sun_path.as_ptr() as *const u8, addrlen - path_offset)
+    };
+    if let Some((0, name)) = path.split_first() {
+        debug!("    load abstract sockaddr_un: {:?}", name);
+        return Ok(UnixAddr::Abstract(name.into()));
+    }
+    let path = path.split(|&c| c == 0).next().unwrap_or_default();
+    if path.is_empty() {
+        return Err(LinuxError::EINVAL);
+    }
+    let path = core::str::from_utf8(path).map_err(|_| LinuxError::EINVAL)?;
+    debug!("    load sockaddr_un: {:?}", path);
+    Ok(UnixAddr::Path(path.into()))
+}
+
+/// Stores the `sockaddr_un` of `addr` in the buffer of `*addrlen` bytes at
+/// `dst`, truncated if the buffer is too small, and sets `*addrlen` to the
+/// full size of the address. An unbound socket has only the family.
+pub(crate) unsafe fn write_sockaddr_un(
+    addr: Option<&UnixAddr>,
+    dst: *mut ctypes::sockaddr,
+    addrlen: *mut ctypes::socklen_t,
+) -> LinuxResult {
+    debug!("    Sockaddr: {:?}", addr);
+    let buf_len = unsafe { *addrlen };
+    if (buf_len as i32) < 0 {
+        return Err(LinuxError::EINVAL);
+    }
+    let mut sockaddr = ctypes::sockaddr_un {
+        sun_family: ctypes::AF_UNIX as _,
+        sun_path: [0; 108],
+    };
+    let (path, terminator) = match addr {
+        None => (&[][..], 0),
+        Some(UnixAddr::Path(path)) => (path.as_bytes(), 1),
+        // The leading null byte is left in place, and there is no terminating one.
+        Some(UnixAddr::Abstract(name)) => (name.as_slice(), 0),
+    };
+    let path_start = usize::from(matches!(addr, Some(UnixAddr::Abstract(_))));
+    for (dst, &src) in sockaddr.sun_path[path_start..].iter_mut().zip(path) {
+        *dst = src as _;
+    }
+    let mut len = size_of::<ctypes::sa_family_t>();
+    if addr.is_some() {
+        len = size_of::<ctypes::sockaddr_un>().min(len + path_start + path.len() + terminator);
+    }
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            &sockaddr as *const _ as *const u8,
+            dst as *mut u8,
+            len.min(buf_len as usize),
+        );
+        *addrlen = len as _;
+    }
+    Ok(())
+}
@@ -25,6 +25,9 @@ extern crate ruxruntime;
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(all(feature = "smoltcp", feature = "lwip"))]
+compile_error!("features `smoltcp` and `lwip` cannot be enabled at the same time");
+
 #[cfg(feature = "alloc")]
 pub use ruxruntime::{environ, environ_iter, RUX_ENVIRON};
 
@@ -49,7 +52,7 @@ pub use imp::cap::sys_cap_get;
 pub use imp::getrandom::{sys_getrandom, sys_rand, sys_random, sys_srand};
 pub use imp::io::{sys_read, sys_readv, sys_write, sys_writev};
 pub use imp::prctl::{sys_arch_prctl, sys_prctl};
-pub use imp::resources::{sys_getrlimit, sys_prlimit64, sys_setrlimit};
+pub use imp::resources::{sys_getrlimit, sys_getrusage, sys_prlimit64, sys_setrlimit};
 pub use imp::rt_sig::{sys_rt_sigaction, sys_rt_sigprocmask};
 pub use imp::stat::{
     sys_getegid, sys_geteuid, sys_getgid, sys_getpgid, sys_getuid, sys_setgid, sys_setpgid,
@@ -57,21 +60,36 @@ pub use imp::stat::{
 };
 pub use imp::sys::{sys_sysinfo, sys_uname};
 pub use imp::sys_invalid;
-pub use imp::task::{sys_exit, sys_getpid, sys_getppid, sys_gettid, sys_sched_yield};
+pub use imp::task::{
+    exit_if_group_exiting, sys_exit, sys_exit_group, sys_getpid, sys_getppid, sys_gettid,
+    sys_sched_yield, sys_wait4,
+};
+#[cfg(feature = "multitask")]
+pub use imp::task::{
+    sys_sched_get_priority_max, sys_sched_get_priority_min, sys_sched_getaffinity,
+    sys_sched_getparam, sys_sched_getscheduler, sys_sched_setaffinity, sys_sched_setscheduler,
+};
 pub use imp::time::{
-    sys_clock_gettime, sys_clock_settime, sys_gettimeofday, sys_nanosleep, sys_times,
+    sys_clock_getres, sys_clock_gettime, sys_clock_settime, sys_gettimeofday, sys_nanosleep,
+    sys_settimeofday, sys_times,
 };
 
+#[cfg(feature = "fd")]
+pub use imp::eventfd::{sys_eventfd, sys_eventfd2};
 #[cfg(all(feature = "fd", feature = "musl"))]
 pub use imp::fd_ops::sys_dup3;
 #[cfg(feature = "fd")]
-pub use imp::fd_ops::{sys_close, sys_dup, sys_dup2, sys_fcntl};
+pub use imp::fd_ops::{
+    close_cloexec_fds, sys_close, sys_dup, sys_dup2, sys_fallocate, sys_fcntl, sys_ftruncate,
+};
 #[cfg(feature = "fs")]
 pub use imp::fs::{
-    sys_chdir, sys_faccessat, sys_fchownat, sys_fdatasync, sys_fstat, sys_fsync, sys_getcwd,
-    sys_getdents64, sys_lseek, sys_lstat, sys_mkdir, sys_mkdirat, sys_newfstatat, sys_open,
-    sys_openat, sys_pread64, sys_preadv, sys_pwrite64, sys_readlinkat, sys_rename, sys_renameat,
-    sys_rmdir, sys_stat, sys_unlink, sys_unlinkat,
+    sys_chdir, sys_chmod, sys_chown, sys_faccessat, sys_fchdir, sys_fchmod, sys_fchmodat,
+    sys_fchown, sys_fchownat, sys_fdatasync, sys_fstat, sys_fstatat, sys_fsync, sys_getcwd,
+    sys_getdents64, sys_lchown, sys_link, sys_linkat, sys_lseek, sys_lstat, sys_mkdir, sys_mkdirat,
+    sys_mount, sys_newfstatat, sys_open, sys_openat, sys_openat2, sys_pread64, sys_preadv,
+    sys_pwrite64, sys_readlinkat, sys_rename, sys_renameat, sys_rmdir, sys_stat, sys_statfs,
+    sys_symlinkat, sys_truncate, sys_umount2, sys_unlink, sys_unlinkat, sys_unshare,
 };
 #[cfg(feature = "epoll")]
 pub use imp::io_mpx::{sys_epoll_create, sys_epoll_ctl, sys_epoll_pwait, sys_epoll_wait};
@@ -85,13 +103,18 @@ pub use imp::ioctl::sys_ioctl;
 pub use imp::mmap::{sys_madvise, sys_mmap, sys_mprotect, sys_mremap, sys_msync, sys_munmap};
 #[cfg(feature = "net")]
 pub use imp::net::{
-    sys_accept, sys_bind, sys_connect, sys_freeaddrinfo, sys_getaddrinfo, sys_getpeername,
-    sys_getsockname, sys_listen, sys_recv, sys_recvfrom, sys_send, sys_sendmsg, sys_sendto,
-    sys_setsockopt, sys_shutdown, sys_socket,
+    sys_accept, sys_accept4, sys_bind, sys_connect, sys_freeaddrinfo, sys_getaddrinfo,
+    sys_getpeername, sys_getsockname, sys_getsockopt, sys_listen, sys_recv, sys_recvfrom,
+    sys_recvmsg, sys_send, sys_sendmsg, sys_sendto, sys_setsockopt, sys_shutdown, sys_sockatmark,
+    sys_socket, sys_socketpair,
 };
 #[cfg(feature = "pipe")]
 pub use imp::pipe::{sys_pipe, sys_pipe2};
 #[cfg(feature = "multitask")]
+pub use imp::pthread::barrier::{
+    sys_pthread_barrier_destroy, sys_pthread_barrier_init, sys_pthread_barrier_wait,
+};
+#[cfg(feature = "multitask")]
 pub use imp::pthread::condvar::{
     sys_pthread_cond_broadcast, sys_pthread_cond_destroy, sys_pthread_cond_init,
     sys_pthread_cond_signal, sys_pthread_cond_timedwait, sys_pthread_cond_wait,
@@ -102,12 +125,22 @@ pub use imp::pthread::mutex::{
     sys_pthread_mutex_trylock, sys_pthread_mutex_unlock,
 };
 #[cfg(feature = "multitask")]
+pub use imp::pthread::rwlock::{
+    sys_pthread_rwlock_destroy, sys_pthread_rwlock_init, sys_pthread_rwlock_rdlock,
+    sys_pthread_rwlock_tryrdlock, sys_pthread_rwlock_trywrlock, sys_pthread_rwlock_unlock,
+    sys_pthread_rwlock_wrlock,
+};
+#[cfg(feature = "multitask")]
 pub use imp::pthread::{
     sys_pthread_getspecific, sys_pthread_key_create, sys_pthread_key_delete,
     sys_pthread_setspecific,
 };
 #[cfg(feature = "signal")]
 pub use imp::signal::{sys_getitimer, sys_kill, sys_setitimer, sys_sigaction, sys_sigaltstack};
+#[cfg(all(feature = "signal", feature = "fd"))]
+pub use imp::signalfd::{sys_signalfd, sys_signalfd4};
+#[cfg(feature = "fd")]
+pub use imp::timerfd::{sys_timerfd_create, sys_timerfd_gettime, sys_timerfd_settime};
 
 #[cfg(feature = "multitask")]
 pub use imp::pthread::futex::sys_futex;
@@ -116,7 +149,10 @@ pub use imp::pthread::sys_clone;
 #[cfg(all(feature = "multitask", feature = "musl"))]
 pub use imp::pthread::sys_set_tid_address;
 #[cfg(feature = "multitask")]
-pub use imp::pthread::{sys_pthread_create, sys_pthread_exit, sys_pthread_join, sys_pthread_self};
+pub use imp::pthread::{
+    sys_pthread_create, sys_pthread_exit, sys_pthread_getname_np, sys_pthread_join,
+    sys_pthread_self, sys_pthread_setname_np,
+};
 
 #[cfg(feature = "fs")]
 pub use imp::execve::sys_execve;
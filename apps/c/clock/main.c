@@ -0,0 +1,133 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS
+ * OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A
+ * PARTICULAR PURPOSE. See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <stdio.h>
+#include <sys/time.h>
+#include <time.h>
+
+// `wall-clock-fallback-epoch` in the default config, used without an RTC
+#define FALLBACK_EPOCH 1704067200
+
+static long long ts_nanos(const struct timespec *ts)
+{
+    return ts->tv_sec * 1000000000LL + ts->tv_nsec;
+}
+
+void test_getres()
+{
+    struct timespec res;
+
+    assert(clock_getres(CLOCK_MONOTONIC, &res) == 0);
+    // one tick of the hardware counter, much finer than a scheduler tick
+    assert(res.tv_sec == 0 && res.tv_nsec > 0 && res.tv_nsec <= 1000);
+    long nsec = res.tv_nsec;
+    assert(clock_getres(CLOCK_REALTIME, &res) == 0);
+    assert(res.tv_sec == 0 && res.tv_nsec == nsec);
+    assert(clock_getres(CLOCK_MONOTONIC, NULL) == 0);
+    assert(clock_getres(-1, &res) == -1 && errno == EINVAL);
+    puts("clock_getres test OK!");
+}
+
+void test_monotonic()
+{
+    struct timespec prev, now;
+
+    assert(clock_gettime(CLOCK_MONOTONIC, &prev) == 0);
+    for (int i = 0; i < 100000; i++) {
+        assert(clock_gettime(CLOCK_MONOTONIC, &now) == 0);
+        assert(now.tv_nsec >= 0 && now.tv_nsec < 1000000000);
+        assert(ts_nanos(&now) >= ts_nanos(&prev));
+        prev = now;
+    }
+
+    // CLOCK_MONOTONIC_RAW and CLOCK_BOOTTIME are aliases of CLOCK_MONOTONIC.
+    struct timespec raw, boot;
+    assert(clock_gettime(CLOCK_MONOTONIC_RAW, &raw) == 0);
+    assert(clock_gettime(CLOCK_BOOTTIME, &boot) == 0);
+    assert(clock_gettime(CLOCK_MONOTONIC, &now) == 0);
+    assert(ts_nanos(&prev) <= ts_nanos(&raw) && ts_nanos(&raw) <= ts_nanos(&boot));
+    assert(ts_nanos(&boot) <= ts_nanos(&now));
+    puts("CLOCK_MONOTONIC non-decreasing test OK!");
+}
+
+void test_gettimeofday()
+{
+    struct timeval tv;
+    struct timespec one_sec = {1, 0};
+
+    // at least one second after the boot time
+    nanosleep(&one_sec, NULL);
+    assert(gettimeofday(&tv, NULL) == 0);
+    assert(tv.tv_sec > FALLBACK_EPOCH);
+    assert(tv.tv_usec >= 0 && tv.tv_usec < 1000000);
+
+    // repeated calls never go backwards while the clock is not set
+    struct timeval prev = tv;
+    for (int i = 0; i < 10000; i++) {
+        assert(gettimeofday(&tv, NULL) == 0);
+        assert(tv.tv_usec >= 0 && tv.tv_usec < 1000000);
+        assert(tv.tv_sec > prev.tv_sec ||
+               (tv.tv_sec == prev.tv_sec && tv.tv_usec >= prev.tv_usec));
+        prev = tv;
+    }
+
+    // the time zone is accepted and ignored
+    struct timezone tz;
+    assert(gettimeofday(&tv, &tz) == 0);
+    puts("gettimeofday test OK!");
+}
+
+void test_settimeofday()
+{
+    struct timeval tv = {1735689600, 500000}, now;
+
+    assert(settimeofday(&tv, NULL) == 0);
+    assert(gettimeofday(&now, NULL) == 0);
+    assert(now.tv_sec >= tv.tv_sec && now.tv_sec < tv.tv_sec + 2);
+
+    tv.tv_usec = 1000000;
+    assert(settimeofday(&tv, NULL) == -1 && errno == EINVAL);
+    puts("settimeofday test OK!");
+}
+
+void test_settime()
+{
+    struct timespec mono_before, mono_after, real, new_time = {1731110400, 0};
+
+    // the monotonic clock can not be set
+    assert(clock_settime(CLOCK_MONOTONIC, &new_time) == -1 && errno == EINVAL);
+
+    assert(clock_gettime(CLOCK_MONOTONIC, &mono_before) == 0);
+    assert(clock_settime(CLOCK_REALTIME, &new_time) == 0);
+    assert(clock_gettime(CLOCK_REALTIME, &real) == 0);
+    assert(clock_gettime(CLOCK_MONOTONIC, &mono_after) == 0);
+
+    // the realtime clock jumped, the monotonic one did not
+    assert(real.tv_sec >= new_time.tv_sec && real.tv_sec < new_time.tv_sec + 2);
+    assert(ts_nanos(&mono_after) >= ts_nanos(&mono_before));
+    assert(mono_after.tv_sec < new_time.tv_sec);
+
+    new_time.tv_nsec = 1000000000;
+    assert(clock_settime(CLOCK_REALTIME, &new_time) == -1 && errno == EINVAL);
+    puts("clock_settime test OK!");
+}
+
+int main()
+{
+    test_getres();
+    test_monotonic();
+    test_gettimeofday();
+    test_settimeofday();
+    test_settime();
+    puts("(C)Clock tests run OK!");
+    return 0;
+}
@@ -0,0 +1 @@
+app-objs := ephemeral.o
@@ -0,0 +1,82 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+#include <arpa/inet.h>
+#include <netinet/in.h>
+#include <sys/socket.h>
+
+#define NUM_SOCKETS 32
+#define PORT_START  0x15b3
+
+// Binds `NUM_SOCKETS` sockets of the given type to port 0, and checks that
+// they get distinct ephemeral ports.
+static int test_bind_any(int type, const char *name)
+{
+    int socks[NUM_SOCKETS];
+    unsigned short ports[NUM_SOCKETS];
+    struct sockaddr_in local;
+    memset(&local, 0, sizeof(local));
+    local.sin_family = AF_INET;
+    local.sin_addr.s_addr = htonl(INADDR_ANY);
+    local.sin_port = 0;
+
+    for (int i = 0; i < NUM_SOCKETS; i++) {
+        socks[i] = socket(AF_INET, type, 0);
+        if (socks[i] == -1) {
+            perror("socket() error");
+            return -1;
+        }
+        if (bind(socks[i], (struct sockaddr *)&local, sizeof(local)) != 0) {
+            perror("bind() error");
+            return -1;
+        }
+        // A TCP socket only reports its address once listening.
+        if (type == SOCK_STREAM && listen(socks[i], 1) != 0) {
+            perror("listen() error");
+            return -1;
+        }
+        struct sockaddr_in addr;
+        socklen_t len = sizeof(addr);
+        if (getsockname(socks[i], (struct sockaddr *)&addr, &len) != 0) {
+            perror("getsockname() error");
+            return -1;
+        }
+        ports[i] = ntohs(addr.sin_port);
+        if (ports[i] < PORT_START) {
+            printf("%s port %u is not ephemeral\n", name, ports[i]);
+            return -1;
+        }
+        for (int j = 0; j < i; j++) {
+            if (ports[j] == ports[i]) {
+                printf("%s port %u is allocated twice\n", name, ports[i]);
+                return -1;
+            }
+        }
+    }
+    for (int i = 0; i < NUM_SOCKETS; i++) {
+        close(socks[i]);
+    }
+    printf("%s: %d distinct ephemeral ports\n", name, NUM_SOCKETS);
+    return 0;
+}
+
+int main()
+{
+    puts("Hello, Ruxos C ephemeral port test!");
+    if (test_bind_any(SOCK_DGRAM, "UDP") != 0)
+        return -1;
+    if (test_bind_any(SOCK_STREAM, "TCP") != 0)
+        return -1;
+    puts("Ephemeral port test OK!");
+    return 0;
+}
@@ -1,10 +1,26 @@
 #include <errno.h>
 #include <fcntl.h>
+#include <limits.h>
 #include <stdio.h>
 #include <string.h>
 #include <sys/stat.h>
+#include <sys/statfs.h>
 #include <unistd.h>
 
+/* Reads at most `size - 1` bytes of the file at `path` into the string `buf`. */
+static ssize_t read_file(const char *path, char *buf, size_t size)
+{
+    buf[0] = '\0';
+    int fd = open(path, O_RDONLY);
+    if (fd == -1)
+        return -1;
+    ssize_t n = read(fd, buf, size - 1);
+    close(fd);
+    if (n >= 0)
+        buf[n] = '\0';
+    return n;
+}
+
 int main()
 {
     int ret;
@@ -42,6 +58,352 @@ int main()
     puts("second fgets success!");
 
     fclose(fp);
+
+    fd = open("filetest/a.txt", O_RDWR);
+    struct stat st;
+    if (ftruncate(fd, 32) == -1 || fstat(fd, &st) == -1 || st.st_size != 32) {
+        perror("ftruncate extend failed");
+        return -1;
+    }
+    memset(s, 0xff, sizeof(s));
+    lseek(fd, 16, SEEK_SET);
+    if (read(fd, s, 50) != 16 || s[0] != 0 || s[15] != 0) {
+        perror("extended part is not zero filled");
+        return -1;
+    }
+    if (ftruncate(fd, 4) == -1 || fstat(fd, &st) == -1 || st.st_size != 4) {
+        perror("ftruncate shrink failed");
+        return -1;
+    }
+    lseek(fd, 0, SEEK_SET);
+    if (read(fd, s, 50) != 4 || strncmp("1 2 ", s, 4)) {
+        perror("read after ftruncate failed");
+        return -1;
+    }
+    if (ftruncate(fd, -1) != -1 || errno != EINVAL) {
+        perror("ftruncate with negative length should fail");
+        return -1;
+    }
+    if (ftruncate(STDIN_FILENO, 0) != -1 || errno != EINVAL) {
+        perror("ftruncate on stdin should fail");
+        return -1;
+    }
+    puts("ftruncate success!");
+
+    if (fallocate(fd, 0, 2, 10) == -1 || fstat(fd, &st) == -1 || st.st_size != 12) {
+        perror("fallocate failed");
+        return -1;
+    }
+    if (fallocate(fd, FALLOC_FL_KEEP_SIZE, 0, 100) == -1 || fstat(fd, &st) == -1 ||
+        st.st_size != 12) {
+        perror("fallocate with FALLOC_FL_KEEP_SIZE should not extend the file");
+        return -1;
+    }
+    if (fallocate(fd, 0, 0, 0) != -1 || errno != EINVAL || posix_fallocate(fd, -1, 1) != EINVAL) {
+        perror("fallocate with an empty or negative range should fail");
+        return -1;
+    }
+    if (posix_fallocate(fd, 0, 4) != 0 || fstat(fd, &st) == -1 || st.st_size != 12) {
+        perror("posix_fallocate should not shrink the file");
+        return -1;
+    }
+    close(fd);
+    puts("fallocate success!");
+
+    // /tmp is a ramfs, which supports symbolic links.
+    fd = open("/tmp/target.txt", O_RDWR | O_CREAT);
+    if (fd == -1 || write(fd, "symlink", 7) != 7) {
+        perror("can not create the symlink target");
+        return -1;
+    }
+    if (symlink("target.txt", "/tmp/link") == -1) {
+        perror("symlink failed");
+        return -1;
+    }
+    if (fstatat(AT_FDCWD, "/tmp/link", &st, AT_SYMLINK_NOFOLLOW) == -1 || !S_ISLNK(st.st_mode) ||
+        st.st_size != strlen("target.txt")) {
+        perror("fstatat with AT_SYMLINK_NOFOLLOW should stat the link");
+        return -1;
+    }
+    if (fstatat(AT_FDCWD, "/tmp/link", &st, 0) == -1 || !S_ISREG(st.st_mode) || st.st_size != 7) {
+        perror("fstatat should stat the link target");
+        return -1;
+    }
+    if (fstatat(fd, "", &st, AT_EMPTY_PATH) == -1 || !S_ISREG(st.st_mode) || st.st_size != 7) {
+        perror("fstatat with AT_EMPTY_PATH should stat the fd");
+        return -1;
+    }
+    if (fstatat(fd, "", &st, 0) != -1 || errno != ENOENT) {
+        perror("fstatat with an empty path should fail");
+        return -1;
+    }
+    if (fstatat(AT_FDCWD, "/tmp/link", &st, 0x4000) != -1 || errno != EINVAL) {
+        perror("fstatat with an unknown flag should fail");
+        return -1;
+    }
+    if (readlink("/tmp/link", s, sizeof(s)) != strlen("target.txt") ||
+        strncmp("target.txt", s, strlen("target.txt"))) {
+        perror("readlink failed");
+        return -1;
+    }
+    close(fd);
+    if (unlink("/tmp/link") == -1 || unlink("/tmp/target.txt") == -1) {
+        perror("remove symlink error");
+        return -1;
+    }
+    puts("symlink and fstatat success!");
+
+    // the hard links share the file, which is freed with the last name
+    fd = open("/tmp/orig.txt", O_RDWR | O_CREAT, 0644);
+    if (fd == -1 || write(fd, "hardlink", 8) != 8 || link("/tmp/orig.txt", "/tmp/hard.txt") == -1) {
+        perror("link failed");
+        return -1;
+    }
+    if (fstat(fd, &st) == -1 || st.st_nlink != 2 || stat("/tmp/hard.txt", &st) == -1 ||
+        st.st_nlink != 2 || st.st_size != 8) {
+        perror("stat should count the hard links");
+        return -1;
+    }
+    if (link("/tmp/orig.txt", "/tmp/hard.txt") != -1 || errno != EEXIST) {
+        perror("link to an existing path should fail");
+        return -1;
+    }
+    if (unlink("/tmp/orig.txt") == -1 || fstat(fd, &st) == -1 || st.st_nlink != 1) {
+        perror("unlink should drop a hard link");
+        return -1;
+    }
+    close(fd);
+    if (unlink("/tmp/hard.txt") == -1) {
+        perror("remove hard link error");
+        return -1;
+    }
+    puts("link success!");
+
+    fd = open("/tmp/mode.txt", O_RDWR | O_CREAT, 0644);
+    if (fd == -1 || chmod("/tmp/mode.txt", 0600) == -1) {
+        perror("chmod failed");
+        return -1;
+    }
+    if (fstat(fd, &st) == -1 || (st.st_mode & 0777) != 0600 || !S_ISREG(st.st_mode)) {
+        perror("fstat after chmod failed");
+        return -1;
+    }
+    if (fchmod(fd, 0644) == -1 || stat("/tmp/mode.txt", &st) == -1 || (st.st_mode & 0777) != 0644) {
+        perror("fchmod failed");
+        return -1;
+    }
+    if (chmod("/dev/null", 0600) != -1 || errno != EROFS) {
+        perror("chmod on devfs should fail");
+        return -1;
+    }
+    gid_t gid = st.st_gid;
+    if (chown("/tmp/mode.txt", 42, -1) == -1 || stat("/tmp/mode.txt", &st) == -1 ||
+        st.st_uid != 42 || st.st_gid != gid) {
+        perror("chown failed");
+        return -1;
+    }
+    if (fchown(fd, -1, 43) == -1 || fstat(fd, &st) == -1 || st.st_uid != 42 || st.st_gid != 43) {
+        perror("fchown failed");
+        return -1;
+    }
+    close(fd);
+    unlink("/tmp/mode.txt");
+    puts("chmod and chown success!");
+
+    char long_path[PATH_MAX + 8] = "/tmp/";
+    memset(long_path + 5, 'a', PATH_MAX);
+    long_path[PATH_MAX + 5] = '\0';
+    if (stat(long_path, &st) != -1 || errno != ENAMETOOLONG) {
+        perror("stat on a too long path should fail");
+        return -1;
+    }
+    if (symlink("/tmp/loop", "/tmp/loop") == -1 || stat("/tmp/loop", &st) != -1 ||
+        errno != ELOOP || lstat("/tmp/loop", &st) == -1 || !S_ISLNK(st.st_mode)) {
+        perror("stat on a symlink loop should fail");
+        return -1;
+    }
+    unlink("/tmp/loop");
+    puts("path limits success!");
+
+    mode_t old_mask = umask(077);
+    fd = open("/tmp/umask.txt", O_RDWR | O_CREAT, 0666);
+    if (old_mask != 022 || fd == -1 || fstat(fd, &st) == -1 || (st.st_mode & 0777) != 0600) {
+        perror("umask on open failed");
+        return -1;
+    }
+    close(fd);
+    unlink("/tmp/umask.txt");
+    if (mkdir("/tmp/umask", 0777) == -1 || stat("/tmp/umask", &st) == -1 ||
+        (st.st_mode & 0777) != 0700) {
+        perror("umask on mkdir failed");
+        return -1;
+    }
+    rmdir("/tmp/umask");
+    if (umask(old_mask) != 077) {
+        perror("umask restore failed");
+        return -1;
+    }
+    puts("umask success!");
+
+    // /tmp is a ramfs without a size limit by default
+    struct statfs sfs;
+    if (statfs("/tmp", &sfs) == -1 || sfs.f_type != 0x858458f6 || sfs.f_bsize != 4096 ||
+        sfs.f_blocks != 0) {
+        perror("statfs on /tmp failed");
+        return -1;
+    }
+    if (statfs("/tmp/nonexistent", &sfs) != -1 || errno != ENOENT) {
+        perror("statfs on a missing path should fail");
+        return -1;
+    }
+    puts("statfs success!");
+
+    // the fdinfo of an fd reports the bytes written through it
+    char data[100], path[64], info[256];
+    memset(data, 'x', sizeof(data));
+    int wfd = open("/tmp/fdinfo.txt", O_CREAT | O_RDWR | O_TRUNC, 0644);
+    if (wfd == -1 || write(wfd, data, sizeof(data)) != sizeof(data)) {
+        perror("write for fdinfo failed");
+        return -1;
+    }
+    snprintf(path, sizeof(path), "/proc/self/fdinfo/%d", wfd);
+    int ifd = open(path, O_RDONLY);
+    ssize_t n = ifd == -1 ? -1 : read(ifd, info, sizeof(info) - 1);
+    if (n <= 0) {
+        perror("read fdinfo failed");
+        return -1;
+    }
+    info[n] = '\0';
+    if (!strstr(info, "pos:\t100\n") || !strstr(info, "wchar:\t100\n") ||
+        !strstr(info, "syscw:\t1\n")) {
+        printf("unexpected fdinfo: %s", info);
+        return -1;
+    }
+    close(ifd);
+    close(wfd);
+    unlink("/tmp/fdinfo.txt");
+    puts("fdinfo success!");
+
+    // the files of procfs are generated from the live state when read
+    char proc[512], prefix[32];
+    snprintf(prefix, sizeof(prefix), "%d (", getpid());
+    if (read_file("/proc/self/stat", proc, sizeof(proc)) <= 0 ||
+        strncmp(proc, prefix, strlen(prefix))) {
+        printf("unexpected /proc/self/stat: %s\n", proc);
+        return -1;
+    }
+    if (read_file("/proc/meminfo", proc, sizeof(proc)) <= 0 || !strstr(proc, "MemTotal:")) {
+        printf("unexpected /proc/meminfo: %s\n", proc);
+        return -1;
+    }
+    n = read_file("/proc/mounts", proc, sizeof(proc));
+    if (n <= 0 || !strstr(proc, "proc /proc proc rw 0 0\n")) {
+        printf("unexpected /proc/mounts: %s\n", proc);
+        return -1;
+    }
+    if (stat("/proc/mounts", &st) == -1 || st.st_size != n) {
+        puts("unexpected size of /proc/mounts");
+        return -1;
+    }
+    puts("procfs success!");
+
+    // the special files in /dev
+    char dev[16], rnd[16];
+    int dfd = open("/dev/full", O_RDWR);
+    if (dfd == -1 || write(dfd, "x", 1) != -1 || errno != ENOSPC ||
+        read(dfd, dev, sizeof(dev)) != sizeof(dev) || dev[0] || dev[sizeof(dev) - 1]) {
+        perror("/dev/full failed");
+        return -1;
+    }
+    close(dfd);
+    dfd = open("/dev/null", O_RDWR);
+    if (dfd == -1 || write(dfd, "x", 1) != 1 || read(dfd, dev, sizeof(dev)) != 0) {
+        perror("/dev/null failed");
+        return -1;
+    }
+    close(dfd);
+    dfd = open("/dev/urandom", O_RDONLY);
+    if (dfd == -1 || read(dfd, dev, sizeof(dev)) != sizeof(dev) ||
+        read(dfd, rnd, sizeof(rnd)) != sizeof(rnd) || !memcmp(dev, rnd, sizeof(dev))) {
+        perror("/dev/urandom failed");
+        return -1;
+    }
+    close(dfd);
+    puts("devices success!");
+
+    // an O_PATH fd of a directory is only an anchor for the *at calls
+    int dirfd = open("filetest", O_PATH);
+    if (dirfd == -1) {
+        perror("open with O_PATH failed");
+        return -1;
+    }
+    int afd = openat(dirfd, "a.txt", O_RDONLY);
+    if (afd == -1 || read(afd, s, sizeof(s)) <= 0) {
+        perror("openat from an O_PATH fd failed");
+        return -1;
+    }
+    close(afd);
+    if (fstat(dirfd, &st) == -1 || !S_ISDIR(st.st_mode)) {
+        perror("fstat on an O_PATH fd failed");
+        return -1;
+    }
+    if (read(dirfd, s, sizeof(s)) != -1 || errno != EBADF) {
+        perror("read on an O_PATH fd should fail");
+        return -1;
+    }
+    close(dirfd);
+    puts("O_PATH success!");
+
+    // an O_TMPFILE has no name until it is linked
+    int tfd = open("/tmp", O_TMPFILE | O_RDWR, 0600);
+    if (tfd == -1 || write(tfd, "anonymous", 9) != 9) {
+        perror("write to an O_TMPFILE failed");
+        return -1;
+    }
+    if (linkat(tfd, "", AT_FDCWD, "/tmp/linked.txt", AT_EMPTY_PATH) == -1) {
+        perror("linkat of an O_TMPFILE failed");
+        return -1;
+    }
+    close(tfd);
+    memset(s, 0, sizeof(s));
+    fd = open("/tmp/linked.txt", O_RDONLY);
+    if (fd == -1 || read(fd, s, sizeof(s)) != 9 || strcmp(s, "anonymous")) {
+        perror("read of a linked O_TMPFILE failed");
+        return -1;
+    }
+    close(fd);
+    unlink("/tmp/linked.txt");
+    puts("O_TMPFILE success!");
+
+    // stdio is the console, which is a tty unlike regular files
+    fd = open("filetest/a.txt", O_RDONLY);
+    errno = 0;
+    if (!isatty(0) || !isatty(1) || !isatty(2) || fd == -1 || isatty(fd) || errno != ENOTTY) {
+        perror("isatty failed");
+        return -1;
+    }
+    close(fd);
+    puts("isatty success!");
+
+    // the close-on-exec flag belongs to each file descriptor
+    fd = open("filetest/a.txt", O_RDONLY | O_CLOEXEC);
+    int dfd = dup(fd);
+    int cfd = fcntl(fd, F_DUPFD_CLOEXEC, 0);
+    if (fd == -1 || fcntl(fd, F_GETFD) != FD_CLOEXEC || fcntl(dfd, F_GETFD) != 0 ||
+        fcntl(cfd, F_GETFD) != FD_CLOEXEC) {
+        perror("close-on-exec flags of new fds failed");
+        return -1;
+    }
+    if (fcntl(fd, F_SETFD, 0) == -1 || fcntl(fd, F_GETFD) != 0 || dup2(cfd, dfd) != dfd ||
+        fcntl(dfd, F_GETFD) != 0) {
+        perror("close-on-exec flags of changed fds failed");
+        return -1;
+    }
+    close(cfd);
+    close(dfd);
+    close(fd);
+    puts("close-on-exec success!");
+
     ret = remove("filetest/a.txt");
     if (ret == -1) {
         perror("remove file error");
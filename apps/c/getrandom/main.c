@@ -0,0 +1,61 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS
+ * OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A
+ * PARTICULAR PURPOSE. See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/random.h>
+
+void test_fill()
+{
+    unsigned char a[100], b[100];
+
+    memset(a, 0, sizeof(a));
+    memset(b, 0, sizeof(b));
+    assert(getrandom(a, sizeof(a), 0) == sizeof(a));
+    assert(getrandom(b, sizeof(b), 0) == sizeof(b));
+    // two reads, or two halves of one, never repeat
+    assert(memcmp(a, b, sizeof(a)) != 0);
+    assert(memcmp(a, a + 50, 50) != 0);
+
+    // a read which is not a multiple of 8 is filled up to its end, not beyond
+    memset(a, 0, sizeof(a));
+    memset(b, 0, sizeof(b));
+    assert(getrandom(a, 13, 0) == 13);
+    assert(memcmp(a + 8, b, 5) != 0);
+    assert(memcmp(a + 13, b, sizeof(a) - 13) == 0);
+    assert(getrandom(a, 0, 0) == 0);
+    puts("test_fill OK");
+}
+
+void test_flags()
+{
+    unsigned char buf[16];
+
+    // the CSPRNG is seeded at boot, so nothing blocks
+    assert(getrandom(buf, sizeof(buf), GRND_NONBLOCK) == sizeof(buf));
+    assert(getrandom(buf, sizeof(buf), GRND_RANDOM) == sizeof(buf));
+    assert(getrandom(buf, sizeof(buf), GRND_RANDOM | GRND_NONBLOCK) == sizeof(buf));
+    assert(getrandom(buf, sizeof(buf), GRND_INSECURE) == sizeof(buf));
+
+    assert(getrandom(buf, sizeof(buf), GRND_RANDOM | GRND_INSECURE) == -1 && errno == EINVAL);
+    assert(getrandom(buf, sizeof(buf), 0x100) == -1 && errno == EINVAL);
+    assert(getrandom(NULL, sizeof(buf), 0) == -1 && errno == EFAULT);
+    puts("test_flags OK");
+}
+
+int main()
+{
+    test_fill();
+    test_flags();
+    puts("(C)getrandom tests run OK!");
+    return 0;
+}
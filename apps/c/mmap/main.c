@@ -0,0 +1,123 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS
+ * OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A
+ * PARTICULAR PURPOSE. See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/mman.h>
+
+#define PAGE_SIZE 4096
+
+void test_mprotect()
+{
+    size_t len = 2 * PAGE_SIZE;
+    char *buf = mmap(NULL, len, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    assert(buf != MAP_FAILED);
+
+    for (size_t i = 0; i < len; i++) buf[i] = (char)i;
+
+    // e.g. a JIT buffer becoming executable after code is written into it
+    assert(mprotect(buf, len, PROT_READ | PROT_EXEC) == 0);
+    for (size_t i = 0; i < len; i++) assert(buf[i] == (char)i);
+
+    // only the second page becomes writable again
+    assert(mprotect(buf + PAGE_SIZE, PAGE_SIZE, PROT_READ | PROT_WRITE) == 0);
+    buf[PAGE_SIZE] = 0x5a;
+    assert(buf[PAGE_SIZE] == 0x5a);
+    assert(buf[0] == 0);
+
+    // misaligned address
+    assert(mprotect(buf + 1, PAGE_SIZE, PROT_READ) == -1 && errno == EINVAL);
+
+    assert(munmap(buf, len) == 0);
+
+    // the range is no longer mapped
+    assert(mprotect(buf, len, PROT_READ) == -1 && errno == ENOMEM);
+
+    puts("test_mprotect: OK");
+}
+
+void test_madvise()
+{
+    size_t len = 2 * PAGE_SIZE;
+    char *buf = mmap(NULL, len, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    assert(buf != MAP_FAILED);
+
+    memset(buf, 0xa5, len);
+    assert(madvise(buf, len, MADV_WILLNEED) == 0);
+    assert(buf[0] == (char)0xa5);
+
+    // the first page is dropped and reads back as zero, the second one is kept
+    assert(madvise(buf, PAGE_SIZE, MADV_DONTNEED) == 0);
+    for (size_t i = 0; i < PAGE_SIZE; i++) assert(buf[i] == 0);
+    assert(buf[PAGE_SIZE] == (char)0xa5);
+
+    // the dropped page is still mapped and writable
+    buf[1] = 0x5a;
+    assert(buf[1] == 0x5a);
+
+    assert(madvise(buf + 1, PAGE_SIZE, MADV_DONTNEED) == -1 && errno == EINVAL);
+    assert(madvise(buf, len, -1) == -1 && errno == EINVAL);
+
+    assert(munmap(buf, len) == 0);
+    assert(madvise(buf, len, MADV_DONTNEED) == -1 && errno == ENOMEM);
+
+    puts("test_madvise: OK");
+}
+
+void test_mremap()
+{
+    size_t len = 2 * PAGE_SIZE;
+    char *buf = mmap(NULL, len, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    assert(buf != MAP_FAILED);
+    for (size_t i = 0; i < len; i++) buf[i] = (char)i;
+
+    // block the pages right after the mapping, so that it can't grow in place
+    char *blocker = mmap(buf + len, PAGE_SIZE, PROT_READ | PROT_WRITE,
+                         MAP_PRIVATE | MAP_ANONYMOUS | MAP_FIXED, -1, 0);
+    assert(blocker == buf + len);
+    assert(mremap(buf, len, 2 * len, 0) == MAP_FAILED && errno == ENOMEM);
+
+    // grow by moving, the prior contents are preserved and the new pages are zero
+    char *moved = mremap(buf, len, 2 * len, MREMAP_MAYMOVE);
+    assert(moved != MAP_FAILED && moved != buf);
+    for (size_t i = 0; i < len; i++) assert(moved[i] == (char)i);
+    for (size_t i = len; i < 2 * len; i++) assert(moved[i] == 0);
+    moved[2 * len - 1] = 0x5a;
+    assert(mprotect(buf, len, PROT_READ) == -1 && errno == ENOMEM);
+
+    // shrink in place unmaps the tail
+    assert(mremap(moved, 2 * len, PAGE_SIZE, 0) == moved);
+    assert(moved[1] == 1);
+    assert(mprotect(moved + PAGE_SIZE, PAGE_SIZE, PROT_READ) == -1 && errno == ENOMEM);
+
+    // grow in place into the freed tail
+    assert(mremap(moved, PAGE_SIZE, len, 0) == moved);
+    assert(moved[1] == 1 && moved[PAGE_SIZE] == 0);
+
+    assert(mremap(moved + 1, PAGE_SIZE, len, MREMAP_MAYMOVE) == MAP_FAILED && errno == EINVAL);
+    assert(mremap(buf, len, 2 * len, MREMAP_MAYMOVE) == MAP_FAILED && errno == EFAULT);
+
+    assert(munmap(moved, len) == 0);
+    assert(munmap(blocker, PAGE_SIZE) == 0);
+
+    puts("test_mremap: OK");
+}
+
+int main()
+{
+    puts("Running mmap tests...");
+    test_mprotect();
+    test_madvise();
+    test_mremap();
+    puts("Mmap tests run OK!");
+    return 0;
+}
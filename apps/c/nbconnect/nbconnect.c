@@ -0,0 +1,179 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <errno.h>
+#include <netdb.h>
+#include <stdio.h>
+#include <string.h>
+#include <time.h>
+#include <unistd.h>
+#include <arpa/inet.h>
+#include <netinet/in.h>
+#include <sys/epoll.h>
+#include <sys/socket.h>
+
+// A closed port on the host side of the QEMU user network.
+#define REFUSING_IP   "10.0.2.2"
+#define REFUSING_PORT 1
+// No host answers for this address, so the handshake never completes.
+#define SILENT_IP "10.0.2.99"
+
+static int wait_writable(int sock)
+{
+    int epfd = epoll_create(1);
+    struct epoll_event ev = {.events = EPOLLOUT, .data.fd = sock};
+    if (epfd == -1 || epoll_ctl(epfd, EPOLL_CTL_ADD, sock, &ev) != 0) {
+        perror("epoll_ctl() error");
+        return -1;
+    }
+    struct epoll_event ready;
+    int n = epoll_wait(epfd, &ready, 1, 10000);
+    close(epfd);
+    if (n != 1 || ready.data.fd != sock || !(ready.events & EPOLLOUT)) {
+        puts("epoll_wait() should report the socket writable");
+        return -1;
+    }
+    return 0;
+}
+
+static int so_error(int sock)
+{
+    int err = -1;
+    socklen_t len = sizeof(err);
+    if (getsockopt(sock, SOL_SOCKET, SO_ERROR, &err, &len) != 0) {
+        perror("getsockopt() error");
+        return -1;
+    }
+    return err;
+}
+
+static void set_addr(struct sockaddr_in *addr, const char *ip, unsigned short port)
+{
+    memset(addr, 0, sizeof(*addr));
+    addr->sin_family = AF_INET;
+    inet_pton(AF_INET, ip, &addr->sin_addr);
+    addr->sin_port = htons(port);
+}
+
+static int test_connected()
+{
+    int sock = socket(AF_INET, SOCK_STREAM | SOCK_NONBLOCK, 0);
+    if (sock == -1) {
+        perror("socket() error");
+        return -1;
+    }
+    struct addrinfo *res;
+    if (getaddrinfo("ident.me", NULL, NULL, &res) != 0) {
+        perror("getaddrinfo() error");
+        return -1;
+    }
+    ((struct sockaddr_in *)(res->ai_addr))->sin_port = htons(80);
+
+    // The connection is not established at once.
+    if (connect(sock, res->ai_addr, sizeof(*(res->ai_addr))) != -1 || errno != EINPROGRESS) {
+        puts("connect() should fail with EINPROGRESS");
+        return -1;
+    }
+    puts("connect in progress");
+
+    // It becomes writable when the attempt completes, then SO_ERROR holds
+    // the result.
+    if (wait_writable(sock) != 0)
+        return -1;
+    int err = so_error(sock);
+    if (err != 0) {
+        printf("connect() failed: %s\n", strerror(err));
+        return -1;
+    }
+    struct sockaddr_in peer;
+    socklen_t peer_len = sizeof(peer);
+    if (getpeername(sock, (struct sockaddr *)&peer, &peer_len) != 0 || peer.sin_port != htons(80)) {
+        perror("getpeername() error");
+        return -1;
+    }
+    puts("connected");
+
+    freeaddrinfo(res);
+    close(sock);
+    return 0;
+}
+
+static int test_refused()
+{
+    int sock = socket(AF_INET, SOCK_STREAM | SOCK_NONBLOCK, 0);
+    struct sockaddr_in addr;
+    set_addr(&addr, REFUSING_IP, REFUSING_PORT);
+    if (connect(sock, (struct sockaddr *)&addr, sizeof(addr)) != -1 || errno != EINPROGRESS) {
+        puts("connect() should fail with EINPROGRESS");
+        return -1;
+    }
+
+    // A refused attempt makes the socket writable too, and getting SO_ERROR
+    // clears it.
+    if (wait_writable(sock) != 0)
+        return -1;
+    if (so_error(sock) != ECONNREFUSED || so_error(sock) != 0) {
+        puts("SO_ERROR should be ECONNREFUSED once");
+        return -1;
+    }
+    close(sock);
+    puts("refused");
+    return 0;
+}
+
+static long long elapsed_ms(const struct timespec *start)
+{
+    struct timespec now;
+    clock_gettime(CLOCK_MONOTONIC, &now);
+    return (now.tv_sec - start->tv_sec) * 1000LL + (now.tv_nsec - start->tv_nsec) / 1000000;
+}
+
+static int test_timeout()
+{
+    int sock = socket(AF_INET, SOCK_STREAM, 0);
+    struct timeval tv = {.tv_sec = 0, .tv_usec = 300000};
+    if (setsockopt(sock, SOL_SOCKET, SO_SNDTIMEO, &tv, sizeof(tv)) != 0) {
+        perror("setsockopt() error");
+        return -1;
+    }
+    struct timeval got;
+    socklen_t len = sizeof(got);
+    if (getsockopt(sock, SOL_SOCKET, SO_SNDTIMEO, &got, &len) != 0 || len != sizeof(got) ||
+        got.tv_sec != 0 || got.tv_usec != 300000) {
+        puts("SO_SNDTIMEO should be 300ms");
+        return -1;
+    }
+
+    // A blocking connect gives up at the deadline.
+    struct sockaddr_in addr;
+    set_addr(&addr, SILENT_IP, 80);
+    struct timespec start;
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    if (connect(sock, (struct sockaddr *)&addr, sizeof(addr)) != -1 || errno != ETIMEDOUT) {
+        puts("connect() should fail with ETIMEDOUT");
+        return -1;
+    }
+    long long ms = elapsed_ms(&start);
+    if (ms < 300 || ms > 5000) {
+        printf("connect() timed out after %lld ms\n", ms);
+        return -1;
+    }
+    close(sock);
+    puts("timed out");
+    return 0;
+}
+
+int main()
+{
+    puts("Hello, Ruxos C nonblocking connect test!");
+    if (test_connected() != 0 || test_refused() != 0 || test_timeout() != 0)
+        return -1;
+    puts("Nonblocking connect test OK!");
+    return 0;
+}
@@ -0,0 +1 @@
+app-objs := ping.o
@@ -0,0 +1,169 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <errno.h>
+#include <fcntl.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+#include <time.h>
+#include <unistd.h>
+#include <arpa/inet.h>
+#include <netinet/in.h>
+#include <sys/socket.h>
+
+// The gateway of QEMU user networking answers pings.
+#define PEER_IP "10.0.2.2"
+
+#define IP_HDR_LEN  20
+#define ICMP_HDR_LEN 8
+#define PAYLOAD_LEN 32
+#define TIMEOUT_MS  3000
+
+#define CHECK(cond, msg)                           \
+    do {                                           \
+        if (!(cond)) {                             \
+            printf("%s failed: %s\n", msg, #cond); \
+            return -1;                             \
+        }                                          \
+    } while (0)
+
+static long now_ms()
+{
+    struct timespec ts;
+    clock_gettime(CLOCK_MONOTONIC, &ts);
+    return ts.tv_sec * 1000 + ts.tv_nsec / 1000000;
+}
+
+// Builds an echo request, leaving the checksum to the stack.
+static void build_echo(uint8_t *buf, uint16_t id, uint16_t seq)
+{
+    buf[0] = 8; // echo request
+    buf[1] = 0;
+    buf[2] = buf[3] = 0;
+    buf[4] = id >> 8;
+    buf[5] = id & 0xff;
+    buf[6] = seq >> 8;
+    buf[7] = seq & 0xff;
+    for (int i = 0; i < PAYLOAD_LEN; i++)
+        buf[ICMP_HDR_LEN + i] = i;
+}
+
+// Waits for the echo reply to `id` and `seq`, skipping other packets.
+static int wait_reply(int fd, uint16_t id, uint16_t seq)
+{
+    uint8_t buf[256];
+    struct sockaddr_in from;
+    long deadline = now_ms() + TIMEOUT_MS;
+    while (now_ms() < deadline) {
+        socklen_t len = sizeof(from);
+        ssize_t n = recvfrom(fd, buf, sizeof(buf), 0, (struct sockaddr *)&from, &len);
+        if (n < 0) {
+            if (errno != EAGAIN)
+                return -1;
+            usleep(10000);
+            continue;
+        }
+        // The IPv4 header is included.
+        int ihl = (buf[0] & 0xf) * 4;
+        if ((buf[0] >> 4) != 4 || buf[9] != IPPROTO_ICMP || n < ihl + ICMP_HDR_LEN)
+            return -1;
+        const uint8_t *icmp = buf + ihl;
+        if (icmp[0] != 0 || icmp[4] != id >> 8 || icmp[5] != (id & 0xff) ||
+            icmp[6] != seq >> 8 || icmp[7] != (seq & 0xff))
+            continue;
+        if (from.sin_family != AF_INET || from.sin_addr.s_addr != inet_addr(PEER_IP))
+            return -1;
+        return n - ihl - ICMP_HDR_LEN;
+    }
+    return -1;
+}
+
+static int test_echo(int fd, struct sockaddr_in *peer)
+{
+    uint8_t buf[ICMP_HDR_LEN + PAYLOAD_LEN];
+    build_echo(buf, 0x1234, 1);
+    CHECK(sendto(fd, buf, sizeof(buf), 0, (struct sockaddr *)peer, sizeof(*peer)) ==
+              sizeof(buf),
+          "sendto");
+    CHECK(wait_reply(fd, 0x1234, 1) == PAYLOAD_LEN, "echo reply");
+    puts("echo OK");
+    return 0;
+}
+
+static int test_hdrincl(int fd, struct sockaddr_in *peer)
+{
+    int on = 1;
+    CHECK(setsockopt(fd, IPPROTO_IP, IP_HDRINCL, &on, sizeof(on)) == 0, "setsockopt");
+
+    // The source address, total length and checksums are left to the stack.
+    uint8_t buf[IP_HDR_LEN + ICMP_HDR_LEN + PAYLOAD_LEN];
+    memset(buf, 0, IP_HDR_LEN);
+    buf[0] = 0x45;
+    buf[8] = 64;
+    buf[9] = IPPROTO_ICMP;
+    memcpy(buf + 16, &peer->sin_addr.s_addr, 4);
+    build_echo(buf + IP_HDR_LEN, 0x1234, 2);
+    // The ICMP checksum is not computed for the packets with a header.
+    uint32_t sum = 0;
+    for (int i = IP_HDR_LEN; i < (int)sizeof(buf); i += 2)
+        sum += (buf[i] << 8) | buf[i + 1];
+    while (sum >> 16)
+        sum = (sum & 0xffff) + (sum >> 16);
+    buf[IP_HDR_LEN + 2] = ~sum >> 8;
+    buf[IP_HDR_LEN + 3] = ~sum & 0xff;
+
+    CHECK(sendto(fd, buf, sizeof(buf), 0, (struct sockaddr *)peer, sizeof(*peer)) ==
+              sizeof(buf),
+          "sendto");
+    CHECK(wait_reply(fd, 0x1234, 2) == PAYLOAD_LEN, "echo reply");
+
+    // The protocol of the header must be that of the socket.
+    buf[9] = IPPROTO_UDP;
+    CHECK(sendto(fd, buf, sizeof(buf), 0, (struct sockaddr *)peer, sizeof(*peer)) == -1 &&
+              errno == EINVAL,
+          "sendto");
+    puts("IP_HDRINCL OK");
+    return 0;
+}
+
+static int test_invalid()
+{
+    CHECK(socket(AF_INET, SOCK_RAW, 0) == -1 && errno == EPROTONOSUPPORT, "socket");
+    CHECK(socket(AF_INET, SOCK_RAW, IPPROTO_RAW) == -1 && errno == EPROTONOSUPPORT, "socket");
+
+    int fd = socket(AF_INET, SOCK_DGRAM, 0);
+    CHECK(fd >= 0, "socket");
+    int on = 1;
+    CHECK(setsockopt(fd, IPPROTO_IP, IP_HDRINCL, &on, sizeof(on)) == -1 && errno == ENOPROTOOPT,
+          "setsockopt");
+    close(fd);
+    puts("invalid OK");
+    return 0;
+}
+
+int main()
+{
+    puts("Hello, Ruxos C raw socket test!");
+
+    int fd = socket(AF_INET, SOCK_RAW, IPPROTO_ICMP);
+    CHECK(fd >= 0, "socket");
+    CHECK(fcntl(fd, F_SETFL, O_NONBLOCK) == 0, "fcntl");
+
+    struct sockaddr_in peer;
+    memset(&peer, 0, sizeof(peer));
+    peer.sin_family = AF_INET;
+    peer.sin_addr.s_addr = inet_addr(PEER_IP);
+
+    if (test_echo(fd, &peer) != 0 || test_hdrincl(fd, &peer) != 0 || test_invalid() != 0)
+        return -1;
+    close(fd);
+    puts("Raw socket test OK!");
+    return 0;
+}
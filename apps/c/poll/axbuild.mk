@@ -0,0 +1 @@
+app-objs := poll.o
@@ -0,0 +1,87 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <poll.h>
+#include <signal.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/time.h>
+#include <time.h>
+#include <unistd.h>
+
+static long elapsed_ms(const struct timespec *start)
+{
+    struct timespec now;
+    clock_gettime(CLOCK_MONOTONIC, &now);
+    return (now.tv_sec - start->tv_sec) * 1000 + (now.tv_nsec - start->tv_nsec) / 1000000;
+}
+
+static void on_alarm(int signum)
+{
+    (void)signum;
+}
+
+int main()
+{
+    int fds[2];
+    assert(pipe(fds) == 0);
+    struct pollfd pfds[4] = {
+        {.fd = fds[0], .events = POLLIN},
+        {.fd = fds[1], .events = POLLOUT},
+        // Negative fds are skipped, and closed ones are invalid.
+        {.fd = -1, .events = POLLIN},
+        {.fd = 100, .events = POLLIN},
+    };
+
+    // Nothing to read: the poll times out.
+    struct timespec start;
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    assert(poll(pfds, 1, 100) == 0);
+    assert(elapsed_ms(&start) >= 100);
+    assert(pfds[0].revents == 0);
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    assert(poll(NULL, 0, 50) == 0);
+    assert(elapsed_ms(&start) >= 50);
+    puts("poll timeout OK");
+
+    assert(poll(pfds, 4, 0) == 2);
+    assert(pfds[0].revents == 0 && pfds[1].revents == POLLOUT);
+    assert(pfds[2].revents == 0 && pfds[3].revents == POLLNVAL);
+    assert(write(fds[1], "x", 1) == 1);
+    assert(poll(pfds, 3, -1) == 2);
+    assert(pfds[0].revents == POLLIN && pfds[1].revents == POLLOUT && pfds[2].revents == 0);
+    puts("poll ready OK");
+
+    // A signal interrupts the wait.
+    char c;
+    assert(read(fds[0], &c, 1) == 1);
+    struct sigaction sa;
+    memset(&sa, 0, sizeof(sa));
+    sa.sa_handler = on_alarm;
+    assert(sigaction(SIGALRM, &sa, NULL) == 0);
+    struct itimerval timer = {.it_value = {.tv_usec = 50000}};
+    assert(setitimer(ITIMER_REAL, &timer, NULL) == 0);
+    assert(poll(pfds, 1, 5000) == -1 && errno == EINTR);
+    puts("poll EINTR OK");
+
+    // POLLHUP is reported once the write end is closed, even if not requested.
+    close(fds[1]);
+    pfds[0].events = 0;
+    assert(poll(pfds, 1, 0) == 1 && pfds[0].revents == POLLHUP);
+    pfds[0].events = POLLIN;
+    assert(poll(pfds, 1, 0) == 1 && pfds[0].revents == (POLLIN | POLLHUP));
+    assert(read(fds[0], &c, 1) == 0);
+    close(fds[0]);
+    puts("poll hangup OK");
+
+    puts("(C)Poll tests run OK!");
+    return 0;
+}
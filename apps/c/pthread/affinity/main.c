@@ -0,0 +1,138 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS
+ * OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A
+ * PARTICULAR PURPOSE. See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <pthread.h>
+#include <sched.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+
+#define NUM_CPUS  4
+#define MAX_CPUS  (8 * sizeof(cpu_set_t))
+#define NUM_YIELD 100
+
+static int count_cpus(cpu_set_t *set)
+{
+    int n = 0;
+    for (int i = 0; i < MAX_CPUS; i++) {
+        if (CPU_ISSET(i, set))
+            n++;
+    }
+    return n;
+}
+
+void test_default(void)
+{
+    cpu_set_t set;
+    CPU_ZERO(&set);
+    assert(sched_getaffinity(0, sizeof(set), &set) == 0);
+    assert(count_cpus(&set) == NUM_CPUS);
+    for (int i = 0; i < NUM_CPUS; i++) {
+        assert(CPU_ISSET(i, &set));
+    }
+    printf("test_default: cpus = %d\n", count_cpus(&set));
+}
+
+void test_invalid(void)
+{
+    cpu_set_t set;
+
+    CPU_ZERO(&set);
+    errno = 0;
+    assert(sched_setaffinity(0, sizeof(set), &set) == -1 && errno == EINVAL);
+
+    // no such CPU
+    CPU_SET(NUM_CPUS, &set);
+    errno = 0;
+    assert(sched_setaffinity(0, sizeof(set), &set) == -1 && errno == EINVAL);
+
+    // no such thread
+    CPU_SET(0, &set);
+    errno = 0;
+    assert(sched_setaffinity(12345, sizeof(set), &set) == -1 && errno == ESRCH);
+    errno = 0;
+    assert(sched_getaffinity(12345, sizeof(set), &set) == -1 && errno == ESRCH);
+
+    puts("test_invalid OK");
+}
+
+static pid_t worker_tid;
+static int pinned = 0;
+
+void *worker(void *arg)
+{
+    __atomic_store_n(&worker_tid, gettid(), __ATOMIC_SEQ_CST);
+    while (!__atomic_load_n(&pinned, __ATOMIC_SEQ_CST)) {
+        sched_yield();
+    }
+
+    // keep running on the CPUs left to it
+    for (int i = 0; i < NUM_YIELD; i++) {
+        sched_yield();
+    }
+
+    cpu_set_t set;
+    CPU_ZERO(&set);
+    assert(sched_getaffinity(0, sizeof(set), &set) == 0);
+    assert(count_cpus(&set) == 1 && CPU_ISSET(NUM_CPUS - 1, &set));
+    return NULL;
+}
+
+void test_pin_other(void)
+{
+    pthread_t t;
+    pthread_create(&t, NULL, worker, NULL);
+    while (!__atomic_load_n(&worker_tid, __ATOMIC_SEQ_CST)) {
+        sched_yield();
+    }
+
+    cpu_set_t set;
+    CPU_ZERO(&set);
+    CPU_SET(NUM_CPUS - 1, &set);
+    assert(sched_setaffinity(worker_tid, sizeof(set), &set) == 0);
+    __atomic_store_n(&pinned, 1, __ATOMIC_SEQ_CST);
+    pthread_join(t, NULL);
+
+    puts("test_pin_other OK");
+}
+
+void test_pin_self(void)
+{
+    cpu_set_t set, old;
+    CPU_ZERO(&old);
+    assert(sched_getaffinity(0, sizeof(old), &old) == 0);
+
+    for (int cpu = 0; cpu < NUM_CPUS; cpu++) {
+        CPU_ZERO(&set);
+        CPU_SET(cpu, &set);
+        // migrated right away if it is running on another CPU
+        assert(sched_setaffinity(0, sizeof(set), &set) == 0);
+        sched_yield();
+
+        CPU_ZERO(&set);
+        assert(sched_getaffinity(0, sizeof(set), &set) == 0);
+        assert(count_cpus(&set) == 1 && CPU_ISSET(cpu, &set));
+    }
+
+    assert(sched_setaffinity(0, sizeof(old), &old) == 0);
+    puts("test_pin_self OK");
+}
+
+int main()
+{
+    test_default();
+    test_invalid();
+    test_pin_other();
+    test_pin_self();
+    puts("(C)Pthread affinity tests run OK!");
+    return 0;
+}
@@ -0,0 +1,82 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS
+ * OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A
+ * PARTICULAR PURPOSE. See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <unistd.h>
+
+#define NUM_THREADS 8
+#define NUM_PHASES  10
+
+static pthread_barrier_t barrier;
+static int counter = 0;
+static int serial_count = 0;
+
+void *worker_func(void *arg)
+{
+    for (int phase = 0; phase < NUM_PHASES; phase++) {
+        __atomic_fetch_add(&counter, 1, __ATOMIC_SEQ_CST);
+
+        int ret = pthread_barrier_wait(&barrier);
+        assert(ret == 0 || ret == PTHREAD_BARRIER_SERIAL_THREAD);
+        if (ret == PTHREAD_BARRIER_SERIAL_THREAD) {
+            __atomic_fetch_add(&serial_count, 1, __ATOMIC_SEQ_CST);
+        }
+
+        // all threads have finished the increments of this phase
+        assert(__atomic_load_n(&counter, __ATOMIC_SEQ_CST) == (phase + 1) * NUM_THREADS);
+
+        // nobody starts the next phase before everyone has checked the counter
+        pthread_barrier_wait(&barrier);
+    }
+    return NULL;
+}
+
+void test_barrier()
+{
+    pthread_t threads[NUM_THREADS];
+    pthread_barrierattr_t attr;
+    int pshared;
+
+    assert(pthread_barrierattr_init(&attr) == 0);
+    assert(pthread_barrierattr_getpshared(&attr, &pshared) == 0);
+    assert(pshared == PTHREAD_PROCESS_PRIVATE);
+    assert(pthread_barrierattr_setpshared(&attr, 2) == EINVAL);
+    assert(pthread_barrierattr_setpshared(&attr, PTHREAD_PROCESS_SHARED) == 0);
+    assert(pthread_barrierattr_getpshared(&attr, &pshared) == 0);
+    assert(pshared == PTHREAD_PROCESS_SHARED);
+    assert(pthread_barrier_init(&barrier, &attr, 0) != 0);
+    assert(pthread_barrier_init(&barrier, &attr, NUM_THREADS) == 0);
+    assert(pthread_barrierattr_destroy(&attr) == 0);
+
+    for (int i = 0; i < NUM_THREADS; i++) {
+        pthread_create(&threads[i], NULL, worker_func, NULL);
+    }
+    for (int i = 0; i < NUM_THREADS; i++) {
+        pthread_join(threads[i], NULL);
+    }
+    assert(counter == NUM_THREADS * NUM_PHASES);
+    assert(pthread_barrier_destroy(&barrier) == 0);
+
+    // the serial thread is counted only at the first barrier of each phase,
+    // the second one also elects exactly one, which is ignored.
+    printf("test_barrier: threads = %d, phases = %d, serial = %d\n", NUM_THREADS, NUM_PHASES,
+           serial_count);
+}
+
+int main()
+{
+    test_barrier();
+    puts("(C)Pthread barrier tests run OK!");
+    return 0;
+}
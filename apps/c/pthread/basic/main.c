@@ -9,9 +9,13 @@
  */
 
 #include <assert.h>
+#include <errno.h>
 #include <pthread.h>
+#include <sched.h>
 #include <stdio.h>
 #include <stdlib.h>
+#include <string.h>
+#include <sys/prctl.h>
 #include <unistd.h>
 
 void *ThreadFunc1(void *arg)
@@ -103,6 +107,26 @@ void test_create_exit()
     printf("test_create_exit: %s\n", (char *)thread_result);
 }
 
+void test_create_no_stack()
+{
+    pthread_attr_t attr;
+    pthread_t t1;
+
+    // a stack which can not be allocated makes the creation fail, not abort
+    pthread_attr_init(&attr);
+    pthread_attr_setstacksize(&attr, 1UL << 40);
+    errno = 0;
+    int res = pthread_create(&t1, &attr, ThreadFunc1, NULL);
+    pthread_attr_destroy(&attr);
+    // the error is returned by musl, and set in `errno` by ruxlibc
+    if (res != EAGAIN && !(res == -1 && errno == EAGAIN)) {
+        printf("pthread create should fail with EAGAIN: %d\n", res);
+        return;
+    }
+
+    puts("test_create_no_stack: EAGAIN");
+}
+
 void test_mutex()
 {
     const int NUM_THREADS = 100;
@@ -204,6 +228,53 @@ void test_condvar()
     pthread_cond_destroy(&condvar);
 }
 
+void *name_func(void *arg)
+{
+    char name[16];
+
+    // the creator names this thread, wait for it
+    while (1) {
+        assert(pthread_getname_np(pthread_self(), name, sizeof(name)) == 0);
+        if (strcmp(name, "worker") == 0)
+            break;
+        sched_yield();
+    }
+    assert(pthread_setname_np(pthread_self(), "worker-renamed") == 0);
+    return NULL;
+}
+
+void test_name()
+{
+    pthread_t t;
+    char name[16];
+
+    pthread_create(&t, NULL, name_func, NULL);
+    assert(pthread_setname_np(t, "worker") == 0);
+    while (1) {
+        assert(pthread_getname_np(t, name, sizeof(name)) == 0);
+        if (strcmp(name, "worker-renamed") == 0)
+            break;
+        sched_yield();
+    }
+    pthread_join(t, NULL);
+
+    // names are truncated to 15 bytes like Linux
+    assert(pthread_setname_np(pthread_self(), "a-very-long-thread-name") == 0);
+    assert(pthread_getname_np(pthread_self(), name, sizeof(name)) == 0);
+    assert(strcmp(name, "a-very-long-thr") == 0);
+    // the buffer must hold the name and the terminator
+    assert(pthread_getname_np(pthread_self(), name, 15) != 0);
+    printf("test_name: %s\n", name);
+
+    // prctl names the calling thread too
+    assert(prctl(PR_SET_NAME, "prctl-name") == 0);
+    assert(pthread_getname_np(pthread_self(), name, sizeof(name)) == 0);
+    assert(strcmp(name, "prctl-name") == 0);
+    assert(pthread_setname_np(pthread_self(), "main") == 0);
+    assert(prctl(PR_GET_NAME, name) == 0 && strcmp(name, "main") == 0);
+    puts("test_name: prctl OK");
+}
+
 int main()
 {
     pthread_t main_thread = pthread_self();
@@ -211,8 +282,10 @@ int main()
 
     test_create_join();
     test_create_exit();
+    test_create_no_stack();
     test_mutex();
     test_condvar();
+    test_name();
     pthread_mutex_destroy(&lock);
 
     puts("(C)Pthread basic tests run OK!");
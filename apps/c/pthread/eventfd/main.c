@@ -0,0 +1,103 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <errno.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <sys/epoll.h>
+#include <sys/eventfd.h>
+#include <unistd.h>
+
+const int ROUND = 5;
+
+void *ChildFunc(void *arg)
+{
+    int efd = *(int *)arg;
+    for (int i = 1; i <= ROUND; i++) {
+        sleep(1);
+        eventfd_write(efd, i);
+    }
+    return NULL;
+}
+
+int test_counter()
+{
+    int efd = eventfd(0, 0);
+    if (efd < 0) {
+        puts("Fail to create eventfd");
+        return -1;
+    }
+
+    pthread_t t1;
+    pthread_create(&t1, NULL, ChildFunc, (void *)&efd);
+
+    eventfd_t sum = 0;
+    while (sum < ROUND * (ROUND + 1) / 2) {
+        eventfd_t value;
+        if (eventfd_read(efd, &value) != 0) {
+            puts("Fail to read eventfd");
+            return -1;
+        }
+        sum += value;
+    }
+    pthread_join(t1, NULL);
+    printf("Main thread receive sum: %llu\n", (unsigned long long)sum);
+    close(efd);
+    return 0;
+}
+
+int test_semaphore()
+{
+    int efd = eventfd(2, EFD_SEMAPHORE | EFD_NONBLOCK);
+    eventfd_t value;
+    for (int i = 0; i < 2; i++) {
+        if (eventfd_read(efd, &value) != 0 || value != 1) {
+            puts("Semaphore read should return 1");
+            return -1;
+        }
+    }
+    if (eventfd_read(efd, &value) == 0 || errno != EAGAIN) {
+        puts("Empty nonblocking eventfd should return EAGAIN");
+        return -1;
+    }
+    puts("Semaphore mode OK");
+    close(efd);
+    return 0;
+}
+
+int test_epoll()
+{
+    int efd = eventfd(0, EFD_NONBLOCK | EFD_CLOEXEC);
+    int epfd = epoll_create(1);
+    struct epoll_event ev = {.events = EPOLLIN, .data.fd = efd};
+    epoll_ctl(epfd, EPOLL_CTL_ADD, efd, &ev);
+
+    struct epoll_event events[1];
+    if (epoll_wait(epfd, events, 1, 0) != 0) {
+        puts("Empty eventfd should not be readable");
+        return -1;
+    }
+    eventfd_write(efd, 1);
+    if (epoll_wait(epfd, events, 1, 0) != 1 || events[0].data.fd != efd) {
+        puts("Eventfd should be readable after write");
+        return -1;
+    }
+    puts("Epoll readiness OK");
+    close(epfd);
+    close(efd);
+    return 0;
+}
+
+int main()
+{
+    if (test_counter() || test_semaphore() || test_epoll())
+        return -1;
+    puts("(C)Eventfd tests run OK");
+    return 0;
+}
@@ -0,0 +1,68 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <pthread.h>
+#include <stdatomic.h>
+#include <stdio.h>
+#include <sys/sysinfo.h>
+#include <unistd.h>
+
+#define NUM_BUSY 2
+
+static atomic_int stop = 0;
+
+void *busy_loop(void *arg)
+{
+    while (!atomic_load(&stop)) {
+    }
+    return NULL;
+}
+
+int main()
+{
+    struct sysinfo info;
+    pthread_t t[NUM_BUSY];
+
+    puts("Running loadavg tests...");
+    assert(sys_sysinfo(&info) == 0);
+    unsigned long before = info.loads[0];
+    unsigned short procs = info.procs;
+    assert(info.freeram > 0 && info.freeram < info.totalram);
+
+    for (int i = 0; i < NUM_BUSY; i++) assert(pthread_create(&t[i], NULL, busy_loop, NULL) == 0);
+    assert(sys_sysinfo(&info) == 0);
+    assert(info.procs == procs + NUM_BUSY);
+
+    // the load averages are updated every 5 seconds
+    for (int i = 0; i < 12 && info.loads[0] <= before; i++) {
+        sleep(1);
+        assert(sys_sysinfo(&info) == 0);
+    }
+    assert(info.loads[0] > before);
+    assert(info.loads[1] > 0 && info.loads[2] > 0);
+    // the 1 minute average reacts faster than the others
+    assert(info.loads[0] >= info.loads[1] && info.loads[1] >= info.loads[2]);
+    // with NUM_BUSY tasks spinning the load can't exceed the count of runnable tasks
+    assert(info.loads[0] <= (NUM_BUSY + 1ul) << SI_LOAD_SHIFT);
+
+    atomic_store(&stop, 1);
+    for (int i = 0; i < NUM_BUSY; i++) assert(pthread_join(t[i], NULL) == 0);
+
+    // and decays once the busy tasks are gone
+    unsigned long peak = info.loads[0];
+    for (int i = 0; i < 12 && info.loads[0] >= peak; i++) {
+        sleep(1);
+        assert(sys_sysinfo(&info) == 0);
+    }
+    assert(info.loads[0] < peak);
+
+    puts("loadavg test OK!");
+    return 0;
+}
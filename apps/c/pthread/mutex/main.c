@@ -0,0 +1,130 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS
+ * OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A
+ * PARTICULAR PURPOSE. See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <pthread.h>
+#include <sched.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#define NUM_THREADS 4
+#define NUM_LOOPS   100
+
+static pthread_mutex_t mutex;
+static volatile int counter = 0;
+
+static void init_mutex(pthread_mutex_t *m, int type)
+{
+    pthread_mutexattr_t attr;
+    int t;
+
+    assert(pthread_mutexattr_init(&attr) == 0);
+    assert(pthread_mutexattr_gettype(&attr, &t) == 0);
+    assert(t == PTHREAD_MUTEX_DEFAULT);
+    assert(pthread_mutexattr_settype(&attr, 3) == EINVAL);
+    assert(pthread_mutexattr_settype(&attr, type) == 0);
+    assert(pthread_mutexattr_gettype(&attr, &t) == 0);
+    assert(t == type);
+    assert(pthread_mutex_init(m, &attr) == 0);
+    assert(pthread_mutexattr_destroy(&attr) == 0);
+}
+
+void *trylock_func(void *arg)
+{
+    return (void *)(long)pthread_mutex_trylock(&mutex);
+}
+
+void *unlock_func(void *arg)
+{
+    return (void *)(long)pthread_mutex_unlock(&mutex);
+}
+
+// Runs `func` in another thread and returns its result.
+static long run_in_thread(void *(*func)(void *))
+{
+    pthread_t t;
+    void *res;
+    assert(pthread_create(&t, NULL, func, NULL) == 0);
+    assert(pthread_join(t, &res) == 0);
+    return (long)res;
+}
+
+void test_recursive()
+{
+    init_mutex(&mutex, PTHREAD_MUTEX_RECURSIVE);
+    assert(pthread_mutex_lock(&mutex) == 0);
+    assert(pthread_mutex_lock(&mutex) == 0);
+    assert(pthread_mutex_trylock(&mutex) == 0);
+    assert(run_in_thread(unlock_func) == EPERM);
+
+    // the mutex is only free after the matching unlocks
+    assert(pthread_mutex_unlock(&mutex) == 0);
+    assert(pthread_mutex_unlock(&mutex) == 0);
+    assert(run_in_thread(trylock_func) == EBUSY);
+    assert(pthread_mutex_unlock(&mutex) == 0);
+    assert(pthread_mutex_unlock(&mutex) == EPERM);
+    assert(run_in_thread(trylock_func) == 0);
+    assert(pthread_mutex_trylock(&mutex) == EBUSY);
+    assert(pthread_mutex_destroy(&mutex) == 0);
+    puts("test_recursive: OK");
+}
+
+void test_errorcheck()
+{
+    init_mutex(&mutex, PTHREAD_MUTEX_ERRORCHECK);
+    assert(pthread_mutex_lock(&mutex) == 0);
+    assert(pthread_mutex_lock(&mutex) == EDEADLK);
+    assert(pthread_mutex_trylock(&mutex) == EBUSY);
+    assert(run_in_thread(unlock_func) == EPERM);
+    assert(pthread_mutex_unlock(&mutex) == 0);
+    assert(pthread_mutex_unlock(&mutex) == EPERM);
+    assert(pthread_mutex_destroy(&mutex) == 0);
+    puts("test_errorcheck: OK");
+}
+
+void *add_func(void *arg)
+{
+    for (int i = 0; i < NUM_LOOPS; i++) {
+        assert(pthread_mutex_lock(&mutex) == 0);
+        assert(pthread_mutex_lock(&mutex) == 0);
+        int value = counter;
+        sched_yield();
+        counter = value + 1;
+        assert(pthread_mutex_unlock(&mutex) == 0);
+        assert(pthread_mutex_unlock(&mutex) == 0);
+    }
+    return NULL;
+}
+
+void test_recursive_contention()
+{
+    pthread_t threads[NUM_THREADS];
+
+    init_mutex(&mutex, PTHREAD_MUTEX_RECURSIVE);
+    for (int i = 0; i < NUM_THREADS; i++) {
+        assert(pthread_create(&threads[i], NULL, add_func, NULL) == 0);
+    }
+    for (int i = 0; i < NUM_THREADS; i++) {
+        assert(pthread_join(threads[i], NULL) == 0);
+    }
+    assert(counter == NUM_THREADS * NUM_LOOPS);
+    assert(pthread_mutex_destroy(&mutex) == 0);
+    printf("test_recursive_contention: threads = %d, counter = %d\n", NUM_THREADS, counter);
+}
+
+int main()
+{
+    test_recursive();
+    test_errorcheck();
+    test_recursive_contention();
+    puts("(C)Pthread mutex tests run OK!");
+    return 0;
+}
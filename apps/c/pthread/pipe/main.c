@@ -7,6 +7,7 @@
  *   See the Mulan PSL v2 for more details.
  */
 
+#include <errno.h>
 #include <pthread.h>
 #include <stdio.h>
 #include <string.h>
@@ -38,6 +39,10 @@ void main()
         puts("Fail to create pipe");
         return;
     }
+    if (isatty(fd[0]) || errno != ENOTTY) {
+        puts("A pipe should not be a tty");
+        return;
+    }
 
     pthread_t t1;
     pthread_create(&t1, NULL, ChildFunc, (void *)fd);
@@ -0,0 +1,114 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS
+ * OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A
+ * PARTICULAR PURPOSE. See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <pthread.h>
+#include <sched.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <unistd.h>
+
+#define NUM_READERS 4
+#define NUM_WRITES  100
+#define NUM_READS   200
+
+static pthread_rwlock_t rwlock = PTHREAD_RWLOCK_INITIALIZER;
+
+static volatile int readers_inside = 0;
+static volatile int writer_inside = 0;
+static volatile int shared_a = 0;
+static volatile int shared_b = 0;
+
+void *reader_func(void *arg)
+{
+    for (int i = 0; i < NUM_READS; i++) {
+        assert(pthread_rwlock_rdlock(&rwlock) == 0);
+        __atomic_fetch_add(&readers_inside, 1, __ATOMIC_SEQ_CST);
+        assert(writer_inside == 0);
+        // the writer always updates both values together
+        assert(shared_a == shared_b);
+        sched_yield();
+        __atomic_fetch_sub(&readers_inside, 1, __ATOMIC_SEQ_CST);
+        assert(pthread_rwlock_unlock(&rwlock) == 0);
+    }
+    return NULL;
+}
+
+void *writer_func(void *arg)
+{
+    for (int i = 0; i < NUM_WRITES; i++) {
+        assert(pthread_rwlock_wrlock(&rwlock) == 0);
+        writer_inside = 1;
+        assert(readers_inside == 0);
+        shared_a++;
+        sched_yield();
+        shared_b++;
+        writer_inside = 0;
+        assert(pthread_rwlock_unlock(&rwlock) == 0);
+    }
+    return NULL;
+}
+
+void test_rwlock_try()
+{
+    pthread_rwlock_t lock;
+    pthread_rwlockattr_t attr;
+    int pshared;
+
+    assert(pthread_rwlockattr_init(&attr) == 0);
+    assert(pthread_rwlockattr_getpshared(&attr, &pshared) == 0);
+    assert(pshared == PTHREAD_PROCESS_PRIVATE);
+    assert(pthread_rwlockattr_setpshared(&attr, 2) == EINVAL);
+    assert(pthread_rwlockattr_setpshared(&attr, PTHREAD_PROCESS_SHARED) == 0);
+    assert(pthread_rwlockattr_getpshared(&attr, &pshared) == 0);
+    assert(pshared == PTHREAD_PROCESS_SHARED);
+    assert(pthread_rwlock_init(&lock, &attr) == 0);
+    assert(pthread_rwlockattr_destroy(&attr) == 0);
+
+    assert(pthread_rwlock_tryrdlock(&lock) == 0);
+    assert(pthread_rwlock_tryrdlock(&lock) == 0);
+    assert(pthread_rwlock_trywrlock(&lock) != 0);
+    assert(pthread_rwlock_unlock(&lock) == 0);
+    assert(pthread_rwlock_unlock(&lock) == 0);
+
+    assert(pthread_rwlock_trywrlock(&lock) == 0);
+    assert(pthread_rwlock_tryrdlock(&lock) != 0);
+    assert(pthread_rwlock_trywrlock(&lock) != 0);
+    assert(pthread_rwlock_unlock(&lock) == 0);
+
+    assert(pthread_rwlock_destroy(&lock) == 0);
+    puts("test_rwlock_try: OK");
+}
+
+void test_rwlock()
+{
+    pthread_t readers[NUM_READERS], writer;
+    for (int i = 0; i < NUM_READERS; i++) {
+        pthread_create(&readers[i], NULL, reader_func, NULL);
+    }
+    pthread_create(&writer, NULL, writer_func, NULL);
+
+    for (int i = 0; i < NUM_READERS; i++) {
+        pthread_join(readers[i], NULL);
+    }
+    pthread_join(writer, NULL);
+
+    assert(shared_a == NUM_WRITES && shared_b == NUM_WRITES);
+    printf("test_rwlock: readers = %d, writes = %d\n", NUM_READERS, shared_a);
+}
+
+int main()
+{
+    test_rwlock_try();
+    test_rwlock();
+    puts("(C)Pthread rwlock tests run OK!");
+    return 0;
+}
@@ -0,0 +1,195 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS
+ * OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A
+ * PARTICULAR PURPOSE. See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <pthread.h>
+#include <sched.h>
+#include <stdio.h>
+#include <time.h>
+#include <unistd.h>
+
+static long elapsed_ms(struct timespec *start)
+{
+    struct timespec now;
+    clock_gettime(CLOCK_MONOTONIC, &now);
+    return (now.tv_sec - start->tv_sec) * 1000 + (now.tv_nsec - start->tv_nsec) / 1000000;
+}
+
+static void set_self(int policy, int prio)
+{
+    struct sched_param param = {.sched_priority = prio};
+    assert(sched_setscheduler(0, policy, &param) == 0);
+}
+
+void test_default(void)
+{
+    struct sched_param param = {.sched_priority = -1};
+    assert(sched_getscheduler(0) == SCHED_OTHER);
+    assert(sched_getparam(0, &param) == 0 && param.sched_priority == 0);
+    assert(sched_get_priority_min(SCHED_OTHER) == 0);
+    assert(sched_get_priority_max(SCHED_OTHER) == 0);
+    printf("test_default: fifo %d..%d, rr %d..%d\n", sched_get_priority_min(SCHED_FIFO),
+           sched_get_priority_max(SCHED_FIFO), sched_get_priority_min(SCHED_RR),
+           sched_get_priority_max(SCHED_RR));
+}
+
+void test_invalid(void)
+{
+    struct sched_param param;
+
+    param.sched_priority = 0;
+    errno = 0;
+    assert(sched_setscheduler(0, SCHED_FIFO, &param) == -1 && errno == EINVAL);
+
+    param.sched_priority = 100;
+    errno = 0;
+    assert(sched_setscheduler(0, SCHED_RR, &param) == -1 && errno == EINVAL);
+
+    param.sched_priority = 1;
+    errno = 0;
+    assert(sched_setscheduler(0, SCHED_OTHER, &param) == -1 && errno == EINVAL);
+    errno = 0;
+    assert(sched_setscheduler(0, 42, &param) == -1 && errno == EINVAL);
+    errno = 0;
+    assert(sched_get_priority_max(42) == -1 && errno == EINVAL);
+
+    // no such thread
+    errno = 0;
+    assert(sched_setscheduler(12345, SCHED_FIFO, &param) == -1 && errno == ESRCH);
+    errno = 0;
+    assert(sched_getscheduler(12345) == -1 && errno == ESRCH);
+
+    assert(sched_getscheduler(0) == SCHED_OTHER);
+    puts("test_invalid OK");
+}
+
+static int low_ran = 0;
+static int low_stop = 0;
+
+void *low_worker(void *arg)
+{
+    __atomic_store_n(&low_ran, 1, __ATOMIC_SEQ_CST);
+    while (!__atomic_load_n(&low_stop, __ATOMIC_SEQ_CST)) {
+    }
+    return NULL;
+}
+
+void test_fifo_not_preempted(void)
+{
+    struct timespec start;
+    pthread_t t;
+
+    set_self(SCHED_FIFO, 10);
+    struct sched_param param;
+    assert(sched_getscheduler(0) == SCHED_FIFO);
+    assert(sched_getparam(0, &param) == 0 && param.sched_priority == 10);
+
+    // A normal task never runs while a FIFO task is busy.
+    pthread_create(&t, NULL, low_worker, NULL);
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    while (elapsed_ms(&start) < 200) {
+    }
+    assert(!__atomic_load_n(&low_ran, __ATOMIC_SEQ_CST));
+
+    // It shares the CPU with the normal ones again.
+    set_self(SCHED_OTHER, 0);
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    while (!__atomic_load_n(&low_ran, __ATOMIC_SEQ_CST)) {
+        assert(elapsed_ms(&start) < 1000);
+    }
+    __atomic_store_n(&low_stop, 1, __ATOMIC_SEQ_CST);
+    pthread_join(t, NULL);
+
+    puts("test_fifo_not_preempted OK");
+}
+
+static int high_ready = 0;
+static int high_done = 0;
+
+void *high_worker(void *arg)
+{
+    set_self(SCHED_FIFO, 20);
+    __atomic_store_n(&high_ready, 1, __ATOMIC_SEQ_CST);
+    usleep(100000);
+    // woken up by the timer, it preempts the busy lower priority task
+    __atomic_store_n(&high_done, 1, __ATOMIC_SEQ_CST);
+    return NULL;
+}
+
+void test_higher_preempts(void)
+{
+    struct timespec start;
+    pthread_t t;
+
+    pthread_create(&t, NULL, high_worker, NULL);
+    while (!__atomic_load_n(&high_ready, __ATOMIC_SEQ_CST)) {
+        sched_yield();
+    }
+
+    set_self(SCHED_FIFO, 10);
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    while (!__atomic_load_n(&high_done, __ATOMIC_SEQ_CST)) {
+        assert(elapsed_ms(&start) < 2000);
+    }
+    set_self(SCHED_OTHER, 0);
+    pthread_join(t, NULL);
+
+    puts("test_higher_preempts OK");
+}
+
+static int yield_ready = 0;
+static int yield_count = 0;
+static int yield_stop = 0;
+
+void *yield_worker(void *arg)
+{
+    set_self(SCHED_FIFO, 10);
+    __atomic_store_n(&yield_ready, 1, __ATOMIC_SEQ_CST);
+    while (!__atomic_load_n(&yield_stop, __ATOMIC_SEQ_CST)) {
+        __atomic_add_fetch(&yield_count, 1, __ATOMIC_SEQ_CST);
+        sched_yield();
+    }
+    return NULL;
+}
+
+void test_yield_progress(void)
+{
+    pthread_t t;
+
+    pthread_create(&t, NULL, yield_worker, NULL);
+    while (!__atomic_load_n(&yield_ready, __ATOMIC_SEQ_CST)) {
+        sched_yield();
+    }
+
+    // Two busy FIFO tasks of the same priority only share the CPU by yielding.
+    set_self(SCHED_FIFO, 10);
+    int start = __atomic_load_n(&yield_count, __ATOMIC_SEQ_CST);
+    for (int i = 0; i < 100; i++) {
+        sched_yield();
+    }
+    assert(__atomic_load_n(&yield_count, __ATOMIC_SEQ_CST) - start >= 50);
+    __atomic_store_n(&yield_stop, 1, __ATOMIC_SEQ_CST);
+    set_self(SCHED_OTHER, 0);
+    pthread_join(t, NULL);
+
+    puts("test_yield_progress OK");
+}
+
+int main()
+{
+    test_default();
+    test_invalid();
+    test_fifo_not_preempted();
+    test_higher_preempts();
+    test_yield_progress();
+    puts("(C)Pthread sched tests run OK!");
+    return 0;
+}
@@ -0,0 +1,50 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS
+ * OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A
+ * PARTICULAR PURPOSE. See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <string.h>
+
+#define STACK_SIZE (64 * 1024)
+
+static int recurse(int depth)
+{
+    volatile char buf[512];
+    memset((char *)buf, depth, sizeof(buf));
+    if (depth % 16 == 0)
+        printf("depth %d\n", depth);
+    return recurse(depth + 1) + buf[depth % sizeof(buf)];
+}
+
+void *overflow_func(void *arg)
+{
+    recurse(0);
+    return NULL;
+}
+
+int main()
+{
+    pthread_attr_t attr;
+    size_t size;
+    pthread_t t;
+
+    pthread_attr_init(&attr);
+    pthread_attr_setstacksize(&attr, STACK_SIZE);
+    pthread_attr_getstacksize(&attr, &size);
+    assert(size == STACK_SIZE);
+
+    puts("Overflowing a 64K thread stack, expecting a \"stack overflow in task N\" panic");
+    pthread_create(&t, &attr, overflow_func, NULL);
+    pthread_join(t, NULL);
+
+    puts("(C)Unreachable: the stack overflow was not detected!");
+    return 1;
+}
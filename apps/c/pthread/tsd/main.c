@@ -9,6 +9,7 @@
  */
 
 #include <assert.h>
+#include <limits.h>
 #include <pthread.h>
 #include <stdio.h>
 #include <stdlib.h>
@@ -62,12 +63,104 @@ void test_specific()
     pthread_key_delete(p_key);
 }
 
+static pthread_key_t visible_key;
+
+void *visible_func(void *arg)
+{
+    // a value set by another thread is not visible here
+    assert(pthread_getspecific(visible_key) == NULL);
+    pthread_setspecific(visible_key, arg);
+    assert(pthread_getspecific(visible_key) == arg);
+    return NULL;
+}
+
+void test_visibility()
+{
+    int val = 0x42;
+    pthread_t t;
+
+    assert(pthread_key_create(&visible_key, NULL) == 0);
+    pthread_setspecific(visible_key, &val);
+    pthread_create(&t, NULL, visible_func, (void *)0x1);
+    pthread_join(t, NULL);
+    // the other thread did not change the value of this thread
+    assert(pthread_getspecific(visible_key) == &val);
+    pthread_key_delete(visible_key);
+    puts("TSD visibility test success");
+}
+
+static pthread_key_t again_key;
+static int destr_rounds = 0;
+
+void again_destr_func(void *arg)
+{
+    destr_rounds++;
+    // the value is reset to NULL before the destructor is called
+    assert(pthread_getspecific(again_key) == NULL);
+    // keep setting a new value, so the destructor is called again
+    pthread_setspecific(again_key, arg);
+}
+
+void *again_func(void *arg)
+{
+    pthread_setspecific(again_key, arg);
+    return NULL;
+}
+
+void *empty_func(void *arg)
+{
+    return NULL;
+}
+
+void test_destr_iterations()
+{
+    pthread_t t;
+
+    assert(sysconf(_SC_THREAD_DESTRUCTOR_ITERATIONS) == PTHREAD_DESTRUCTOR_ITERATIONS);
+    pthread_key_create(&again_key, again_destr_func);
+    pthread_create(&t, NULL, again_func, (void *)0x1);
+    pthread_join(t, NULL);
+    printf("destructor called %d times\n", destr_rounds);
+    assert(destr_rounds == PTHREAD_DESTRUCTOR_ITERATIONS);
+
+    // a thread that never set a value does not call the destructor
+    destr_rounds = 0;
+    pthread_create(&t, NULL, empty_func, NULL);
+    pthread_join(t, NULL);
+    assert(destr_rounds == 0);
+    pthread_key_delete(again_key);
+    puts("TSD destructor iterations test success");
+}
+
+static pthread_key_t all_keys[PTHREAD_KEYS_MAX];
+
+void test_keys_max()
+{
+    int val = 0x42;
+    pthread_key_t key;
+
+    for (int i = 0; i < PTHREAD_KEYS_MAX; i++) assert(pthread_key_create(&all_keys[i], NULL) == 0);
+    // no key is left
+    assert(pthread_key_create(&key, NULL) != 0);
+
+    // the value of a deleted key is not seen through a new key
+    pthread_setspecific(all_keys[0], &val);
+    assert(pthread_key_delete(all_keys[0]) == 0);
+    assert(pthread_key_create(&key, NULL) == 0 && key == all_keys[0]);
+    assert(pthread_getspecific(key) == NULL);
+    for (int i = 0; i < PTHREAD_KEYS_MAX; i++) pthread_key_delete(all_keys[i]);
+    puts("TSD keys max test success");
+}
+
 int main()
 {
     pthread_t main_thread = pthread_self();
     assert(main_thread != 0);
 
     test_specific();
+    test_visibility();
+    test_destr_iterations();
+    test_keys_max();
 
     puts("(C)Pthread TSD tests run OK!");
 
@@ -0,0 +1 @@
+app-objs := reuseaddr.o
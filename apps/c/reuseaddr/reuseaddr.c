@@ -0,0 +1,188 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+#include <arpa/inet.h>
+#include <netinet/in.h>
+#include <netinet/tcp.h>
+#include <sys/socket.h>
+
+#define PORT 5555
+// No host answers for this address, so the handshake never completes.
+#define SILENT_IP "10.0.2.99"
+
+// Creates a TCP socket bound to `PORT`, with SO_REUSEADDR set to `reuse`.
+static int bind_port(int reuse)
+{
+    struct sockaddr_in local;
+    memset(&local, 0, sizeof(local));
+    local.sin_family = AF_INET;
+    local.sin_addr.s_addr = htonl(INADDR_ANY);
+    local.sin_port = htons(PORT);
+
+    int sock = socket(AF_INET, SOCK_STREAM, IPPROTO_TCP);
+    if (sock == -1) {
+        perror("socket() error");
+        return -1;
+    }
+    if (setsockopt(sock, SOL_SOCKET, SO_REUSEADDR, &reuse, sizeof(reuse)) != 0) {
+        perror("setsockopt() error");
+        close(sock);
+        return -1;
+    }
+    if (bind(sock, (struct sockaddr *)&local, sizeof(local)) != 0) {
+        int err = errno;
+        close(sock);
+        errno = err;
+        return -1;
+    }
+    return sock;
+}
+
+// Creates a TCP socket with SO_REUSEADDR and listens on `PORT`.
+static int listen_reuse(void)
+{
+    int sock = bind_port(1);
+    if (sock == -1)
+        return -1;
+    if (listen(sock, 8) != 0) {
+        perror("listen() error");
+        close(sock);
+        return -1;
+    }
+    return sock;
+}
+
+// Starts connecting from `PORT` to an address which never answers, so that
+// the connection keeps using the port.
+static int connect_pending(void)
+{
+    int sock = bind_port(0);
+    if (sock == -1) {
+        perror("bind() error");
+        return -1;
+    }
+    struct sockaddr_in peer;
+    memset(&peer, 0, sizeof(peer));
+    peer.sin_family = AF_INET;
+    peer.sin_addr.s_addr = inet_addr(SILENT_IP);
+    peer.sin_port = htons(80);
+    fcntl(sock, F_SETFL, fcntl(sock, F_GETFL) | O_NONBLOCK);
+    if (connect(sock, (struct sockaddr *)&peer, sizeof(peer)) != -1 || errno != EINPROGRESS) {
+        puts("connect() should fail with EINPROGRESS");
+        close(sock);
+        return -1;
+    }
+    return sock;
+}
+
+// Reads the `int` option `optname` at `level`, or returns -1.
+static int get_int_option(int sock, int level, int optname)
+{
+    int val = -1;
+    socklen_t len = sizeof(val);
+    if (getsockopt(sock, level, optname, &val, &len) != 0 || len != sizeof(val))
+        return -1;
+    return val;
+}
+
+// Checks the options of the listening socket `sock`.
+static int check_options(int sock)
+{
+    if (get_int_option(sock, SOL_SOCKET, SO_REUSEADDR) != 1 ||
+        get_int_option(sock, SOL_SOCKET, SO_TYPE) != SOCK_STREAM ||
+        get_int_option(sock, SOL_SOCKET, SO_ERROR) != 0) {
+        puts("unexpected SOL_SOCKET option value");
+        return -1;
+    }
+    int on = 1;
+    if (setsockopt(sock, IPPROTO_TCP, TCP_NODELAY, &on, sizeof(on)) != 0 ||
+        get_int_option(sock, IPPROTO_TCP, TCP_NODELAY) != 1) {
+        puts("TCP_NODELAY not set");
+        return -1;
+    }
+    if (setsockopt(sock, SOL_SOCKET, SO_DEBUG, &on, sizeof(on)) != -1 || errno != ENOPROTOOPT) {
+        puts("unknown option should fail with ENOPROTOOPT");
+        return -1;
+    }
+    // Buffer sizes are doubled and clamped like on Linux.
+    int size = 1 << 30;
+    if (setsockopt(sock, SOL_SOCKET, SO_RCVBUF, &size, sizeof(size)) != 0 ||
+        get_int_option(sock, SOL_SOCKET, SO_RCVBUF) != 4 * 1024 * 1024) {
+        puts("SO_RCVBUF not clamped");
+        return -1;
+    }
+    int udp = socket(AF_INET, SOCK_DGRAM, 0);
+    int rcvbuf = 16384, sndbuf = 1;
+    if (setsockopt(udp, SOL_SOCKET, SO_RCVBUF, &rcvbuf, sizeof(rcvbuf)) != 0 ||
+        setsockopt(udp, SOL_SOCKET, SO_SNDBUF, &sndbuf, sizeof(sndbuf)) != 0 ||
+        get_int_option(udp, SOL_SOCKET, SO_RCVBUF) != 2 * rcvbuf ||
+        get_int_option(udp, SOL_SOCKET, SO_SNDBUF) != 2048) {
+        puts("UDP buffers not resized");
+        return -1;
+    }
+    close(udp);
+    return 0;
+}
+
+int main()
+{
+    puts("Hello, Ruxos C SO_REUSEADDR test!");
+    int sock = listen_reuse();
+    if (sock == -1) {
+        perror("first bind() error");
+        return -1;
+    }
+    puts("listening");
+    if (check_options(sock) != 0)
+        return -1;
+    puts("options checked");
+
+    // A second live listener on the same address still conflicts.
+    if (listen_reuse() != -1 || errno != EADDRINUSE) {
+        puts("second listener should fail with EADDRINUSE");
+        return -1;
+    }
+    puts("second listener refused");
+
+    // Once closed, the port can be bound again right away.
+    close(sock);
+    sock = listen_reuse();
+    if (sock == -1) {
+        perror("rebind() error");
+        return -1;
+    }
+    puts("listening again");
+    close(sock);
+
+    // A port still used by a connection can only be bound with SO_REUSEADDR.
+    int conn = connect_pending();
+    if (conn == -1)
+        return -1;
+    if (bind_port(0) != -1 || errno != EADDRINUSE) {
+        puts("bind() without SO_REUSEADDR should fail with EADDRINUSE");
+        return -1;
+    }
+    puts("port in use refused");
+    sock = listen_reuse();
+    if (sock == -1) {
+        perror("bind() with SO_REUSEADDR error");
+        return -1;
+    }
+    puts("port in use reused");
+    close(sock);
+    close(conn);
+    puts("SO_REUSEADDR test OK!");
+    return 0;
+}
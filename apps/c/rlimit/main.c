@@ -0,0 +1,94 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <stdio.h>
+#include <sys/resource.h>
+#include <unistd.h>
+
+#define NOFILE_SOFT 8
+
+static void set_limit_fails(int resource, rlim_t cur, rlim_t max, int err)
+{
+    struct rlimit rl = {.rlim_cur = cur, .rlim_max = max};
+    assert(setrlimit(resource, &rl) == -1);
+    assert(errno == err);
+}
+
+void test_nofile()
+{
+    struct rlimit rl;
+    assert(getrlimit(RLIMIT_NOFILE, &rl) == 0);
+    assert(rl.rlim_cur == rl.rlim_max);
+    rlim_t hard = rl.rlim_max;
+    printf("RLIMIT_NOFILE: %llu\n", hard);
+
+    // New fds stay below the soft limit.
+    rl.rlim_cur = NOFILE_SOFT;
+    assert(setrlimit(RLIMIT_NOFILE, &rl) == 0);
+    int fd, last = -1;
+    while ((fd = dup(1)) >= 0) {
+        last = fd;
+    }
+    assert(errno == EMFILE);
+    assert(last == NOFILE_SOFT - 1);
+
+    // The soft limit can go below the open fds, which stay open.
+    rl.rlim_cur = 4;
+    assert(setrlimit(RLIMIT_NOFILE, &rl) == 0);
+    assert(dup(1) == -1 && errno == EMFILE);
+    for (fd = 4; fd <= last; fd++) {
+        assert(close(fd) == 0);
+    }
+    assert((fd = dup(1)) == -1 && errno == EMFILE);
+    close(3);
+    assert((fd = dup(1)) == 3);
+    close(fd);
+
+    // The hard limit can be lowered, but not raised again.
+    set_limit_fails(RLIMIT_NOFILE, hard + 1, hard, EINVAL);
+    set_limit_fails(RLIMIT_NOFILE, hard, hard + 1, EPERM);
+    rl.rlim_cur = rl.rlim_max = hard / 2;
+    assert(setrlimit(RLIMIT_NOFILE, &rl) == 0);
+    set_limit_fails(RLIMIT_NOFILE, hard / 2, hard, EPERM);
+    assert(getrlimit(RLIMIT_NOFILE, &rl) == 0);
+    assert(rl.rlim_cur == hard / 2 && rl.rlim_max == hard / 2);
+    puts("test_nofile OK");
+}
+
+void test_stack_nproc()
+{
+    struct rlimit rl;
+    assert(getrlimit(RLIMIT_STACK, &rl) == 0);
+    assert(rl.rlim_cur > 0 && rl.rlim_cur <= rl.rlim_max);
+    rl.rlim_cur /= 2;
+    assert(setrlimit(RLIMIT_STACK, &rl) == 0);
+    struct rlimit new_rl;
+    assert(getrlimit(RLIMIT_STACK, &new_rl) == 0);
+    assert(new_rl.rlim_cur == rl.rlim_cur && new_rl.rlim_max == rl.rlim_max);
+
+    assert(getrlimit(RLIMIT_NPROC, &rl) == 0);
+    assert(rl.rlim_cur >= 1);
+
+    // Limits not set otherwise are unlimited.
+    assert(getrlimit(RLIMIT_CORE, &rl) == 0);
+    assert(rl.rlim_cur == RLIM_INFINITY && rl.rlim_max == RLIM_INFINITY);
+
+    assert(getrlimit(RLIMIT_NLIMITS, &rl) == -1 && errno == EINVAL);
+    puts("test_stack_nproc OK");
+}
+
+int main()
+{
+    test_nofile();
+    test_stack_nproc();
+    puts("(C)rlimit tests run OK!");
+    return 0;
+}
@@ -0,0 +1,157 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/resource.h>
+#include <time.h>
+#include <unistd.h>
+
+#define SPIN_MS 100
+#define MAXRSS_GROWTH (1 << 20)
+
+static long now_ms()
+{
+    struct timespec ts;
+    clock_gettime(CLOCK_MONOTONIC, &ts);
+    return ts.tv_sec * 1000 + ts.tv_nsec / 1000000;
+}
+
+static void spin(long ms)
+{
+    long end = now_ms() + ms;
+    while (now_ms() < end) {
+    }
+}
+
+static long utime_ms(const struct rusage *ru)
+{
+    return ru->ru_utime.tv_sec * 1000 + ru->ru_utime.tv_usec / 1000;
+}
+
+void test_spin()
+{
+    struct rusage before, after;
+    assert(getrusage(RUSAGE_SELF, &before) == 0);
+    spin(SPIN_MS);
+    assert(getrusage(RUSAGE_SELF, &after) == 0);
+    assert(utime_ms(&after) - utime_ms(&before) >= SPIN_MS);
+    assert(after.ru_stime.tv_sec == 0 && after.ru_stime.tv_usec == 0);
+    puts("test_spin OK");
+}
+
+void test_voluntary()
+{
+    struct rusage before, after;
+    assert(getrusage(RUSAGE_THREAD, &before) == 0);
+    usleep(10000);
+    assert(getrusage(RUSAGE_THREAD, &after) == 0);
+    assert(after.ru_nvcsw > before.ru_nvcsw);
+    puts("test_voluntary OK");
+}
+
+static void *spinner(void *arg)
+{
+    struct rusage *ru = arg;
+    spin(SPIN_MS);
+    assert(getrusage(RUSAGE_THREAD, ru) == 0);
+    return NULL;
+}
+
+void test_preempted()
+{
+    // Two threads spinning at the same time on a single CPU preempt each other.
+    pthread_t t1, t2;
+    struct rusage ru1, ru2, self;
+    assert(pthread_create(&t1, NULL, spinner, &ru1) == 0);
+    assert(pthread_create(&t2, NULL, spinner, &ru2) == 0);
+    assert(pthread_join(t1, NULL) == 0);
+    assert(pthread_join(t2, NULL) == 0);
+    assert(ru1.ru_nivcsw > 0 && ru2.ru_nivcsw > 0);
+
+    // The process time includes the time of both threads.
+    assert(getrusage(RUSAGE_SELF, &self) == 0);
+    assert(utime_ms(&self) >= utime_ms(&ru1) + utime_ms(&ru2));
+    puts("test_preempted OK");
+}
+
+static long cputime_ms(clockid_t clk)
+{
+    struct timespec ts;
+    assert(clock_gettime(clk, &ts) == 0);
+    return ts.tv_sec * 1000 + ts.tv_nsec / 1000000;
+}
+
+static void *sleeper(void *arg)
+{
+    long *slept = arg;
+    long before = cputime_ms(CLOCK_THREAD_CPUTIME_ID);
+    usleep(SPIN_MS * 1000);
+    *slept = cputime_ms(CLOCK_THREAD_CPUTIME_ID) - before;
+    return NULL;
+}
+
+void test_cputime()
+{
+    // The CPU time of a thread only advances while it runs, not while it sleeps.
+    pthread_t t;
+    long slept;
+    assert(pthread_create(&t, NULL, sleeper, &slept) == 0);
+    long thread_before = cputime_ms(CLOCK_THREAD_CPUTIME_ID);
+    long process_before = cputime_ms(CLOCK_PROCESS_CPUTIME_ID);
+    spin(SPIN_MS);
+    // The sleeper may briefly run in the meantime.
+    long spun = cputime_ms(CLOCK_THREAD_CPUTIME_ID) - thread_before;
+    assert(spun >= SPIN_MS * 9 / 10 && spun <= SPIN_MS * 11 / 10);
+    assert(cputime_ms(CLOCK_PROCESS_CPUTIME_ID) - process_before >= spun);
+    assert(pthread_join(t, NULL) == 0);
+    assert(slept < SPIN_MS / 10);
+    puts("test_cputime OK");
+}
+
+void test_maxrss()
+{
+    // The peak of the heap is reported in kilobytes, and stays after freeing.
+    struct rusage before, after;
+    assert(getrusage(RUSAGE_SELF, &before) == 0);
+    size_t len = before.ru_maxrss * 1024 + MAXRSS_GROWTH;
+    char *buf = malloc(len);
+    assert(buf != NULL);
+    memset(buf, 0, len);
+    free(buf);
+    assert(getrusage(RUSAGE_THREAD, &after) == 0);
+    assert(after.ru_maxrss >= before.ru_maxrss + MAXRSS_GROWTH / 1024);
+    puts("test_maxrss OK");
+}
+
+void test_children_invalid()
+{
+    struct rusage ru;
+    assert(getrusage(RUSAGE_CHILDREN, &ru) == 0);
+    assert(ru.ru_utime.tv_sec == 0 && ru.ru_utime.tv_usec == 0);
+    assert(ru.ru_nvcsw == 0 && ru.ru_nivcsw == 0 && ru.ru_maxrss == 0);
+    assert(getrusage(42, &ru) == -1 && errno == EINVAL);
+    puts("test_children_invalid OK");
+}
+
+int main()
+{
+    test_spin();
+    test_voluntary();
+    test_preempted();
+    test_cputime();
+    test_maxrss();
+    test_children_invalid();
+    puts("(C)rusage tests run OK!");
+    return 0;
+}
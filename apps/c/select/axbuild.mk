@@ -0,0 +1 @@
+app-objs := select.o
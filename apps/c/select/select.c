@@ -0,0 +1,93 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/select.h>
+#include <sys/time.h>
+#include <time.h>
+#include <unistd.h>
+
+static long elapsed_ms(const struct timespec *start)
+{
+    struct timespec now;
+    clock_gettime(CLOCK_MONOTONIC, &now);
+    return (now.tv_sec - start->tv_sec) * 1000 + (now.tv_nsec - start->tv_nsec) / 1000000;
+}
+
+static void on_alarm(int signum)
+{
+    (void)signum;
+}
+
+int main()
+{
+    int fds[2];
+    assert(pipe(fds) == 0);
+    int nfds = fds[1] + 1;
+    fd_set rfds, wfds;
+
+    // Nothing to read: the select times out, and the remaining time is 0.
+    struct timespec start;
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    FD_ZERO(&rfds);
+    FD_SET(fds[0], &rfds);
+    struct timeval tv = {.tv_usec = 100000};
+    assert(select(nfds, &rfds, NULL, NULL, &tv) == 0);
+    assert(elapsed_ms(&start) >= 100);
+    assert(!FD_ISSET(fds[0], &rfds));
+    assert(tv.tv_sec == 0 && tv.tv_usec == 0);
+    // With no sets, it only sleeps.
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    tv.tv_usec = 50000;
+    assert(select(0, NULL, NULL, NULL, &tv) == 0);
+    assert(elapsed_ms(&start) >= 50);
+    tv.tv_usec = -1;
+    assert(select(0, NULL, NULL, NULL, &tv) == -1 && errno == EINVAL);
+    puts("select timeout OK");
+
+    // Only the ready fds below nfds are left in the sets.
+    FD_ZERO(&rfds);
+    FD_ZERO(&wfds);
+    FD_SET(fds[0], &rfds);
+    FD_SET(fds[0], &wfds);
+    FD_SET(fds[1], &wfds);
+    assert(select(nfds, &rfds, &wfds, NULL, NULL) == 1);
+    assert(!FD_ISSET(fds[0], &rfds) && !FD_ISSET(fds[0], &wfds) && FD_ISSET(fds[1], &wfds));
+    FD_SET(fds[0], &rfds);
+    assert(write(fds[1], "x", 1) == 1);
+    tv.tv_sec = 5;
+    tv.tv_usec = 0;
+    assert(select(fds[0] + 1, &rfds, NULL, NULL, &tv) == 1 && FD_ISSET(fds[0], &rfds));
+    // The time left is updated.
+    assert(tv.tv_sec == 4 || tv.tv_sec == 5);
+    puts("select ready OK");
+
+    // A signal interrupts the wait.
+    char c;
+    assert(read(fds[0], &c, 1) == 1);
+    struct sigaction sa;
+    memset(&sa, 0, sizeof(sa));
+    sa.sa_handler = on_alarm;
+    assert(sigaction(SIGALRM, &sa, NULL) == 0);
+    struct itimerval timer = {.it_value = {.tv_usec = 50000}};
+    assert(setitimer(ITIMER_REAL, &timer, NULL) == 0);
+    tv.tv_sec = 5;
+    tv.tv_usec = 0;
+    assert(select(nfds, &rfds, NULL, NULL, &tv) == -1 && errno == EINTR);
+    close(fds[0]);
+    close(fds[1]);
+    puts("select EINTR OK");
+
+    puts("(C)Select tests run OK!");
+    return 0;
+}
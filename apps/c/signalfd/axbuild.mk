@@ -0,0 +1 @@
+app-objs := signalfd.o
@@ -0,0 +1,51 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <sys/epoll.h>
+#include <sys/signalfd.h>
+#include <unistd.h>
+
+int main()
+{
+    sigset_t mask, old;
+    sigemptyset(&mask);
+    sigaddset(&mask, SIGUSR1);
+    assert(sigprocmask(SIG_BLOCK, &mask, NULL) == 0);
+    assert(sigprocmask(SIG_BLOCK, NULL, &old) == 0);
+    assert(sigismember(&old, SIGUSR1));
+    puts("sigprocmask test OK!");
+
+    int sfd = signalfd(-1, &mask, SFD_NONBLOCK | SFD_CLOEXEC);
+    assert(sfd >= 0);
+    int epfd = epoll_create(1);
+    struct epoll_event ev = {.events = EPOLLIN, .data.fd = sfd};
+    assert(epoll_ctl(epfd, EPOLL_CTL_ADD, sfd, &ev) == 0);
+
+    struct signalfd_siginfo info;
+    assert(read(sfd, &info, sizeof(info)) == -1 && errno == EAGAIN);
+    struct epoll_event events[1];
+    assert(epoll_wait(epfd, events, 1, 0) == 0);
+
+    // SIGUSR1 is blocked, so it stays pending until it is read from the fd.
+    assert(raise(SIGUSR1) == 0);
+    assert(epoll_wait(epfd, events, 1, 0) == 1 && events[0].data.fd == sfd);
+    assert(read(sfd, &info, sizeof(info)) == sizeof(info));
+    assert(info.ssi_signo == SIGUSR1);
+    assert(read(sfd, &info, sizeof(info)) == -1 && errno == EAGAIN);
+    puts("signalfd read test OK!");
+
+    close(epfd);
+    close(sfd);
+    puts("(C)Signalfd tests run OK!");
+    return 0;
+}
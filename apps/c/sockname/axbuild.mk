@@ -0,0 +1 @@
+app-objs := sockname.o
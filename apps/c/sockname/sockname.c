@@ -0,0 +1,242 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+#include <arpa/inet.h>
+#include <netdb.h>
+#include <netinet/in.h>
+#include <sys/socket.h>
+#include <sys/uio.h>
+
+#define PEER_IP   "10.0.2.2"
+#define PEER_PORT 5555
+#define BIND_PORT 5556
+
+#define CHECK(cond, msg)                           \
+    do {                                           \
+        if (!(cond)) {                             \
+            printf("%s failed: %s\n", msg, #cond); \
+            return -1;                             \
+        }                                          \
+    } while (0)
+
+static int get_name(int fd, int peer, struct sockaddr_in *addr, socklen_t *len)
+{
+    memset(addr, 0xff, sizeof(*addr));
+    *len = sizeof(*addr);
+    if (peer)
+        return getpeername(fd, (struct sockaddr *)addr, len);
+    return getsockname(fd, (struct sockaddr *)addr, len);
+}
+
+static int test_udp()
+{
+    struct sockaddr_in addr;
+    socklen_t len;
+    int fd = socket(AF_INET, SOCK_DGRAM, 0);
+    CHECK(fd >= 0, "socket");
+
+    // An unbound socket reports the wildcard address, and has no peer.
+    CHECK(get_name(fd, 0, &addr, &len) == 0, "getsockname");
+    CHECK(len == sizeof(addr) && addr.sin_family == AF_INET, "getsockname");
+    CHECK(addr.sin_addr.s_addr == htonl(INADDR_ANY) && addr.sin_port == 0, "getsockname");
+    CHECK(get_name(fd, 1, &addr, &len) == -1 && errno == ENOTCONN, "getpeername");
+    puts("UDP: unbound OK");
+
+    struct sockaddr_in peer;
+    memset(&peer, 0, sizeof(peer));
+    peer.sin_family = AF_INET;
+    peer.sin_addr.s_addr = inet_addr(PEER_IP);
+    peer.sin_port = htons(PEER_PORT);
+    CHECK(connect(fd, (struct sockaddr *)&peer, sizeof(peer)) == 0, "connect");
+    CHECK(get_name(fd, 1, &addr, &len) == 0, "getpeername");
+    CHECK(len == sizeof(addr) && addr.sin_family == AF_INET, "getpeername");
+    CHECK(addr.sin_addr.s_addr == peer.sin_addr.s_addr, "getpeername");
+    CHECK(addr.sin_port == peer.sin_port, "getpeername");
+    CHECK(get_name(fd, 0, &addr, &len) == 0 && addr.sin_port != 0, "getsockname");
+    puts("UDP: connected OK");
+
+    // A small buffer gets the beginning of the address, and the full length.
+    unsigned short port = addr.sin_port;
+    memset(&addr, 0xff, sizeof(addr));
+    len = 4;
+    CHECK(getsockname(fd, (struct sockaddr *)&addr, &len) == 0, "getsockname");
+    CHECK(len == sizeof(addr), "getsockname");
+    CHECK(addr.sin_family == AF_INET && addr.sin_port == port, "getsockname");
+    CHECK(addr.sin_addr.s_addr == 0xffffffff, "getsockname");
+    puts("UDP: truncated OK");
+
+    close(fd);
+    return 0;
+}
+
+static int test_tcp()
+{
+    struct sockaddr_in addr;
+    socklen_t len;
+    int fd = socket(AF_INET, SOCK_STREAM, 0);
+    CHECK(fd >= 0, "socket");
+
+    CHECK(get_name(fd, 0, &addr, &len) == 0, "getsockname");
+    CHECK(addr.sin_addr.s_addr == htonl(INADDR_ANY) && addr.sin_port == 0, "getsockname");
+    CHECK(get_name(fd, 1, &addr, &len) == -1 && errno == ENOTCONN, "getpeername");
+
+    struct sockaddr_in local;
+    memset(&local, 0, sizeof(local));
+    local.sin_family = AF_INET;
+    local.sin_addr.s_addr = htonl(INADDR_ANY);
+    local.sin_port = htons(BIND_PORT);
+    CHECK(bind(fd, (struct sockaddr *)&local, sizeof(local)) == 0, "bind");
+    CHECK(get_name(fd, 0, &addr, &len) == 0 && addr.sin_port == htons(BIND_PORT), "getsockname");
+    puts("TCP: bound OK");
+
+    // A listening socket has no peer either.
+    CHECK(listen(fd, 1) == 0, "listen");
+    CHECK(get_name(fd, 0, &addr, &len) == 0 && addr.sin_port == htons(BIND_PORT), "getsockname");
+    CHECK(get_name(fd, 1, &addr, &len) == -1 && errno == ENOTCONN, "getpeername");
+    puts("TCP: listening OK");
+
+    close(fd);
+    return 0;
+}
+
+static int test_msg()
+{
+    int fd = socket(AF_INET, SOCK_DGRAM, 0);
+    CHECK(fd >= 0, "socket");
+
+    struct sockaddr_in peer;
+    memset(&peer, 0, sizeof(peer));
+    peer.sin_family = AF_INET;
+    peer.sin_addr.s_addr = inet_addr(PEER_IP);
+    peer.sin_port = htons(PEER_PORT);
+    CHECK(connect(fd, (struct sockaddr *)&peer, sizeof(peer)) == 0, "connect");
+
+    // The buffers are gathered into a single datagram.
+    char hello[] = "hello, ", world[] = "world";
+    struct iovec iov[2] = {{hello, strlen(hello)}, {world, strlen(world)}};
+    struct msghdr msg;
+    memset(&msg, 0, sizeof(msg));
+    msg.msg_iov = iov;
+    msg.msg_iovlen = 2;
+    CHECK(sendmsg(fd, &msg, 0) == strlen(hello) + strlen(world), "sendmsg");
+
+    // Fds can only be passed over AF_UNIX sockets.
+    union {
+        char buf[CMSG_SPACE(sizeof(int))];
+        struct cmsghdr align;
+    } control;
+    msg.msg_control = control.buf;
+    msg.msg_controllen = sizeof(control.buf);
+    struct cmsghdr *cmsg = CMSG_FIRSTHDR(&msg);
+    cmsg->cmsg_level = SOL_SOCKET;
+    cmsg->cmsg_type = SCM_RIGHTS;
+    cmsg->cmsg_len = CMSG_LEN(sizeof(int));
+    memcpy(CMSG_DATA(cmsg), &fd, sizeof(int));
+    CHECK(sendmsg(fd, &msg, 0) == -1 && errno == EINVAL, "sendmsg");
+    puts("UDP: sendmsg OK");
+
+    // The peer does not answer.
+    char buf[16];
+    struct iovec riov = {buf, sizeof(buf)};
+    memset(&msg, 0, sizeof(msg));
+    msg.msg_iov = &riov;
+    msg.msg_iovlen = 1;
+    CHECK(fcntl(fd, F_SETFL, O_NONBLOCK) == 0, "fcntl");
+    CHECK(recvmsg(fd, &msg, 0) == -1 && errno == EAGAIN, "recvmsg");
+    puts("UDP: recvmsg OK");
+
+    close(fd);
+    return 0;
+}
+
+static int test_broadcast()
+{
+    int fd = socket(AF_INET, SOCK_DGRAM, 0);
+    CHECK(fd >= 0, "socket");
+
+    struct sockaddr_in dst;
+    memset(&dst, 0, sizeof(dst));
+    dst.sin_family = AF_INET;
+    dst.sin_addr.s_addr = htonl(INADDR_BROADCAST);
+    dst.sin_port = htons(PEER_PORT);
+    char msg[] = "hello";
+    int val;
+    socklen_t len = sizeof(val);
+    CHECK(getsockopt(fd, SOL_SOCKET, SO_BROADCAST, &val, &len) == 0 && val == 0, "getsockopt");
+    CHECK(sendto(fd, msg, strlen(msg), 0, (struct sockaddr *)&dst, sizeof(dst)) == -1 &&
+              errno == EACCES,
+          "sendto");
+
+    val = 1;
+    CHECK(setsockopt(fd, SOL_SOCKET, SO_BROADCAST, &val, sizeof(val)) == 0, "setsockopt");
+    CHECK(getsockopt(fd, SOL_SOCKET, SO_BROADCAST, &val, &len) == 0 && val == 1, "getsockopt");
+    CHECK(sendto(fd, msg, strlen(msg), 0, (struct sockaddr *)&dst, sizeof(dst)) == strlen(msg),
+          "sendto");
+    puts("UDP: broadcast OK");
+
+    close(fd);
+    return 0;
+}
+
+static int test_resolve()
+{
+    // Numeric addresses are not queried, and there is a result for TCP and UDP.
+    struct addrinfo *res;
+    CHECK(getaddrinfo(PEER_IP, "5555", NULL, &res) == 0, "getaddrinfo");
+    CHECK(res->ai_family == AF_INET && res->ai_socktype == SOCK_STREAM, "getaddrinfo");
+    CHECK(res->ai_next && res->ai_next->ai_socktype == SOCK_DGRAM, "getaddrinfo");
+    CHECK(res->ai_next->ai_protocol == IPPROTO_UDP && res->ai_next->ai_next == NULL, "getaddrinfo");
+    struct sockaddr_in *addr = (struct sockaddr_in *)res->ai_addr;
+    CHECK(addr->sin_addr.s_addr == inet_addr(PEER_IP), "getaddrinfo");
+    CHECK(addr->sin_port == htons(PEER_PORT), "getaddrinfo");
+    freeaddrinfo(res);
+
+    // Service names are mapped to their port, for the socket type hinted.
+    struct addrinfo hints;
+    memset(&hints, 0, sizeof(hints));
+    hints.ai_socktype = SOCK_STREAM;
+    CHECK(getaddrinfo(PEER_IP, "http", &hints, &res) == 0, "getaddrinfo");
+    CHECK(res->ai_protocol == IPPROTO_TCP && res->ai_next == NULL, "getaddrinfo");
+    addr = (struct sockaddr_in *)res->ai_addr;
+    CHECK(addr->sin_port == htons(80), "getaddrinfo");
+    freeaddrinfo(res);
+    hints.ai_socktype = SOCK_DGRAM;
+    CHECK(getaddrinfo(PEER_IP, "domain", &hints, &res) == 0, "getaddrinfo");
+    CHECK(res->ai_protocol == IPPROTO_UDP && res->ai_next == NULL, "getaddrinfo");
+    addr = (struct sockaddr_in *)res->ai_addr;
+    CHECK(addr->sin_port == htons(53), "getaddrinfo");
+    freeaddrinfo(res);
+
+    // Errors are reported as EAI_* codes, not in errno.
+    errno = 0;
+    CHECK(getaddrinfo(NULL, NULL, NULL, &res) == EAI_NONAME && errno == 0, "getaddrinfo");
+    CHECK(getaddrinfo(PEER_IP, "http", &hints, &res) == EAI_SERVICE, "getaddrinfo");
+    CHECK(getaddrinfo(PEER_IP, "no-such-service", NULL, &res) == EAI_SERVICE, "getaddrinfo");
+    hints.ai_protocol = IPPROTO_TCP;
+    CHECK(getaddrinfo(PEER_IP, "5555", &hints, &res) == EAI_SOCKTYPE, "getaddrinfo");
+    hints.ai_family = AF_INET6;
+    CHECK(getaddrinfo(PEER_IP, "5555", &hints, &res) == EAI_FAMILY, "getaddrinfo");
+    puts("getaddrinfo OK");
+    return 0;
+}
+
+int main()
+{
+    puts("Hello, Ruxos C getsockname/getpeername test!");
+    if (test_udp() != 0 || test_tcp() != 0 || test_msg() != 0 ||
+        test_broadcast() != 0 || test_resolve() != 0)
+        return -1;
+    puts("Sockname test OK!");
+    return 0;
+}
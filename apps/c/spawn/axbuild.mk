@@ -0,0 +1,15 @@
+app-objs=main.o
+
+ENVS = 
+V9P_PATH=${APP}/rootfs
+
+# the spawned program is a Linux one, linked with the musl of the toolchain
+spawn_prog := $(APP)/rootfs/bin/exit42
+
+$(APP)/main.o: $(spawn_prog)
+
+$(spawn_prog): $(APP)/rootfs/exit42.c
+	@mkdir -p $(dir $@)
+	$(call run_cmd,$(CC),-static -o $@ $<)
+
+# make run ARCH=x86_64 A=apps/c/spawn V9P=y MUSL=y LOG=info
@@ -0,0 +1,41 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <spawn.h>
+#include <stdio.h>
+#include <sys/wait.h>
+
+extern char **environ;
+
+int main()
+{
+    pid_t pid;
+    int status = -1;
+    char *argv[] = {"/bin/exit42", NULL};
+
+    // The child runs the program in a new task, and is reaped like a process.
+    assert(posix_spawn(&pid, argv[0], NULL, NULL, argv, environ) == 0);
+    assert(waitpid(pid, &status, 0) == pid);
+    assert(WIFEXITED(status) && WEXITSTATUS(status) == 42);
+    assert(waitpid(-1, &status, WNOHANG) == -1 && errno == ECHILD);
+    puts("spawn exit status OK");
+
+    // The child of a missing program fails to exec, like a shell does.
+    char *none[] = {"/bin/none", NULL};
+    if (posix_spawn(&pid, none[0], NULL, NULL, none, environ) == 0) {
+        assert(waitpid(pid, &status, 0) == pid);
+        assert(WIFEXITED(status) && WEXITSTATUS(status) == 127);
+    }
+    puts("spawn missing program OK");
+
+    puts("(C)Spawn tests run OK!");
+    return 0;
+}
@@ -0,0 +1 @@
+app-objs := tcpbacklog.o
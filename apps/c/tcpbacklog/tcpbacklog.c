@@ -0,0 +1,87 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+#include <arpa/inet.h>
+#include <netinet/in.h>
+#include <sys/socket.h>
+
+#define BACKLOG 3
+
+int main()
+{
+    puts("Hello, Ruxos C TCP backlog test!");
+    struct sockaddr_in local, remote;
+    socklen_t addr_len = sizeof(remote);
+    local.sin_family = AF_INET;
+    if (inet_pton(AF_INET, "0.0.0.0", &(local.sin_addr)) != 1) {
+        perror("inet_pton() error");
+        return -1;
+    }
+    local.sin_port = htons(5555);
+    int sock = socket(AF_INET, SOCK_STREAM, IPPROTO_TCP);
+    if (sock == -1) {
+        perror("socket() error");
+        return -1;
+    }
+    if (bind(sock, (struct sockaddr *)&local, sizeof(local)) != 0) {
+        perror("bind() error");
+        return -1;
+    }
+    if (listen(sock, BACKLOG) != 0) {
+        perror("listen() error");
+        return -1;
+    }
+    printf("listen on: 0.0.0.0:5555, backlog: %d\n", BACKLOG);
+
+    // Wait for the first client, then give the others time to queue up
+    // before any of them is accepted.
+    int conns[BACKLOG];
+    conns[0] = accept(sock, (struct sockaddr *)&remote, &addr_len);
+    if (conns[0] == -1) {
+        perror("accept() error");
+        return -1;
+    }
+    sleep(2);
+    for (int i = 1; i < BACKLOG; i++) {
+        conns[i] = accept(sock, (struct sockaddr *)&remote, &addr_len);
+        if (conns[i] == -1) {
+            perror("accept() error");
+            return -1;
+        }
+    }
+
+    // Connections are accepted in arrival order.
+    char buf[64];
+    for (int i = 0; i < BACKLOG; i++) {
+        ssize_t l = recv(conns[i], buf, sizeof(buf) - 1, 0);
+        if (l == -1) {
+            perror("recv() error");
+            return -1;
+        }
+        buf[l] = '\0';
+        printf("accepted %d: %s", i, buf);
+        close(conns[i]);
+    }
+
+    // The queue is empty now, a non-blocking accept must not wait.
+    fcntl(sock, F_SETFL, fcntl(sock, F_GETFL) | O_NONBLOCK);
+    if (accept(sock, (struct sockaddr *)&remote, &addr_len) != -1 || errno != EAGAIN) {
+        puts("non-blocking accept should fail with EAGAIN");
+        return -1;
+    }
+    close(sock);
+    puts("TCP backlog test OK!");
+    return 0;
+}
@@ -0,0 +1,5 @@
+for i in 1 2 3; do
+    echo "client $i" | nc -q 3 localhost 5555 &
+    sleep 0.5
+done
+wait
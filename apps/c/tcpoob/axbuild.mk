@@ -0,0 +1 @@
+app-objs := tcpoob.o
@@ -0,0 +1,98 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+#include <arpa/inet.h>
+#include <netinet/in.h>
+#include <sys/socket.h>
+
+static int expect_recv(int sock, int flags, const char *expected)
+{
+    char buf[16] = {0};
+    ssize_t n = recv(sock, buf, sizeof(buf) - 1, flags);
+    if (n == -1) {
+        perror("recv() error");
+        return -1;
+    }
+    printf("recv%s: \"%s\"\n", flags & MSG_OOB ? "(MSG_OOB)" : "", buf);
+    if (strcmp(buf, expected) != 0) {
+        printf("expected \"%s\"\n", expected);
+        return -1;
+    }
+    return 0;
+}
+
+static int expect_mark(int sock, int expected)
+{
+    int at_mark = sockatmark(sock);
+    printf("sockatmark: %d\n", at_mark);
+    if (at_mark != expected) {
+        printf("expected %d\n", expected);
+        return -1;
+    }
+    return 0;
+}
+
+int main()
+{
+    puts("Hello, Ruxos C TCP urgent data test!");
+    struct sockaddr_in remote;
+    remote.sin_family = AF_INET;
+    // The host side of the QEMU user network.
+    if (inet_pton(AF_INET, "10.0.2.2", &(remote.sin_addr)) != 1) {
+        perror("inet_pton() error");
+        return -1;
+    }
+    remote.sin_port = htons(5555);
+
+    int sock = socket(AF_INET, SOCK_STREAM, IPPROTO_TCP);
+    if (sock == -1) {
+        perror("socket() error");
+        return -1;
+    }
+    if (connect(sock, (struct sockaddr *)&remote, sizeof(remote)) != 0) {
+        perror("connect() error");
+        return -1;
+    }
+
+    // The host sends "ab", "!" as urgent data, then "cd".
+    sleep(2);
+    if (expect_mark(sock, 0) != 0)
+        return -1;
+    // A normal read stops at the mark.
+    if (expect_recv(sock, 0, "ab") != 0)
+        return -1;
+    if (expect_mark(sock, 1) != 0)
+        return -1;
+    if (expect_recv(sock, MSG_OOB, "!") != 0)
+        return -1;
+    // The urgent byte is out of the stream, and can only be read once.
+    if (expect_recv(sock, 0, "cd") != 0)
+        return -1;
+    if (expect_mark(sock, 0) != 0)
+        return -1;
+    char c;
+    if (recv(sock, &c, 1, MSG_OOB) != -1) {
+        puts("recv(MSG_OOB) should fail without urgent data");
+        return -1;
+    }
+
+    // Send "xy" and "z" as urgent data to the host.
+    if (send(sock, "xy", 2, 0) != 2 || send(sock, "z", 1, MSG_OOB) != 1) {
+        perror("send() error");
+        return -1;
+    }
+    sleep(2);
+    close(sock);
+    puts("TCP urgent data test OK!");
+    return 0;
+}
@@ -0,0 +1,21 @@
+python3 - <<'PY'
+import socket
+import time
+
+server = socket.socket(socket.AF_INET, socket.SOCK_STREAM)
+server.setsockopt(socket.SOL_SOCKET, socket.SO_REUSEADDR, 1)
+server.bind(("0.0.0.0", 5555))
+server.listen(1)
+conn, _ = server.accept()
+
+conn.sendall(b"ab")
+time.sleep(0.2)
+conn.send(b"!", socket.MSG_OOB)
+time.sleep(0.2)
+conn.sendall(b"cd")
+
+time.sleep(3)
+print("urgent:", conn.recv(1, socket.MSG_OOB))
+print("normal:", conn.recv(16))
+conn.close()
+PY
@@ -0,0 +1 @@
+app-objs := tcpreset.o
@@ -0,0 +1,119 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <errno.h>
+#include <fcntl.h>
+#include <netdb.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+#include <arpa/inet.h>
+#include <netinet/in.h>
+#include <sys/socket.h>
+
+#define ROUNDS 3
+
+// No host answers for this address, so the handshake never completes.
+#define SILENT_IP "10.0.2.99"
+
+static const char request[] = "HEAD / HTTP/1.0\r\nHost: ident.me\r\n\r\n";
+
+// Resets the connection of `sock`, so that it can connect again.
+static int reset(int sock)
+{
+    struct sockaddr unspec = {.sa_family = AF_UNSPEC};
+    if (connect(sock, &unspec, sizeof(unspec)) != 0) {
+        perror("connect(AF_UNSPEC) error");
+        return -1;
+    }
+    if (send(sock, request, strlen(request), 0) != -1 || errno != ENOTCONN) {
+        puts("send() should fail with ENOTCONN after reset");
+        return -1;
+    }
+    return 0;
+}
+
+// Sends a request and checks the start of the response. The rest of the
+// response is left unread, and must not show up after a reset.
+static int request_head(int sock)
+{
+    if (send(sock, request, strlen(request), 0) != (ssize_t)strlen(request)) {
+        perror("send() error");
+        return -1;
+    }
+    char buf[5];
+    size_t got = 0;
+    while (got < sizeof(buf)) {
+        ssize_t n = recv(sock, buf + got, sizeof(buf) - got, 0);
+        if (n <= 0) {
+            perror("recv() error");
+            return -1;
+        }
+        got += n;
+    }
+    if (memcmp(buf, "HTTP/", sizeof(buf)) != 0) {
+        puts("the response should start with HTTP/");
+        return -1;
+    }
+    return 0;
+}
+
+int main()
+{
+    puts("Hello, Ruxos C TCP reset test!");
+    struct addrinfo *res;
+    if (getaddrinfo("ident.me", NULL, NULL, &res) != 0) {
+        perror("getaddrinfo() error");
+        return -1;
+    }
+    ((struct sockaddr_in *)(res->ai_addr))->sin_port = htons(80);
+
+    // The same socket is connected, reset and connected again.
+    int sock = socket(AF_INET, SOCK_STREAM, IPPROTO_TCP);
+    if (sock == -1) {
+        perror("socket() error");
+        return -1;
+    }
+    for (int i = 0; i < ROUNDS; i++) {
+        if (connect(sock, res->ai_addr, sizeof(*(res->ai_addr))) != 0) {
+            perror("connect() error");
+            return -1;
+        }
+        if (request_head(sock) != 0 || reset(sock) != 0)
+            return -1;
+        printf("round %d: connected and reset\n", i);
+    }
+
+    // A connection still in progress can be reset too.
+    struct sockaddr_in silent;
+    memset(&silent, 0, sizeof(silent));
+    silent.sin_family = AF_INET;
+    inet_pton(AF_INET, SILENT_IP, &silent.sin_addr);
+    silent.sin_port = htons(80);
+    fcntl(sock, F_SETFL, O_NONBLOCK);
+    if (connect(sock, (struct sockaddr *)&silent, sizeof(silent)) != -1 || errno != EINPROGRESS) {
+        puts("connect() should fail with EINPROGRESS");
+        return -1;
+    }
+    if (reset(sock) != 0)
+        return -1;
+    fcntl(sock, F_SETFL, 0);
+    if (connect(sock, res->ai_addr, sizeof(*(res->ai_addr))) != 0) {
+        perror("connect() error");
+        return -1;
+    }
+    if (request_head(sock) != 0)
+        return -1;
+    puts("pending connect reset");
+
+    freeaddrinfo(res);
+    close(sock);
+    puts("TCP reset test OK!");
+    return 0;
+}
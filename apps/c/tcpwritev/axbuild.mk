@@ -0,0 +1 @@
+app-objs := tcpwritev.o
@@ -0,0 +1,166 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <errno.h>
+#include <fcntl.h>
+#include <poll.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+#include <arpa/inet.h>
+#include <netinet/in.h>
+#include <sys/socket.h>
+#include <sys/uio.h>
+
+#define LARGE_BODY_LEN 200000
+
+static const char header[] = "HTTP/1.0 200 OK\r\nContent-Length: 5\r\n\r\n";
+static const char body[] = "hello";
+
+static int connect_host()
+{
+    struct sockaddr_in remote;
+    remote.sin_family = AF_INET;
+    // The host side of the QEMU user network.
+    if (inet_pton(AF_INET, "10.0.2.2", &(remote.sin_addr)) != 1) {
+        perror("inet_pton() error");
+        return -1;
+    }
+    remote.sin_port = htons(5555);
+
+    int sock = socket(AF_INET, SOCK_STREAM, IPPROTO_TCP);
+    if (sock == -1) {
+        perror("socket() error");
+        return -1;
+    }
+    if (connect(sock, (struct sockaddr *)&remote, sizeof(remote)) != 0) {
+        perror("connect() error");
+        close(sock);
+        return -1;
+    }
+    return sock;
+}
+
+// Reads the reply of the host until it closes the connection.
+static ssize_t recv_all(int sock, char *buf, size_t len)
+{
+    size_t total = 0;
+    while (total < len) {
+        ssize_t n = recv(sock, buf + total, len - total, 0);
+        if (n == -1) {
+            perror("recv() error");
+            return -1;
+        }
+        if (n == 0)
+            break;
+        total += n;
+    }
+    return total;
+}
+
+static int test_small()
+{
+    int sock = connect_host();
+    if (sock == -1)
+        return -1;
+    struct iovec iov[2] = {
+        {.iov_base = (void *)header, .iov_len = strlen(header)},
+        {.iov_base = (void *)body, .iov_len = strlen(body)},
+    };
+    size_t len = strlen(header) + strlen(body);
+    ssize_t n = writev(sock, iov, 2);
+    printf("writev: %d bytes\n", (int)n);
+    if (n != len) {
+        printf("expected %d bytes\n", (int)len);
+        return -1;
+    }
+    shutdown(sock, SHUT_WR);
+
+    // The host echoes what it received, which is the header then the body.
+    char reply[128] = {0};
+    if (recv_all(sock, reply, sizeof(reply) - 1) != len || strncmp(reply, header, strlen(header)) ||
+        strcmp(reply + strlen(header), body)) {
+        printf("unexpected echo: \"%s\"\n", reply);
+        return -1;
+    }
+    close(sock);
+    puts("small response OK");
+    return 0;
+}
+
+static int test_large_nonblocking()
+{
+    int sock = connect_host();
+    if (sock == -1)
+        return -1;
+    fcntl(sock, F_SETFL, fcntl(sock, F_GETFL) | O_NONBLOCK);
+    char *large = malloc(LARGE_BODY_LEN);
+    for (int i = 0; i < LARGE_BODY_LEN; i++) large[i] = '0' + i % 10;
+    struct iovec iov[2] = {
+        {.iov_base = (void *)header, .iov_len = strlen(header)},
+        {.iov_base = large, .iov_len = LARGE_BODY_LEN},
+    };
+    size_t len = strlen(header) + LARGE_BODY_LEN, total = 0;
+
+    // The tx buffer is smaller than the response: it is queued in parts.
+    int partial = 0;
+    while (total < len) {
+        ssize_t n = writev(sock, iov, 2);
+        if (n == -1) {
+            if (errno != EAGAIN) {
+                perror("writev() error");
+                return -1;
+            }
+            struct pollfd pfd = {.fd = sock, .events = POLLOUT};
+            poll(&pfd, 1, -1);
+            continue;
+        }
+        if (total + n < len)
+            partial = 1;
+        total += n;
+        // Skip what was queued.
+        for (int i = 0; i < 2; i++) {
+            size_t skip = n < iov[i].iov_len ? n : iov[i].iov_len;
+            iov[i].iov_base = (char *)iov[i].iov_base + skip;
+            iov[i].iov_len -= skip;
+            n -= skip;
+        }
+    }
+    free(large);
+    if (!partial) {
+        puts("writev() should queue a part of the response");
+        return -1;
+    }
+    shutdown(sock, SHUT_WR);
+
+    fcntl(sock, F_SETFL, fcntl(sock, F_GETFL) & ~O_NONBLOCK);
+    char reply[32] = {0}, expected[32];
+    snprintf(expected, sizeof(expected), "%d ok", (int)len);
+    if (recv_all(sock, reply, sizeof(reply) - 1) == -1 || strcmp(reply, expected)) {
+        printf("unexpected reply: \"%s\", expected \"%s\"\n", reply, expected);
+        return -1;
+    }
+    close(sock);
+    puts("large response OK");
+    return 0;
+}
+
+int main()
+{
+    puts("Hello, Ruxos C TCP writev test!");
+    if (test_small() != 0)
+        return -1;
+    // Give the host server time to accept again.
+    sleep(1);
+    if (test_large_nonblocking() != 0)
+        return -1;
+    puts("TCP writev test OK!");
+    return 0;
+}
@@ -0,0 +1,34 @@
+python3 - <<'PY'
+import socket
+
+server = socket.socket(socket.AF_INET, socket.SOCK_STREAM)
+server.setsockopt(socket.SOL_SOCKET, socket.SO_REUSEADDR, 1)
+server.bind(("0.0.0.0", 5555))
+server.listen(1)
+
+
+def recv_all(conn):
+    data = b""
+    while True:
+        chunk = conn.recv(65536)
+        if not chunk:
+            return data
+        data += chunk
+
+
+# The small response is echoed back.
+conn, _ = server.accept()
+data = recv_all(conn)
+print("small:", data)
+conn.sendall(data)
+conn.close()
+
+# The large one is checked here: the header, then the body of "0123456789"s.
+conn, _ = server.accept()
+data = recv_all(conn)
+header, _, body = data.partition(b"\r\n\r\n")
+ok = all(b == ord("0") + i % 10 for i, b in enumerate(body))
+print("large:", len(data), "bytes,", "ok" if ok else "bad body")
+conn.sendall(b"%d %s" % (len(data), b"ok" if ok else b"bad"))
+conn.close()
+PY
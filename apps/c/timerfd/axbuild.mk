@@ -0,0 +1 @@
+app-objs := timerfd.o
@@ -0,0 +1,143 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <assert.h>
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <sys/epoll.h>
+#include <sys/timerfd.h>
+#include <unistd.h>
+
+#define MS 1000000L
+
+static void test_oneshot()
+{
+    int tfd = timerfd_create(CLOCK_MONOTONIC, 0);
+    assert(tfd >= 0);
+
+    struct itimerspec its = {.it_value = {0, 50 * MS}};
+    assert(timerfd_settime(tfd, 0, &its, NULL) == 0);
+
+    struct itimerspec curr;
+    assert(timerfd_gettime(tfd, &curr) == 0);
+    assert(curr.it_value.tv_sec == 0 && curr.it_value.tv_nsec > 0);
+    assert(curr.it_interval.tv_sec == 0 && curr.it_interval.tv_nsec == 0);
+
+    uint64_t count;
+    assert(read(tfd, &count, sizeof(count)) == sizeof(count));
+    assert(count == 1);
+
+    // A one-shot timer is disarmed after it expires.
+    assert(timerfd_gettime(tfd, &curr) == 0);
+    assert(curr.it_value.tv_sec == 0 && curr.it_value.tv_nsec == 0);
+    close(tfd);
+    puts("timerfd one-shot test OK!");
+}
+
+static void test_interval()
+{
+    int tfd = timerfd_create(CLOCK_MONOTONIC, 0);
+    assert(tfd >= 0);
+
+    struct itimerspec its = {.it_interval = {0, 50 * MS}, .it_value = {0, 50 * MS}};
+    assert(timerfd_settime(tfd, 0, &its, NULL) == 0);
+
+    struct timespec start, end;
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    uint64_t total = 0;
+    while (total < 2) {
+        uint64_t count;
+        assert(read(tfd, &count, sizeof(count)) == sizeof(count));
+        assert(count >= 1);
+        total += count;
+    }
+    clock_gettime(CLOCK_MONOTONIC, &end);
+    long elapsed = (end.tv_sec - start.tv_sec) * 1000 * MS + (end.tv_nsec - start.tv_nsec);
+    assert(elapsed >= 100 * MS);
+    close(tfd);
+    puts("timerfd interval test OK!");
+}
+
+static void test_abstime()
+{
+    int tfd = timerfd_create(CLOCK_REALTIME, TFD_NONBLOCK);
+    assert(tfd >= 0);
+
+    uint64_t count;
+    assert(read(tfd, &count, sizeof(count)) == -1 && errno == EAGAIN);
+
+    struct itimerspec its = {0};
+    clock_gettime(CLOCK_REALTIME, &its.it_value);
+    its.it_value.tv_nsec += 50 * MS;
+    if (its.it_value.tv_nsec >= 1000 * MS) {
+        its.it_value.tv_sec += 1;
+        its.it_value.tv_nsec -= 1000 * MS;
+    }
+    assert(timerfd_settime(tfd, TFD_TIMER_ABSTIME, &its, NULL) == 0);
+    assert(read(tfd, &count, sizeof(count)) == -1 && errno == EAGAIN);
+
+    usleep(100 * 1000);
+    assert(read(tfd, &count, sizeof(count)) == sizeof(count));
+    assert(count == 1);
+    close(tfd);
+    puts("timerfd absolute time test OK!");
+}
+
+static void test_periodic_epoll()
+{
+    int tfd = timerfd_create(CLOCK_MONOTONIC, TFD_NONBLOCK | TFD_CLOEXEC);
+    assert(tfd >= 0);
+    int epfd = epoll_create(1);
+    struct epoll_event ev = {.events = EPOLLIN, .data.fd = tfd};
+    assert(epoll_ctl(epfd, EPOLL_CTL_ADD, tfd, &ev) == 0);
+
+    struct epoll_event events[1];
+    assert(epoll_wait(epfd, events, 1, 0) == 0);
+
+    struct itimerspec its = {.it_interval = {0, 20 * MS}, .it_value = {0, 20 * MS}};
+    assert(timerfd_settime(tfd, 0, &its, NULL) == 0);
+
+    uint64_t total = 0;
+    for (int i = 0; i < 3; i++) {
+        assert(epoll_wait(epfd, events, 1, -1) == 1);
+        assert(events[0].data.fd == tfd);
+        uint64_t count;
+        assert(read(tfd, &count, sizeof(count)) == sizeof(count));
+        assert(count >= 1);
+        total += count;
+    }
+    assert(total >= 3);
+
+    // Expirations keep accumulating until they are read.
+    usleep(100 * 1000);
+    uint64_t count;
+    assert(read(tfd, &count, sizeof(count)) == sizeof(count));
+    assert(count >= 4);
+
+    // Disarm the timer, and the old setting is returned.
+    struct itimerspec old, zero = {0};
+    assert(timerfd_settime(tfd, 0, &zero, &old) == 0);
+    assert(old.it_interval.tv_nsec == 20 * MS);
+    assert(epoll_wait(epfd, events, 1, 50) == 0);
+
+    close(epfd);
+    close(tfd);
+    puts("timerfd periodic epoll test OK!");
+}
+
+int main()
+{
+    test_oneshot();
+    test_interval();
+    test_abstime();
+    test_periodic_epoll();
+    puts("(C)Timerfd tests run OK!");
+    return 0;
+}
@@ -7,6 +7,7 @@
  *   See the Mulan PSL v2 for more details.
  */
 
+#include <errno.h>
 #include <stdio.h>
 #include <string.h>
 #include <arpa/inet.h>
@@ -36,14 +37,25 @@ int main()
         return -1;
     }
     puts("listen on: 0.0.0.0:5555");
-    char buf[1024] = {};
+    char buf[1024] = {}, peek_buf[1024] = {};
+    // Nothing is received yet, and MSG_DONTWAIT must not block.
+    if (recv(sock, buf, 1024, MSG_DONTWAIT) != -1 || errno != EAGAIN) {
+        puts("recv() with MSG_DONTWAIT should fail with EAGAIN");
+        return -1;
+    }
     for (;;) {
+        // A peeked datagram is still received afterwards.
+        ssize_t peeked = recv(sock, peek_buf, 1024, MSG_PEEK);
         ssize_t l =
             recvfrom(sock, buf, 1024, 0, (struct sockaddr *)&remote, (socklen_t *)&addr_len);
-        if (l == -1) {
+        if (peeked == -1 || l == -1) {
             perror("recvfrom() error");
             return -1;
         }
+        if (peeked != l || memcmp(peek_buf, buf, l) != 0) {
+            puts("recv() with MSG_PEEK returned another datagram");
+            return -1;
+        }
         uint8_t *addr = (uint8_t *)&(remote.sin_addr);
         printf("recv: %d Bytes from %d.%d.%d.%d:%d\n", l, addr[0], addr[1], addr[2], addr[3],
                ntohs(remote.sin_port));
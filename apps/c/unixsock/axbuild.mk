@@ -0,0 +1 @@
+app-objs := unixsock.o
@@ -0,0 +1,279 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#include <errno.h>
+#include <fcntl.h>
+#include <stddef.h>
+#include <poll.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+#include <sys/socket.h>
+#include <sys/stat.h>
+#include <sys/uio.h>
+#include <sys/un.h>
+
+#define SOCK_PATH "/tmp/unixsock.sock"
+#define FILE_PATH "/tmp/unixsock.txt"
+
+#define CHECK(cond, msg)                           \
+    do {                                           \
+        if (!(cond)) {                             \
+            printf("%s failed: %s\n", msg, #cond); \
+            return -1;                             \
+        }                                          \
+    } while (0)
+
+// Sends `msg` on `from`, and checks it is received on `to`.
+static int echo(int from, int to, const char *msg)
+{
+    char buf[64];
+    size_t len = strlen(msg);
+    CHECK(write(from, msg, len) == len, "write");
+    memset(buf, 0, sizeof(buf));
+    CHECK(read(to, buf, sizeof(buf)) == len, "read");
+    CHECK(memcmp(buf, msg, len) == 0, "read");
+    return 0;
+}
+
+static int test_socketpair()
+{
+    int sv[2];
+    char buf[16];
+    CHECK(socketpair(AF_UNIX, SOCK_STREAM, 0, sv) == 0, "socketpair");
+    CHECK(echo(sv[0], sv[1], "ping") == 0, "echo");
+    CHECK(echo(sv[1], sv[0], "pong") == 0, "echo");
+    puts("socketpair: echo OK");
+
+    // A message may be read in pieces, and peeked at first.
+    CHECK(send(sv[0], "hello", 5, 0) == 5, "send");
+    CHECK(recv(sv[1], buf, 2, MSG_PEEK) == 2 && memcmp(buf, "he", 2) == 0, "recv");
+    CHECK(recv(sv[1], buf, 2, 0) == 2 && memcmp(buf, "he", 2) == 0, "recv");
+    CHECK(recv(sv[1], buf, sizeof(buf), 0) == 3 && memcmp(buf, "llo", 3) == 0, "recv");
+    CHECK(recv(sv[1], buf, sizeof(buf), MSG_DONTWAIT) == -1 && errno == EAGAIN, "recv");
+
+    struct pollfd pfd = {.fd = sv[1], .events = POLLIN | POLLOUT};
+    CHECK(poll(&pfd, 1, 0) == 1 && pfd.revents == POLLOUT, "poll");
+    CHECK(write(sv[0], "x", 1) == 1, "write");
+    CHECK(poll(&pfd, 1, 0) == 1 && pfd.revents == (POLLIN | POLLOUT), "poll");
+    CHECK(read(sv[1], buf, 1) == 1, "read");
+    puts("socketpair: recv OK");
+
+    // Shutting down the sending side ends the stream of the peer.
+    CHECK(shutdown(sv[0], SHUT_WR) == 0, "shutdown");
+    CHECK(read(sv[1], buf, sizeof(buf)) == 0, "read");
+    CHECK(echo(sv[1], sv[0], "still") == 0, "echo");
+    close(sv[1]);
+    CHECK(read(sv[0], buf, sizeof(buf)) == 0, "read");
+    close(sv[0]);
+
+    CHECK(socketpair(AF_INET, SOCK_STREAM, 0, sv) == -1 && errno == EOPNOTSUPP, "socketpair");
+    puts("socketpair: shutdown OK");
+    return 0;
+}
+
+static int test_bound()
+{
+    struct sockaddr_un addr, name;
+    socklen_t len;
+    memset(&addr, 0, sizeof(addr));
+    addr.sun_family = AF_UNIX;
+    strcpy(addr.sun_path, SOCK_PATH);
+
+    int server = socket(AF_UNIX, SOCK_STREAM, 0);
+    CHECK(server >= 0, "socket");
+    int client = socket(AF_UNIX, SOCK_STREAM, 0);
+    CHECK(client >= 0, "socket");
+
+    // Nothing is bound to the path yet.
+    CHECK(connect(client, (struct sockaddr *)&addr, sizeof(addr)) == -1 && errno == ENOENT,
+          "connect");
+    CHECK(bind(server, (struct sockaddr *)&addr, sizeof(addr)) == 0, "bind");
+    struct stat st;
+    CHECK(stat(SOCK_PATH, &st) == 0 && S_ISSOCK(st.st_mode), "stat");
+    CHECK(connect(client, (struct sockaddr *)&addr, sizeof(addr)) == -1 && errno == ECONNREFUSED,
+          "connect");
+    int other = socket(AF_UNIX, SOCK_STREAM, 0);
+    CHECK(bind(other, (struct sockaddr *)&addr, sizeof(addr)) == -1 && errno == EADDRINUSE,
+          "bind");
+    close(other);
+    puts("bind OK");
+
+    CHECK(listen(server, 4) == 0, "listen");
+    CHECK(connect(client, (struct sockaddr *)&addr, sizeof(addr)) == 0, "connect");
+    len = sizeof(name);
+    int conn = accept(server, (struct sockaddr *)&name, &len);
+    CHECK(conn >= 0, "accept");
+    CHECK(len == sizeof(sa_family_t) && name.sun_family == AF_UNIX, "accept");
+
+    len = sizeof(name);
+    CHECK(getpeername(client, (struct sockaddr *)&name, &len) == 0, "getpeername");
+    CHECK(strcmp(name.sun_path, SOCK_PATH) == 0, "getpeername");
+    len = sizeof(name);
+    CHECK(getsockname(conn, (struct sockaddr *)&name, &len) == 0, "getsockname");
+    CHECK(strcmp(name.sun_path, SOCK_PATH) == 0, "getsockname");
+    puts("accept OK");
+
+    CHECK(echo(client, conn, "request") == 0, "echo");
+    CHECK(echo(conn, client, "response") == 0, "echo");
+    close(conn);
+    char buf[8];
+    CHECK(read(client, buf, sizeof(buf)) == 0, "read");
+    CHECK(send(client, "x", 1, MSG_NOSIGNAL) == -1 && errno == EPIPE, "send");
+    close(client);
+    close(server);
+    CHECK(unlink(SOCK_PATH) == 0, "unlink");
+    puts("echo OK");
+    return 0;
+}
+
+static int test_abstract()
+{
+    // The name takes the whole address, with no terminating null byte.
+    static const char name[] = "\0myservice";
+    struct sockaddr_un addr, peer;
+    socklen_t addrlen = offsetof(struct sockaddr_un, sun_path) + sizeof(name) - 1, len;
+    memset(&addr, 0, sizeof(addr));
+    addr.sun_family = AF_UNIX;
+    memcpy(addr.sun_path, name, sizeof(name) - 1);
+
+    int server = socket(AF_UNIX, SOCK_STREAM, 0);
+    CHECK(server >= 0, "socket");
+    int client = socket(AF_UNIX, SOCK_STREAM, 0);
+    CHECK(client >= 0, "socket");
+    CHECK(connect(client, (struct sockaddr *)&addr, addrlen) == -1 && errno == ECONNREFUSED,
+          "connect");
+    CHECK(bind(server, (struct sockaddr *)&addr, addrlen) == 0, "bind");
+    CHECK(listen(server, 4) == 0, "listen");
+    // No node is created for the name.
+    struct stat st;
+    CHECK(stat("myservice", &st) == -1 && errno == ENOENT, "stat");
+
+    CHECK(connect(client, (struct sockaddr *)&addr, addrlen) == 0, "connect");
+    int conn = accept(server, NULL, NULL);
+    CHECK(conn >= 0, "accept");
+    len = sizeof(peer);
+    CHECK(getpeername(client, (struct sockaddr *)&peer, &len) == 0, "getpeername");
+    CHECK(len == addrlen && memcmp(peer.sun_path, name, sizeof(name) - 1) == 0, "getpeername");
+    puts("abstract: connect OK");
+
+    CHECK(echo(client, conn, "hello service") == 0, "echo");
+    CHECK(echo(conn, client, "hello client") == 0, "echo");
+    close(conn);
+    close(client);
+
+    // The name is released when the listening socket is closed.
+    close(server);
+    client = socket(AF_UNIX, SOCK_STREAM, 0);
+    CHECK(connect(client, (struct sockaddr *)&addr, addrlen) == -1 && errno == ECONNREFUSED,
+          "connect");
+    close(client);
+    puts("abstract: echo OK");
+    return 0;
+}
+
+static int test_accept4()
+{
+    static const char name[] = "\0accept4";
+    struct sockaddr_un addr;
+    socklen_t addrlen = offsetof(struct sockaddr_un, sun_path) + sizeof(name) - 1;
+    memset(&addr, 0, sizeof(addr));
+    addr.sun_family = AF_UNIX;
+    memcpy(addr.sun_path, name, sizeof(name) - 1);
+
+    int server = socket(AF_UNIX, SOCK_STREAM, 0);
+    CHECK(server >= 0, "socket");
+    CHECK(bind(server, (struct sockaddr *)&addr, addrlen) == 0, "bind");
+    CHECK(listen(server, 4) == 0, "listen");
+    int client = socket(AF_UNIX, SOCK_STREAM, 0);
+    CHECK(client >= 0, "socket");
+    CHECK(connect(client, (struct sockaddr *)&addr, addrlen) == 0, "connect");
+
+    CHECK(accept4(server, NULL, NULL, ~(SOCK_NONBLOCK | SOCK_CLOEXEC)) == -1 && errno == EINVAL,
+          "accept4");
+    int conn = accept4(server, NULL, NULL, SOCK_NONBLOCK | SOCK_CLOEXEC);
+    CHECK(conn >= 0, "accept4");
+    // The accepted socket is nonblocking, although the listening one is not.
+    char buf[16];
+    CHECK(read(conn, buf, sizeof(buf)) == -1 && errno == EAGAIN, "read");
+    CHECK(echo(client, conn, "hello accept4") == 0, "echo");
+
+    close(conn);
+    close(client);
+    close(server);
+    puts("accept4 OK");
+    return 0;
+}
+
+static int test_scm_rights()
+{
+    int sv[2];
+    char buf[16], byte = 'x';
+    CHECK(socketpair(AF_UNIX, SOCK_STREAM, 0, sv) == 0, "socketpair");
+    int fd = open(FILE_PATH, O_CREAT | O_RDWR | O_TRUNC, 0644);
+    CHECK(fd >= 0, "open");
+    CHECK(write(fd, "passed", 6) == 6, "write");
+
+    union {
+        char buf[CMSG_SPACE(sizeof(int))];
+        struct cmsghdr align;
+    } control;
+    struct iovec iov = {&byte, 1};
+    struct msghdr msg;
+    memset(&msg, 0, sizeof(msg));
+    msg.msg_iov = &iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.buf;
+    msg.msg_controllen = sizeof(control.buf);
+    struct cmsghdr *cmsg = CMSG_FIRSTHDR(&msg);
+    cmsg->cmsg_level = SOL_SOCKET;
+    cmsg->cmsg_type = SCM_RIGHTS;
+    cmsg->cmsg_len = CMSG_LEN(sizeof(int));
+    memcpy(CMSG_DATA(cmsg), &fd, sizeof(int));
+    CHECK(write(sv[0], "ab", 2) == 2, "write");
+    CHECK(sendmsg(sv[0], &msg, 0) == 1, "sendmsg");
+    // The file stays open while it is passed.
+    close(fd);
+
+    // The bytes sent before the fd are received on their own.
+    CHECK(read(sv[1], buf, sizeof(buf)) == 2 && memcmp(buf, "ab", 2) == 0, "read");
+    iov.iov_base = buf;
+    iov.iov_len = sizeof(buf);
+    memset(&control, 0, sizeof(control));
+    msg.msg_controllen = sizeof(control.buf);
+    CHECK(recvmsg(sv[1], &msg, 0) == 1 && buf[0] == 'x', "recvmsg");
+    cmsg = CMSG_FIRSTHDR(&msg);
+    CHECK(cmsg != NULL && cmsg->cmsg_level == SOL_SOCKET && cmsg->cmsg_type == SCM_RIGHTS &&
+              cmsg->cmsg_len == CMSG_LEN(sizeof(int)),
+          "recvmsg");
+    int received;
+    memcpy(&received, CMSG_DATA(cmsg), sizeof(int));
+    puts("SCM_RIGHTS: recvmsg OK");
+
+    // The fd received refers to the file sent.
+    CHECK(lseek(received, 0, SEEK_SET) == 0, "lseek");
+    memset(buf, 0, sizeof(buf));
+    CHECK(read(received, buf, sizeof(buf)) == 6 && memcmp(buf, "passed", 6) == 0, "read");
+    close(received);
+    close(sv[0]);
+    close(sv[1]);
+    CHECK(unlink(FILE_PATH) == 0, "unlink");
+    puts("SCM_RIGHTS: read OK");
+    return 0;
+}
+
+int main()
+{
+    puts("Hello, Ruxos C Unix socket test!");
+    if (test_socketpair() != 0 || test_bound() != 0 || test_abstract() != 0 ||
+        test_accept4() != 0 || test_scm_rights() != 0)
+        return -1;
+    puts("Unix socket test OK!");
+    return 0;
+}
@@ -0,0 +1,6 @@
+app-objs := wait.o
+
+# only musl has `clone` to create a child
+ifeq ($(MUSL), y)
+  APP_CFLAGS += -DHAVE_CLONE
+endif
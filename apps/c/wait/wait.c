@@ -0,0 +1,73 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+#define _GNU_SOURCE
+#include <assert.h>
+#include <errno.h>
+#include <sched.h>
+#include <signal.h>
+#include <stdio.h>
+#include <sys/resource.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#ifdef HAVE_CLONE
+static char child_stack[4096] __attribute__((aligned(16)));
+
+static int child_exit(void *code)
+{
+    _exit((int)(long)code);
+}
+#endif
+
+int main()
+{
+    int status = -1;
+    struct rusage usage;
+
+    // Threads are not children: there is nothing to wait for.
+    assert(waitpid(-1, &status, WNOHANG) == -1 && errno == ECHILD);
+    assert(waitpid(-1, &status, 0) == -1 && errno == ECHILD);
+    assert(wait(&status) == -1 && errno == ECHILD);
+    assert(wait4(2, &status, 0, &usage) == -1 && errno == ECHILD);
+    assert(wait3(NULL, WNOHANG, &usage) == -1 && errno == ECHILD);
+    assert(status == -1);
+    puts("wait ECHILD OK");
+
+    assert(waitpid(-1, &status, 0x100) == -1 && errno == EINVAL);
+    puts("wait EINVAL OK");
+
+    // The status is encoded like Linux does.
+    status = 42 << 8;
+    assert(WIFEXITED(status) && WEXITSTATUS(status) == 42);
+    assert(!WIFSIGNALED(status) && !WIFSTOPPED(status));
+    status = 9;
+    assert(WIFSIGNALED(status) && WTERMSIG(status) == 9 && !WIFEXITED(status));
+    puts("wait status OK");
+
+#ifdef HAVE_CLONE
+    // The child of `clone` without `CLONE_THREAD` is reaped with the status
+    // it exits with.
+    char *stack = child_stack + sizeof(child_stack);
+    pid_t pid = clone(child_exit, stack, CLONE_VM | SIGCHLD, (void *)42);
+    assert(pid > 0);
+    status = -1;
+    assert(waitpid(pid, &status, 0) == pid);
+    assert(WIFEXITED(status) && WEXITSTATUS(status) == 42);
+    pid = clone(child_exit, stack, CLONE_VM | SIGCHLD, (void *)7);
+    assert(pid > 0);
+    assert(wait4(-1, &status, 0, &usage) == pid);
+    assert(WIFEXITED(status) && WEXITSTATUS(status) == 7);
+    assert(wait(&status) == -1 && errno == ECHILD);
+    puts("wait child OK");
+#endif
+
+    puts("(C)Wait tests run OK!");
+    return 0;
+}
@@ -97,6 +97,21 @@ pub enum AxError {
     /// It is a temporary error code that usually returns when a non_blocking operation
     /// is not completed, prompting the caller to try again later.
     InProgress,
+    /// The filesystem or the object on it can not be modified.
+    ReadOnlyFilesystem,
+    /// Too many symbolic links were encountered when resolving a path, which
+    /// likely contains a loop.
+    FilesystemLoop,
+    /// A path or a component of it is too long.
+    NameTooLong,
+    /// The I/O control operation is not supported by the object, which is
+    /// typically not a terminal.
+    NotATty,
+    /// The operation did not complete before its deadline.
+    TimedOut,
+    /// The operation crosses the boundary of a filesystem, or of the
+    /// directory a path is resolved beneath.
+    CrossesDevices,
 }
 
 /// A specialized [`Result`] type with [`AxError`] as the error type.
@@ -248,6 +263,12 @@ impl AxError {
             WouldBlock => "Operation would block",
             WriteZero => "Write zero",
             InProgress => "non_blocking operation is not completed",
+            ReadOnlyFilesystem => "Read-only file system",
+            FilesystemLoop => "Too many levels of symbolic links",
+            NameTooLong => "File name too long",
+            NotATty => "Inappropriate ioctl for device",
+            TimedOut => "Connection timed out",
+            CrossesDevices => "Cross-device link",
         }
     }
 
@@ -300,6 +321,12 @@ impl From<AxError> for LinuxError {
             UnexpectedEof | WriteZero => LinuxError::EIO,
             WouldBlock => LinuxError::EAGAIN,
             InProgress => LinuxError::EINPROGRESS,
+            ReadOnlyFilesystem => LinuxError::EROFS,
+            FilesystemLoop => LinuxError::ELOOP,
+            NameTooLong => LinuxError::ENAMETOOLONG,
+            NotATty => LinuxError::ENOTTY,
+            TimedOut => LinuxError::ETIMEDOUT,
+            CrossesDevices => LinuxError::EXDEV,
         }
     }
 }
@@ -322,13 +349,13 @@ mod tests {
     #[test]
     fn test_try_from() {
         let max_code = core::mem::variant_count::<AxError>() as i32;
-        assert_eq!(max_code, 23);
-        assert_eq!(max_code, AxError::InProgress.code());
+        assert_eq!(max_code, 29);
+        assert_eq!(max_code, AxError::CrossesDevices.code());
 
         assert_eq!(AxError::AddrInUse.code(), 1);
         assert_eq!(Ok(AxError::AddrInUse), AxError::try_from(1));
         assert_eq!(Ok(AxError::AlreadyExists), AxError::try_from(2));
-        assert_eq!(Ok(AxError::InProgress), AxError::try_from(max_code));
+        assert_eq!(Ok(AxError::CrossesDevices), AxError::try_from(max_code));
         assert_eq!(Err(max_code + 1), AxError::try_from(max_code + 1));
         assert_eq!(Err(0), AxError::try_from(0));
         assert_eq!(Err(-1), AxError::try_from(-1));
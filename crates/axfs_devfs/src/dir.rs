@@ -9,7 +9,7 @@
 
 use alloc::collections::BTreeMap;
 use alloc::sync::{Arc, Weak};
-use axfs_vfs::{VfsDirEntry, VfsNodeAttr, VfsNodeOps, VfsNodeRef, VfsNodeType};
+use axfs_vfs::{VfsDirEntry, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType};
 use axfs_vfs::{VfsError, VfsResult};
 use spin::RwLock;
 
@@ -53,6 +53,16 @@ impl VfsNodeOps for DirNode {
         Ok(VfsNodeAttr::new_dir(4096, 0))
     }
 
+    fn setattr(
+        &self,
+        _mode: Option<VfsNodePerm>,
+        _uid: Option<u32>,
+        _gid: Option<u32>,
+        _size: Option<u64>,
+    ) -> VfsResult {
+        Err(VfsError::ReadOnlyFilesystem) // the nodes are created statically
+    }
+
     fn parent(&self) -> Option<VfsNodeRef> {
         self.parent.read().upgrade()
     }
@@ -16,6 +16,7 @@
 extern crate alloc;
 
 mod dir;
+mod full;
 mod null;
 mod random;
 mod zero;
@@ -24,6 +25,7 @@ mod zero;
 mod tests;
 
 pub use self::dir::DirNode;
+pub use self::full::FullDev;
 pub use self::null::NullDev;
 pub use self::random::RandomDev;
 pub use self::zero::ZeroDev;
@@ -73,6 +75,10 @@ impl VfsOps for DeviceFileSystem {
     fn root_dir(&self) -> VfsNodeRef {
         self.root.clone()
     }
+
+    fn fs_type(&self) -> &str {
+        "devtmpfs"
+    }
 }
 
 impl Default for DeviceFileSystem {
@@ -7,26 +7,14 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use axfs_vfs::{VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType, VfsResult};
-use core::sync::atomic::{AtomicU64, Ordering::SeqCst};
-
-static SEED: AtomicU64 = AtomicU64::new(0xae_f3);
+use axfs_vfs::{VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType, VfsResult};
 
 /// A random device behaves like `/dev/random`.
 ///
-/// It always returns a chunk of random bytes when read, and all writes are discarded.
+/// It always returns a chunk of random bytes when read, and all writes are
+/// discarded. The bytes come from the kernel CSPRNG of [`chacha_rng`].
 pub struct RandomDev;
 
-/// Returns a 32-bit unsigned pseudo random interger using LCG.
-fn rand_lcg32() -> u32 {
-    let new_seed = SEED
-        .load(SeqCst)
-        .wrapping_mul(6364136223846793005)
-        .wrapping_add(1);
-    SEED.store(new_seed, SeqCst);
-    (new_seed >> 33) as u32
-}
-
 impl VfsNodeOps for RandomDev {
     fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
         Ok(VfsNodeAttr::new(
@@ -37,19 +25,18 @@ impl VfsNodeOps for RandomDev {
         ))
     }
 
-    fn read_at(&self, _offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
-        let len = buf.len() >> 2;
-        let remainder = buf.len() & 0x3;
-        for i in 0..len {
-            let random = rand_lcg32();
-            let start_idx = i * 4;
-            // MSB
-            buf[start_idx..start_idx + 4].copy_from_slice(random.to_be_bytes().as_ref());
-        }
-
-        let random = rand_lcg32();
-        buf[len * 4..].copy_from_slice(random.to_be_bytes()[..remainder].as_ref());
+    fn setattr(
+        &self,
+        _mode: Option<VfsNodePerm>,
+        _uid: Option<u32>,
+        _gid: Option<u32>,
+        _size: Option<u64>,
+    ) -> VfsResult {
+        Err(VfsError::ReadOnlyFilesystem)
+    }
 
+    fn read_at(&self, _offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
+        chacha_rng::fill_bytes(buf);
         Ok(buf.len())
     }
 
@@ -7,9 +7,13 @@
  *   See the Mulan PSL v2 for more details.
  */
 
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
-use axfs_vfs::{VfsError, VfsNodeType, VfsResult};
+use axfs_vfs::{
+    impl_vfs_non_dir_default, PollState, VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm,
+    VfsNodeType, VfsResult,
+};
 
 use crate::*;
 
@@ -36,6 +40,11 @@ fn test_devfs_ops(devfs: &DeviceFileSystem) -> VfsResult {
     assert_eq!(node.read_at(0, &mut buf)?, 0);
     assert_eq!(buf, [1; N]);
     assert_eq!(node.write_at(N as _, &buf)?, N);
+    assert_eq!(
+        node.setattr(Some(VfsNodePerm::default_file()), None, None, None)
+            .err(),
+        Some(VfsError::ReadOnlyFilesystem)
+    );
     assert_eq!(node.lookup("/").err(), Some(VfsError::NotADirectory));
 
     let node = devfs.root_dir().lookup(".///.//././/.////zero")?;
@@ -122,3 +131,93 @@ fn test_devfs() {
     test_devfs_ops(&devfs).unwrap();
     test_get_parent(&devfs).unwrap();
 }
+
+#[test]
+fn test_special_devices() {
+    let mut buf = [1; 100];
+    assert_eq!(NullDev.read_at(0, &mut buf), Ok(0));
+    assert_eq!(NullDev.write_at(0, &buf), Ok(100));
+
+    assert_eq!(ZeroDev.read_at(0, &mut buf), Ok(100));
+    assert_eq!(buf, [0; 100]);
+    assert_eq!(ZeroDev.write_at(0, &buf), Ok(100));
+
+    buf.fill(1);
+    assert_eq!(FullDev.read_at(0, &mut buf), Ok(100));
+    assert_eq!(buf, [0; 100]);
+    assert_eq!(FullDev.write_at(0, &buf), Err(VfsError::StorageFull));
+    assert_eq!(FullDev.write_at(0, &[]), Err(VfsError::StorageFull));
+
+    // the reads never repeat, whatever their size
+    let mut other = [0; 100];
+    assert_eq!(RandomDev.read_at(0, &mut buf), Ok(100));
+    assert_eq!(RandomDev.read_at(0, &mut other), Ok(100));
+    assert_ne!(buf, other);
+    assert_ne!(buf[..64], buf[64..]);
+    assert_eq!(RandomDev.read_at(0, &mut other[..3]), Ok(3));
+    assert_eq!(RandomDev.write_at(0, &buf), Ok(100));
+}
+
+/// A device that can only be read after something is written to it, like the
+/// read end of a FIFO.
+#[derive(Default)]
+struct EventDev {
+    pending: AtomicUsize,
+}
+
+impl VfsNodeOps for EventDev {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        Ok(VfsNodeAttr::new(
+            VfsNodePerm::default_file(),
+            VfsNodeType::CharDevice,
+            0,
+            0,
+        ))
+    }
+
+    fn read_at(&self, _offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
+        let len = self.pending.swap(0, Ordering::AcqRel).min(buf.len());
+        if len == 0 {
+            return Err(VfsError::WouldBlock);
+        }
+        buf[..len].fill(1);
+        Ok(len)
+    }
+
+    fn write_at(&self, _offset: u64, buf: &[u8]) -> VfsResult<usize> {
+        self.pending.fetch_add(buf.len(), Ordering::AcqRel);
+        Ok(buf.len())
+    }
+
+    fn poll(&self) -> VfsResult<PollState> {
+        Ok(PollState {
+            readable: self.pending.load(Ordering::Acquire) > 0,
+            writable: true,
+            ..Default::default()
+        })
+    }
+
+    impl_vfs_non_dir_default! {}
+}
+
+#[test]
+fn test_devfs_poll() {
+    let devfs = DeviceFileSystem::new();
+    devfs.add("null", Arc::new(NullDev));
+    devfs.add("event", Arc::new(EventDev::default()));
+
+    // nodes are always ready by default
+    let state = devfs.root_dir().lookup("null").unwrap().poll().unwrap();
+    assert!(state.readable && state.writable);
+
+    let node = devfs.root_dir().lookup("event").unwrap();
+    let mut buf = [0; 4];
+    let state = node.poll().unwrap();
+    assert!(!state.readable && state.writable);
+    assert_eq!(node.read_at(0, &mut buf).err(), Some(VfsError::WouldBlock));
+
+    assert_eq!(node.write_at(0, &buf[..2]).unwrap(), 2);
+    assert!(node.poll().unwrap().readable);
+    assert_eq!(node.read_at(0, &mut buf).unwrap(), 2);
+    assert!(!node.poll().unwrap().readable);
+}
@@ -7,7 +7,7 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use axfs_vfs::{VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType, VfsResult};
+use axfs_vfs::{VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType, VfsResult};
 
 /// A zero device behaves like `/dev/zero`.
 ///
@@ -24,6 +24,16 @@ impl VfsNodeOps for ZeroDev {
         ))
     }
 
+    fn setattr(
+        &self,
+        _mode: Option<VfsNodePerm>,
+        _uid: Option<u32>,
+        _gid: Option<u32>,
+        _size: Option<u64>,
+    ) -> VfsResult {
+        Err(VfsError::ReadOnlyFilesystem)
+    }
+
     fn read_at(&self, _offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
         buf.fill(0);
         Ok(buf.len())
@@ -0,0 +1,227 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use axfs_vfs::{VfsDirEntry, VfsError, VfsNodeRef, VfsNodeType, VfsResult};
+use spin::{Mutex, Once, RwLock};
+
+/// Size of the chunks a file is copied up by.
+const COPY_CHUNK_SIZE: usize = 4096;
+
+/// The two layers of an overlay, and the paths removed from the lower one.
+///
+/// The paths are relative to the roots of the layers and canonical, the root
+/// itself is the empty path.
+pub(crate) struct Layers {
+    lower: VfsNodeRef,
+    upper: VfsNodeRef,
+    whiteouts: RwLock<BTreeSet<String>>,
+    /// Serializes the copy-ups, so that a node is copied only once.
+    copy_up_lock: Mutex<()>,
+    parent: Once<VfsNodeRef>,
+}
+
+/// Returns the path of the entry `name` in the directory at `dir`.
+pub(crate) fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.into()
+    } else {
+        let mut path = String::from(dir);
+        path.push('/');
+        path.push_str(name);
+        path
+    }
+}
+
+/// Returns the path of the parent directory of `path`, which is not the root.
+pub(crate) fn dirname(path: &str) -> &str {
+    path.rfind('/').map_or("", |n| &path[..n])
+}
+
+/// Returns the name of the last component of `path`.
+pub(crate) fn basename(path: &str) -> &str {
+    path.rfind('/').map_or(path, |n| &path[n + 1..])
+}
+
+/// Returns the paths of the ancestors of `path` from the outermost, and the
+/// path itself, except the root.
+fn prefixes(path: &str) -> impl Iterator<Item = &str> {
+    path.match_indices('/')
+        .map(|(n, _)| &path[..n])
+        .chain(core::iter::once(path))
+        .filter(|prefix| !prefix.is_empty())
+}
+
+/// Reads the names and types of all the entries of the directory `dir`, but
+/// `.` and `..`.
+fn read_entries(dir: &VfsNodeRef) -> VfsResult<Vec<(String, VfsNodeType)>> {
+    let mut entries = Vec::new();
+    let mut buf = [(); 16].map(|_| VfsDirEntry::default());
+    loop {
+        let n = dir.read_dir(entries.len() + 2, &mut buf)?;
+        if n == 0 {
+            return Ok(entries);
+        }
+        entries.extend(buf[..n].iter().map(|entry| {
+            let name = String::from_utf8_lossy(entry.name_as_bytes()).into_owned();
+            (name, entry.entry_type())
+        }));
+    }
+}
+
+impl Layers {
+    pub(crate) fn new(lower: VfsNodeRef, upper: VfsNodeRef) -> Self {
+        Self {
+            lower,
+            upper,
+            whiteouts: RwLock::new(BTreeSet::new()),
+            copy_up_lock: Mutex::new(()),
+            parent: Once::new(),
+        }
+    }
+
+    pub(crate) fn set_parent(&self, parent: VfsNodeRef) {
+        self.parent.call_once(|| parent);
+    }
+
+    /// Returns the directory the overlay is mounted in.
+    pub(crate) fn parent(&self) -> Option<VfsNodeRef> {
+        self.parent.get().cloned()
+    }
+
+    /// Whether the lower layer is hidden at `path` by a whiteout.
+    fn is_whiteout(&self, path: &str) -> bool {
+        let whiteouts = self.whiteouts.read();
+        !whiteouts.is_empty() && prefixes(path).any(|prefix| whiteouts.contains(prefix))
+    }
+
+    /// Returns the node at `path` in the upper layer.
+    pub(crate) fn upper(&self, path: &str) -> Option<VfsNodeRef> {
+        self.upper.clone().lookup(path).ok()
+    }
+
+    /// Returns the node at `path` in the lower layer, if it is not hidden.
+    pub(crate) fn lower(&self, path: &str) -> Option<VfsNodeRef> {
+        if self.is_whiteout(path) {
+            return None;
+        }
+        self.lower.clone().lookup(path).ok()
+    }
+
+    /// Returns the node at `path` seen through the overlay.
+    pub(crate) fn node(&self, path: &str) -> VfsResult<VfsNodeRef> {
+        self.upper(path)
+            .or_else(|| self.lower(path))
+            .ok_or(VfsError::NotFound)
+    }
+
+    /// Returns the types of the entries of the directory at `path` by name,
+    /// those of the upper layer hiding those of the lower one.
+    pub(crate) fn entries(&self, path: &str) -> VfsResult<BTreeMap<String, VfsNodeType>> {
+        let mut entries = BTreeMap::new();
+        let upper = self.upper(path);
+        // A file of the upper layer hides a directory of the lower one.
+        let lower_shown = match &upper {
+            Some(upper) => upper.get_attr()?.is_dir(),
+            None => true,
+        };
+        if let Some(lower) = self.lower(path).filter(|_| lower_shown) {
+            for (name, ty) in read_entries(&lower)? {
+                if !self.is_whiteout(&join(path, &name)) {
+                    entries.insert(name, ty);
+                }
+            }
+        }
+        if let Some(upper) = upper {
+            entries.extend(read_entries(&upper)?);
+        }
+        Ok(entries)
+    }
+
+    /// Hides the node at `path` of the lower layer, and all the nodes below.
+    pub(crate) fn add_whiteout(&self, path: &str) {
+        let mut whiteouts = self.whiteouts.write();
+        // The whiteouts below are covered by this one.
+        whiteouts.retain(|w| !w.starts_with(path) || w.as_bytes().get(path.len()) != Some(&b'/'));
+        whiteouts.insert(path.into());
+    }
+
+    /// Returns the node at `path` in the upper layer, after copying it from
+    /// the lower layer with its parent directories if needed.
+    pub(crate) fn copy_up(&self, path: &str) -> VfsResult<VfsNodeRef> {
+        if let Some(node) = self.upper(path) {
+            return Ok(node);
+        }
+        let _guard = self.copy_up_lock.lock();
+        for prefix in prefixes(path) {
+            if self.upper(prefix).is_none() {
+                self.copy_up_one(prefix)?;
+            }
+        }
+        self.node_in_upper(path)
+    }
+
+    fn node_in_upper(&self, path: &str) -> VfsResult<VfsNodeRef> {
+        if path.is_empty() {
+            Ok(self.upper.clone())
+        } else {
+            self.upper.clone().lookup(path)
+        }
+    }
+
+    /// Copies the node at `path` of the lower layer, whose parent is in the
+    /// upper layer already.
+    fn copy_up_one(&self, path: &str) -> VfsResult {
+        let lower = self.lower(path).ok_or(VfsError::NotFound)?;
+        let attr = lower.get_attr()?;
+        log::debug!("copy up {:?} of overlay: {}", attr.file_type(), path);
+        match attr.file_type() {
+            VfsNodeType::SymLink => {
+                let mut target = vec![0; attr.size() as usize];
+                let len = lower.readlink(&mut target)?;
+                let target =
+                    core::str::from_utf8(&target[..len]).map_err(|_| VfsError::InvalidData)?;
+                return self.upper.symlink(path, target);
+            }
+            ty => self.upper.create(path, ty)?,
+        }
+        let upper = self.upper.clone().lookup(path)?;
+        let res = Self::copy_content(&lower, &upper, attr.is_file()).and_then(|_| {
+            match upper.setattr(Some(attr.perm()), attr.uid(), attr.gid(), None) {
+                Err(VfsError::Unsupported) => Ok(()),
+                res => res,
+            }
+        });
+        if res.is_err() {
+            self.upper.remove(path).ok();
+        }
+        res
+    }
+
+    fn copy_content(src: &VfsNodeRef, dst: &VfsNodeRef, is_file: bool) -> VfsResult {
+        if !is_file {
+            return Ok(());
+        }
+        let mut buf = vec![0; COPY_CHUNK_SIZE];
+        let mut offset = 0;
+        loop {
+            let n = src.read_at(offset, &mut buf)?;
+            if n == 0 {
+                return Ok(());
+            }
+            if dst.write_at(offset, &buf[..n])? < n {
+                return Err(VfsError::WriteZero);
+            }
+            offset += n as u64;
+        }
+    }
+}
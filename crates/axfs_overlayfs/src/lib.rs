@@ -0,0 +1,96 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Overlay filesystem, which shows a writable upper layer over a read-only
+//! lower layer, like the overlayfs of Linux.
+//!
+//! Both layers are directories of other filesystems. A path is looked up in
+//! the upper layer first, then in the lower one, and the entries of a
+//! directory are those of both layers. The lower layer is never modified:
+//!
+//! - a node of the lower layer is copied up to the upper layer, with its
+//!   parent directories, when it is first modified;
+//! - removing a node of the lower layer records a **whiteout**, which hides
+//!   it and everything below it. A directory created in its place later is
+//!   opaque, none of the lower entries show through it.
+//!
+//! The whiteouts are kept in memory as long as the filesystem, they are not
+//! stored in the upper layer.
+//!
+//! # Examples
+//!
+//! ```
+//! use axfs_overlayfs::OverlayFileSystem;
+//! use axfs_ramfs::RamFileSystem;
+//! use axfs_vfs::{VfsNodeType, VfsOps};
+//!
+//! let (lower, upper) = (RamFileSystem::new(), RamFileSystem::new());
+//! lower.root_dir().create("base", VfsNodeType::File).unwrap();
+//! lower.root_dir().lookup("base").unwrap().write_at(0, b"lower").unwrap();
+//!
+//! let overlay = OverlayFileSystem::new(lower.root_dir(), upper.root_dir());
+//! let file = overlay.root_dir().lookup("base").unwrap();
+//! file.write_at(0, b"upper").unwrap();
+//!
+//! // The file is copied up, the lower layer is unchanged.
+//! let mut buf = [0; 5];
+//! upper.root_dir().lookup("base").unwrap().read_at(0, &mut buf).unwrap();
+//! assert_eq!(&buf, b"upper");
+//! lower.root_dir().lookup("base").unwrap().read_at(0, &mut buf).unwrap();
+//! assert_eq!(&buf, b"lower");
+//! ```
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+mod layers;
+mod node;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::node::OverlayNode;
+
+use alloc::sync::Arc;
+use axfs_vfs::{VfsNodeRef, VfsOps, VfsResult};
+
+use self::layers::Layers;
+
+/// An overlay filesystem that implements [`axfs_vfs::VfsOps`].
+pub struct OverlayFileSystem {
+    layers: Arc<Layers>,
+}
+
+impl OverlayFileSystem {
+    /// Creates an overlay of the directory `upper` over the directory
+    /// `lower`, where all the changes are made.
+    pub fn new(lower: VfsNodeRef, upper: VfsNodeRef) -> Self {
+        Self {
+            layers: Arc::new(Layers::new(lower, upper)),
+        }
+    }
+}
+
+impl VfsOps for OverlayFileSystem {
+    fn mount(&self, _path: &str, mount_point: VfsNodeRef) -> VfsResult {
+        if let Some(parent) = mount_point.parent() {
+            self.layers.set_parent(parent);
+        }
+        Ok(())
+    }
+
+    fn root_dir(&self) -> VfsNodeRef {
+        Arc::new(OverlayNode::new(self.layers.clone(), "".into()))
+    }
+
+    fn fs_type(&self) -> &str {
+        "overlay"
+    }
+}
@@ -0,0 +1,207 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use axfs_vfs::path::canonicalize;
+use axfs_vfs::{PollState, VfsDirEntry, VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm};
+use axfs_vfs::{VfsNodeRef, VfsNodeType, VfsResult};
+
+use crate::layers::{basename, dirname, join, Layers};
+
+/// A node of the overlay filesystem.
+///
+/// It is known by its path, which is looked up in the layers on each
+/// operation, so it keeps working once copied up.
+/// It implements [`axfs_vfs::VfsNodeOps`].
+pub struct OverlayNode {
+    layers: Arc<Layers>,
+    path: String,
+}
+
+fn split_path(path: &str) -> (&str, Option<&str>) {
+    let trimmed_path = path.trim_start_matches('/');
+    trimmed_path.find('/').map_or((trimmed_path, None), |n| {
+        (&trimmed_path[..n], Some(&trimmed_path[n + 1..]))
+    })
+}
+
+impl OverlayNode {
+    pub(crate) fn new(layers: Arc<Layers>, path: String) -> Self {
+        Self { layers, path }
+    }
+
+    /// Returns the canonical path of `path` relative to this directory, and
+    /// the path of its parent directory, which must exist. Returns `None` for
+    /// the root, which has no parent in the overlay.
+    fn child_path(&self, path: &str) -> VfsResult<Option<(String, String)>> {
+        let path = canonicalize(&join(&self.path, path.trim_start_matches('/')));
+        if path.is_empty() {
+            return Ok(None);
+        }
+        let parent = String::from(dirname(&path));
+        if !self.layers.node(&parent)?.get_attr()?.is_dir() {
+            return Err(VfsError::NotADirectory);
+        }
+        Ok(Some((path, parent)))
+    }
+
+    /// Fails with [`AlreadyExists`](VfsError::AlreadyExists) if there is a
+    /// node at `path`.
+    fn check_absent(&self, path: &str) -> VfsResult {
+        match self.layers.node(path) {
+            Ok(_) => Err(VfsError::AlreadyExists),
+            Err(VfsError::NotFound) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl VfsNodeOps for OverlayNode {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        self.layers.node(&self.path)?.get_attr()
+    }
+
+    fn setattr(
+        &self,
+        mode: Option<VfsNodePerm>,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        size: Option<u64>,
+    ) -> VfsResult {
+        self.layers
+            .copy_up(&self.path)?
+            .setattr(mode, uid, gid, size)
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
+        self.layers.node(&self.path)?.read_at(offset, buf)
+    }
+
+    fn write_at(&self, offset: u64, buf: &[u8]) -> VfsResult<usize> {
+        self.layers.copy_up(&self.path)?.write_at(offset, buf)
+    }
+
+    fn fsync(&self) -> VfsResult {
+        match self.layers.upper(&self.path) {
+            Some(node) => node.fsync(),
+            // nothing was written
+            None => Ok(()),
+        }
+    }
+
+    fn truncate(&self, size: u64) -> VfsResult {
+        self.layers.copy_up(&self.path)?.truncate(size)
+    }
+
+    fn fallocate(&self, offset: u64, len: u64) -> VfsResult {
+        self.layers.copy_up(&self.path)?.fallocate(offset, len)
+    }
+
+    fn poll(&self) -> VfsResult<PollState> {
+        self.layers.node(&self.path)?.poll()
+    }
+
+    fn ioctl(&self, cmd: usize, arg: usize) -> VfsResult<usize> {
+        self.layers.node(&self.path)?.ioctl(cmd, arg)
+    }
+
+    fn readlink(&self, buf: &mut [u8]) -> VfsResult<usize> {
+        self.layers.node(&self.path)?.readlink(buf)
+    }
+
+    fn parent(&self) -> Option<VfsNodeRef> {
+        if self.path.is_empty() {
+            self.layers.parent()
+        } else {
+            let parent = dirname(&self.path).into();
+            Some(Arc::new(Self::new(self.layers.clone(), parent)))
+        }
+    }
+
+    fn lookup(self: Arc<Self>, path: &str) -> VfsResult<VfsNodeRef> {
+        if !self.get_attr()?.is_dir() {
+            return Err(VfsError::NotADirectory);
+        }
+        let (name, rest) = split_path(path);
+        let node: VfsNodeRef = match name {
+            "" | "." => self.clone(),
+            ".." => self.parent().ok_or(VfsError::NotFound)?,
+            _ => {
+                let path = join(&self.path, name);
+                self.layers.node(&path)?;
+                Arc::new(Self::new(self.layers.clone(), path))
+            }
+        };
+        match rest {
+            Some(rest) => node.lookup(rest),
+            None => Ok(node),
+        }
+    }
+
+    fn create(&self, path: &str, ty: VfsNodeType) -> VfsResult {
+        log::debug!("create {:?} at overlayfs: {}", ty, path);
+        let Some((path, parent)) = self.child_path(path)? else {
+            return Ok(()); // already exists
+        };
+        self.check_absent(&path)?;
+        self.layers.copy_up(&parent)?.create(basename(&path), ty)
+    }
+
+    fn remove(&self, path: &str) -> VfsResult {
+        log::debug!("remove at overlayfs: {}", path);
+        let (path, parent) = self.child_path(path)?.ok_or(VfsError::InvalidInput)?;
+        if self.layers.node(&path)?.get_attr()?.is_dir() && !self.layers.entries(&path)?.is_empty()
+        {
+            return Err(VfsError::DirectoryNotEmpty);
+        }
+        if self.layers.upper(&path).is_some() {
+            self.layers.copy_up(&parent)?.remove(basename(&path))?;
+        }
+        if self.layers.lower(&path).is_some() {
+            self.layers.add_whiteout(&path);
+        }
+        Ok(())
+    }
+
+    fn symlink(&self, path: &str, target: &str) -> VfsResult {
+        log::debug!("symlink at overlayfs: {} -> {}", path, target);
+        let (path, parent) = self.child_path(path)?.ok_or(VfsError::AlreadyExists)?;
+        self.check_absent(&path)?;
+        self.layers
+            .copy_up(&parent)?
+            .symlink(basename(&path), target)
+    }
+
+    fn read_dir(&self, start_idx: usize, dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
+        if !self.get_attr()?.is_dir() {
+            return Err(VfsError::NotADirectory);
+        }
+        let entries = self.layers.entries(&self.path)?;
+        let mut entries = entries.iter().skip(start_idx.max(2) - 2);
+        for (i, ent) in dirents.iter_mut().enumerate() {
+            match i + start_idx {
+                0 => *ent = VfsDirEntry::new(".", VfsNodeType::Dir),
+                1 => *ent = VfsDirEntry::new("..", VfsNodeType::Dir),
+                _ => {
+                    if let Some((name, &ty)) = entries.next() {
+                        *ent = VfsDirEntry::new(name, ty);
+                    } else {
+                        return Ok(i);
+                    }
+                }
+            }
+        }
+        Ok(dirents.len())
+    }
+
+    fn as_any(&self) -> &dyn core::any::Any {
+        self
+    }
+}
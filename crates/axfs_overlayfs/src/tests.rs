@@ -0,0 +1,238 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use axfs_ramfs::RamFileSystem;
+use axfs_vfs::{VfsDirEntry, VfsError, VfsNodeRef, VfsNodeType, VfsOps, VfsResult};
+
+use crate::*;
+
+struct Layers {
+    lower: RamFileSystem,
+    upper: RamFileSystem,
+    overlay: OverlayFileSystem,
+}
+
+/// Creates an overlay whose lower layer is:
+///
+/// ```text
+/// .
+/// ├── f1 ("lower f1")
+/// ├── f2 ("lower f2")
+/// ├── foo
+/// │   ├── bar
+/// │   │   └── f3 ("lower f3")
+/// │   └── f4 ("lower f4")
+/// └── link -> foo/f4
+/// ```
+///
+/// and whose upper layer only has `f2` ("upper f2") and `foo/f5`.
+fn setup() -> VfsResult<Layers> {
+    let lower = RamFileSystem::new();
+    let root = lower.root_dir();
+    for (path, ty) in [
+        ("f1", VfsNodeType::File),
+        ("f2", VfsNodeType::File),
+        ("foo", VfsNodeType::Dir),
+        ("foo/bar", VfsNodeType::Dir),
+        ("foo/bar/f3", VfsNodeType::File),
+        ("foo/f4", VfsNodeType::File),
+    ] {
+        root.create(path, ty)?;
+        if ty == VfsNodeType::File {
+            let content = format!("lower {}", path.rsplit('/').next().unwrap());
+            root.clone().lookup(path)?.write_at(0, content.as_bytes())?;
+        }
+    }
+    root.symlink("link", "foo/f4")?;
+
+    let upper = RamFileSystem::new();
+    let root = upper.root_dir();
+    root.create("f2", VfsNodeType::File)?;
+    root.clone().lookup("f2")?.write_at(0, b"upper f2")?;
+    root.create("foo", VfsNodeType::Dir)?;
+    root.create("foo/f5", VfsNodeType::File)?;
+
+    let overlay = OverlayFileSystem::new(lower.root_dir(), upper.root_dir());
+    Ok(Layers {
+        lower,
+        upper,
+        overlay,
+    })
+}
+
+fn read(node: VfsNodeRef) -> VfsResult<String> {
+    let mut buf = [0; 64];
+    let len = node.read_at(0, &mut buf)?;
+    Ok(String::from_utf8(buf[..len].to_vec()).unwrap())
+}
+
+fn names(dir: &VfsNodeRef) -> VfsResult<Vec<String>> {
+    let mut names = Vec::new();
+    let mut buf = [(); 2].map(|_| VfsDirEntry::default());
+    loop {
+        let n = dir.read_dir(names.len(), &mut buf)?;
+        if n == 0 {
+            return Ok(names);
+        }
+        for entry in &buf[..n] {
+            names.push(String::from_utf8(entry.name_as_bytes().to_vec()).unwrap());
+        }
+    }
+}
+
+#[test]
+fn test_lookup() -> VfsResult {
+    let layers = setup()?;
+    let root = layers.overlay.root_dir();
+    assert_eq!(read(root.clone().lookup("f1")?)?, "lower f1");
+    assert_eq!(read(root.clone().lookup("f2")?)?, "upper f2");
+    assert_eq!(read(root.clone().lookup("foo/bar/f3")?)?, "lower f3");
+    assert_eq!(read(root.clone().lookup("/foo/bar/../f4")?)?, "lower f4");
+    assert!(root.clone().lookup("foo/f5")?.get_attr()?.is_file());
+    assert_eq!(
+        root.clone().lookup("foo/f6").err(),
+        Some(VfsError::NotFound)
+    );
+    assert_eq!(
+        root.clone().lookup("f1/f2").err(),
+        Some(VfsError::NotADirectory)
+    );
+
+    let mut buf = [0; 16];
+    let link = root.clone().lookup("link")?;
+    assert_eq!(link.get_attr()?.file_type(), VfsNodeType::SymLink);
+    let len = link.readlink(&mut buf)?;
+    assert_eq!(&buf[..len], b"foo/f4");
+
+    let bar = root.clone().lookup("foo/bar")?;
+    let foo = bar.parent().unwrap();
+    assert_eq!(read(foo.clone().lookup("f4")?)?, "lower f4");
+    assert_eq!(names(&foo.parent().unwrap())?, names(&root)?);
+    Ok(())
+}
+
+#[test]
+fn test_read_dir() -> VfsResult {
+    let layers = setup()?;
+    let root = layers.overlay.root_dir();
+    assert_eq!(names(&root)?, [".", "..", "f1", "f2", "foo", "link"]);
+    let foo = root.clone().lookup("foo")?;
+    assert_eq!(names(&foo)?, [".", "..", "bar", "f4", "f5"]);
+    assert_eq!(
+        root.lookup("f1")?
+            .read_dir(0, &mut [VfsDirEntry::default()])
+            .err(),
+        Some(VfsError::NotADirectory)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_copy_up() -> VfsResult {
+    let layers = setup()?;
+    let root = layers.overlay.root_dir();
+
+    let f3 = root.clone().lookup("foo/bar/f3")?;
+    assert_eq!(f3.write_at(0, b"upper")?, 5);
+    assert_eq!(read(f3.clone())?, "upper f3");
+    assert_eq!(
+        read(layers.upper.root_dir().lookup("foo/bar/f3")?)?,
+        "upper f3"
+    );
+    assert_eq!(
+        read(layers.lower.root_dir().lookup("foo/bar/f3")?)?,
+        "lower f3"
+    );
+
+    let f1 = root.clone().lookup("f1")?;
+    f1.truncate(2)?;
+    assert_eq!(read(f1)?, "lo");
+    assert_eq!(read(layers.lower.root_dir().lookup("f1")?)?, "lower f1");
+
+    // a copied up symlink keeps its target
+    let link = root.clone().lookup("link")?;
+    link.setattr(None, Some(1), Some(1), None).ok();
+    let mut buf = [0; 16];
+    let upper_link = layers.upper.root_dir().lookup("link")?;
+    let len = upper_link.readlink(&mut buf)?;
+    assert_eq!(&buf[..len], b"foo/f4");
+    Ok(())
+}
+
+#[test]
+fn test_create() -> VfsResult {
+    let layers = setup()?;
+    let root = layers.overlay.root_dir();
+    root.create("foo/bar/baz", VfsNodeType::Dir)?;
+    root.create("foo/bar/baz/f6", VfsNodeType::File)?;
+    root.symlink("foo/bar/link", "../f4")?;
+    assert!(layers.upper.root_dir().lookup("foo/bar/baz/f6").is_ok());
+    assert!(layers.lower.root_dir().lookup("foo/bar/baz").is_err());
+    assert_eq!(
+        names(&root.clone().lookup("foo/bar")?)?,
+        [".", "..", "baz", "f3", "link"]
+    );
+
+    assert_eq!(
+        root.create("f1", VfsNodeType::File).err(),
+        Some(VfsError::AlreadyExists)
+    );
+    assert_eq!(
+        root.create("foo/f5", VfsNodeType::Dir).err(),
+        Some(VfsError::AlreadyExists)
+    );
+    assert_eq!(
+        root.symlink("link", "f1").err(),
+        Some(VfsError::AlreadyExists)
+    );
+    assert_eq!(
+        root.create("f1/f6", VfsNodeType::File).err(),
+        Some(VfsError::NotADirectory)
+    );
+    assert_eq!(
+        root.create("nothing/f6", VfsNodeType::File).err(),
+        Some(VfsError::NotFound)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_remove() -> VfsResult {
+    let layers = setup()?;
+    let root = layers.overlay.root_dir();
+
+    root.remove("f1")?;
+    root.remove("f2")?;
+    assert_eq!(root.clone().lookup("f1").err(), Some(VfsError::NotFound));
+    assert_eq!(root.clone().lookup("f2").err(), Some(VfsError::NotFound));
+    assert_eq!(root.remove("f2").err(), Some(VfsError::NotFound));
+    assert!(layers.lower.root_dir().lookup("f1").is_ok());
+    assert!(layers.lower.root_dir().lookup("f2").is_ok());
+    assert!(layers.upper.root_dir().lookup("f2").is_err());
+
+    assert_eq!(root.remove("foo").err(), Some(VfsError::DirectoryNotEmpty));
+    root.remove("foo/bar/f3")?;
+    root.remove("foo/bar")?;
+    assert_eq!(
+        names(&root.clone().lookup("foo")?)?,
+        [".", "..", "f4", "f5"]
+    );
+
+    // a directory created over a removed one hides the lower entries
+    root.create("foo/bar", VfsNodeType::Dir)?;
+    assert_eq!(names(&root.clone().lookup("foo/bar")?)?, [".", ".."]);
+    assert_eq!(
+        root.clone().lookup("foo/bar/f3").err(),
+        Some(VfsError::NotFound)
+    );
+    root.create("f1", VfsNodeType::File)?;
+    assert_eq!(read(root.clone().lookup("f1")?)?, "");
+    assert_eq!(names(&root)?, [".", "..", "f1", "foo", "link"]);
+    Ok(())
+}
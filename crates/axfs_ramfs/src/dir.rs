@@ -11,11 +11,15 @@ use alloc::collections::BTreeMap;
 use alloc::sync::{Arc, Weak};
 use alloc::{string::String, vec::Vec};
 
-use axfs_vfs::{VfsDirEntry, VfsNodeAttr, VfsNodeOps, VfsNodeRef, VfsNodeType};
+use axfs_vfs::{VfsDirEntry, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType};
 use axfs_vfs::{VfsError, VfsResult};
 use spin::RwLock;
 
 use crate::file::FileNode;
+use crate::meta::NodeMeta;
+use crate::socket::SocketNode;
+use crate::space::Space;
+use crate::symlink::SymlinkNode;
 
 /// The directory node in the RAM filesystem.
 ///
@@ -24,14 +28,18 @@ pub struct DirNode {
     this: Weak<DirNode>,
     parent: RwLock<Weak<dyn VfsNodeOps>>,
     children: RwLock<BTreeMap<String, VfsNodeRef>>,
+    meta: RwLock<NodeMeta>,
+    space: Arc<Space>,
 }
 
 impl DirNode {
-    pub(super) fn new(parent: Option<Weak<dyn VfsNodeOps>>) -> Arc<Self> {
+    pub(super) fn new(parent: Option<Weak<dyn VfsNodeOps>>, space: Arc<Space>) -> Arc<Self> {
         Arc::new_cyclic(|this| Self {
             this: this.clone(),
             parent: RwLock::new(parent.unwrap_or_else(|| Weak::<Self>::new())),
             children: RwLock::new(BTreeMap::new()),
+            meta: RwLock::new(NodeMeta::new(VfsNodePerm::default_dir())),
+            space,
         })
     }
 
@@ -56,14 +64,54 @@ impl DirNode {
             return Err(VfsError::AlreadyExists);
         }
         let node: VfsNodeRef = match ty {
-            VfsNodeType::File => Arc::new(FileNode::new()),
-            VfsNodeType::Dir => Self::new(Some(self.this.clone())),
+            VfsNodeType::File => Arc::new(FileNode::new(self.space.clone())),
+            VfsNodeType::Dir => Self::new(Some(self.this.clone()), self.space.clone()),
+            VfsNodeType::Socket => Arc::new(SocketNode::new()),
             _ => return Err(VfsError::Unsupported),
         };
         self.children.write().insert(name.into(), node);
         Ok(())
     }
 
+    /// Creates a new symbolic link with the given name in this directory.
+    pub fn create_symlink(&self, name: &str, target: &str) -> VfsResult {
+        let mut children = self.children.write();
+        if children.contains_key(name) {
+            return Err(VfsError::AlreadyExists);
+        }
+        let node = SymlinkNode::new(target, self.space.clone())?;
+        children.insert(name.into(), Arc::new(node));
+        Ok(())
+    }
+
+    /// Adds an existing node with the given name in this directory, e.g. one
+    /// which generates its content when read.
+    pub fn add_node(&self, name: &str, node: VfsNodeRef) -> VfsResult {
+        let mut children = self.children.write();
+        if children.contains_key(name) {
+            return Err(VfsError::AlreadyExists);
+        }
+        children.insert(name.into(), node);
+        Ok(())
+    }
+
+    /// Creates a hard link with the given name in this directory to `node`.
+    ///
+    /// Only the regular files of the same filesystem can be linked.
+    pub fn link_node(&self, name: &str, node: VfsNodeRef) -> VfsResult {
+        if node.get_attr()?.is_dir() {
+            return Err(VfsError::PermissionDenied);
+        }
+        match node.as_any().downcast_ref::<FileNode>() {
+            Some(file) if file.in_space(&self.space) => {
+                self.add_node(name, node.clone())?;
+                file.link();
+                Ok(())
+            }
+            _ => Err(VfsError::Unsupported),
+        }
+    }
+
     /// Removes a node by the given name in this directory.
     pub fn remove_node(&self, name: &str) -> VfsResult {
         let mut children = self.children.write();
@@ -72,6 +120,8 @@ impl DirNode {
             if !dir.children.read().is_empty() {
                 return Err(VfsError::DirectoryNotEmpty);
             }
+        } else if let Some(file) = node.as_any().downcast_ref::<FileNode>() {
+            file.unlink();
         }
         children.remove(name);
         Ok(())
@@ -80,7 +130,30 @@ impl DirNode {
 
 impl VfsNodeOps for DirNode {
     fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
-        Ok(VfsNodeAttr::new_dir(4096, 0))
+        let mut attr = self.meta.read().attr(VfsNodeType::Dir, 4096, 0);
+        // its name, `.` and the `..` of the subdirectories
+        let subdirs = self
+            .children
+            .read()
+            .values()
+            .filter(|node| node.as_any().is::<DirNode>())
+            .count();
+        attr.set_nlink(2 + subdirs as u64);
+        Ok(attr)
+    }
+
+    fn setattr(
+        &self,
+        mode: Option<VfsNodePerm>,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        size: Option<u64>,
+    ) -> VfsResult {
+        if size.is_some() {
+            return Err(VfsError::IsADirectory);
+        }
+        self.meta.write().update(mode, uid, gid);
+        Ok(())
     }
 
     fn parent(&self) -> Option<VfsNodeRef> {
@@ -174,6 +247,61 @@ impl VfsNodeOps for DirNode {
         }
     }
 
+    fn symlink(&self, path: &str, target: &str) -> VfsResult {
+        log::debug!("symlink at ramfs: {} -> {}", path, target);
+        let (name, rest) = split_path(path);
+        if let Some(rest) = rest {
+            match name {
+                "" | "." => self.symlink(rest, target),
+                ".." => self
+                    .parent()
+                    .ok_or(VfsError::NotFound)?
+                    .symlink(rest, target),
+                _ => {
+                    let subdir = self
+                        .children
+                        .read()
+                        .get(name)
+                        .ok_or(VfsError::NotFound)?
+                        .clone();
+                    subdir.symlink(rest, target)
+                }
+            }
+        } else if name.is_empty() || name == "." || name == ".." {
+            Err(VfsError::AlreadyExists)
+        } else {
+            self.create_symlink(name, target)
+        }
+    }
+
+    fn link(&self, path: &str, node: VfsNodeRef) -> VfsResult {
+        log::debug!("link at ramfs: {}", path);
+        let (name, rest) = split_path(path);
+        if let Some(rest) = rest {
+            match name {
+                "" | "." => self.link(rest, node),
+                ".." => self.parent().ok_or(VfsError::NotFound)?.link(rest, node),
+                _ => {
+                    let subdir = self
+                        .children
+                        .read()
+                        .get(name)
+                        .ok_or(VfsError::NotFound)?
+                        .clone();
+                    subdir.link(rest, node)
+                }
+            }
+        } else if name.is_empty() || name == "." || name == ".." {
+            Err(VfsError::AlreadyExists)
+        } else {
+            self.link_node(name, node)
+        }
+    }
+
+    fn tmpfile(&self) -> VfsResult<VfsNodeRef> {
+        Ok(Arc::new(FileNode::new_unnamed(self.space.clone())))
+    }
+
     axfs_vfs::impl_vfs_dir_default! {}
 }
 
@@ -7,36 +7,97 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use alloc::vec::Vec;
+use alloc::{sync::Arc, vec::Vec};
 use axfs_vfs::{impl_vfs_non_dir_default, VfsNodeAttr, VfsNodeOps, VfsResult};
+use axfs_vfs::{VfsError, VfsNodePerm, VfsNodeType};
 use spin::RwLock;
 
+use crate::meta::NodeMeta;
+use crate::space::Space;
+
 /// The file node in the RAM filesystem.
 ///
-/// It implements [`axfs_vfs::VfsNodeOps`].
+/// It implements [`axfs_vfs::VfsNodeOps`]. Its content is counted in the
+/// space of the filesystem until the node is dropped, i.e. when all its
+/// names are removed and it is no longer open.
 pub struct FileNode {
     content: RwLock<Vec<u8>>,
+    meta: RwLock<NodeMeta>,
+    space: Arc<Space>,
 }
 
 impl FileNode {
-    pub(super) const fn new() -> Self {
+    pub(super) const fn new(space: Arc<Space>) -> Self {
         Self {
             content: RwLock::new(Vec::new()),
+            meta: RwLock::new(NodeMeta::new(VfsNodePerm::default_file())),
+            space,
         }
     }
+
+    /// Creates a file which has no name yet, e.g. for `O_TMPFILE`.
+    pub(super) fn new_unnamed(space: Arc<Space>) -> Self {
+        let file = Self::new(space);
+        file.meta.write().set_nlink(0);
+        file
+    }
+
+    /// Counts a new name of the file in a directory.
+    pub(super) fn link(&self) {
+        self.meta.write().inc_nlink();
+    }
+
+    /// Counts a name of the file removed from a directory.
+    pub(super) fn unlink(&self) {
+        self.meta.write().dec_nlink();
+    }
+
+    /// Returns whether the node is in the filesystem which has `space`.
+    pub(super) fn in_space(&self, space: &Arc<Space>) -> bool {
+        Arc::ptr_eq(&self.space, space)
+    }
+
+    /// Resizes the content, if there is enough space for it.
+    fn resize(&self, content: &mut Vec<u8>, size: usize) -> VfsResult {
+        self.space.resize(content.len() as _, size as _)?;
+        content.resize(size, 0);
+        if size < content.capacity() / 2 {
+            content.shrink_to_fit();
+        }
+        Ok(())
+    }
 }
 
 impl VfsNodeOps for FileNode {
     fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
-        Ok(VfsNodeAttr::new_file(self.content.read().len() as _, 0))
+        let size = self.content.read().len() as _;
+        Ok(self.meta.read().attr(VfsNodeType::File, size, 0))
+    }
+
+    fn setattr(
+        &self,
+        mode: Option<VfsNodePerm>,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        size: Option<u64>,
+    ) -> VfsResult {
+        if let Some(size) = size {
+            self.truncate(size)?;
+        }
+        self.meta.write().update(mode, uid, gid);
+        Ok(())
     }
 
     fn truncate(&self, size: u64) -> VfsResult {
         let mut content = self.content.write();
-        if size < content.len() as u64 {
-            content.truncate(size as _);
-        } else {
-            content.resize(size as _, 0);
+        self.resize(&mut content, size as _)
+    }
+
+    fn fallocate(&self, offset: u64, len: u64) -> VfsResult {
+        let end = offset.checked_add(len).ok_or(VfsError::InvalidInput)?;
+        let mut content = self.content.write();
+        if end > content.len() as u64 {
+            self.resize(&mut content, end as _)?;
         }
         Ok(())
     }
@@ -54,12 +115,23 @@ impl VfsNodeOps for FileNode {
         let offset = offset as usize;
         let mut content = self.content.write();
         if offset + buf.len() > content.len() {
-            content.resize(offset + buf.len(), 0);
+            self.resize(&mut content, offset + buf.len())?;
         }
         let dst = &mut content[offset..offset + buf.len()];
         dst.copy_from_slice(&buf[..dst.len()]);
         Ok(buf.len())
     }
 
+    fn fsync(&self) -> VfsResult {
+        // the content only lives in memory
+        Ok(())
+    }
+
     impl_vfs_non_dir_default! {}
 }
+
+impl Drop for FileNode {
+    fn drop(&mut self) {
+        self.space.free(self.content.get_mut().len() as _);
+    }
+}
@@ -17,29 +17,60 @@ extern crate alloc;
 
 mod dir;
 mod file;
+mod meta;
+mod socket;
+mod space;
+mod symlink;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::dir::DirNode;
 pub use self::file::FileNode;
+pub use self::socket::SocketNode;
+pub use self::symlink::SymlinkNode;
 
 use alloc::sync::Arc;
-use axfs_vfs::{VfsNodeRef, VfsOps, VfsResult};
+use axfs_vfs::{FileSystemInfo, VfsNodeRef, VfsOps, VfsResult};
 use spin::once::Once;
 
+use self::space::Space;
+
+/// `f_type` of a RAM filesystem without a size limit in `statfs`.
+const RAMFS_MAGIC: u64 = 0x8584_58f6;
+/// `f_type` of a size-limited RAM filesystem in `statfs`.
+const TMPFS_MAGIC: u64 = 0x0102_1994;
+/// Block size reported by `statfs`.
+const BLOCK_SIZE: u64 = 4096;
+
 /// A RAM filesystem that implements [`axfs_vfs::VfsOps`].
+///
+/// The contents of its files and symbolic links may be limited to a number
+/// of bytes, like a tmpfs. The writes beyond the limit fail with
+/// [`StorageFull`](axfs_vfs::VfsError::StorageFull).
 pub struct RamFileSystem {
     parent: Once<VfsNodeRef>,
     root: Arc<DirNode>,
+    space: Arc<Space>,
 }
 
 impl RamFileSystem {
     /// Create a new instance.
     pub fn new() -> Self {
+        Self::with_space(Space::new(None))
+    }
+
+    /// Create a new instance, whose contents take at most `max_bytes` bytes.
+    pub fn with_capacity(max_bytes: u64) -> Self {
+        Self::with_space(Space::new(Some(max_bytes)))
+    }
+
+    fn with_space(space: Space) -> Self {
+        let space = Arc::new(space);
         Self {
             parent: Once::new(),
-            root: DirNode::new(None),
+            root: DirNode::new(None, space.clone()),
+            space,
         }
     }
 
@@ -47,6 +78,12 @@ impl RamFileSystem {
     pub fn root_dir_node(&self) -> Arc<DirNode> {
         self.root.clone()
     }
+
+    /// Returns the number of bytes used by the contents of the files and
+    /// symbolic links.
+    pub fn used_bytes(&self) -> u64 {
+        self.space.used()
+    }
 }
 
 impl VfsOps for RamFileSystem {
@@ -59,9 +96,28 @@ impl VfsOps for RamFileSystem {
         Ok(())
     }
 
+    fn statfs(&self) -> VfsResult<FileSystemInfo> {
+        // Like Linux, an unlimited filesystem reports no blocks at all.
+        Ok(match self.space.max() {
+            Some(max) => {
+                let free = max.saturating_sub(self.space.used());
+                FileSystemInfo::new(TMPFS_MAGIC, BLOCK_SIZE, max / BLOCK_SIZE, free / BLOCK_SIZE)
+            }
+            None => FileSystemInfo::new(RAMFS_MAGIC, BLOCK_SIZE, 0, 0),
+        })
+    }
+
     fn root_dir(&self) -> VfsNodeRef {
         self.root.clone()
     }
+
+    fn fs_type(&self) -> &str {
+        if self.space.max().is_some() {
+            "tmpfs"
+        } else {
+            "ramfs"
+        }
+    }
 }
 
 impl Default for RamFileSystem {
@@ -0,0 +1,69 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use axfs_vfs::{VfsNodeAttr, VfsNodePerm, VfsNodeType};
+
+/// The attributes of a node which can be changed by
+/// [`setattr`](axfs_vfs::VfsNodeOps::setattr), other than its size.
+pub(crate) struct NodeMeta {
+    perm: VfsNodePerm,
+    uid: Option<u32>,
+    gid: Option<u32>,
+    /// Number of names of the node in the directories.
+    nlink: u64,
+}
+
+impl NodeMeta {
+    /// Creates the metadata of a new node, which has no owner recorded and
+    /// one name.
+    pub(crate) const fn new(perm: VfsNodePerm) -> Self {
+        Self {
+            perm,
+            uid: None,
+            gid: None,
+            nlink: 1,
+        }
+    }
+
+    /// Returns the attributes of the node with this metadata.
+    pub(crate) fn attr(&self, ty: VfsNodeType, size: u64, blocks: u64) -> VfsNodeAttr {
+        let mut attr = VfsNodeAttr::new(self.perm, ty, size, blocks);
+        attr.set_owner(self.uid, self.gid);
+        attr.set_nlink(self.nlink);
+        attr
+    }
+
+    /// Sets the number of names of the node.
+    pub(crate) fn set_nlink(&mut self, nlink: u64) {
+        self.nlink = nlink;
+    }
+
+    /// Counts a name added to the node.
+    pub(crate) fn inc_nlink(&mut self) {
+        self.nlink += 1;
+    }
+
+    /// Counts a name of the node removed.
+    pub(crate) fn dec_nlink(&mut self) {
+        self.nlink = self.nlink.saturating_sub(1);
+    }
+
+    /// Changes the attributes which are not `None`.
+    pub(crate) fn update(&mut self, perm: Option<VfsNodePerm>, uid: Option<u32>, gid: Option<u32>) {
+        if let Some(perm) = perm {
+            self.perm = perm;
+        }
+        if uid.is_some() {
+            self.uid = uid;
+        }
+        if gid.is_some() {
+            self.gid = gid;
+        }
+    }
+}
@@ -0,0 +1,52 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use axfs_vfs::{impl_vfs_non_dir_default, VfsNodeAttr, VfsNodeOps, VfsResult};
+use axfs_vfs::{VfsError, VfsNodePerm, VfsNodeType};
+use spin::RwLock;
+
+use crate::meta::NodeMeta;
+
+/// The node a Unix domain socket is bound to in the RAM filesystem.
+///
+/// It has no content, the socket itself is looked up by the path of the node.
+/// It implements [`axfs_vfs::VfsNodeOps`].
+pub struct SocketNode {
+    meta: RwLock<NodeMeta>,
+}
+
+impl SocketNode {
+    pub(super) fn new() -> Self {
+        Self {
+            meta: RwLock::new(NodeMeta::new(VfsNodePerm::from_bits_truncate(0o777))),
+        }
+    }
+}
+
+impl VfsNodeOps for SocketNode {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        Ok(self.meta.read().attr(VfsNodeType::Socket, 0, 0))
+    }
+
+    fn setattr(
+        &self,
+        mode: Option<VfsNodePerm>,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        size: Option<u64>,
+    ) -> VfsResult {
+        if size.is_some() {
+            return Err(VfsError::InvalidInput);
+        }
+        self.meta.write().update(mode, uid, gid);
+        Ok(())
+    }
+
+    impl_vfs_non_dir_default! {}
+}
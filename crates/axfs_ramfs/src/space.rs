@@ -0,0 +1,66 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use axfs_vfs::{VfsError, VfsResult};
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// The memory used by the contents of the files and symbolic links of a
+/// filesystem, and its limit.
+pub(crate) struct Space {
+    used: AtomicU64,
+    max: Option<u64>,
+}
+
+impl Space {
+    /// Creates an empty space of at most `max` bytes, or unlimited if `None`.
+    pub(crate) const fn new(max: Option<u64>) -> Self {
+        Self {
+            used: AtomicU64::new(0),
+            max,
+        }
+    }
+
+    /// Returns the number of bytes used.
+    pub(crate) fn used(&self) -> u64 {
+        self.used.load(Ordering::Acquire)
+    }
+
+    /// Returns the maximum number of bytes, or `None` if unlimited.
+    pub(crate) fn max(&self) -> Option<u64> {
+        self.max
+    }
+
+    /// Uses `bytes` more, or fails with
+    /// [`StorageFull`](VfsError::StorageFull) if it would exceed the limit.
+    pub(crate) fn alloc(&self, bytes: u64) -> VfsResult {
+        self.used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                let used = used.checked_add(bytes)?;
+                self.max.map_or(true, |max| used <= max).then_some(used)
+            })
+            .map(|_| ())
+            .map_err(|_| VfsError::StorageFull)
+    }
+
+    /// Gives back `bytes` which were used.
+    pub(crate) fn free(&self, bytes: u64) {
+        self.used.fetch_sub(bytes, Ordering::AcqRel);
+    }
+
+    /// Changes the bytes used from `old` to `new`, failing like
+    /// [`alloc`](Self::alloc) if they grow.
+    pub(crate) fn resize(&self, old: u64, new: u64) -> VfsResult {
+        if new > old {
+            self.alloc(new - old)
+        } else {
+            self.free(old - new);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,73 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use alloc::{string::String, sync::Arc};
+use axfs_vfs::{impl_vfs_non_dir_default, VfsNodeAttr, VfsNodeOps, VfsResult};
+use axfs_vfs::{VfsError, VfsNodePerm, VfsNodeType};
+use spin::RwLock;
+
+use crate::meta::NodeMeta;
+use crate::space::Space;
+
+/// The symbolic link node in the RAM filesystem.
+///
+/// It implements [`axfs_vfs::VfsNodeOps`].
+pub struct SymlinkNode {
+    target: String,
+    meta: RwLock<NodeMeta>,
+    space: Arc<Space>,
+}
+
+impl SymlinkNode {
+    /// Creates a link to `target`, if there is enough space for it.
+    pub(super) fn new(target: &str, space: Arc<Space>) -> VfsResult<Self> {
+        space.alloc(target.len() as _)?;
+        Ok(Self {
+            target: target.into(),
+            meta: RwLock::new(NodeMeta::new(VfsNodePerm::from_bits_truncate(0o777))),
+            space,
+        })
+    }
+}
+
+impl VfsNodeOps for SymlinkNode {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        let size = self.target.len() as _;
+        Ok(self.meta.read().attr(VfsNodeType::SymLink, size, 0))
+    }
+
+    fn setattr(
+        &self,
+        mode: Option<VfsNodePerm>,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        size: Option<u64>,
+    ) -> VfsResult {
+        // the permission of symbolic links is not used, and is always 0o777
+        if mode.is_some() || size.is_some() {
+            return Err(VfsError::Unsupported);
+        }
+        self.meta.write().update(None, uid, gid);
+        Ok(())
+    }
+
+    fn readlink(&self, buf: &mut [u8]) -> VfsResult<usize> {
+        let len = self.target.len().min(buf.len());
+        buf[..len].copy_from_slice(&self.target.as_bytes()[..len]);
+        Ok(len)
+    }
+
+    impl_vfs_non_dir_default! {}
+}
+
+impl Drop for SymlinkNode {
+    fn drop(&mut self) {
+        self.space.free(self.target.len() as _);
+    }
+}
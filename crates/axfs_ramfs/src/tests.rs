@@ -9,7 +9,7 @@
 
 use std::sync::Arc;
 
-use axfs_vfs::{VfsError, VfsNodeType, VfsResult};
+use axfs_vfs::{VfsDirEntry, VfsError, VfsNodePerm, VfsNodeType, VfsResult};
 
 use crate::*;
 
@@ -97,6 +97,67 @@ fn test_get_parent(devfs: &RamFileSystem) -> VfsResult {
     Ok(())
 }
 
+fn test_symlink(devfs: &RamFileSystem) -> VfsResult {
+    let mut buf = [0; 32];
+
+    let root = devfs.root_dir();
+    root.symlink("foo/link", "bar/f4")?;
+    assert_eq!(
+        root.symlink("foo/link", "f3").err(),
+        Some(VfsError::AlreadyExists)
+    );
+    assert_eq!(
+        root.symlink("f1/link", "f3").err(),
+        Some(VfsError::NotADirectory)
+    );
+
+    let link = root.clone().lookup("foo/link")?;
+    assert_eq!(link.get_attr()?.file_type(), VfsNodeType::SymLink);
+    assert_eq!(link.get_attr()?.size(), 6);
+    assert_eq!(link.readlink(&mut buf)?, 6);
+    assert_eq!(&buf[..6], b"bar/f4");
+    assert_eq!(link.readlink(&mut buf[..3])?, 3);
+    assert_eq!(link.lookup("f4").err(), Some(VfsError::NotADirectory));
+    assert_eq!(
+        root.clone().lookup("foo/f3")?.readlink(&mut buf).err(),
+        Some(VfsError::InvalidInput)
+    );
+
+    root.remove("foo/link")?;
+    assert_eq!(root.lookup("foo/link").err(), Some(VfsError::NotFound));
+    Ok(())
+}
+
+fn test_setattr(devfs: &RamFileSystem) -> VfsResult {
+    let perm = VfsNodePerm::from_bits_truncate;
+    let root = devfs.root_dir();
+    let file = root.clone().lookup("foo/f3")?;
+    assert_eq!(file.get_attr()?.perm().bits(), 0o666);
+    file.setattr(Some(perm(0o600)), None, None, None)?;
+    assert_eq!(file.get_attr()?.perm().bits(), 0o600);
+    file.setattr(None, None, None, Some(5))?;
+    assert_eq!(file.get_attr()?.size(), 5);
+    assert_eq!(file.get_attr()?.perm().bits(), 0o600);
+
+    assert_eq!(file.get_attr()?.uid(), None);
+    file.setattr(None, Some(42), None, None)?;
+    assert_eq!(file.get_attr()?.uid(), Some(42));
+    assert_eq!(file.get_attr()?.gid(), None);
+    file.setattr(None, None, Some(43), None)?;
+    assert_eq!(file.get_attr()?.uid(), Some(42));
+    assert_eq!(file.get_attr()?.gid(), Some(43));
+
+    let dir = root.lookup("foo/bar")?;
+    dir.setattr(Some(perm(0o700)), None, None, None)?;
+    assert_eq!(dir.get_attr()?.perm().bits(), 0o700);
+    assert!(dir.get_attr()?.is_dir());
+    assert_eq!(
+        dir.setattr(None, None, None, Some(0)).err(),
+        Some(VfsError::IsADirectory)
+    );
+    Ok(())
+}
+
 #[test]
 fn test_ramfs() {
     // .
@@ -126,6 +187,8 @@ fn test_ramfs() {
 
     test_ramfs_ops(&ramfs).unwrap();
     test_get_parent(&ramfs).unwrap();
+    test_symlink(&ramfs).unwrap();
+    test_setattr(&ramfs).unwrap();
 
     let root = ramfs.root_dir();
     assert_eq!(root.remove("f1"), Ok(()));
@@ -143,3 +206,132 @@ fn test_ramfs() {
     assert_eq!(root.remove("./foo"), Ok(()));
     assert!(ramfs.root_dir_node().get_entries().is_empty());
 }
+
+#[test]
+fn test_capacity() {
+    const MAX: usize = 8192;
+    let tmpfs = RamFileSystem::with_capacity(MAX as _);
+    let root = tmpfs.root_dir();
+    let info = tmpfs.statfs().unwrap();
+    assert_eq!((info.blocks(), info.blocks_free()), (2, 2));
+
+    // Fill the filesystem, then fail to grow it.
+    root.create("f1", VfsNodeType::File).unwrap();
+    let f1 = root.clone().lookup("f1").unwrap();
+    assert_eq!(f1.write_at(0, &[1; MAX - 10]), Ok(MAX - 10));
+    root.symlink("link", "0123456789").unwrap();
+    assert_eq!(tmpfs.used_bytes(), MAX as u64);
+    assert_eq!(tmpfs.statfs().unwrap().blocks_free(), 0);
+    assert_eq!(f1.write_at(0, &[2; 8]), Ok(8));
+    assert_eq!(f1.write_at(MAX as _, &[2]), Err(VfsError::StorageFull));
+    assert_eq!(f1.truncate(MAX as u64), Err(VfsError::StorageFull));
+    assert_eq!(root.symlink("link2", "x"), Err(VfsError::StorageFull));
+    assert_eq!(f1.get_attr().unwrap().size(), (MAX - 10) as u64);
+
+    // Truncating and removing give the space back.
+    f1.truncate(4096).unwrap();
+    assert_eq!(tmpfs.statfs().unwrap().blocks_free(), 0);
+    root.remove("link").unwrap();
+    assert_eq!(tmpfs.used_bytes(), 4096);
+    assert_eq!(tmpfs.statfs().unwrap().blocks_free(), 1);
+
+    // A removed file still takes its space until it is no longer used.
+    root.remove("f1").unwrap();
+    assert_eq!(tmpfs.used_bytes(), 4096);
+    drop(f1);
+    assert_eq!(tmpfs.used_bytes(), 0);
+    root.create("f2", VfsNodeType::File).unwrap();
+    let f2 = root.lookup("f2").unwrap();
+    assert_eq!(f2.write_at(0, &[1; MAX]), Ok(MAX));
+
+    // Allocating extends the file only, within the space left.
+    f2.truncate(100).unwrap();
+    assert_eq!(f2.fallocate(0, 50), Ok(()));
+    assert_eq!(f2.get_attr().unwrap().size(), 100);
+    assert_eq!(f2.fallocate(4000, 4000), Ok(()));
+    assert_eq!(tmpfs.used_bytes(), 8000);
+    assert_eq!(f2.fallocate(8000, 200), Err(VfsError::StorageFull));
+    assert_eq!(f2.fallocate(u64::MAX, 1), Err(VfsError::InvalidInput));
+    assert_eq!(f2.get_attr().unwrap().size(), 8000);
+    let mut buf = [1; 4];
+    assert_eq!(f2.read_at(7996, &mut buf), Ok(4));
+    assert_eq!(buf, [0; 4]);
+
+    let ramfs = RamFileSystem::new();
+    let info = ramfs.statfs().unwrap();
+    assert_eq!((info.blocks(), info.blocks_free()), (0, 0));
+}
+
+#[test]
+fn test_tmpfile_link() {
+    let tmpfs = RamFileSystem::with_capacity(8192);
+    let root = tmpfs.root_dir();
+    root.create("dir", VfsNodeType::Dir).unwrap();
+
+    // An unnamed file takes space until it is dropped.
+    let tmp = root.tmpfile().unwrap();
+    assert_eq!(tmp.write_at(0, b"unnamed"), Ok(7));
+    assert_eq!(tmpfs.used_bytes(), 7);
+    let mut entries = [(); 4].map(|_| VfsDirEntry::default());
+    assert_eq!(root.read_dir(0, &mut entries), Ok(3));
+    drop(tmp);
+    assert_eq!(tmpfs.used_bytes(), 0);
+
+    // Once linked, it stays after the last reference is dropped.
+    let tmp = root.tmpfile().unwrap();
+    assert_eq!(tmp.write_at(0, b"linked"), Ok(6));
+    assert_eq!(tmp.get_attr().unwrap().nlink(), 0);
+    root.link("dir/f1", tmp.clone()).unwrap();
+    assert_eq!(
+        root.link("dir/f1", tmp.clone()),
+        Err(VfsError::AlreadyExists)
+    );
+    root.link("f2", tmp).unwrap();
+    let mut buf = [0; 8];
+    let f1 = root.clone().lookup("dir/f1").unwrap();
+    assert_eq!(f1.read_at(0, &mut buf), Ok(6));
+    assert_eq!(&buf[..6], b"linked");
+    assert_eq!(f1.get_attr().unwrap().nlink(), 2);
+    root.remove("dir/f1").unwrap();
+    assert_eq!(f1.get_attr().unwrap().nlink(), 1);
+    drop(f1);
+    assert_eq!(tmpfs.used_bytes(), 6);
+
+    // A directory is linked by its name, `.` and the `..` of its
+    // subdirectories.
+    assert_eq!(root.get_attr().unwrap().nlink(), 3);
+    root.create("dir/sub", VfsNodeType::Dir).unwrap();
+    let dir = root.clone().lookup("dir").unwrap();
+    assert_eq!(dir.get_attr().unwrap().nlink(), 3);
+    root.remove("dir/sub").unwrap();
+    assert_eq!(dir.get_attr().unwrap().nlink(), 2);
+
+    // Directories and nodes of other filesystems are not linked.
+    assert_eq!(root.link("d", dir), Err(VfsError::PermissionDenied));
+    let other = RamFileSystem::new().root_dir().tmpfile().unwrap();
+    assert_eq!(root.link("o", other), Err(VfsError::Unsupported));
+}
+
+#[test]
+fn test_socket_node() {
+    let ramfs = RamFileSystem::new();
+    let root = ramfs.root_dir();
+    root.create("sock", VfsNodeType::Socket).unwrap();
+    assert_eq!(
+        root.create("sock", VfsNodeType::Socket),
+        Err(VfsError::AlreadyExists)
+    );
+
+    let sock = root.clone().lookup("sock").unwrap();
+    let attr = sock.get_attr().unwrap();
+    assert_eq!(attr.file_type(), VfsNodeType::Socket);
+    assert_eq!(attr.size(), 0);
+    assert_eq!(sock.read_at(0, &mut [0; 4]), Err(VfsError::InvalidInput));
+    assert_eq!(sock.write_at(0, b"data"), Err(VfsError::InvalidInput));
+    let perm = VfsNodePerm::from_bits_truncate(0o755);
+    sock.setattr(Some(perm), None, None, None).unwrap();
+    assert_eq!(sock.get_attr().unwrap().perm().bits(), 0o755);
+
+    root.remove("sock").unwrap();
+    assert_eq!(root.lookup("sock").err(), Some(VfsError::NotFound));
+}
@@ -9,8 +9,8 @@
 
 //! Virtual filesystem interfaces used by [ArceOS](https://github.com/rcore-os/arceos).
 //!
-//! A filesystem is a set of files and directories (symbol links are not
-//! supported currently), collectively referred to as **nodes**, which are
+//! A filesystem is a set of files, directories and symbolic links,
+//! collectively referred to as **nodes**, which are
 //! conceptually similar to [inodes] in Linux. A file system needs to implement
 //! the [`VfsOps`] trait, its files and directories need to implement the
 //! [`VfsNodeOps`] trait.
@@ -31,14 +31,19 @@
 //! | [`open()`](VfsNodeOps::open) | Do something when the node is opened | both |
 //! | [`release()`](VfsNodeOps::release) | Do something when the node is closed | both |
 //! | [`get_attr()`](VfsNodeOps::get_attr) | Get the attributes of the node | both |
+//! | [`setattr()`](VfsNodeOps::setattr) | Set the attributes of the node | both |
 //! | [`read_at()`](VfsNodeOps::read_at) | Read data from the file | file |
 //! | [`write_at()`](VfsNodeOps::write_at) | Write data to the file | file |
 //! | [`fsync()`](VfsNodeOps::fsync) | Synchronize the file data to disk | file |
 //! | [`truncate()`](VfsNodeOps::truncate) | Truncate the file | file |
+//! | [`poll()`](VfsNodeOps::poll) | Whether the file can be read or written now | file |
+//! | [`ioctl()`](VfsNodeOps::ioctl) | Device-specific I/O control operation | file |
+//! | [`readlink()`](VfsNodeOps::readlink) | Read the target of the symbolic link | symlink |
 //! | [`parent()`](VfsNodeOps::parent) | Get the parent directory | directory |
 //! | [`lookup()`](VfsNodeOps::lookup) | Lookup the node with the given path | directory |
 //! | [`create()`](VfsNodeOps::create) | Create a new node with the given path | directory |
 //! | [`remove()`](VfsNodeOps::remove) | Remove the node with the given path | directory |
+//! | [`symlink()`](VfsNodeOps::symlink) | Create a symbolic link with the given path | directory |
 //! | [`read_dir()`](VfsNodeOps::read_dir) | Read directory entries | directory |
 //!
 //! [inodes]: https://en.wikipedia.org/wiki/Inode
@@ -55,6 +60,8 @@ pub mod path;
 use alloc::sync::Arc;
 use axerrno::{ax_err, AxError, AxResult};
 
+pub use axio::PollState;
+
 pub use self::structs::{FileSystemInfo, VfsDirEntry, VfsNodeAttr, VfsNodePerm, VfsNodeType};
 
 /// A wrapper of [`Arc<dyn VfsNodeOps>`].
@@ -90,6 +97,18 @@ pub trait VfsOps: Send + Sync {
 
     /// Get the root directory of the filesystem.
     fn root_dir(&self) -> VfsNodeRef;
+
+    /// Get the type name of the filesystem, as shown in `/proc/mounts`.
+    fn fs_type(&self) -> &str {
+        "none"
+    }
+
+    /// Whether the node found at a path may change without any operation on
+    /// the filesystem, e.g. because it depends on the calling task, so that
+    /// it must not be cached.
+    fn is_volatile(&self) -> bool {
+        false
+    }
 }
 
 /// Node (file/directory) operations.
@@ -109,6 +128,18 @@ pub trait VfsNodeOps: Send + Sync {
         ax_err!(Unsupported)
     }
 
+    /// Set the attributes of the node: its permission mode, owner, group and
+    /// size. The attributes which are `None` are left unchanged.
+    fn setattr(
+        &self,
+        _mode: Option<VfsNodePerm>,
+        _uid: Option<u32>,
+        _gid: Option<u32>,
+        _size: Option<u64>,
+    ) -> VfsResult {
+        ax_err!(Unsupported)
+    }
+
     // file operations:
 
     /// Read data from the file at the given offset.
@@ -131,6 +162,49 @@ pub trait VfsNodeOps: Send + Sync {
         ax_err!(InvalidInput)
     }
 
+    /// Allocate the storage of the `len` bytes at `offset` of the file, which
+    /// is extended with zeros if it ends before.
+    ///
+    /// The default extends the file with [`truncate`](Self::truncate).
+    fn fallocate(&self, offset: u64, len: u64) -> VfsResult {
+        let end = offset.checked_add(len).ok_or(AxError::InvalidInput)?;
+        if end > self.get_attr()?.size() {
+            self.truncate(end)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the file can be read or written now without blocking, e.g. for
+    /// a FIFO or a device.
+    ///
+    /// Regular files are always ready, which is the default.
+    fn poll(&self) -> VfsResult<PollState> {
+        Ok(PollState {
+            readable: true,
+            writable: true,
+            ..Default::default()
+        })
+    }
+
+    /// Perform the device-specific I/O control operation `cmd`, whose argument
+    /// `arg` is usually the address of a buffer.
+    ///
+    /// Nodes without any, like regular files, fail with
+    /// [`NotATty`](AxError::NotATty), which is the default.
+    fn ioctl(&self, _cmd: usize, _arg: usize) -> VfsResult<usize> {
+        ax_err!(NotATty)
+    }
+
+    // symbolic link operations:
+
+    /// Read the target of the symbolic link into `buf`.
+    ///
+    /// Return the number of bytes read, the target is truncated if `buf` is
+    /// too small. Its full length is the size of the node.
+    fn readlink(&self, _buf: &mut [u8]) -> VfsResult<usize> {
+        ax_err!(InvalidInput)
+    }
+
     // directory operations:
 
     /// Get the parent directory of this directory.
@@ -159,6 +233,26 @@ pub trait VfsNodeOps: Send + Sync {
         ax_err!(Unsupported)
     }
 
+    /// Create a symbolic link with the given `path` in the directory, which
+    /// points to `target`.
+    fn symlink(&self, _path: &str, _target: &str) -> VfsResult {
+        ax_err!(Unsupported)
+    }
+
+    /// Create a hard link with the given `path` in the directory to the
+    /// existing `node`, which must be in the same filesystem.
+    fn link(&self, _path: &str, _node: VfsNodeRef) -> VfsResult {
+        ax_err!(Unsupported)
+    }
+
+    /// Create a regular file in the directory without a name (`O_TMPFILE`).
+    ///
+    /// The file is removed when the last reference to it is dropped, unless
+    /// it is [linked](Self::link) in a directory before.
+    fn tmpfile(&self) -> VfsResult<VfsNodeRef> {
+        ax_err!(Unsupported)
+    }
+
     /// Read directory entries into `dirents`, starting from `start_idx`.
     fn read_dir(&self, _start_idx: usize, _dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
         ax_err!(Unsupported)
@@ -59,6 +59,18 @@ macro_rules! impl_vfs_non_dir_default {
             $crate::__priv::ax_err!(NotADirectory)
         }
 
+        fn symlink(&self, _path: &str, _target: &str) -> $crate::VfsResult {
+            $crate::__priv::ax_err!(NotADirectory)
+        }
+
+        fn link(&self, _path: &str, _node: $crate::VfsNodeRef) -> $crate::VfsResult {
+            $crate::__priv::ax_err!(NotADirectory)
+        }
+
+        fn tmpfile(&self) -> $crate::VfsResult<$crate::VfsNodeRef> {
+            $crate::__priv::ax_err!(NotADirectory)
+        }
+
         fn read_dir(
             &self,
             _start_idx: usize,
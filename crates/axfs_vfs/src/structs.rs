@@ -7,11 +7,18 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-/// Filesystem attributes.
-///
-/// Currently not used.
-#[non_exhaustive]
-pub struct FileSystemInfo;
+/// Filesystem attributes, as reported by `statfs`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileSystemInfo {
+    /// Magic number of the filesystem type, e.g. `TMPFS_MAGIC`.
+    fs_type: u64,
+    /// Size of a block, in bytes.
+    block_size: u64,
+    /// Total number of blocks, or 0 if the size is not limited.
+    blocks: u64,
+    /// Number of free blocks.
+    blocks_free: u64,
+}
 
 /// Node (file/directory) attributes.
 #[allow(dead_code)]
@@ -25,6 +32,12 @@ pub struct VfsNodeAttr {
     size: u64,
     /// Number of 512B blocks allocated.
     blocks: u64,
+    /// User ID of the owner, if the filesystem records it.
+    uid: Option<u32>,
+    /// Group ID of the owner, if the filesystem records it.
+    gid: Option<u32>,
+    /// Number of hard links to the node.
+    nlink: u64,
 }
 
 bitflags::bitflags! {
@@ -205,6 +218,39 @@ impl VfsNodeType {
     }
 }
 
+impl FileSystemInfo {
+    /// Creates a new `FileSystemInfo` with the given filesystem type, block
+    /// size, and total and free numbers of blocks.
+    pub const fn new(fs_type: u64, block_size: u64, blocks: u64, blocks_free: u64) -> Self {
+        Self {
+            fs_type,
+            block_size,
+            blocks,
+            blocks_free,
+        }
+    }
+
+    /// Returns the magic number of the filesystem type.
+    pub const fn fs_type(&self) -> u64 {
+        self.fs_type
+    }
+
+    /// Returns the size of a block, in bytes.
+    pub const fn block_size(&self) -> u64 {
+        self.block_size
+    }
+
+    /// Returns the total number of blocks, or 0 if the size is not limited.
+    pub const fn blocks(&self) -> u64 {
+        self.blocks
+    }
+
+    /// Returns the number of free blocks.
+    pub const fn blocks_free(&self) -> u64 {
+        self.blocks_free
+    }
+}
+
 impl VfsNodeAttr {
     /// Creates a new `VfsNodeAttr` with the given permission mode, type, size
     /// and number of blocks.
@@ -214,6 +260,9 @@ impl VfsNodeAttr {
             ty,
             size,
             blocks,
+            uid: None,
+            gid: None,
+            nlink: 1,
         }
     }
 
@@ -224,6 +273,9 @@ impl VfsNodeAttr {
             ty: VfsNodeType::File,
             size,
             blocks,
+            uid: None,
+            gid: None,
+            nlink: 1,
         }
     }
 
@@ -235,6 +287,9 @@ impl VfsNodeAttr {
             ty: VfsNodeType::Dir,
             size,
             blocks,
+            uid: None,
+            gid: None,
+            nlink: 1,
         }
     }
 
@@ -258,6 +313,35 @@ impl VfsNodeAttr {
         self.mode = perm
     }
 
+    /// Returns the user ID of the owner, or `None` if the filesystem does not
+    /// record it.
+    pub const fn uid(&self) -> Option<u32> {
+        self.uid
+    }
+
+    /// Returns the group ID of the owner, or `None` if the filesystem does
+    /// not record it.
+    pub const fn gid(&self) -> Option<u32> {
+        self.gid
+    }
+
+    /// Sets the user and group IDs of the owner of the node.
+    pub fn set_owner(&mut self, uid: Option<u32>, gid: Option<u32>) {
+        self.uid = uid;
+        self.gid = gid;
+    }
+
+    /// Returns the number of hard links to the node, which is 1 if the
+    /// filesystem does not count them.
+    pub const fn nlink(&self) -> u64 {
+        self.nlink
+    }
+
+    /// Sets the number of hard links to the node.
+    pub fn set_nlink(&mut self, nlink: u64) {
+        self.nlink = nlink;
+    }
+
     /// Returns the type of the node.
     pub const fn file_type(&self) -> VfsNodeType {
         self.ty
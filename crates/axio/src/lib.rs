@@ -266,4 +266,9 @@ pub struct PollState {
     pub readable: bool,
     /// Object can be writen now.
     pub writable: bool,
+    /// The peer has closed the connection, or the other end of a pipe is
+    /// closed: no more data will be read.
+    pub hangup: bool,
+    /// An error is pending on the object.
+    pub error: bool,
 }
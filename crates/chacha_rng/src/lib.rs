@@ -0,0 +1,159 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! A cryptographically secure random number generator based on ChaCha20.
+//!
+//! The generator outputs the key stream of ChaCha20 under a secret key, and
+//! replaces the key after each request ("fast key erasure"), so that the bytes
+//! already returned cannot be recovered from a later state.
+//!
+//! [`ChaChaRng`] is a single generator. The functions of the crate share a
+//! global one, which is the kernel CSPRNG: it is seeded at boot with
+//! [`add_entropy`] and backs `getrandom()` and `/dev/urandom`.
+//!
+//! # Examples
+//!
+//! ```
+//! use chacha_rng::ChaChaRng;
+//!
+//! let mut rng = ChaChaRng::new();
+//! assert!(!rng.is_seeded());
+//! rng.add_entropy(&[0x5a; 32]);
+//! assert!(rng.is_seeded());
+//!
+//! let (mut a, mut b) = ([0u8; 16], [0u8; 16]);
+//! rng.fill_bytes(&mut a);
+//! rng.fill_bytes(&mut b);
+//! assert_ne!(a, b);
+//! ```
+
+#![cfg_attr(not(test), no_std)]
+
+use spin::Mutex;
+
+#[cfg(test)]
+mod tests;
+
+/// How many bytes of entropy are mixed in before a generator is seeded.
+pub const SEED_LEN: usize = 32;
+
+/// The generator shared by the whole kernel.
+static RNG: Mutex<ChaChaRng> = Mutex::new(ChaChaRng::new());
+
+/// Mixes `data` into the key of the global generator.
+pub fn add_entropy(data: &[u8]) {
+    RNG.lock().add_entropy(data);
+}
+
+/// Fills `buf` with random bytes from the global generator.
+pub fn fill_bytes(buf: &mut [u8]) {
+    RNG.lock().fill_bytes(buf);
+}
+
+/// Whether the global generator has been given [`SEED_LEN`] bytes of entropy.
+pub fn is_seeded() -> bool {
+    RNG.lock().is_seeded()
+}
+
+/// A ChaCha20 generator with fast key erasure.
+pub struct ChaChaRng {
+    key: [u32; 8],
+    entropy: usize,
+}
+
+impl ChaChaRng {
+    /// Creates a generator with an all-zero key, which is not seeded.
+    pub const fn new() -> Self {
+        Self {
+            key: [0; 8],
+            entropy: 0,
+        }
+    }
+
+    /// Mixes `data` into the key. It may be called any number of times, e.g.
+    /// once per entropy source.
+    pub fn add_entropy(&mut self, data: &[u8]) {
+        for chunk in data.chunks(32) {
+            for (i, word) in chunk.chunks(4).enumerate() {
+                let mut bytes = [0; 4];
+                bytes[..word.len()].copy_from_slice(word);
+                self.key[i] ^= u32::from_le_bytes(bytes);
+            }
+            self.rekey(0);
+        }
+        self.entropy = self.entropy.saturating_add(data.len());
+    }
+
+    /// Whether [`SEED_LEN`] bytes of entropy have been mixed in.
+    pub fn is_seeded(&self) -> bool {
+        self.entropy >= SEED_LEN
+    }
+
+    /// Fills `buf` with the key stream, then replaces the key.
+    pub fn fill_bytes(&mut self, buf: &mut [u8]) {
+        // block 0 is the next key
+        let mut counter = 1;
+        for chunk in buf.chunks_mut(64) {
+            let block = chacha20_block(&self.key, counter);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            counter += 1;
+        }
+        self.rekey(0);
+    }
+
+    fn rekey(&mut self, counter: u64) {
+        let block = chacha20_block(&self.key, counter);
+        for (word, bytes) in self.key.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+    }
+}
+
+impl Default for ChaChaRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(16);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(12);
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(8);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(7);
+}
+
+/// Returns the block `counter` of the ChaCha20 key stream of `key`, with a
+/// 64-bit counter and a zero nonce.
+pub fn chacha20_block(key: &[u32; 8], counter: u64) -> [u8; 64] {
+    let mut init = [0; 16];
+    init[..4].copy_from_slice(&[0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574]);
+    init[4..12].copy_from_slice(key);
+    init[12] = counter as u32;
+    init[13] = (counter >> 32) as u32;
+    let mut state = init;
+    for _ in 0..10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+    let mut block = [0; 64];
+    for (i, bytes) in block.chunks_mut(4).enumerate() {
+        bytes.copy_from_slice(&state[i].wrapping_add(init[i]).to_le_bytes());
+    }
+    block
+}
@@ -0,0 +1,84 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use crate::{chacha20_block, ChaChaRng, SEED_LEN};
+
+#[test]
+fn test_chacha20() {
+    // the key stream of the all-zero key and nonce
+    let block = chacha20_block(&[0; 8], 0);
+    assert_eq!(
+        block[..16],
+        [
+            0x76, 0xb8, 0xe0, 0xad, 0xa0, 0xf1, 0x3d, 0x90, 0x40, 0x5d, 0x6a, 0xe5, 0x53, 0x86,
+            0xbd, 0x28
+        ]
+    );
+    assert_eq!(
+        block[48..],
+        [
+            0x6a, 0x43, 0xb8, 0xf4, 0x15, 0x18, 0xa1, 0x1c, 0xc3, 0x87, 0xb6, 0x69, 0xb2, 0xee,
+            0x65, 0x86
+        ]
+    );
+}
+
+#[test]
+fn test_seeding() {
+    let mut rng = ChaChaRng::new();
+    rng.add_entropy(&[1; SEED_LEN - 1]);
+    assert!(!rng.is_seeded());
+    rng.add_entropy(&[2]);
+    assert!(rng.is_seeded());
+
+    // the same entropy gives the same output, other entropy another one
+    let (mut a, mut b) = (ChaChaRng::new(), ChaChaRng::new());
+    a.add_entropy(b"seed");
+    b.add_entropy(b"seed");
+    let (mut x, mut y) = ([0; 100], [0; 100]);
+    a.fill_bytes(&mut x);
+    b.fill_bytes(&mut y);
+    assert_eq!(x, y);
+    b.add_entropy(b"more");
+    a.fill_bytes(&mut x);
+    b.fill_bytes(&mut y);
+    assert_ne!(x, y);
+}
+
+#[test]
+fn test_fill_bytes() {
+    let mut rng = ChaChaRng::new();
+    rng.add_entropy(&[0x5a; SEED_LEN]);
+
+    // the output never repeats, whatever the size of the requests
+    let (mut buf, mut other) = ([0; 100], [0; 100]);
+    rng.fill_bytes(&mut buf);
+    rng.fill_bytes(&mut other);
+    assert_ne!(buf, other);
+    assert_ne!(buf[..64], buf[64..]);
+    rng.fill_bytes(&mut other[..3]);
+    assert_ne!(other[..3], buf[..3]);
+    rng.fill_bytes(&mut []);
+
+    // the output is the key stream of a key which is then replaced
+    let key = rng.key;
+    rng.fill_bytes(&mut buf);
+    assert_eq!(buf[..64], chacha20_block(&key, 1));
+    assert_ne!(rng.key, key);
+}
+
+#[test]
+fn test_global() {
+    crate::add_entropy(&[7; SEED_LEN]);
+    assert!(crate::is_seeded());
+    let (mut a, mut b) = ([0; 32], [0; 32]);
+    crate::fill_bytes(&mut a);
+    crate::fill_bytes(&mut b);
+    assert_ne!(a, b);
+}
@@ -0,0 +1,240 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Checksum algorithms commonly used by filesystems and archive formats.
+//!
+//! - [`Crc32`]: CRC-32 (IEEE 802.3), used by gzip, zip and PNG.
+//! - [`Crc32c`]: CRC-32C (Castagnoli), used by ext4, btrfs and iSCSI.
+//! - [`Adler32`]: Adler-32, used by zlib.
+//!
+//! The CRC lookup tables are computed at compile time.
+//!
+//! # Examples
+//!
+//! ```
+//! use checksum::{adler32, crc32, Crc32};
+//!
+//! assert_eq!(crc32(b"123456789"), 0xCBF43926);
+//! assert_eq!(adler32(b"Wikipedia"), 0x11E60398);
+//!
+//! // Checksums can also be computed incrementally.
+//! let mut crc = Crc32::new();
+//! crc.update(b"1234");
+//! crc.update(b"56789");
+//! assert_eq!(crc.finalize(), 0xCBF43926);
+//! ```
+
+#![cfg_attr(not(test), no_std)]
+
+/// Reversed polynomial of CRC-32 (IEEE 802.3).
+const CRC32_POLY: u32 = 0xEDB8_8320;
+/// Reversed polynomial of CRC-32C (Castagnoli).
+const CRC32C_POLY: u32 = 0x82F6_3B78;
+
+static CRC32_TABLE: [u32; 256] = make_crc_table(CRC32_POLY);
+static CRC32C_TABLE: [u32; 256] = make_crc_table(CRC32C_POLY);
+
+const fn make_crc_table(poly: u32) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+            j += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+#[inline]
+fn crc_update(table: &[u32; 256], mut crc: u32, data: &[u8]) -> u32 {
+    for &b in data {
+        crc = table[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    crc
+}
+
+macro_rules! def_crc {
+    ($name: ident, $table: ident, $doc: literal) => {
+        #[doc = concat!("Incremental ", $doc, " hasher.")]
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name {
+            state: u32,
+        }
+
+        impl $name {
+            /// Creates a new hasher with the initial state.
+            pub const fn new() -> Self {
+                Self { state: !0 }
+            }
+
+            /// Feeds more data into the hasher.
+            pub fn update(&mut self, data: &[u8]) {
+                self.state = crc_update(&$table, self.state, data);
+            }
+
+            /// Returns the checksum of all data fed so far.
+            ///
+            /// The hasher is not consumed, more data can still be fed later.
+            pub const fn finalize(&self) -> u32 {
+                !self.state
+            }
+
+            /// Resets the hasher to the initial state.
+            pub fn reset(&mut self) {
+                self.state = !0;
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+def_crc!(Crc32, CRC32_TABLE, "CRC-32 (IEEE 802.3)");
+def_crc!(Crc32c, CRC32C_TABLE, "CRC-32C (Castagnoli)");
+
+/// Computes the CRC-32 (IEEE 802.3) checksum of `data`.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.finalize()
+}
+
+/// Computes the CRC-32C (Castagnoli) checksum of `data`.
+pub fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = Crc32c::new();
+    crc.update(data);
+    crc.finalize()
+}
+
+/// Largest prime smaller than 65536.
+const ADLER_MOD: u32 = 65521;
+/// Max number of bytes that can be summed before `b` may overflow `u32`.
+const ADLER_NMAX: usize = 5552;
+
+/// Incremental Adler-32 hasher.
+#[derive(Debug, Clone, Copy)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    /// Creates a new hasher with the initial state.
+    pub const fn new() -> Self {
+        Self { a: 1, b: 0 }
+    }
+
+    /// Feeds more data into the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        // Defer the modulo until just before the sums could overflow.
+        for chunk in data.chunks(ADLER_NMAX) {
+            for &byte in chunk {
+                self.a += byte as u32;
+                self.b += self.a;
+            }
+            self.a %= ADLER_MOD;
+            self.b %= ADLER_MOD;
+        }
+    }
+
+    /// Returns the checksum of all data fed so far.
+    pub const fn finalize(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+
+    /// Resets the hasher to the initial state.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for Adler32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Computes the Adler-32 checksum of `data`.
+pub fn adler32(data: &[u8]) -> u32 {
+    let mut adler = Adler32::new();
+    adler.update(data);
+    adler.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414F_A339
+        );
+    }
+
+    #[test]
+    fn test_crc32c() {
+        assert_eq!(crc32c(b""), 0);
+        assert_eq!(crc32c(b"123456789"), 0xE306_9283);
+        assert_eq!(crc32c(&[0u8; 32]), 0x8A91_36AA);
+    }
+
+    #[test]
+    fn test_adler32() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        // long input crosses the deferred modulo boundary
+        let data = [0xffu8; 3 * ADLER_NMAX + 7];
+        let (mut a, mut b) = (1u64, 0u64);
+        for &byte in data.iter() {
+            a = (a + byte as u64) % ADLER_MOD as u64;
+            b = (b + a) % ADLER_MOD as u64;
+        }
+        assert_eq!(adler32(&data), ((b << 16) | a) as u32);
+    }
+
+    #[test]
+    fn test_incremental() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let (left, right) = data.split_at(17);
+
+        let mut crc = Crc32::new();
+        crc.update(left);
+        crc.update(right);
+        assert_eq!(crc.finalize(), crc32(data));
+
+        let mut crc = Crc32c::default();
+        crc.update(left);
+        crc.update(right);
+        assert_eq!(crc.finalize(), crc32c(data));
+
+        let mut adler = Adler32::new();
+        adler.update(left);
+        adler.update(right);
+        assert_eq!(adler.finalize(), adler32(data));
+        adler.reset();
+        assert_eq!(adler.finalize(), 1);
+    }
+}
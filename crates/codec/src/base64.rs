@@ -0,0 +1,124 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Base64 encoding with the standard alphabet and padding (RFC 4648 section 4).
+
+use crate::{check_len, Error, Result};
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const PAD: u8 = b'=';
+
+/// Maps each byte to its 6-bit value, or `INVALID` if it is out of the alphabet.
+static DECODE_TABLE: [u8; 256] = make_decode_table();
+const INVALID: u8 = 0xff;
+
+const fn make_decode_table() -> [u8; 256] {
+    let mut table = [INVALID; 256];
+    let mut i = 0;
+    while i < 64 {
+        table[ALPHABET[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+}
+
+/// Length of the padded base64 encoding of `len` bytes.
+pub const fn encoded_len(len: usize) -> usize {
+    (len + 2) / 3 * 4
+}
+
+/// Length of the data decoded from the base64 input `src`.
+///
+/// The input must be padded, so its length is a multiple of 4.
+pub fn decoded_len(src: &[u8]) -> Result {
+    if src.len() % 4 != 0 {
+        return Err(Error::InvalidLength);
+    }
+    let pad = src.iter().rev().take(2).take_while(|&&b| b == PAD).count();
+    Ok(src.len() / 4 * 3 - pad)
+}
+
+/// Encode `src` into `dst` as padded base64.
+///
+/// Returns the number of bytes written, i.e. [`encoded_len`] of `src.len()`.
+pub fn encode(src: &[u8], dst: &mut [u8]) -> Result {
+    let len = encoded_len(src.len());
+    check_len(dst, len)?;
+    for (chunk, out) in src.chunks(3).zip(dst.chunks_exact_mut(4)) {
+        let b0 = chunk[0] as u32;
+        let b1 = chunk.get(1).copied().unwrap_or(0) as u32;
+        let b2 = chunk.get(2).copied().unwrap_or(0) as u32;
+        let n = (b0 << 16) | (b1 << 8) | b2;
+        out[0] = ALPHABET[(n >> 18) as usize & 0x3f];
+        out[1] = ALPHABET[(n >> 12) as usize & 0x3f];
+        out[2] = if chunk.len() > 1 {
+            ALPHABET[(n >> 6) as usize & 0x3f]
+        } else {
+            PAD
+        };
+        out[3] = if chunk.len() > 2 {
+            ALPHABET[n as usize & 0x3f]
+        } else {
+            PAD
+        };
+    }
+    Ok(len)
+}
+
+/// Encode `src` into `dst` like [`encode`], and return the output as a `&str`.
+pub fn encode_to_str<'a>(src: &[u8], dst: &'a mut [u8]) -> Result<&'a str> {
+    let len = encode(src, dst)?;
+    // Only characters of the alphabet and padding have been written.
+    Ok(unsafe { core::str::from_utf8_unchecked(&dst[..len]) })
+}
+
+/// Decode the padded base64 input `src` into `dst`.
+///
+/// Padding is only accepted at the end, and the unused bits before it must be
+/// zero, so every byte string has exactly one accepted encoding. Returns the
+/// number of bytes written, i.e. [`decoded_len`] of `src`.
+pub fn decode(src: &[u8], dst: &mut [u8]) -> Result {
+    let len = decoded_len(src)?;
+    check_len(dst, len)?;
+    let groups = src.len() / 4;
+    let mut written = 0;
+    for (g, chunk) in src.chunks_exact(4).enumerate() {
+        let last = g + 1 == groups;
+        let mut n = 0u32;
+        let mut pad = 0;
+        for (j, &byte) in chunk.iter().enumerate() {
+            let index = g * 4 + j;
+            if byte == PAD {
+                // Only the last one or two bytes of the final group may be padding.
+                if !last || j < 2 {
+                    return Err(Error::InvalidPadding);
+                }
+                pad += 1;
+                n <<= 6;
+                continue;
+            }
+            if pad != 0 {
+                return Err(Error::InvalidPadding);
+            }
+            let value = DECODE_TABLE[byte as usize];
+            if value == INVALID {
+                return Err(Error::InvalidByte { index, byte });
+            }
+            n = (n << 6) | value as u32;
+        }
+        let bytes = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+        // The bits hidden by the padding must be zero.
+        if pad != 0 && bytes[3 - pad..].iter().any(|&b| b != 0) {
+            return Err(Error::InvalidPadding);
+        }
+        dst[written..written + 3 - pad].copy_from_slice(&bytes[..3 - pad]);
+        written += 3 - pad;
+    }
+    Ok(written)
+}
@@ -0,0 +1,75 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Base16 (hex) encoding.
+
+use crate::{check_len, Error, Result};
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Length of the hex encoding of `len` bytes.
+pub const fn encoded_len(len: usize) -> usize {
+    len * 2
+}
+
+/// Length of the data decoded from `len` hex digits, which must be even.
+pub const fn decoded_len(len: usize) -> Result {
+    if len % 2 != 0 {
+        Err(Error::InvalidLength)
+    } else {
+        Ok(len / 2)
+    }
+}
+
+/// Encode `src` into `dst` as lowercase hex digits.
+///
+/// Returns the number of bytes written, i.e. [`encoded_len`] of `src.len()`.
+pub fn encode(src: &[u8], dst: &mut [u8]) -> Result {
+    let len = encoded_len(src.len());
+    check_len(dst, len)?;
+    for (byte, out) in src.iter().zip(dst.chunks_exact_mut(2)) {
+        out[0] = DIGITS[(byte >> 4) as usize];
+        out[1] = DIGITS[(byte & 0xf) as usize];
+    }
+    Ok(len)
+}
+
+/// Encode `src` into `dst` like [`encode`], and return the digits as a `&str`.
+pub fn encode_to_str<'a>(src: &[u8], dst: &'a mut [u8]) -> Result<&'a str> {
+    let len = encode(src, dst)?;
+    // Only ASCII digits have been written.
+    Ok(unsafe { core::str::from_utf8_unchecked(&dst[..len]) })
+}
+
+fn digit_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decode the hex digits of `src` into `dst`, accepting both cases.
+///
+/// Returns the number of bytes written, i.e. [`decoded_len`] of `src.len()`.
+pub fn decode(src: &[u8], dst: &mut [u8]) -> Result {
+    let len = decoded_len(src.len())?;
+    check_len(dst, len)?;
+    for (i, (pair, out)) in src.chunks_exact(2).zip(dst.iter_mut()).enumerate() {
+        let value = |j: usize| {
+            digit_value(pair[j]).ok_or(Error::InvalidByte {
+                index: i * 2 + j,
+                byte: pair[j],
+            })
+        };
+        *out = (value(0)? << 4) | value(1)?;
+    }
+    Ok(len)
+}
@@ -0,0 +1,72 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Hex and base64 codecs working on caller-provided buffers.
+//!
+//! - [`hex`]: base16, lowercase on encoding, either case on decoding.
+//! - [`base64`]: the standard alphabet of RFC 4648, with `=` padding.
+//!
+//! Nothing is allocated: every function writes into the given output slice and
+//! returns the number of bytes written, or [`Error::BufferTooSmall`] with the
+//! required size if the output does not fit. The `encoded_len`/`decoded_len`
+//! functions of each module tell the sizes up front.
+//!
+//! # Examples
+//!
+//! ```
+//! use codec::{base64, hex};
+//!
+//! let mut buf = [0u8; 16];
+//! assert_eq!(hex::encode_to_str(b"\x12\xab", &mut buf), Ok("12ab"));
+//! assert_eq!(base64::encode_to_str(b"foob", &mut buf), Ok("Zm9vYg=="));
+//!
+//! let mut out = [0u8; 4];
+//! let n = base64::decode(b"Zm9vYg==", &mut out).unwrap();
+//! assert_eq!(&out[..n], b"foob");
+//! ```
+
+#![cfg_attr(not(test), no_std)]
+
+pub mod base64;
+pub mod hex;
+
+#[cfg(test)]
+mod tests;
+
+/// Errors of encoding and decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The output buffer is too small, `required` bytes are needed.
+    BufferTooSmall {
+        /// Size of the output in bytes.
+        required: usize,
+    },
+    /// The length of the encoded input is not valid.
+    InvalidLength,
+    /// The encoded input contains a byte out of the alphabet.
+    InvalidByte {
+        /// Position of the byte in the input.
+        index: usize,
+        /// The byte itself.
+        byte: u8,
+    },
+    /// The padding of the encoded input is malformed.
+    InvalidPadding,
+}
+
+/// A specialized [`Result`] type with [`Error`] as the error type.
+pub type Result<T = usize> = core::result::Result<T, Error>;
+
+fn check_len(dst: &[u8], required: usize) -> Result<()> {
+    if dst.len() < required {
+        Err(Error::BufferTooSmall { required })
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use crate::{base64, hex, Error};
+
+/// Test vectors from RFC 4648 section 10.
+const RFC4648: [(&[u8], &str, &str); 7] = [
+    (b"", "", ""),
+    (b"f", "Zg==", "66"),
+    (b"fo", "Zm8=", "666f"),
+    (b"foo", "Zm9v", "666f6f"),
+    (b"foob", "Zm9vYg==", "666f6f62"),
+    (b"fooba", "Zm9vYmE=", "666f6f6261"),
+    (b"foobar", "Zm9vYmFy", "666f6f626172"),
+];
+
+#[test]
+fn test_vectors() {
+    let mut buf = [0u8; 16];
+    for (data, b64, b16) in RFC4648 {
+        assert_eq!(base64::encode_to_str(data, &mut buf), Ok(b64));
+        assert_eq!(hex::encode_to_str(data, &mut buf), Ok(b16));
+
+        let n = base64::decode(b64.as_bytes(), &mut buf).unwrap();
+        assert_eq!(&buf[..n], data);
+        let n = hex::decode(b16.as_bytes(), &mut buf).unwrap();
+        assert_eq!(&buf[..n], data);
+    }
+    assert_eq!(
+        hex::encode_to_str(&[0x00, 0x7f, 0xff], &mut buf),
+        Ok("007fff")
+    );
+    let n = hex::decode(b"DEADbeef", &mut buf).unwrap();
+    assert_eq!(&buf[..n], &[0xde, 0xad, 0xbe, 0xef]);
+}
+
+#[test]
+fn test_buffer_sizes() {
+    let mut small = [0u8; 3];
+    assert_eq!(
+        base64::encode(b"foob", &mut small),
+        Err(Error::BufferTooSmall { required: 8 })
+    );
+    assert_eq!(
+        hex::encode(b"foob", &mut small),
+        Err(Error::BufferTooSmall { required: 8 })
+    );
+    assert_eq!(
+        base64::decode(b"Zm9vYg==", &mut small),
+        Err(Error::BufferTooSmall { required: 4 })
+    );
+    assert_eq!(
+        hex::decode(b"666f6f62", &mut small),
+        Err(Error::BufferTooSmall { required: 4 })
+    );
+    assert_eq!(base64::decoded_len(b"Zm9vYmE="), Ok(5));
+    assert_eq!(hex::decoded_len(7), Err(Error::InvalidLength));
+}
+
+#[test]
+fn test_invalid_input() {
+    let mut buf = [0u8; 16];
+    assert_eq!(
+        hex::decode(b"6g", &mut buf),
+        Err(Error::InvalidByte {
+            index: 1,
+            byte: b'g'
+        })
+    );
+    assert_eq!(base64::decode(b"Zm9", &mut buf), Err(Error::InvalidLength));
+    assert_eq!(
+        base64::decode(b"Zm9v!mFy", &mut buf),
+        Err(Error::InvalidByte {
+            index: 4,
+            byte: b'!'
+        })
+    );
+    // padding in the middle, or too much of it
+    assert_eq!(
+        base64::decode(b"Zg==Zm9v", &mut buf),
+        Err(Error::InvalidPadding)
+    );
+    assert_eq!(
+        base64::decode(b"Z===", &mut buf),
+        Err(Error::InvalidPadding)
+    );
+    assert_eq!(
+        base64::decode(b"Zm=v", &mut buf),
+        Err(Error::InvalidPadding)
+    );
+    // non-zero bits hidden by the padding
+    assert_eq!(
+        base64::decode(b"Zh==", &mut buf),
+        Err(Error::InvalidPadding)
+    );
+    assert_eq!(
+        base64::decode(b"Zm9=", &mut buf),
+        Err(Error::InvalidPadding)
+    );
+}
+
+/// A xorshift generator, so that the round trips are reproducible.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+    let mut data = [0u8; 256];
+    let mut encoded = [0u8; hex::encoded_len(256)];
+    let mut decoded = [0u8; 256];
+    for _ in 0..1000 {
+        let len = rng.next() as usize % (data.len() + 1);
+        for byte in data[..len].iter_mut() {
+            *byte = rng.next() as u8;
+        }
+        let data = &data[..len];
+
+        let n = base64::encode(data, &mut encoded).unwrap();
+        assert_eq!(n, base64::encoded_len(len));
+        assert_eq!(base64::decoded_len(&encoded[..n]), Ok(len));
+        assert_eq!(base64::decode(&encoded[..n], &mut decoded), Ok(len));
+        assert_eq!(&decoded[..len], data);
+
+        let n = hex::encode(data, &mut encoded).unwrap();
+        assert_eq!(n, hex::encoded_len(len));
+        assert_eq!(hex::decode(&encoded[..n], &mut decoded), Ok(len));
+        assert_eq!(&decoded[..len], data);
+    }
+}
@@ -57,29 +57,39 @@ impl BaseDriverOps for SDHCIDriver {
 
 impl BlockDriverOps for SDHCIDriver {
     fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> DevResult {
-        if buf.len() < BLOCK_SIZE {
+        if buf.len() < BLOCK_SIZE || buf.len() % BLOCK_SIZE != 0 {
             return Err(DevError::InvalidParam);
         }
         let (prefix, aligned_buf, suffix) = unsafe { buf.align_to_mut::<u32>() };
         if !prefix.is_empty() || !suffix.is_empty() {
             return Err(DevError::InvalidParam);
         }
-        self.0
-            .read_block(block_id as u32, 1, aligned_buf)
-            .map_err(deal_sdhci_err)
+        // one block at a time, for the buffers of multiple blocks
+        for (id, chunk) in (block_id..).zip(aligned_buf.chunks_mut(BLOCK_SIZE / 4)) {
+            self.0
+                .read_block(id as u32, 1, chunk)
+                .map_err(deal_sdhci_err)?;
+        }
+        Ok(())
     }
 
     fn write_block(&mut self, block_id: u64, buf: &[u8]) -> DevResult {
         if buf.len() < BLOCK_SIZE {
             return Err(DevError::Io);
         }
+        if buf.len() % BLOCK_SIZE != 0 {
+            return Err(DevError::InvalidParam);
+        }
         let (prefix, aligned_buf, suffix) = unsafe { buf.align_to::<u32>() };
         if !prefix.is_empty() || !suffix.is_empty() {
             return Err(DevError::InvalidParam);
         }
-        self.0
-            .write_block(block_id as u32, 1, aligned_buf)
-            .map_err(deal_sdhci_err)
+        for (id, chunk) in (block_id..).zip(aligned_buf.chunks(BLOCK_SIZE / 4)) {
+            self.0
+                .write_block(id as u32, 1, chunk)
+                .map_err(deal_sdhci_err)?;
+        }
+        Ok(())
     }
     fn flush(&mut self) -> DevResult {
         Ok(())
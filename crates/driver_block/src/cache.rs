@@ -0,0 +1,302 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! An LRU cache of blocks in front of a block device, in write-back or
+//! write-through mode.
+
+extern crate alloc;
+
+use crate::BlockDriverOps;
+use alloc::{boxed::Box, collections::BTreeMap, vec, vec::Vec};
+use core::ops::RangeBounds;
+use core::str::FromStr;
+use driver_common::{BaseDriverOps, DevError, DevResult, DeviceType};
+
+/// When the data written to a [`BlockCache`] reaches the device.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Writes go to the device at once, and also update the cache.
+    WriteThrough,
+    /// Writes only update the cache, the dirty blocks are written back when
+    /// evicted or flushed.
+    #[default]
+    WriteBack,
+}
+
+impl FromStr for CacheMode {
+    type Err = ();
+
+    /// Parses `"write-through"` or `"write-back"`.
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "write-through" => Ok(Self::WriteThrough),
+            "write-back" => Ok(Self::WriteBack),
+            _ => Err(()),
+        }
+    }
+}
+
+struct CachedBlock {
+    data: Vec<u8>,
+    dirty: bool,
+    /// Value of the access clock when the block was last used.
+    last_access: u64,
+}
+
+/// A block device wrapper that keeps the recently used blocks in memory.
+///
+/// Reads of cached blocks are served by the cache. How writes are handled
+/// depends on the [`CacheMode`], write-back by default: then dirty blocks
+/// are written back to the underlying device when evicted or on
+/// [`flush`](BlockDriverOps::flush). When the cache is full, the least
+/// recently used block is evicted. In [bypass](Self::set_bypass) mode, the accesses go to the device
+/// but the cache stays coherent.
+///
+/// Like other block drivers it needs `&mut self`, so concurrent users must
+/// serialize their accesses, e.g. with the lock of the owning filesystem.
+pub struct BlockCache<D> {
+    dev: D,
+    capacity: usize,
+    mode: CacheMode,
+    bypass: bool,
+    blocks: BTreeMap<u64, CachedBlock>,
+    clock: u64,
+}
+
+impl<D: BlockDriverOps> BlockCache<D> {
+    /// Creates a cache of at most `capacity` blocks for the device `dev`.
+    ///
+    /// If `capacity` is 0, all accesses go directly to the device.
+    pub fn new(dev: D, capacity: usize) -> Self {
+        Self {
+            dev,
+            capacity,
+            mode: CacheMode::WriteBack,
+            bypass: false,
+            blocks: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// Sets how the writes reach the device.
+    pub fn with_mode(mut self, mode: CacheMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns how the writes reach the device.
+    pub const fn mode(&self) -> CacheMode {
+        self.mode
+    }
+
+    /// Sets whether the accesses bypass the cache and go to the device.
+    ///
+    /// The dirty blocks are written back before the device is read, and the
+    /// cached copies of the blocks written to the device are dropped, so the
+    /// cache never serves stale data once the bypass is turned off.
+    pub fn set_bypass(&mut self, bypass: bool) {
+        self.bypass = bypass;
+    }
+
+    /// Returns whether the accesses bypass the cache.
+    pub const fn bypass(&self) -> bool {
+        self.bypass
+    }
+
+    /// Returns the maximum number of cached blocks.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of blocks currently cached.
+    pub fn cached_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Returns a reference to the underlying device.
+    pub const fn inner(&self) -> &D {
+        &self.dev
+    }
+
+    /// Consumes the cache and returns the underlying device.
+    ///
+    /// Dirty blocks are discarded, [`flush`](BlockDriverOps::flush) first to
+    /// keep them.
+    pub fn into_inner(self) -> D {
+        self.dev
+    }
+
+    /// Returns the cached block `block_id`, inserting it if absent.
+    ///
+    /// A newly inserted block is read from the device if `fill` is set, or
+    /// left zeroed if it is about to be overwritten entirely.
+    fn lookup(&mut self, block_id: u64, fill: bool) -> DevResult<&mut CachedBlock> {
+        if !self.blocks.contains_key(&block_id) {
+            if self.blocks.len() >= self.capacity {
+                self.evict()?;
+            }
+            let mut data = vec![0; self.dev.block_size()];
+            if fill {
+                self.dev.read_block(block_id, &mut data)?;
+            }
+            let block = CachedBlock {
+                data,
+                dirty: false,
+                last_access: 0,
+            };
+            self.blocks.insert(block_id, block);
+        }
+        self.clock += 1;
+        let block = self.blocks.get_mut(&block_id).unwrap();
+        block.last_access = self.clock;
+        Ok(block)
+    }
+
+    /// Removes the least recently used block, writing it back if dirty.
+    fn evict(&mut self) -> DevResult {
+        let Some((&block_id, _)) = self.blocks.iter().min_by_key(|(_, b)| b.last_access) else {
+            return Ok(());
+        };
+        let block = &self.blocks[&block_id];
+        if block.dirty {
+            self.dev.write_block(block_id, &block.data)?;
+        }
+        self.blocks.remove(&block_id);
+        Ok(())
+    }
+
+    /// Writes the dirty blocks in `range` back to the device.
+    fn write_back_range(&mut self, range: impl RangeBounds<u64>) -> DevResult {
+        for (&id, block) in self.blocks.range_mut(range) {
+            if block.dirty {
+                self.dev.write_block(id, &block.data)?;
+                block.dirty = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes all dirty blocks back to the device.
+    ///
+    /// Each block is written on its own, as some drivers handle a single
+    /// block per request.
+    fn write_back(&mut self) -> DevResult {
+        self.write_back_range(..)
+    }
+}
+
+impl<D: BlockDriverOps> BaseDriverOps for BlockCache<D> {
+    fn device_type(&self) -> DeviceType {
+        self.dev.device_type()
+    }
+
+    fn device_name(&self) -> &str {
+        self.dev.device_name()
+    }
+}
+
+impl<D: BlockDriverOps> BlockDriverOps for BlockCache<D> {
+    #[inline]
+    fn num_blocks(&self) -> u64 {
+        self.dev.num_blocks()
+    }
+
+    #[inline]
+    fn block_size(&self) -> usize {
+        self.dev.block_size()
+    }
+
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> DevResult {
+        if self.capacity == 0 {
+            return self.dev.read_block(block_id, buf);
+        }
+        let block_size = self.dev.block_size();
+        if buf.len() % block_size != 0 {
+            return Err(DevError::InvalidParam);
+        }
+        if self.bypass {
+            self.write_back_range(block_id..block_id + (buf.len() / block_size) as u64)?;
+            return self.dev.read_block(block_id, buf);
+        }
+        for (id, chunk) in (block_id..).zip(buf.chunks_mut(block_size)) {
+            chunk.copy_from_slice(&self.lookup(id, true)?.data);
+        }
+        Ok(())
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> DevResult {
+        if self.capacity == 0 {
+            return self.dev.write_block(block_id, buf);
+        }
+        let block_size = self.dev.block_size();
+        if buf.len() % block_size != 0 {
+            return Err(DevError::InvalidParam);
+        }
+        let end = block_id + (buf.len() / block_size) as u64;
+        if end > self.dev.num_blocks() {
+            return Err(DevError::Io);
+        }
+        if self.bypass {
+            self.dev.write_block(block_id, buf)?;
+            self.blocks.retain(|id, _| !(block_id..end).contains(id));
+            return Ok(());
+        }
+        let write_back = self.mode == CacheMode::WriteBack;
+        if !write_back {
+            self.dev.write_block(block_id, buf)?;
+        }
+        for (id, chunk) in (block_id..).zip(buf.chunks(block_size)) {
+            let block = self.lookup(id, false)?;
+            block.data.copy_from_slice(chunk);
+            block.dirty = write_back;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> DevResult {
+        self.write_back()?;
+        self.dev.flush()
+    }
+
+    fn prefetch(&mut self, block_id: u64, count: usize) -> DevResult {
+        self.dev.prefetch(block_id, count)
+    }
+}
+
+/// Forwards to the boxed driver, so that a cache can be built on a
+/// dynamically dispatched device.
+impl<T: BlockDriverOps + ?Sized> BlockDriverOps for Box<T> {
+    fn num_blocks(&self) -> u64 {
+        (**self).num_blocks()
+    }
+
+    fn block_size(&self) -> usize {
+        (**self).block_size()
+    }
+
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> DevResult {
+        (**self).read_block(block_id, buf)
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> DevResult {
+        (**self).write_block(block_id, buf)
+    }
+
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> DevResult {
+        (**self).write_at(offset, buf)
+    }
+
+    fn flush(&mut self) -> DevResult {
+        (**self).flush()
+    }
+
+    fn prefetch(&mut self, block_id: u64, count: usize) -> DevResult {
+        (**self).prefetch(block_id, count)
+    }
+}
@@ -9,19 +9,28 @@
 
 //! Common traits and types for block storage device drivers (i.e. disk).
 
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 #![feature(doc_auto_cfg)]
 #![feature(const_trait_impl)]
 
 #[cfg(feature = "ramdisk")]
 pub mod ramdisk;
 
+#[cfg(feature = "cache")]
+pub mod cache;
+
 #[cfg(feature = "bcm2835-sdhci")]
 pub mod bcm2835sdhci;
 
+#[cfg(test)]
+mod tests;
+
 #[doc(no_inline)]
 pub use driver_common::{BaseDriverOps, DevError, DevResult, DeviceType};
 
+/// The maximum block size supported by [`BlockDriverOps::write_at`].
+pub const MAX_BLOCK_SIZE: usize = 4096;
+
 /// Operations that require a block storage device driver to implement.
 pub trait BlockDriverOps: BaseDriverOps {
     /// The number of blocks in this storage device.
@@ -40,9 +49,57 @@ pub trait BlockDriverOps: BaseDriverOps {
     /// Writes blocked data to the given block.
     ///
     /// The size of the buffer may exceed the block size, in which case multiple
-    /// contiguous blocks will be written.
+    /// contiguous blocks will be written. It must be a multiple of the block
+    /// size, otherwise drivers may fail with [`DevError::InvalidParam`]; use
+    /// [`write_at`](Self::write_at) for unaligned data.
     fn write_block(&mut self, block_id: u64, buf: &[u8]) -> DevResult;
 
+    /// Writes `buf` at the byte `offset` of the device, neither of which needs
+    /// to be aligned to blocks.
+    ///
+    /// The partially covered blocks at both ends are read, patched and written
+    /// back, so the bytes around the written range are preserved. The fully
+    /// covered blocks in between are written directly.
+    fn write_at(&mut self, offset: u64, mut buf: &[u8]) -> DevResult {
+        let block_size = self.block_size();
+        if block_size > MAX_BLOCK_SIZE {
+            return Err(DevError::Unsupported);
+        }
+        let mut block = [0u8; MAX_BLOCK_SIZE];
+        let block = &mut block[..block_size];
+        let mut block_id = offset / block_size as u64;
+        let mut start = (offset % block_size as u64) as usize;
+        while !buf.is_empty() {
+            if start == 0 && buf.len() >= block_size {
+                // whole blocks
+                let len = buf.len() / block_size * block_size;
+                self.write_block(block_id, &buf[..len])?;
+                block_id += (len / block_size) as u64;
+                buf = &buf[len..];
+            } else {
+                // partial block
+                let count = buf.len().min(block_size - start);
+                self.read_block(block_id, block)?;
+                block[start..start + count].copy_from_slice(&buf[..count]);
+                self.write_block(block_id, block)?;
+                block_id += 1;
+                start = 0;
+                buf = &buf[count..];
+            }
+        }
+        Ok(())
+    }
+
     /// Flushes the device to write all pending data to the storage.
     fn flush(&mut self) -> DevResult;
+
+    /// Advises the device that `count` contiguous blocks starting from
+    /// `block_id` will be read soon.
+    ///
+    /// A driver may start fetching them in the background (e.g. via DMA), so
+    /// that the following [`read_block`](Self::read_block) completes faster.
+    /// It is only a hint, the default implementation does nothing.
+    fn prefetch(&mut self, _block_id: u64, _count: usize) -> DevResult {
+        Ok(())
+    }
 }
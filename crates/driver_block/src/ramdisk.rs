@@ -12,7 +12,7 @@
 extern crate alloc;
 
 use crate::BlockDriverOps;
-use alloc::{vec, vec::Vec};
+use alloc::{collections::BTreeSet, vec, vec::Vec};
 use driver_common::{BaseDriverOps, DevError, DevResult, DeviceType};
 
 const BLOCK_SIZE: usize = 512;
@@ -22,6 +22,10 @@ const BLOCK_SIZE: usize = 512;
 pub struct RamDisk {
     size: usize,
     data: Vec<u8>,
+    /// Pseudo access latency of each block, in spin loop iterations.
+    latency: usize,
+    /// Blocks that have been prefetched and can be read without latency.
+    prefetched: BTreeSet<u64>,
 }
 
 impl RamDisk {
@@ -34,6 +38,7 @@ impl RamDisk {
         Self {
             size,
             data: vec![0; size],
+            ..Default::default()
         }
     }
 
@@ -45,13 +50,30 @@ impl RamDisk {
         let size = align_up(buf.len());
         let mut data = vec![0; size];
         data[..buf.len()].copy_from_slice(buf);
-        Self { size, data }
+        Self {
+            size,
+            data,
+            ..Default::default()
+        }
+    }
+
+    /// Sets a pseudo latency (in spin loop iterations) for reading each block
+    /// that has not been prefetched, to mimic a slow storage device.
+    pub fn with_latency(mut self, latency: usize) -> Self {
+        self.latency = latency;
+        self
     }
 
     /// Returns the size of the RAM disk in bytes.
     pub const fn size(&self) -> usize {
         self.size
     }
+
+    fn wait_latency(&self) {
+        for _ in 0..self.latency {
+            core::hint::spin_loop();
+        }
+    }
 }
 
 impl const BaseDriverOps for RamDisk {
@@ -83,6 +105,14 @@ impl BlockDriverOps for RamDisk {
         if buf.len() % BLOCK_SIZE != 0 {
             return Err(DevError::InvalidParam);
         }
+        if self.latency > 0 {
+            let count = (buf.len() / BLOCK_SIZE) as u64;
+            for id in block_id..block_id + count {
+                if !self.prefetched.remove(&id) {
+                    self.wait_latency();
+                }
+            }
+        }
         buf.copy_from_slice(&self.data[offset..offset + buf.len()]);
         Ok(())
     }
@@ -102,6 +132,16 @@ impl BlockDriverOps for RamDisk {
     fn flush(&mut self) -> DevResult {
         Ok(())
     }
+
+    fn prefetch(&mut self, block_id: u64, count: usize) -> DevResult {
+        // The latency of prefetched blocks is assumed to be hidden by an
+        // asynchronous transfer, so just remember them.
+        if self.latency > 0 {
+            let end = (block_id + count as u64).min(self.num_blocks());
+            self.prefetched.extend(block_id..end);
+        }
+        Ok(())
+    }
 }
 
 const fn align_up(val: usize) -> usize {
@@ -0,0 +1,320 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use std::time::{Duration, Instant};
+
+use driver_common::{BaseDriverOps, DevError, DevResult, DeviceType};
+
+use crate::cache::{BlockCache, CacheMode};
+use crate::ramdisk::RamDisk;
+use crate::BlockDriverOps;
+
+const BLOCK_SIZE: usize = 512;
+const NUM_BLOCKS: usize = 64;
+const LATENCY: usize = 100_000;
+
+fn sequential_scan(disk: &mut RamDisk, prefetch: bool) -> Duration {
+    let mut buf = [0u8; BLOCK_SIZE];
+    let start = Instant::now();
+    for id in 0..NUM_BLOCKS as u64 {
+        if prefetch && id % 8 == 0 {
+            disk.prefetch(id, 8).unwrap();
+        }
+        disk.read_block(id, &mut buf).unwrap();
+        assert!(buf.iter().all(|&b| b == id as u8));
+    }
+    start.elapsed()
+}
+
+fn ramdisk_with_pattern() -> RamDisk {
+    let mut data = vec![0u8; NUM_BLOCKS * BLOCK_SIZE];
+    for (id, block) in data.chunks_mut(BLOCK_SIZE).enumerate() {
+        block.fill(id as u8);
+    }
+    RamDisk::from(&data).with_latency(LATENCY)
+}
+
+#[test]
+fn test_prefetch_sequential_scan() {
+    let mut disk = ramdisk_with_pattern();
+    let plain = sequential_scan(&mut disk, false);
+    let prefetched = sequential_scan(&mut disk, true);
+    assert!(
+        prefetched < plain,
+        "prefetched scan ({:?}) is not faster than plain scan ({:?})",
+        prefetched,
+        plain
+    );
+}
+
+#[test]
+fn test_prefetch_out_of_range() {
+    let mut disk = ramdisk_with_pattern();
+    // prefetching past the end of the disk is harmless
+    disk.prefetch(NUM_BLOCKS as u64 - 2, 8).unwrap();
+    let mut buf = [0u8; BLOCK_SIZE];
+    disk.read_block(NUM_BLOCKS as u64 - 1, &mut buf).unwrap();
+    assert!(disk.read_block(NUM_BLOCKS as u64, &mut buf).is_err());
+}
+
+#[test]
+fn test_write_at_partial_block() {
+    let mut disk = RamDisk::from(&[0xaa; 2 * BLOCK_SIZE]);
+    // unaligned writes are rejected by the driver itself
+    assert!(disk.write_block(0, &[0x55; 10]).is_err());
+
+    disk.write_at(5, &[0x55; 10]).unwrap();
+    let mut buf = [0u8; 2 * BLOCK_SIZE];
+    disk.read_block(0, &mut buf).unwrap();
+    assert!(buf[..5].iter().all(|&b| b == 0xaa));
+    assert!(buf[5..15].iter().all(|&b| b == 0x55));
+    assert!(buf[15..].iter().all(|&b| b == 0xaa));
+
+    // spans a block boundary, with whole blocks in between
+    let mut disk = RamDisk::from(&[0xaa; 4 * BLOCK_SIZE]);
+    disk.write_at(BLOCK_SIZE as u64 - 3, &[0x55; BLOCK_SIZE + 6])
+        .unwrap();
+    let mut buf = [0u8; 4 * BLOCK_SIZE];
+    disk.read_block(0, &mut buf).unwrap();
+    let end = 2 * BLOCK_SIZE + 3;
+    assert!(buf[..BLOCK_SIZE - 3].iter().all(|&b| b == 0xaa));
+    assert!(buf[BLOCK_SIZE - 3..end].iter().all(|&b| b == 0x55));
+    assert!(buf[end..].iter().all(|&b| b == 0xaa));
+
+    assert!(disk.write_at(4 * BLOCK_SIZE as u64 - 1, &[0; 2]).is_err());
+}
+
+/// A RAM disk that counts the accesses to it.
+struct CountingDisk {
+    disk: RamDisk,
+    reads: usize,
+    writes: usize,
+}
+
+impl CountingDisk {
+    fn new(num_blocks: usize) -> Self {
+        Self {
+            disk: RamDisk::new(num_blocks * BLOCK_SIZE),
+            reads: 0,
+            writes: 0,
+        }
+    }
+}
+
+impl BaseDriverOps for CountingDisk {
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Block
+    }
+
+    fn device_name(&self) -> &str {
+        "counting"
+    }
+}
+
+impl BlockDriverOps for CountingDisk {
+    fn num_blocks(&self) -> u64 {
+        self.disk.num_blocks()
+    }
+
+    fn block_size(&self) -> usize {
+        BLOCK_SIZE
+    }
+
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> DevResult {
+        self.reads += 1;
+        self.disk.read_block(block_id, buf)
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> DevResult {
+        self.writes += 1;
+        self.disk.write_block(block_id, buf)
+    }
+
+    fn flush(&mut self) -> DevResult {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_cache_write_back() {
+    let mut cache = BlockCache::new(CountingDisk::new(8), 4);
+    cache.write_block(1, &[0x55; BLOCK_SIZE]).unwrap();
+    let mut buf = [0u8; BLOCK_SIZE];
+    cache.read_block(1, &mut buf).unwrap();
+    assert!(buf.iter().all(|&b| b == 0x55));
+    // served by the cache without a device round-trip
+    assert_eq!(cache.inner().reads, 0);
+    assert_eq!(cache.inner().writes, 0);
+
+    cache.flush().unwrap();
+    assert_eq!(cache.inner().writes, 1);
+    cache.flush().unwrap(); // nothing dirty anymore
+    assert_eq!(cache.inner().writes, 1);
+
+    let mut disk = cache.into_inner();
+    disk.read_block(1, &mut buf).unwrap();
+    assert!(buf.iter().all(|&b| b == 0x55));
+}
+
+#[test]
+fn test_cache_batch_and_evict() {
+    let mut cache = BlockCache::new(CountingDisk::new(8), 3);
+    // dirty blocks are written back together on flush, one block per write
+    cache.write_block(2, &[0x11; 2 * BLOCK_SIZE]).unwrap();
+    cache.write_block(5, &[0x22; BLOCK_SIZE]).unwrap();
+    assert_eq!(cache.inner().writes, 0);
+    cache.flush().unwrap();
+    assert_eq!(cache.inner().writes, 3);
+
+    // block 2 is the least recently used, so it's evicted for block 0
+    let mut buf = [0u8; BLOCK_SIZE];
+    cache.read_block(3, &mut buf).unwrap();
+    cache.read_block(5, &mut buf).unwrap();
+    cache.write_block(3, &[0x33; BLOCK_SIZE]).unwrap();
+    cache.read_block(0, &mut buf).unwrap();
+    assert_eq!(cache.inner().reads, 1);
+    assert_eq!(cache.cached_blocks(), 3);
+    cache.read_block(2, &mut buf).unwrap();
+    assert!(buf.iter().all(|&b| b == 0x11));
+    assert_eq!(cache.inner().reads, 2);
+
+    // dirty block 3 is written back when evicted
+    cache.read_block(6, &mut buf).unwrap();
+    cache.read_block(7, &mut buf).unwrap();
+    assert_eq!(cache.inner().writes, 4);
+    let mut disk = cache.into_inner();
+    disk.read_block(3, &mut buf).unwrap();
+    assert!(buf.iter().all(|&b| b == 0x33));
+}
+
+/// A RAM disk that only accepts a single block per request, like some SD
+/// card controllers.
+struct SingleBlockDisk(RamDisk);
+
+impl BaseDriverOps for SingleBlockDisk {
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Block
+    }
+
+    fn device_name(&self) -> &str {
+        "single-block"
+    }
+}
+
+impl BlockDriverOps for SingleBlockDisk {
+    fn num_blocks(&self) -> u64 {
+        self.0.num_blocks()
+    }
+
+    fn block_size(&self) -> usize {
+        BLOCK_SIZE
+    }
+
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> DevResult {
+        if buf.len() != BLOCK_SIZE {
+            return Err(DevError::InvalidParam);
+        }
+        self.0.read_block(block_id, buf)
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> DevResult {
+        if buf.len() != BLOCK_SIZE {
+            return Err(DevError::InvalidParam);
+        }
+        self.0.write_block(block_id, buf)
+    }
+
+    fn flush(&mut self) -> DevResult {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_cache_single_block_device() {
+    let mut cache = BlockCache::new(SingleBlockDisk(RamDisk::new(8 * BLOCK_SIZE)), 4);
+    cache.write_block(1, &[0x11; 4 * BLOCK_SIZE]).unwrap();
+    // the evicted dirty block, then the contiguous dirty blocks are written
+    // back one by one
+    let mut buf = [0u8; BLOCK_SIZE];
+    cache.read_block(6, &mut buf).unwrap();
+    cache.flush().unwrap();
+
+    let mut disk = cache.into_inner();
+    for (id, byte) in [(0, 0), (1, 0x11), (2, 0x11), (4, 0x11), (5, 0)] {
+        disk.read_block(id, &mut buf).unwrap();
+        assert!(buf.iter().all(|&b| b == byte), "block {}", id);
+    }
+}
+
+#[test]
+fn test_cache_modes() {
+    let block = [0x55; BLOCK_SIZE];
+    let mut buf = [0u8; BLOCK_SIZE];
+
+    // write-back: repeated writes to a block are coalesced until flushed
+    let mut cache = BlockCache::new(CountingDisk::new(8), 4);
+    assert_eq!(cache.mode(), CacheMode::WriteBack);
+    for _ in 0..3 {
+        cache.write_block(2, &block).unwrap();
+    }
+    assert_eq!(cache.inner().writes, 0);
+    cache.flush().unwrap();
+    assert_eq!(cache.inner().writes, 1);
+    let mut disk = cache.into_inner();
+    disk.read_block(2, &mut buf).unwrap();
+    assert_eq!(buf, block);
+
+    // write-through: every write reaches the device at once
+    let mode = "write-through".parse().unwrap();
+    let mut cache = BlockCache::new(CountingDisk::new(8), 4).with_mode(mode);
+    for _ in 0..3 {
+        cache.write_block(2, &block).unwrap();
+    }
+    assert_eq!(cache.inner().writes, 3);
+    cache.read_block(2, &mut buf).unwrap();
+    assert_eq!(cache.inner().reads, 0);
+    cache.flush().unwrap();
+    assert_eq!(cache.inner().writes, 3);
+    let mut disk = cache.into_inner();
+    disk.read_block(2, &mut buf).unwrap();
+    assert_eq!(buf, block);
+
+    assert!("write-around".parse::<CacheMode>().is_err());
+}
+
+#[test]
+fn test_cache_bypass() {
+    let mut buf = [0u8; BLOCK_SIZE];
+    let mut cache = BlockCache::new(CountingDisk::new(8), 4);
+    cache.write_block(1, &[0x11; 2 * BLOCK_SIZE]).unwrap();
+    cache.read_block(3, &mut buf).unwrap();
+    assert_eq!(cache.inner().reads, 1);
+
+    // reads go to the device, once the dirty blocks are written back
+    cache.set_bypass(true);
+    assert!(cache.bypass());
+    cache.read_block(2, &mut buf).unwrap();
+    assert!(buf.iter().all(|&b| b == 0x11));
+    assert_eq!((cache.inner().reads, cache.inner().writes), (2, 1));
+    cache.read_block(3, &mut buf).unwrap();
+    assert_eq!((cache.inner().reads, cache.inner().writes), (3, 1));
+
+    // writes go to the device, and drop the cached copies
+    cache.write_block(1, &[0x22; 3 * BLOCK_SIZE]).unwrap();
+    assert_eq!(cache.inner().writes, 2);
+    assert_eq!(cache.cached_blocks(), 0);
+
+    // the blocks written meanwhile are read again from the device
+    cache.set_bypass(false);
+    cache.read_block(3, &mut buf).unwrap();
+    assert!(buf.iter().all(|&b| b == 0x22));
+    assert_eq!(cache.inner().reads, 4);
+    cache.flush().unwrap();
+    assert_eq!(cache.inner().writes, 2);
+}
@@ -25,6 +25,9 @@
 #![no_std]
 #![feature(const_trait_impl)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 /// All supported device types.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum DeviceType {
@@ -73,3 +76,14 @@ pub trait BaseDriverOps: Send + Sync {
     /// The type of the device.
     fn device_type(&self) -> DeviceType;
 }
+
+#[cfg(feature = "alloc")]
+impl<T: BaseDriverOps + ?Sized> BaseDriverOps for alloc::boxed::Box<T> {
+    fn device_name(&self) -> &str {
+        (**self).device_name()
+    }
+
+    fn device_type(&self) -> DeviceType {
+        (**self).device_type()
+    }
+}
@@ -12,7 +12,7 @@ use core::fmt::Debug;
 use core::ops::Deref;
 use core::sync::atomic::{AtomicIsize, Ordering};
 
-use crate::BaseScheduler;
+use crate::{BaseScheduler, SchedPolicy};
 
 /// task for CFS
 pub struct CFSTask<T> {
@@ -203,4 +203,8 @@ impl<T> BaseScheduler for CFScheduler<T> {
             false
         }
     }
+
+    fn set_policy(&mut self, _task: &Self::SchedItem, policy: SchedPolicy, prio: usize) -> bool {
+        policy == SchedPolicy::Normal && prio == 0
+    }
 }
@@ -12,7 +12,7 @@ use core::{fmt::Debug, ops::Deref};
 
 use linked_list::{Adapter, Links, List};
 
-use crate::BaseScheduler;
+use crate::{BaseScheduler, SchedPolicy};
 
 /// A task wrapper for the [`FifoScheduler`].
 ///
@@ -114,4 +114,8 @@ impl<T> BaseScheduler for FifoScheduler<T> {
     fn set_priority(&mut self, _task: &Self::SchedItem, _prio: isize) -> bool {
         false
     }
+
+    fn set_policy(&mut self, _task: &Self::SchedItem, policy: SchedPolicy, prio: usize) -> bool {
+        policy == SchedPolicy::Normal && prio == 0
+    }
 }
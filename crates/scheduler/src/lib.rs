@@ -14,12 +14,15 @@
 //! - [`FifoScheduler`]: FIFO (First-In-First-Out) scheduler (cooperative).
 //! - [`RRScheduler`]: Round-robin scheduler (preemptive).
 //! - [`CFScheduler`]: Completely Fair Scheduler (preemptive).
+//! - [`PrioScheduler`]: Static priority scheduler with FIFO and round-robin
+//!   policies (preemptive).
 
 #![cfg_attr(not(test), no_std)]
 #![feature(const_mut_refs)]
 
 mod cfs;
 mod fifo;
+mod prio;
 mod round_robin;
 
 #[cfg(test)]
@@ -29,8 +32,47 @@ extern crate alloc;
 
 pub use cfs::{CFSTask, CFScheduler};
 pub use fifo::{FifoScheduler, FifoTask};
+pub use prio::{PrioScheduler, PrioTask};
 pub use round_robin::{RRScheduler, RRTask};
 
+/// The lowest static priority of the [`Fifo`](SchedPolicy::Fifo) and
+/// [`RoundRobin`](SchedPolicy::RoundRobin) policies.
+pub const RT_PRIORITY_MIN: usize = 1;
+/// The highest static priority of the [`Fifo`](SchedPolicy::Fifo) and
+/// [`RoundRobin`](SchedPolicy::RoundRobin) policies.
+pub const RT_PRIORITY_MAX: usize = 99;
+
+/// Scheduling policies, with the same values as `SCHED_*` in Linux.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedPolicy {
+    /// The default time-sharing policy, whose static priority is always 0.
+    Normal = 0,
+    /// First-in first-out real-time policy, without time slicing.
+    Fifo = 1,
+    /// Round-robin real-time policy, with time slicing.
+    RoundRobin = 2,
+}
+
+impl SchedPolicy {
+    /// Converts a `SCHED_*` value to the policy.
+    pub const fn try_from_raw(policy: u32) -> Option<Self> {
+        match policy {
+            0 => Some(Self::Normal),
+            1 => Some(Self::Fifo),
+            2 => Some(Self::RoundRobin),
+            _ => None,
+        }
+    }
+
+    const fn from_u8(policy: u8) -> Self {
+        match Self::try_from_raw(policy as u32) {
+            Some(policy) => policy,
+            None => Self::Normal,
+        }
+    }
+}
+
 /// The base scheduler trait that all schedulers should implement.
 ///
 /// All tasks in the scheduler are considered runnable. If a task is go to
@@ -75,4 +117,8 @@ pub trait BaseScheduler {
 
     /// set priority for a task
     fn set_priority(&mut self, task: &Self::SchedItem, prio: isize) -> bool;
+
+    /// Sets the scheduling policy and the static priority of a task. Returns
+    /// `false` if the scheduler does not support them.
+    fn set_policy(&mut self, task: &Self::SchedItem, policy: SchedPolicy, prio: usize) -> bool;
 }
@@ -0,0 +1,221 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use alloc::{collections::VecDeque, sync::Arc};
+use core::fmt::Debug;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicIsize, AtomicU8, AtomicUsize, Ordering};
+
+use crate::{BaseScheduler, SchedPolicy, RT_PRIORITY_MAX, RT_PRIORITY_MIN};
+
+const NUM_LEVELS: usize = RT_PRIORITY_MAX + 1;
+
+/// A task wrapper for the [`PrioScheduler`].
+///
+/// It adds the scheduling policy, the static priority and a time slice
+/// counter to the inner task struct.
+pub struct PrioTask<T, const RR_TIME_SLICE: usize> {
+    inner: T,
+    policy: AtomicU8,
+    priority: AtomicUsize,
+    time_slice: AtomicIsize,
+}
+
+impl<T, const S: usize> PrioTask<T, S> {
+    /// Creates a new [`PrioTask`] with the [`SchedPolicy::Normal`] policy.
+    pub const fn new(inner: T) -> Self {
+        Self {
+            inner,
+            policy: AtomicU8::new(SchedPolicy::Normal as u8),
+            priority: AtomicUsize::new(0),
+            time_slice: AtomicIsize::new(S as isize),
+        }
+    }
+
+    /// Returns the scheduling policy of the task.
+    pub fn policy(&self) -> SchedPolicy {
+        SchedPolicy::from_u8(self.policy.load(Ordering::Acquire))
+    }
+
+    /// Returns the static priority of the task, 0 for normal tasks.
+    pub fn priority(&self) -> usize {
+        self.priority.load(Ordering::Acquire)
+    }
+
+    fn time_slice(&self) -> isize {
+        self.time_slice.load(Ordering::Acquire)
+    }
+
+    fn reset_time_slice(&self) {
+        self.time_slice.store(S as isize, Ordering::Release);
+    }
+
+    /// Returns a reference to the inner task struct.
+    pub const fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T, const S: usize> Deref for PrioTask<T, S> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T: Debug, const S: usize> Debug for PrioTask<T, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", &self.inner)
+    }
+}
+
+/// A preemptive scheduler with static priorities, similar to the real-time
+/// policies of Linux.
+///
+/// Every task has a [`SchedPolicy`] and a static priority:
+///
+/// - [`SchedPolicy::Normal`] tasks, the default, have priority 0 and share
+///   the CPU in round-robin.
+/// - [`SchedPolicy::Fifo`] and [`SchedPolicy::RoundRobin`] tasks have a
+///   priority from [`RT_PRIORITY_MIN`] to [`RT_PRIORITY_MAX`], the higher the
+///   more urgent. A FIFO task runs until it blocks or yields, while a
+///   round-robin task is also preempted when its time slice of
+///   `RR_TIME_SLICE` ticks is used up, in favor of the tasks of the same
+///   priority.
+///
+/// The runnable task with the highest priority is always picked first. When a
+/// timer tick finds a task with a higher priority than the current one in the
+/// ready queues, the current task is preempted.
+///
+/// There is a ready queue per priority, and a bitmap of the non-empty ones.
+pub struct PrioScheduler<T, const RR_TIME_SLICE: usize> {
+    ready_queues: [VecDeque<Arc<PrioTask<T, RR_TIME_SLICE>>>; NUM_LEVELS],
+    ready_bitmap: u128,
+}
+
+impl<T, const S: usize> PrioScheduler<T, S> {
+    /// Creates a new empty [`PrioScheduler`].
+    pub fn new() -> Self {
+        Self {
+            ready_queues: core::array::from_fn(|_| VecDeque::new()),
+            ready_bitmap: 0,
+        }
+    }
+    /// get the name of scheduler
+    pub fn scheduler_name() -> &'static str {
+        "Priority"
+    }
+
+    /// Returns the highest priority of the ready tasks.
+    fn highest_ready(&self) -> Option<usize> {
+        (self.ready_bitmap != 0).then(|| 127 - self.ready_bitmap.leading_zeros() as usize)
+    }
+
+    fn push(&mut self, task: Arc<PrioTask<T, S>>, front: bool) {
+        let prio = task.priority();
+        if front {
+            self.ready_queues[prio].push_front(task);
+        } else {
+            self.ready_queues[prio].push_back(task);
+        }
+        self.ready_bitmap |= 1 << prio;
+    }
+
+    fn remove_at(&mut self, prio: usize, idx: usize) -> Option<Arc<PrioTask<T, S>>> {
+        let queue = &mut self.ready_queues[prio];
+        let task = queue.remove(idx);
+        if queue.is_empty() {
+            self.ready_bitmap &= !(1 << prio);
+        }
+        task
+    }
+
+    fn position(&self, task: &Arc<PrioTask<T, S>>) -> Option<usize> {
+        // TODO: more efficient
+        self.ready_queues[task.priority()]
+            .iter()
+            .position(|t| Arc::ptr_eq(t, task))
+    }
+}
+
+impl<T, const S: usize> Default for PrioScheduler<T, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const S: usize> BaseScheduler for PrioScheduler<T, S> {
+    type SchedItem = Arc<PrioTask<T, S>>;
+
+    fn init(&mut self) {}
+
+    fn add_task(&mut self, task: Self::SchedItem) {
+        self.push(task, false);
+    }
+
+    fn remove_task(&mut self, task: &Self::SchedItem) -> Option<Self::SchedItem> {
+        let idx = self.position(task)?;
+        self.remove_at(task.priority(), idx)
+    }
+
+    fn pick_next_task(&mut self) -> Option<Self::SchedItem> {
+        let prio = self.highest_ready()?;
+        self.remove_at(prio, 0)
+    }
+
+    fn put_prev_task(&mut self, prev: Self::SchedItem, preempt: bool) {
+        // A preempted task keeps its place at the head of its queue, unless it
+        // has used up its time slice.
+        if preempt && (prev.policy() == SchedPolicy::Fifo || prev.time_slice() > 0) {
+            self.push(prev, true)
+        } else {
+            prev.reset_time_slice();
+            self.push(prev, false)
+        }
+    }
+
+    fn task_tick(&mut self, current: &Self::SchedItem) -> bool {
+        let expired = match current.policy() {
+            SchedPolicy::Fifo => false,
+            _ => current.time_slice.fetch_sub(1, Ordering::Release) <= 1,
+        };
+        let preempted = self
+            .highest_ready()
+            .is_some_and(|prio| prio > current.priority());
+        expired || preempted
+    }
+
+    fn set_priority(&mut self, _task: &Self::SchedItem, _prio: isize) -> bool {
+        false
+    }
+
+    fn set_policy(&mut self, task: &Self::SchedItem, policy: SchedPolicy, prio: usize) -> bool {
+        let valid = match policy {
+            SchedPolicy::Normal => prio == 0,
+            SchedPolicy::Fifo | SchedPolicy::RoundRobin => {
+                (RT_PRIORITY_MIN..=RT_PRIORITY_MAX).contains(&prio)
+            }
+        };
+        if !valid {
+            return false;
+        }
+        // Move a ready task to the queue of its new priority.
+        let ready = self
+            .position(task)
+            .and_then(|idx| self.remove_at(task.priority(), idx));
+        task.policy.store(policy as u8, Ordering::Release);
+        task.priority.store(prio, Ordering::Release);
+        task.reset_time_slice();
+        if let Some(task) = ready {
+            self.push(task, false);
+        }
+        true
+    }
+}
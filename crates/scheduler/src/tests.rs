@@ -91,3 +91,76 @@ macro_rules! def_test_sched {
 def_test_sched!(fifo, FifoScheduler::<usize>, FifoTask::<usize>);
 def_test_sched!(rr, RRScheduler::<usize, 5>, RRTask::<usize, 5>);
 def_test_sched!(cfs, CFScheduler::<usize>, CFSTask::<usize>);
+def_test_sched!(prio, PrioScheduler::<usize, 5>, PrioTask::<usize, 5>);
+
+mod prio_policy {
+    use crate::*;
+    use alloc::sync::Arc;
+
+    type Task = Arc<PrioTask<usize, 5>>;
+
+    #[test]
+    fn test_highest_first() {
+        let mut scheduler = PrioScheduler::<usize, 5>::new();
+        let tasks: Vec<Task> = (0..4).map(|i| Arc::new(PrioTask::new(i))).collect();
+        for t in &tasks {
+            scheduler.add_task(t.clone());
+        }
+        assert!(scheduler.set_policy(&tasks[1], SchedPolicy::RoundRobin, 10));
+        assert!(scheduler.set_policy(&tasks[2], SchedPolicy::Fifo, 20));
+        assert!(scheduler.set_policy(&tasks[3], SchedPolicy::Fifo, 10));
+
+        let order: Vec<usize> = core::iter::from_fn(|| scheduler.pick_next_task())
+            .map(|t| *t.inner())
+            .collect();
+        assert_eq!(order, [2, 1, 3, 0]);
+    }
+
+    #[test]
+    fn test_invalid_priority() {
+        let mut scheduler = PrioScheduler::<usize, 5>::new();
+        let task: Task = Arc::new(PrioTask::new(0));
+        assert!(!scheduler.set_policy(&task, SchedPolicy::Normal, 1));
+        assert!(!scheduler.set_policy(&task, SchedPolicy::Fifo, 0));
+        assert!(!scheduler.set_policy(&task, SchedPolicy::RoundRobin, RT_PRIORITY_MAX + 1));
+        assert_eq!(task.policy(), SchedPolicy::Normal);
+        assert_eq!(task.priority(), 0);
+    }
+
+    #[test]
+    fn test_tick_preempt() {
+        let mut scheduler = PrioScheduler::<usize, 5>::new();
+        let low: Task = Arc::new(PrioTask::new(0));
+        let high: Task = Arc::new(PrioTask::new(1));
+        assert!(scheduler.set_policy(&low, SchedPolicy::Fifo, 1));
+        assert!(scheduler.set_policy(&high, SchedPolicy::Fifo, 2));
+
+        // A FIFO task is never preempted by ticks alone.
+        for _ in 0..10 {
+            assert!(!scheduler.task_tick(&low));
+        }
+        // But it is as soon as a higher priority task is ready.
+        scheduler.add_task(high.clone());
+        assert!(scheduler.task_tick(&low));
+        scheduler.put_prev_task(low.clone(), true);
+        assert!(Arc::ptr_eq(&scheduler.pick_next_task().unwrap(), &high));
+        assert!(Arc::ptr_eq(&scheduler.pick_next_task().unwrap(), &low));
+    }
+
+    #[test]
+    fn test_rr_time_slice() {
+        let mut scheduler = PrioScheduler::<usize, 5>::new();
+        let a: Task = Arc::new(PrioTask::new(0));
+        let b: Task = Arc::new(PrioTask::new(1));
+        assert!(scheduler.set_policy(&a, SchedPolicy::RoundRobin, 50));
+        assert!(scheduler.set_policy(&b, SchedPolicy::RoundRobin, 50));
+        scheduler.add_task(b.clone());
+
+        for _ in 0..4 {
+            assert!(!scheduler.task_tick(&a));
+        }
+        assert!(scheduler.task_tick(&a));
+        scheduler.put_prev_task(a.clone(), true);
+        assert!(Arc::ptr_eq(&scheduler.pick_next_task().unwrap(), &b));
+    }
+}
@@ -25,6 +25,7 @@ mod page;
 use allocator::{AllocResult, BaseAllocator, BitmapPageAllocator, ByteAllocator, PageAllocator};
 use core::alloc::{GlobalAlloc, Layout};
 use core::ptr::NonNull;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use spinlock::SpinNoIrq;
 
 const PAGE_SIZE: usize = 0x1000;
@@ -56,6 +57,10 @@ cfg_if::cfg_if! {
 pub struct GlobalAllocator {
     balloc: SpinNoIrq<DefaultByteAllocator>,
     palloc: SpinNoIrq<BitmapPageAllocator<PAGE_SIZE>>,
+    /// bytes added to the byte allocator directly by [`GlobalAllocator::add_memory`]
+    added_bytes: AtomicUsize,
+    /// the highest number of bytes ever allocated in the byte allocator
+    peak_bytes: AtomicUsize,
 }
 
 impl GlobalAllocator {
@@ -64,6 +69,8 @@ impl GlobalAllocator {
         Self {
             balloc: SpinNoIrq::new(DefaultByteAllocator::new()),
             palloc: SpinNoIrq::new(BitmapPageAllocator::new()),
+            added_bytes: AtomicUsize::new(0),
+            peak_bytes: AtomicUsize::new(0),
         }
     }
 
@@ -99,7 +106,9 @@ impl GlobalAllocator {
     ///
     /// It will add the whole region to the byte allocator.
     pub fn add_memory(&self, start_vaddr: usize, size: usize) -> AllocResult {
-        self.balloc.lock().add_memory(start_vaddr, size)
+        self.balloc.lock().add_memory(start_vaddr, size)?;
+        self.added_bytes.fetch_add(size, Ordering::Relaxed);
+        Ok(())
     }
 
     /// Allocate arbitrary number of bytes. Returns the left bound of the
@@ -116,6 +125,8 @@ impl GlobalAllocator {
         let mut balloc = self.balloc.lock();
         loop {
             if let Ok(ptr) = balloc.alloc(layout) {
+                self.peak_bytes
+                    .fetch_max(balloc.used_bytes(), Ordering::Relaxed);
                 return Ok(ptr);
             } else {
                 let old_size = balloc.total_bytes();
@@ -171,6 +182,12 @@ impl GlobalAllocator {
         self.balloc.lock().used_bytes()
     }
 
+    /// Returns the highest number of bytes allocated in the byte allocator at
+    /// the same time since boot.
+    pub fn peak_used_bytes(&self) -> usize {
+        self.peak_bytes.load(Ordering::Relaxed)
+    }
+
     /// Returns the number of available bytes in the byte allocator.
     pub fn available_bytes(&self) -> usize {
         self.balloc.lock().available_bytes()
@@ -185,6 +202,25 @@ impl GlobalAllocator {
     pub fn available_pages(&self) -> usize {
         self.palloc.lock().available_pages()
     }
+
+    /// Returns the size of all the memory managed by the allocator, in bytes.
+    ///
+    /// The heap of the byte allocator is allocated from the page allocator,
+    /// so only the regions added by [`add_memory`] are counted besides the
+    /// pages.
+    ///
+    /// [`add_memory`]: GlobalAllocator::add_memory
+    pub fn total_memory(&self) -> usize {
+        self.palloc.lock().total_pages() * PAGE_SIZE + self.added_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Returns the size of the memory not allocated by anyone, in bytes.
+    ///
+    /// It counts the available pages and the available bytes in the heap of
+    /// the byte allocator, which lies in the used pages.
+    pub fn free_memory(&self) -> usize {
+        self.available_pages() * PAGE_SIZE + self.available_bytes()
+    }
 }
 
 unsafe impl GlobalAlloc for GlobalAllocator {
@@ -134,6 +134,33 @@ pub trait LogIf {
     ///
     /// Returns [`None`] if you don't want to show the task ID in the log.
     fn current_task_id() -> Option<u64>;
+
+    /// Gets the name of the current task, padded with NUL bytes.
+    ///
+    /// Returns [`None`] if you don't want to show the task name in the log.
+    fn current_task_name() -> Option<[u8; TASK_NAME_LEN]>;
+}
+
+/// Size of the buffer holding a task name in [`LogIf::current_task_name`].
+pub const TASK_NAME_LEN: usize = 16;
+
+/// The task ID shown in the log, followed by the task name if any.
+#[cfg_attr(feature = "std", allow(dead_code))]
+struct TaskTag(u64, Option<[u8; TASK_NAME_LEN]>);
+
+impl fmt::Display for TaskTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)?;
+        if let Some(name) = &self.1 {
+            let len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+            if let Ok(name) = core::str::from_utf8(&name[..len]) {
+                if !name.is_empty() {
+                    write!(f, ":{}", name)?;
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 struct Logger;
@@ -196,7 +223,7 @@ impl Log for Logger {
                             now.as_secs(),
                             now.subsec_micros(),
                             cpu_id = cpu_id,
-                            tid = tid,
+                            tid = TaskTag(tid, call_interface!(LogIf::current_task_name)),
                             path = path,
                             line = line,
                             args = with_color!(args_color, "{}", record.args()),
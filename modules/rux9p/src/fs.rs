@@ -98,6 +98,10 @@ impl VfsOps for _9pFileSystem {
     fn root_dir(&self) -> VfsNodeRef {
         self.root.clone()
     }
+
+    fn fs_type(&self) -> &str {
+        "9p"
+    }
 }
 
 /// The directory node in the 9P filesystem.
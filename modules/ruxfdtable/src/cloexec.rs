@@ -0,0 +1,49 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Close-on-exec flags of the file descriptors.
+//!
+//! A file descriptor is closed by `execve` if its flag is set, by `O_CLOEXEC`
+//! when it is opened or by `FD_CLOEXEC`. The flag of a file descriptor is
+//! cleared when it is closed, so a new one starts without it.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::RUX_FILE_LIMIT;
+
+#[allow(clippy::declare_interior_mutable_const)]
+const CLEAR: AtomicU64 = AtomicU64::new(0);
+
+/// One bit for each fd, indexed like `FD_TABLE`.
+static CLOEXEC: [AtomicU64; RUX_FILE_LIMIT / 64] = [CLEAR; RUX_FILE_LIMIT / 64];
+
+/// Sets or clears the close-on-exec flag of `fd`.
+pub fn set_cloexec(fd: usize, cloexec: bool) {
+    if let Some(word) = CLOEXEC.get(fd / 64) {
+        let bit = 1 << (fd % 64);
+        if cloexec {
+            word.fetch_or(bit, Ordering::AcqRel);
+        } else {
+            word.fetch_and(!bit, Ordering::AcqRel);
+        }
+    }
+}
+
+/// Returns whether `fd` is to be closed by `execve`.
+pub fn is_cloexec(fd: usize) -> bool {
+    CLOEXEC
+        .get(fd / 64)
+        .is_some_and(|word| word.load(Ordering::Acquire) & (1 << (fd % 64)) != 0)
+}
+
+/// Returns the file descriptors to be closed by `execve`, in increasing
+/// order.
+pub fn cloexec_fds() -> impl Iterator<Item = usize> {
+    (0..RUX_FILE_LIMIT).filter(|&fd| is_cloexec(fd))
+}
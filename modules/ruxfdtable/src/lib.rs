@@ -11,14 +11,21 @@
 #![no_std]
 extern crate alloc;
 use alloc::sync::Arc;
+use core::ffi::c_int;
 use core::marker::Send;
 use core::marker::Sync;
 
-use axerrno::LinuxResult;
+use axerrno::{LinuxError, LinuxResult};
 use axio::PollState;
 use flatten_objects::FlattenObjects;
 use spin::RwLock;
 
+mod cloexec;
+mod stats;
+
+pub use cloexec::{cloexec_fds, is_cloexec, set_cloexec};
+pub use stats::{count_read, count_write, fd_stats, reset_stats, FdStats};
+
 ///Rust version for struct timespec in ctypes. Represents a high-resolution time specification.
 pub struct RuxTimeSpec {
     /// Whole seconds part of the timespec.
@@ -110,6 +117,28 @@ pub trait FileLike: Send + Sync {
     /// Returns the number of bytes written on success.
     fn write(&self, buf: &[u8]) -> LinuxResult<usize>;
 
+    /// Writes data from the provided buffers to the file-like object, one
+    /// after another, like `writev`.
+    ///
+    /// Returns the number of bytes written on success. The default
+    /// implementation writes each buffer in turn, and stops at the first one
+    /// not written entirely.
+    fn write_vectored(&self, bufs: &[&[u8]]) -> LinuxResult<usize> {
+        let mut len = 0;
+        for buf in bufs {
+            let n = match self.write(buf) {
+                Ok(n) => n,
+                Err(_) if len > 0 => break, // report the data written first
+                Err(e) => return Err(e),
+            };
+            len += n;
+            if n < buf.len() {
+                break;
+            }
+        }
+        Ok(len)
+    }
+
     /// Flushes any buffered data to the file-like object.
     fn flush(&self) -> LinuxResult;
 
@@ -124,6 +153,37 @@ pub trait FileLike: Send + Sync {
 
     /// Sets or clears the non-blocking I/O mode for the file-like object.
     fn set_nonblocking(&self, nonblocking: bool) -> LinuxResult;
+
+    /// Returns the current offset of the file-like object, e.g. for
+    /// `fdinfo`. Objects without one, like sockets and pipes, return 0.
+    fn pos(&self) -> u64 {
+        0
+    }
+
+    /// Truncates or extends the file-like object to `size` bytes.
+    ///
+    /// Objects which can not be resized, like sockets and pipes, return
+    /// `EINVAL`.
+    fn truncate(&self, _size: u64) -> LinuxResult {
+        Err(LinuxError::EINVAL)
+    }
+
+    /// Allocates the storage of the `len` bytes at `offset` of the file-like
+    /// object, extending it with zeros if it ends before.
+    ///
+    /// Objects which are not regular files, like sockets and pipes, return
+    /// `ENODEV`.
+    fn fallocate(&self, _offset: u64, _len: u64) -> LinuxResult {
+        Err(LinuxError::ENODEV)
+    }
+
+    /// Performs the I/O control operation `cmd`, whose argument `arg` is
+    /// usually the address of a buffer, like `ioctl`.
+    ///
+    /// Objects which are not devices, like regular files, return `ENOTTY`.
+    fn ioctl(&self, _cmd: usize, _arg: usize) -> LinuxResult<c_int> {
+        Err(LinuxError::ENOTTY)
+    }
 }
 /// Maximum number of files per process
 pub const RUX_FILE_LIMIT: usize = 1024;
@@ -0,0 +1,132 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! I/O counters of the file descriptors, for diagnostics.
+//!
+//! The counters are only kept with the `stats` feature. Without it, counting
+//! does nothing and the counters always read as zero.
+
+/// I/O totals of a file descriptor since it was opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FdStats {
+    /// Number of bytes read.
+    pub bytes_read: u64,
+    /// Number of bytes written.
+    pub bytes_written: u64,
+    /// Number of successful read operations.
+    pub reads: u64,
+    /// Number of successful write operations.
+    pub writes: u64,
+}
+
+#[cfg(feature = "stats")]
+mod counters {
+    use super::FdStats;
+    use crate::RUX_FILE_LIMIT;
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    pub(super) struct Counters {
+        bytes_read: AtomicU64,
+        bytes_written: AtomicU64,
+        reads: AtomicU64,
+        writes: AtomicU64,
+    }
+
+    impl Counters {
+        const fn new() -> Self {
+            Self {
+                bytes_read: AtomicU64::new(0),
+                bytes_written: AtomicU64::new(0),
+                reads: AtomicU64::new(0),
+                writes: AtomicU64::new(0),
+            }
+        }
+
+        pub(super) fn add_read(&self, bytes: usize) {
+            self.bytes_read.fetch_add(bytes as u64, Ordering::Relaxed);
+            self.reads.fetch_add(1, Ordering::Relaxed);
+        }
+
+        pub(super) fn add_write(&self, bytes: usize) {
+            self.bytes_written
+                .fetch_add(bytes as u64, Ordering::Relaxed);
+            self.writes.fetch_add(1, Ordering::Relaxed);
+        }
+
+        pub(super) fn load(&self) -> FdStats {
+            FdStats {
+                bytes_read: self.bytes_read.load(Ordering::Relaxed),
+                bytes_written: self.bytes_written.load(Ordering::Relaxed),
+                reads: self.reads.load(Ordering::Relaxed),
+                writes: self.writes.load(Ordering::Relaxed),
+            }
+        }
+
+        pub(super) fn reset(&self) {
+            self.bytes_read.store(0, Ordering::Relaxed);
+            self.bytes_written.store(0, Ordering::Relaxed);
+            self.reads.store(0, Ordering::Relaxed);
+            self.writes.store(0, Ordering::Relaxed);
+        }
+    }
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: Counters = Counters::new();
+
+    /// Counters of each fd, indexed like `FD_TABLE`.
+    static COUNTERS: [Counters; RUX_FILE_LIMIT] = [ZERO; RUX_FILE_LIMIT];
+
+    pub(super) fn get(fd: usize) -> Option<&'static Counters> {
+        COUNTERS.get(fd)
+    }
+}
+
+/// Counts a read of `bytes` bytes on `fd`.
+#[inline]
+pub fn count_read(fd: usize, bytes: usize) {
+    #[cfg(feature = "stats")]
+    if let Some(counters) = counters::get(fd) {
+        counters.add_read(bytes);
+    }
+    #[cfg(not(feature = "stats"))]
+    let _ = (fd, bytes);
+}
+
+/// Counts a write of `bytes` bytes on `fd`.
+#[inline]
+pub fn count_write(fd: usize, bytes: usize) {
+    #[cfg(feature = "stats")]
+    if let Some(counters) = counters::get(fd) {
+        counters.add_write(bytes);
+    }
+    #[cfg(not(feature = "stats"))]
+    let _ = (fd, bytes);
+}
+
+/// Returns the I/O totals of `fd`.
+pub fn fd_stats(fd: usize) -> FdStats {
+    #[cfg(feature = "stats")]
+    if let Some(counters) = counters::get(fd) {
+        return counters.load();
+    }
+    #[cfg(not(feature = "stats"))]
+    let _ = fd;
+    FdStats::default()
+}
+
+/// Clears the counters of `fd`, when it is closed.
+#[inline]
+pub fn reset_stats(fd: usize) {
+    #[cfg(feature = "stats")]
+    if let Some(counters) = counters::get(fd) {
+        counters.reset();
+    }
+    #[cfg(not(feature = "stats"))]
+    let _ = fd;
+}
@@ -32,9 +32,10 @@ pub struct DirEntry<'a> {
 }
 
 /// A builder used to create directories in various manners.
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct DirBuilder {
     recursive: bool,
+    mode: u32,
 }
 
 impl<'a> ReadDir<'a> {
@@ -129,7 +130,10 @@ impl DirBuilder {
     /// Creates a new set of options with default mode/security settings for all
     /// platforms and also non-recursive.
     pub fn new() -> Self {
-        Self { recursive: false }
+        Self {
+            recursive: false,
+            mode: 0o777,
+        }
     }
 
     /// Indicates that directories should be created recursively, creating all
@@ -140,19 +144,36 @@ impl DirBuilder {
         self
     }
 
+    /// Sets the permission mode of the new directories, before the umask is
+    /// applied. It is `0o777` by default.
+    pub fn mode(&mut self, mode: u32) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
     /// Creates the specified directory with the options configured in this
     /// builder.
     pub fn create(&self, path: &str) -> Result<()> {
         if self.recursive {
             self.create_dir_all(path)
         } else {
-            crate::root::create_dir(None, path)
+            crate::root::create_dir(None, path, self.perm())
         }
     }
 
     /// Recursively create a directory and all of its parent components if they
     /// are missing.
     pub fn create_dir_all(&self, path: &str) -> Result<()> {
-        crate::root::create_dir_all(None, path)
+        crate::root::create_dir_all(None, path, self.perm())
+    }
+
+    fn perm(&self) -> fops::FilePerm {
+        fops::FilePerm::from_bits_truncate(self.mode as u16)
+    }
+}
+
+impl Default for DirBuilder {
+    fn default() -> Self {
+        Self::new()
     }
 }
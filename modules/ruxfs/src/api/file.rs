@@ -73,6 +73,13 @@ impl OpenOptions {
         self
     }
 
+    /// Sets the permission mode of a new file, before the umask is applied.
+    /// It is `0o666` by default.
+    pub fn mode(&mut self, mode: u32) -> &mut Self {
+        self.0.mode(mode);
+        self
+    }
+
     /// Opens a file at `path` with the options specified by `self`.
     pub fn open(&self, path: &str) -> Result<File> {
         fops::File::open(path, &self.0).map(|inner| File { inner })
@@ -35,6 +35,9 @@ pub fn current_dir() -> io::Result<String> {
 }
 
 /// Changes the current working directory to the specified path.
+///
+/// With `multitask`, each task has its own current directory, which the tasks
+/// it creates afterwards start in.
 pub fn set_current_dir(path: &str) -> io::Result<()> {
     crate::root::set_current_dir(path)
 }
@@ -89,6 +92,11 @@ pub fn remove_file(path: &str) -> io::Result<()> {
     crate::root::remove_file(None, path)
 }
 
+/// Truncates or extends a file to `len` bytes, given its path.
+pub fn truncate(path: &str, len: u64) -> io::Result<()> {
+    crate::fops::truncate(path, len)
+}
+
 /// Rename a file or directory to a new name.
 /// Delete the original file if `old` already exists.
 ///
@@ -7,7 +7,6 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use alloc::format;
 use alloc::string::String;
 
 #[cfg(target_arch = "x86_64")]
@@ -28,21 +27,20 @@ pub fn get_cpuinfo() -> String {
     }
 }
 
+/// Returns the content of `/proc/meminfo`, from the live statistics of the
+/// global allocator, or nothing without the `alloc` feature.
 pub fn get_meminfo() -> String {
     #[cfg(feature = "alloc")]
     {
-        use core::ffi::c_ulong;
         let allocator = axalloc::global_allocator();
-        let freeram = (allocator.available_bytes()
-            + allocator.available_pages() * memory_addr::PAGE_SIZE_4K)
-            as c_ulong;
-        let totalram = freeram + allocator.used_bytes() as c_ulong;
-
-        let mut meminfo = String::new();
-        meminfo.push_str(format!("MemTotal:       {:8}\n", totalram).as_ref());
-        meminfo.push_str(format!("MemFree:        {:8}\n", freeram).as_ref());
-
-        meminfo
+        let total_kb = allocator.total_memory() / 1024;
+        let free_kb = allocator.free_memory() / 1024;
+        alloc::format!(
+            "MemTotal:       {:8} kB\nMemFree:        {:8} kB\nMemAvailable:   {:8} kB\n",
+            total_kb,
+            free_kb,
+            free_kb
+        )
     }
     #[cfg(not(feature = "alloc"))]
     String::new()
@@ -0,0 +1,87 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Cache of the nodes found by path lookups.
+//!
+//! The nodes are keyed by their canonical absolute path, and the least
+//! recently used one is evicted when the cache is full. Only existing nodes
+//! are cached, so creating a file does not need to invalidate anything, but
+//! removing or renaming a path drops it and all the paths below it.
+
+use alloc::{collections::VecDeque, string::String};
+use axfs_vfs::VfsNodeRef;
+use axsync::Mutex;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Maximum number of cached nodes.
+const CACHE_CAPACITY: usize = 64;
+
+/// Cached nodes, the most recently used first.
+///
+/// TODO: use a hash map if the capacity grows.
+static NODE_CACHE: Mutex<VecDeque<(String, VfsNodeRef)>> = Mutex::new(VecDeque::new());
+
+static HITS: AtomicUsize = AtomicUsize::new(0);
+static MISSES: AtomicUsize = AtomicUsize::new(0);
+
+/// Statistics of the path lookup cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LookupStats {
+    /// Number of lookups served from the cache.
+    pub hits: usize,
+    /// Number of lookups which traversed the filesystems.
+    pub misses: usize,
+}
+
+/// Returns the statistics of the path lookup cache since boot.
+pub fn lookup_stats() -> LookupStats {
+    LookupStats {
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+    }
+}
+
+/// Returns the cached node at the canonical absolute path `path`.
+pub(crate) fn get(path: &str) -> Option<VfsNodeRef> {
+    let mut cache = NODE_CACHE.lock();
+    let idx = cache.iter().position(|(p, _)| p == path)?;
+    let entry = cache.remove(idx)?;
+    let node = entry.1.clone();
+    cache.push_front(entry);
+    HITS.fetch_add(1, Ordering::Relaxed);
+    Some(node)
+}
+
+/// Caches the node found at the canonical absolute path `path`.
+pub(crate) fn insert(path: String, node: VfsNodeRef) {
+    let mut cache = NODE_CACHE.lock();
+    cache.retain(|(p, _)| *p != path);
+    if cache.len() >= CACHE_CAPACITY {
+        cache.pop_back();
+    }
+    cache.push_front((path, node));
+}
+
+/// Counts a lookup which traversed the filesystems.
+pub(crate) fn count_miss() {
+    MISSES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Drops the canonical absolute path `path` and the paths below it.
+pub(crate) fn invalidate(path: &str) {
+    let prefix = path.trim_end_matches('/');
+    NODE_CACHE.lock().retain(|(p, _)| {
+        !(p == prefix || (p.starts_with(prefix) && p[prefix.len()..].starts_with('/')))
+    });
+}
+
+/// Drops all the cached nodes.
+pub(crate) fn clear() {
+    NODE_CACHE.lock().clear();
+}
@@ -7,31 +7,104 @@
  *   See the Mulan PSL v2 for more details.
  */
 
+use alloc::{sync::Arc, vec::Vec};
+use axsync::Mutex;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use driver_block::cache::{BlockCache, CacheMode};
 use ruxdriver::prelude::*;
 
 const BLOCK_SIZE: usize = 512;
 
+/// Number of blocks to prefetch when a sequential read is detected.
+const READ_AHEAD_BLOCKS: usize = 16;
+
+/// Total size of the blocks cached by all the disks.
+static CACHED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the size of the blocks cached in memory by all the disks, in bytes.
+pub fn cached_bytes() -> usize {
+    CACHED_BYTES.load(Ordering::Relaxed)
+}
+
+type CachedDevice = Arc<Mutex<BlockCache<AxBlockDevice>>>;
+
+/// The block caches of all the disks, written back by [`sync`].
+static BLOCK_CACHES: Mutex<Vec<CachedDevice>> = Mutex::new(Vec::new());
+
+/// Writes the dirty blocks of all the disks back to the devices, e.g. before
+/// the system shuts down.
+pub fn sync() -> DevResult {
+    for dev in BLOCK_CACHES.lock().iter() {
+        dev.lock().flush()?;
+    }
+    Ok(())
+}
+
+/// Number of direct accesses in progress, during which the disks bypass
+/// their block caches.
+static DIRECT_IO: AtomicUsize = AtomicUsize::new(0);
+
+/// Makes the disks bypass their block caches until it is dropped, during an
+/// access to a file opened with [`direct`](crate::fops::OpenOptions::direct).
+///
+/// A disk does not know which file a block belongs to, so the other accesses
+/// in progress bypass the caches as well, which is slower but still coherent.
+pub(crate) struct DirectIo;
+
+impl DirectIo {
+    pub(crate) fn begin() -> Self {
+        DIRECT_IO.fetch_add(1, Ordering::Relaxed);
+        Self
+    }
+}
+
+impl Drop for DirectIo {
+    fn drop(&mut self) {
+        DIRECT_IO.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 /// A disk device with a cursor.
+///
+/// Blocks are cached in memory, up to `ruxconfig::BLOCK_CACHE_SIZE` of them.
+/// In the default write-back `ruxconfig::BLOCK_CACHE_MODE`, dirty ones are
+/// written back on [`do_flush`](Self::do_flush).
 pub struct Disk {
     block_id: u64,
     offset: usize,
-    dev: AxBlockDevice,
+    dev: CachedDevice,
+    /// The block that a sequential read is expected to access next.
+    next_seq_block: u64,
+    /// End of the range that has been prefetched (exclusive).
+    read_ahead_end: u64,
+    /// Number of cached blocks accounted in `CACHED_BYTES`.
+    cached_blocks: usize,
 }
 
 impl Disk {
     /// Create a new disk.
     pub fn new(dev: AxBlockDevice) -> Self {
         assert_eq!(BLOCK_SIZE, dev.block_size());
+        let mode: CacheMode = ruxconfig::BLOCK_CACHE_MODE
+            .parse()
+            .expect("invalid block-cache-mode");
+        let dev = Arc::new(Mutex::new(
+            BlockCache::new(dev, ruxconfig::BLOCK_CACHE_SIZE).with_mode(mode),
+        ));
+        BLOCK_CACHES.lock().push(dev.clone());
         Self {
             block_id: 0,
             offset: 0,
             dev,
+            next_seq_block: u64::MAX,
+            read_ahead_end: 0,
+            cached_blocks: 0,
         }
     }
 
     /// Get the size of the disk.
     pub fn size(&self) -> u64 {
-        self.dev.num_blocks() * BLOCK_SIZE as u64
+        self.dev.lock().num_blocks() * BLOCK_SIZE as u64
     }
 
     /// Get the position of the cursor.
@@ -45,11 +118,55 @@ impl Disk {
         self.offset = pos as usize % BLOCK_SIZE;
     }
 
+    /// Hints the device to prefetch the following blocks if the current read
+    /// continues a sequential scan.
+    fn read_ahead(&mut self) {
+        let block_id = self.block_id;
+        if block_id == self.next_seq_block && block_id + 1 >= self.read_ahead_end {
+            let start = (block_id + 1).max(self.read_ahead_end);
+            let mut dev = self.dev.lock();
+            let count = READ_AHEAD_BLOCKS.min(dev.num_blocks().saturating_sub(start) as usize);
+            if count > 0 && dev.prefetch(start, count).is_ok() {
+                self.read_ahead_end = start + count as u64;
+            }
+        }
+        self.next_seq_block = block_id + 1;
+    }
+
+    /// Makes the cache bypassed while a direct access is in progress, and
+    /// returns whether it is.
+    fn update_bypass(&mut self) -> bool {
+        let bypass = DIRECT_IO.load(Ordering::Relaxed) != 0;
+        self.dev.lock().set_bypass(bypass);
+        bypass
+    }
+
+    /// Accounts the blocks cached since the last call in `CACHED_BYTES`.
+    fn update_cached_bytes(&mut self) {
+        let cached_blocks = self.dev.lock().cached_blocks();
+        if cached_blocks > self.cached_blocks {
+            CACHED_BYTES.fetch_add(
+                (cached_blocks - self.cached_blocks) * BLOCK_SIZE,
+                Ordering::Relaxed,
+            );
+        } else {
+            CACHED_BYTES.fetch_sub(
+                (self.cached_blocks - cached_blocks) * BLOCK_SIZE,
+                Ordering::Relaxed,
+            );
+        }
+        self.cached_blocks = cached_blocks;
+    }
+
     /// Read within one block, returns the number of bytes read.
     pub fn read_one(&mut self, buf: &mut [u8]) -> DevResult<usize> {
+        if !self.update_bypass() {
+            self.read_ahead();
+        }
         let read_size = if self.offset == 0 && buf.len() >= BLOCK_SIZE {
             // whole block
             self.dev
+                .lock()
                 .read_block(self.block_id, &mut buf[0..BLOCK_SIZE])?;
             self.block_id += 1;
             BLOCK_SIZE
@@ -59,7 +176,7 @@ impl Disk {
             let start = self.offset;
             let count = buf.len().min(BLOCK_SIZE - self.offset);
 
-            self.dev.read_block(self.block_id, &mut data)?;
+            self.dev.lock().read_block(self.block_id, &mut data)?;
             buf[..count].copy_from_slice(&data[start..start + count]);
 
             self.offset += count;
@@ -69,25 +186,25 @@ impl Disk {
             }
             count
         };
+        self.update_cached_bytes();
         Ok(read_size)
     }
 
     /// Write within one block, returns the number of bytes written.
     pub fn write_one(&mut self, buf: &[u8]) -> DevResult<usize> {
+        self.update_bypass();
         let write_size = if self.offset == 0 && buf.len() >= BLOCK_SIZE {
             // whole block
-            self.dev.write_block(self.block_id, &buf[0..BLOCK_SIZE])?;
+            self.dev
+                .lock()
+                .write_block(self.block_id, &buf[0..BLOCK_SIZE])?;
             self.block_id += 1;
             BLOCK_SIZE
         } else {
             // partial block
-            let mut data = [0u8; BLOCK_SIZE];
-            let start = self.offset;
+            let pos = self.position();
             let count = buf.len().min(BLOCK_SIZE - self.offset);
-
-            self.dev.read_block(self.block_id, &mut data)?;
-            data[start..start + count].copy_from_slice(&buf[..count]);
-            self.dev.write_block(self.block_id, &data)?;
+            self.dev.lock().write_at(pos, &buf[..count])?;
 
             self.offset += count;
             if self.offset >= BLOCK_SIZE {
@@ -96,11 +213,23 @@ impl Disk {
             }
             count
         };
+        self.update_cached_bytes();
         Ok(write_size)
     }
 
     ///flush device cache
     pub fn do_flush(&mut self) -> DevResult {
-        self.dev.flush()
+        self.dev.lock().flush()
+    }
+}
+
+impl Drop for Disk {
+    fn drop(&mut self) {
+        // the blocks are written back before the device goes away
+        let _ = self.dev.lock().flush();
+        BLOCK_CACHES
+            .lock()
+            .retain(|dev| !Arc::ptr_eq(dev, &self.dev));
+        CACHED_BYTES.fetch_sub(self.cached_blocks * BLOCK_SIZE, Ordering::Relaxed);
     }
 }
@@ -0,0 +1,133 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! The `/proc/<tid>/fdinfo` directory, which has a file for each open fd with
+//! its offset and I/O totals, like:
+//!
+//! ```text
+//! pos:    100
+//! rchar:  0
+//! wchar:  100
+//! syscr:  0
+//! syscw:  1
+//! ```
+
+use alloc::{format, string::String, sync::Arc};
+use axfs_vfs::{VfsDirEntry, VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef};
+use axfs_vfs::{VfsNodeType, VfsResult};
+use ruxfdtable::FD_TABLE;
+
+/// The `fdinfo` directory, whose entries are the open fds.
+pub(crate) struct FdInfoDir {
+    parent: VfsNodeRef,
+}
+
+impl FdInfoDir {
+    pub(crate) fn new(parent: VfsNodeRef) -> Self {
+        Self { parent }
+    }
+}
+
+impl VfsNodeOps for FdInfoDir {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        let perm = VfsNodePerm::from_bits_truncate(0o555);
+        Ok(VfsNodeAttr::new(perm, VfsNodeType::Dir, 0, 0))
+    }
+
+    fn parent(&self) -> Option<VfsNodeRef> {
+        Some(self.parent.clone())
+    }
+
+    fn lookup(self: Arc<Self>, path: &str) -> VfsResult<VfsNodeRef> {
+        let path = path.trim_start_matches('/');
+        let (name, rest) = path.split_once('/').unwrap_or((path, ""));
+        let node: VfsNodeRef = match name {
+            "" | "." => self,
+            ".." => self.parent.clone(),
+            _ => {
+                let fd = name.parse().map_err(|_| VfsError::NotFound)?;
+                if !FD_TABLE.read().is_assigned(fd) {
+                    return Err(VfsError::NotFound);
+                }
+                Arc::new(FdInfoFile { fd })
+            }
+        };
+        if rest.is_empty() {
+            Ok(node)
+        } else {
+            node.lookup(rest)
+        }
+    }
+
+    fn read_dir(&self, start_idx: usize, dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
+        let table = FD_TABLE.read();
+        let mut fds = (0..table.capacity())
+            .filter(|&fd| table.is_assigned(fd))
+            .skip(start_idx.max(2) - 2);
+        for (i, ent) in dirents.iter_mut().enumerate() {
+            match i + start_idx {
+                0 => *ent = VfsDirEntry::new(".", VfsNodeType::Dir),
+                1 => *ent = VfsDirEntry::new("..", VfsNodeType::Dir),
+                _ => match fds.next() {
+                    Some(fd) => *ent = VfsDirEntry::new(&format!("{}", fd), VfsNodeType::File),
+                    None => return Ok(i),
+                },
+            }
+        }
+        Ok(dirents.len())
+    }
+
+    axfs_vfs::impl_vfs_dir_default! {}
+}
+
+/// The `fdinfo` file of an fd, whose content is generated when it is read.
+struct FdInfoFile {
+    fd: usize,
+}
+
+impl FdInfoFile {
+    fn content(&self) -> VfsResult<String> {
+        let file = FD_TABLE
+            .read()
+            .get(self.fd)
+            .cloned()
+            .ok_or(VfsError::NotFound)?;
+        let stats = ruxfdtable::fd_stats(self.fd);
+        Ok(format!(
+            "pos:\t{}\nrchar:\t{}\nwchar:\t{}\nsyscr:\t{}\nsyscw:\t{}\n",
+            file.pos(),
+            stats.bytes_read,
+            stats.bytes_written,
+            stats.reads,
+            stats.writes
+        ))
+    }
+}
+
+impl VfsNodeOps for FdInfoFile {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        let perm = VfsNodePerm::from_bits_truncate(0o444);
+        Ok(VfsNodeAttr::new(perm, VfsNodeType::File, 0, 0))
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
+        let content = self.content()?;
+        let content = content.as_bytes();
+        let start = content.len().min(offset as usize);
+        let len = buf.len().min(content.len() - start);
+        buf[..len].copy_from_slice(&content[start..start + len]);
+        Ok(len)
+    }
+
+    fn write_at(&self, _offset: u64, _buf: &[u8]) -> VfsResult<usize> {
+        Err(VfsError::PermissionDenied)
+    }
+
+    axfs_vfs::impl_vfs_non_dir_default! {}
+}
@@ -9,17 +9,27 @@
 
 //! Low-level filesystem operations.
 
+use alloc::{format, string::String};
 use axerrno::{ax_err, ax_err_type, AxResult};
 use axfs_vfs::{VfsError, VfsNodeRef};
-use axio::SeekFrom;
+use axio::{PollState, SeekFrom};
 use capability::{Cap, WithCap};
 use core::fmt;
 
+use crate::dev::DirectIo;
+use crate::MountNamespace;
+
+pub use crate::cache::{lookup_stats, LookupStats};
+pub use crate::dev::cached_bytes;
 #[cfg(feature = "myfs")]
 pub use crate::dev::Disk;
 #[cfg(feature = "myfs")]
 pub use crate::fs::myfs::MyFileSystemIf;
 
+/// Alignment of the offsets and the lengths of the accesses to a file opened
+/// with [`direct`](OpenOptions::direct), which is the block size of the disks.
+pub const DIRECT_IO_ALIGN: usize = 512;
+
 /// Alias of [`axfs_vfs::VfsNodeType`].
 pub type FileType = axfs_vfs::VfsNodeType;
 /// Alias of [`axfs_vfs::VfsDirEntry`].
@@ -28,11 +38,15 @@ pub type DirEntry = axfs_vfs::VfsDirEntry;
 pub type FileAttr = axfs_vfs::VfsNodeAttr;
 /// Alias of [`axfs_vfs::VfsNodePerm`].
 pub type FilePerm = axfs_vfs::VfsNodePerm;
+/// Alias of [`axfs_vfs::FileSystemInfo`].
+pub type FileSystemInfo = axfs_vfs::FileSystemInfo;
 
 /// An opened file object, with open permissions and a cursor.
 pub struct File {
     node: WithCap<VfsNodeRef>,
     is_append: bool,
+    is_path: bool,
+    is_direct: bool,
     offset: u64,
 }
 
@@ -40,6 +54,9 @@ pub struct File {
 /// [`read_dir`](Directory::read_dir).
 pub struct Directory {
     node: WithCap<VfsNodeRef>,
+    /// Canonical absolute path at which the directory was opened.
+    path: String,
+    is_path: bool,
     entry_idx: usize,
 }
 
@@ -53,9 +70,14 @@ pub struct OpenOptions {
     truncate: bool,
     create: bool,
     create_new: bool,
+    path: bool,
+    tmpfile: bool,
+    direct: bool,
+    resolve_beneath: bool,
+    resolve_no_symlinks: bool,
     // system-specific
     _custom_flags: i32,
-    _mode: u32,
+    mode: u32,
 }
 
 impl OpenOptions {
@@ -69,9 +91,14 @@ impl OpenOptions {
             truncate: false,
             create: false,
             create_new: false,
+            path: false,
+            tmpfile: false,
+            direct: false,
+            resolve_beneath: false,
+            resolve_no_symlinks: false,
             // system-specific
             _custom_flags: 0,
-            _mode: 0o666,
+            mode: 0o666,
         }
     }
     /// Sets the option for read access.
@@ -98,8 +125,67 @@ impl OpenOptions {
     pub fn create_new(&mut self, create_new: bool) {
         self.create_new = create_new;
     }
+    /// Sets the option to only open the path (`O_PATH`), without read or
+    /// write access. The opened object can only be used to get its
+    /// attributes, or as the directory of relative paths.
+    pub fn path(&mut self, path: bool) {
+        self.path = path;
+    }
+    /// Sets the option to create a file without a name in the directory at
+    /// the path (`O_TMPFILE`), which is removed when it is closed, unless it
+    /// is [linked](File::link) before. It needs write access.
+    pub fn tmpfile(&mut self, tmpfile: bool) {
+        self.tmpfile = tmpfile;
+    }
+    /// Sets the option to access the file directly (`O_DIRECT`), bypassing
+    /// the block cache of the disk.
+    ///
+    /// The offset and the length of every read and write must then be
+    /// multiples of [`DIRECT_IO_ALIGN`], including the end of the file for an
+    /// append, or they fail with `InvalidInput`. The buffer may be anywhere
+    /// in memory. Reads near the end of the file may still return a length
+    /// which is not aligned.
+    pub fn direct(&mut self, direct: bool) {
+        self.direct = direct;
+    }
+    /// Sets the option to fail with `CrossesDevices` if the path, or the
+    /// symbolic link it names, leaves the directory it is relative to
+    /// (`RESOLVE_BENEATH`). Absolute paths are rejected.
+    pub fn resolve_beneath(&mut self, beneath: bool) {
+        self.resolve_beneath = beneath;
+    }
+    /// Sets the option to fail with `FilesystemLoop` if any component of the
+    /// path is a symbolic link (`RESOLVE_NO_SYMLINKS`).
+    pub fn resolve_no_symlinks(&mut self, no_symlinks: bool) {
+        self.resolve_no_symlinks = no_symlinks;
+    }
+    /// Sets the permission mode of a new file, before the umask is applied.
+    pub fn mode(&mut self, mode: u32) {
+        self.mode = mode;
+    }
+
+    /// Checks the restrictions on the resolution of `path` from `dir`.
+    fn check_resolve(&self, dir: Option<&VfsNodeRef>, path: &str) -> AxResult {
+        if self.resolve_beneath || self.resolve_no_symlinks {
+            crate::root::check_resolve(dir, path, self.resolve_beneath, self.resolve_no_symlinks)
+        } else {
+            Ok(())
+        }
+    }
 
     const fn is_valid(&self) -> bool {
+        if self.path {
+            // no access mode, and nothing to create or truncate
+            return !(self.read
+                || self.write
+                || self.append
+                || self.truncate
+                || self.create
+                || self.create_new);
+        }
+        if self.tmpfile && !self.write {
+            return false;
+        }
         if !self.read && !self.write && !self.append {
             return false;
         }
@@ -126,10 +212,15 @@ impl File {
         if !opts.is_valid() {
             return ax_err!(InvalidInput);
         }
-
-        let node_option = crate::root::lookup(dir, path);
-        let node = if opts.create || opts.create_new {
-            match node_option {
+        opts.check_resolve(dir, path)?;
+
+        let mut created = false;
+        let node = if opts.tmpfile {
+            created = true;
+            let perm = FilePerm::from_bits_truncate(opts.mode as u16);
+            crate::root::create_tmpfile(dir, path, perm)?
+        } else if opts.create || opts.create_new {
+            match crate::root::lookup(dir, path) {
                 Ok(node) => {
                     // already exists
                     if opts.create_new {
@@ -138,12 +229,16 @@ impl File {
                     node
                 }
                 // not exists, create new
-                Err(VfsError::NotFound) => crate::root::create_file(dir, path)?,
+                Err(VfsError::NotFound) => {
+                    created = true;
+                    let perm = FilePerm::from_bits_truncate(opts.mode as u16);
+                    crate::root::create_file(dir, path, perm)?
+                }
                 Err(e) => return Err(e),
             }
         } else {
             // just open the existing
-            node_option?
+            crate::root::lookup(dir, path)?
         };
 
         let attr = node.get_attr()?;
@@ -152,8 +247,9 @@ impl File {
         {
             return ax_err!(IsADirectory);
         }
+        // the mode of a new file only applies to the later opens
         let access_cap = opts.into();
-        if !perm_to_cap(attr.perm()).contains(access_cap) {
+        if !created && !perm_to_cap(attr.perm()).contains(access_cap) {
             return ax_err!(PermissionDenied);
         }
 
@@ -164,6 +260,8 @@ impl File {
         Ok(Self {
             node: WithCap::new(node, access_cap),
             is_append: opts.append,
+            is_path: opts.path,
+            is_direct: opts.direct,
             offset: 0,
         })
     }
@@ -180,12 +278,20 @@ impl File {
         Ok(())
     }
 
+    /// Allocates the storage of the `len` bytes at `offset`, extending the
+    /// file with zeros if it ends before.
+    pub fn fallocate(&self, offset: u64, len: u64) -> AxResult {
+        self.node.access(Cap::WRITE)?.fallocate(offset, len)?;
+        Ok(())
+    }
+
     /// Reads the file at the current position. Returns the number of bytes
     /// read.
     ///
     /// After the read, the cursor will be advanced by the number of bytes read.
     pub fn read(&mut self, buf: &mut [u8]) -> AxResult<usize> {
         let node = self.node.access(Cap::READ)?;
+        let _direct = self.begin_direct_io(self.offset, buf.len())?;
         let read_len = node.read_at(self.offset, buf)?;
         self.offset += read_len as u64;
         Ok(read_len)
@@ -196,6 +302,7 @@ impl File {
     /// It does not update the file cursor.
     pub fn read_at(&self, offset: u64, buf: &mut [u8]) -> AxResult<usize> {
         let node = self.node.access(Cap::READ)?;
+        let _direct = self.begin_direct_io(offset, buf.len())?;
         let read_len = node.read_at(offset, buf)?;
         Ok(read_len)
     }
@@ -210,6 +317,7 @@ impl File {
         if self.is_append {
             self.offset = self.get_attr()?.size();
         };
+        let _direct = self.begin_direct_io(self.offset, buf.len())?;
         let write_len = node.write_at(self.offset, buf)?;
         self.offset += write_len as u64;
         Ok(write_len)
@@ -221,16 +329,43 @@ impl File {
     /// It does not update the file cursor.
     pub fn write_at(&self, offset: u64, buf: &[u8]) -> AxResult<usize> {
         let node = self.node.access(Cap::WRITE)?;
+        let _direct = self.begin_direct_io(offset, buf.len())?;
         let write_len = node.write_at(offset, buf)?;
         Ok(write_len)
     }
 
+    /// Checks the alignment of an access to a file opened with
+    /// [`direct`](OpenOptions::direct), and makes the disks bypass their
+    /// caches until the returned guard is dropped.
+    fn begin_direct_io(&self, offset: u64, len: usize) -> AxResult<Option<DirectIo>> {
+        if !self.is_direct {
+            return Ok(None);
+        }
+        if offset % DIRECT_IO_ALIGN as u64 != 0 || len % DIRECT_IO_ALIGN != 0 {
+            return ax_err!(InvalidInput);
+        }
+        Ok(Some(DirectIo::begin()))
+    }
+
     /// Flushes the file, writes all buffered data to the underlying device.
+    ///
+    /// Like `fsync`, it also writes back the data written through other
+    /// handles, so it works on a read-only file too.
     pub fn flush(&self) -> AxResult {
-        self.node.access(Cap::WRITE)?.fsync()?;
+        self.node.access(Cap::empty())?.fsync()?;
         Ok(())
     }
 
+    /// Whether the file can be read or written now without blocking.
+    pub fn poll(&self) -> AxResult<PollState> {
+        self.node.access(Cap::empty())?.poll()
+    }
+
+    /// Performs the I/O control operation `cmd` of the underlying device.
+    pub fn ioctl(&self, cmd: usize, arg: usize) -> AxResult<usize> {
+        self.node.access(Cap::empty())?.ioctl(cmd, arg)
+    }
+
     /// Sets the cursor of the file to the specified offset. Returns the new
     /// position after the seek.
     pub fn seek(&mut self, pos: SeekFrom) -> AxResult<u64> {
@@ -245,21 +380,54 @@ impl File {
         Ok(new_offset)
     }
 
+    /// Links the file at the path relative to the current directory, e.g. to
+    /// give a name to a file opened with [`tmpfile`](OpenOptions::tmpfile).
+    pub fn link(&self, path: &str) -> AxResult {
+        crate::root::link(None, path, self.node.access(Cap::empty())?)
+    }
+
+    /// Returns whether only the path of the file is open (`O_PATH`), so it
+    /// can not be read or written.
+    pub fn is_path(&self) -> bool {
+        self.is_path
+    }
+
     /// Gets the file attributes.
     pub fn get_attr(&self) -> AxResult<FileAttr> {
         self.node.access(Cap::empty())?.get_attr()
     }
+
+    /// Sets the permission mode of the file.
+    pub fn set_perm(&self, perm: FilePerm) -> AxResult {
+        self.node
+            .access(Cap::empty())?
+            .setattr(Some(perm), None, None, None)
+    }
+
+    /// Sets the user and group IDs of the owner of the file, the ones
+    /// which are `None` are left unchanged.
+    pub fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> AxResult {
+        self.node
+            .access(Cap::empty())?
+            .setattr(None, uid, gid, None)
+    }
 }
 
 impl Directory {
-    fn _open_dir_at(dir: Option<&VfsNodeRef>, path: &str, opts: &OpenOptions) -> AxResult<Self> {
+    fn _open_dir_at(
+        dir: Option<&VfsNodeRef>,
+        abs_path: String,
+        path: &str,
+        opts: &OpenOptions,
+    ) -> AxResult<Self> {
         debug!("open dir: {}", path);
-        if !opts.read {
+        if !opts.read && !opts.path {
             return ax_err!(InvalidInput);
         }
         if opts.create || opts.create_new || opts.write || opts.append || opts.truncate {
             return ax_err!(InvalidInput);
         }
+        opts.check_resolve(dir, path)?;
 
         let node = crate::root::lookup(dir, path)?;
         let attr = node.get_attr()?;
@@ -274,6 +442,8 @@ impl Directory {
         node.open()?;
         Ok(Self {
             node: WithCap::new(node, access_cap | Cap::EXECUTE),
+            path: abs_path,
+            is_path: opts.path,
             entry_idx: 0,
         })
     }
@@ -289,13 +459,18 @@ impl Directory {
     /// Opens a directory at the path relative to the current directory.
     /// Returns a [`Directory`] object.
     pub fn open_dir(path: &str, opts: &OpenOptions) -> AxResult<Self> {
-        Self::_open_dir_at(None, path, opts)
+        Self::_open_dir_at(None, crate::root::absolute_path(path)?, path, opts)
     }
 
     /// Opens a directory at the path relative to this directory. Returns a
     /// [`Directory`] object.
     pub fn open_dir_at(&self, path: &str, opts: &OpenOptions) -> AxResult<Self> {
-        Self::_open_dir_at(self.access_at(path)?, path, opts)
+        let abs_path = if path.starts_with('/') {
+            crate::root::absolute_path(path)?
+        } else {
+            axfs_vfs::path::canonicalize(&format!("{}/{}", self.path, path))
+        };
+        Self::_open_dir_at(self.access_at(path)?, abs_path, path, opts)
     }
 
     /// Opens a file at the path relative to this directory. Returns a [`File`]
@@ -304,14 +479,16 @@ impl Directory {
         File::_open_at(self.access_at(path)?, path, opts)
     }
 
-    /// Creates an empty file at the path relative to this directory.
-    pub fn create_file(&self, path: &str) -> AxResult<VfsNodeRef> {
-        crate::root::create_file(self.access_at(path)?, path)
+    /// Creates an empty file at the path relative to this directory, with the
+    /// permission `perm` without the bits of the umask.
+    pub fn create_file(&self, path: &str, perm: FilePerm) -> AxResult<VfsNodeRef> {
+        crate::root::create_file(self.access_at(path)?, path, perm)
     }
 
-    /// Creates an empty directory at the path relative to this directory.
-    pub fn create_dir(&self, path: &str) -> AxResult {
-        crate::root::create_dir(self.access_at(path)?, path)
+    /// Creates an empty directory at the path relative to this directory,
+    /// with the permission `perm` without the bits of the umask.
+    pub fn create_dir(&self, path: &str, perm: FilePerm) -> AxResult {
+        crate::root::create_dir(self.access_at(path)?, path, perm)
     }
 
     /// Removes a file at the path relative to this directory.
@@ -346,10 +523,117 @@ impl Directory {
         crate::root::rename(old, new)
     }
 
+    /// Returns the canonical absolute path at which the directory was opened.
+    /// It is not updated if the directory is renamed later.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns whether only the path of the directory is open (`O_PATH`), so
+    /// its entries can not be read.
+    pub fn is_path(&self) -> bool {
+        self.is_path
+    }
+
     /// Gets the file attributes.
     pub fn get_attr(&self) -> AxResult<FileAttr> {
         self.node.access(Cap::empty())?.get_attr()
     }
+
+    /// Sets the permission mode of the directory.
+    pub fn set_perm(&self, perm: FilePerm) -> AxResult {
+        self.node
+            .access(Cap::empty())?
+            .setattr(Some(perm), None, None, None)
+    }
+
+    /// Sets the user and group IDs of the owner of the directory, the ones
+    /// which are `None` are left unchanged.
+    pub fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> AxResult {
+        self.node
+            .access(Cap::empty())?
+            .setattr(None, uid, gid, None)
+    }
+}
+
+/// Looks up the file or directory at the path relative to the current
+/// directory, without opening it. The symbolic link at the path is followed.
+///
+/// Recently looked up paths are served from a cache, see [`lookup_stats`].
+pub fn lookup(path: &str) -> AxResult<VfsNodeRef> {
+    crate::root::lookup(None, path)
+}
+
+/// Looks up the node at the path relative to the current directory, which is
+/// the symbolic link itself if the path names one.
+pub fn lookup_nofollow(path: &str) -> AxResult<VfsNodeRef> {
+    crate::root::lookup_nofollow(None, path)
+}
+
+/// Creates a symbolic link at the path relative to the current directory,
+/// which points to `target`.
+pub fn symlink(target: &str, path: &str) -> AxResult {
+    crate::root::symlink(None, path, target)
+}
+
+/// Creates the node of a Unix domain socket at the path relative to the
+/// current directory, with the permission `perm` without the bits of the umask.
+pub fn create_socket(path: &str, perm: FilePerm) -> AxResult {
+    crate::root::create_socket(None, path, perm)
+}
+
+/// Creates a hard link to `node` at the path relative to the current
+/// directory. Only the regular files can be linked, in their filesystem.
+pub fn link(node: &VfsNodeRef, path: &str) -> AxResult {
+    crate::root::link(None, path, node)
+}
+
+/// Truncates or extends the regular file at the path relative to the current
+/// directory to `size` bytes, without opening it. The extended part reads as
+/// zeros.
+pub fn truncate(path: &str, size: u64) -> AxResult {
+    let node = crate::root::lookup(None, path)?;
+    let attr = node.get_attr()?;
+    if attr.is_dir() {
+        return ax_err!(IsADirectory);
+    }
+    if !perm_to_cap(attr.perm()).contains(Cap::WRITE) {
+        return ax_err!(PermissionDenied);
+    }
+    node.truncate(size)
+}
+
+/// Returns the target of the symbolic link at the path relative to the
+/// current directory.
+pub fn read_link(path: &str) -> AxResult<String> {
+    crate::root::read_link(None, path)
+}
+
+/// Returns the attributes of the filesystem which contains the path relative
+/// to the current directory, e.g. its free space.
+///
+/// The filesystems which do not report them are shown with 512-byte blocks
+/// and an unknown size.
+pub fn statfs(path: &str) -> AxResult<FileSystemInfo> {
+    crate::root::statfs(path)
+}
+
+/// Returns the file mode creation mask, the permission bits cleared on the
+/// new files and directories. It is `0o022` by default.
+pub fn umask() -> FilePerm {
+    crate::root::umask()
+}
+
+/// Sets the file mode creation mask, and returns the previous one.
+pub fn set_umask(mask: FilePerm) -> FilePerm {
+    crate::root::set_umask(mask)
+}
+
+/// Switches the calling task to a copy of its mount namespace, like
+/// `unshare(CLONE_NEWNS)`, and returns the previous namespace. The mounts
+/// made afterwards are not visible in the previous one.
+pub fn unshare_mounts() -> MountNamespace {
+    MountNamespace::current().duplicate().set_current()
 }
 
 impl Drop for File {
@@ -385,6 +669,11 @@ impl fmt::Debug for OpenOptions {
         fmt_opt!(truncate, "TRUNC");
         fmt_opt!(create, "CREATE");
         fmt_opt!(create_new, "CREATE_NEW");
+        fmt_opt!(path, "PATH");
+        fmt_opt!(tmpfile, "TMPFILE");
+        fmt_opt!(direct, "DIRECT");
+        fmt_opt!(resolve_beneath, "RESOLVE_BENEATH");
+        fmt_opt!(resolve_no_symlinks, "RESOLVE_NO_SYMLINKS");
         Ok(())
     }
 }
@@ -245,6 +245,10 @@ impl VfsOps for FatFileSystem {
         let root_dir = unsafe { (*self.root_dir.get()).as_ref().unwrap() };
         root_dir.clone()
     }
+
+    fn fs_type(&self) -> &str {
+        "vfat"
+    }
 }
 
 impl fatfs::IoBase for Disk {
@@ -20,3 +20,6 @@ pub use axfs_devfs as devfs;
 
 #[cfg(feature = "ramfs")]
 pub use axfs_ramfs as ramfs;
+
+#[cfg(feature = "overlayfs")]
+pub use axfs_overlayfs as overlayfs;
@@ -19,6 +19,14 @@
 //!    **enabled** by default.
 //! - `ramfs`: Mount [`axfs_ramfs::RamFileSystem`] on `/tmp`. This feature is
 //!    **enabled** by default.
+//! - `procfs`: Mount a proc filesystem on `/proc`, whose `meminfo`, `mounts`
+//!    and task directories are generated when read. This feature is
+//!    **enabled** by default.
+//! - `multitask`: List all the tasks in `/proc` instead of only the main one.
+//!    This feature is **disabled** by default.
+//! - `overlayfs`: Allow mounting an [`axfs_overlayfs::OverlayFileSystem`] of
+//!    two directories with [`mount_overlay`]. This feature is **disabled** by
+//!    default.
 //! - `myfs`: Allow users to define their custom filesystems to override the
 //!    default. In this case, [`MyFileSystemIf`] is required to be implemented
 //!    to create and initialize other filesystems. This feature is **disabled** by
@@ -35,12 +43,17 @@
 extern crate log;
 extern crate alloc;
 
+mod cache;
 mod dev;
+#[cfg(feature = "fdinfo")]
+mod fdinfo;
 mod fs;
 mod mounts;
+#[cfg(feature = "procfs")]
+mod procfs;
 mod root;
 
-#[cfg(feature = "alloc")]
+#[cfg(feature = "procfs")]
 mod arch;
 
 pub mod api;
@@ -58,7 +71,14 @@ cfg_if::cfg_if! {
     }
 }
 
-pub use root::MountPoint;
+pub use root::{MountNamespace, MountPoint};
+
+/// Checksum algorithms (CRC32, CRC32C, Adler-32) shared by filesystems and
+/// archive readers.
+pub use checksum;
+/// Allocation-free hex and base64 codecs, e.g. for exposing binary data in
+/// text files.
+pub use codec;
 
 /// Initialize an empty filesystems by ramfs.
 #[cfg(not(any(feature = "blkfs", feature = "virtio-9p", feature = "net-9p")))]
@@ -88,6 +108,12 @@ pub fn init_blkfs(mut blk_devs: AxDeviceContainer<AxBlockDevice>) -> MountPoint
     MountPoint::new("/", blk_fs)
 }
 
+/// Writes the blocks cached in memory back to the disks, which must be done
+/// before the system shuts down.
+pub fn sync() -> axerrno::AxResult {
+    dev::sync().map_err(|_| axerrno::AxError::Io)
+}
+
 /// Initializes common filesystems.
 pub fn prepare_commonfs(mount_points: &mut Vec<self::root::MountPoint>) {
     #[cfg(feature = "devfs")]
@@ -95,10 +121,10 @@ pub fn prepare_commonfs(mount_points: &mut Vec<self::root::MountPoint>) {
     mount_points.push(mount_point);
 
     #[cfg(feature = "ramfs")]
-    let mount_point = MountPoint::new("/tmp", mounts::ramfs());
+    let mount_point = MountPoint::new("/tmp", mounts::tmpfs(ruxconfig::TMPFS_SIZE as _));
     mount_points.push(mount_point);
 
-    // Mount another ramfs as procfs
+    // Mount procfs
     #[cfg(feature = "procfs")]
     let mount_point = MountPoint::new("/proc", mounts::procfs().unwrap());
     mount_points.push(mount_point);
@@ -114,6 +140,95 @@ pub fn prepare_commonfs(mount_points: &mut Vec<self::root::MountPoint>) {
     mount_points.push(mount_point);
 }
 
+/// Mounts a new tmpfs on `path` in the mount namespace in use, whose files
+/// take at most `max_bytes` bytes, or unlimited if it is 0. The writes beyond
+/// it fail with [`StorageFull`](axerrno::AxError::StorageFull).
+#[cfg(feature = "ramfs")]
+pub fn mount_tmpfs(path: &'static str, max_bytes: u64) -> axerrno::AxResult {
+    MountNamespace::current().mount(path, mounts::tmpfs(max_bytes))
+}
+
+/// Mounts a new filesystem of the type `fstype` on the directory at `path`,
+/// in the mount namespace in use. The types are:
+///
+/// - `tmpfs`: a ramfs, whose size is given in bytes by the `size=` option in
+///   `data`, optionally with a `k`, `m` or `g` suffix. It is unlimited by
+///   default.
+/// - `proc`: a proc filesystem, like the one on `/proc`.
+/// - `devtmpfs`: the device filesystem, like the one on `/dev`.
+/// - `sysfs`: a filesystem like the one on `/sys`.
+///
+/// The other types, or those of the disabled features, are
+/// [`Unsupported`](axerrno::AxError::Unsupported). The filesystems on block
+/// devices can only be mounted at boot.
+#[cfg_attr(not(feature = "ramfs"), allow(unused_variables))]
+pub fn mount(fstype: &str, path: &str, data: &str) -> axerrno::AxResult {
+    let fs: alloc::sync::Arc<dyn axfs_vfs::VfsOps> = match fstype {
+        #[cfg(feature = "ramfs")]
+        "tmpfs" => {
+            let size = data.split(',').find_map(|opt| opt.strip_prefix("size="));
+            mounts::tmpfs(match size {
+                Some(size) => parse_size(size)?,
+                None => 0,
+            })
+        }
+        #[cfg(feature = "procfs")]
+        "proc" => mounts::procfs()?,
+        #[cfg(feature = "devfs")]
+        "devtmpfs" => mounts::devfs(),
+        #[cfg(feature = "sysfs")]
+        "sysfs" => mounts::sysfs()?,
+        _ => return axerrno::ax_err!(Unsupported, "unknown filesystem type"),
+    };
+    // the mount table keeps the path until it is unmounted
+    let path = alloc::boxed::Box::leak(root::absolute_path(path)?.into_boxed_str());
+    MountNamespace::current().mount(path, fs)
+}
+
+/// Parses a size in bytes with an optional `k`, `m` or `g` suffix.
+#[cfg(feature = "ramfs")]
+fn parse_size(size: &str) -> axerrno::AxResult<u64> {
+    let (digits, shift) = match size.as_bytes().last() {
+        Some(b'k' | b'K') => (&size[..size.len() - 1], 10),
+        Some(b'm' | b'M') => (&size[..size.len() - 1], 20),
+        Some(b'g' | b'G') => (&size[..size.len() - 1], 30),
+        _ => (size, 0),
+    };
+    let size: u64 = digits.parse().map_err(|_| axerrno::AxError::InvalidInput)?;
+    size.checked_mul(1 << shift)
+        .ok_or(axerrno::AxError::InvalidInput)
+}
+
+/// Bind-mounts the file or directory at `src` on `path` in the mount
+/// namespace in use, see [`MountNamespace::bind`]. The changes made through
+/// either path are made to the same nodes.
+pub fn bind_mount(src: &str, path: &str, recursive: bool) -> axerrno::AxResult {
+    let src = root::absolute_path(src)?;
+    // the mount table keeps the path until it is unmounted
+    let path = alloc::boxed::Box::leak(root::absolute_path(path)?.into_boxed_str());
+    MountNamespace::current().bind(&src, path, recursive)
+}
+
+/// Unmounts the filesystem mounted on `path` in the mount namespace in use,
+/// see [`MountNamespace::umount`].
+pub fn umount(path: &str, force: bool) -> axerrno::AxResult {
+    MountNamespace::current().umount(&root::absolute_path(path)?, force)
+}
+
+/// Mounts on `path` an overlay of the directory `upperdir` over the directory
+/// `lowerdir`, in the mount namespace in use. The changes to the overlay are
+/// made in `upperdir` only, `lowerdir` is never modified.
+#[cfg(feature = "overlayfs")]
+pub fn mount_overlay(path: &'static str, lowerdir: &str, upperdir: &str) -> axerrno::AxResult {
+    let lower = root::lookup(None, lowerdir)?;
+    let upper = root::lookup(None, upperdir)?;
+    if !lower.get_attr()?.is_dir() || !upper.get_attr()?.is_dir() {
+        return Err(axerrno::AxError::NotADirectory);
+    }
+    let fs = fs::overlayfs::OverlayFileSystem::new(lower, upper);
+    MountNamespace::current().mount(path, alloc::sync::Arc::new(fs))
+}
+
 /// Initializes root filesystems.
 pub fn init_filesystems(mount_points: Vec<self::root::MountPoint>) {
     self::root::init_rootfs(mount_points);
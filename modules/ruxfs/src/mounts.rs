@@ -10,19 +10,21 @@
 use alloc::sync::Arc;
 use axfs_vfs::{VfsNodeType, VfsOps, VfsResult};
 
-#[cfg(feature = "alloc")]
-use crate::arch::{get_cpuinfo, get_meminfo};
+#[cfg(feature = "procfs")]
+use crate::arch::get_cpuinfo;
 use crate::fs;
 
 #[cfg(feature = "devfs")]
 pub(crate) fn devfs() -> Arc<fs::devfs::DeviceFileSystem> {
     let null = fs::devfs::NullDev;
     let zero = fs::devfs::ZeroDev;
+    let full = fs::devfs::FullDev;
     let random = fs::devfs::RandomDev;
     let urandom = fs::devfs::RandomDev;
     let devfs = fs::devfs::DeviceFileSystem::new();
     devfs.add("null", Arc::new(null));
     devfs.add("zero", Arc::new(zero));
+    devfs.add("full", Arc::new(full));
     devfs.add("random", Arc::new(random));
     devfs.add("urandom", Arc::new(urandom));
     Arc::new(devfs)
@@ -33,23 +35,26 @@ pub(crate) fn ramfs() -> Arc<fs::ramfs::RamFileSystem> {
     Arc::new(fs::ramfs::RamFileSystem::new())
 }
 
-#[cfg(feature = "procfs")]
-pub(crate) fn procfs() -> VfsResult<Arc<fs::ramfs::RamFileSystem>> {
-    let procfs = fs::ramfs::RamFileSystem::new();
-    let proc_root = procfs.root_dir();
-
-    #[cfg(feature = "alloc")]
-    {
-        // Create /proc/cpuinfo
-        proc_root.create("cpuinfo", VfsNodeType::File)?;
-        let file_cpuinfo = proc_root.clone().lookup("./cpuinfo")?;
-        file_cpuinfo.write_at(0, get_cpuinfo().as_bytes())?;
-
-        // Create /proc/meminfo
-        proc_root.create("meminfo", VfsNodeType::File)?;
-        let file_meminfo = proc_root.clone().lookup("./meminfo")?;
-        file_meminfo.write_at(0, get_meminfo().as_bytes())?;
+/// Creates a ramfs whose contents take at most `max_bytes` bytes, or
+/// unlimited if `max_bytes` is 0.
+#[cfg(feature = "ramfs")]
+pub(crate) fn tmpfs(max_bytes: u64) -> Arc<fs::ramfs::RamFileSystem> {
+    if max_bytes == 0 {
+        ramfs()
+    } else {
+        Arc::new(fs::ramfs::RamFileSystem::with_capacity(max_bytes))
     }
+}
+
+#[cfg(feature = "procfs")]
+pub(crate) fn procfs() -> VfsResult<Arc<crate::procfs::ProcFileSystem>> {
+    let procfs = crate::procfs::ProcFileSystem::new();
+    let proc_root = procfs.static_dir();
+
+    // Create /proc/cpuinfo
+    proc_root.create("cpuinfo", VfsNodeType::File)?;
+    let file_cpuinfo = proc_root.clone().lookup("./cpuinfo")?;
+    file_cpuinfo.write_at(0, get_cpuinfo().as_bytes())?;
 
     // Create /proc/sys/net/core/somaxconn
     proc_root.create_recursive("sys/net/core/somaxconn", VfsNodeType::File)?;
@@ -61,9 +66,6 @@ pub(crate) fn procfs() -> VfsResult<Arc<fs::ramfs::RamFileSystem>> {
     let file_over = proc_root.clone().lookup("./sys/vm/overcommit_memory")?;
     file_over.write_at(0, b"0\n")?;
 
-    // Create /proc/self/stat
-    proc_root.create_recursive("self/stat", VfsNodeType::File)?;
-
     Ok(Arc::new(procfs))
 }
 
@@ -130,7 +132,7 @@ pub(crate) fn etcfs() -> VfsResult<Arc<fs::ramfs::RamFileSystem>> {
     let file_resolv = etc_root.clone().lookup("resolv.conf")?;
     file_resolv.write_at(
         0,
-        b"nameserver 127.0.0.53\n\
+        b"nameserver 8.8.8.8\n\
         options edns0 trust-ad\n\
         search lan\n
         ",
@@ -0,0 +1,355 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! The proc filesystem, whose files are generated from the live state of the
+//! system each time they are read:
+//!
+//! - `meminfo`: the total and free memory of the global allocator, empty
+//!   without the `alloc` feature.
+//! - `mounts`: the mount table of the mount namespace in use.
+//! - `<tid>/`: a directory for each task, with its `stat`, its `comm` and,
+//!   with the `fdinfo` feature, the `fdinfo` directory.
+//! - `self`: a symbolic link to the directory of the current task.
+//!
+//! The other files, like `cpuinfo`, are static ones kept in a ramfs.
+
+use alloc::{format, string::String, sync::Arc, vec::Vec};
+use axfs_ramfs::{DirNode, RamFileSystem};
+use axfs_vfs::{FileSystemInfo, VfsDirEntry, VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm};
+use axfs_vfs::{VfsNodeRef, VfsNodeType, VfsOps, VfsResult};
+use core::time::Duration;
+
+/// `f_type` of the proc filesystem in `statfs`.
+const PROC_SUPER_MAGIC: u64 = 0x9fa0;
+/// Clock ticks per second of the times in `stat`, i.e. `sysconf(_SC_CLK_TCK)`.
+const USER_HZ: u128 = 100;
+/// The id of the process, as returned by `getpid`.
+const PID: u64 = 2;
+
+/// The proc filesystem.
+pub(crate) struct ProcFileSystem {
+    statics: RamFileSystem,
+    root: Arc<ProcRootDir>,
+}
+
+impl ProcFileSystem {
+    pub(crate) fn new() -> Self {
+        let statics = RamFileSystem::new();
+        let root = Arc::new(ProcRootDir {
+            statics: statics.root_dir_node(),
+        });
+        Self { statics, root }
+    }
+
+    /// Returns the directory of the static files, which are listed along with
+    /// the generated ones.
+    pub(crate) fn static_dir(&self) -> VfsNodeRef {
+        self.statics.root_dir()
+    }
+}
+
+impl VfsOps for ProcFileSystem {
+    fn mount(&self, path: &str, mount_point: VfsNodeRef) -> VfsResult {
+        self.statics.mount(path, mount_point)
+    }
+
+    fn statfs(&self) -> VfsResult<FileSystemInfo> {
+        Ok(FileSystemInfo::new(PROC_SUPER_MAGIC, 4096, 0, 0))
+    }
+
+    fn root_dir(&self) -> VfsNodeRef {
+        self.root.clone()
+    }
+
+    fn fs_type(&self) -> &str {
+        "proc"
+    }
+
+    fn is_volatile(&self) -> bool {
+        // e.g. `self` is the directory of the task which looks it up
+        true
+    }
+}
+
+/// A task, as shown in its directory.
+struct TaskInfo {
+    tid: u64,
+    name: String,
+    state: char,
+    cpu_time: Duration,
+}
+
+#[cfg(feature = "multitask")]
+fn tasks() -> Vec<TaskInfo> {
+    use ruxtask::TaskState;
+    ruxtask::all_tasks()
+        .iter()
+        .map(|task| TaskInfo {
+            tid: task.id().as_u64(),
+            name: task.name(),
+            state: match task.state() {
+                TaskState::Running | TaskState::Ready => 'R',
+                TaskState::Blocked => 'S',
+                TaskState::Exited => 'Z',
+            },
+            cpu_time: task.usage().cpu_time,
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "multitask"))]
+fn tasks() -> Vec<TaskInfo> {
+    alloc::vec![TaskInfo {
+        tid: PID,
+        name: "main".into(),
+        state: 'R',
+        cpu_time: Duration::ZERO,
+    }]
+}
+
+fn current_tid() -> u64 {
+    #[cfg(feature = "multitask")]
+    {
+        ruxtask::current().id().as_u64()
+    }
+    #[cfg(not(feature = "multitask"))]
+    {
+        PID
+    }
+}
+
+fn find_task(tid: u64) -> VfsResult<TaskInfo> {
+    tasks()
+        .into_iter()
+        .find(|task| task.tid == tid)
+        .ok_or(VfsError::NotFound)
+}
+
+/// Looks up `rest` in `node`, or returns `node` if `rest` is empty.
+fn lookup_rest(node: VfsNodeRef, rest: &str) -> VfsResult<VfsNodeRef> {
+    if rest.is_empty() {
+        Ok(node)
+    } else {
+        node.lookup(rest)
+    }
+}
+
+/// Fills `dirents` from `start_idx` with `.`, `..` and then `entries`.
+fn fill_dirents<S: AsRef<str>>(
+    entries: &[(S, VfsNodeType)],
+    start_idx: usize,
+    dirents: &mut [VfsDirEntry],
+) -> usize {
+    let mut entries = entries.iter().skip(start_idx.max(2) - 2);
+    for (i, ent) in dirents.iter_mut().enumerate() {
+        match i + start_idx {
+            0 => *ent = VfsDirEntry::new(".", VfsNodeType::Dir),
+            1 => *ent = VfsDirEntry::new("..", VfsNodeType::Dir),
+            _ => match entries.next() {
+                Some((name, ty)) => *ent = VfsDirEntry::new(name.as_ref(), *ty),
+                None => return i,
+            },
+        }
+    }
+    dirents.len()
+}
+
+fn dir_attr() -> VfsNodeAttr {
+    let perm = VfsNodePerm::from_bits_truncate(0o555);
+    VfsNodeAttr::new(perm, VfsNodeType::Dir, 0, 0)
+}
+
+/// The root directory, whose entries besides the generated ones are looked
+/// up in the static directory.
+struct ProcRootDir {
+    statics: Arc<DirNode>,
+}
+
+impl VfsNodeOps for ProcRootDir {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        Ok(dir_attr())
+    }
+
+    fn parent(&self) -> Option<VfsNodeRef> {
+        self.statics.parent()
+    }
+
+    fn lookup(self: Arc<Self>, path: &str) -> VfsResult<VfsNodeRef> {
+        let path = path.trim_start_matches('/');
+        let (name, rest) = path.split_once('/').unwrap_or((path, ""));
+        let node: VfsNodeRef = match name {
+            "" | "." => self,
+            ".." => self.parent().ok_or(VfsError::NotFound)?,
+            "meminfo" => Arc::new(ProcFile::new(|| Ok(crate::arch::get_meminfo()))),
+            "mounts" => Arc::new(ProcFile::new(mounts)),
+            // the link is followed here if it is not the last component
+            "self" if !rest.is_empty() => Arc::new(TaskDir::new(current_tid(), self)),
+            "self" => Arc::new(SelfLink),
+            _ => match name.parse() {
+                Ok(tid) => Arc::new(TaskDir::new(find_task(tid)?.tid, self)),
+                Err(_) => return self.statics.clone().lookup(path),
+            },
+        };
+        lookup_rest(node, rest)
+    }
+
+    fn read_dir(&self, start_idx: usize, dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
+        let mut entries = Vec::new();
+        for name in self.statics.get_entries() {
+            let ty = self.statics.clone().lookup(&name)?.get_attr()?.file_type();
+            entries.push((name, ty));
+        }
+        entries.push(("meminfo".into(), VfsNodeType::File));
+        entries.push(("mounts".into(), VfsNodeType::File));
+        entries.push(("self".into(), VfsNodeType::SymLink));
+        for task in tasks() {
+            entries.push((format!("{}", task.tid), VfsNodeType::Dir));
+        }
+        Ok(fill_dirents(&entries, start_idx, dirents))
+    }
+
+    axfs_vfs::impl_vfs_dir_default! {}
+}
+
+/// The `self` link, whose target is the id of the current task.
+struct SelfLink;
+
+impl VfsNodeOps for SelfLink {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        let size = format!("{}", current_tid()).len() as u64;
+        let perm = VfsNodePerm::from_bits_truncate(0o777);
+        Ok(VfsNodeAttr::new(perm, VfsNodeType::SymLink, size, 0))
+    }
+
+    fn readlink(&self, buf: &mut [u8]) -> VfsResult<usize> {
+        let target = format!("{}", current_tid());
+        let len = target.len().min(buf.len());
+        buf[..len].copy_from_slice(&target.as_bytes()[..len]);
+        Ok(len)
+    }
+
+    axfs_vfs::impl_vfs_non_dir_default! {}
+}
+
+/// The directory of a task.
+struct TaskDir {
+    tid: u64,
+    parent: VfsNodeRef,
+}
+
+impl TaskDir {
+    const ENTRIES: &'static [(&'static str, VfsNodeType)] = &[
+        ("comm", VfsNodeType::File),
+        ("stat", VfsNodeType::File),
+        #[cfg(feature = "fdinfo")]
+        ("fdinfo", VfsNodeType::Dir),
+    ];
+
+    fn new(tid: u64, parent: VfsNodeRef) -> Self {
+        Self { tid, parent }
+    }
+}
+
+impl VfsNodeOps for TaskDir {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        find_task(self.tid)?;
+        Ok(dir_attr())
+    }
+
+    fn parent(&self) -> Option<VfsNodeRef> {
+        Some(self.parent.clone())
+    }
+
+    fn lookup(self: Arc<Self>, path: &str) -> VfsResult<VfsNodeRef> {
+        let path = path.trim_start_matches('/');
+        let (name, rest) = path.split_once('/').unwrap_or((path, ""));
+        let tid = self.tid;
+        let node: VfsNodeRef = match name {
+            "" | "." => self,
+            ".." => self.parent.clone(),
+            "comm" => Arc::new(ProcFile::new(move || Ok(find_task(tid)?.name + "\n"))),
+            "stat" => Arc::new(ProcFile::new(move || Ok(task_stat(&find_task(tid)?)))),
+            #[cfg(feature = "fdinfo")]
+            "fdinfo" => Arc::new(crate::fdinfo::FdInfoDir::new(self)),
+            _ => return Err(VfsError::NotFound),
+        };
+        lookup_rest(node, rest)
+    }
+
+    fn read_dir(&self, start_idx: usize, dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
+        find_task(self.tid)?;
+        Ok(fill_dirents(Self::ENTRIES, start_idx, dirents))
+    }
+
+    axfs_vfs::impl_vfs_dir_default! {}
+}
+
+/// A read-only file, whose content is generated when it is read.
+struct ProcFile<F> {
+    content: F,
+}
+
+impl<F: Fn() -> VfsResult<String>> ProcFile<F> {
+    fn new(content: F) -> Self {
+        Self { content }
+    }
+}
+
+impl<F: Fn() -> VfsResult<String> + Send + Sync + 'static> VfsNodeOps for ProcFile<F> {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        let size = (self.content)()?.len();
+        let perm = VfsNodePerm::from_bits_truncate(0o444);
+        Ok(VfsNodeAttr::new(perm, VfsNodeType::File, size as _, 0))
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
+        let content = (self.content)()?;
+        let content = content.as_bytes();
+        let start = content.len().min(offset as usize);
+        let len = buf.len().min(content.len() - start);
+        buf[..len].copy_from_slice(&content[start..start + len]);
+        Ok(len)
+    }
+
+    fn write_at(&self, _offset: u64, _buf: &[u8]) -> VfsResult<usize> {
+        Err(VfsError::PermissionDenied)
+    }
+
+    axfs_vfs::impl_vfs_non_dir_default! {}
+}
+
+/// Returns the content of `/proc/mounts`, with a line for each mount like:
+///
+/// ```text
+/// tmpfs /tmp tmpfs rw 0 0
+/// ```
+fn mounts() -> VfsResult<String> {
+    let mut content = String::new();
+    for (path, fs) in crate::root::mount_table() {
+        let ty = fs.fs_type();
+        content += &format!("{} {} {} rw 0 0\n", ty, path, ty);
+    }
+    Ok(content)
+}
+
+/// Returns the content of `/proc/<tid>/stat`, whose fields up to `rss` are
+/// those of Linux, but with 0 for the ones not tracked.
+fn task_stat(task: &TaskInfo) -> String {
+    let utime = task.cpu_time.as_millis() * USER_HZ / 1000;
+    format!(
+        "{} ({}) {} 0 {} {} 0 -1 0 0 0 0 0 {} 0 0 0 20 0 {} 0 0 0 0\n",
+        task.tid,
+        task.name,
+        task.state,
+        PID,
+        PID,
+        utime,
+        tasks().len()
+    )
+}
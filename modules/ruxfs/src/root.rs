@@ -11,34 +11,88 @@
 //!
 //! TODO: it doesn't work very well if the mount points have containment relationships.
 
-use alloc::{format, string::String, sync::Arc, vec::Vec};
+use alloc::{format, string::String, sync::Arc, vec, vec::Vec};
 use axerrno::{ax_err, AxError, AxResult};
-use axfs_vfs::{VfsError, VfsNodeAttr, VfsNodeOps, VfsNodeRef, VfsNodeType, VfsOps, VfsResult};
+use axfs_vfs::{FileSystemInfo, VfsOps, VfsResult};
+use axfs_vfs::{VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType};
 use axsync::Mutex;
+use core::sync::atomic::{AtomicU16, Ordering};
 use lazy_init::LazyInit;
 
 use crate::api::FileType;
+use crate::cache;
 
-static CURRENT_DIR_PATH: Mutex<String> = Mutex::new(String::new());
-static CURRENT_DIR: LazyInit<Mutex<VfsNodeRef>> = LazyInit::new();
+/// A current directory, with its canonical absolute path ending with `/`.
+struct CurrentDir {
+    path: String,
+    node: VfsNodeRef,
+}
+
+/// Current directory of the tasks which have not changed it, or of all the
+/// tasks without `multitask`.
+static CURRENT_DIR: LazyInit<Mutex<Arc<CurrentDir>>> = LazyInit::new();
+
+/// File mode creation mask, the permission bits cleared on the new nodes.
+static UMASK: AtomicU16 = AtomicU16::new(0o022);
 
 /// mount point information
 pub struct MountPoint {
     path: &'static str,
     fs: Arc<dyn VfsOps>,
+    /// Whether `fs` is a [`BindFs`], which aliases nodes visible elsewhere.
+    bind: bool,
+}
+
+/// A bind mount, which exposes the node `root` of the filesystem `fs` at
+/// another path. It does not own `fs`, so it is not unmounted with it.
+struct BindFs {
+    fs: Arc<dyn VfsOps>,
+    root: VfsNodeRef,
 }
 
 struct RootDirectory {
     main_fs: Arc<dyn VfsOps>,
-    mounts: Vec<MountPoint>,
+    mounts: Mutex<Vec<Arc<MountPoint>>>,
 }
 
-static ROOT_DIR: LazyInit<Arc<RootDirectory>> = LazyInit::new();
+/// Root directory of the tasks which have not switched to another mount
+/// namespace, or of all the tasks without `multitask`.
+static ROOT_DIR: LazyInit<Mutex<Arc<RootDirectory>>> = LazyInit::new();
+
+/// A mount namespace, i.e. a root directory with its own table of mounted
+/// filesystems.
+///
+/// The namespace in use is that of the calling task, which the tasks it
+/// creates later inherit.
+#[derive(Clone)]
+pub struct MountNamespace(Arc<RootDirectory>);
 
 impl MountPoint {
     /// create new MountPoint from data
     pub fn new(path: &'static str, fs: Arc<dyn VfsOps>) -> Self {
-        Self { path, fs }
+        Self {
+            path,
+            fs,
+            bind: false,
+        }
+    }
+}
+
+impl VfsOps for BindFs {
+    fn statfs(&self) -> VfsResult<FileSystemInfo> {
+        self.fs.statfs()
+    }
+
+    fn root_dir(&self) -> VfsNodeRef {
+        self.root.clone()
+    }
+
+    fn fs_type(&self) -> &str {
+        self.fs.fs_type()
+    }
+
+    fn is_volatile(&self) -> bool {
+        self.fs.is_volatile()
     }
 }
 
@@ -48,22 +102,137 @@ impl Drop for MountPoint {
     }
 }
 
+impl MountNamespace {
+    /// Creates a namespace with `main_fs` on `/`, and nothing mounted on it.
+    pub fn new(main_fs: Arc<dyn VfsOps>) -> Self {
+        Self(Arc::new(RootDirectory::new(main_fs)))
+    }
+
+    /// Returns the namespace in use.
+    pub fn current() -> Self {
+        Self(root_dir())
+    }
+
+    /// Creates a namespace with a copy of the mount table of this one. The
+    /// later mounts in either of them are not visible in the other.
+    pub fn duplicate(&self) -> Self {
+        Self(Arc::new(RootDirectory {
+            main_fs: self.0.main_fs.clone(),
+            mounts: Mutex::new(self.0.mounts.lock().clone()),
+        }))
+    }
+
+    /// Mounts `fs` on `path` in this namespace.
+    pub fn mount(&self, path: &'static str, fs: Arc<dyn VfsOps>) -> AxResult {
+        self.0.mount(path, fs)?;
+        // the nodes cached below `path` are now hidden
+        cache::invalidate(path);
+        Ok(())
+    }
+
+    /// Bind-mounts the node at `src` on `path` in this namespace, so that the
+    /// lookups below `path` find the nodes below `src`. Both paths must be
+    /// canonical and absolute.
+    ///
+    /// The node may be a file or a directory, but `path` must exist and be of
+    /// the same kind, or it fails with
+    /// [`NotADirectory`](AxError::NotADirectory). The filesystems mounted
+    /// below `src` are only visible below `path` if `recursive` is set.
+    pub fn bind(&self, src: &str, path: &'static str, recursive: bool) -> AxResult {
+        let node = self.0.clone().lookup(src)?;
+        if node.get_attr()?.is_dir() != self.0.clone().lookup(path)?.get_attr()?.is_dir() {
+            return ax_err!(NotADirectory);
+        }
+        let fs = self.0.lookup_mounted_fs(src, |fs, _| Ok(fs))?;
+        // the mounts below `src`, parents first, before `path` is added
+        let submounts: Vec<_> = if recursive {
+            let mounts = self.0.mounts.lock();
+            mounts
+                .iter()
+                .filter(|mp| mp.path != src && is_below(mp.path, src))
+                .map(|mp| (&mp.path[src.len()..], mp.fs.clone()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.0.mount_point(MountPoint {
+            path,
+            fs: Arc::new(BindFs { fs, root: node }),
+            bind: true,
+        })?;
+        for (rest, fs) in submounts {
+            let sub_path = format!("{}/{}", path.trim_end_matches('/'), rest.trim_matches('/'));
+            // the mount table keeps the path until it is unmounted
+            let sub_path = alloc::boxed::Box::leak(sub_path.into_boxed_str());
+            let root = fs.root_dir();
+            self.0.mount_point(MountPoint {
+                path: sub_path,
+                fs: Arc::new(BindFs { fs, root }),
+                bind: true,
+            })?;
+        }
+        // the nodes cached below `path` are now hidden
+        cache::invalidate(path);
+        Ok(())
+    }
+
+    /// Unmounts the filesystem mounted on `path` in this namespace.
+    ///
+    /// Fails with [`ResourceBusy`](AxError::ResourceBusy) if another
+    /// filesystem is mounted below `path`, or if the current directory of the
+    /// calling task is in the filesystem, unless `force` is set. The files which are still open
+    /// in it remain usable.
+    pub fn umount(&self, path: &str, force: bool) -> AxResult {
+        if !force && is_below(&current_dir_ref().path, path) {
+            return ax_err!(ResourceBusy);
+        }
+        self.0.umount(path)?;
+        // the nodes cached below `path` are in the unmounted filesystem
+        cache::invalidate(path);
+        Ok(())
+    }
+
+    /// Makes this namespace the one in use by the calling task, and returns
+    /// the previous one.
+    ///
+    /// The current directory of the calling task is looked up again in this
+    /// namespace, and is `/` if it does not exist there.
+    pub fn set_current(&self) -> Self {
+        let old = root_dir();
+        set_root_dir(self.0.clone());
+        let cwd = current_dir_ref().path.clone();
+        if set_current_dir(&cwd).is_err() {
+            set_current_dir_ref(CurrentDir {
+                path: "/".into(),
+                node: self.0.clone(),
+            });
+        }
+        Self(old)
+    }
+}
+
 impl RootDirectory {
     pub const fn new(main_fs: Arc<dyn VfsOps>) -> Self {
         Self {
             main_fs,
-            mounts: Vec::new(),
+            mounts: Mutex::new(Vec::new()),
         }
     }
 
-    pub fn mount(&mut self, path: &'static str, fs: Arc<dyn VfsOps>) -> AxResult {
+    pub fn mount(&self, path: &'static str, fs: Arc<dyn VfsOps>) -> AxResult {
+        self.mount_point(MountPoint::new(path, fs))
+    }
+
+    fn mount_point(&self, mp: MountPoint) -> AxResult {
+        let path = mp.path;
         if path == "/" {
             return ax_err!(InvalidInput, "cannot mount root filesystem");
         }
         if !path.starts_with('/') {
             return ax_err!(InvalidInput, "mount path must start with '/'");
         }
-        if self.mounts.iter().any(|mp| mp.path == path) {
+        let mut mounts = self.mounts.lock();
+        if mounts.iter().any(|mp| mp.path == path) {
             return ax_err!(InvalidInput, "mount point already exists");
         }
         // create the mount point in the main filesystem if it does not exist
@@ -75,17 +244,39 @@ impl RootDirectory {
                 }
             }
         }
-        fs.mount(path, self.main_fs.root_dir().lookup(path)?)?;
-        self.mounts.push(MountPoint::new(path, fs));
+        mp.fs.mount(path, self.main_fs.root_dir().lookup(path)?)?;
+        mounts.push(Arc::new(mp));
         Ok(())
     }
 
-    pub fn _umount(&mut self, path: &str) {
-        self.mounts.retain(|mp| mp.path != path);
+    /// Removes the mount on `path`. It is busy while another filesystem is
+    /// mounted below it.
+    pub fn umount(&self, path: &str) -> AxResult {
+        let mut mounts = self.mounts.lock();
+        let idx = match mounts.iter().position(|mp| mp.path == path) {
+            Some(idx) => idx,
+            None if path == "/" => return ax_err!(ResourceBusy),
+            None => return ax_err!(InvalidInput, "not a mount point"),
+        };
+        if mounts
+            .iter()
+            .any(|mp| mp.path != path && is_below(mp.path, path))
+        {
+            return ax_err!(ResourceBusy);
+        }
+        // the filesystem is unmounted when no namespace has it anymore
+        mounts.remove(idx);
+        Ok(())
     }
 
     pub fn contains(&self, path: &str) -> bool {
-        self.mounts.iter().any(|mp| mp.path == path)
+        self.mounts.lock().iter().any(|mp| mp.path == path)
+    }
+
+    /// Whether a node may be visible at several paths, because of the bind
+    /// mounts.
+    fn has_binds(&self) -> bool {
+        self.mounts.lock().iter().any(|mp| mp.bind)
     }
 
     fn lookup_mounted_fs<F, T>(&self, path: &str, f: F) -> AxResult<T>
@@ -98,24 +289,21 @@ impl RootDirectory {
             return self.lookup_mounted_fs(rest, f);
         }
 
-        let mut idx = 0;
+        let mut fs = self.main_fs.clone();
         let mut max_len = 0;
 
         // Find the filesystem that has the longest mounted path match
         // TODO: more efficient, e.g. trie
-        for (i, mp) in self.mounts.iter().enumerate() {
+        for mp in self.mounts.lock().iter() {
             // skip the first '/'
             if path.starts_with(&mp.path[1..]) && mp.path.len() - 1 > max_len {
                 max_len = mp.path.len() - 1;
-                idx = i;
+                fs = mp.fs.clone();
             }
         }
 
-        if max_len == 0 {
-            f(self.main_fs.clone(), path) // not matched any mount point
-        } else {
-            f(self.mounts[idx].fs.clone(), &path[max_len..]) // matched at `idx`
-        }
+        // not matched any mount point if `max_len` is 0
+        f(fs, &path[max_len..])
     }
 }
 
@@ -126,8 +314,25 @@ impl VfsNodeOps for RootDirectory {
         self.main_fs.root_dir().get_attr()
     }
 
+    fn setattr(
+        &self,
+        mode: Option<VfsNodePerm>,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        size: Option<u64>,
+    ) -> VfsResult {
+        self.main_fs.root_dir().setattr(mode, uid, gid, size)
+    }
+
     fn lookup(self: Arc<Self>, path: &str) -> VfsResult<VfsNodeRef> {
-        self.lookup_mounted_fs(path, |fs, rest_path| fs.root_dir().lookup(rest_path))
+        self.lookup_mounted_fs(path, |fs, rest_path| {
+            if rest_path.is_empty() {
+                // the root of a bind mount may be a file
+                Ok(fs.root_dir())
+            } else {
+                fs.root_dir().lookup(rest_path)
+            }
+        })
     }
 
     fn create(&self, path: &str, ty: VfsNodeType) -> VfsResult {
@@ -150,6 +355,30 @@ impl VfsNodeOps for RootDirectory {
         })
     }
 
+    fn symlink(&self, path: &str, target: &str) -> VfsResult {
+        self.lookup_mounted_fs(path, |fs, rest_path| {
+            if rest_path.is_empty() {
+                ax_err!(AlreadyExists)
+            } else {
+                fs.root_dir().symlink(rest_path, target)
+            }
+        })
+    }
+
+    fn link(&self, path: &str, node: VfsNodeRef) -> VfsResult {
+        self.lookup_mounted_fs(path, |fs, rest_path| {
+            if rest_path.is_empty() {
+                ax_err!(AlreadyExists)
+            } else {
+                fs.root_dir().link(rest_path, node)
+            }
+        })
+    }
+
+    fn tmpfile(&self) -> VfsResult<VfsNodeRef> {
+        self.main_fs.root_dir().tmpfile()
+    }
+
     fn rename(&self, src_path: &str, dst_path: &str) -> VfsResult {
         self.lookup_mounted_fs(src_path, |fs, rest_path| {
             if rest_path.is_empty() {
@@ -167,7 +396,7 @@ pub(crate) fn init_rootfs(mount_points: Vec<MountPoint>) {
         .expect("No filesystem found")
         .fs
         .clone();
-    let mut root_dir = RootDirectory::new(main_fs);
+    let root_dir = RootDirectory::new(main_fs);
 
     for mp in mount_points.iter().skip(1) {
         let path = mp.path;
@@ -177,33 +406,188 @@ pub(crate) fn init_rootfs(mount_points: Vec<MountPoint>) {
         root_dir.mount(path, vfsops).expect(&message);
     }
 
-    ROOT_DIR.init_by(Arc::new(root_dir));
-    CURRENT_DIR.init_by(Mutex::new(ROOT_DIR.clone()));
-    *CURRENT_DIR_PATH.lock() = "/".into();
+    let root_dir = Arc::new(root_dir);
+    ROOT_DIR.init_by(Mutex::new(root_dir.clone()));
+    CURRENT_DIR.init_by(Mutex::new(Arc::new(CurrentDir {
+        path: "/".into(),
+        node: root_dir,
+    })));
+}
+
+/// Returns the current directory of the calling task.
+fn current_dir_ref() -> Arc<CurrentDir> {
+    #[cfg(feature = "multitask")]
+    if let Some(cwd) = ruxtask::current_may_uninit().and_then(|curr| curr.cwd()) {
+        if let Ok(cwd) = cwd.downcast::<CurrentDir>() {
+            return cwd;
+        }
+    }
+    CURRENT_DIR.lock().clone()
+}
+
+/// Changes the current directory of the calling task, and of the tasks it
+/// creates later.
+fn set_current_dir_ref(cwd: CurrentDir) {
+    #[cfg(feature = "multitask")]
+    if let Some(curr) = ruxtask::current_may_uninit() {
+        curr.set_cwd(Arc::new(cwd));
+        return;
+    }
+    *CURRENT_DIR.lock() = Arc::new(cwd);
+}
+
+/// Whether `path` is `dir` or a path below it, both being canonical.
+fn is_below(path: &str, dir: &str) -> bool {
+    match path.strip_prefix(dir) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || dir.ends_with('/'),
+        None => false,
+    }
+}
+
+/// Returns the root directory of the mount namespace of the calling task.
+fn root_dir() -> Arc<RootDirectory> {
+    #[cfg(feature = "multitask")]
+    if let Some(mnt_ns) = ruxtask::current_may_uninit().and_then(|curr| curr.mnt_ns()) {
+        if let Ok(root) = mnt_ns.downcast::<RootDirectory>() {
+            return root;
+        }
+    }
+    ROOT_DIR.lock().clone()
+}
+
+/// Switches the calling task, and the tasks it creates later, to the mount
+/// namespace of `root`.
+fn set_root_dir(root: Arc<RootDirectory>) {
+    #[cfg(feature = "multitask")]
+    if let Some(curr) = ruxtask::current_may_uninit() {
+        curr.set_mnt_ns(root);
+        return;
+    }
+    *ROOT_DIR.lock() = root;
+    // the nodes cached are those of the previous namespace
+    cache::clear();
+}
+
+/// Returns the mount path and the filesystem of each mount in the namespace
+/// in use, `/` first.
+#[cfg(feature = "procfs")]
+pub(crate) fn mount_table() -> Vec<(&'static str, Arc<dyn VfsOps>)> {
+    let root = root_dir();
+    let mounts = root.mounts.lock();
+    let mut table = Vec::with_capacity(mounts.len() + 1);
+    table.push(("/", root.main_fs.clone()));
+    table.extend(mounts.iter().map(|mp| (mp.path, mp.fs.clone())));
+    table
 }
 
 fn parent_node_of(dir: Option<&VfsNodeRef>, path: &str) -> VfsNodeRef {
     if path.starts_with('/') {
-        ROOT_DIR.clone()
+        root_dir()
+    } else {
+        dir.cloned()
+            .unwrap_or_else(|| current_dir_ref().node.clone())
+    }
+}
+
+/// Fails with [`NameTooLong`](AxError::NameTooLong) if `path` does not fit
+/// in `PATH_MAX` bytes with its terminating null byte.
+fn check_path_len(path: &str) -> AxResult {
+    if path.len() >= ruxconfig::PATH_MAX {
+        ax_err!(NameTooLong)
     } else {
-        dir.cloned().unwrap_or_else(|| CURRENT_DIR.lock().clone())
+        Ok(())
     }
 }
 
 pub(crate) fn absolute_path(path: &str) -> AxResult<String> {
+    check_path_len(path)?;
     if path.starts_with('/') {
         Ok(axfs_vfs::path::canonicalize(path))
     } else {
-        let path = CURRENT_DIR_PATH.lock().clone() + path;
+        let path = current_dir_ref().path.clone() + path;
         Ok(axfs_vfs::path::canonicalize(&path))
     }
 }
 
-pub(crate) fn lookup(dir: Option<&VfsNodeRef>, path: &str) -> AxResult<VfsNodeRef> {
+/// Returns the canonical absolute path of `path` if it does not depend on
+/// `dir` and it is not in a volatile filesystem, like procfs, so that its
+/// node can be cached.
+///
+/// The cache is shared by all the tasks, so only the nodes of the mount
+/// namespace in [`ROOT_DIR`] are cached.
+fn cache_key(dir: Option<&VfsNodeRef>, path: &str) -> Option<String> {
+    if dir.is_some() && !path.starts_with('/') {
+        return None;
+    }
+    let root = root_dir();
+    if !Arc::ptr_eq(&root, &ROOT_DIR.lock()) {
+        return None;
+    }
+    let key = absolute_path(path).ok()?;
+    let volatile = root
+        .lookup_mounted_fs(&key, |fs, _| Ok(fs.is_volatile()))
+        .ok()?;
+    (!volatile).then_some(key)
+}
+
+/// Drops the cached nodes at and below `path`, or all of them if `path` is
+/// relative to `dir` or the node may also be cached at another path through
+/// a bind mount.
+fn invalidate_cache(dir: Option<&VfsNodeRef>, path: &str) {
+    match cache_key(dir, path) {
+        Some(key) if !root_dir().has_binds() => cache::invalidate(&key),
+        _ => cache::clear(),
+    }
+}
+
+/// Looks up the node at `path`, which is the symbolic link itself if `path`
+/// names one.
+pub(crate) fn lookup_nofollow(dir: Option<&VfsNodeRef>, path: &str) -> AxResult<VfsNodeRef> {
     if path.is_empty() {
         return ax_err!(NotFound);
     }
-    let node = parent_node_of(dir, path).lookup(path)?;
+    check_path_len(path)?;
+    let key = cache_key(dir, path);
+    let node = match key.as_deref().and_then(cache::get) {
+        Some(node) => node,
+        None => {
+            cache::count_miss();
+            let node = parent_node_of(dir, path).lookup(path)?;
+            if let Some(key) = key {
+                cache::insert(key, node.clone());
+            }
+            node
+        }
+    };
+    if path.ends_with('/') && !node.get_attr()?.is_dir() {
+        ax_err!(NotADirectory)
+    } else {
+        Ok(node)
+    }
+}
+
+/// Looks up the node at `path`, following the symbolic link it names, if any.
+///
+/// Fails with [`FilesystemLoop`](AxError::FilesystemLoop) if more than
+/// `SYMLOOP_MAX` links are followed, as they likely form a loop.
+///
+/// TODO: follow the symbolic links in the intermediate components.
+pub(crate) fn lookup(dir: Option<&VfsNodeRef>, path: &str) -> AxResult<VfsNodeRef> {
+    let trimmed = path.trim_end_matches('/');
+    if trimmed.is_empty() {
+        return lookup_nofollow(dir, path);
+    }
+    let mut curr = String::from(trimmed);
+    let mut node = lookup_nofollow(dir, &curr)?;
+    let mut followed = 0;
+    while node.get_attr()?.file_type().is_symlink() {
+        followed += 1;
+        if followed > ruxconfig::SYMLOOP_MAX {
+            return ax_err!(FilesystemLoop);
+        }
+        curr = link_target_path(&curr, &read_link_node(&node)?);
+        node = lookup_nofollow(dir, curr.trim_end_matches('/'))?;
+    }
     if path.ends_with('/') && !node.get_attr()?.is_dir() {
         ax_err!(NotADirectory)
     } else {
@@ -211,44 +595,232 @@ pub(crate) fn lookup(dir: Option<&VfsNodeRef>, path: &str) -> AxResult<VfsNodeRe
     }
 }
 
-pub(crate) fn create_file(dir: Option<&VfsNodeRef>, path: &str) -> AxResult<VfsNodeRef> {
+/// Whether the lexical resolution of `path` leaves the directory it starts
+/// from, i.e. `path` is absolute or has more `..` than the names before them.
+fn escapes_beneath(path: &str) -> bool {
+    if path.starts_with('/') {
+        return true;
+    }
+    let mut depth = 0usize;
+    for name in path.split('/') {
+        match name {
+            "" | "." => {}
+            ".." => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => return true,
+            },
+            _ => depth += 1,
+        }
+    }
+    false
+}
+
+/// Checks the restrictions of `openat2` on the resolution of `path`:
+///
+/// - `beneath`: the path and the symbolic link it names must not leave `dir`,
+///   or fails with [`CrossesDevices`](AxError::CrossesDevices).
+/// - `no_symlinks`: no component may be a symbolic link, or fails with
+///   [`FilesystemLoop`](AxError::FilesystemLoop).
+///
+/// The components which do not exist yet are not checked, as they may be
+/// created by the caller.
+pub(crate) fn check_resolve(
+    dir: Option<&VfsNodeRef>,
+    path: &str,
+    beneath: bool,
+    no_symlinks: bool,
+) -> AxResult {
+    check_path_len(path)?;
+    let mut curr = String::from(path.trim_end_matches('/'));
+    let mut followed = 0;
+    'resolve: loop {
+        if beneath && escapes_beneath(&curr) {
+            return ax_err!(CrossesDevices);
+        }
+        let mut end = 0;
+        while end < curr.len() {
+            end = curr[end + 1..]
+                .find('/')
+                .map_or(curr.len(), |i| end + 1 + i);
+            let node = match lookup_nofollow(dir, &curr[..end]) {
+                Ok(node) => node,
+                Err(AxError::NotFound) => return Ok(()),
+                Err(e) => return Err(e),
+            };
+            if !node.get_attr()?.file_type().is_symlink() {
+                continue;
+            }
+            if no_symlinks {
+                return ax_err!(FilesystemLoop);
+            }
+            if end == curr.len() {
+                // the last component is followed by the lookup
+                followed += 1;
+                if followed > ruxconfig::SYMLOOP_MAX {
+                    return ax_err!(FilesystemLoop);
+                }
+                curr = link_target_path(&curr, &read_link_node(&node)?);
+                continue 'resolve;
+            }
+        }
+        return Ok(());
+    }
+}
+
+/// Returns the path of `target` of the symbolic link at `link`, which is
+/// relative to the directory of the link if it is not absolute.
+fn link_target_path(link: &str, target: &str) -> String {
+    if target.starts_with('/') {
+        return target.into();
+    }
+    match link.rfind('/') {
+        Some(idx) => String::from(&link[..=idx]) + target,
+        None => target.into(),
+    }
+}
+
+fn read_link_node(node: &VfsNodeRef) -> AxResult<String> {
+    let mut buf = vec![0; node.get_attr()?.size() as usize];
+    let len = node.readlink(&mut buf)?;
+    buf.truncate(len);
+    String::from_utf8(buf).map_err(|_| AxError::InvalidData)
+}
+
+/// Returns the target of the symbolic link at `path`.
+pub(crate) fn read_link(dir: Option<&VfsNodeRef>, path: &str) -> AxResult<String> {
+    let node = lookup_nofollow(dir, path)?;
+    if !node.get_attr()?.file_type().is_symlink() {
+        return ax_err!(InvalidInput);
+    }
+    read_link_node(&node)
+}
+
+/// Creates a symbolic link at `path` which points to `target`.
+pub(crate) fn symlink(dir: Option<&VfsNodeRef>, path: &str, target: &str) -> AxResult {
+    if path.is_empty() || target.is_empty() {
+        return ax_err!(NotFound);
+    } else if path.ends_with('/') {
+        return ax_err!(NotADirectory);
+    }
+    check_path_len(target)?;
+    match lookup_nofollow(dir, path) {
+        Ok(_) => ax_err!(AlreadyExists),
+        Err(AxError::NotFound) => parent_node_of(dir, path).symlink(path, target),
+        Err(e) => Err(e),
+    }
+}
+
+/// Creates a hard link at `path` to the existing `node`.
+pub(crate) fn link(dir: Option<&VfsNodeRef>, path: &str, node: &VfsNodeRef) -> AxResult {
+    if path.is_empty() {
+        return ax_err!(NotFound);
+    } else if path.ends_with('/') {
+        return ax_err!(NotADirectory);
+    }
+    match lookup_nofollow(dir, path) {
+        Ok(_) => ax_err!(AlreadyExists),
+        Err(AxError::NotFound) => parent_node_of(dir, path).link(path, node.clone()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Sets the permission of the node just created to `perm`, without the bits
+/// of the umask.
+fn set_created_perm(node: &VfsNodeRef, perm: VfsNodePerm) -> AxResult {
+    match node.setattr(Some(perm & !umask()), None, None, None) {
+        // the filesystem has no permissions, e.g. FAT
+        Err(AxError::Unsupported) => Ok(()),
+        res => res,
+    }
+}
+
+pub(crate) fn create_file(
+    dir: Option<&VfsNodeRef>,
+    path: &str,
+    perm: VfsNodePerm,
+) -> AxResult<VfsNodeRef> {
     if path.is_empty() {
         return ax_err!(NotFound);
     } else if path.ends_with('/') {
         return ax_err!(NotADirectory);
     }
+    check_path_len(path)?;
     let parent = parent_node_of(dir, path);
     parent.create(path, VfsNodeType::File)?;
-    parent.lookup(path)
+    let node = parent.lookup(path)?;
+    set_created_perm(&node, perm)?;
+    Ok(node)
+}
+
+/// Creates a regular file without a name in the directory at `path`.
+pub(crate) fn create_tmpfile(
+    dir: Option<&VfsNodeRef>,
+    path: &str,
+    perm: VfsNodePerm,
+) -> AxResult<VfsNodeRef> {
+    let parent = lookup(dir, path)?;
+    if !parent.get_attr()?.is_dir() {
+        return ax_err!(NotADirectory);
+    }
+    let node = parent.tmpfile()?;
+    set_created_perm(&node, perm)?;
+    Ok(node)
+}
+
+pub(crate) fn create_dir(dir: Option<&VfsNodeRef>, path: &str, perm: VfsNodePerm) -> AxResult {
+    match lookup_nofollow(dir, path) {
+        Ok(_) => ax_err!(AlreadyExists),
+        Err(AxError::NotFound) => {
+            let parent = parent_node_of(dir, path);
+            parent.create(path, VfsNodeType::Dir)?;
+            set_created_perm(&parent.lookup(path)?, perm)
+        }
+        Err(e) => Err(e),
+    }
 }
 
-pub(crate) fn create_dir(dir: Option<&VfsNodeRef>, path: &str) -> AxResult {
-    match lookup(dir, path) {
+/// Creates the node of a Unix domain socket bound to `path`.
+pub(crate) fn create_socket(dir: Option<&VfsNodeRef>, path: &str, perm: VfsNodePerm) -> AxResult {
+    if path.is_empty() {
+        return ax_err!(NotFound);
+    } else if path.ends_with('/') {
+        return ax_err!(NotADirectory);
+    }
+    check_path_len(path)?;
+    match lookup_nofollow(dir, path) {
         Ok(_) => ax_err!(AlreadyExists),
-        Err(AxError::NotFound) => parent_node_of(dir, path).create(path, VfsNodeType::Dir),
+        Err(AxError::NotFound) => {
+            let parent = parent_node_of(dir, path);
+            parent.create(path, VfsNodeType::Socket)?;
+            set_created_perm(&parent.lookup(path)?, perm)
+        }
         Err(e) => Err(e),
     }
 }
 
-pub(crate) fn create_dir_all(dir: Option<&VfsNodeRef>, path: &str) -> AxResult {
-    match lookup(dir, path) {
+pub(crate) fn create_dir_all(dir: Option<&VfsNodeRef>, path: &str, perm: VfsNodePerm) -> AxResult {
+    match lookup_nofollow(dir, path) {
         Ok(_) => ax_err!(AlreadyExists),
         Err(AxError::NotFound) => {
-            parent_node_of(dir, path).create_recursive(path, VfsNodeType::Dir)
+            let parent = parent_node_of(dir, path);
+            parent.create_recursive(path, VfsNodeType::Dir)?;
+            set_created_perm(&parent.lookup(path)?, perm)
         }
         Err(e) => Err(e),
     }
 }
 
 pub(crate) fn remove_file(dir: Option<&VfsNodeRef>, path: &str) -> AxResult {
-    let node = lookup(dir, path)?;
+    let node = lookup_nofollow(dir, path)?;
     let attr = node.get_attr()?;
     if attr.is_dir() {
         ax_err!(IsADirectory)
     } else if !attr.perm().owner_writable() {
         ax_err!(PermissionDenied)
     } else {
-        parent_node_of(dir, path).remove(path)
+        parent_node_of(dir, path).remove(path)?;
+        invalidate_cache(dir, path);
+        Ok(())
     }
 }
 
@@ -266,23 +838,43 @@ pub(crate) fn remove_dir(dir: Option<&VfsNodeRef>, path: &str) -> AxResult {
     {
         return ax_err!(InvalidInput);
     }
-    if ROOT_DIR.contains(&absolute_path(path)?) {
+    if root_dir().contains(&absolute_path(path)?) {
         return ax_err!(PermissionDenied);
     }
 
-    let node = lookup(dir, path)?;
+    let node = lookup_nofollow(dir, path)?;
     let attr = node.get_attr()?;
     if !attr.is_dir() {
         ax_err!(NotADirectory)
     } else if !attr.perm().owner_writable() {
         ax_err!(PermissionDenied)
     } else {
-        parent_node_of(dir, path).remove(path)
+        parent_node_of(dir, path).remove(path)?;
+        invalidate_cache(dir, path);
+        Ok(())
     }
 }
 
+/// Returns the attributes of the filesystem which contains `path`.
+pub(crate) fn statfs(path: &str) -> AxResult<FileSystemInfo> {
+    lookup(None, path)?;
+    let path = absolute_path(path)?;
+    root_dir().lookup_mounted_fs(&path, |fs, _| match fs.statfs() {
+        Err(AxError::Unsupported) => Ok(FileSystemInfo::new(0, 512, 0, 0)),
+        res => res,
+    })
+}
+
+pub(crate) fn umask() -> VfsNodePerm {
+    VfsNodePerm::from_bits_truncate(UMASK.load(Ordering::Acquire))
+}
+
+pub(crate) fn set_umask(mask: VfsNodePerm) -> VfsNodePerm {
+    VfsNodePerm::from_bits_truncate(UMASK.swap(mask.bits(), Ordering::AcqRel))
+}
+
 pub(crate) fn current_dir() -> AxResult<String> {
-    Ok(CURRENT_DIR_PATH.lock().clone())
+    Ok(current_dir_ref().path.clone())
 }
 
 pub(crate) fn set_current_dir(path: &str) -> AxResult {
@@ -291,8 +883,10 @@ pub(crate) fn set_current_dir(path: &str) -> AxResult {
         abs_path += "/";
     }
     if abs_path == "/" {
-        *CURRENT_DIR.lock() = ROOT_DIR.clone();
-        *CURRENT_DIR_PATH.lock() = "/".into();
+        set_current_dir_ref(CurrentDir {
+            path: abs_path,
+            node: root_dir(),
+        });
         return Ok(());
     }
 
@@ -303,16 +897,23 @@ pub(crate) fn set_current_dir(path: &str) -> AxResult {
     } else if !attr.perm().owner_executable() {
         ax_err!(PermissionDenied)
     } else {
-        *CURRENT_DIR.lock() = node;
-        *CURRENT_DIR_PATH.lock() = abs_path;
+        set_current_dir_ref(CurrentDir {
+            path: abs_path,
+            node,
+        });
         Ok(())
     }
 }
 
 pub(crate) fn rename(old: &str, new: &str) -> AxResult {
+    check_path_len(old)?;
+    check_path_len(new)?;
     if parent_node_of(None, new).lookup(new).is_ok() {
         warn!("dst file already exist, now remove it");
         remove_file(None, new)?;
     }
-    parent_node_of(None, old).rename(old, new)
+    parent_node_of(None, old).rename(old, new)?;
+    invalidate_cache(None, old);
+    invalidate_cache(None, new);
+    Ok(())
 }
@@ -173,6 +173,55 @@ fn test_remove_file_dir() -> Result<()> {
     Ok(())
 }
 
+fn test_lookup_cache() -> Result<()> {
+    use ruxfs::fops::{lookup, lookup_stats};
+
+    let fname = "/cached-file.txt";
+    println!("test lookup cache {:?}:", fname);
+    fs::write(fname, "cached\n")?;
+
+    // repeated lookups of the same path only traverse the filesystem once
+    let before = lookup_stats();
+    for _ in 0..10 {
+        assert!(lookup(fname)?.get_attr()?.is_file());
+        assert!(lookup("/.//cached-file.txt")?.get_attr()?.is_file());
+    }
+    let after = lookup_stats();
+    assert!(after.misses - before.misses <= 1);
+    assert!(after.hits - before.hits >= 19);
+
+    // removed paths are not served from the cache anymore, and a new file
+    // at the same path is found instead
+    fs::remove_file(fname)?;
+    assert_err!(lookup(fname), NotFound);
+    fs::write(fname, "recreated\n")?;
+    assert_eq!(lookup(fname)?.get_attr()?.size(), 10);
+
+    // renamed paths as well, on the filesystems that support renaming
+    match fs::rename(fname, "/cached-file2.txt") {
+        Err(Error::Unsupported) => fs::remove_file(fname)?,
+        res => {
+            res?;
+            assert_err!(lookup(fname), NotFound);
+            assert_eq!(lookup("/cached-file2.txt")?.get_attr()?.size(), 10);
+            fs::remove_file("/cached-file2.txt")?;
+            assert_err!(lookup("/cached-file2.txt"), NotFound);
+        }
+    }
+
+    // so are the paths below a removed directory
+    fs::create_dir("/cached-dir")?;
+    fs::write("/cached-dir/f.txt", "")?;
+    assert!(lookup("/cached-dir/f.txt").is_ok());
+    fs::remove_file("/cached-dir/f.txt")?;
+    fs::remove_dir("/cached-dir")?;
+    assert_err!(lookup("/cached-dir/f.txt"), NotFound);
+    assert_err!(lookup("/cached-dir"), NotFound);
+
+    println!("test_lookup_cache() OK!");
+    Ok(())
+}
+
 fn test_devfs_ramfs() -> Result<()> {
     const N: usize = 32;
     let mut buf = [1; N];
@@ -252,11 +301,446 @@ fn test_devfs_ramfs() -> Result<()> {
     Ok(())
 }
 
+fn test_path_limits() -> Result<()> {
+    use ruxfs::fops::{lookup, lookup_nofollow, symlink};
+
+    println!("test path limits:");
+    let long_path = "/tmp/".to_string() + &"a".repeat(ruxconfig::PATH_MAX);
+    assert_err!(fs::metadata(&long_path), NameTooLong);
+    assert_err!(File::create(&long_path), NameTooLong);
+    assert_err!(fs::create_dir(&long_path), NameTooLong);
+
+    // a symbolic link to itself, and two links to each other
+    symlink("/tmp/self-link", "/tmp/self-link")?;
+    symlink("ping-link", "/tmp/pong-link")?;
+    symlink("pong-link", "/tmp/ping-link")?;
+    assert!(lookup_nofollow("/tmp/self-link").is_ok());
+    assert_err!(lookup("/tmp/self-link"), FilesystemLoop);
+    assert_err!(lookup("/tmp/ping-link"), FilesystemLoop);
+    assert_err!(File::open("/tmp/pong-link"), FilesystemLoop);
+    fs::remove_file("/tmp/self-link")?;
+    fs::remove_file("/tmp/ping-link")?;
+    fs::remove_file("/tmp/pong-link")?;
+
+    println!("test_path_limits() OK!");
+    Ok(())
+}
+
+fn test_umask() -> Result<()> {
+    use ruxfs::fops::{lookup, set_umask, FilePerm};
+
+    println!("test umask:");
+    let old = set_umask(FilePerm::from_bits_truncate(0o077));
+    assert_eq!(old.bits(), 0o022);
+
+    let fname = "/tmp/umask.txt";
+    File::options()
+        .write(true)
+        .create_new(true)
+        .mode(0o666)
+        .open(fname)?;
+    assert_eq!(fs::metadata(fname)?.permissions().bits(), 0o600);
+
+    // a new file is writable even if its mode is not
+    let ro_name = "/tmp/umask-ro.txt";
+    let mut file = File::options()
+        .write(true)
+        .create_new(true)
+        .mode(0o444)
+        .open(ro_name)?;
+    assert_eq!(file.write(b"test")?, 4);
+    drop(file);
+    assert_eq!(fs::metadata(ro_name)?.permissions().bits(), 0o400);
+    assert_err!(File::options().write(true).open(ro_name), PermissionDenied);
+
+    fs::DirBuilder::new().mode(0o755).create("/tmp/umask-dir")?;
+    assert_eq!(fs::metadata("/tmp/umask-dir")?.permissions().bits(), 0o700);
+
+    assert_eq!(set_umask(old).bits(), 0o077);
+    fs::remove_file(fname)?;
+    lookup(ro_name)?.setattr(Some(FilePerm::default_file()), None, None, None)?;
+    fs::remove_file(ro_name)?;
+    fs::remove_dir("/tmp/umask-dir")?;
+
+    println!("test_umask() OK!");
+    Ok(())
+}
+
+fn test_mount_namespace() -> Result<()> {
+    use axfs_ramfs::RamFileSystem;
+    use ruxfs::{fops::unshare_mounts, MountNamespace};
+    use std::sync::Arc;
+
+    println!("test mount namespace:");
+    let old = unshare_mounts();
+    let ns = MountNamespace::current();
+    ns.mount("/mnt-ns", Arc::new(RamFileSystem::new()))?;
+    fs::write("/mnt-ns/test.txt", "test")?;
+    // the filesystems mounted before are shared
+    fs::write("/tmp/ns.txt", "shared")?;
+
+    // only the mount point is visible in the other namespace
+    old.set_current();
+    assert!(fs::metadata("/mnt-ns")?.is_dir());
+    assert_err!(fs::metadata("/mnt-ns/test.txt"), NotFound);
+    assert_eq!(fs::read_to_string("/tmp/ns.txt")?, "shared");
+
+    ns.set_current();
+    assert_eq!(fs::read_to_string("/mnt-ns/test.txt")?, "test");
+    old.set_current();
+    fs::remove_file("/tmp/ns.txt")?;
+    fs::remove_dir("/mnt-ns")?;
+
+    println!("test_mount_namespace() OK!");
+    Ok(())
+}
+
+fn test_tmpfs() -> Result<()> {
+    use ruxfs::fops::statfs;
+
+    const MAX: usize = 8192;
+    println!("test tmpfs:");
+    ruxfs::mount_tmpfs("/tmp-small", MAX as u64)?;
+    let info = statfs("/tmp-small")?;
+    assert_eq!((info.block_size(), info.blocks()), (4096, 2));
+    assert_eq!(info.blocks_free(), 2);
+
+    // fill it up, then fail to write more
+    let fname = "/tmp-small/full.txt";
+    fs::write(fname, [1; MAX - 1])?;
+    let mut file = File::options().append(true).open(fname)?;
+    assert_eq!(file.write(b"a")?, 1);
+    assert_err!(file.write(b"b"), StorageFull);
+    assert_err!(fs::write("/tmp-small/more.txt", "more"), StorageFull);
+    assert_eq!(statfs("/tmp-small/full.txt")?.blocks_free(), 0);
+
+    // the space is back once the file is removed and closed
+    fs::remove_file(fname)?;
+    assert_eq!(statfs("/tmp-small")?.blocks_free(), 0);
+    drop(file);
+    assert_eq!(statfs("/tmp-small")?.blocks_free(), 2);
+    fs::write("/tmp-small/more.txt", [2; MAX])?;
+    fs::remove_file("/tmp-small/more.txt")?;
+
+    println!("test_tmpfs() OK!");
+    Ok(())
+}
+
+fn test_open_path() -> Result<()> {
+    use ruxfs::fops::{self, lookup, DirEntry, FilePerm};
+
+    println!("test O_PATH:");
+    fs::create_dir("/tmp/path-dir")?;
+    fs::write("/tmp/path-dir/f.txt", "path")?;
+    lookup("/tmp/path-dir")?.setattr(
+        Some(FilePerm::from_bits_truncate(0o300)),
+        None,
+        None,
+        None,
+    )?;
+
+    // an unreadable directory can still be the anchor of relative paths
+    let mut path_opts = fops::OpenOptions::new();
+    path_opts.path(true);
+    let mut dir = fops::Directory::open_dir("/tmp/path-dir", &path_opts)?;
+    assert!(dir.is_path());
+    let mut read_opts = fops::OpenOptions::new();
+    read_opts.read(true);
+    let mut file = dir.open_file_at("f.txt", &read_opts)?;
+    let mut buf = [0; 8];
+    assert_eq!(file.read(&mut buf)?, 4);
+    assert_err!(dir.read_dir(&mut [DirEntry::default()]), PermissionDenied);
+
+    // only the attributes of a file are available
+    let mut file = fops::File::open("/tmp/path-dir/f.txt", &path_opts)?;
+    assert!(file.is_path());
+    assert!(file.get_attr()?.is_file());
+    assert_err!(file.read(&mut buf), PermissionDenied);
+    assert_err!(file.write(b"test"), PermissionDenied);
+    path_opts.read(true);
+    assert_err!(
+        fops::File::open("/tmp/path-dir/f.txt", &path_opts),
+        InvalidInput
+    );
+
+    lookup("/tmp/path-dir")?.setattr(Some(FilePerm::default_dir()), None, None, None)?;
+    fs::remove_file("/tmp/path-dir/f.txt")?;
+    fs::remove_dir("/tmp/path-dir")?;
+
+    println!("test_open_path() OK!");
+    Ok(())
+}
+
+fn test_tmpfile() -> Result<()> {
+    use ruxfs::fops;
+
+    println!("test O_TMPFILE:");
+    let mut opts = fops::OpenOptions::new();
+    opts.read(true);
+    opts.write(true);
+    opts.tmpfile(true);
+    let mut file = fops::File::open("/tmp", &opts)?;
+    assert_eq!(file.write(b"anonymous")?, 9);
+    assert!(!fs::read_dir("/tmp")?.any(|e| e.unwrap().file_name().starts_with("anon")));
+
+    file.link("/tmp/anon.txt")?;
+    assert_err!(file.link("/tmp/anon.txt"), AlreadyExists);
+    drop(file);
+    assert_eq!(fs::read_to_string("/tmp/anon.txt")?, "anonymous");
+    fs::remove_file("/tmp/anon.txt")?;
+
+    // a directory is needed, and write access
+    assert_err!(fops::File::open("/tmp/none", &opts), NotFound);
+    opts.write(false);
+    assert_err!(fops::File::open("/tmp", &opts), InvalidInput);
+
+    println!("test_tmpfile() OK!");
+    Ok(())
+}
+
+fn test_direct_io() -> Result<()> {
+    use ruxfs::fops::{self, DIRECT_IO_ALIGN};
+
+    println!("test O_DIRECT:");
+    let mut opts = fops::OpenOptions::new();
+    opts.read(true);
+    opts.write(true);
+    opts.create(true);
+    opts.direct(true);
+    let mut file = fops::File::open("/direct.bin", &opts)?;
+
+    // the offset and the length must be aligned
+    let data: Vec<u8> = (0..2 * DIRECT_IO_ALIGN).map(|i| i as u8).collect();
+    assert_err!(file.write(&data[..100]), InvalidInput);
+    assert_err!(file.write_at(1, &data[..DIRECT_IO_ALIGN]), InvalidInput);
+    assert_eq!(file.write(&data)?, data.len());
+    let mut buf = vec![0; DIRECT_IO_ALIGN];
+    assert_err!(file.read_at(100, &mut buf), InvalidInput);
+    assert_eq!(file.read_at(DIRECT_IO_ALIGN as u64, &mut buf)?, buf.len());
+    assert_eq!(buf, data[DIRECT_IO_ALIGN..]);
+    drop(file);
+
+    // the data is the same through the cache
+    assert_eq!(fs::read("/direct.bin")?, data);
+    fs::remove_file("/direct.bin")?;
+
+    println!("test_direct_io() OK!");
+    Ok(())
+}
+
+fn test_truncate() -> Result<()> {
+    use ruxfs::fops::{lookup, FilePerm};
+
+    let fname = "/tmp/truncate.txt";
+    println!("test truncate {:?}:", fname);
+    fs::write(fname, "0123456789")?;
+
+    // the extended part reads as zeros
+    fs::truncate(fname, 4096)?;
+    let data = fs::read(fname)?;
+    assert_eq!(data.len(), 4096);
+    assert_eq!(&data[..10], b"0123456789");
+    assert!(data[10..].iter().all(|&b| b == 0));
+    fs::truncate(fname, 4)?;
+    assert_eq!(fs::read_to_string(fname)?, "0123");
+
+    assert_err!(fs::truncate("/tmp", 0), IsADirectory);
+    assert_err!(fs::truncate("/tmp/truncate-none.txt", 0), NotFound);
+    let node = lookup(fname)?;
+    node.setattr(Some(FilePerm::from_bits_truncate(0o444)), None, None, None)?;
+    assert_err!(fs::truncate(fname, 0), PermissionDenied);
+    node.setattr(Some(FilePerm::default_file()), None, None, None)?;
+    fs::remove_file(fname)?;
+
+    println!("test_truncate() OK!");
+    Ok(())
+}
+
+fn test_resolve() -> Result<()> {
+    use ruxfs::fops::{self, symlink};
+
+    println!("test resolve restrictions:");
+    fs::create_dir_all("/tmp/resolve/sub")?;
+    fs::write("/tmp/resolve/sub/f.txt", "resolve")?;
+    fs::write("/tmp/outside.txt", "outside")?;
+    symlink("sub/f.txt", "/tmp/resolve/in-link")?;
+    symlink("../outside.txt", "/tmp/resolve/out-link")?;
+
+    let mut opts = fops::OpenOptions::new();
+    opts.read(true);
+    let dir = fops::Directory::open_dir("/tmp/resolve", &opts)?;
+    opts.resolve_beneath(true);
+    dir.open_file_at("sub/../sub/f.txt", &opts)?;
+    dir.open_file_at("in-link", &opts)?;
+    assert_err!(dir.open_file_at("../outside.txt", &opts), CrossesDevices);
+    assert_err!(
+        dir.open_file_at("sub/../../outside.txt", &opts),
+        CrossesDevices
+    );
+    assert_err!(dir.open_file_at("/tmp/outside.txt", &opts), CrossesDevices);
+    assert_err!(dir.open_file_at("out-link", &opts), CrossesDevices);
+    assert_err!(dir.open_dir_at("..", &opts), CrossesDevices);
+    // without the restriction, the link is followed outside
+    opts.resolve_beneath(false);
+    dir.open_file_at("out-link", &opts)?;
+
+    opts.resolve_no_symlinks(true);
+    dir.open_file_at("sub/f.txt", &opts)?;
+    assert_err!(dir.open_file_at("in-link", &opts), FilesystemLoop);
+    assert_err!(
+        fops::File::open("/tmp/resolve/in-link", &opts),
+        FilesystemLoop
+    );
+    // a new file is created if nothing is a link
+    opts.write(true);
+    opts.create(true);
+    dir.open_file_at("sub/new.txt", &opts)?;
+
+    fs::remove_file("/tmp/resolve/in-link")?;
+    fs::remove_file("/tmp/resolve/out-link")?;
+    fs::remove_file("/tmp/resolve/sub/new.txt")?;
+    fs::remove_file("/tmp/resolve/sub/f.txt")?;
+    fs::remove_file("/tmp/outside.txt")?;
+    fs::remove_dir("/tmp/resolve/sub")?;
+    fs::remove_dir("/tmp/resolve")?;
+
+    println!("test_resolve() OK!");
+    Ok(())
+}
+
+fn test_mount_umount() -> Result<()> {
+    use ruxfs::fops::statfs;
+
+    println!("test mount and umount:");
+    let cwd = fs::current_dir()?;
+    fs::create_dir("/mnt-rt")?;
+    ruxfs::mount("tmpfs", "/mnt-rt", "mode=755,size=8k")?;
+    assert_eq!(statfs("/mnt-rt")?.blocks(), 2);
+    fs::write("/mnt-rt/f.txt", "mounted")?;
+    assert_err!(ruxfs::mount("tmpfs", "/mnt-rt", ""), InvalidInput);
+    assert_err!(ruxfs::mount("tmpfs", "/mnt-rt2", "size=1x"), InvalidInput);
+    assert_err!(ruxfs::mount("ext4", "/mnt-rt2", ""), Unsupported);
+
+    // busy while another filesystem is mounted below, or it has the current
+    // directory
+    ruxfs::mount("proc", "/mnt-rt/proc", "")?;
+    // the proc nodes may depend on the calling task, so they are never cached
+    let hits = ruxfs::fops::lookup_stats().hits;
+    assert!(fs::metadata("/mnt-rt/proc/meminfo")?.is_file());
+    assert!(fs::metadata("/mnt-rt/proc/meminfo")?.is_file());
+    assert_eq!(ruxfs::fops::lookup_stats().hits, hits);
+    assert_err!(ruxfs::umount("/mnt-rt", false), ResourceBusy);
+    ruxfs::umount("/mnt-rt/proc", false)?;
+    fs::set_current_dir("/mnt-rt")?;
+    assert_err!(ruxfs::umount("/mnt-rt", false), ResourceBusy);
+    fs::set_current_dir(&cwd)?;
+
+    ruxfs::umount("/mnt-rt", false)?;
+    assert_err!(fs::metadata("/mnt-rt/f.txt"), NotFound);
+    assert_err!(ruxfs::umount("/mnt-rt", false), InvalidInput);
+    assert_err!(ruxfs::umount("/", true), ResourceBusy);
+    fs::remove_dir("/mnt-rt/proc")?;
+    fs::remove_dir("/mnt-rt")?;
+
+    println!("test_mount_umount() OK!");
+    Ok(())
+}
+
+fn test_bind_mount() -> Result<()> {
+    println!("test bind mount:");
+    fs::create_dir_all("/bind-src/sub")?;
+    fs::write("/bind-src/f.txt", "source")?;
+    fs::create_dir("/bind-dst")?;
+    ruxfs::bind_mount("/bind-src", "/bind-dst", false)?;
+    assert_eq!(fs::read_to_string("/bind-dst/f.txt")?, "source");
+
+    // the writes and deletions are made in the source
+    fs::write("/bind-dst/g.txt", "through bind")?;
+    assert_eq!(fs::read_to_string("/bind-src/g.txt")?, "through bind");
+    fs::remove_file("/bind-dst/f.txt")?;
+    assert_err!(fs::metadata("/bind-src/f.txt"), NotFound);
+
+    // a file on a file, but not on a directory
+    fs::write("/bind-file", "")?;
+    ruxfs::bind_mount("/bind-src/g.txt", "/bind-file", false)?;
+    assert_eq!(fs::read_to_string("/bind-file")?, "through bind");
+    assert_err!(
+        ruxfs::bind_mount("/bind-src/g.txt", "/bind-src/sub", false),
+        NotADirectory
+    );
+
+    // the mounts below the source are only bound with `recursive`
+    ruxfs::mount("tmpfs", "/bind-src/sub", "")?;
+    fs::write("/bind-src/sub/m.txt", "mounted")?;
+    fs::create_dir("/bind-rec")?;
+    ruxfs::bind_mount("/bind-src", "/bind-rec", true)?;
+    assert_eq!(fs::read_to_string("/bind-rec/sub/m.txt")?, "mounted");
+    assert_err!(fs::metadata("/bind-dst/sub/m.txt"), NotFound);
+
+    ruxfs::umount("/bind-rec/sub", false)?;
+    ruxfs::umount("/bind-rec", false)?;
+    ruxfs::umount("/bind-src/sub", false)?;
+    ruxfs::umount("/bind-file", false)?;
+    ruxfs::umount("/bind-dst", false)?;
+    assert_err!(fs::metadata("/bind-dst/g.txt"), NotFound);
+    fs::remove_file("/bind-file")?;
+    fs::remove_file("/bind-src/g.txt")?;
+    fs::remove_dir("/bind-rec/sub")?;
+    fs::remove_dir("/bind-rec")?;
+    fs::remove_dir("/bind-dst")?;
+    fs::remove_dir("/bind-src/sub")?;
+    fs::remove_dir("/bind-src")?;
+
+    println!("test_bind_mount() OK!");
+    Ok(())
+}
+
+fn test_dir_path() -> Result<()> {
+    use ruxfs::fops;
+
+    println!("test directory path:");
+    fs::create_dir_all("/tmp/cwd/sub")?;
+    fs::write("/tmp/cwd/f.txt", "cwd")?;
+    let mut opts = fops::OpenOptions::new();
+    opts.read(true);
+    let dir = fops::Directory::open_dir("/tmp//cwd/./", &opts)?;
+    assert_eq!(dir.path(), "/tmp/cwd");
+    let sub = dir.open_dir_at("sub/../sub", &opts)?;
+    assert_eq!(sub.path(), "/tmp/cwd/sub");
+
+    // the path of an open directory can become the current directory
+    let cwd = fs::current_dir()?;
+    fs::set_current_dir(sub.path())?;
+    assert_eq!(fs::read_to_string("../f.txt")?, "cwd");
+    assert_err!(fs::set_current_dir("../f.txt"), NotADirectory);
+    fs::set_current_dir(&cwd)?;
+
+    fs::remove_file("/tmp/cwd/f.txt")?;
+    fs::remove_dir("/tmp/cwd/sub")?;
+    fs::remove_dir("/tmp/cwd")?;
+
+    println!("test_dir_path() OK!");
+    Ok(())
+}
+
 pub fn test_all() {
     test_read_write_file().expect("test_read_write_file() failed");
     test_read_dir().expect("test_read_dir() failed");
     test_file_permission().expect("test_file_permission() failed");
     test_create_file_dir().expect("test_create_file_dir() failed");
     test_remove_file_dir().expect("test_remove_file_dir() failed");
+    test_lookup_cache().expect("test_lookup_cache() failed");
     test_devfs_ramfs().expect("test_devfs_ramfs() failed");
+    test_path_limits().expect("test_path_limits() failed");
+    test_umask().expect("test_umask() failed");
+    test_mount_namespace().expect("test_mount_namespace() failed");
+    test_tmpfs().expect("test_tmpfs() failed");
+    test_open_path().expect("test_open_path() failed");
+    test_tmpfile().expect("test_tmpfile() failed");
+    test_direct_io().expect("test_direct_io() failed");
+    test_truncate().expect("test_truncate() failed");
+    test_resolve().expect("test_resolve() failed");
+    test_mount_umount().expect("test_mount_umount() failed");
+    test_bind_mount().expect("test_bind_mount() failed");
+    test_dir_path().expect("test_dir_path() failed");
 }
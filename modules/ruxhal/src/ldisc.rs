@@ -0,0 +1,168 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! A simple canonical-mode line discipline for the console, used when the
+//! interrupt-driven one of the `tty` feature is disabled.
+//!
+//! The input is polled from the console when read. Characters typed are echoed
+//! and can be erased with backspace, and a line is returned only once it ends
+//! with a newline, or with `^D` which also ends the input when the line is
+//! empty.
+
+/// Maximum length of a line, a line which is longer is cut.
+pub const MAX_CANON: usize = 255;
+
+const EOT: u8 = 0x04; // ^D
+const BS: u8 = 0x08;
+const LF: u8 = b'\n';
+const CR: u8 = b'\r';
+const DEL: u8 = 0x7f;
+
+/// A console line being typed, then read.
+pub struct LineDiscipline {
+    buf: [u8; MAX_CANON + 1],
+    /// Length of the line, including its newline.
+    len: usize,
+    /// Length of the line which is already read.
+    read_len: usize,
+    /// Whether the line is complete and can be read.
+    complete: bool,
+}
+
+impl LineDiscipline {
+    /// Creates an empty line discipline.
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; MAX_CANON + 1],
+            len: 0,
+            read_len: 0,
+            complete: false,
+        }
+    }
+
+    /// Reads the current line into `buf`, taking the characters from
+    /// `getchar` and echoing them with `echo` until the line is complete.
+    ///
+    /// Returns `None` if `getchar` runs out of characters before, and
+    /// `Some(0)` at the end of the input.
+    pub fn read(
+        &mut self,
+        buf: &mut [u8],
+        mut getchar: impl FnMut() -> Option<u8>,
+        mut echo: impl FnMut(&[u8]),
+    ) -> Option<usize> {
+        if buf.is_empty() {
+            return Some(0);
+        }
+        while !self.complete {
+            self.receive(getchar()?, &mut echo);
+        }
+        let line = &self.buf[self.read_len..self.len];
+        let len = line.len().min(buf.len());
+        buf[..len].copy_from_slice(&line[..len]);
+        self.read_len += len;
+        if self.read_len == self.len {
+            self.len = 0;
+            self.read_len = 0;
+            self.complete = false;
+        }
+        Some(len)
+    }
+
+    /// Processes a character typed on the console.
+    fn receive(&mut self, c: u8, echo: &mut impl FnMut(&[u8])) {
+        match c {
+            CR | LF => {
+                self.buf[self.len] = LF;
+                self.len += 1;
+                self.complete = true;
+                echo(&[LF]);
+            }
+            EOT => self.complete = true,
+            BS | DEL => {
+                if self.len > 0 {
+                    self.len -= 1;
+                    echo(&[BS, b' ', BS]);
+                }
+            }
+            _ => {
+                // keep room for the newline
+                if self.len < MAX_CANON {
+                    self.buf[self.len] = c;
+                    self.len += 1;
+                    echo(&[c]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    /// Reads from a mock console which has received `input`, returns the
+    /// results of the reads and what is echoed.
+    fn read_lines(input: &[u8], reads: usize) -> (Vec<Option<usize>>, Vec<u8>, Vec<u8>) {
+        let mut ldisc = LineDiscipline::new();
+        let mut input = input.iter().copied();
+        let mut echoed = Vec::new();
+        let mut data = Vec::new();
+        let mut results = Vec::new();
+        for _ in 0..reads {
+            let mut buf = [0; 64];
+            let res = ldisc.read(&mut buf, || input.next(), |s| echoed.extend_from_slice(s));
+            data.extend_from_slice(&buf[..res.unwrap_or(0)]);
+            results.push(res);
+        }
+        (results, data, echoed)
+    }
+
+    #[test]
+    fn test_read_line() {
+        let (results, data, echoed) = read_lines(b"helo\x7flo\r", 1);
+        assert_eq!(results, [Some(6)]);
+        assert_eq!(data, b"hello\n");
+        assert_eq!(data.strip_suffix(b"\n"), Some(&b"hello"[..]));
+        assert_eq!(echoed, b"helo\x08 \x08lo\n");
+    }
+
+    #[test]
+    fn test_incomplete_line() {
+        // nothing is returned until the newline
+        let (results, data, _) = read_lines(b"abc", 2);
+        assert_eq!(results, [None, None]);
+        assert!(data.is_empty());
+
+        // backspace at the start of a line does nothing
+        let (results, data, echoed) = read_lines(b"\x08a\nb\n", 3);
+        assert_eq!(results, [Some(2), Some(2), None]);
+        assert_eq!(data, b"a\nb\n");
+        assert_eq!(echoed, b"a\nb\n");
+    }
+
+    #[test]
+    fn test_eof_and_short_reads() {
+        // ^D ends a line without a newline, or the input on an empty line
+        let (results, data, _) = read_lines(b"ab\x04\x04", 2);
+        assert_eq!(results, [Some(2), Some(0)]);
+        assert_eq!(data, b"ab");
+
+        let mut ldisc = LineDiscipline::new();
+        let mut input = b"hello\n".iter().copied();
+        let mut buf = [0; 4];
+        assert_eq!(ldisc.read(&mut buf, || input.next(), |_| {}), Some(4));
+        assert_eq!(&buf, b"hell");
+        assert_eq!(ldisc.read(&mut buf, || None, |_| {}), Some(2));
+        assert_eq!(&buf[..2], b"o\n");
+    }
+}
@@ -45,9 +45,13 @@ extern crate log;
 
 mod platform;
 
+#[cfg(not(feature = "tty"))]
+mod ldisc;
+
 pub mod arch;
 pub mod cpu;
 pub mod mem;
+pub mod random;
 pub mod time;
 pub mod trap;
 
@@ -97,19 +101,18 @@ pub static mut COMLINE_BUF: [u8; 256] = [0; 256];
 
 #[allow(unused)]
 /// read a tty device specified by its name.
+///
+/// Without the `tty` feature, the console is read in canonical mode: the
+/// characters typed are echoed, and are returned once the line is complete.
 pub fn tty_read(buf: &mut [u8], dev_name: &str) -> usize {
     #[cfg(not(feature = "tty"))]
     {
-        let mut read_len = 0;
-        while read_len < buf.len() {
-            if let Some(c) = console::getchar().map(|c| if c == b'\r' { b'\n' } else { c }) {
-                buf[read_len] = c;
-                read_len += 1;
-            } else {
-                break;
-            }
-        }
-        read_len
+        static LDISC: spinlock::SpinNoIrq<ldisc::LineDiscipline> =
+            spinlock::SpinNoIrq::new(ldisc::LineDiscipline::new());
+        LDISC
+            .lock()
+            .read(buf, console::getchar, console::write_bytes)
+            .unwrap_or(0)
     }
 
     #[cfg(feature = "tty")]
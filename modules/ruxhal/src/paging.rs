@@ -16,6 +16,7 @@ use crate::mem::{
     direct_virt_to_phys, memory_regions, phys_to_virt, MemRegionFlags, PhysAddr, VirtAddr,
     PAGE_SIZE_4K,
 };
+use alloc::collections::BTreeMap;
 use axalloc::global_allocator;
 use lazy_init::LazyInit;
 
@@ -81,6 +82,10 @@ cfg_if::cfg_if! {
 
 pub(crate) static KERNEL_PAGE_TABLE: LazyInit<SpinNoIrq<PageTable>> = LazyInit::new();
 
+/// Guard pages of the task stacks, mapping the address of each guard page to
+/// the id of the task owning the stack.
+static STACK_GUARDS: SpinNoIrq<BTreeMap<usize, u64>> = SpinNoIrq::new(BTreeMap::new());
+
 /// Remap the regions for kernel memory
 pub fn remap_kernel_memory() -> PagingResult {
     if crate::cpu::this_cpu_is_bsp() {
@@ -191,3 +196,75 @@ pub fn pte_unmap_page(vaddr: VirtAddr) -> PagingResult {
     flush_tlb(Some(vaddr));
     Ok(())
 }
+
+/// Map `size` bytes of newly allocated memory at `vaddr` as the stack of task `task_id`.
+///
+/// The stack is backed by physically contiguous frames, as buffers on the
+/// stack may be handed to DMA devices by their start address only.
+///
+/// The 4K page right below `vaddr` is left unmapped and recorded as the guard
+/// page of the stack, so that an overflow raises a page fault which is reported
+/// as a stack overflow instead of silently corrupting the adjacent memory.
+pub fn map_task_stack(vaddr: VirtAddr, size: usize, task_id: u64) -> PagingResult {
+    let num_pages = size / PAGE_SIZE_4K;
+    let frames = global_allocator()
+        .alloc_pages(num_pages, PAGE_SIZE_4K)
+        .map_err(|_| PagingError::NoMemory)?;
+    let paddr = direct_virt_to_phys(frames.into());
+    let mut kernel_page_table = KERNEL_PAGE_TABLE.lock();
+    for offset in (0..size).step_by(PAGE_SIZE_4K) {
+        if let Err(e) = kernel_page_table.map(
+            vaddr + offset,
+            paddr + offset,
+            PageSize::Size4K,
+            MappingFlags::READ | MappingFlags::WRITE,
+        ) {
+            unmap_stack_pages(&mut kernel_page_table, vaddr, offset);
+            drop(kernel_page_table);
+            global_allocator().dealloc_pages(frames, num_pages);
+            return Err(e);
+        }
+    }
+    STACK_GUARDS
+        .lock()
+        .insert(vaddr.as_usize() - PAGE_SIZE_4K, task_id);
+    Ok(())
+}
+
+/// Unmap and free the task stack mapped by [`map_task_stack`].
+pub fn unmap_task_stack(vaddr: VirtAddr, size: usize) {
+    STACK_GUARDS
+        .lock()
+        .remove(&(vaddr.as_usize() - PAGE_SIZE_4K));
+    let mut kernel_page_table = KERNEL_PAGE_TABLE.lock();
+    let paddr = match kernel_page_table.query(vaddr) {
+        Ok((paddr, _, _)) => paddr,
+        Err(e) => {
+            warn!("failed to query task stack {:#x?}: {:?}", vaddr, e);
+            return;
+        }
+    };
+    unmap_stack_pages(&mut kernel_page_table, vaddr, size);
+    drop(kernel_page_table);
+    global_allocator().dealloc_pages(phys_to_virt(paddr).as_usize(), size / PAGE_SIZE_4K);
+}
+
+/// Unmap the `size` bytes of a task stack at `vaddr`, without freeing them.
+fn unmap_stack_pages(kernel_page_table: &mut PageTable, vaddr: VirtAddr, size: usize) {
+    for offset in (0..size).step_by(PAGE_SIZE_4K) {
+        if let Err(e) = kernel_page_table.unmap(vaddr + offset) {
+            warn!(
+                "failed to unmap task stack page {:#x?}: {:?}",
+                vaddr + offset,
+                e
+            );
+        }
+        flush_tlb(Some(vaddr + offset));
+    }
+}
+
+/// Returns the id of the task whose stack guard page contains `vaddr`, if any.
+pub fn stack_guard_owner(vaddr: usize) -> Option<u64> {
+    let guard = vaddr & !(PAGE_SIZE_4K - 1);
+    STACK_GUARDS.lock().get(&guard).copied()
+}
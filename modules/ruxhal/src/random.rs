@@ -0,0 +1,63 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Hardware random number generators.
+//!
+//! - x86_64: the `rdrand` instruction, available on Intel CPUs since Ivy
+//!   Bridge and on AMD CPUs since Ryzen.
+//! - AArch64: the `RNDR` register, available on some cores since ARMv8.5.
+//! - RISC-V: none.
+
+/// Whether the CPU has a random number instruction.
+pub fn has_hw_random() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        unsafe { core::arch::x86_64::__cpuid(1).ecx & (1 << 30) != 0 }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        let id_aa64_isar0_el1: u64;
+        unsafe { core::arch::asm!("mrs {}, ID_AA64ISAR0_EL1", out(reg) id_aa64_isar0_el1) };
+        id_aa64_isar0_el1 & (0b1111 << 60) == 0b0001 << 60
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        false
+    }
+}
+
+/// Returns 64 random bits from the CPU, or `None` if it has no random number
+/// instruction or it failed.
+pub fn hw_random() -> Option<u64> {
+    if !has_hw_random() {
+        return None;
+    }
+    #[cfg(target_arch = "x86_64")]
+    {
+        // the instruction may fail transiently when drained
+        for _ in 0..10 {
+            let mut random = 0;
+            if unsafe { core::arch::x86_64::_rdrand64_step(&mut random) } == 1 {
+                return Some(random);
+            }
+        }
+        None
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        let random: u64;
+        // s3_3_c2_c4_0 is `RNDR`, which reads 0 on failure
+        unsafe { core::arch::asm!("mrs {}, s3_3_c2_c4_0", out(reg) random) };
+        (random != 0).then_some(random)
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        None
+    }
+}
@@ -8,7 +8,6 @@
  */
 
 //! Time-related operations.
-#[cfg(all(feature = "rtc", target_arch = "x86_64"))]
 use core::sync::atomic::{AtomicU64, Ordering};
 pub use core::time::Duration;
 
@@ -42,47 +41,70 @@ pub const NANOS_PER_MICROS: u64 = 1_000;
 pub fn current_time_nanos() -> u64 {
     ticks_to_nanos(current_ticks())
 }
-///record first time res from x86 rtc as base time
-#[cfg(all(feature = "rtc", target_arch = "x86_64"))]
-static BASE_TIME: AtomicU64 = AtomicU64::new(0);
-///record last time res from x86 rtc to correct time
-#[cfg(all(feature = "rtc", target_arch = "x86_64"))]
-static LAST_TIME: AtomicU64 = AtomicU64::new(0);
+
+/// Returns the resolution of the clock in nanoseconds, which is the length of
+/// a hardware tick rounded up, and also the granularity of the one-shot timer.
+pub fn clock_resolution_nanos() -> u64 {
+    // one tick may be shorter than a nanosecond, and the conversion truncates
+    ticks_to_nanos(NANOS_PER_MICROS)
+        .div_ceil(NANOS_PER_MICROS)
+        .max(1)
+}
 
 /// Returns the current clock time in [`TimeValue`].
-#[allow(unreachable_code)]
+///
+/// It counts from booting and never jumps, see [`wall_time`] for the time of
+/// the day.
 pub fn current_time() -> TimeValue {
-    #[cfg(all(feature = "rtc", target_arch = "x86_64"))]
-    {
-        let nanos = current_time_nanos();
-        let mut base_time = BASE_TIME.load(Ordering::Relaxed);
-        if base_time == 0 {
-            base_time = rtc_read_time();
-            BASE_TIME.store(base_time, Ordering::Relaxed);
-        }
-        let last_time = LAST_TIME.load(Ordering::Relaxed);
-        if (nanos / NANOS_PER_SEC) - last_time > 1800 {
-            base_time = rtc_read_time() - (nanos / NANOS_PER_SEC);
-            BASE_TIME.store(base_time, Ordering::Relaxed);
-            LAST_TIME.store(nanos / NANOS_PER_SEC, Ordering::Relaxed);
-        }
-        let rtc_time = base_time + (nanos / NANOS_PER_SEC);
-        return Duration::new(rtc_time, (nanos % NANOS_PER_SEC) as u32);
+    TimeValue::from_nanos(current_time_nanos())
+}
+
+/// Wall-clock time at boot in nanoseconds since the Unix epoch, `u64::MAX`
+/// until it is first read or set.
+static BOOT_WALL_TIME: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// Reads the wall-clock time at boot from the RTC, or falls back to
+/// [`ruxconfig::WALL_CLOCK_FALLBACK_EPOCH`] if there is no RTC.
+#[allow(unreachable_code)]
+fn read_boot_wall_time() -> u64 {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+    #[cfg(feature = "rtc")]
+    return (rtc_read_time() * NANOS_PER_SEC).saturating_sub(current_time_nanos());
+    ruxconfig::WALL_CLOCK_FALLBACK_EPOCH as u64 * NANOS_PER_SEC
+}
+
+fn boot_wall_time() -> u64 {
+    let boot = BOOT_WALL_TIME.load(Ordering::Acquire);
+    if boot != u64::MAX {
+        return boot;
     }
-    #[cfg(all(feature = "rtc", target_arch = "aarch64"))]
-    {
-        let nanos = current_time_nanos();
-        let rtc_time = rtc_read_time();
-        return Duration::new(rtc_time, (nanos % (NANOS_PER_SEC)) as u32);
+    let boot = read_boot_wall_time();
+    match BOOT_WALL_TIME.compare_exchange(u64::MAX, boot, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => boot,
+        Err(boot) => boot,
     }
-    TimeValue::from_nanos(current_time_nanos())
 }
 
-/// set time value
-pub fn set_current_time(_new_tv: TimeValue) {
+/// Returns the wall-clock time since the Unix epoch.
+///
+/// It is the wall-clock time at boot plus the elapsed [`current_time`].
+pub fn wall_time() -> TimeValue {
+    TimeValue::from_nanos(boot_wall_time() + current_time_nanos())
+}
+
+/// Sets the wall-clock time, by adjusting the wall-clock time at boot.
+///
+/// The RTC is also updated if there is one. Returns `false` if the new time
+/// is earlier than the time elapsed since booting.
+pub fn set_wall_time(new_time: TimeValue) -> bool {
+    let Some(boot) = (new_time.as_nanos() as u64).checked_sub(current_time_nanos()) else {
+        return false;
+    };
+    BOOT_WALL_TIME.store(boot, Ordering::Release);
     #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
     #[cfg(feature = "rtc")]
-    rtc_write_time(_new_tv.as_secs() as u32);
+    rtc_write_time(new_time.as_secs() as u32);
+    true
 }
 
 /// Busy waiting for the given duration.
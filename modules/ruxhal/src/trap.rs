@@ -59,9 +59,15 @@ pub(crate) fn handle_syscall(syscall_id: usize, args: [usize; 6]) -> isize {
     call_interface!(TrapHandler::handle_syscall, syscall_id, args)
 }
 
-/// Call the external IRQ handler.
+/// Call the external page fault handler.
+///
+/// Faults in the guard page of a task stack are never handled, they are
+/// reported as a stack overflow.
 #[allow(dead_code)]
 #[cfg(feature = "paging")]
 pub(crate) fn handle_page_fault(vaddr: usize, cause: PageFaultCause) -> bool {
+    if let Some(task_id) = crate::paging::stack_guard_owner(vaddr) {
+        panic!("stack overflow in task {}", task_id);
+    }
     call_interface!(TrapHandler::handle_page_fault, vaddr, cause)
 }
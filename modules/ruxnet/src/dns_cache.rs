@@ -0,0 +1,244 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! A small LRU cache of DNS resolutions, consulted before issuing a query.
+
+use alloc::{string::String, vec::Vec};
+use axerrno::{AxError, AxResult};
+use core::net::IpAddr;
+use core::time::Duration;
+use spin::Mutex;
+
+use crate::resolver::Answer;
+
+/// Maximum number of cached names.
+const CAPACITY: usize = 32;
+/// How long a name which does not exist (NXDOMAIN) is cached.
+const NEGATIVE_TTL: Duration = Duration::from_secs(10);
+
+struct Entry {
+    name: String,
+    result: Result<Vec<IpAddr>, AxError>,
+    expires: Duration,
+}
+
+/// Cached resolutions, from the least to the most recently used.
+pub(crate) struct DnsCache {
+    entries: Vec<Entry>,
+    capacity: usize,
+}
+
+impl DnsCache {
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Looks up an unexpired resolution of `name`, and marks it as recently used.
+    fn lookup(&mut self, name: &str, now: Duration) -> Option<AxResult<Vec<IpAddr>>> {
+        let idx = self.entries.iter().position(|e| e.name == name)?;
+        let entry = self.entries.remove(idx);
+        if entry.expires <= now {
+            return None;
+        }
+        let result = entry.result.clone();
+        self.entries.push(entry);
+        Some(result)
+    }
+
+    /// Records the result of a query for the TTL of its answer, evicting the
+    /// least recently used entry if full.
+    fn insert(&mut self, name: &str, result: &AxResult<Answer>, now: Duration) {
+        let (result, ttl) = match result {
+            Ok(answer) => (Ok(answer.addrs.clone()), answer.ttl),
+            Err(AxError::NotFound) => (Err(AxError::NotFound), NEGATIVE_TTL),
+            // Not an answer about the name, e.g. no server answered.
+            Err(_) => return,
+        };
+        self.entries.retain(|e| e.name != name);
+        if self.entries.len() >= self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push(Entry {
+            name: String::from(name),
+            result,
+            expires: now + ttl,
+        });
+    }
+
+    fn flush(&mut self) {
+        self.entries.clear();
+    }
+}
+
+static DNS_CACHE: Mutex<DnsCache> = Mutex::new(DnsCache::new(CAPACITY));
+
+/// Resolves `name` from `cache`, or by `query` on a miss.
+///
+/// The cache is not locked during the query, which may block.
+pub(crate) fn resolve_with<F>(
+    cache: &Mutex<DnsCache>,
+    name: &str,
+    now: Duration,
+    query: F,
+) -> AxResult<Vec<IpAddr>>
+where
+    F: FnOnce(&str) -> AxResult<Answer>,
+{
+    if let Some(result) = cache.lock().lookup(name, now) {
+        trace!("DNS cache hit: {}", name);
+        return result;
+    }
+    let result = query(name);
+    cache.lock().insert(name, &result, now);
+    result.map(|answer| answer.addrs)
+}
+
+/// Resolves `name` through the global DNS cache.
+pub(crate) fn resolve<F>(name: &str, query: F) -> AxResult<Vec<IpAddr>>
+where
+    F: FnOnce(&str) -> AxResult<Answer>,
+{
+    let now = Duration::from_nanos(ruxhal::time::current_time_nanos());
+    resolve_with(&DNS_CACHE, name, now, query)
+}
+
+/// Drops all the cached DNS resolutions.
+pub fn flush_dns_cache() {
+    DNS_CACHE.lock().flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use core::net::Ipv4Addr;
+
+    const ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 2, 2));
+    const TTL: Duration = Duration::from_secs(60);
+
+    /// Answers `result` with a TTL of [`TTL`], and counts the queries.
+    fn counting(
+        count: &mut usize,
+        result: AxResult<Vec<IpAddr>>,
+    ) -> impl FnOnce(&str) -> AxResult<Answer> + '_ {
+        move |_| {
+            *count += 1;
+            result.map(|addrs| Answer { addrs, ttl: TTL })
+        }
+    }
+
+    #[test]
+    fn test_cached_within_ttl() {
+        let cache = Mutex::new(DnsCache::new(CAPACITY));
+        let mut queries = 0;
+        let t0 = Duration::from_secs(100);
+
+        let res = resolve_with(
+            &cache,
+            "example.com",
+            t0,
+            counting(&mut queries, Ok(vec![ADDR])),
+        );
+        assert_eq!(res, Ok(vec![ADDR]));
+        let res = resolve_with(
+            &cache,
+            "example.com",
+            t0 + Duration::from_secs(1),
+            counting(&mut queries, Ok(vec![])),
+        );
+        assert_eq!(res, Ok(vec![ADDR]));
+        assert_eq!(queries, 1);
+
+        // expired
+        let res = resolve_with(
+            &cache,
+            "example.com",
+            t0 + TTL,
+            counting(&mut queries, Ok(vec![])),
+        );
+        assert_eq!(res, Ok(vec![]));
+        assert_eq!(queries, 2);
+    }
+
+    #[test]
+    fn test_negative_caching() {
+        let cache = Mutex::new(DnsCache::new(CAPACITY));
+        let mut queries = 0;
+        let t0 = Duration::from_secs(100);
+
+        let nx = Err(AxError::NotFound);
+        assert_eq!(
+            resolve_with(&cache, "nx.example", t0, counting(&mut queries, nx.clone())),
+            nx
+        );
+        assert_eq!(
+            resolve_with(
+                &cache,
+                "nx.example",
+                t0 + Duration::from_secs(1),
+                counting(&mut queries, Ok(vec![ADDR]))
+            ),
+            nx
+        );
+        assert_eq!(queries, 1);
+        // the negative TTL is shorter
+        let later = t0 + NEGATIVE_TTL;
+        assert!(later < t0 + TTL);
+        assert_eq!(
+            resolve_with(
+                &cache,
+                "nx.example",
+                later,
+                counting(&mut queries, Ok(vec![ADDR]))
+            ),
+            Ok(vec![ADDR])
+        );
+        assert_eq!(queries, 2);
+
+        // transient errors are not cached
+        for err in [AxError::ResourceBusy, AxError::ConnectionRefused] {
+            assert_eq!(
+                resolve_with(&cache, "busy.example", t0, counting(&mut queries, Err(err))),
+                Err(err)
+            );
+        }
+        resolve_with(
+            &cache,
+            "busy.example",
+            t0,
+            counting(&mut queries, Ok(vec![ADDR])),
+        )
+        .unwrap();
+        assert_eq!(queries, 5);
+    }
+
+    #[test]
+    fn test_lru_eviction_and_flush() {
+        let cache = Mutex::new(DnsCache::new(2));
+        let mut queries = 0;
+        let t0 = Duration::from_secs(100);
+
+        for name in ["a", "b", "a", "c"] {
+            resolve_with(&cache, name, t0, counting(&mut queries, Ok(vec![ADDR]))).unwrap();
+        }
+        // "b" was the least recently used one when "c" came in
+        assert_eq!(queries, 3);
+        resolve_with(&cache, "a", t0, counting(&mut queries, Ok(vec![ADDR]))).unwrap();
+        assert_eq!(queries, 3);
+        resolve_with(&cache, "b", t0, counting(&mut queries, Ok(vec![ADDR]))).unwrap();
+        assert_eq!(queries, 4);
+
+        cache.lock().flush();
+        resolve_with(&cache, "a", t0, counting(&mut queries, Ok(vec![ADDR]))).unwrap();
+        assert_eq!(queries, 5);
+    }
+}
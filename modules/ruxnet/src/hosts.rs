@@ -0,0 +1,65 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Static host name table in the format of `/etc/hosts`.
+
+use alloc::vec::Vec;
+use core::net::IpAddr;
+
+/// Returns the addresses of `name` in the host table `table`, in their order
+/// in the table.
+///
+/// Each line of the table is an address followed by its canonical name and
+/// aliases, separated by blanks. Everything after a `#` is a comment, and the
+/// lines with an invalid address are skipped. Names are matched regardless
+/// of their case.
+pub fn lookup_hosts(table: &str, name: &str) -> Vec<IpAddr> {
+    table
+        .lines()
+        .filter_map(|line| {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut fields = line.split_whitespace();
+            let addr = fields.next()?.parse::<IpAddr>().ok()?;
+            fields
+                .any(|host| host.eq_ignore_ascii_case(name))
+                .then_some(addr)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use core::net::{Ipv4Addr, Ipv6Addr};
+
+    const HOSTS: &str = "127.0.0.1\tlocalhost\n\
+        # 10.0.0.1 commented\n\
+        10.0.2.2 gateway gw.example  # the host\n\
+        not-an-address gateway\n\
+        ::1 localhost ip6-localhost\n";
+
+    #[test]
+    fn test_lookup_hosts() {
+        assert_eq!(
+            lookup_hosts(HOSTS, "localhost"),
+            vec![
+                IpAddr::V4(Ipv4Addr::LOCALHOST),
+                IpAddr::V6(Ipv6Addr::LOCALHOST)
+            ]
+        );
+        assert_eq!(
+            lookup_hosts(HOSTS, "GW.example"),
+            vec![IpAddr::V4(Ipv4Addr::new(10, 0, 2, 2))]
+        );
+        assert!(lookup_hosts(HOSTS, "commented").is_empty());
+        assert!(lookup_hosts(HOSTS, "the").is_empty());
+        assert!(lookup_hosts(HOSTS, "").is_empty());
+    }
+}
@@ -17,7 +17,18 @@
 //!
 //! - [`TcpSocket`]: A TCP socket that provides POSIX-like APIs.
 //! - [`UdpSocket`]: A UDP socket that provides POSIX-like APIs.
-//! - [`dns_query`]: Function for DNS query.
+//! - [`RawSocket`]: A raw IPv4 socket, e.g. for ICMP, that provides POSIX-like
+//!   APIs. Only available with [smoltcp].
+//! - [`dns_query`]: Function for DNS query, answered from a small cache of
+//!   recent resolutions when possible.
+//! - [`flush_dns_cache`]: Function to drop the cached resolutions.
+//! - [`set_nameservers`]: Function to set the nameservers asked by
+//!   [`dns_query`], e.g. those parsed from `/etc/resolv.conf` by
+//!   [`parse_resolv_conf`].
+//! - [`lookup_hosts`]: Function to look up a name in a static host table,
+//!   e.g. the contents of `/etc/hosts`.
+//! - [`lookup_service`]: Function to look up the port of a service in a
+//!   service table, e.g. the contents of `/etc/services`.
 //!
 //! # Cargo Features
 //!
@@ -26,7 +37,7 @@
 //!
 //! [smoltcp]: https://github.com/smoltcp-rs/smoltcp
 
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 #![feature(c_variadic)]
 #![feature(ip_in_core)]
 #![feature(ip_bits)]
@@ -47,18 +58,42 @@ cfg_if::cfg_if! {
         mod smoltcp_impl;
         use smoltcp_impl as net_impl;
         pub use self::net_impl::{bench_receive, bench_transmit};
+        pub use self::net_impl::RawSocket;
     }
     else {
         error!("No network stack is selected");
     }
 }
 
+pub use self::net_impl::poll_interfaces;
 pub use self::net_impl::TcpSocket;
 pub use self::net_impl::UdpSocket;
-pub use self::net_impl::{dns_query, poll_interfaces};
+
+mod dns_cache;
+mod hosts;
+mod resolver;
+mod services;
+pub use dns_cache::flush_dns_cache;
+pub use hosts::lookup_hosts;
+pub use resolver::{nameservers, parse_resolv_conf, set_nameservers};
+pub use services::{lookup_service, WELL_KNOWN_SERVICES};
+
+/// Allocation-free hex and base64 codecs, e.g. for logging addresses and keys.
+pub use codec;
 
 use ruxdriver::{prelude::*, AxDeviceContainer};
 
+/// Resolves the IPv4 addresses of the given host name, asking the
+/// [`nameservers`] in turn.
+///
+/// Successful resolutions are cached for the TTL of their records, and names
+/// which do not exist for a short time. It fails with
+/// [`NotFound`](axerrno::AxError::NotFound) if the name does not exist, or
+/// with [`TimedOut`](axerrno::AxError::TimedOut) if no nameserver answered.
+pub fn dns_query(name: &str) -> axerrno::AxResult<alloc::vec::Vec<core::net::IpAddr>> {
+    dns_cache::resolve(name, resolver::query)
+}
+
 /// Initializes the network subsystem by NIC devices.
 pub fn init_network(mut net_devs: AxDeviceContainer<AxNetDevice>) {
     info!("Initialize network subsystem...");
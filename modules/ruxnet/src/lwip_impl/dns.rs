@@ -96,9 +96,3 @@ pub fn resolve_socket_addr(name: &str) -> AxResult<Vec<IpAddr>> {
         _ => ax_err!(InvalidInput, "LWIP dns client error"),
     }
 }
-
-/// Public function for DNS query.
-pub fn dns_query(name: &str) -> AxResult<alloc::vec::Vec<core::net::IpAddr>> {
-    let empty_vec = alloc::vec::Vec::new();
-    Ok(empty_vec)
-}
@@ -5,7 +5,7 @@ mod tcp;
 mod udp;
 
 pub use self::addr::{IpAddr, Ipv4Addr, SocketAddr};
-pub use self::dns::{dns_query, resolve_socket_addr};
+pub use self::dns::resolve_socket_addr;
 pub use self::driver::{init, poll_interfaces};
 pub use self::tcp::TcpSocket;
 pub use self::udp::UdpSocket;
@@ -20,6 +20,9 @@ static LWIP_MUTEX: LazyInit<Mutex<u32>> = LazyInit::new();
 const RECV_QUEUE_LEN: usize = 16;
 const ACCEPT_QUEUE_LEN: usize = 16;
 
+const MSG_PEEK: i32 = 0x2;
+const MSG_DONTWAIT: i32 = 0x40;
+
 #[no_mangle]
 extern "C" fn sys_now() -> c_uint {
     current_time().as_millis() as c_uint
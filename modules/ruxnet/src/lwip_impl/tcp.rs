@@ -7,18 +7,25 @@ use axerrno::{ax_err, AxError, AxResult};
 use axio::PollState;
 use axsync::Mutex;
 use core::cell::UnsafeCell;
-use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use core::time::Duration;
 use core::{ffi::c_void, pin::Pin, ptr::null_mut};
 use lwip_rust::bindings::{
     err_enum_t_ERR_MEM, err_enum_t_ERR_OK, err_enum_t_ERR_USE, err_enum_t_ERR_VAL, err_t,
     ip_addr_t, pbuf, pbuf_free, tcp_accept, tcp_arg, tcp_bind, tcp_close, tcp_connect,
     tcp_listen_with_backlog, tcp_new, tcp_output, tcp_pcb, tcp_recv, tcp_recved, tcp_state_CLOSED,
-    tcp_state_LISTEN, tcp_write, TCP_DEFAULT_LISTEN_BACKLOG, TCP_MSS,
+    tcp_state_LISTEN, tcp_write, tcpflags_t, TCP_MSS, TF_NODELAY,
 };
+use ruxhal::time::current_time;
 use ruxtask::yield_now;
 
 use super::LWIP_MUTEX;
 
+/// Size of the receive window, `TCP_WND` in `lwipopts.h`.
+const TCP_RECV_BUF_LEN: usize = 32 * TCP_MSS as usize;
+/// Size of the send buffer, `TCP_SND_BUF` in `lwipopts.h`.
+const TCP_SEND_BUF_LEN: usize = 16 * TCP_MSS as usize;
+
 struct TcpPcbPointer(Mutex<*mut tcp_pcb>);
 
 unsafe impl Sync for TcpPcbPointer {}
@@ -38,6 +45,10 @@ unsafe impl Send for PbuffPointer {}
 
 struct TcpSocketInner {
     nonblock: AtomicBool,
+    /// Whether the Nagle algorithm is disabled, see `TCP_NODELAY`.
+    nodelay: AtomicBool,
+    /// Timeout of connects in nanoseconds, 0 for none, see `SO_SNDTIMEO`.
+    send_timeout: AtomicU64,
     remote_closed: bool,
     connect_result: UnsafeCell<i8>,
     recv_queue: Mutex<VecDeque<(PbuffPointer, usize)>>,
@@ -100,6 +111,8 @@ extern "C" fn accept_callback(arg: *mut c_void, newpcb: *mut tcp_pcb, err: err_t
         pcb: TcpPcbPointer(Mutex::new(newpcb)),
         inner: Box::pin(TcpSocketInner {
             nonblock: AtomicBool::new(false),
+            nodelay: AtomicBool::new(false),
+            send_timeout: AtomicU64::new(0),
             remote_closed: false,
             connect_result: 0.into(),
             recv_queue: Mutex::new(VecDeque::with_capacity(RECV_QUEUE_LEN)),
@@ -129,6 +142,8 @@ impl TcpSocket {
             pcb: TcpPcbPointer(Mutex::new(unsafe { tcp_new() })),
             inner: Box::pin(TcpSocketInner {
                 nonblock: AtomicBool::new(false),
+                nodelay: AtomicBool::new(false),
+                send_timeout: AtomicU64::new(0),
                 remote_closed: false,
                 connect_result: 0.into(),
                 recv_queue: Mutex::new(VecDeque::new()),
@@ -209,6 +224,83 @@ impl TcpSocket {
         self.inner.nonblock.store(nonblocking, Ordering::Release);
     }
 
+    /// Returns whether the local address can be reused, always `false` with
+    /// lwIP.
+    #[inline]
+    pub fn is_reuse_addr(&self) -> bool {
+        false
+    }
+
+    /// Allows to reuse the local address like `SO_REUSEADDR`, which is
+    /// ignored with lwIP.
+    #[inline]
+    pub fn set_reuse_addr(&self, _reuse_addr: bool) {}
+
+    /// Returns whether small segments are sent without delay, as with
+    /// `TCP_NODELAY`.
+    #[inline]
+    pub fn is_nodelay(&self) -> bool {
+        self.inner.nodelay.load(Ordering::Acquire)
+    }
+
+    /// Disables or enables the Nagle algorithm, like `TCP_NODELAY`.
+    ///
+    /// If set, the data is sent as soon as possible, even in small segments
+    /// while previous data is not acknowledged.
+    #[inline]
+    pub fn set_nodelay(&self, nodelay: bool) {
+        self.inner.nodelay.store(nodelay, Ordering::Release);
+    }
+
+    /// Returns the size of the receive buffer, as with `SO_RCVBUF`.
+    #[inline]
+    pub fn recv_buffer_size(&self) -> usize {
+        TCP_RECV_BUF_LEN
+    }
+
+    /// Sets the size of the receive buffer like `SO_RCVBUF`, which is ignored
+    /// with lwIP as its buffers are sized at build time.
+    #[inline]
+    pub fn set_recv_buffer_size(&self, _size: usize) {}
+
+    /// Returns the size of the send buffer, as with `SO_SNDBUF`.
+    #[inline]
+    pub fn send_buffer_size(&self) -> usize {
+        TCP_SEND_BUF_LEN
+    }
+
+    /// Sets the size of the send buffer like `SO_SNDBUF`, which is ignored
+    /// with lwIP as its buffers are sized at build time.
+    #[inline]
+    pub fn set_send_buffer_size(&self, _size: usize) {}
+
+    /// Returns the timeout of blocking sends and connects, as with
+    /// `SO_SNDTIMEO`.
+    pub fn send_timeout(&self) -> Option<Duration> {
+        match self.inner.send_timeout.load(Ordering::Acquire) {
+            0 => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
+    }
+
+    /// Sets the timeout of blocking sends and connects, like `SO_SNDTIMEO`.
+    ///
+    /// Sends never block with lwIP, so only a [`connect`](Self::connect) can
+    /// time out, and fails with [`TimedOut`](AxError::TimedOut). `None` or a
+    /// zero duration waits forever.
+    pub fn set_send_timeout(&self, timeout: Option<Duration>) {
+        let nanos = timeout.map_or(0, |t| t.as_nanos().min(u64::MAX as u128) as u64);
+        self.inner.send_timeout.store(nanos, Ordering::Release);
+    }
+
+    /// Returns and clears the pending error of the socket, like `SO_ERROR`.
+    ///
+    /// Always `None` with lwIP, as [`connect`](Self::connect) blocks until the
+    /// connection is established and reports its failure itself.
+    pub fn take_error(&self) -> Option<AxError> {
+        None
+    }
+
     /// Connects to the given address and port.
     ///
     /// The local port is generated automatically.
@@ -242,9 +334,13 @@ impl TcpSocket {
 
         // wait for connect
         debug!("[TcpSocket] wait for connect");
+        let deadline = self.send_timeout().map(|timeout| current_time() + timeout);
         lwip_loop_once();
         #[allow(clippy::while_immutable_condition)]
         while unsafe { self.inner.connect_result.get().read() == 1 } {
+            if deadline.is_some_and(|deadline| current_time() >= deadline) {
+                return ax_err!(TimedOut, "LWIP [tcp_connect] Timed out.");
+            }
             yield_now();
             lwip_loop_once();
         }
@@ -293,14 +389,17 @@ impl TcpSocket {
 
     /// Starts listening on the bound address and port.
     ///
+    /// At most `backlog` connections are queued until they are accepted, the
+    /// others are refused.
+    ///
     /// It's must be called after [`bind`](Self::bind) and before
     /// [`accept`](Self::accept).
-    pub fn listen(&mut self) -> AxResult {
+    pub fn listen(&mut self, backlog: usize) -> AxResult {
         let guard = LWIP_MUTEX.lock();
         unsafe {
             if (*self.pcb.get()).state == tcp_state_CLOSED {
-                let listen_res =
-                    tcp_listen_with_backlog(self.pcb.get(), TCP_DEFAULT_LISTEN_BACKLOG as u8);
+                let backlog = backlog.clamp(1, u8::MAX as usize) as u8;
+                let listen_res = tcp_listen_with_backlog(self.pcb.get(), backlog);
                 self.pcb.set(listen_res);
                 tcp_arg(
                     self.pcb.get(),
@@ -368,6 +467,18 @@ impl TcpSocket {
         }
     }
 
+    /// Aborts the connection or stops listening, and returns the socket to the
+    /// unbound and unconnected state, so that it can [`connect`](Self::connect)
+    /// or [`bind`](Self::bind) again.
+    ///
+    /// The nonblocking mode is kept.
+    pub fn reset(&mut self) -> AxResult {
+        let nonblocking = self.is_nonblocking();
+        *self = Self::new();
+        self.set_nonblocking(nonblocking);
+        Ok(())
+    }
+
     /// Receives data from the socket, stores it in the given buffer.
     pub fn recv(&self, buf: &mut [u8], flags: i32) -> AxResult<usize> {
         loop {
@@ -432,6 +543,12 @@ impl TcpSocket {
         let copy_len = core::cmp::min(buf.len(), TCP_MSS as usize);
         unsafe {
             let _guard = LWIP_MUTEX.lock();
+            let pcb = self.pcb.get();
+            if self.is_nodelay() {
+                (*pcb).flags |= TF_NODELAY as tcpflags_t;
+            } else {
+                (*pcb).flags &= !(TF_NODELAY as tcpflags_t);
+            }
             trace!("[TcpSocket] tcp_write");
             #[allow(non_upper_case_globals)]
             match tcp_write(self.pcb.get(), buf.as_ptr() as *const _, copy_len as u16, 0) as i32 {
@@ -457,6 +574,38 @@ impl TcpSocket {
         Ok(copy_len)
     }
 
+    /// Transmits data in the given buffers, one after another.
+    ///
+    /// Returns the number of bytes sent, which stops at the first buffer not
+    /// sent entirely.
+    pub fn send_vectored(&self, bufs: &[&[u8]]) -> AxResult<usize> {
+        let mut len = 0;
+        for buf in bufs {
+            let n = match self.send(buf) {
+                Ok(n) => n,
+                Err(_) if len > 0 => break, // report the data sent first
+                Err(e) => return Err(e),
+            };
+            len += n;
+            if n < buf.len() {
+                break;
+            }
+        }
+        Ok(len)
+    }
+
+    /// Transmits data with its last byte marked as urgent, which is not
+    /// supported with lwIP.
+    pub fn send_urgent(&self, _buf: &[u8]) -> AxResult<usize> {
+        ax_err!(Unsupported, "LWIP urgent data is not supported")
+    }
+
+    /// Whether the next byte to receive is urgent, always `false` as urgent
+    /// data is not supported with lwIP.
+    pub fn at_mark(&self) -> AxResult<bool> {
+        Ok(false)
+    }
+
     /// Detect whether the socket needs to receive/can send.
     ///
     /// Return is <need to receive, can send>
@@ -469,6 +618,7 @@ impl TcpSocket {
             Ok(PollState {
                 readable: self.inner.accept_queue.lock().len() != 0,
                 writable: false,
+                ..Default::default()
             })
         } else {
             let test = self.inner.recv_queue.lock().len();
@@ -476,6 +626,7 @@ impl TcpSocket {
             Ok(PollState {
                 readable: self.inner.recv_queue.lock().len() != 0,
                 writable: true,
+                ..Default::default()
             })
         }
     }
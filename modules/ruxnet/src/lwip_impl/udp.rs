@@ -1,5 +1,5 @@
 use crate::{
-    net_impl::{driver::lwip_loop_once, RECV_QUEUE_LEN},
+    net_impl::{driver::lwip_loop_once, MSG_DONTWAIT, MSG_PEEK, RECV_QUEUE_LEN},
     IpAddr, SocketAddr,
 };
 use alloc::{boxed::Box, collections::VecDeque};
@@ -18,6 +18,10 @@ use ruxtask::yield_now;
 
 use super::LWIP_MUTEX;
 
+/// Size reported for the buffers, lwIP queues the datagrams without limiting
+/// their total size.
+const UDP_BUF_LEN: usize = u16::MAX as usize;
+
 struct UdpPcbPointer(Mutex<*mut udp_pcb>);
 unsafe impl Send for UdpPcbPointer {}
 struct PbuffPointer(*mut pbuf);
@@ -34,6 +38,7 @@ impl UdpPcbPointer {
 
 struct UdpSocketInner {
     nonblock: AtomicBool,
+    broadcast: AtomicBool,
     recv_queue: Mutex<VecDeque<(PbuffPointer, usize, core::net::SocketAddr)>>,
 }
 
@@ -76,6 +81,7 @@ impl UdpSocket {
             pcb: UdpPcbPointer(unsafe { Mutex::new(udp_new()) }),
             inner: Box::pin(UdpSocketInner {
                 nonblock: AtomicBool::new(false),
+                broadcast: AtomicBool::new(false),
                 recv_queue: Mutex::new(VecDeque::with_capacity(RECV_QUEUE_LEN)),
             }),
         };
@@ -152,6 +158,44 @@ impl UdpSocket {
         self.inner.nonblock.store(nonblocking, Ordering::Release);
     }
 
+    /// Returns whether sending to a broadcast address is allowed, as with
+    /// `SO_BROADCAST`.
+    #[inline]
+    pub fn is_broadcast(&self) -> bool {
+        self.inner.broadcast.load(Ordering::Acquire)
+    }
+
+    /// Allows or forbids sending to a broadcast address, like `SO_BROADCAST`.
+    ///
+    /// If forbidden, which is the default, sending to the limited broadcast
+    /// address fails with [`Err(PermissionDenied)`](AxError::PermissionDenied).
+    #[inline]
+    pub fn set_broadcast(&self, broadcast: bool) {
+        self.inner.broadcast.store(broadcast, Ordering::Release);
+    }
+
+    /// Returns the size of the receive buffer, as with `SO_RCVBUF`.
+    #[inline]
+    pub fn recv_buffer_size(&self) -> usize {
+        UDP_BUF_LEN
+    }
+
+    /// Sets the size of the receive buffer like `SO_RCVBUF`, which is ignored
+    /// with lwIP.
+    #[inline]
+    pub fn set_recv_buffer_size(&self, _size: usize) {}
+
+    /// Returns the size of the send buffer, as with `SO_SNDBUF`.
+    #[inline]
+    pub fn send_buffer_size(&self) -> usize {
+        UDP_BUF_LEN
+    }
+
+    /// Sets the size of the send buffer like `SO_SNDBUF`, which is ignored
+    /// with lwIP.
+    #[inline]
+    pub fn set_send_buffer_size(&self, _size: usize) {}
+
     /// Binds an unbound socket to the given address and port.
     ///
     /// It's must be called before [`send_to`](Self::send_to) and
@@ -178,6 +222,11 @@ impl UdpSocket {
 
     /// Transmits data in the given buffer to the given address.
     pub fn send_to(&self, buf: &[u8], caddr: core::net::SocketAddr) -> AxResult<usize> {
+        if !self.is_broadcast()
+            && matches!(caddr.ip(), core::net::IpAddr::V4(ip) if ip.is_broadcast())
+        {
+            return ax_err!(PermissionDenied, "socket send() failed: broadcast");
+        }
         let addr = SocketAddr::from(caddr);
         trace!("[UdpSocket] send (len = {})", buf.len());
         let copy_len = core::cmp::min(buf.len(), 1472);
@@ -221,12 +270,34 @@ impl UdpSocket {
 
     /// Receives data from the socket, stores it in the given buffer.
     pub fn recv_from(&self, buf: &mut [u8]) -> AxResult<(usize, core::net::SocketAddr)> {
-        trace!("[UdpSocket] recvfrom");
+        self.recv_from_flags(buf, 0)
+    }
+
+    /// Receives data from the socket, like `recvfrom` with `flags`.
+    ///
+    /// `MSG_PEEK` leaves the data in the queue, and `MSG_DONTWAIT` does not
+    /// block even if the socket is in blocking mode. Other flags are ignored.
+    pub fn recv_from_flags(
+        &self,
+        buf: &mut [u8],
+        flags: i32,
+    ) -> AxResult<(usize, core::net::SocketAddr)> {
+        trace!("[UdpSocket] recvfrom (flags: {:#x})", flags);
+        let nonblocking = self.is_nonblocking() || flags & MSG_DONTWAIT != 0;
         loop {
             lwip_loop_once();
             let mut recv_queue = self.inner.recv_queue.lock();
             let res: Result<(usize, SocketAddr), AxError> = if recv_queue.len() == 0 {
                 Err(AxError::WouldBlock)
+            } else if flags & MSG_PEEK != 0 {
+                let (p, offset, caddr) = recv_queue.front().unwrap();
+                let p: *mut pbuf = p.0;
+                let len = unsafe { (*p).len as usize };
+                let payload = unsafe { (*p).payload };
+                let payload = unsafe { core::slice::from_raw_parts(payload as *const u8, len) };
+                let copy_len = core::cmp::min(len - offset, buf.len());
+                buf[0..copy_len].copy_from_slice(&payload[*offset..offset + copy_len]);
+                Ok((copy_len, SocketAddr::from(*caddr)))
             } else {
                 let (p, offset, caddr) = recv_queue.pop_front().unwrap();
                 let addr = SocketAddr::from(caddr);
@@ -262,7 +333,7 @@ impl UdpSocket {
                     return Ok((len, addr.into()));
                 }
                 Err(AxError::WouldBlock) => {
-                    if self.is_nonblocking() {
+                    if nonblocking {
                         return Err(AxError::WouldBlock);
                     } else {
                         yield_now();
@@ -323,8 +394,8 @@ impl UdpSocket {
     }
 
     /// Receives data from the socket, stores it in the given buffer, without removing it from the queue.
-    pub fn peek_from(&self, _buf: &mut [u8]) -> AxResult<(usize, core::net::SocketAddr)> {
-        ax_err!(Unsupported, "LWIP Unsupported UDP peek_from")
+    pub fn peek_from(&self, buf: &mut [u8]) -> AxResult<(usize, core::net::SocketAddr)> {
+        self.recv_from_flags(buf, MSG_PEEK)
     }
 
     /// Detect whether the socket needs to receive/can send.
@@ -335,6 +406,7 @@ impl UdpSocket {
         Ok(PollState {
             readable: self.inner.recv_queue.lock().len() != 0,
             writable: true,
+            ..Default::default()
         })
     }
 }
@@ -0,0 +1,374 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! A DNS stub resolver, which asks the nameservers in turn for the A records
+//! of a name over UDP.
+
+use alloc::vec::Vec;
+use axerrno::{ax_err, AxError, AxResult};
+use core::net::{IpAddr, Ipv4Addr};
+use core::sync::atomic::{AtomicU16, Ordering};
+use core::time::Duration;
+use spin::Mutex;
+
+/// The nameserver used if none is set.
+const DEFAULT_NAMESERVER: Ipv4Addr = Ipv4Addr::new(8, 8, 8, 8);
+/// How long to wait for the answer of a nameserver before trying the next one.
+const TIMEOUT: Duration = Duration::from_secs(2);
+/// Maximum size of a DNS message over UDP.
+const MAX_MSG_LEN: usize = 512;
+/// Size of the header of a DNS message.
+const HEADER_LEN: usize = 12;
+
+const TYPE_A: u16 = 1;
+const CLASS_IN: u16 = 1;
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAG_RECURSION_DESIRED: u16 = 0x0100;
+const RCODE_NAME_ERROR: u16 = 3;
+
+static NAMESERVERS: Mutex<Vec<Ipv4Addr>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU16 = AtomicU16::new(0);
+
+/// Sets the nameservers, which are asked in this order. The next one is asked
+/// if one does not answer in time or fails.
+///
+/// An empty list restores the default one, `8.8.8.8`.
+pub fn set_nameservers(servers: &[Ipv4Addr]) {
+    *NAMESERVERS.lock() = servers.to_vec();
+}
+
+/// Returns the nameservers in the order they are asked.
+pub fn nameservers() -> Vec<Ipv4Addr> {
+    let servers = NAMESERVERS.lock();
+    if servers.is_empty() {
+        alloc::vec![DEFAULT_NAMESERVER]
+    } else {
+        servers.clone()
+    }
+}
+
+/// Returns the IPv4 nameservers listed in `conf`, in the format of
+/// `/etc/resolv.conf`.
+///
+/// Only the `nameserver` lines are read. Everything after a `#` or a `;` is
+/// a comment.
+pub fn parse_resolv_conf(conf: &str) -> Vec<Ipv4Addr> {
+    conf.lines()
+        .filter_map(|line| {
+            let line = line.split(['#', ';']).next().unwrap_or_default();
+            let mut fields = line.split_whitespace();
+            if fields.next() != Some("nameserver") {
+                return None;
+            }
+            fields.next()?.parse().ok()
+        })
+        .collect()
+}
+
+/// The addresses of a name, and how long they may be cached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Answer {
+    pub addrs: Vec<IpAddr>,
+    pub ttl: Duration,
+}
+
+/// The way the queries reach the nameservers.
+pub(crate) trait Transport {
+    /// Sends `query` to `server` and receives its reply into `buf`, or fails
+    /// with [`TimedOut`](AxError::TimedOut) if it does not come in time.
+    fn exchange(&mut self, server: Ipv4Addr, query: &[u8], buf: &mut [u8]) -> AxResult<usize>;
+}
+
+/// Sends the queries from a new UDP socket each time.
+struct UdpTransport;
+
+impl Transport for UdpTransport {
+    fn exchange(&mut self, server: Ipv4Addr, query: &[u8], buf: &mut [u8]) -> AxResult<usize> {
+        let server = core::net::SocketAddr::new(IpAddr::V4(server), 53);
+        let socket = crate::UdpSocket::new();
+        socket.bind(core::net::SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0))?;
+        socket.set_nonblocking(true);
+        socket.send_to(query, server)?;
+        let deadline = ruxhal::time::current_time() + TIMEOUT;
+        loop {
+            crate::poll_interfaces();
+            match socket.recv_from(buf) {
+                Ok((len, from)) if from == server => return Ok(len),
+                // a stray datagram
+                Ok(_) => {}
+                Err(AxError::WouldBlock) if ruxhal::time::current_time() < deadline => {
+                    ruxtask::yield_now()
+                }
+                Err(AxError::WouldBlock) => return ax_err!(TimedOut, "DNS query timed out"),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Resolves the IPv4 addresses of `name` with the nameservers set.
+pub(crate) fn query(name: &str) -> AxResult<Answer> {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed) ^ ruxhal::time::current_ticks() as u16;
+    query_with(&mut UdpTransport, &nameservers(), name, id)
+}
+
+/// Resolves the IPv4 addresses of `name`, asking `servers` in turn through
+/// `transport`.
+///
+/// Fails with [`NotFound`](AxError::NotFound) if a server answers that the
+/// name does not exist or has no addresses, or with
+/// [`TimedOut`](AxError::TimedOut) if no server answers.
+pub(crate) fn query_with<T: Transport>(
+    transport: &mut T,
+    servers: &[Ipv4Addr],
+    name: &str,
+    id: u16,
+) -> AxResult<Answer> {
+    let query = build_query(name, id)?;
+    let mut buf = [0; MAX_MSG_LEN];
+    for &server in servers {
+        let len = match transport.exchange(server, &query, &mut buf) {
+            Ok(len) => len,
+            Err(e) => {
+                debug!("DNS server {} failed: {:?}", server, e);
+                continue;
+            }
+        };
+        match parse_reply(&buf[..len], id) {
+            Err(AxError::NotFound) => return Err(AxError::NotFound),
+            Err(e) => debug!("DNS server {} failed: {:?}", server, e),
+            answer => return answer,
+        }
+    }
+    ax_err!(TimedOut, "no DNS server answered")
+}
+
+/// Returns a query for the A records of `name`.
+fn build_query(name: &str, id: u16) -> AxResult<Vec<u8>> {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    if name.is_empty() || name.len() > 253 {
+        return ax_err!(InvalidInput, "invalid DNS name");
+    }
+    let mut query = Vec::with_capacity(HEADER_LEN + name.len() + 6);
+    for word in [id, FLAG_RECURSION_DESIRED, 1, 0, 0, 0] {
+        query.extend_from_slice(&word.to_be_bytes());
+    }
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return ax_err!(InvalidInput, "invalid DNS name");
+        }
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&TYPE_A.to_be_bytes());
+    query.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(query)
+}
+
+fn read_u16(msg: &[u8], pos: usize) -> AxResult<u16> {
+    match msg.get(pos..pos + 2) {
+        Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+        None => ax_err!(InvalidData, "truncated DNS message"),
+    }
+}
+
+/// Returns the position after the name at `pos`, which may end with a
+/// compression pointer.
+fn skip_name(msg: &[u8], mut pos: usize) -> AxResult<usize> {
+    loop {
+        match msg.get(pos) {
+            Some(0) => return Ok(pos + 1),
+            Some(len) if len & 0xc0 == 0xc0 => return Ok(pos + 2),
+            Some(len) => pos += 1 + *len as usize,
+            None => return ax_err!(InvalidData, "truncated DNS message"),
+        }
+    }
+}
+
+/// Returns the A records of the reply `msg` to the query `id`, and the
+/// smallest of their TTLs.
+fn parse_reply(msg: &[u8], id: u16) -> AxResult<Answer> {
+    let flags = read_u16(msg, 2)?;
+    if read_u16(msg, 0)? != id || flags & FLAG_RESPONSE == 0 {
+        return ax_err!(InvalidData, "not a reply to the DNS query");
+    }
+    match flags & 0xf {
+        0 => {}
+        RCODE_NAME_ERROR => return Err(AxError::NotFound),
+        _ => return ax_err!(ConnectionRefused, "DNS server failure"),
+    }
+    let questions = read_u16(msg, 4)?;
+    let answers = read_u16(msg, 6)?;
+    let mut pos = HEADER_LEN;
+    for _ in 0..questions {
+        pos = skip_name(msg, pos)? + 4;
+    }
+    let mut addrs = Vec::new();
+    let mut ttl = u32::MAX;
+    for _ in 0..answers {
+        pos = skip_name(msg, pos)?;
+        let ty = read_u16(msg, pos)?;
+        let class = read_u16(msg, pos + 2)?;
+        let rr_ttl = (read_u16(msg, pos + 4)? as u32) << 16 | read_u16(msg, pos + 6)? as u32;
+        let len = read_u16(msg, pos + 8)? as usize;
+        pos += 10;
+        let data = msg.get(pos..pos + len).ok_or(AxError::InvalidData)?;
+        // the records of the aliases are skipped, their targets follow
+        if ty == TYPE_A && class == CLASS_IN && len == 4 {
+            addrs.push(IpAddr::V4(Ipv4Addr::new(
+                data[0], data[1], data[2], data[3],
+            )));
+            ttl = ttl.min(rr_ttl);
+        }
+        pos += len;
+    }
+    if addrs.is_empty() {
+        return Err(AxError::NotFound);
+    }
+    Ok(Answer {
+        addrs,
+        ttl: Duration::from_secs(ttl as u64),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    const SERVER1: Ipv4Addr = Ipv4Addr::new(10, 0, 2, 3);
+    const SERVER2: Ipv4Addr = Ipv4Addr::new(10, 0, 2, 4);
+    const ADDR: Ipv4Addr = Ipv4Addr::new(93, 184, 216, 34);
+
+    /// A nameserver which knows `example.com` only, aliased by `www.example.com`.
+    fn respond(query: &[u8], buf: &mut [u8]) -> usize {
+        let question_end = skip_name(query, HEADER_LEN).unwrap() + 4;
+        let qname = &query[HEADER_LEN..question_end - 4];
+        let (rcode, records): (u16, &[&[u8]]) = match qname {
+            b"\x07example\x03com\x00" => (0, &[b"\x00\x01\x00\x01\x00\x00\x01\x2c\x00\x04"]),
+            b"\x03www\x07example\x03com\x00" => (
+                0,
+                &[
+                    // CNAME to the name at offset 20, then its A record
+                    b"\x00\x05\x00\x01\x00\x00\x0e\x10\x00\x02\xc0\x10",
+                    b"\x00\x01\x00\x01\x00\x00\x00\x3c\x00\x04",
+                ],
+            ),
+            _ => (RCODE_NAME_ERROR, &[]),
+        };
+        let mut reply = query[..question_end].to_vec();
+        reply[2..4].copy_from_slice(&(FLAG_RESPONSE | rcode).to_be_bytes());
+        reply[6..8].copy_from_slice(&(records.len() as u16).to_be_bytes());
+        for record in records {
+            // the owner name points to the question
+            reply.extend_from_slice(b"\xc0\x0c");
+            reply.extend_from_slice(record);
+            if record[1] == TYPE_A as u8 {
+                reply.extend_from_slice(&ADDR.octets());
+            }
+        }
+        buf[..reply.len()].copy_from_slice(&reply);
+        reply.len()
+    }
+
+    /// Answers through `respond` from the servers in `alive`, the others time out.
+    struct MockTransport {
+        alive: Vec<Ipv4Addr>,
+        queries: usize,
+    }
+
+    impl Transport for MockTransport {
+        fn exchange(&mut self, server: Ipv4Addr, query: &[u8], buf: &mut [u8]) -> AxResult<usize> {
+            self.queries += 1;
+            if !self.alive.contains(&server) {
+                return Err(AxError::TimedOut);
+            }
+            Ok(respond(query, buf))
+        }
+    }
+
+    fn mock(alive: &[Ipv4Addr]) -> MockTransport {
+        MockTransport {
+            alive: alive.to_vec(),
+            queries: 0,
+        }
+    }
+
+    #[test]
+    fn test_query() {
+        let mut transport = mock(&[SERVER1]);
+        let answer = query_with(&mut transport, &[SERVER1], "example.com", 7);
+        assert_eq!(
+            answer,
+            Ok(Answer {
+                addrs: vec![IpAddr::V4(ADDR)],
+                ttl: Duration::from_secs(300),
+            })
+        );
+        // the alias is skipped and the smallest TTL is kept
+        let answer = query_with(&mut transport, &[SERVER1], "www.example.com.", 8).unwrap();
+        assert_eq!(answer.addrs, vec![IpAddr::V4(ADDR)]);
+        assert_eq!(answer.ttl, Duration::from_secs(60));
+
+        assert_eq!(
+            query_with(&mut transport, &[SERVER1], "nx.example.com", 9),
+            Err(AxError::NotFound)
+        );
+        assert_eq!(
+            query_with(&mut transport, &[SERVER1], "a..b", 10),
+            Err(AxError::InvalidInput)
+        );
+        assert_eq!(transport.queries, 3);
+    }
+
+    #[test]
+    fn test_server_fallback() {
+        let mut transport = mock(&[SERVER2]);
+        let answer = query_with(&mut transport, &[SERVER1, SERVER2], "example.com", 1);
+        assert_eq!(answer.unwrap().addrs, vec![IpAddr::V4(ADDR)]);
+        assert_eq!(transport.queries, 2);
+
+        // the next server is not asked about a name which does not exist
+        let mut transport = mock(&[SERVER1, SERVER2]);
+        let answer = query_with(&mut transport, &[SERVER1, SERVER2], "nx.example", 2);
+        assert_eq!(answer, Err(AxError::NotFound));
+        assert_eq!(transport.queries, 1);
+
+        let mut transport = mock(&[]);
+        let answer = query_with(&mut transport, &[SERVER1, SERVER2], "example.com", 3);
+        assert_eq!(answer, Err(AxError::TimedOut));
+        assert_eq!(transport.queries, 2);
+    }
+
+    #[test]
+    fn test_cached_answer() {
+        let cache = Mutex::new(crate::dns_cache::DnsCache::new(4));
+        let mut transport = mock(&[SERVER1]);
+        let now = Duration::from_secs(100);
+        for _ in 0..2 {
+            let addrs = crate::dns_cache::resolve_with(&cache, "example.com", now, |name| {
+                query_with(&mut transport, &[SERVER1], name, 1)
+            });
+            assert_eq!(addrs, Ok(vec![IpAddr::V4(ADDR)]));
+        }
+        assert_eq!(transport.queries, 1);
+    }
+
+    #[test]
+    fn test_parse_resolv_conf() {
+        let conf = "# generated\n\
+            nameserver 10.0.2.3\n\
+            search lan\n\
+            nameserver 10.0.2.4 ; secondary\n\
+            nameserver ::1\n\
+            options edns0\n";
+        assert_eq!(parse_resolv_conf(conf), vec![SERVER1, SERVER2]);
+    }
+}
@@ -0,0 +1,83 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Service name table in the format of `/etc/services`.
+
+/// Well-known services, used when there is no `/etc/services` or the
+/// service is not found in it.
+pub const WELL_KNOWN_SERVICES: &str = "\
+echo            7/tcp
+echo            7/udp
+ftp-data        20/tcp
+ftp             21/tcp
+ssh             22/tcp
+telnet          23/tcp
+smtp            25/tcp          mail
+domain          53/tcp
+domain          53/udp
+tftp            69/udp
+http            80/tcp          www
+pop3            110/tcp         pop-3
+ntp             123/udp
+imap            143/tcp         imap2
+snmp            161/udp
+ldap            389/tcp
+https           443/tcp
+submission      587/tcp
+imaps           993/tcp
+pop3s           995/tcp
+mysql           3306/tcp
+postgresql      5432/tcp        postgres
+";
+
+/// Returns the port of the service `name` over `protocol` (e.g. `"tcp"`) in
+/// the service table `table`.
+///
+/// Each line of the table is a service name, its `port/protocol`, and its
+/// aliases, separated by blanks. Everything after a `#` is a comment, and the
+/// lines with an invalid port are skipped. Names are case sensitive.
+pub fn lookup_service(table: &str, name: &str, protocol: &str) -> Option<u16> {
+    table.lines().find_map(|line| {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut fields = line.split_whitespace();
+        let service = fields.next()?;
+        let (port, proto) = fields.next()?.split_once('/')?;
+        let port = port.parse::<u16>().ok()?;
+        (proto == protocol && (service == name || fields.any(|alias| alias == name)))
+            .then_some(port)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVICES: &str = "http\t80/tcp\twww # WorldWideWeb HTTP\n\
+        # gopher 70/tcp\n\
+        domain 53/tcp\n\
+        domain 53/udp\n\
+        broken 99999/tcp\n\
+        lonely\n";
+
+    #[test]
+    fn test_lookup_service() {
+        assert_eq!(lookup_service(SERVICES, "http", "tcp"), Some(80));
+        assert_eq!(lookup_service(SERVICES, "www", "tcp"), Some(80));
+        assert_eq!(lookup_service(SERVICES, "http", "udp"), None);
+        assert_eq!(lookup_service(SERVICES, "domain", "udp"), Some(53));
+        assert_eq!(lookup_service(SERVICES, "gopher", "tcp"), None);
+        assert_eq!(lookup_service(SERVICES, "broken", "tcp"), None);
+        assert_eq!(lookup_service(SERVICES, "lonely", "tcp"), None);
+        assert_eq!(lookup_service(SERVICES, "WorldWideWeb", "tcp"), None);
+        assert_eq!(
+            lookup_service(WELL_KNOWN_SERVICES, "https", "tcp"),
+            Some(443)
+        );
+    }
+}
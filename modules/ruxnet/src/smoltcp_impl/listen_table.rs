@@ -22,14 +22,28 @@ const PORT_NUM: usize = 65536;
 
 struct ListenTableEntry {
     listen_endpoint: IpListenEndpoint,
+    /// Maximum number of pending connections, established or not.
+    backlog: usize,
+    /// Pending connections, from the oldest to the newest.
     syn_queue: VecDeque<SocketHandle>,
+    /// Buffer lengths of the sockets of new connections.
+    rx_buf_len: usize,
+    tx_buf_len: usize,
 }
 
 impl ListenTableEntry {
-    pub fn new(listen_endpoint: IpListenEndpoint) -> Self {
+    pub fn new(
+        listen_endpoint: IpListenEndpoint,
+        backlog: usize,
+        rx_buf_len: usize,
+        tx_buf_len: usize,
+    ) -> Self {
         Self {
             listen_endpoint,
-            syn_queue: VecDeque::with_capacity(LISTEN_QUEUE_SIZE),
+            backlog,
+            rx_buf_len,
+            tx_buf_len,
+            syn_queue: VecDeque::with_capacity(backlog),
         }
     }
 
@@ -70,12 +84,29 @@ impl ListenTable {
         self.tcp[port as usize].lock().is_none()
     }
 
-    pub fn listen(&self, listen_endpoint: IpListenEndpoint) -> AxResult {
+    /// Starts listening on `listen_endpoint`, queueing at most `backlog`
+    /// pending connections, which is clamped to `1..=LISTEN_QUEUE_SIZE`.
+    ///
+    /// The sockets of new connections get buffers of `rx_buf_len` and
+    /// `tx_buf_len` bytes.
+    pub fn listen(
+        &self,
+        listen_endpoint: IpListenEndpoint,
+        backlog: usize,
+        rx_buf_len: usize,
+        tx_buf_len: usize,
+    ) -> AxResult {
         let port = listen_endpoint.port;
         assert_ne!(port, 0);
+        let backlog = backlog.clamp(1, LISTEN_QUEUE_SIZE);
         let mut entry = self.tcp[port as usize].lock();
         if entry.is_none() {
-            *entry = Some(Box::new(ListenTableEntry::new(listen_endpoint)));
+            *entry = Some(Box::new(ListenTableEntry::new(
+                listen_endpoint,
+                backlog,
+                rx_buf_len,
+                tx_buf_len,
+            )));
             Ok(())
         } else {
             ax_err!(AddrInUse, "socket listen() failed")
@@ -119,23 +150,28 @@ impl ListenTable {
         }
     }
 
+    /// Prepares a socket for the connection opened by an incoming SYN, and
+    /// returns whether it is accepted into the SYN queue.
     pub fn incoming_tcp_packet(
         &self,
         src: IpEndpoint,
         dst: IpEndpoint,
         sockets: &mut SocketSet<'_>,
-    ) {
+    ) -> bool {
         if let Some(entry) = self.tcp[dst.port as usize].lock().deref_mut() {
             if !entry.can_accept(dst.addr) {
                 // not listening on this address
-                return;
+                return false;
             }
-            if entry.syn_queue.len() >= LISTEN_QUEUE_SIZE {
-                // SYN queue is full, drop the packet
-                warn!("SYN queue overflow!");
-                return;
+            if entry.syn_queue.len() >= entry.backlog {
+                // The backlog is full, no socket takes the SYN so it is refused.
+                debug!(
+                    "TCP backlog of {} is full, refuse connection {} -> {}",
+                    entry.backlog, src, dst
+                );
+                return false;
             }
-            let mut socket = SocketSetWrapper::new_tcp_socket();
+            let mut socket = SocketSetWrapper::new_tcp_socket(entry.rx_buf_len, entry.tx_buf_len);
             if socket.listen(entry.listen_endpoint).is_ok() {
                 let handle = sockets.add(socket);
                 debug!(
@@ -143,8 +179,10 @@ impl ListenTable {
                     handle, src, entry.listen_endpoint
                 );
                 entry.syn_queue.push_back(handle);
+                return true;
             }
         }
+        false
     }
 }
 
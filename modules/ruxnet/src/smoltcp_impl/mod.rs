@@ -9,15 +9,17 @@
 
 mod addr;
 mod bench;
-mod dns;
 mod listen_table;
+mod raw;
 mod tcp;
 mod udp;
+mod urgent;
 
 use alloc::vec;
 use core::cell::RefCell;
 use core::ops::DerefMut;
 
+use axerrno::{ax_err, AxResult};
 use axsync::Mutex;
 use driver_net::{DevError, NetBufPtr};
 use lazy_init::LazyInit;
@@ -27,11 +29,11 @@ use smoltcp::iface::{Config, Interface, SocketHandle, SocketSet};
 use smoltcp::phy::{Device, DeviceCapabilities, Medium, RxToken, TxToken};
 use smoltcp::socket::{self, AnySocket};
 use smoltcp::time::Instant;
-use smoltcp::wire::{EthernetAddress, HardwareAddress, IpAddress, IpCidr};
+use smoltcp::wire::{EthernetAddress, HardwareAddress, IpAddress, IpCidr, IpProtocol, IpVersion};
 
 use self::listen_table::ListenTable;
 
-pub use self::dns::dns_query;
+pub use self::raw::RawSocket;
 pub use self::tcp::TcpSocket;
 pub use self::udp::UdpSocket;
 
@@ -46,7 +48,6 @@ macro_rules! env_or_default {
 
 const IP: &str = env_or_default!("RUX_IP");
 const GATEWAY: &str = env_or_default!("RUX_GW");
-const DNS_SEVER: &str = "8.8.8.8";
 const IP_PREFIX: u8 = 24;
 
 const STANDARD_MTU: usize = 1500;
@@ -57,8 +58,23 @@ const TCP_RX_BUF_LEN: usize = 64 * 1024;
 const TCP_TX_BUF_LEN: usize = 64 * 1024;
 const UDP_RX_BUF_LEN: usize = 64 * 1024;
 const UDP_TX_BUF_LEN: usize = 64 * 1024;
+const RAW_RX_BUF_LEN: usize = 64 * 1024;
+const RAW_TX_BUF_LEN: usize = 64 * 1024;
 const LISTEN_QUEUE_SIZE: usize = 512;
 
+/// Smallest TCP or UDP buffer set by `SO_RCVBUF`/`SO_SNDBUF`.
+const SOCK_BUF_LEN_MIN: usize = 2 * 1024;
+/// Largest TCP or UDP buffer set by `SO_RCVBUF`/`SO_SNDBUF`.
+const SOCK_BUF_LEN_MAX: usize = 4 * 1024 * 1024;
+
+const MSG_OOB: i32 = 0x1;
+const MSG_PEEK: i32 = 0x2;
+const MSG_DONTWAIT: i32 = 0x40;
+const MSG_WAITALL: i32 = 0x100;
+
+const EPHEMERAL_PORT_START: u16 = 0x15b3;
+const EPHEMERAL_PORT_END: u16 = 0xffff;
+
 static LISTEN_TABLE: LazyInit<ListenTable> = LazyInit::new();
 static SOCKET_SET: LazyInit<SocketSetWrapper> = LazyInit::new();
 static ETH0: LazyInit<InterfaceWrapper> = LazyInit::new();
@@ -81,27 +97,34 @@ impl<'a> SocketSetWrapper<'a> {
         Self(Mutex::new(SocketSet::new(vec![])))
     }
 
-    pub fn new_tcp_socket() -> socket::tcp::Socket<'a> {
-        let tcp_rx_buffer = socket::tcp::SocketBuffer::new(vec![0; TCP_RX_BUF_LEN]);
-        let tcp_tx_buffer = socket::tcp::SocketBuffer::new(vec![0; TCP_TX_BUF_LEN]);
+    pub fn new_tcp_socket(rx_len: usize, tx_len: usize) -> socket::tcp::Socket<'a> {
+        let tcp_rx_buffer = socket::tcp::SocketBuffer::new(vec![0; rx_len]);
+        let tcp_tx_buffer = socket::tcp::SocketBuffer::new(vec![0; tx_len]);
         socket::tcp::Socket::new(tcp_rx_buffer, tcp_tx_buffer)
     }
 
-    pub fn new_udp_socket() -> socket::udp::Socket<'a> {
+    pub fn new_udp_socket(rx_len: usize, tx_len: usize) -> socket::udp::Socket<'a> {
         let udp_rx_buffer = socket::udp::PacketBuffer::new(
             vec![socket::udp::PacketMetadata::EMPTY; 8],
-            vec![0; UDP_RX_BUF_LEN],
+            vec![0; rx_len],
         );
         let udp_tx_buffer = socket::udp::PacketBuffer::new(
             vec![socket::udp::PacketMetadata::EMPTY; 8],
-            vec![0; UDP_TX_BUF_LEN],
+            vec![0; tx_len],
         );
         socket::udp::Socket::new(udp_rx_buffer, udp_tx_buffer)
     }
 
-    pub fn new_dns_socket() -> socket::dns::Socket<'a> {
-        let server_addr = DNS_SEVER.parse().expect("invalid DNS server address");
-        socket::dns::Socket::new(&[server_addr], vec![])
+    pub fn new_raw_socket(protocol: IpProtocol) -> socket::raw::Socket<'a> {
+        let raw_rx_buffer = socket::raw::PacketBuffer::new(
+            vec![socket::raw::PacketMetadata::EMPTY; 8],
+            vec![0; RAW_RX_BUF_LEN],
+        );
+        let raw_tx_buffer = socket::raw::PacketBuffer::new(
+            vec![socket::raw::PacketMetadata::EMPTY; 8],
+            vec![0; RAW_TX_BUF_LEN],
+        );
+        socket::raw::Socket::new(IpVersion::Ipv4, protocol, raw_rx_buffer, raw_tx_buffer)
     }
 
     pub fn add<T: AnySocket<'a>>(&self, socket: T) -> SocketHandle {
@@ -128,6 +151,19 @@ impl<'a> SocketSetWrapper<'a> {
         f(socket)
     }
 
+    /// Returns whether a socket of the given protocol is bound to the local
+    /// `port`.
+    pub fn port_in_use(&self, protocol: IpProtocol, port: u16) -> bool {
+        self.0.lock().iter().any(|(_, socket)| match socket {
+            socket::Socket::Tcp(tcp) if protocol == IpProtocol::Tcp => {
+                tcp.listen_endpoint().port == port
+                    || tcp.local_endpoint().is_some_and(|e| e.port == port)
+            }
+            socket::Socket::Udp(udp) if protocol == IpProtocol::Udp => udp.endpoint().port == port,
+            _ => false,
+        })
+    }
+
     pub fn poll_interfaces(&self) {
         ETH0.poll(&self.0);
     }
@@ -172,6 +208,16 @@ impl InterfaceWrapper {
         });
     }
 
+    /// Returns whether `addr` is the limited broadcast address, or the
+    /// broadcast address of a subnet of the interface.
+    pub fn is_broadcast(&self, addr: IpAddress) -> bool {
+        let IpAddress::Ipv4(v4) = addr;
+        v4.is_broadcast()
+            || self.iface.lock().ip_addrs().iter().any(|cidr| match cidr {
+                IpCidr::Ipv4(cidr) => cidr.broadcast() == Some(v4),
+            })
+    }
+
     pub fn setup_gateway(&self, gateway: IpAddress) {
         let mut iface = self.iface.lock();
         match gateway {
@@ -276,6 +322,7 @@ impl<'a> TxToken for AxNetTxToken<'a> {
         let mut dev = self.0.borrow_mut();
         let mut tx_buf = dev.alloc_tx_buffer(len).unwrap();
         let ret = f(tx_buf.packet_mut());
+        snoop_tcp_tx_packet(tx_buf.packet_mut()).ok();
         trace!("SEND {} bytes: {:02X?}", len, tx_buf.packet());
         dev.transmit(tx_buf).unwrap();
         ret
@@ -293,14 +340,73 @@ fn snoop_tcp_packet(buf: &[u8], sockets: &mut SocketSet<'_>) -> Result<(), smolt
         let src_addr = (ipv4_packet.src_addr(), tcp_packet.src_port()).into();
         let dst_addr = (ipv4_packet.dst_addr(), tcp_packet.dst_port()).into();
         let is_first = tcp_packet.syn() && !tcp_packet.ack();
+        let mut accepted = false;
         if is_first {
             // create a socket for the first incoming TCP packet, as the later accept() returns.
-            LISTEN_TABLE.incoming_tcp_packet(src_addr, dst_addr, sockets);
+            accepted = LISTEN_TABLE.incoming_tcp_packet(src_addr, dst_addr, sockets);
         }
+        urgent::snoop_rx(dst_addr, src_addr, &tcp_packet, accepted);
+    }
+    Ok(())
+}
+
+fn snoop_tcp_tx_packet(buf: &mut [u8]) -> Result<(), smoltcp::wire::Error> {
+    use smoltcp::wire::{EthernetFrame, IpProtocol, Ipv4Packet, TcpPacket};
+
+    let mut ether_frame = EthernetFrame::new_checked(buf)?;
+    let mut ipv4_packet = Ipv4Packet::new_checked(ether_frame.payload_mut())?;
+
+    if ipv4_packet.next_header() == IpProtocol::Tcp {
+        let (src_ip, dst_ip) = (ipv4_packet.src_addr(), ipv4_packet.dst_addr());
+        let mut tcp_packet = TcpPacket::new_checked(ipv4_packet.payload_mut())?;
+        let src_addr = (src_ip, tcp_packet.src_port()).into();
+        let dst_addr = (dst_ip, tcp_packet.dst_port()).into();
+        // set the urgent pointer, which smoltcp does not support.
+        urgent::snoop_tx(src_addr, dst_addr, &mut tcp_packet);
     }
     Ok(())
 }
 
+/// Allocates an ephemeral port for the given protocol, which no socket in
+/// [`SOCKET_SET`] is bound to and `is_free` accepts.
+///
+/// Ports are handed out in turn from a random point of the ephemeral range,
+/// so that a port is only reused after going through the whole range.
+fn alloc_ephemeral_port(protocol: IpProtocol, is_free: impl Fn(u16) -> bool) -> AxResult<u16> {
+    const NUM_PORTS: u32 = (EPHEMERAL_PORT_END - EPHEMERAL_PORT_START) as u32 + 1;
+    static CURR: Mutex<Option<u16>> = Mutex::new(None);
+
+    let mut curr = CURR.lock();
+    let start = curr.unwrap_or_else(|| {
+        let seed = current_time_nanos() ^ RANDOM_SEED;
+        let rand = (seed.wrapping_mul(0x5851_F42D_4C95_7F2D) >> 32) as u32;
+        EPHEMERAL_PORT_START + (rand % NUM_PORTS) as u16
+    });
+    for i in 0..NUM_PORTS {
+        let offset = ((start - EPHEMERAL_PORT_START) as u32 + i) % NUM_PORTS;
+        let port = EPHEMERAL_PORT_START + offset as u16;
+        if is_free(port) && !SOCKET_SET.port_in_use(protocol, port) {
+            *curr = Some(if port == EPHEMERAL_PORT_END {
+                EPHEMERAL_PORT_START
+            } else {
+                port + 1
+            });
+            return Ok(port);
+        }
+    }
+    ax_err!(AddrInUse, "no available ephemeral ports")
+}
+
+/// Returns the length of the buffer allocated for a `SO_RCVBUF`/`SO_SNDBUF`
+/// request of `size` bytes.
+///
+/// Like Linux, the size is doubled and clamped, here to
+/// `SOCK_BUF_LEN_MIN..=SOCK_BUF_LEN_MAX`.
+fn sock_buf_len(size: usize) -> usize {
+    size.saturating_mul(2)
+        .clamp(SOCK_BUF_LEN_MIN, SOCK_BUF_LEN_MAX)
+}
+
 /// Poll the network stack.
 ///
 /// It may receive packets from the NIC and process them, and transmit queued
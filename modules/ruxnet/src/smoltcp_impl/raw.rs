@@ -0,0 +1,328 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use core::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use axerrno::{ax_err, ax_err_type, AxError, AxResult};
+use axio::PollState;
+use spin::RwLock;
+
+use smoltcp::iface::SocketHandle;
+use smoltcp::phy::ChecksumCapabilities;
+use smoltcp::socket::raw::{self, SendError};
+use smoltcp::wire::{Icmpv4Packet, IpProtocol, Ipv4Address, Ipv4Packet, Ipv4Repr};
+
+use super::{SocketSetWrapper, ETH0, MSG_DONTWAIT, SOCKET_SET};
+
+/// Length of an IPv4 header without options.
+const IPV4_HEADER_LEN: usize = 20;
+
+/// Hop limit of the IPv4 headers built for the payloads sent.
+const DEFAULT_TTL: u8 = 64;
+
+/// A raw IPv4 socket that provides POSIX-like APIs.
+///
+/// It sends and receives the IPv4 packets of a single protocol, e.g. ICMP.
+/// Like on Linux, the received packets always start with their IPv4 header.
+/// The data sent is the payload of the packet, for which the IPv4 header is
+/// built, unless the header is [included](Self::set_header_included) in it.
+pub struct RawSocket {
+    handle: SocketHandle,
+    protocol: IpProtocol,
+    local_addr: RwLock<Option<Ipv4Address>>,
+    peer_addr: RwLock<Option<Ipv4Address>>,
+    hdrincl: AtomicBool,
+    nonblock: AtomicBool,
+}
+
+impl RawSocket {
+    /// Creates a new raw socket for the given IP protocol number.
+    pub fn new(protocol: u8) -> Self {
+        let protocol = IpProtocol::from(protocol);
+        let socket = SocketSetWrapper::new_raw_socket(protocol);
+        let handle = SOCKET_SET.add(socket);
+        Self {
+            handle,
+            protocol,
+            local_addr: RwLock::new(None),
+            peer_addr: RwLock::new(None),
+            hdrincl: AtomicBool::new(false),
+            nonblock: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the local address, or
+    /// [`Err(NotConnected)`](AxError::NotConnected) if not bound.
+    ///
+    /// Raw sockets have no ports, so the port is always 0.
+    pub fn local_addr(&self) -> AxResult<SocketAddr> {
+        self.local_addr
+            .read()
+            .map(into_core_sockaddr)
+            .ok_or(AxError::NotConnected)
+    }
+
+    /// Returns the remote address, or
+    /// [`Err(NotConnected)`](AxError::NotConnected) if not connected.
+    pub fn peer_addr(&self) -> AxResult<SocketAddr> {
+        self.peer_addr
+            .read()
+            .map(into_core_sockaddr)
+            .ok_or(AxError::NotConnected)
+    }
+
+    /// Returns whether this socket is in nonblocking mode.
+    #[inline]
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblock.load(Ordering::Acquire)
+    }
+
+    /// Moves this raw socket into or out of nonblocking mode.
+    ///
+    /// This will result in `recv`, `recv_from`, `send`, and `send_to`
+    /// operations becoming nonblocking, i.e., immediately returning from their
+    /// calls. If the IO operation could not be completed and needs to be
+    /// retried, an error with kind [`Err(WouldBlock)`](AxError::WouldBlock)
+    /// is returned.
+    #[inline]
+    pub fn set_nonblocking(&self, nonblocking: bool) {
+        self.nonblock.store(nonblocking, Ordering::Release);
+    }
+
+    /// Returns whether the data sent starts with its IPv4 header
+    /// (`IP_HDRINCL`).
+    #[inline]
+    pub fn header_included(&self) -> bool {
+        self.hdrincl.load(Ordering::Acquire)
+    }
+
+    /// Sets whether the data sent starts with its IPv4 header (`IP_HDRINCL`).
+    ///
+    /// When it does, the total length, the header checksum and an unspecified
+    /// source address of the header are filled in, and the rest is sent as
+    /// is. Otherwise the header is built, and the checksum of the ICMP
+    /// messages is computed.
+    #[inline]
+    pub fn set_header_included(&self, included: bool) {
+        self.hdrincl.store(included, Ordering::Release);
+    }
+
+    /// Binds the socket to the given local address, used as the source
+    /// address of the packets sent. The port is ignored.
+    pub fn bind(&self, local_addr: SocketAddr) -> AxResult {
+        let addr = from_core_sockaddr(local_addr)?;
+        if !addr.is_unspecified() && addr != interface_addr() {
+            return ax_err!(
+                AddrNotAvailable,
+                "socket bind() failed: not a local address"
+            );
+        }
+        *self.local_addr.write() = Some(addr);
+        debug!("raw socket {}: bound on {}", self.handle, addr);
+        Ok(())
+    }
+
+    /// Sets the default destination of the packets sent, and only receives
+    /// the packets from it. The port is ignored.
+    pub fn connect(&self, addr: SocketAddr) -> AxResult {
+        let addr = from_core_sockaddr(addr)?;
+        *self.peer_addr.write() = Some(addr);
+        debug!("raw socket {}: connected to {}", self.handle, addr);
+        Ok(())
+    }
+
+    /// Sends a packet to the given address. On success, returns the number of
+    /// bytes written.
+    pub fn send_to(&self, buf: &[u8], remote_addr: SocketAddr) -> AxResult<usize> {
+        let remote_addr = from_core_sockaddr(remote_addr)?;
+        if remote_addr.is_unspecified() {
+            return ax_err!(InvalidInput, "socket send_to() failed: invalid address");
+        }
+        self.send_impl(buf, remote_addr)
+    }
+
+    /// Sends a packet to the remote address to which it is connected.
+    pub fn send(&self, buf: &[u8]) -> AxResult<usize> {
+        let remote_addr = self.peer_addr.read().ok_or(AxError::NotConnected)?;
+        self.send_impl(buf, remote_addr)
+    }
+
+    /// Receives a single packet, including its IPv4 header. On success,
+    /// returns the number of bytes read and the origin.
+    ///
+    /// The packet is truncated if `buf` is too small.
+    pub fn recv_from(&self, buf: &mut [u8]) -> AxResult<(usize, SocketAddr)> {
+        self.recv_from_flags(buf, 0)
+    }
+
+    /// Receives a single packet like [`recv_from`](Self::recv_from), with the
+    /// `flags` of `recvfrom`.
+    ///
+    /// `MSG_DONTWAIT` does not block even if the socket is in blocking mode,
+    /// other flags are ignored.
+    pub fn recv_from_flags(&self, buf: &mut [u8], flags: i32) -> AxResult<(usize, SocketAddr)> {
+        let peer_addr = *self.peer_addr.read();
+        let nonblocking = self.is_nonblocking() || flags & MSG_DONTWAIT != 0;
+        self.block_on(nonblocking, || {
+            SOCKET_SET.with_socket_mut::<raw::Socket, _, _>(self.handle, |socket| {
+                let packet = socket.recv().map_err(|_| AxError::WouldBlock)?;
+                let src_addr = match Ipv4Packet::new_checked(packet) {
+                    Ok(packet) => packet.src_addr(),
+                    Err(_) => return Err(AxError::WouldBlock),
+                };
+                if peer_addr.is_some_and(|peer| peer != src_addr) {
+                    return Err(AxError::WouldBlock);
+                }
+                let len = packet.len().min(buf.len());
+                buf[..len].copy_from_slice(&packet[..len]);
+                Ok((len, into_core_sockaddr(src_addr)))
+            })
+        })
+    }
+
+    /// Receives a single packet from the remote address to which it is
+    /// connected, or from any address if it is not.
+    pub fn recv(&self, buf: &mut [u8]) -> AxResult<usize> {
+        self.recv_from(buf).map(|(len, _)| len)
+    }
+
+    /// Close the socket.
+    pub fn shutdown(&self) -> AxResult {
+        SOCKET_SET.poll_interfaces();
+        Ok(())
+    }
+
+    /// Whether the socket is readable or writable.
+    pub fn poll(&self) -> AxResult<PollState> {
+        SOCKET_SET.poll_interfaces();
+        SOCKET_SET.with_socket_mut::<raw::Socket, _, _>(self.handle, |socket| {
+            Ok(PollState {
+                readable: socket.can_recv(),
+                writable: socket.can_send(),
+                ..Default::default()
+            })
+        })
+    }
+}
+
+/// Private methods
+impl RawSocket {
+    /// Returns the source address of the packets sent.
+    fn source_addr(&self) -> Ipv4Address {
+        match *self.local_addr.read() {
+            Some(addr) if !addr.is_unspecified() => addr,
+            _ => interface_addr(),
+        }
+    }
+
+    fn send_impl(&self, buf: &[u8], remote_addr: Ipv4Address) -> AxResult<usize> {
+        let hdrincl = self.header_included();
+        let len = if hdrincl {
+            let packet = Ipv4Packet::new_checked(buf)
+                .map_err(|_| ax_err_type!(InvalidInput, "socket send() failed: bad header"))?;
+            if packet.next_header() != self.protocol {
+                return ax_err!(InvalidInput, "socket send() failed: protocol mismatch");
+            }
+            buf.len()
+        } else {
+            IPV4_HEADER_LEN + buf.len()
+        };
+        if len > u16::MAX as usize {
+            return ax_err!(InvalidInput, "socket send() failed: message too long");
+        }
+
+        let src_addr = self.source_addr();
+        let sent = self.block_on(self.is_nonblocking(), || {
+            SOCKET_SET.with_socket_mut::<raw::Socket, _, _>(self.handle, |socket| {
+                let packet = socket.send(len).map_err(|e| match e {
+                    SendError::BufferFull => AxError::WouldBlock,
+                })?;
+                if hdrincl {
+                    packet.copy_from_slice(buf);
+                    let mut packet = Ipv4Packet::new_unchecked(packet);
+                    packet.set_total_len(len as u16);
+                    if packet.src_addr().is_unspecified() {
+                        packet.set_src_addr(src_addr);
+                    }
+                    // the header checksum is filled by smoltcp when sending
+                } else {
+                    let repr = Ipv4Repr {
+                        src_addr,
+                        dst_addr: remote_addr,
+                        next_header: self.protocol,
+                        payload_len: buf.len(),
+                        hop_limit: DEFAULT_TTL,
+                    };
+                    let mut packet = Ipv4Packet::new_unchecked(packet);
+                    repr.emit(&mut packet, &ChecksumCapabilities::default());
+                    packet.payload_mut().copy_from_slice(buf);
+                    if self.protocol == IpProtocol::Icmp {
+                        fill_icmp_checksum(packet.payload_mut());
+                    }
+                }
+                Ok(buf.len())
+            })
+        })?;
+        SOCKET_SET.poll_interfaces();
+        Ok(sent)
+    }
+
+    fn block_on<F, T>(&self, nonblocking: bool, mut f: F) -> AxResult<T>
+    where
+        F: FnMut() -> AxResult<T>,
+    {
+        if nonblocking {
+            SOCKET_SET.poll_interfaces();
+            f()
+        } else {
+            loop {
+                SOCKET_SET.poll_interfaces();
+                match f() {
+                    Ok(t) => return Ok(t),
+                    Err(AxError::WouldBlock) => ruxtask::yield_now(),
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+}
+
+impl Drop for RawSocket {
+    fn drop(&mut self) {
+        SOCKET_SET.remove(self.handle);
+    }
+}
+
+/// Computes the checksum of an ICMP message, if it is long enough to have
+/// one.
+fn fill_icmp_checksum(message: &mut [u8]) {
+    if let Ok(mut packet) = Icmpv4Packet::new_checked(message) {
+        packet.fill_checksum();
+    }
+}
+
+/// Returns the IPv4 address of the interface.
+fn interface_addr() -> Ipv4Address {
+    ETH0.iface
+        .lock()
+        .ipv4_addr()
+        .unwrap_or(Ipv4Address::UNSPECIFIED)
+}
+
+fn from_core_sockaddr(addr: SocketAddr) -> AxResult<Ipv4Address> {
+    match addr.ip() {
+        IpAddr::V4(ip) => Ok(Ipv4Address(ip.octets())),
+        IpAddr::V6(_) => ax_err!(InvalidInput, "IPv6 not supported"),
+    }
+}
+
+fn into_core_sockaddr(addr: Ipv4Address) -> SocketAddr {
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::from(addr.0), 0))
+}
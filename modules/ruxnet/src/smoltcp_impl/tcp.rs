@@ -9,7 +9,8 @@
 
 use core::cell::UnsafeCell;
 use core::net::SocketAddr;
-use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use core::time::Duration;
 
 use axerrno::{ax_err, ax_err_type, AxError, AxResult};
 use axio::PollState;
@@ -17,10 +18,12 @@ use axsync::Mutex;
 
 use smoltcp::iface::SocketHandle;
 use smoltcp::socket::tcp::{self, ConnectError, State};
-use smoltcp::wire::{IpEndpoint, IpListenEndpoint};
+use smoltcp::wire::{IpEndpoint, IpListenEndpoint, IpProtocol};
 
 use super::addr::{from_core_sockaddr, into_core_sockaddr, is_unspecified, UNSPECIFIED_ENDPOINT};
-use super::{SocketSetWrapper, ETH0, LISTEN_TABLE, SOCKET_SET};
+use super::urgent::{self, UrgentState};
+use super::{alloc_ephemeral_port, sock_buf_len, SocketSetWrapper, ETH0, LISTEN_TABLE, SOCKET_SET};
+use super::{MSG_DONTWAIT, MSG_OOB, MSG_PEEK, MSG_WAITALL, TCP_RX_BUF_LEN, TCP_TX_BUF_LEN};
 
 // State transitions:
 // CLOSED -(connect)-> BUSY -> CONNECTING -> CONNECTED -(shutdown)-> BUSY -> CLOSED
@@ -34,9 +37,6 @@ const STATE_CONNECTING: u8 = 2;
 const STATE_CONNECTED: u8 = 3;
 const STATE_LISTENING: u8 = 4;
 
-const MSG_PEEK: i32 = 2;
-const MSG_DONTWAIT: i32 = 4;
-
 /// A TCP socket that provides POSIX-like APIs.
 ///
 /// - [`connect`] is for TCP clients.
@@ -47,12 +47,42 @@ const MSG_DONTWAIT: i32 = 4;
 /// [`bind`]: TcpSocket::bind
 /// [`listen`]: TcpSocket::listen
 /// [`accept`]: TcpSocket::accept
+///
+/// # Urgent data
+///
+/// smoltcp has no support for the TCP urgent pointer, so it is emulated as a
+/// 1-byte out-of-band channel, like Linux without `SO_OOBINLINE`:
+///
+/// - [`send_urgent`] sends data whose last byte is urgent.
+/// - [`recv`] stops at the urgent byte, so that [`at_mark`] tells when it is
+///   the next one, then takes it out of the stream.
+/// - [`recv`] with `MSG_OOB` returns the urgent byte, even before the data
+///   preceding it is read.
+///
+/// Only the urgent byte of the latest urgent pointer received is kept. A
+/// `MSG_PEEK` read at the mark does not skip the urgent byte, and urgent
+/// pointers more than 65535 bytes ahead of a segment are not sent.
+///
+/// [`send_urgent`]: TcpSocket::send_urgent
+/// [`recv`]: TcpSocket::recv
+/// [`at_mark`]: TcpSocket::at_mark
 pub struct TcpSocket {
     state: AtomicU8,
     handle: UnsafeCell<Option<SocketHandle>>,
     local_addr: UnsafeCell<IpEndpoint>,
     peer_addr: UnsafeCell<IpEndpoint>,
     nonblock: AtomicBool,
+    reuse_addr: AtomicBool,
+    nodelay: AtomicBool,
+    /// Buffer lengths for the next connection, see `SO_RCVBUF`/`SO_SNDBUF`.
+    recv_buf_len: AtomicUsize,
+    send_buf_len: AtomicUsize,
+    /// Timeout of blocking sends and connects in nanoseconds, 0 for none, see
+    /// `SO_SNDTIMEO`.
+    send_timeout: AtomicU64,
+    /// Error of a failed connection attempt, not reported yet.
+    error: Mutex<Option<AxError>>,
+    urgent: Mutex<UrgentState>,
 }
 
 unsafe impl Sync for TcpSocket {}
@@ -66,6 +96,13 @@ impl TcpSocket {
             local_addr: UnsafeCell::new(UNSPECIFIED_ENDPOINT),
             peer_addr: UnsafeCell::new(UNSPECIFIED_ENDPOINT),
             nonblock: AtomicBool::new(false),
+            reuse_addr: AtomicBool::new(false),
+            nodelay: AtomicBool::new(false),
+            recv_buf_len: AtomicUsize::new(TCP_RX_BUF_LEN),
+            send_buf_len: AtomicUsize::new(TCP_TX_BUF_LEN),
+            send_timeout: AtomicU64::new(0),
+            error: Mutex::new(None),
+            urgent: Mutex::new(UrgentState::new()),
         }
     }
 
@@ -81,6 +118,13 @@ impl TcpSocket {
             local_addr: UnsafeCell::new(local_addr),
             peer_addr: UnsafeCell::new(peer_addr),
             nonblock: AtomicBool::new(false),
+            reuse_addr: AtomicBool::new(false),
+            nodelay: AtomicBool::new(false),
+            recv_buf_len: AtomicUsize::new(TCP_RX_BUF_LEN),
+            send_buf_len: AtomicUsize::new(TCP_TX_BUF_LEN),
+            send_timeout: AtomicU64::new(0),
+            error: Mutex::new(None),
+            urgent: Mutex::new(UrgentState::new()),
         }
     }
 
@@ -127,14 +171,127 @@ impl TcpSocket {
         self.nonblock.store(nonblocking, Ordering::Release);
     }
 
+    /// Returns whether the local address can be reused, as with `SO_REUSEADDR`.
+    #[inline]
+    pub fn is_reuse_addr(&self) -> bool {
+        self.reuse_addr.load(Ordering::Acquire)
+    }
+
+    /// Allows or disallows to [`bind`](Self::bind) to a port still used by
+    /// connections, like `SO_REUSEADDR`, e.g. to restart a server whose old
+    /// connections are not closed yet.
+    ///
+    /// A port with a listening socket can never be reused. A closed socket
+    /// releases its smoltcp socket right away, without lingering in
+    /// `TIME-WAIT`, so a closed listener's port can be bound again at once.
+    #[inline]
+    pub fn set_reuse_addr(&self, reuse_addr: bool) {
+        self.reuse_addr.store(reuse_addr, Ordering::Release);
+    }
+
+    /// Returns whether small segments are sent without delay, as with
+    /// `TCP_NODELAY`.
+    #[inline]
+    pub fn is_nodelay(&self) -> bool {
+        self.nodelay.load(Ordering::Acquire)
+    }
+
+    /// Disables or enables the Nagle algorithm, like `TCP_NODELAY`.
+    ///
+    /// If set, the data is sent as soon as possible, even in small segments
+    /// while previous data is not acknowledged.
+    #[inline]
+    pub fn set_nodelay(&self, nodelay: bool) {
+        self.nodelay.store(nodelay, Ordering::Release);
+    }
+
+    /// Returns the size of the receive buffer, as with `SO_RCVBUF`.
+    pub fn recv_buffer_size(&self) -> usize {
+        match self.connected_handle() {
+            Some(handle) => {
+                SOCKET_SET.with_socket::<tcp::Socket, _, _>(handle, |socket| socket.recv_capacity())
+            }
+            None => self.recv_buf_len.load(Ordering::Acquire),
+        }
+    }
+
+    /// Sets the size of the receive buffer, like `SO_RCVBUF`.
+    ///
+    /// Like Linux, `size` is doubled and clamped. The buffer is allocated by
+    /// [`connect`](Self::connect), or for each connection accepted after
+    /// [`listen`](Self::listen), so the buffer of a connection already
+    /// established is not resized.
+    pub fn set_recv_buffer_size(&self, size: usize) {
+        self.recv_buf_len
+            .store(sock_buf_len(size), Ordering::Release);
+    }
+
+    /// Returns the size of the send buffer, as with `SO_SNDBUF`.
+    pub fn send_buffer_size(&self) -> usize {
+        match self.connected_handle() {
+            Some(handle) => {
+                SOCKET_SET.with_socket::<tcp::Socket, _, _>(handle, |socket| socket.send_capacity())
+            }
+            None => self.send_buf_len.load(Ordering::Acquire),
+        }
+    }
+
+    /// Sets the size of the send buffer, like `SO_SNDBUF`.
+    ///
+    /// The same rules as [`set_recv_buffer_size`](Self::set_recv_buffer_size)
+    /// apply.
+    pub fn set_send_buffer_size(&self, size: usize) {
+        self.send_buf_len
+            .store(sock_buf_len(size), Ordering::Release);
+    }
+
+    /// Returns the timeout of blocking sends and connects, as with
+    /// `SO_SNDTIMEO`.
+    pub fn send_timeout(&self) -> Option<Duration> {
+        match self.send_timeout.load(Ordering::Acquire) {
+            0 => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
+    }
+
+    /// Sets the timeout of blocking sends and connects, like `SO_SNDTIMEO`.
+    ///
+    /// A [`send`](Self::send) which times out fails with
+    /// [`WouldBlock`](AxError::WouldBlock) if nothing could be sent, and a
+    /// [`connect`](Self::connect) with [`TimedOut`](AxError::TimedOut). `None`
+    /// or a zero duration waits forever.
+    pub fn set_send_timeout(&self, timeout: Option<Duration>) {
+        let nanos = timeout.map_or(0, |t| t.as_nanos().min(u64::MAX as u128) as u64);
+        self.send_timeout.store(nanos, Ordering::Release);
+    }
+
+    /// Returns and clears the pending error of the socket, like `SO_ERROR`.
+    ///
+    /// An error is pending when a connection attempt fails in the background,
+    /// e.g. after a nonblocking [`connect`](Self::connect).
+    pub fn take_error(&self) -> Option<AxError> {
+        self.error.lock().take()
+    }
+
     /// Connects to the given address and port.
     ///
     /// The local port is generated automatically.
+    ///
+    /// In nonblocking mode, it fails with [`InProgress`](AxError::InProgress)
+    /// instead of waiting for the connection. The socket becomes writable when
+    /// the attempt completes, then [`take_error`](Self::take_error) tells
+    /// whether it failed. Otherwise, it fails with
+    /// [`TimedOut`](AxError::TimedOut) if the connection is not established
+    /// within the [`send_timeout`](Self::send_timeout), if any.
     pub fn connect(&self, remote_addr: SocketAddr) -> AxResult {
         self.update_state(STATE_CLOSED, STATE_CONNECTING, || {
             // SAFETY: no other threads can read or write these fields.
-            let handle = unsafe { self.handle.get().read() }
-                .unwrap_or_else(|| SOCKET_SET.add(SocketSetWrapper::new_tcp_socket()));
+            let handle = unsafe { self.handle.get().read() }.unwrap_or_else(|| {
+                SOCKET_SET.add(SocketSetWrapper::new_tcp_socket(
+                    self.recv_buf_len.load(Ordering::Acquire),
+                    self.send_buf_len.load(Ordering::Acquire),
+                ))
+            });
 
             // TODO: check remote addr unreachable
             let remote_endpoint = from_core_sockaddr(remote_addr);
@@ -168,7 +325,8 @@ impl TcpSocket {
         })
         .unwrap_or_else(|_| ax_err!(AlreadyExists, "socket connect() failed: already connected"))?; // EISCONN
 
-        self.block_on(|| {
+        let deadline = self.send_deadline();
+        let res = self.block_on_until(self.is_nonblocking(), deadline, || {
             let PollState { writable, .. } = self.poll_connect()?;
             if !writable {
                 // When set to non_blocking, directly return inporgress
@@ -183,32 +341,44 @@ impl TcpSocket {
                 if self.is_nonblocking() {
                     return Err(AxError::InProgress);
                 }
+                self.take_error(); // reported right now
                 ax_err!(ConnectionRefused, "socket connect() failed")
             }
-        })
+        });
+        if res == Err(AxError::WouldBlock) {
+            // timed out, give up the handshake
+            self.abort_connect();
+            return ax_err!(TimedOut, "socket connect() failed");
+        }
+        res
     }
 
     /// Binds an unbound socket to the given address and port.
     ///
     /// If the given port is 0, it generates one automatically.
     ///
+    /// It fails with [`AddrInUse`](AxError::AddrInUse) if the port is listened
+    /// on, or used by other connections unless
+    /// [`set_reuse_addr`](Self::set_reuse_addr) is set.
+    ///
     /// It's must be called before [`listen`](Self::listen) and
     /// [`accept`](Self::accept).
     pub fn bind(&self, mut local_addr: SocketAddr) -> AxResult {
         self.update_state(STATE_CLOSED, STATE_BUSY, || {
-            // TODO: check addr is available
-            if local_addr.port() == 0 {
-                local_addr.set_port(get_ephemeral_port()?);
-            }
             // SAFETY: no other threads can read or write `self.local_addr` as we
             // have changed the state to `BUSY`.
-            unsafe {
-                let old = self.local_addr.get().read();
-                if old != UNSPECIFIED_ENDPOINT {
-                    return ax_err!(InvalidInput, "socket bind() failed: already bound");
-                }
-                self.local_addr.get().write(from_core_sockaddr(local_addr));
+            if unsafe { self.local_addr.get().read() } != UNSPECIFIED_ENDPOINT {
+                return ax_err!(InvalidInput, "socket bind() failed: already bound");
             }
+            let port = local_addr.port();
+            if port == 0 {
+                local_addr.set_port(get_ephemeral_port()?);
+            } else if !LISTEN_TABLE.can_listen(port)
+                || (!self.is_reuse_addr() && SOCKET_SET.port_in_use(IpProtocol::Tcp, port))
+            {
+                return ax_err!(AddrInUse, "socket bind() failed: address in use");
+            }
+            unsafe { self.local_addr.get().write(from_core_sockaddr(local_addr)) };
             Ok(())
         })
         .unwrap_or_else(|_| ax_err!(InvalidInput, "socket bind() failed: already bound"))
@@ -216,25 +386,34 @@ impl TcpSocket {
 
     /// Starts listening on the bound address and port.
     ///
+    /// At most `backlog` connections are queued until they are accepted, the
+    /// others are refused.
+    ///
     /// It's must be called after [`bind`](Self::bind) and before
     /// [`accept`](Self::accept).
-    pub fn listen(&self) -> AxResult {
+    pub fn listen(&self, backlog: usize) -> AxResult {
         self.update_state(STATE_BUSY, STATE_LISTENING, || {
             let bound_endpoint = self.bound_endpoint()?;
             unsafe {
                 (*self.local_addr.get()).port = bound_endpoint.port;
             }
-            LISTEN_TABLE.listen(bound_endpoint)?;
+            LISTEN_TABLE.listen(
+                bound_endpoint,
+                backlog,
+                self.recv_buf_len.load(Ordering::Acquire),
+                self.send_buf_len.load(Ordering::Acquire),
+            )?;
             debug!("TCP socket listening on {}", bound_endpoint);
             Ok(())
         })
         .unwrap_or(Ok(())) // ignore simultaneous `listen`s.
     }
 
-    /// Accepts a new connection.
+    /// Accepts a new connection, the oldest established one in the backlog.
     ///
     /// This function will block the calling thread until a new TCP connection
-    /// is established. When established, a new [`TcpSocket`] is returned.
+    /// is established, or returns [`AxError::WouldBlock`] in non-blocking mode.
+    /// When established, a new [`TcpSocket`] is returned.
     ///
     /// It's must be called after [`bind`](Self::bind) and [`listen`](Self::listen).
     pub fn accept(&self) -> AxResult<TcpSocket> {
@@ -244,7 +423,7 @@ impl TcpSocket {
 
         // SAFETY: `self.local_addr` should be initialized after `bind()`.
         let local_port = unsafe { self.local_addr.get().read().port };
-        self.block_on(|| {
+        self.block_on(self.is_nonblocking(), || {
             let (handle, (local_addr, peer_addr)) = LISTEN_TABLE.accept(local_port)?;
             debug!("TCP socket accepted a new connection {}", peer_addr);
             Ok(TcpSocket::new_connected(handle, local_addr, peer_addr))
@@ -262,6 +441,8 @@ impl TcpSocket {
                 debug!("TCP socket {}: shutting down", handle);
                 socket.close();
             });
+            let (local_addr, peer_addr) = self.endpoints();
+            urgent::remove(local_addr, peer_addr);
             unsafe { self.local_addr.get().write(UNSPECIFIED_ENDPOINT) }; // clear bound address
             SOCKET_SET.poll_interfaces();
             Ok(())
@@ -284,7 +465,64 @@ impl TcpSocket {
         Ok(())
     }
 
+    /// Aborts the connection or stops listening, and returns the socket to the
+    /// unbound and unconnected state, so that it can [`connect`](Self::connect)
+    /// or [`bind`](Self::bind) again.
+    ///
+    /// Unlike [`shutdown`](Self::shutdown), pending data in both directions is
+    /// discarded and the underlying smoltcp socket is released. Other settings,
+    /// such as the nonblocking mode and [`set_reuse_addr`](Self::set_reuse_addr),
+    /// are kept.
+    pub fn reset(&self) -> AxResult {
+        let state = self.get_state();
+        if state == STATE_BUSY {
+            return ax_err!(WouldBlock, "socket reset() failed: busy");
+        }
+        self.update_state(state, STATE_CLOSED, || {
+            if state == STATE_LISTENING {
+                // SAFETY: `self.local_addr` should be initialized in a listening socket,
+                // and no other threads can read or write it.
+                let local_port = unsafe { self.local_addr.get().read().port };
+                LISTEN_TABLE.unlisten(local_port);
+            }
+            // SAFETY: no other threads can read or write these fields as we
+            // have changed the state to `BUSY`.
+            if let Some(handle) = unsafe { self.handle.get().replace(None) } {
+                SOCKET_SET.with_socket_mut::<tcp::Socket, _, _>(handle, |socket| {
+                    debug!("TCP socket {}: reset", handle);
+                    socket.abort();
+                });
+                SOCKET_SET.poll_interfaces(); // send the RST
+                SOCKET_SET.remove(handle);
+            }
+            let (local_addr, peer_addr) = self.endpoints();
+            urgent::remove(local_addr, peer_addr);
+            *self.urgent.lock() = UrgentState::new();
+            unsafe {
+                self.local_addr.get().write(UNSPECIFIED_ENDPOINT);
+                self.peer_addr.get().write(UNSPECIFIED_ENDPOINT);
+            }
+            Ok(())
+        })
+        .unwrap_or_else(|_| ax_err!(WouldBlock, "socket reset() failed: busy"))
+    }
+
     /// Receives data from the socket, stores it in the given buffer.
+    ///
+    /// With `MSG_OOB`, it receives the urgent byte instead. It fails with
+    /// [`InvalidInput`](AxError::InvalidInput) if there is no urgent byte to
+    /// read, or with [`WouldBlock`](AxError::WouldBlock) if it has not arrived
+    /// yet.
+    ///
+    /// Other `flags` are those of `recv`:
+    ///
+    /// - `MSG_PEEK` leaves the data in the receive queue.
+    /// - `MSG_DONTWAIT` does not block, even if the socket is in blocking mode.
+    /// - `MSG_WAITALL` blocks until `buf` is full, unless the connection is
+    ///   closed or fails first. It is ignored with `MSG_PEEK` or in
+    ///   nonblocking mode.
+    ///
+    /// The other flags are ignored.
     pub fn recv(&self, buf: &mut [u8], flags: i32) -> AxResult<usize> {
         if self.is_connecting() {
             return Err(AxError::WouldBlock);
@@ -294,42 +532,39 @@ impl TcpSocket {
 
         // SAFETY: `self.handle` should be initialized in a connected socket.
         let handle = unsafe { self.handle.get().read().unwrap() };
-        self.block_on(|| {
-            SOCKET_SET.with_socket_mut::<tcp::Socket, _, _>(handle, |socket| {
-                if !socket.is_active() {
-                    // not open
-                    ax_err!(ConnectionRefused, "socket recv() failed")
-                } else if !socket.may_recv() {
-                    // connection closed
-                    Ok(0)
-                } else if socket.recv_queue() > 0 {
-                    // data available
-                    // TODO: use socket.recv(|buf| {...})
-                    if flags & MSG_DONTWAIT != 0 {
-                        self.set_nonblocking(true);
-                    }
-                    if flags & MSG_PEEK != 0 {
-                        let len = socket
-                            .peek_slice(buf)
-                            .map_err(|_| ax_err_type!(BadState, "socket recv() failed"))?;
-                        Ok(len)
-                    } else {
-                        let len = socket
-                            .recv_slice(buf)
-                            .map_err(|_| ax_err_type!(BadState, "socket recv() failed"))?;
-                        Ok(len)
-                    }
-                } else {
-                    // no more data
-                    Err(AxError::WouldBlock)
-                }
-            })
-        })
+        if flags & MSG_OOB != 0 {
+            return self.recv_urgent(handle, buf);
+        }
+        let nonblocking = self.is_nonblocking() || flags & MSG_DONTWAIT != 0;
+        let peek = flags & MSG_PEEK != 0;
+        if flags & MSG_WAITALL == 0 || nonblocking || peek {
+            return self.recv_some(handle, buf, nonblocking, peek);
+        }
+
+        let mut len = 0;
+        while len < buf.len() {
+            match self.recv_some(handle, &mut buf[len..], false, false) {
+                Ok(0) => break, // connection closed
+                Ok(n) => len += n,
+                Err(_) if len > 0 => break, // report the data received first
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(len)
     }
 
     /// Transmits data in the given buffer.
     /// TODO: impl send flags
     pub fn send(&self, buf: &[u8]) -> AxResult<usize> {
+        self.send_vectored(&[buf])
+    }
+
+    /// Transmits data in the given buffers, one after another.
+    ///
+    /// The buffers are queued together, so that they can go out in a single
+    /// segment. As much as fits in the tx buffer is queued, and the number of
+    /// bytes queued is returned.
+    pub fn send_vectored(&self, bufs: &[&[u8]]) -> AxResult<usize> {
         if self.is_connecting() {
             return Err(AxError::WouldBlock);
         } else if !self.is_connected() {
@@ -338,7 +573,8 @@ impl TcpSocket {
 
         // SAFETY: `self.handle` should be initialized in a connected socket.
         let handle = unsafe { self.handle.get().read().unwrap() };
-        self.block_on(|| {
+        let deadline = self.send_deadline();
+        self.block_on_until(self.is_nonblocking(), deadline, || {
             SOCKET_SET.with_socket_mut::<tcp::Socket, _, _>(handle, |socket| {
                 if !socket.is_active() || !socket.may_send() {
                     // closed by remote
@@ -346,9 +582,20 @@ impl TcpSocket {
                 } else if socket.can_send() {
                     // connected, and the tx buffer is not full
                     // TODO: use socket.send(|buf| {...})
-                    let len = socket
-                        .send_slice(buf)
-                        .map_err(|_| ax_err_type!(BadState, "socket send() failed"))?;
+                    socket.set_nagle_enabled(!self.is_nodelay());
+                    let mut len = 0;
+                    for buf in bufs {
+                        let n = socket
+                            .send_slice(buf)
+                            .map_err(|_| ax_err_type!(BadState, "socket send() failed"))?;
+                        len += n;
+                        if n < buf.len() {
+                            // tx buffer is full
+                            break;
+                        }
+                    }
+                    let mut urgent = self.urgent.lock();
+                    urgent.send_offset = urgent.send_offset.wrapping_add(len as u32);
                     Ok(len)
                 } else {
                     // tx buffer is full
@@ -358,16 +605,45 @@ impl TcpSocket {
         })
     }
 
+    /// Transmits data in the given buffer, and marks its last byte sent as
+    /// urgent, like `send` with `MSG_OOB`.
+    pub fn send_urgent(&self, buf: &[u8]) -> AxResult<usize> {
+        let len = self.send(buf)?;
+        if len > 0 {
+            let (local_addr, peer_addr) = self.endpoints();
+            urgent::set_tx_mark(local_addr, peer_addr, self.urgent.lock().send_offset);
+            SOCKET_SET.poll_interfaces();
+        }
+        Ok(len)
+    }
+
+    /// Whether the next byte to receive is the urgent byte, the one of the
+    /// latest urgent pointer received.
+    pub fn at_mark(&self) -> AxResult<bool> {
+        if !self.is_connected() {
+            return Ok(false);
+        }
+        let (local_addr, peer_addr) = self.endpoints();
+        let mark = urgent::rx_mark(local_addr, peer_addr);
+        Ok(self.urgent.lock().distance_to(mark) == Some(0))
+    }
+
     /// Whether the socket is readable or writable.
     pub fn poll(&self) -> AxResult<PollState> {
         match self.get_state() {
             STATE_CONNECTING => self.poll_connect(),
             STATE_CONNECTED => self.poll_stream(),
             STATE_LISTENING => self.poll_listener(),
-            _ => Ok(PollState {
-                readable: false,
-                writable: false,
-            }),
+            // a failed connection attempt stays ready until its error is taken
+            _ => {
+                let failed = self.error.lock().is_some();
+                Ok(PollState {
+                    readable: failed,
+                    writable: failed,
+                    hangup: failed,
+                    error: failed,
+                })
+            }
         }
     }
 }
@@ -428,6 +704,121 @@ impl TcpSocket {
         self.get_state() == STATE_LISTENING
     }
 
+    /// Returns the handle of the smoltcp socket if connecting or connected.
+    fn connected_handle(&self) -> Option<SocketHandle> {
+        match self.get_state() {
+            // SAFETY: the handle is only written when the state is `BUSY`.
+            STATE_CONNECTING | STATE_CONNECTED => unsafe { self.handle.get().read() },
+            _ => None,
+        }
+    }
+
+    /// Returns the local and remote endpoints of the connection.
+    fn endpoints(&self) -> (IpEndpoint, IpEndpoint) {
+        // SAFETY: the addresses are only written when the state is `BUSY`.
+        unsafe { (self.local_addr.get().read(), self.peer_addr.get().read()) }
+    }
+
+    /// Receives the data available, waiting for some unless `nonblocking`.
+    fn recv_some(
+        &self,
+        handle: SocketHandle,
+        buf: &mut [u8],
+        nonblocking: bool,
+        peek: bool,
+    ) -> AxResult<usize> {
+        self.block_on(nonblocking, || {
+            SOCKET_SET.with_socket_mut::<tcp::Socket, _, _>(handle, |socket| {
+                if !socket.is_active() {
+                    // not open
+                    ax_err!(ConnectionRefused, "socket recv() failed")
+                } else if !socket.may_recv() {
+                    // connection closed
+                    Ok(0)
+                } else if socket.recv_queue() > 0 {
+                    // data available
+                    // TODO: use socket.recv(|buf| {...})
+                    self.recv_stream(socket, buf, peek)
+                } else {
+                    // no more data
+                    Err(AxError::WouldBlock)
+                }
+            })
+        })
+    }
+
+    /// Receives data up to the urgent byte, and takes the urgent byte out of
+    /// the stream when it is the next one.
+    fn recv_stream(&self, socket: &mut tcp::Socket, buf: &mut [u8], peek: bool) -> AxResult<usize> {
+        let (local_addr, peer_addr) = self.endpoints();
+        let mark = urgent::rx_mark(local_addr, peer_addr);
+        let mut urgent = self.urgent.lock();
+        let mut len = buf.len();
+        match urgent.distance_to(mark) {
+            Some(0) if !peek => {
+                let mut byte = 0;
+                socket
+                    .recv_slice(core::slice::from_mut(&mut byte))
+                    .map_err(|_| ax_err_type!(BadState, "socket recv() failed"))?;
+                urgent.recv_offset = urgent.recv_offset.wrapping_add(1);
+                if urgent.read_mark != mark {
+                    urgent.read_mark = mark;
+                    urgent.pending = Some(byte);
+                }
+                if socket.recv_queue() == 0 {
+                    return Err(AxError::WouldBlock);
+                }
+            }
+            // stop at the mark, so that `at_mark` tells when it is reached.
+            Some(dist) if dist > 0 => len = len.min(dist),
+            _ => {}
+        }
+        let buf = &mut buf[..len];
+        let len = if peek {
+            socket.peek_slice(buf)
+        } else {
+            socket.recv_slice(buf)
+        }
+        .map_err(|_| ax_err_type!(BadState, "socket recv() failed"))?;
+        if !peek {
+            urgent.recv_offset = urgent.recv_offset.wrapping_add(len as u32);
+        }
+        Ok(len)
+    }
+
+    /// Receives the urgent byte, without waiting for it.
+    fn recv_urgent(&self, handle: SocketHandle, buf: &mut [u8]) -> AxResult<usize> {
+        let (local_addr, peer_addr) = self.endpoints();
+        let mark = urgent::rx_mark(local_addr, peer_addr);
+        let mut urgent = self.urgent.lock();
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if let Some(byte) = urgent.pending.take() {
+            buf[0] = byte;
+            return Ok(1);
+        }
+        let dist = match urgent.distance_to(mark) {
+            Some(dist) if urgent.read_mark != mark => dist,
+            _ => return ax_err!(InvalidInput, "socket recv() failed: no urgent data"),
+        };
+        SOCKET_SET.with_socket_mut::<tcp::Socket, _, _>(handle, |socket| {
+            if socket.recv_queue() <= dist {
+                return Err(AxError::WouldBlock);
+            }
+            let mut data = alloc::vec![0; dist + 1];
+            let len = socket
+                .peek_slice(&mut data)
+                .map_err(|_| ax_err_type!(BadState, "socket recv() failed"))?;
+            if len <= dist {
+                return Err(AxError::WouldBlock);
+            }
+            buf[0] = data[dist];
+            urgent.read_mark = mark;
+            Ok(1)
+        })
+    }
+
     fn bound_endpoint(&self) -> AxResult<IpListenEndpoint> {
         // SAFETY: no other threads can read or write `self.local_addr`.
         let local_addr = unsafe { self.local_addr.get().read() };
@@ -461,17 +852,23 @@ impl TcpSocket {
                     true
                 }
                 _ => {
+                    let (local_addr, peer_addr) = self.endpoints();
+                    urgent::remove(local_addr, peer_addr);
                     unsafe {
                         self.local_addr.get().write(UNSPECIFIED_ENDPOINT);
                         self.peer_addr.get().write(UNSPECIFIED_ENDPOINT);
                     }
                     self.set_state(STATE_CLOSED); // connection failed
+                    *self.error.lock() = Some(AxError::ConnectionRefused);
                     true
                 }
             });
+        let failed = self.error.lock().is_some();
         Ok(PollState {
             readable: false,
             writable,
+            hangup: failed,
+            error: failed,
         })
     }
 
@@ -482,6 +879,9 @@ impl TcpSocket {
             Ok(PollState {
                 readable: !socket.may_recv() || socket.can_recv(),
                 writable: !socket.may_send() || socket.can_send(),
+                // both directions are shut down, or the connection was reset
+                hangup: !socket.may_recv() && !socket.may_send(),
+                error: false,
             })
         })
     }
@@ -492,25 +892,65 @@ impl TcpSocket {
         Ok(PollState {
             readable: LISTEN_TABLE.can_accept(local_addr.port)?,
             writable: false,
+            ..Default::default()
         })
     }
 
+    /// Returns when a blocking send or connect started now times out.
+    fn send_deadline(&self) -> Option<Duration> {
+        self.send_timeout()
+            .map(|timeout| ruxhal::time::current_time() + timeout)
+    }
+
+    /// Gives up a connection attempt in progress, without reporting an error.
+    fn abort_connect(&self) {
+        // SAFETY: `self.handle` should be initialized above.
+        let handle = unsafe { self.handle.get().read().unwrap() };
+        SOCKET_SET.with_socket_mut::<tcp::Socket, _, _>(handle, |socket| socket.abort());
+        let (local_addr, peer_addr) = self.endpoints();
+        urgent::remove(local_addr, peer_addr);
+        unsafe {
+            self.local_addr.get().write(UNSPECIFIED_ENDPOINT);
+            self.peer_addr.get().write(UNSPECIFIED_ENDPOINT);
+        }
+        self.set_state(STATE_CLOSED);
+    }
+
     /// Block the current thread until the given function completes or fails.
     ///
-    /// If the socket is non-blocking, it calls the function once and returns
+    /// If `nonblocking` is set, it calls the function once and returns
     /// immediately. Otherwise, it may call the function multiple times if it
     /// returns [`Err(WouldBlock)`](AxError::WouldBlock).
-    fn block_on<F, T>(&self, mut f: F) -> AxResult<T>
+    fn block_on<F, T>(&self, nonblocking: bool, f: F) -> AxResult<T>
+    where
+        F: FnMut() -> AxResult<T>,
+    {
+        self.block_on_until(nonblocking, None, f)
+    }
+
+    /// Like [`block_on`](Self::block_on), but gives up with
+    /// [`Err(WouldBlock)`](AxError::WouldBlock) once `deadline` is passed.
+    fn block_on_until<F, T>(
+        &self,
+        nonblocking: bool,
+        deadline: Option<Duration>,
+        mut f: F,
+    ) -> AxResult<T>
     where
         F: FnMut() -> AxResult<T>,
     {
-        if self.is_nonblocking() {
+        if nonblocking {
             f()
         } else {
             loop {
                 SOCKET_SET.poll_interfaces();
                 match f() {
                     Ok(t) => return Ok(t),
+                    Err(AxError::WouldBlock)
+                        if deadline.is_some_and(|d| ruxhal::time::current_time() >= d) =>
+                    {
+                        return Err(AxError::WouldBlock);
+                    }
                     Err(AxError::WouldBlock) => ruxtask::yield_now(),
                     Err(e) => return Err(e),
                 }
@@ -522,6 +962,9 @@ impl TcpSocket {
 impl Drop for TcpSocket {
     fn drop(&mut self) {
         self.shutdown().ok();
+        // a connection still in progress is not shut down
+        let (local_addr, peer_addr) = self.endpoints();
+        urgent::remove(local_addr, peer_addr);
         // Safe because we have mut reference to `self`.
         if let Some(handle) = unsafe { self.handle.get().read() } {
             SOCKET_SET.remove(handle);
@@ -546,24 +989,6 @@ impl axio::Write for TcpSocket {
 }
 
 fn get_ephemeral_port() -> AxResult<u16> {
-    const PORT_START: u16 = 0x15b3;
-    const PORT_END: u16 = 0xffff;
-    static CURR: Mutex<u16> = Mutex::new(PORT_START);
-
-    let mut curr = CURR.lock();
-    let mut tries = 0;
-    // TODO: more robust
-    while tries <= PORT_END - PORT_START {
-        let port = *curr;
-        if *curr == PORT_END {
-            *curr = PORT_START;
-        } else {
-            *curr += 1;
-        }
-        if LISTEN_TABLE.can_listen(port) {
-            return Ok(port);
-        }
-        tries += 1;
-    }
-    ax_err!(AddrInUse, "no avaliable ports!")
+    // skip the ports listened on, which have no socket until a SYN arrives.
+    alloc_ephemeral_port(IpProtocol::Tcp, |port| LISTEN_TABLE.can_listen(port))
 }
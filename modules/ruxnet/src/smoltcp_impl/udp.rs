@@ -12,15 +12,15 @@ use core::sync::atomic::{AtomicBool, Ordering};
 
 use axerrno::{ax_err, ax_err_type, AxError, AxResult};
 use axio::PollState;
-use axsync::Mutex;
 use spin::RwLock;
 
 use smoltcp::iface::SocketHandle;
 use smoltcp::socket::udp::{self, BindError, SendError};
-use smoltcp::wire::{IpEndpoint, IpListenEndpoint};
+use smoltcp::wire::{IpEndpoint, IpListenEndpoint, IpProtocol};
 
 use super::addr::{from_core_sockaddr, into_core_sockaddr, is_unspecified, UNSPECIFIED_ENDPOINT};
-use super::{SocketSetWrapper, SOCKET_SET};
+use super::{alloc_ephemeral_port, sock_buf_len, SocketSetWrapper, ETH0, SOCKET_SET};
+use super::{MSG_DONTWAIT, MSG_PEEK, UDP_RX_BUF_LEN, UDP_TX_BUF_LEN};
 
 /// A UDP socket that provides POSIX-like APIs.
 pub struct UdpSocket {
@@ -28,19 +28,21 @@ pub struct UdpSocket {
     local_addr: RwLock<Option<IpEndpoint>>,
     peer_addr: RwLock<Option<IpEndpoint>>,
     nonblock: AtomicBool,
+    broadcast: AtomicBool,
 }
 
 impl UdpSocket {
     /// Creates a new UDP socket.
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        let socket = SocketSetWrapper::new_udp_socket();
+        let socket = SocketSetWrapper::new_udp_socket(UDP_RX_BUF_LEN, UDP_TX_BUF_LEN);
         let handle = SOCKET_SET.add(socket);
         Self {
             handle,
             local_addr: RwLock::new(None),
             peer_addr: RwLock::new(None),
             nonblock: AtomicBool::new(false),
+            broadcast: AtomicBool::new(false),
         }
     }
 
@@ -78,6 +80,51 @@ impl UdpSocket {
         self.nonblock.store(nonblocking, Ordering::Release);
     }
 
+    /// Returns whether sending to a broadcast address is allowed, as with
+    /// `SO_BROADCAST`.
+    #[inline]
+    pub fn is_broadcast(&self) -> bool {
+        self.broadcast.load(Ordering::Acquire)
+    }
+
+    /// Allows or forbids sending to a broadcast address, like `SO_BROADCAST`.
+    ///
+    /// If forbidden, which is the default, sending to the limited broadcast
+    /// address or to the broadcast address of the subnet fails with
+    /// [`Err(PermissionDenied)`](AxError::PermissionDenied).
+    #[inline]
+    pub fn set_broadcast(&self, broadcast: bool) {
+        self.broadcast.store(broadcast, Ordering::Release);
+    }
+
+    /// Returns the size of the receive buffer, as with `SO_RCVBUF`.
+    pub fn recv_buffer_size(&self) -> usize {
+        SOCKET_SET
+            .with_socket::<udp::Socket, _, _>(self.handle, |socket| socket.payload_recv_capacity())
+    }
+
+    /// Sets the size of the receive buffer, like `SO_RCVBUF`.
+    ///
+    /// Like Linux, `size` is doubled and clamped. The buffer is reallocated
+    /// only while the socket is not bound, it is kept as is afterwards.
+    pub fn set_recv_buffer_size(&self, size: usize) {
+        self.resize_buffers(|socket| (sock_buf_len(size), socket.payload_send_capacity()));
+    }
+
+    /// Returns the size of the send buffer, as with `SO_SNDBUF`.
+    pub fn send_buffer_size(&self) -> usize {
+        SOCKET_SET
+            .with_socket::<udp::Socket, _, _>(self.handle, |socket| socket.payload_send_capacity())
+    }
+
+    /// Sets the size of the send buffer, like `SO_SNDBUF`.
+    ///
+    /// The same rules as [`set_recv_buffer_size`](Self::set_recv_buffer_size)
+    /// apply.
+    pub fn set_send_buffer_size(&self, size: usize) {
+        self.resize_buffers(|socket| (socket.payload_recv_capacity(), sock_buf_len(size)));
+    }
+
     /// Binds an unbound socket to the given address and port.
     ///
     /// It's must be called before [`send_to`](Self::send_to) and
@@ -121,18 +168,36 @@ impl UdpSocket {
     /// Receives a single datagram message on the socket. On success, returns
     /// the number of bytes read and the origin.
     pub fn recv_from(&self, buf: &mut [u8]) -> AxResult<(usize, SocketAddr)> {
-        self.recv_impl(|socket| match socket.recv_slice(buf) {
-            Ok((len, meta)) => Ok((len, into_core_sockaddr(meta.endpoint))),
-            Err(_) => ax_err!(BadState, "socket recv_from() failed"),
-        })
+        self.recv_from_flags(buf, 0)
     }
 
     /// Receives a single datagram message on the socket, without removing it from
     /// the queue. On success, returns the number of bytes read and the origin.
     pub fn peek_from(&self, buf: &mut [u8]) -> AxResult<(usize, SocketAddr)> {
-        self.recv_impl(|socket| match socket.peek_slice(buf) {
-            Ok((len, meta)) => Ok((len, into_core_sockaddr(meta.endpoint))),
-            Err(_) => ax_err!(BadState, "socket recv_from() failed"),
+        self.recv_from_flags(buf, MSG_PEEK)
+    }
+
+    /// Receives a single datagram message on the socket, like `recvfrom` with
+    /// `flags`. On success, returns the number of bytes read and the origin.
+    ///
+    /// `MSG_PEEK` leaves the datagram in the queue, and `MSG_DONTWAIT` does not
+    /// block even if the socket is in blocking mode. Other flags are ignored.
+    pub fn recv_from_flags(&self, buf: &mut [u8], flags: i32) -> AxResult<(usize, SocketAddr)> {
+        let nonblocking = self.is_nonblocking() || flags & MSG_DONTWAIT != 0;
+        self.recv_impl(nonblocking, |socket| {
+            let res = if flags & MSG_PEEK != 0 {
+                socket
+                    .peek_slice(buf)
+                    .map(|(len, meta)| (len, meta.endpoint))
+            } else {
+                socket
+                    .recv_slice(buf)
+                    .map(|(len, meta)| (len, meta.endpoint))
+            };
+            match res {
+                Ok((len, endpoint)) => Ok((len, into_core_sockaddr(endpoint))),
+                Err(_) => ax_err!(BadState, "socket recv_from() failed"),
+            }
         })
     }
 
@@ -165,7 +230,7 @@ impl UdpSocket {
     /// to which it is connected. On success, returns the number of bytes read.
     pub fn recv(&self, buf: &mut [u8]) -> AxResult<usize> {
         let remote_endpoint = self.remote_endpoint()?;
-        self.recv_impl(|socket| {
+        self.recv_impl(self.is_nonblocking(), |socket| {
             let (len, meta) = socket
                 .recv_slice(buf)
                 .map_err(|_| ax_err_type!(BadState, "socket recv() failed"))?;
@@ -195,12 +260,14 @@ impl UdpSocket {
             return Ok(PollState {
                 readable: false,
                 writable: false,
+                ..Default::default()
             });
         }
         SOCKET_SET.with_socket_mut::<udp::Socket, _, _>(self.handle, |socket| {
             Ok(PollState {
                 readable: socket.can_recv(),
                 writable: socket.can_send(),
+                ..Default::default()
             })
         })
     }
@@ -208,6 +275,20 @@ impl UdpSocket {
 
 /// Private methods
 impl UdpSocket {
+    /// Replaces the smoltcp socket by one with the buffer lengths returned
+    /// by `lens`, if the socket is not bound yet.
+    fn resize_buffers(&self, lens: impl FnOnce(&udp::Socket) -> (usize, usize)) {
+        // hold the lock so that the socket is not bound meanwhile
+        let local_addr = self.local_addr.read();
+        if local_addr.is_some() {
+            return;
+        }
+        SOCKET_SET.with_socket_mut::<udp::Socket, _, _>(self.handle, |socket| {
+            let (rx_len, tx_len) = lens(socket);
+            *socket = SocketSetWrapper::new_udp_socket(rx_len, tx_len);
+        });
+    }
+
     fn remote_endpoint(&self) -> AxResult<IpEndpoint> {
         match self.peer_addr.try_read() {
             Some(addr) => addr.ok_or(AxError::NotConnected),
@@ -216,12 +297,15 @@ impl UdpSocket {
     }
 
     fn send_impl(&self, buf: &[u8], remote_endpoint: IpEndpoint) -> AxResult<usize> {
+        if !self.is_broadcast() && ETH0.is_broadcast(remote_endpoint.addr) {
+            return ax_err!(PermissionDenied, "socket send() failed: broadcast");
+        }
         if self.local_addr.read().is_none() {
             let res = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 0));
             self.bind(res)?;
         }
 
-        self.block_on(|| {
+        self.block_on(self.is_nonblocking(), || {
             SOCKET_SET.with_socket_mut::<udp::Socket, _, _>(self.handle, |socket| {
                 if socket.can_send() {
                     socket
@@ -241,7 +325,7 @@ impl UdpSocket {
         })
     }
 
-    fn recv_impl<F, T>(&self, mut op: F) -> AxResult<T>
+    fn recv_impl<F, T>(&self, nonblocking: bool, mut op: F) -> AxResult<T>
     where
         F: FnMut(&mut udp::Socket) -> AxResult<T>,
     {
@@ -249,7 +333,7 @@ impl UdpSocket {
             return ax_err!(NotConnected, "socket send() failed");
         }
 
-        self.block_on(|| {
+        self.block_on(nonblocking, || {
             SOCKET_SET.with_socket_mut::<udp::Socket, _, _>(self.handle, |socket| {
                 if socket.can_recv() {
                     // data available
@@ -262,11 +346,11 @@ impl UdpSocket {
         })
     }
 
-    fn block_on<F, T>(&self, mut f: F) -> AxResult<T>
+    fn block_on<F, T>(&self, nonblocking: bool, mut f: F) -> AxResult<T>
     where
         F: FnMut() -> AxResult<T>,
     {
-        if self.is_nonblocking() {
+        if nonblocking {
             f()
         } else {
             loop {
@@ -289,16 +373,5 @@ impl Drop for UdpSocket {
 }
 
 fn get_ephemeral_port() -> AxResult<u16> {
-    const PORT_START: u16 = 0x15b3;
-    const PORT_END: u16 = 0xffff;
-    static CURR: Mutex<u16> = Mutex::new(PORT_START);
-    let mut curr = CURR.lock();
-
-    let port = *curr;
-    if *curr == PORT_END {
-        *curr = PORT_START;
-    } else {
-        *curr += 1;
-    }
-    Ok(port)
+    alloc_ephemeral_port(IpProtocol::Udp, |_| true)
 }
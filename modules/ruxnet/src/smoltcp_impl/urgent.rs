@@ -0,0 +1,223 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Emulation of TCP urgent data on top of smoltcp.
+//!
+//! smoltcp ignores the urgent pointer of incoming segments and never sets it
+//! on outgoing ones, so it is tracked here, outside of the TCP sockets:
+//!
+//! - The urgent pointers of the received segments are snooped before smoltcp
+//!   processes them, and turned into the offset of the urgent byte in the
+//!   receive stream (the *mark*).
+//! - The outgoing segments are patched to carry the urgent pointer of the
+//!   last byte sent with `MSG_OOB`, until the segments have passed it.
+//!
+//! Stream offsets are counted from the first byte after the SYN, so the
+//! initial sequence numbers of both directions are recorded as well. The
+//! state of a connection is forgotten once it is reset in either direction.
+//!
+//! The urgent byte itself stays in the smoltcp receive buffer, and the
+//! [`TcpSocket`](super::TcpSocket) takes it out of the stream when reading
+//! past the mark. Like Linux without `SO_OOBINLINE`, only a single byte of
+//! urgent data is kept, the one of the latest mark.
+
+use alloc::collections::BTreeMap;
+
+use axsync::Mutex;
+use smoltcp::wire::{IpEndpoint, TcpPacket};
+
+#[derive(Default)]
+struct UrgentEntry {
+    /// Sequence number of the first byte received, if the SYN was seen.
+    rx_base: Option<u32>,
+    /// Sequence number of the first byte sent, if the SYN was seen.
+    tx_base: Option<u32>,
+    /// Offset of the latest urgent byte received.
+    rx_mark: Option<u32>,
+    /// Offset right after the latest urgent byte sent.
+    tx_mark: Option<u32>,
+}
+
+/// Urgent state of the connections, keyed by their local and remote endpoints.
+static URGENT_TABLE: Mutex<BTreeMap<(IpEndpoint, IpEndpoint), UrgentEntry>> =
+    Mutex::new(BTreeMap::new());
+
+#[inline]
+fn is_after(a: u32, b: u32) -> bool {
+    (a.wrapping_sub(b) as i32) > 0
+}
+
+/// Inspects a TCP segment received from `remote` to `local`.
+///
+/// `accepted` tells whether a SYN opens a new connection on a listening
+/// socket. The SYN-ACK answering a connection of ours is always tracked.
+pub(crate) fn snoop_rx(
+    local: IpEndpoint,
+    remote: IpEndpoint,
+    packet: &TcpPacket<&[u8]>,
+    accepted: bool,
+) {
+    let seq = packet.seq_number().0 as u32;
+    let mut table = URGENT_TABLE.lock();
+    if packet.rst() {
+        // e.g. a connection of ours refused
+        table.remove(&(local, remote));
+        return;
+    }
+    if packet.syn() {
+        let entry = if packet.ack() {
+            table.get_mut(&(local, remote))
+        } else if accepted {
+            Some(table.entry((local, remote)).or_default())
+        } else {
+            None
+        };
+        if let Some(entry) = entry {
+            *entry = UrgentEntry {
+                rx_base: Some(seq.wrapping_add(1)),
+                tx_base: entry.tx_base,
+                ..Default::default()
+            };
+        }
+        return;
+    }
+    if !packet.urg() || packet.urgent_at() == 0 {
+        return;
+    }
+    if let Some(entry) = table.get_mut(&(local, remote)) {
+        let Some(base) = entry.rx_base else {
+            return;
+        };
+        // The urgent pointer points right after the urgent byte.
+        let mark = seq
+            .wrapping_add(packet.urgent_at() as u32 - 1)
+            .wrapping_sub(base);
+        if entry.rx_mark.map_or(true, |old| is_after(mark, old)) {
+            debug!("TCP urgent data from {} at offset {}", remote, mark);
+            entry.rx_mark = Some(mark);
+        }
+    }
+}
+
+/// Inspects a TCP segment sent from `local` to `remote`, and sets its urgent
+/// pointer if it comes before the urgent byte sent.
+pub(crate) fn snoop_tx(local: IpEndpoint, remote: IpEndpoint, packet: &mut TcpPacket<&mut [u8]>) {
+    let seq = packet.seq_number().0 as u32;
+    let mut table = URGENT_TABLE.lock();
+    if packet.rst() {
+        table.remove(&(local, remote));
+        return;
+    }
+    if packet.syn() {
+        let entry = table.entry((local, remote)).or_default();
+        entry.tx_base = Some(seq.wrapping_add(1));
+        entry.tx_mark = None;
+        return;
+    }
+    let Some(entry) = table.get(&(local, remote)) else {
+        return;
+    };
+    if let (Some(base), Some(mark)) = (entry.tx_base, entry.tx_mark) {
+        let urgent_at = mark.wrapping_sub(seq.wrapping_sub(base));
+        if urgent_at as i32 > 0 && urgent_at <= u16::MAX as u32 {
+            packet.set_urg(true);
+            packet.set_urgent_at(urgent_at as u16);
+            packet.fill_checksum(&local.addr, &remote.addr);
+        }
+    }
+}
+
+/// Returns the offset of the latest urgent byte received on the connection.
+pub(crate) fn rx_mark(local: IpEndpoint, remote: IpEndpoint) -> Option<u32> {
+    URGENT_TABLE
+        .lock()
+        .get(&(local, remote))
+        .and_then(|entry| entry.rx_mark)
+}
+
+/// Sets the urgent pointer of the connection right after the byte at offset
+/// `end - 1` of the send stream.
+pub(crate) fn set_tx_mark(local: IpEndpoint, remote: IpEndpoint, end: u32) {
+    if let Some(entry) = URGENT_TABLE.lock().get_mut(&(local, remote)) {
+        entry.tx_mark = Some(end);
+    }
+}
+
+/// Forgets the urgent state of a closed connection.
+pub(crate) fn remove(local: IpEndpoint, remote: IpEndpoint) {
+    URGENT_TABLE.lock().remove(&(local, remote));
+}
+
+/// Urgent data state of a connected [`TcpSocket`](super::TcpSocket).
+pub(crate) struct UrgentState {
+    /// Offset of the next byte to receive.
+    pub recv_offset: u32,
+    /// Offset of the next byte to send.
+    pub send_offset: u32,
+    /// The mark whose urgent byte has been read with `MSG_OOB`, or taken out
+    /// of the stream.
+    pub read_mark: Option<u32>,
+    /// The urgent byte taken out of the stream but not read yet.
+    pub pending: Option<u8>,
+}
+
+impl UrgentState {
+    pub const fn new() -> Self {
+        Self {
+            recv_offset: 0,
+            send_offset: 0,
+            read_mark: None,
+            pending: None,
+        }
+    }
+
+    /// Returns the number of bytes to receive before the urgent byte at
+    /// `mark`, or `None` if it has already been received.
+    pub fn distance_to(&self, mark: Option<u32>) -> Option<usize> {
+        let dist = mark?.wrapping_sub(self.recv_offset);
+        (dist as i32 >= 0).then_some(dist as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smoltcp::wire::{IpAddress, TcpSeqNumber};
+
+    const LOCAL: IpEndpoint = IpEndpoint::new(IpAddress::v4(10, 0, 2, 15), 49152);
+    const REMOTE: IpEndpoint = IpEndpoint::new(IpAddress::v4(10, 0, 2, 2), 5555);
+
+    /// Builds a TCP header from `src_port` to `dst_port` with the given flags.
+    fn segment(src_port: u16, dst_port: u16, seq: i32, syn: bool, rst: bool) -> [u8; 20] {
+        let mut buf = [0u8; 20];
+        let mut packet = TcpPacket::new_unchecked(&mut buf[..]);
+        packet.set_src_port(src_port);
+        packet.set_dst_port(dst_port);
+        packet.set_seq_number(TcpSeqNumber(seq));
+        packet.set_header_len(20);
+        packet.clear_flags();
+        packet.set_syn(syn);
+        packet.set_rst(rst);
+        packet.set_ack(rst);
+        buf
+    }
+
+    #[test]
+    fn test_refused_connect_forgotten() {
+        ruxtask::init_scheduler(); // call this to use `axsync::Mutex`.
+        let mut syn = segment(LOCAL.port, REMOTE.port, 1000, true, false);
+        snoop_tx(LOCAL, REMOTE, &mut TcpPacket::new_unchecked(&mut syn[..]));
+        assert!(URGENT_TABLE.lock().contains_key(&(LOCAL, REMOTE)));
+
+        let rst = segment(REMOTE.port, LOCAL.port, 0, false, true);
+        snoop_rx(LOCAL, REMOTE, &TcpPacket::new_unchecked(&rst[..]), false);
+        assert!(URGENT_TABLE.lock().is_empty());
+        assert_eq!(rx_mark(LOCAL, REMOTE), None);
+    }
+}
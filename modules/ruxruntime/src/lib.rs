@@ -131,6 +131,26 @@ impl axlog::LogIf for LogIfImpl {
             None
         }
     }
+
+    fn current_task_name() -> Option<[u8; axlog::TASK_NAME_LEN]> {
+        if is_init_ok() {
+            #[cfg(feature = "multitask")]
+            {
+                ruxtask::current_may_uninit().map(|curr| {
+                    let mut buf = [0; axlog::TASK_NAME_LEN];
+                    curr.with_name(|name| {
+                        let len = name.len().min(axlog::TASK_NAME_LEN - 1);
+                        buf[..len].copy_from_slice(&name.as_bytes()[..len]);
+                    });
+                    buf
+                })
+            }
+            #[cfg(not(feature = "multitask"))]
+            None
+        } else {
+            None
+        }
+    }
 }
 
 use core::sync::atomic::{AtomicUsize, Ordering};
@@ -201,6 +221,8 @@ pub extern "C" fn rust_main(cpu_id: usize, dtb: usize) -> ! {
     info!("Initialize platform devices...");
     ruxhal::platform_init();
 
+    init_random();
+
     #[cfg(feature = "multitask")]
     {
         ruxtask::init_scheduler();
@@ -248,6 +270,13 @@ pub extern "C" fn rust_main(cpu_id: usize, dtb: usize) -> ! {
 
             // setup and initialize rootfs
             ruxfs::init_filesystems(mount_points);
+            #[cfg(feature = "multitask")]
+            ruxtask::on_terminate(sync_filesystems);
+        }
+
+        #[cfg(all(feature = "fs", feature = "net"))]
+        if let Ok(conf) = ruxfs::api::read_to_string("/etc/resolv.conf") {
+            ruxnet::set_nameservers(&ruxnet::parse_resolv_conf(&conf));
         }
 
         #[cfg(feature = "display")]
@@ -304,14 +333,25 @@ pub extern "C" fn rust_main(cpu_id: usize, dtb: usize) -> ! {
     };
 
     #[cfg(feature = "multitask")]
-    ruxtask::exit(0);
+    ruxtask::exit_group(0);
     #[cfg(not(feature = "multitask"))]
     {
         debug!("main task exited: exit_code={}", 0);
+        #[cfg(feature = "fs")]
+        sync_filesystems();
         ruxhal::misc::terminate();
     }
 }
 
+/// Writes the blocks cached in memory back to the disks before the system
+/// shuts down.
+#[cfg(feature = "fs")]
+fn sync_filesystems() {
+    if let Err(e) = ruxfs::sync() {
+        warn!("failed to write back the filesystems: {:?}", e);
+    }
+}
+
 #[cfg(feature = "alloc")]
 cfg_if::cfg_if! {
     if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
@@ -393,6 +433,39 @@ fn init_allocator() {
 #[cfg(feature = "paging")]
 use ruxhal::paging::remap_kernel_memory;
 
+/// Seeds the kernel CSPRNG behind `getrandom()` and `/dev/urandom`, from:
+///
+/// - the jitter of the timer: the time taken by short busy loops, whose low
+///   bits vary with the caches, the interrupts and, when virtualized, the host;
+/// - the random number instruction of the CPU, if it has one (see
+///   [`ruxhal::random`]).
+fn init_random() {
+    use ruxhal::time::current_ticks;
+
+    const JITTER_SAMPLES: usize = 256;
+    const HW_SAMPLES: usize = 4;
+
+    info!("Initialize kernel CSPRNG...");
+    let mut jitter = [0u8; JITTER_SAMPLES + 8];
+    jitter[..8].copy_from_slice(&current_ticks().to_le_bytes());
+    for (i, sample) in jitter[8..].iter_mut().enumerate() {
+        let start = current_ticks();
+        for _ in 0..(i % 7 + 1) * 16 {
+            core::hint::black_box(current_ticks());
+        }
+        *sample = current_ticks().wrapping_sub(start) as u8;
+    }
+    chacha_rng::add_entropy(&jitter);
+
+    if ruxhal::random::has_hw_random() {
+        for random in (0..HW_SAMPLES).filter_map(|_| ruxhal::random::hw_random()) {
+            chacha_rng::add_entropy(&random.to_le_bytes());
+        }
+    } else {
+        warn!("  no hardware random number generator, seeded from the timer only");
+    }
+}
+
 #[cfg(feature = "irq")]
 fn init_interrupt() {
     use ruxhal::time::TIMER_IRQ_NUM;
@@ -433,11 +506,9 @@ fn init_interrupt() {
                 Signal::timer_deadline(which, Some(ddl));
             }
         }
-        let signal = Signal::signal(-1, true).unwrap();
+        let signal = Signal::signal(-1, true).unwrap() & !Signal::blocked(None);
         for signum in 0..32 {
-            if signal & (1 << signum) != 0
-            /* TODO: && support mask */
-            {
+            if signal & (1 << signum) != 0 {
                 Signal::sigaction(signum as u8, None, None);
                 Signal::signal(signum as i8, false);
             }
@@ -7,10 +7,9 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-#[cfg(feature = "irq")]
-use core::sync::atomic::AtomicI64;
 use core::{
     ffi::{c_int, c_uint, c_ulong},
+    sync::atomic::{AtomicI64, AtomicU32, AtomicUsize, Ordering},
     time::Duration,
 };
 
@@ -42,20 +41,23 @@ impl rx_sigaction {
 
 /// Signal struct
 pub struct Signal {
-    #[cfg(feature = "irq")]
     signal: AtomicI64,
+    blocked: AtomicU32,
     sigaction: [rx_sigaction; 32],
     timer_value: [Duration; 3],
     timer_interval: [Duration; 3],
 }
 
+/// How many signal handlers have run, see [`Signal::delivered`].
+static DELIVERED: AtomicUsize = AtomicUsize::new(0);
+
 unsafe extern "C" fn default_handler(signum: c_int) {
     panic!("default_handler, signum: {}", signum);
 }
 
 static mut SIGNAL_IF: Signal = Signal {
-    #[cfg(feature = "irq")]
     signal: AtomicI64::new(0),
+    blocked: AtomicU32::new(0),
     sigaction: [rx_sigaction::new(); 32],
     // Default::default() is not const
     timer_value: [Duration::from_nanos(0); 3],
@@ -66,9 +68,7 @@ impl Signal {
     /// Set signal
     /// signum: signal number, if signum < 0, just return current signal
     /// on: true: enable signal, false: disable signal
-    #[cfg(feature = "irq")]
     pub fn signal(signum: i8, on: bool) -> Option<u32> {
-        use core::sync::atomic::Ordering;
         if signum >= 32 {
             return None;
         }
@@ -96,6 +96,35 @@ impl Signal {
         }
         Some(old.try_into().unwrap())
     }
+    /// Set blocked signals
+    /// new_blocked: new mask of blocked signals, if None, just return current mask
+    ///
+    /// The mask has bit `signum` set for each blocked signal, like the pending
+    /// signals returned by [`Signal::signal`].
+    pub fn blocked(new_blocked: Option<u32>) -> u32 {
+        match new_blocked {
+            Some(new) => unsafe { SIGNAL_IF.blocked.swap(new, Ordering::AcqRel) },
+            None => unsafe { SIGNAL_IF.blocked.load(Ordering::Acquire) },
+        }
+    }
+    /// Consume a pending signal
+    /// mask: signals to look for
+    ///
+    /// Return the lowest pending signal in `mask` after clearing it, or None if
+    /// there is no such signal.
+    pub fn take_pending(mask: u32) -> Option<u8> {
+        loop {
+            let pending = Self::signal(-1, true)? & mask;
+            if pending == 0 {
+                return None;
+            }
+            let signum = pending.trailing_zeros() as i8;
+            // Another task may have consumed it in between, try again then.
+            if Self::signal(signum, false)? & (1 << signum) != 0 {
+                return Some(signum as u8);
+            }
+        }
+    }
     /// Set signal action
     /// signum: signal number
     /// sigaction: signal action, if sigaction == None, call the handler
@@ -117,10 +146,18 @@ impl Signal {
                 SIGNAL_IF.sigaction[signum as usize] = *s;
             },
             None => unsafe {
+                DELIVERED.fetch_add(1, Ordering::AcqRel);
                 SIGNAL_IF.sigaction[signum as usize].sa_handler.unwrap()(signum as c_int)
             },
         }
     }
+    /// Count the signals delivered
+    ///
+    /// Return how many signal handlers have run so far. A blocking call which
+    /// sees it change is interrupted by a signal, and fails with `EINTR`.
+    pub fn delivered() -> usize {
+        DELIVERED.load(Ordering::Acquire)
+    }
     /// Set timer
     /// which: timer type
     /// new_value: new timer value
@@ -9,22 +9,34 @@
 
 //! Task APIs for multi-task configuration.
 
-use alloc::{string::String, sync::Arc};
+use alloc::{string::String, sync::Arc, vec::Vec};
+use axerrno::AxResult;
+use lazy_init::LazyInit;
 
 pub(crate) use crate::run_queue::{AxRunQueue, RUN_QUEUE};
 
 #[doc(cfg(feature = "multitask"))]
-pub use crate::task::{CurrentTask, TaskId, TaskInner};
+pub use crate::task::{
+    all_tasks, task_count, CurrentTask, TaskId, TaskInner, ALL_CPUS_MASK, TASK_NAME_MAX,
+};
 #[cfg(not(feature = "musl"))]
 use crate::tsd;
 #[doc(cfg(feature = "multitask"))]
+pub use crate::usage::{total_usage, TaskUsage};
+#[doc(cfg(feature = "multitask"))]
 pub use crate::wait_queue::{WaitQueue, WaitQueueWithMetadata};
+#[doc(cfg(feature = "multitask"))]
+pub use scheduler::{SchedPolicy, RT_PRIORITY_MAX, RT_PRIORITY_MIN};
 
 /// The reference type of a task.
 pub type AxTaskRef = Arc<AxTask>;
 
 cfg_if::cfg_if! {
-    if #[cfg(feature = "sched_rr")] {
+    if #[cfg(feature = "sched_prio")] {
+        const MAX_TIME_SLICE: usize = 5;
+        pub(crate) type AxTask = scheduler::PrioTask<TaskInner, MAX_TIME_SLICE>;
+        pub(crate) type Scheduler = scheduler::PrioScheduler<TaskInner, MAX_TIME_SLICE>;
+    } else if #[cfg(feature = "sched_rr")] {
         const MAX_TIME_SLICE: usize = 5;
         pub(crate) type AxTask = scheduler::RRTask<TaskInner, MAX_TIME_SLICE>;
         pub(crate) type Scheduler = scheduler::RRScheduler<TaskInner, MAX_TIME_SLICE>;
@@ -107,12 +119,40 @@ pub fn spawn_raw<F>(f: F, name: String, stack_size: usize) -> AxTaskRef
 where
     F: FnOnce() + Send + 'static,
 {
-    let task = TaskInner::new(f, name, stack_size);
+    try_spawn_raw(f, name, stack_size).expect("failed to allocate task stack")
+}
+
+/// Spawns a new task with the given parameters, or fails with
+/// [`NoMemory`](axerrno::AxError::NoMemory) if its stack can not be allocated.
+///
+/// Returns the task reference.
+pub fn try_spawn_raw<F>(f: F, name: String, stack_size: usize) -> AxResult<AxTaskRef>
+where
+    F: FnOnce() + Send + 'static,
+{
+    let task = TaskInner::try_new(f, name, stack_size, false)?;
+    RUN_QUEUE.lock().add_task(task.clone());
+    Ok(task)
+}
+
+/// Spawns a new task with the given parameters as a child of the current
+/// task, in a new thread group, like a process created by `fork`.
+///
+/// Returns the task reference.
+pub fn spawn_child<F>(f: F, name: String, stack_size: usize) -> AxTaskRef
+where
+    F: FnOnce() + Send + 'static,
+{
+    let task = TaskInner::new(f, name, stack_size, true);
+    current().add_child(task.clone());
     RUN_QUEUE.lock().add_task(task.clone());
     task
 }
 
 /// Used by musl
+///
+/// A child task is created in a new thread group, and has to be added with
+/// [`TaskInner::add_child`] before it is put into the run queue.
 #[cfg(feature = "musl")]
 pub fn pspawn_raw<F>(
     f: F,
@@ -121,11 +161,12 @@ pub fn pspawn_raw<F>(
     tls: usize,
     set_tid: core::sync::atomic::AtomicU64,
     tl: core::sync::atomic::AtomicU64,
-) -> AxTaskRef
+    is_child: bool,
+) -> AxResult<AxTaskRef>
 where
     F: FnOnce() + Send + 'static,
 {
-    TaskInner::new_musl(f, name, stack_size, tls, set_tid, tl)
+    TaskInner::new_musl(f, name, stack_size, tls, set_tid, tl, is_child)
 }
 
 /// Spawns a new task with the default parameters.
@@ -148,11 +189,20 @@ pub fn pspawn<F>(
     tls: usize,
     set_tid: core::sync::atomic::AtomicU64,
     tl: core::sync::atomic::AtomicU64,
-) -> AxTaskRef
+    is_child: bool,
+) -> AxResult<AxTaskRef>
 where
     F: FnOnce() + Send + 'static,
 {
-    pspawn_raw(f, "".into(), ruxconfig::TASK_STACK_SIZE, tls, set_tid, tl)
+    pspawn_raw(
+        f,
+        "".into(),
+        ruxconfig::TASK_STACK_SIZE,
+        tls,
+        set_tid,
+        tl,
+        is_child,
+    )
 }
 
 /// Used by musl
@@ -175,10 +225,51 @@ pub fn set_priority(prio: isize) -> bool {
     RUN_QUEUE.lock().set_current_priority(prio)
 }
 
+/// Sets the CPU affinity mask of the given task, bit `i` stands for CPU `i`.
+///
+/// The task is migrated if it is running on a CPU which is not in the mask
+/// any more. CPUs which do not exist are ignored.
+///
+/// Returns `false` if the mask contains no existing CPU.
+pub fn set_affinity(task: &AxTaskRef, mask: usize) -> bool {
+    let mask = mask & ALL_CPUS_MASK;
+    if mask == 0 {
+        return false;
+    }
+    RUN_QUEUE.lock().set_task_affinity(task, mask);
+    true
+}
+
+/// Sets the scheduling policy and the static priority of the given task.
+///
+/// Only the `sched_prio` scheduler supports the real-time policies, whose
+/// priorities range from [`RT_PRIORITY_MIN`] to [`RT_PRIORITY_MAX`]. The
+/// other schedulers only accept [`SchedPolicy::Normal`] with priority 0, the
+/// default of all tasks.
+///
+/// Returns `false` if the policy or the priority is not supported.
+pub fn set_sched_policy(task: &AxTaskRef, policy: SchedPolicy, prio: usize) -> bool {
+    RUN_QUEUE.lock().set_task_policy(task, policy, prio)
+}
+
+/// Returns the scheduling policy and the static priority of the given task.
+pub fn sched_policy(task: &AxTaskRef) -> (SchedPolicy, usize) {
+    #[cfg(feature = "sched_prio")]
+    {
+        (task.policy(), task.priority())
+    }
+    #[cfg(not(feature = "sched_prio"))]
+    {
+        let _ = task;
+        (SchedPolicy::Normal, 0)
+    }
+}
+
 /// Current task gives up the CPU time voluntarily, and switches to another
 /// ready task.
 pub fn yield_now() {
     RUN_QUEUE.lock().yield_current();
+    exit_if_group_exiting();
 }
 
 /// Current task is going to sleep for the given duration.
@@ -196,13 +287,84 @@ pub fn sleep_until(deadline: ruxhal::time::TimeValue) {
     RUN_QUEUE.lock().sleep_until(deadline);
     #[cfg(not(feature = "irq"))]
     ruxhal::time::busy_wait_until(deadline);
+    exit_if_group_exiting();
+}
+
+/// The function called before the system shuts down.
+static ON_TERMINATE: LazyInit<fn()> = LazyInit::new();
+
+/// Sets the function called when the main program ends, before the system
+/// shuts down, e.g. to write back the data cached in memory.
+///
+/// It can only be set once.
+pub fn on_terminate(f: fn()) {
+    ON_TERMINATE.init_by(f);
 }
 
 /// Exits the current task.
+///
+/// The other tasks of its thread group keep running. The system is shut down
+/// when the last task of the main program exits.
 pub fn exit(exit_code: i32) -> ! {
+    current().set_exiting();
     #[cfg(not(feature = "musl"))]
     current().destroy_keys();
-    RUN_QUEUE.lock().exit_current(exit_code)
+    let last = {
+        let group = current().thread_group();
+        let last = group.exit_one(exit_code);
+        // called before locking the run queue, as it may block
+        if group.is_init() && (last || group.is_exiting()) {
+            if let Some(f) = ON_TERMINATE.try_get() {
+                f();
+            }
+        }
+        last
+    };
+    RUN_QUEUE.lock().exit_current(exit_code, last)
+}
+
+/// Exits the current task and all the tasks of its thread group, like the
+/// threads of a process, with `exit_code`.
+///
+/// The blocked tasks of the group are woken up, and the other tasks exit at
+/// their next safe point, see [`exit_if_group_exiting`]. The system is shut
+/// down at once if it is the group of the main program.
+pub fn exit_group(exit_code: i32) -> ! {
+    {
+        let group = current().thread_group();
+        group.start_exit(exit_code);
+        // collected before locking the run queue, not to nest the two locks
+        let tasks: Vec<_> = all_tasks()
+            .into_iter()
+            .filter(|task| Arc::ptr_eq(&task.thread_group(), &group))
+            .collect();
+        let mut rq = RUN_QUEUE.lock();
+        for task in tasks {
+            rq.unblock_task(task, false);
+        }
+    }
+    exit(exit_code)
+}
+
+/// Exits the current task if another task of its thread group called
+/// [`exit_group`].
+///
+/// A task can not be stopped at any point, as it may hold locks, so it is
+/// only checked at safe points: when the task yields, sleeps or returns from
+/// a wait on a [`WaitQueue`], and on the return path of the system calls. A
+/// task which never reaches one, e.g. in a busy loop, keeps running.
+pub fn exit_if_group_exiting() {
+    let curr = current();
+    // a spinlock is still held
+    #[cfg(feature = "preempt")]
+    if !curr.can_preempt(0) {
+        return;
+    }
+    if let Some(exit_code) = curr.pending_group_exit() {
+        curr.set_exiting();
+        let last = curr.thread_group().exit_one(exit_code);
+        RUN_QUEUE.lock().exit_current(exit_code, last);
+    }
 }
 
 /// The idle task routine.
@@ -22,6 +22,8 @@
 //!    APIs can be used, such as [`sleep`], [`sleep_until`], and
 //!    [`WaitQueue::wait_timeout`].
 //! - `preempt`: Enable preemptive scheduling.
+//! - `paging`: Map each task stack with an unmapped guard page below it, so
+//!   that a stack overflow faults instead of corrupting the adjacent memory.
 //! - `sched_fifo`: Use the [FIFO cooperative scheduler][1]. It also enables the
 //!   `multitask` feature if it is enabled. This feature is enabled by default,
 //!   and it can be overriden by other scheduler features.
@@ -29,10 +31,16 @@
 //!   the `multitask` and `preempt` features if it is enabled.
 //! - `sched_cfs`: Use the [Completely Fair Scheduler][3]. It also enables the
 //!   the `multitask` and `preempt` features if it is enabled.
+//! - `sched_prio`: Use the [static priority scheduler][4], which supports the
+//!   `SCHED_FIFO` and `SCHED_RR` real-time policies with priorities from 1 to
+//!   99 besides the default `SCHED_OTHER` one. It also enables the `multitask`
+//!   and `preempt` features if it is enabled, and overrides the other
+//!   scheduler features.
 //!
 //! [1]: scheduler::FifoScheduler
 //! [2]: scheduler::RRScheduler
 //! [3]: scheduler::CFScheduler
+//! [4]: scheduler::PrioScheduler
 
 #![cfg_attr(not(test), no_std)]
 #![feature(doc_cfg)]
@@ -46,6 +54,7 @@ cfg_if::cfg_if! {
 
         mod run_queue;
         mod task;
+        mod usage;
         mod api;
         mod wait_queue;
         #[cfg(feature = "irq")]
@@ -54,17 +63,22 @@ cfg_if::cfg_if! {
         /// specific key-value storage for each task
         #[cfg(not(feature = "musl"))]
         pub mod tsd;
-        /// TODO: if irq is disabled, what value should AVENRUN be?
+        /// bits to shift the fixed-point load averages, same as in linux kernel
+        pub const FSHIFT: u64 = 11;
         /// average run load, same as in linux kernel
-        static mut AVENRUN: [u64; 3] = [0, 0, 0];
+        ///
+        /// It stays zero if irq is disabled, as there is no timer tick to sample the load.
+        static AVENRUN: [core::sync::atomic::AtomicU64; 3] = [
+            core::sync::atomic::AtomicU64::new(0),
+            core::sync::atomic::AtomicU64::new(0),
+            core::sync::atomic::AtomicU64::new(0),
+        ];
 
-        /// Get the load average
+        /// Get the 1, 5 and 15 minute load averages, as fixed-point numbers
+        /// shifted by [`FSHIFT`] bits.
         pub fn get_avenrun(loads: &mut [u64; 3]) {
-            for i in 0..3 {
-                unsafe {
-                    // TODO: disable irq for safety
-                    loads[i] = AVENRUN[i];
-                }
+            for (load, avenrun) in loads.iter_mut().zip(AVENRUN.iter()) {
+                *load = avenrun.load(core::sync::atomic::Ordering::Relaxed);
             }
         }
 
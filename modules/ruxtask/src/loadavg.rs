@@ -7,12 +7,11 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use core::sync::atomic::{AtomicU64, Ordering};
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
-use crate::AVENRUN;
+use crate::task::TaskState;
+use crate::{AVENRUN, FSHIFT};
 
-/// bits to shift fixed point
-const FSHIFT: u64 = 16;
 /// fixed point
 const FIXED_1: u64 = 1 << FSHIFT;
 /// update AVENRUN per 5 seconds
@@ -23,12 +22,10 @@ const LOAD_FREQ: u64 = 5 * ruxhal::time::NANOS_PER_SEC + 1;
 /* 1/exp(5sec/15min) */
 const EXP: [u64; 3] = [1884, 2014, 2037];
 
-/// count of idle ticks
-static mut IDLE_CNT: AtomicU64 = AtomicU64::new(0);
-/// count of all ticks
-static mut ALL_CNT: AtomicU64 = AtomicU64::new(0);
+/// count of runnable (ready or running) tasks, idle tasks excluded
+static NR_RUNNING: AtomicUsize = AtomicUsize::new(0);
 /// last update time
-static mut LAST_UPDATE: AtomicU64 = AtomicU64::new(0);
+static LAST_UPDATE: AtomicU64 = AtomicU64::new(0);
 
 /*
  * a1 = a0 * e + a * (1 - e)
@@ -41,38 +38,87 @@ fn calc_load(load: u64, exp: u64, active: u64) -> u64 {
     newload / FIXED_1
 }
 
+/// `x ^ n` of the fixed-point `x`, by squaring.
+fn fixed_power_int(mut x: u64, mut n: u64) -> u64 {
+    let mut result = FIXED_1;
+    while n > 0 {
+        if n & 1 != 0 {
+            result = (result * x + FIXED_1 / 2) >> FSHIFT;
+        }
+        n >>= 1;
+        if n > 0 {
+            x = (x * x + FIXED_1 / 2) >> FSHIFT;
+        }
+    }
+    result
+}
+
 /*
- * calc_load_tick - update the avenrun load
+ * a_n = a_0 * e^n + a * (1 - e^n)
  *
- * Called from the scheduler_timer_tick.
+ * The same as applying `calc_load` `n` times, for the periods in which no
+ * sample was taken, e.g. as no timer tick happened.
  */
-pub(crate) fn calc_load_tick(is_idle: bool) {
-    if is_idle {
-        unsafe {
-            IDLE_CNT.fetch_add(1, Ordering::Relaxed);
+fn calc_load_n(load: u64, exp: u64, active: u64, n: u64) -> u64 {
+    calc_load(load, fixed_power_int(exp, n), active)
+}
+
+const fn is_runnable(state: TaskState) -> bool {
+    matches!(state, TaskState::Ready | TaskState::Running)
+}
+
+/// Counts a task that enters the run queue for the first time.
+pub(crate) fn task_spawned() {
+    NR_RUNNING.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Keeps the count of runnable tasks up to date when a non-idle task
+/// changes its state from `prev` to `next`.
+pub(crate) fn task_state_changed(prev: TaskState, next: TaskState) {
+    match (is_runnable(prev), is_runnable(next)) {
+        (false, true) => {
+            NR_RUNNING.fetch_add(1, Ordering::Relaxed);
         }
+        (true, false) => {
+            NR_RUNNING.fetch_sub(1, Ordering::Relaxed);
+        }
+        _ => {}
     }
-    unsafe {
-        ALL_CNT.fetch_add(1, Ordering::Relaxed);
-    }
+}
 
+/*
+ * calc_load_tick - update the avenrun load
+ *
+ * Called from the scheduler_timer_tick, samples the count of runnable tasks
+ * every `LOAD_FREQ` on one of the CPUs. The samples stay on the `LOAD_FREQ`
+ * grid, and the periods missed since the last one are decayed with the same
+ * count of runnable tasks, like the nohz catch-up of linux.
+ */
+pub(crate) fn calc_load_tick() {
     let curr = ruxhal::time::current_time_nanos();
-
-    if curr - unsafe { LAST_UPDATE.load(Ordering::Relaxed) } < LOAD_FREQ {
+    let last = LAST_UPDATE.load(Ordering::Relaxed);
+    let periods = curr.saturating_sub(last) / LOAD_FREQ;
+    if periods == 0
+        || LAST_UPDATE
+            .compare_exchange(
+                last,
+                last + periods * LOAD_FREQ,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .is_err()
+    {
         return;
     }
-    let idle_cnt;
-    let all_cnt;
-    unsafe {
-        LAST_UPDATE.store(curr, Ordering::Relaxed);
-        idle_cnt = IDLE_CNT.load(Ordering::Relaxed);
-        IDLE_CNT.store(0, Ordering::Relaxed);
-        all_cnt = ALL_CNT.load(Ordering::Relaxed);
-        ALL_CNT.store(0, Ordering::Relaxed);
-    }
-    for i in 0..3 {
-        unsafe {
-            AVENRUN[i] = calc_load(AVENRUN[i], EXP[i], (all_cnt - idle_cnt) * FIXED_1 / all_cnt);
-        }
+
+    let active = NR_RUNNING.load(Ordering::Relaxed) as u64 * FIXED_1;
+    for (avenrun, exp) in AVENRUN.iter().zip(EXP) {
+        let load = avenrun.load(Ordering::Relaxed);
+        let load = if periods == 1 {
+            calc_load(load, exp, active)
+        } else {
+            calc_load_n(load, exp, active, periods)
+        };
+        avenrun.store(load, Ordering::Relaxed);
     }
 }
@@ -9,10 +9,11 @@
 
 use alloc::collections::VecDeque;
 use alloc::sync::Arc;
+use alloc::vec::Vec;
 use axerrno::{LinuxError, LinuxResult};
 use lazy_init::LazyInit;
 use ruxfdtable::{FD_TABLE, RUX_FILE_LIMIT};
-use scheduler::BaseScheduler;
+use scheduler::{BaseScheduler, SchedPolicy};
 use spinlock::SpinNoIrq;
 
 use crate::task::{CurrentTask, TaskState};
@@ -35,8 +36,10 @@ pub(crate) struct AxRunQueue {
 
 impl AxRunQueue {
     pub fn new() -> SpinNoIrq<Self> {
-        let gc_task = TaskInner::new(gc_entry, "gc".into(), ruxconfig::TASK_STACK_SIZE);
+        let gc_task = TaskInner::new(gc_entry, "gc".into(), ruxconfig::TASK_STACK_SIZE, false);
         let mut scheduler = Scheduler::new();
+        #[cfg(feature = "irq")]
+        crate::loadavg::task_spawned();
         scheduler.add_task(gc_task);
         SpinNoIrq::new(Self { scheduler })
     }
@@ -44,14 +47,15 @@ impl AxRunQueue {
     pub fn add_task(&mut self, task: AxTaskRef) {
         debug!("task spawn: {}", task.id_name());
         assert!(task.is_ready());
+        #[cfg(feature = "irq")]
+        crate::loadavg::task_spawned();
         self.scheduler.add_task(task);
     }
 
     #[cfg(feature = "irq")]
     pub fn scheduler_timer_tick(&mut self) {
-        use crate::loadavg;
         let curr = crate::current();
-        loadavg::calc_load_tick(curr.is_idle());
+        crate::loadavg::calc_load_tick();
         if !curr.is_idle() && self.scheduler.task_tick(curr.as_task_ref()) {
             #[cfg(feature = "preempt")]
             curr.set_preempt_pending(true);
@@ -70,6 +74,35 @@ impl AxRunQueue {
             .set_priority(crate::current().as_task_ref(), prio)
     }
 
+    /// Restricts `task` to the CPUs in `mask`.
+    ///
+    /// If the current task may no longer run on this CPU, it is moved away
+    /// right now. Another running task is moved at its next scheduling point.
+    pub fn set_task_affinity(&mut self, task: &AxTaskRef, mask: usize) {
+        task.set_cpu_mask(mask);
+        let curr = crate::current();
+        if curr.ptr_eq(task) {
+            if !curr.can_run_on(ruxhal::cpu::this_cpu_id()) {
+                self.resched(false);
+            }
+        } else if task.is_running() {
+            #[cfg(feature = "preempt")]
+            task.set_preempt_pending(true);
+        }
+    }
+
+    /// Changes the scheduling policy and the static priority of `task`, which
+    /// take effect at the next scheduling point.
+    pub fn set_task_policy(&mut self, task: &AxTaskRef, policy: SchedPolicy, prio: usize) -> bool {
+        if !self.scheduler.set_policy(task, policy, prio) {
+            return false;
+        }
+        // A ready task may now have a higher priority than the current one.
+        #[cfg(feature = "preempt")]
+        crate::current().set_preempt_pending(true);
+        true
+    }
+
     #[cfg(feature = "preempt")]
     pub fn preempt_resched(&mut self) {
         let curr = crate::current();
@@ -94,12 +127,17 @@ impl AxRunQueue {
         }
     }
 
-    pub fn exit_current(&mut self, exit_code: i32) -> ! {
+    /// Exits the current task, already counted out of its thread group by
+    /// [`ThreadGroup::exit_one`](crate::task::ThreadGroup::exit_one), which
+    /// returned `last`.
+    pub fn exit_current(&mut self, exit_code: i32, last: bool) -> ! {
         let curr = crate::current();
         debug!("task exit: {}, exit_code={}", curr.id_name(), exit_code);
         assert!(curr.is_running());
         assert!(!curr.is_idle());
-        if curr.is_init() {
+        let group = curr.thread_group();
+        // the end of the main program, with all its threads, shuts down
+        if group.is_init() && (last || group.is_exiting()) {
             EXITED_TASKS.lock().clear();
             ruxhal::misc::terminate();
         } else {
@@ -125,6 +163,11 @@ impl AxRunQueue {
         #[cfg(feature = "preempt")]
         assert!(curr.can_preempt(1));
 
+        // the task would never be woken up if its group is exiting, so it
+        // returns at once to exit
+        if curr.pending_group_exit().is_some() {
+            return;
+        }
         curr.set_state(TaskState::Blocked);
         wait_queue_push(curr.clone());
         self.resched(false);
@@ -150,7 +193,7 @@ impl AxRunQueue {
         assert!(!curr.is_idle());
 
         let now = ruxhal::time::current_time();
-        if now < deadline {
+        if now < deadline && curr.pending_group_exit().is_none() {
             crate::timers::set_alarm_wakeup(deadline, curr.clone());
             curr.set_state(TaskState::Blocked);
             self.resched(false);
@@ -169,14 +212,33 @@ impl AxRunQueue {
                 self.scheduler.put_prev_task(prev.clone(), preempt);
             }
         }
-        let next = self.scheduler.pick_next_task().unwrap_or_else(|| unsafe {
+        let next = self.pick_next_task().unwrap_or_else(|| unsafe {
             // Safety: IRQs must be disabled at this time.
             IDLE_TASK.current_ref_raw().get_unchecked().clone()
         });
-        self.switch_to(prev, next);
+        self.switch_to(prev, next, preempt);
     }
 
-    fn switch_to(&mut self, prev_task: CurrentTask, next_task: AxTaskRef) {
+    /// Picks the next task allowed to run on this CPU, leaving the others
+    /// for the CPUs in their affinity masks.
+    fn pick_next_task(&mut self) -> Option<AxTaskRef> {
+        let cpu_id = ruxhal::cpu::this_cpu_id();
+        let mut skipped = Vec::new();
+        let next = loop {
+            match self.scheduler.pick_next_task() {
+                Some(task) if !task.can_run_on(cpu_id) => skipped.push(task),
+                next => break next,
+            }
+        };
+        for task in skipped {
+            self.scheduler.put_prev_task(task, false);
+        }
+        next
+    }
+
+    /// Switches from `prev_task` to `next_task`, counting an involuntary
+    /// context switch for `prev_task` if it is preempted.
+    fn switch_to(&mut self, prev_task: CurrentTask, next_task: AxTaskRef, preempt: bool) {
         trace!(
             "context switch: {} -> {}",
             prev_task.id_name(),
@@ -189,6 +251,12 @@ impl AxRunQueue {
             return;
         }
 
+        let now = ruxhal::time::current_time_nanos();
+        prev_task
+            .usage_counters()
+            .switch_out(now, preempt, prev_task.is_idle());
+        next_task.usage_counters().switch_in(now);
+
         unsafe {
             let prev_ctx_ptr = prev_task.ctx_mut_ptr();
             let next_ctx_ptr = next_task.ctx_mut_ptr();
@@ -244,11 +312,18 @@ fn gc_entry() {
 
 pub(crate) fn init() {
     const IDLE_TASK_STACK_SIZE: usize = 4096;
-    let idle_task = TaskInner::new(|| crate::run_idle(), "idle".into(), IDLE_TASK_STACK_SIZE);
+    let idle_task = TaskInner::new(
+        || crate::run_idle(),
+        "idle".into(),
+        IDLE_TASK_STACK_SIZE,
+        false,
+    );
     IDLE_TASK.with_current(|i| i.init_by(idle_task.clone()));
 
     let main_task = TaskInner::new_init("main".into());
     main_task.set_state(TaskState::Running);
+    #[cfg(feature = "irq")]
+    crate::loadavg::task_spawned();
 
     RUN_QUEUE.init_by(AxRunQueue::new());
     unsafe { CurrentTask::init_current(main_task) }
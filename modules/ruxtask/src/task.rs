@@ -7,24 +7,154 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use alloc::{boxed::Box, string::String, sync::Arc};
+use alloc::collections::BTreeMap;
+use alloc::sync::{Arc, Weak};
+use alloc::{boxed::Box, string::String};
+use core::any::Any;
 use core::ops::Deref;
-use core::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicU8, Ordering};
-use core::{alloc::Layout, cell::UnsafeCell, fmt, ptr::NonNull};
-
-#[cfg(feature = "preempt")]
-use core::sync::atomic::AtomicUsize;
+use core::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+#[cfg(not(feature = "paging"))]
+use core::{alloc::Layout, ptr::NonNull};
+use core::{cell::UnsafeCell, fmt};
 
 #[cfg(feature = "tls")]
 use ruxhal::tls::TlsArea;
 
+use alloc::vec::Vec;
+use axerrno::{AxError, AxResult};
+#[cfg(feature = "paging")]
+use memory_addr::PAGE_SIZE_4K;
 use memory_addr::{align_up_4k, VirtAddr};
 use ruxhal::arch::TaskContext;
+use spinlock::SpinNoIrq;
 
 #[cfg(not(feature = "musl"))]
 use crate::tsd::{DestrFunction, KEYS, TSD};
+use crate::usage::{TaskUsage, UsageCounters};
 use crate::{AxRunQueue, AxTask, AxTaskRef, WaitQueue};
 
+/// Maximum length of a task name in bytes, not including the NUL terminator
+/// (`TASK_COMM_LEN - 1` in Linux).
+pub const TASK_NAME_MAX: usize = 15;
+
+/// The CPU affinity mask with all the CPUs set, bit `i` stands for CPU `i`.
+pub const ALL_CPUS_MASK: usize = usize::MAX >> (usize::BITS as usize - ruxconfig::SMP);
+
+const _: () = assert!(
+    ruxconfig::SMP <= usize::BITS as usize,
+    "too many CPUs for the affinity mask"
+);
+
+/// Number of the tasks not dropped yet, idle tasks excluded.
+static TASK_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// The tasks not dropped yet by ID, idle tasks excluded.
+static TASKS: SpinNoIrq<BTreeMap<u64, Weak<AxTask>>> = SpinNoIrq::new(BTreeMap::new());
+
+/// Returns the number of tasks, including the exited ones that are not
+/// dropped yet. Idle tasks are not counted.
+pub fn task_count() -> usize {
+    TASK_COUNT.load(Ordering::Relaxed)
+}
+
+/// Returns the tasks sorted by ID, including the exited ones that are not
+/// dropped yet. Idle tasks are not included.
+pub fn all_tasks() -> Vec<AxTaskRef> {
+    TASKS.lock().values().filter_map(Weak::upgrade).collect()
+}
+
+/// The tasks which exit together, like the threads of a process.
+///
+/// A task joins the group of the task which creates it, unless it is created
+/// as a child, e.g. by [`spawn_child`](crate::spawn_child).
+pub(crate) struct ThreadGroup {
+    /// Whether it is the group of the main task, whose end shuts the system
+    /// down.
+    init: bool,
+    /// Number of tasks in the group which have not exited.
+    live: AtomicUsize,
+    /// Whether [`exit_group`](crate::exit_group) was called in the group.
+    exiting: AtomicBool,
+    /// The code passed to `exit_group`, or else that of the last task which
+    /// exited.
+    exit_code: AtomicI32,
+}
+
+impl ThreadGroup {
+    fn new(init: bool) -> Self {
+        Self {
+            init,
+            live: AtomicUsize::new(1),
+            exiting: AtomicBool::new(false),
+            exit_code: AtomicI32::new(0),
+        }
+    }
+
+    /// Returns the group of a new task: a new group if it is a child, else
+    /// that of the running task.
+    fn of_new_task(is_child: bool) -> Arc<Self> {
+        if is_child {
+            Arc::new(Self::new(false))
+        } else {
+            Self::join_current()
+        }
+    }
+
+    /// Returns the group of the running task with one more task in it, or a
+    /// new group if there is no running task yet.
+    fn join_current() -> Arc<Self> {
+        match crate::current_may_uninit() {
+            Some(curr) => {
+                let group = curr.thread_group();
+                group.live.fetch_add(1, Ordering::AcqRel);
+                group
+            }
+            None => Arc::new(Self::new(false)),
+        }
+    }
+
+    pub(crate) fn is_init(&self) -> bool {
+        self.init
+    }
+
+    pub(crate) fn is_exiting(&self) -> bool {
+        self.exiting.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn exit_code(&self) -> i32 {
+        self.exit_code.load(Ordering::Acquire)
+    }
+
+    /// Makes all the tasks of the group exit with `exit_code`, unless it is
+    /// already exiting.
+    pub(crate) fn start_exit(&self, exit_code: i32) {
+        if !self.exiting.swap(true, Ordering::AcqRel) {
+            self.exit_code.store(exit_code, Ordering::Release);
+        }
+    }
+
+    /// Counts a task of the group which exits with `exit_code`, and returns
+    /// whether it was the last one.
+    pub(crate) fn exit_one(&self, exit_code: i32) -> bool {
+        if !self.is_exiting() {
+            self.exit_code.store(exit_code, Ordering::Release);
+        }
+        self.live.fetch_sub(1, Ordering::AcqRel) == 1
+    }
+}
+
+/// Returns the current directory of the running task, which a new task
+/// starts in, if there is a running task yet.
+fn inherited_cwd() -> Option<Arc<dyn Any + Send + Sync>> {
+    crate::current_may_uninit().and_then(|curr| curr.cwd())
+}
+
+/// Returns the mount namespace of the running task, which a new task starts
+/// in, if there is a running task yet.
+fn inherited_mnt_ns() -> Option<Arc<dyn Any + Send + Sync>> {
+    crate::current_may_uninit().and_then(|curr| curr.mnt_ns())
+}
+
 /// A unique identifier for a thread.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct TaskId(u64);
@@ -46,7 +176,7 @@ pub enum TaskState {
 /// The inner task structure.
 pub struct TaskInner {
     id: TaskId,
-    name: String,
+    name: SpinNoIrq<String>,
     is_idle: bool,
     is_init: bool,
 
@@ -57,13 +187,27 @@ pub struct TaskInner {
     #[cfg(feature = "irq")]
     in_timer_list: AtomicBool,
 
+    cpu_mask: AtomicUsize,
+    usage: UsageCounters,
+
     #[cfg(feature = "preempt")]
     need_resched: AtomicBool,
     #[cfg(feature = "preempt")]
     preempt_disable_count: AtomicUsize,
 
     exit_code: AtomicI32,
+    /// Whether the task has started exiting, so that it does not exit again
+    /// when its thread group exits.
+    exiting: AtomicBool,
     wait_for_exit: WaitQueue,
+    children: SpinNoIrq<Vec<AxTaskRef>>,
+    /// Whether the task has replaced its program by `execve`.
+    exec_done: AtomicBool,
+    /// The current directory, which is opaque to the scheduler.
+    cwd: SpinNoIrq<Option<Arc<dyn Any + Send + Sync>>>,
+    /// The mount namespace, which is opaque to the scheduler.
+    mnt_ns: SpinNoIrq<Option<Arc<dyn Any + Send + Sync>>>,
+    group: Arc<ThreadGroup>,
 
     kstack: Option<TaskStack>,
     ctx: UnsafeCell<TaskContext>,
@@ -123,13 +267,29 @@ impl TaskInner {
     }
 
     /// Gets the name of the task.
-    pub fn name(&self) -> &str {
-        self.name.as_str()
+    pub fn name(&self) -> String {
+        self.name.lock().clone()
+    }
+
+    /// Calls `f` with the name of the task, without copying it.
+    pub fn with_name<R>(&self, f: impl FnOnce(&str) -> R) -> R {
+        f(self.name.lock().as_str())
+    }
+
+    /// Sets the name of the task.
+    ///
+    /// Like Linux, the name is truncated to [`TASK_NAME_MAX`] bytes.
+    pub fn set_name(&self, name: &str) {
+        let mut len = name.len().min(TASK_NAME_MAX);
+        while !name.is_char_boundary(len) {
+            len -= 1;
+        }
+        *self.name.lock() = String::from(&name[..len]);
     }
 
     /// Get a combined string of the task ID and name.
     pub fn id_name(&self) -> alloc::string::String {
-        alloc::format!("Task({}, {:?})", self.id.as_u64(), self.name)
+        alloc::format!("Task({}, {:?})", self.id.as_u64(), *self.name.lock())
     }
 
     /// Wait for the task to exit, and return the exit code.
@@ -141,6 +301,104 @@ impl TaskInner {
         Some(self.exit_code.load(Ordering::Acquire))
     }
 
+    /// Marks that the task has replaced its program by `execve`, which wakes
+    /// up the parent waiting in [`wait_for_exec`](Self::wait_for_exec).
+    pub fn notify_exec(&self) {
+        self.exec_done.store(true, Ordering::Release);
+        self.wait_for_exit.notify_all(false);
+    }
+
+    /// Waits for the task to call `execve` or to exit, like the parent of a
+    /// `vfork` does before it resumes.
+    pub fn wait_for_exec(&self) {
+        self.wait_for_exit.wait_until(|| {
+            self.exec_done.load(Ordering::Acquire) || self.state() == TaskState::Exited
+        });
+    }
+
+    /// Returns the current directory of the task, or `None` if neither it nor
+    /// the task which created it have set one.
+    pub fn cwd(&self) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.cwd.lock().clone()
+    }
+
+    /// Sets the current directory of the task. The tasks it creates later
+    /// start in the same directory.
+    pub fn set_cwd(&self, cwd: Arc<dyn Any + Send + Sync>) {
+        *self.cwd.lock() = Some(cwd);
+    }
+
+    /// Returns the mount namespace of the task, or `None` if neither it nor
+    /// the task which created it have switched to one.
+    pub fn mnt_ns(&self) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.mnt_ns.lock().clone()
+    }
+
+    /// Switches the task to the mount namespace `mnt_ns`. The tasks it
+    /// creates later start in the same namespace.
+    pub fn set_mnt_ns(&self, mnt_ns: Arc<dyn Any + Send + Sync>) {
+        *self.mnt_ns.lock() = Some(mnt_ns);
+    }
+
+    /// Adds `child` to the children of the task, like a process created by
+    /// `fork`. It is kept after it exits, until it is reaped by
+    /// [`reap_child`](Self::reap_child).
+    ///
+    /// The child must have been created in its own thread group, which can
+    /// not change once it runs.
+    pub fn add_child(&self, child: AxTaskRef) {
+        debug_assert!(!Arc::ptr_eq(&child.thread_group(), &self.thread_group()));
+        self.children.lock().push(child);
+    }
+
+    /// Whether the task has the child `id`, or any child if `id` is `None`.
+    pub fn has_child(&self, id: Option<u64>) -> bool {
+        self.children
+            .lock()
+            .iter()
+            .any(|child| id.map_or(true, |id| child.id.as_u64() == id))
+    }
+
+    /// Removes the child `id`, or any child if `id` is `None`, if all the
+    /// tasks of its thread group have exited, and returns its ID and the exit
+    /// code of the group.
+    pub fn reap_child(&self, id: Option<u64>) -> Option<(u64, i32)> {
+        let mut children = self.children.lock();
+        let idx = children.iter().position(|child| {
+            id.map_or(true, |id| child.id.as_u64() == id)
+                && child.thread_group().live.load(Ordering::Acquire) == 0
+        })?;
+        let child = children.remove(idx);
+        let exit_code = child.thread_group().exit_code();
+        Some((child.id.as_u64(), exit_code))
+    }
+
+    /// Returns the thread group of the task.
+    pub(crate) fn thread_group(&self) -> Arc<ThreadGroup> {
+        self.group.clone()
+    }
+
+    /// Whether the task is a thread of the main program, rather than of a
+    /// child added by [`add_child`](Self::add_child).
+    pub fn in_main_program(&self) -> bool {
+        self.group.is_init()
+    }
+
+    /// Returns the exit code of the thread group of the task if it is
+    /// exiting, and the task has not started exiting yet. The idle tasks never
+    /// exit.
+    pub(crate) fn pending_group_exit(&self) -> Option<i32> {
+        if self.is_idle || self.exiting.load(Ordering::Acquire) {
+            return None;
+        }
+        self.group.is_exiting().then(|| self.group.exit_code())
+    }
+
+    /// Marks the task as exiting.
+    pub(crate) fn set_exiting(&self) {
+        self.exiting.store(true, Ordering::Release);
+    }
+
     /// set 0 to thread_list_lock
     #[cfg(feature = "musl")]
     pub fn free_thread_list_lock(&self) {
@@ -154,23 +412,35 @@ impl TaskInner {
 
 // private methods
 impl TaskInner {
-    fn new_common(id: TaskId, name: String) -> Self {
+    fn new_common(id: TaskId, name: String, is_child: bool) -> Self {
+        let is_idle = name == "idle";
+        if !is_idle {
+            TASK_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
         Self {
             id,
-            name,
-            is_idle: false,
+            name: SpinNoIrq::new(name),
+            is_idle,
             is_init: false,
             entry: None,
             state: AtomicU8::new(TaskState::Ready as u8),
             in_wait_queue: AtomicBool::new(false),
             #[cfg(feature = "irq")]
             in_timer_list: AtomicBool::new(false),
+            cpu_mask: AtomicUsize::new(ALL_CPUS_MASK),
+            usage: UsageCounters::new(),
             #[cfg(feature = "preempt")]
             need_resched: AtomicBool::new(false),
             #[cfg(feature = "preempt")]
             preempt_disable_count: AtomicUsize::new(0),
             exit_code: AtomicI32::new(0),
+            exiting: AtomicBool::new(false),
             wait_for_exit: WaitQueue::new(),
+            children: SpinNoIrq::new(Vec::new()),
+            exec_done: AtomicBool::new(false),
+            cwd: SpinNoIrq::new(inherited_cwd()),
+            mnt_ns: SpinNoIrq::new(inherited_mnt_ns()),
+            group: ThreadGroup::of_new_task(is_child),
             kstack: None,
             ctx: UnsafeCell::new(TaskContext::new()),
             #[cfg(feature = "tls")]
@@ -192,22 +462,34 @@ impl TaskInner {
         set_tid: AtomicU64,
         tl: AtomicU64,
     ) -> Self {
+        let is_idle = name == "idle";
+        if !is_idle {
+            TASK_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
         Self {
             id,
-            name,
-            is_idle: false,
+            name: SpinNoIrq::new(name),
+            is_idle,
             is_init: false,
             entry: None,
             state: AtomicU8::new(TaskState::Ready as u8),
             in_wait_queue: AtomicBool::new(false),
             #[cfg(feature = "irq")]
             in_timer_list: AtomicBool::new(false),
+            cpu_mask: AtomicUsize::new(ALL_CPUS_MASK),
+            usage: UsageCounters::new(),
             #[cfg(feature = "preempt")]
             need_resched: AtomicBool::new(false),
             #[cfg(feature = "preempt")]
             preempt_disable_count: AtomicUsize::new(0),
             exit_code: AtomicI32::new(0),
+            exiting: AtomicBool::new(false),
             wait_for_exit: WaitQueue::new(),
+            children: SpinNoIrq::new(Vec::new()),
+            exec_done: AtomicBool::new(false),
+            cwd: SpinNoIrq::new(inherited_cwd()),
+            mnt_ns: SpinNoIrq::new(inherited_mnt_ns()),
+            group: ThreadGroup::of_new_task(is_child),
             kstack: None,
             ctx: UnsafeCell::new(TaskContext::new()),
             #[cfg(feature = "tls")]
@@ -225,6 +507,8 @@ impl TaskInner {
     }
 
     /// Create a new task with the given entry function, stack size and tls area address
+    ///
+    /// A child task is created in a new thread group.
     #[cfg(feature = "musl")]
     pub(crate) fn new_musl<F>(
         entry: F,
@@ -234,13 +518,17 @@ impl TaskInner {
         set_tid: AtomicU64,
         // clear child tid
         tl: AtomicU64,
-    ) -> AxTaskRef
+        is_child: bool,
+    ) -> AxResult<AxTaskRef>
     where
         F: FnOnce() + Send + 'static,
     {
-        let mut t = Self::new_common_tls(TaskId::new(), name, tls, set_tid, tl);
+        // allocated before joining a thread group, so that a failure leaves
+        // nothing to undo
+        let id = TaskId::new();
+        let kstack = TaskStack::alloc(align_up_4k(stack_size), id)?;
+        let mut t = Self::new_common_tls(id, name, tls, set_tid, tl, is_child);
         debug!("new task: {}", t.id_name());
-        let kstack = TaskStack::alloc(align_up_4k(stack_size));
 
         #[cfg(feature = "tls")]
         let tls = VirtAddr::from(t.tls.tls_ptr() as usize);
@@ -250,20 +538,39 @@ impl TaskInner {
         t.entry = Some(Box::into_raw(Box::new(entry)));
         t.ctx.get_mut().init(task_entry as usize, kstack.top(), tls);
         t.kstack = Some(kstack);
-        if t.name == "idle" {
-            t.is_idle = true;
-        }
-        Arc::new(AxTask::new(t))
+        Ok(Self::register(t))
     }
 
     /// Create a new task with the given entry function and stack size.
-    pub(crate) fn new<F>(entry: F, name: String, stack_size: usize) -> AxTaskRef
+    ///
+    /// A child task is created in a new thread group.
+    ///
+    /// Panics if the stack can not be allocated, see [`try_new`](Self::try_new).
+    pub(crate) fn new<F>(entry: F, name: String, stack_size: usize, is_child: bool) -> AxTaskRef
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        Self::try_new(entry, name, stack_size, is_child).expect("failed to allocate task stack")
+    }
+
+    /// Create a new task with the given entry function and stack size, or
+    /// fails with [`NoMemory`](AxError::NoMemory) if its stack can not be
+    /// allocated.
+    pub(crate) fn try_new<F>(
+        entry: F,
+        name: String,
+        stack_size: usize,
+        is_child: bool,
+    ) -> AxResult<AxTaskRef>
     where
         F: FnOnce() + Send + 'static,
     {
-        let mut t = Self::new_common(TaskId::new(), name);
+        // allocated before joining a thread group, so that a failure leaves
+        // nothing to undo
+        let id = TaskId::new();
+        let kstack = TaskStack::alloc(align_up_4k(stack_size), id)?;
+        let mut t = Self::new_common(id, name, is_child);
         debug!("new task: {}", t.id_name());
-        let kstack = TaskStack::alloc(align_up_4k(stack_size));
 
         #[cfg(feature = "tls")]
         let tls = VirtAddr::from(t.tls.tls_ptr() as usize);
@@ -273,10 +580,7 @@ impl TaskInner {
         t.entry = Some(Box::into_raw(Box::new(entry)));
         t.ctx.get_mut().init(task_entry as usize, kstack.top(), tls);
         t.kstack = Some(kstack);
-        if t.name == "idle" {
-            t.is_idle = true;
-        }
-        Arc::new(AxTask::new(t))
+        Ok(Self::register(t))
     }
 
     /// Creates an "init task" using the current CPU states, to use as the
@@ -288,12 +592,23 @@ impl TaskInner {
     /// And there is no need to set the `entry`, `kstack` or `tls` fields, as
     /// they will be filled automatically when the task is switches out.
     pub(crate) fn new_init(name: String) -> AxTaskRef {
-        let mut t = Self::new_common(TaskId::new(), name);
+        let mut t = Self::new_common(TaskId::new(), name, false);
         t.is_init = true;
-        if t.name == "idle" {
-            t.is_idle = true;
+        if !t.is_idle {
+            t.group = Arc::new(ThreadGroup::new(true));
         }
-        Arc::new(AxTask::new(t))
+        Self::register(t)
+    }
+
+    /// Wraps `t` into a task, which is listed by [`all_tasks`] until it is
+    /// dropped unless it is an idle task.
+    fn register(t: Self) -> AxTaskRef {
+        let (id, is_idle) = (t.id.as_u64(), t.is_idle);
+        let task = Arc::new(AxTask::new(t));
+        if !is_idle {
+            TASKS.lock().insert(id, Arc::downgrade(&task));
+        }
+        task
     }
 
     /// Get task state
@@ -305,7 +620,11 @@ impl TaskInner {
     /// Set task state
     #[inline]
     pub fn set_state(&self, state: TaskState) {
-        self.state.store(state as u8, Ordering::Release)
+        let _prev = self.state.swap(state as u8, Ordering::AcqRel);
+        #[cfg(feature = "irq")]
+        if !self.is_idle {
+            crate::loadavg::task_state_changed(_prev.into(), state);
+        }
     }
 
     #[inline]
@@ -356,6 +675,32 @@ impl TaskInner {
         self.in_timer_list.store(in_timer_list, Ordering::Release);
     }
 
+    /// Gets the set of CPUs the task is allowed to run on.
+    #[inline]
+    pub fn cpu_mask(&self) -> usize {
+        self.cpu_mask.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub(crate) fn set_cpu_mask(&self, mask: usize) {
+        self.cpu_mask.store(mask, Ordering::Release)
+    }
+
+    #[inline]
+    pub(crate) fn can_run_on(&self, cpu_id: usize) -> bool {
+        self.cpu_mask() & (1 << cpu_id) != 0
+    }
+
+    /// Returns the CPU time and the number of context switches of the task.
+    pub fn usage(&self) -> TaskUsage {
+        self.usage.usage(self.is_running())
+    }
+
+    #[inline]
+    pub(crate) fn usage_counters(&self) -> &UsageCounters {
+        &self.usage
+    }
+
     #[inline]
     #[cfg(feature = "preempt")]
     pub(crate) fn set_preempt_pending(&self, pending: bool) {
@@ -411,20 +756,25 @@ impl TaskInner {
     pub fn alloc_key(&self, destr_function: Option<DestrFunction>) -> Option<usize> {
         unsafe { KEYS.lock() }.alloc(destr_function)
     }
-    /// Get the destructor function of a key
+    /// Free a key, and clear its value in all the tasks, so that it is NULL
+    /// again if the key is allocated later.
     pub fn free_key(&self, key: usize) -> Option<()> {
-        unsafe { KEYS.lock() }.free(key)
+        unsafe { KEYS.lock() }.free(key)?;
+        for task in all_tasks() {
+            task.tsd.lock()[key] = core::ptr::null_mut();
+        }
+        Some(())
     }
-    /// Get the destructor function of a key
+    /// Set the value of a key for this task
     pub fn set_tsd(&self, key: usize, value: *mut core::ffi::c_void) -> Option<()> {
-        if key < self.tsd.lock().len() {
+        if unsafe { KEYS.lock() }.is_used(key) {
             self.tsd.lock()[key] = value;
             Some(())
         } else {
             None
         }
     }
-    /// Get the destructor function of a key
+    /// Get the value of a key for this task
     pub fn get_tsd(&self, key: usize) -> Option<*mut core::ffi::c_void> {
         if key < self.tsd.lock().len() {
             Some(self.tsd.lock()[key])
@@ -432,9 +782,30 @@ impl TaskInner {
             None
         }
     }
-    /// Get the destructor function of a key
+    /// Run the destructors of the keys with non-NULL values, when the task exits.
+    ///
+    /// Each value is reset to NULL before its destructor is called. Destructors
+    /// may set new values, so this is repeated until no value is left, at most
+    /// `PTHREAD_DESTRUCTOR_ITERATIONS` times. No lock is held while a
+    /// destructor runs, as it may use the keys itself.
     pub fn destroy_keys(&self) {
-        unsafe { KEYS.lock() }.destr_used_keys(&self.tsd)
+        for _ in 0..ruxconfig::PTHREAD_DESTRUCTOR_ITERATIONS {
+            let mut called = false;
+            for key in 0..ruxconfig::PTHREAD_KEY_MAX {
+                let value = core::mem::replace(&mut self.tsd.lock()[key], core::ptr::null_mut());
+                if value.is_null() {
+                    continue;
+                }
+                let destr_function = unsafe { KEYS.lock() }.destr_function(key);
+                if let Some(destr_function) = destr_function {
+                    unsafe { destr_function(value) };
+                    called = true;
+                }
+            }
+            if !called {
+                break;
+            }
+        }
     }
 }
 
@@ -442,7 +813,7 @@ impl fmt::Debug for TaskInner {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("TaskInner")
             .field("id", &self.id)
-            .field("name", &self.name)
+            .field("name", &*self.name.lock())
             .field("state", &self.state())
             .finish()
     }
@@ -451,22 +822,26 @@ impl fmt::Debug for TaskInner {
 impl Drop for TaskInner {
     fn drop(&mut self) {
         debug!("task drop: {}", self.id_name());
+        if !self.is_idle {
+            TASK_COUNT.fetch_sub(1, Ordering::Relaxed);
+            TASKS.lock().remove(&self.id.as_u64());
+        }
     }
 }
 
+#[cfg(not(feature = "paging"))]
 struct TaskStack {
     ptr: NonNull<u8>,
     layout: Layout,
 }
 
+#[cfg(not(feature = "paging"))]
 impl TaskStack {
-    pub fn alloc(size: usize) -> Self {
+    pub fn alloc(size: usize, _owner: TaskId) -> AxResult<Self> {
         let layout = Layout::from_size_align(size, 8).unwrap();
         debug!("taskStack::layout = {:?}", layout);
-        Self {
-            ptr: NonNull::new(unsafe { alloc::alloc::alloc(layout) }).unwrap(),
-            layout,
-        }
+        let ptr = NonNull::new(unsafe { alloc::alloc::alloc(layout) }).ok_or(AxError::NoMemory)?;
+        Ok(Self { ptr, layout })
     }
 
     pub const fn top(&self) -> VirtAddr {
@@ -474,12 +849,92 @@ impl TaskStack {
     }
 }
 
+#[cfg(not(feature = "paging"))]
 impl Drop for TaskStack {
     fn drop(&mut self) {
         unsafe { alloc::alloc::dealloc(self.ptr.as_ptr(), self.layout) }
     }
 }
 
+/// Virtual address space reserved for the task stacks.
+///
+/// Each slot holds a stack and the guard page below it. Freed slots are
+/// reused by stacks of the same size, which is the common case.
+#[cfg(feature = "paging")]
+struct StackRegion {
+    next: usize,
+    free: Vec<(usize, usize)>,
+}
+
+#[cfg(feature = "paging")]
+impl StackRegion {
+    const fn new() -> Self {
+        Self {
+            next: ruxconfig::STACK_START_VADDR,
+            free: Vec::new(),
+        }
+    }
+
+    fn alloc(&mut self, size: usize) -> Option<usize> {
+        if let Some(idx) = self.free.iter().position(|&(_, s)| s == size) {
+            return Some(self.free.swap_remove(idx).0);
+        }
+        let start = self.next;
+        if ruxconfig::STACK_END_VADDR - start < size {
+            return None;
+        }
+        self.next += size;
+        Some(start)
+    }
+
+    fn dealloc(&mut self, start: usize, size: usize) {
+        self.free.push((start, size));
+    }
+}
+
+#[cfg(feature = "paging")]
+static STACK_REGION: SpinNoIrq<StackRegion> = SpinNoIrq::new(StackRegion::new());
+
+/// A task stack mapped with an unmapped guard page at its low end.
+#[cfg(feature = "paging")]
+struct TaskStack {
+    base: VirtAddr,
+    size: usize,
+}
+
+#[cfg(feature = "paging")]
+impl TaskStack {
+    pub fn alloc(size: usize, owner: TaskId) -> AxResult<Self> {
+        let slot = STACK_REGION
+            .lock()
+            .alloc(size + PAGE_SIZE_4K)
+            .ok_or(AxError::NoMemory)?;
+        let base = VirtAddr::from(slot + PAGE_SIZE_4K);
+        debug!("taskStack::base = {:#x?}, size = {:#x}", base, size);
+        if let Err(e) = ruxhal::paging::map_task_stack(base, size, owner.as_u64()) {
+            warn!("failed to map task stack {:#x?}: {:?}", base, e);
+            STACK_REGION.lock().dealloc(slot, size + PAGE_SIZE_4K);
+            return Err(AxError::NoMemory);
+        }
+        Ok(Self { base, size })
+    }
+
+    pub fn top(&self) -> VirtAddr {
+        self.base + self.size
+    }
+}
+
+#[cfg(feature = "paging")]
+impl Drop for TaskStack {
+    fn drop(&mut self) {
+        ruxhal::paging::unmap_task_stack(self.base, self.size);
+        STACK_REGION.lock().dealloc(
+            self.base.as_usize() - PAGE_SIZE_4K,
+            self.size + PAGE_SIZE_4K,
+        );
+    }
+}
+
 use core::mem::ManuallyDrop;
 
 /// A wrapper of [`AxTaskRef`] as the current task.
@@ -137,3 +137,168 @@ fn test_task_join() {
         assert_eq!(tasks[i].join(), Some(i as _));
     }
 }
+
+#[test]
+fn test_reap_child() {
+    let _lock = SERIAL.lock();
+    INIT.call_once(ruxtask::init_scheduler);
+
+    let child = ruxtask::spawn_child(|| ruxtask::exit(7), "child".into(), 0x1000);
+    let id = child.id().as_u64();
+    assert!(current().has_child(Some(id)));
+    assert!(!current().has_child(Some(id + 1)));
+
+    // the child is kept until it is reaped, with its exit code
+    let (reaped, exit_code) = loop {
+        if let Some(reaped) = current().reap_child(None) {
+            break reaped;
+        }
+        ruxtask::yield_now();
+    };
+    assert_eq!((reaped, exit_code), (id, 7));
+    assert!(!current().has_child(None));
+    assert_eq!(current().reap_child(None), None);
+}
+
+#[test]
+fn test_inherit_cwd() {
+    let _lock = SERIAL.lock();
+    INIT.call_once(ruxtask::init_scheduler);
+
+    fn cwd(task: &crate::task::TaskInner) -> Option<u32> {
+        task.cwd()?.downcast::<u32>().ok().map(|cwd| *cwd)
+    }
+    current().set_cwd(std::sync::Arc::new(1u32));
+    let child = ruxtask::spawn_raw(
+        || {
+            // the child starts in the directory of its parent, then moves
+            assert_eq!(cwd(&current()), Some(1));
+            current().set_cwd(std::sync::Arc::new(2u32));
+        },
+        "cwd".into(),
+        0x1000,
+    );
+    child.join();
+    assert_eq!(cwd(&child), Some(2));
+    assert_eq!(cwd(&current()), Some(1));
+}
+
+#[test]
+fn test_inherit_mnt_ns() {
+    let _lock = SERIAL.lock();
+    INIT.call_once(ruxtask::init_scheduler);
+
+    fn mnt_ns(task: &crate::task::TaskInner) -> Option<u32> {
+        task.mnt_ns()?.downcast::<u32>().ok().map(|mnt_ns| *mnt_ns)
+    }
+    current().set_mnt_ns(std::sync::Arc::new(1u32));
+    let child = ruxtask::spawn_raw(
+        || {
+            // the child starts in the namespace of its parent, then unshares it
+            assert_eq!(mnt_ns(&current()), Some(1));
+            current().set_mnt_ns(std::sync::Arc::new(2u32));
+        },
+        "mnt_ns".into(),
+        0x1000,
+    );
+    child.join();
+    assert_eq!(mnt_ns(&child), Some(2));
+    assert_eq!(mnt_ns(&current()), Some(1));
+}
+
+#[test]
+fn test_exit_group() {
+    let _lock = SERIAL.lock();
+    INIT.call_once(ruxtask::init_scheduler);
+
+    static TICKS: AtomicUsize = AtomicUsize::new(0);
+    let leader = crate::TaskInner::new(
+        || {
+            // a thread exits alone, the others keep running
+            let thread = ruxtask::spawn_raw(|| ruxtask::exit(1), "exit".into(), 0x1000);
+            assert_eq!(thread.join(), Some(1));
+            ruxtask::spawn_raw(
+                || loop {
+                    TICKS.fetch_add(1, Ordering::Relaxed);
+                    ruxtask::yield_now();
+                },
+                "spin".into(),
+                0x1000,
+            );
+            while TICKS.load(Ordering::Relaxed) < 10 {
+                ruxtask::yield_now();
+            }
+            ruxtask::exit_group(5);
+        },
+        "leader".into(),
+        0x1000,
+        true,
+    );
+    current().add_child(leader.clone());
+    ruxtask::put_task(leader);
+
+    // the child is reaped once all its threads have exited
+    let (_, exit_code) = loop {
+        if let Some(reaped) = current().reap_child(None) {
+            break reaped;
+        }
+        ruxtask::yield_now();
+    };
+    assert_eq!(exit_code, 5);
+    let ticks = TICKS.load(Ordering::Relaxed);
+    for _ in 0..10 {
+        ruxtask::yield_now();
+    }
+    assert_eq!(TICKS.load(Ordering::Relaxed), ticks);
+}
+
+#[test]
+fn test_exit_group_blocked() {
+    let _lock = SERIAL.lock();
+    INIT.call_once(ruxtask::init_scheduler);
+
+    // never notified, the threads only stop waiting to exit with their group
+    static WQ: WaitQueue = WaitQueue::new();
+    static WAITING: AtomicUsize = AtomicUsize::new(0);
+    let leader = crate::TaskInner::new(
+        || {
+            ruxtask::spawn_raw(
+                || {
+                    WAITING.fetch_add(1, Ordering::Relaxed);
+                    WQ.wait();
+                    unreachable!();
+                },
+                "wait".into(),
+                0x1000,
+            );
+            ruxtask::spawn_raw(
+                || {
+                    WAITING.fetch_add(1, Ordering::Relaxed);
+                    WQ.wait_until(|| false);
+                    unreachable!();
+                },
+                "wait_until".into(),
+                0x1000,
+            );
+            while WAITING.load(Ordering::Relaxed) < 2 {
+                ruxtask::yield_now();
+            }
+            ruxtask::exit_group(3);
+        },
+        "leader".into(),
+        0x1000,
+        true,
+    );
+    current().add_child(leader.clone());
+    ruxtask::put_task(leader);
+
+    let (_, exit_code) = loop {
+        if let Some(reaped) = current().reap_child(None) {
+            break reaped;
+        }
+        ruxtask::yield_now();
+    };
+    assert_eq!(exit_code, 3);
+    // the exited threads left the wait queue
+    assert!(!WQ.notify_one(false));
+}
@@ -82,7 +82,7 @@ impl PthreadKeys {
 
     /// Free a key
     pub fn free(&self, key: usize) -> Option<()> {
-        if key < self.keys.len() {
+        if self.is_used(key) {
             self.keys[key]
                 .in_use
                 .store(false, core::sync::atomic::Ordering::Relaxed);
@@ -92,21 +92,26 @@ impl PthreadKeys {
         }
     }
 
-    /// Get all keys used
-    pub fn destr_used_keys(&self, tsd: &TSD) {
-        for (i, key) in self.keys.iter().enumerate() {
-            if key.in_use.load(core::sync::atomic::Ordering::Relaxed) {
-                let destr_function = key
-                    .destr_function
-                    .load(core::sync::atomic::Ordering::Relaxed);
-                if !destr_function.is_null() {
-                    unsafe {
-                        let destr_function =
-                            core::mem::transmute::<*mut c_void, DestrFunction>(destr_function);
-                        destr_function(tsd.lock()[i]);
-                    }
-                }
-            }
+    /// Check whether a key is allocated
+    pub fn is_used(&self, key: usize) -> bool {
+        key < self.keys.len()
+            && self.keys[key]
+                .in_use
+                .load(core::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Get the destructor function of a key in use
+    pub fn destr_function(&self, key: usize) -> Option<DestrFunction> {
+        if !self.is_used(key) {
+            return None;
+        }
+        let destr_function = self.keys[key]
+            .destr_function
+            .load(core::sync::atomic::Ordering::Relaxed);
+        if destr_function.is_null() {
+            None
+        } else {
+            Some(unsafe { core::mem::transmute::<*mut c_void, DestrFunction>(destr_function) })
         }
     }
 }
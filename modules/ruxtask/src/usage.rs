@@ -0,0 +1,108 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! CPU time and context switch accounting.
+//!
+//! The time a task runs is accounted when it is switched out, and the time of
+//! the tasks other than the idle ones is added to the totals of the whole
+//! system as well.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
+static TOTAL_CPU_NANOS: AtomicU64 = AtomicU64::new(0);
+static TOTAL_NVCSW: AtomicU64 = AtomicU64::new(0);
+static TOTAL_NIVCSW: AtomicU64 = AtomicU64::new(0);
+
+/// Resource usage of a task, or of all the tasks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskUsage {
+    /// Time spent running on a CPU.
+    pub cpu_time: Duration,
+    /// Number of times the task gave up the CPU by itself, by blocking,
+    /// sleeping or yielding.
+    pub nvcsw: u64,
+    /// Number of times the task was preempted.
+    pub nivcsw: u64,
+}
+
+/// Per-task usage counters.
+pub(crate) struct UsageCounters {
+    cpu_nanos: AtomicU64,
+    /// Time the task was last switched in.
+    run_since: AtomicU64,
+    nvcsw: AtomicU64,
+    nivcsw: AtomicU64,
+}
+
+impl UsageCounters {
+    pub const fn new() -> Self {
+        Self {
+            cpu_nanos: AtomicU64::new(0),
+            run_since: AtomicU64::new(0),
+            nvcsw: AtomicU64::new(0),
+            nivcsw: AtomicU64::new(0),
+        }
+    }
+
+    pub fn switch_in(&self, now: u64) {
+        self.run_since.store(now, Ordering::Relaxed);
+    }
+
+    pub fn switch_out(&self, now: u64, preempt: bool, is_idle: bool) {
+        let slice = now.saturating_sub(self.run_since.load(Ordering::Relaxed));
+        self.cpu_nanos.fetch_add(slice, Ordering::Relaxed);
+        let csw = if preempt { &self.nivcsw } else { &self.nvcsw };
+        csw.fetch_add(1, Ordering::Relaxed);
+        if !is_idle {
+            TOTAL_CPU_NANOS.fetch_add(slice, Ordering::Relaxed);
+            let total_csw = if preempt { &TOTAL_NIVCSW } else { &TOTAL_NVCSW };
+            total_csw.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the time the task has been running since it was switched in,
+    /// or zero if it is not running.
+    pub fn current_slice(&self, running: bool, now: u64) -> u64 {
+        if running {
+            now.saturating_sub(self.run_since.load(Ordering::Relaxed))
+        } else {
+            0
+        }
+    }
+
+    pub fn usage(&self, running: bool) -> TaskUsage {
+        let now = ruxhal::time::current_time_nanos();
+        let nanos = self.cpu_nanos.load(Ordering::Relaxed) + self.current_slice(running, now);
+        TaskUsage {
+            cpu_time: Duration::from_nanos(nanos),
+            nvcsw: self.nvcsw.load(Ordering::Relaxed),
+            nivcsw: self.nivcsw.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Returns the resource usage of all the tasks, excluding the idle ones.
+///
+/// The time of the tasks running on the other CPUs is only counted up to the
+/// last time they were switched in.
+pub fn total_usage() -> TaskUsage {
+    let now = ruxhal::time::current_time_nanos();
+    let curr = crate::current();
+    let slice = if curr.is_idle() {
+        0
+    } else {
+        curr.usage_counters().current_slice(true, now)
+    };
+    TaskUsage {
+        cpu_time: Duration::from_nanos(TOTAL_CPU_NANOS.load(Ordering::Relaxed) + slice),
+        nvcsw: TOTAL_NVCSW.load(Ordering::Relaxed),
+        nivcsw: TOTAL_NIVCSW.load(Ordering::Relaxed),
+    }
+}
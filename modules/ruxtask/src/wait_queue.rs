@@ -89,6 +89,7 @@ impl<Meta> WaitQueueWithMetadata<Meta> {
             self.queue.lock().push_back((task, meta))
         });
         self.cancel_events(crate::current());
+        crate::exit_if_group_exiting();
     }
 
     /// If `condition` returns [`Ok`], blocks the current task and put it into the wait queue,
@@ -107,6 +108,7 @@ impl<Meta> WaitQueueWithMetadata<Meta> {
             drop(wq);
         });
         self.cancel_events(crate::current());
+        crate::exit_if_group_exiting();
 
         Ok(())
     }
@@ -153,6 +155,7 @@ impl<Meta> WaitQueueWithMetadata<Meta> {
         });
         let timeout = curr.in_wait_queue(); // still in the wait queue, must have timed out
         self.cancel_events(curr);
+        crate::exit_if_group_exiting();
         timeout
     }
 
@@ -187,6 +190,7 @@ impl<Meta> WaitQueueWithMetadata<Meta> {
         });
         let timeout = curr.in_wait_queue(); // still in the wait queue, must have timed out
         self.cancel_events(curr);
+        crate::exit_if_group_exiting();
         Ok(timeout)
     }
 
@@ -345,6 +349,7 @@ impl<Meta: Clone> WaitQueueWithMetadata<Meta> {
     where
         F: FnMut() -> bool,
     {
+        let curr = crate::current();
         loop {
             let mut rq = RUN_QUEUE.lock();
             if condition() {
@@ -354,8 +359,13 @@ impl<Meta: Clone> WaitQueueWithMetadata<Meta> {
                 task.set_in_wait_queue(true);
                 self.queue.lock().push_back((task, meta.clone()));
             });
+            // woken up to exit with its thread group
+            if curr.pending_group_exit().is_some() {
+                break;
+            }
         }
-        self.cancel_events(crate::current());
+        self.cancel_events(curr);
+        crate::exit_if_group_exiting();
     }
 
     /// Blocks the current task and put it into the wait queue, until the given
@@ -393,8 +403,13 @@ impl<Meta: Clone> WaitQueueWithMetadata<Meta> {
                 task.set_in_wait_queue(true);
                 self.queue.lock().push_back((task, meta.clone()));
             });
+            // woken up to exit with its thread group
+            if curr.pending_group_exit().is_some() {
+                break;
+            }
         }
         self.cancel_events(curr);
+        crate::exit_if_group_exiting();
         timeout
     }
 }
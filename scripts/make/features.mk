@@ -18,7 +18,7 @@ ifeq ($(APP_TYPE),c)
   else
     lib_feat_prefix := ruxlibc/
   endif
-  lib_features := fp_simd alloc irq sched_rr paging multitask fs net fd pipe select poll epoll random-hw signal
+  lib_features := fp_simd alloc irq sched_rr sched_prio paging multitask fs net smoltcp lwip fd pipe select poll epoll random-hw signal
 else
   # TODO: it's better to use `ruxfeat/` as `ax_feat_prefix`, but all apps need to have `ruxfeat` as a dependency
   ax_feat_prefix := axstd/
@@ -40,6 +40,11 @@ ifeq ($(APP_TYPE), c)
   ifneq ($(filter fs net pipe select poll epoll,$(FEATURES)),)
     override FEATURES += fd
   endif
+  ifneq ($(filter net,$(FEATURES)),)
+    ifeq ($(filter lwip,$(FEATURES)),)
+      override FEATURES += smoltcp
+    endif
+  endif
   ifeq ($(RUX_MUSL), y)
     override FEATURES += musl
     override FEATURES += fp_simd
@@ -108,14 +108,39 @@ if [ -z "$1" ]; then
     test_list=(
         "apps/c/helloworld"
         "apps/c/memtest"
+        "apps/c/mmap"
+        "apps/c/clock"
+        "apps/c/getrandom"
+        "apps/c/timerfd"
+        "apps/c/signalfd"
+        "apps/c/poll"
+        "apps/c/select"
+        "apps/c/wait"
+        "apps/c/spawn"
         "apps/c/sqlite3"
         "apps/c/httpclient"
+        "apps/c/ephemeral"
+        "apps/c/reuseaddr"
+        "apps/c/nbconnect"
+        "apps/c/tcpreset"
+        "apps/c/sockname"
+        "apps/c/unixsock"
+        "apps/c/ping"
         "apps/c/pthread/basic"
         "apps/c/pthread/sleep"
+        "apps/c/pthread/loadavg"
         "apps/c/pthread/pipe"
+        "apps/c/pthread/eventfd"
         "apps/c/pthread/parallel"
+        "apps/c/pthread/mutex"
+        "apps/c/pthread/rwlock"
+        "apps/c/pthread/barrier"
+        "apps/c/pthread/affinity"
+        "apps/c/pthread/sched"
+        "apps/c/rusage"
 		"apps/c/envtest"
 		"apps/c/filetest"
+		"apps/c/rlimit"
     )
 else
     test_list="$@"
@@ -33,6 +33,7 @@ int fcntl(int fd, int cmd, ... /* arg */)
 
 // TODO: remove this function in future work
 int ax_open(const char *filename, int flags, mode_t mode);
+int ax_openat(int dirfd, const char *pathname, int flags, mode_t mode);
 
 int open(const char *filename, int flags, ...)
 {
@@ -64,8 +65,16 @@ int sync_file_range(int fd, off_t pos, off_t len, unsigned flags)
 
 int openat(int dirfd, const char *pathname, int flags, ...)
 {
-    unimplemented();
-    return 0;
+    mode_t mode = 0;
+
+    if ((flags & O_CREAT) || (flags & O_TMPFILE) == O_TMPFILE) {
+        va_list ap;
+        va_start(ap, flags);
+        mode = va_arg(ap, mode_t);
+        va_end(ap);
+    }
+
+    return ax_openat(dirfd, pathname, flags, mode);
 }
 
 #endif // RUX_CONFIG_FS
@@ -8,12 +8,19 @@
  * PARTICULAR PURPOSE. See the Mulan PSL v2 for more details.
  */
 
-#include <stdio.h>
-#include <sys/ioctl.h>
+#include <stdarg.h>
+#include <sys/prctl.h>
+
+int ax_prctl(int option, unsigned long arg2, unsigned long arg3, unsigned long arg4,
+             unsigned long arg5);
 
-// TODO
 int prctl(int option, ...)
 {
-    unimplemented();
-    return 0;
+    unsigned long x[4];
+    va_list ap;
+    va_start(ap, option);
+    for (int i = 0; i < 4; i++) x[i] = va_arg(ap, unsigned long);
+    va_end(ap);
+
+    return ax_prctl(option, x[0], x[1], x[2], x[3]);
 }
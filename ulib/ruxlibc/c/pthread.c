@@ -41,13 +41,6 @@ int pthread_cancel(pthread_t t)
     return 0;
 }
 
-// TODO
-int pthread_setname_np(pthread_t thread, const char *name)
-{
-    unimplemented();
-    return 0;
-}
-
 #define DEFAULT_STACK_SIZE 131072
 #define DEFAULT_GUARD_SIZE 8192
 
@@ -77,4 +70,82 @@ int pthread_attr_setstacksize(pthread_attr_t *a, size_t size)
     return 0;
 }
 
+int pthread_mutexattr_init(pthread_mutexattr_t *a)
+{
+    *a = (pthread_mutexattr_t){0};
+    return 0;
+}
+
+int pthread_mutexattr_destroy(pthread_mutexattr_t *a)
+{
+    return 0;
+}
+
+int pthread_mutexattr_gettype(const pthread_mutexattr_t *restrict a, int *restrict type)
+{
+    *type = a->__attr & 3;
+    return 0;
+}
+
+// The type is kept in the low bits, as `pthread_mutex_init` expects.
+int pthread_mutexattr_settype(pthread_mutexattr_t *a, int type)
+{
+    if ((unsigned)type > PTHREAD_MUTEX_ERRORCHECK)
+        return EINVAL;
+    a->__attr = (a->__attr & ~3) | type;
+    return 0;
+}
+
+int pthread_rwlockattr_init(pthread_rwlockattr_t *a)
+{
+    *a = (pthread_rwlockattr_t){0};
+    return 0;
+}
+
+int pthread_rwlockattr_destroy(pthread_rwlockattr_t *a)
+{
+    return 0;
+}
+
+int pthread_rwlockattr_getpshared(const pthread_rwlockattr_t *restrict a, int *restrict pshared)
+{
+    *pshared = a->__attr[0];
+    return 0;
+}
+
+// There is a single address space, so a lock is always shared by all threads.
+int pthread_rwlockattr_setpshared(pthread_rwlockattr_t *a, int pshared)
+{
+    if (pshared != PTHREAD_PROCESS_PRIVATE && pshared != PTHREAD_PROCESS_SHARED)
+        return EINVAL;
+    a->__attr[0] = pshared;
+    return 0;
+}
+
+int pthread_barrierattr_init(pthread_barrierattr_t *a)
+{
+    *a = (pthread_barrierattr_t){0};
+    return 0;
+}
+
+int pthread_barrierattr_destroy(pthread_barrierattr_t *a)
+{
+    return 0;
+}
+
+int pthread_barrierattr_getpshared(const pthread_barrierattr_t *restrict a, int *restrict pshared)
+{
+    *pshared = a->__attr;
+    return 0;
+}
+
+// There is a single address space, so a barrier is always shared by all threads.
+int pthread_barrierattr_setpshared(pthread_barrierattr_t *a, int pshared)
+{
+    if (pshared != PTHREAD_PROCESS_PRIVATE && pshared != PTHREAD_PROCESS_SHARED)
+        return EINVAL;
+    a->__attr = pshared;
+    return 0;
+}
+
 #endif // RUX_CONFIG_MULTITASK
@@ -12,13 +12,6 @@
 #include <stdio.h>
 #include <sys/resource.h>
 
-// TODO
-int getrusage(int __who, struct rusage *__usage)
-{
-    unimplemented();
-    return 0;
-}
-
 int setpriority(int which, id_t who, int prio)
 {
     unimplemented();
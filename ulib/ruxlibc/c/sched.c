@@ -1,24 +0,0 @@
-/* Copyright (c) [2023] [Syswonder Community]
- *   [Ruxos] is licensed under Mulan PSL v2.
- *   You can use this software according to the terms and conditions of the Mulan PSL v2.
- *   You may obtain a copy of Mulan PSL v2 at:
- *               http://license.coscl.org.cn/MulanPSL2
- *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
- *   See the Mulan PSL v2 for more details.
- */
-
-#include <sched.h>
-#include <stdio.h>
-
-// TODO
-int sched_setaffinity(pid_t __pid, size_t __cpusetsize, const cpu_set_t *__cpuset)
-{
-    unimplemented();
-    return 0;
-}
-
-int sched_yield(void)
-{
-    unimplemented();
-    return 0;
-}
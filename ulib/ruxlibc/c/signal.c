@@ -12,6 +12,7 @@
 #include <signal.h>
 #include <stddef.h>
 #include <stdio.h>
+#include <unistd.h>
 
 extern int sigaction_inner(int, const struct sigaction *, struct sigaction *);
 
@@ -33,12 +34,14 @@ int sigaction(int sig, const struct sigaction *restrict act, struct sigaction *r
     return sigaction_inner(sig, act, oact);
 }
 
+#ifndef RUX_CONFIG_SIGNAL
 // TODO
 int kill(pid_t __pid, int __sig)
 {
     unimplemented();
     return 0;
 }
+#endif
 
 int sigemptyset(sigset_t *set)
 {
@@ -52,12 +55,19 @@ int sigemptyset(sigset_t *set)
     return 0;
 }
 
+#ifdef RUX_CONFIG_SIGNAL
+int raise(int __sig)
+{
+    return kill(getpid(), __sig);
+}
+#else
 // TODO
 int raise(int __sig)
 {
     unimplemented();
     return 0;
 }
+#endif
 
 int sigaddset(sigset_t *set, int sig)
 {
@@ -70,17 +80,31 @@ int sigaddset(sigset_t *set, int sig)
     return 0;
 }
 
-// TODO
+int sigismember(const sigset_t *set, int sig)
+{
+    unsigned s = sig - 1;
+    if (s >= _NSIG - 1)
+        return 0;
+    return !!(set->__bits[s / 8 / sizeof *set->__bits] &
+              1UL << (s & (8 * sizeof *set->__bits - 1)));
+}
+
+extern int rux_sigprocmask(int, const sigset_t *, sigset_t *);
+
+// The mask is shared by all threads for now.
 int pthread_sigmask(int __how, const sigset_t *restrict __newmask, sigset_t *restrict __oldmask)
 {
-    unimplemented();
-    return 0;
+    int ret = rux_sigprocmask(__how, __newmask, __oldmask);
+    return ret < 0 ? -ret : 0;
 }
 
-// TODO
 int sigprocmask(int how, const sigset_t *__restrict set, sigset_t *__restrict oldset)
 {
-    unimplemented();
+    int ret = rux_sigprocmask(how, set, oldset);
+    if (ret < 0) {
+        errno = -ret;
+        return -1;
+    }
     return 0;
 }
 
@@ -10,43 +10,11 @@
 #ifdef RUX_CONFIG_NET
 
 #include <errno.h>
-#include <fcntl.h>
 #include <stdio.h>
 #include <sys/socket.h>
 #include <sys/types.h>
 #include <string.h>
 
-int accept4(int fd, struct sockaddr *restrict addr, socklen_t *restrict len, int flg)
-{
-    if (!flg)
-        return accept(fd, addr, len);
-    if (flg & ~(SOCK_CLOEXEC | SOCK_NONBLOCK)) {
-        errno = EINVAL;
-        return -1;
-    }
-    int ret = accept(fd, addr, len);
-    if (ret < 0)
-        return ret;
-    if (flg & SOCK_CLOEXEC)
-        fcntl(ret, F_SETFD, FD_CLOEXEC);
-    if (flg & SOCK_NONBLOCK)
-        fcntl(ret, F_SETFL, O_NONBLOCK);
-    return ret;
-}
-
-int getsockopt(int fd, int level, int optname, void *restrict optval, socklen_t *restrict optlen)
-{
-    unimplemented();
-    return -1;
-}
-
-int setsockopt(int fd, int level, int optname, const void *optval, socklen_t optlen)
-{
-    unimplemented("fd: %d, level: %d, optname: %d, optval: %d, optlen: %d", fd, level, optname,
-                  *(int *)optval, optlen);
-    return 0;
-}
-
 // TODO: remove this function in future work
 ssize_t ax_sendmsg(int fd, const struct msghdr *msg, int flags);
 
@@ -77,18 +45,4 @@ ssize_t sendmsg(int fd, const struct msghdr *msg, int flags)
     return ax_sendmsg(fd, msg, flags);
 }
 
-// TODO
-ssize_t recvmsg(int sockfd, struct msghdr *msg, int flags)
-{
-    unimplemented();
-    return 0;
-}
-
-// TODO
-int socketpair(int domain, int type, int protocol, int sv[2])
-{
-    unimplemented();
-    return 0;
-}
-
 #endif // RUX_CONFIG_NET
@@ -159,27 +159,6 @@ time_t time(time_t *t)
     return ret;
 }
 
-int gettimeofday(struct timeval *tv, struct timezone *tz)
-{
-    struct timespec ts;
-    if (!tv)
-        return 0;
-    clock_gettime(CLOCK_REALTIME, &ts);
-    tv->tv_sec = ts.tv_sec;
-    tv->tv_usec = (int)ts.tv_nsec / 1000;
-    return 0;
-}
-
-int settimeofday(const struct timeval *tv, const struct timezone *_tz)
-{
-    if (!tv)
-        return 0;
-    if (tv->tv_usec >= 1000000ULL)
-        return -EINVAL;
-    return clock_settime(CLOCK_REALTIME,
-                         &((struct timespec){.tv_sec = tv->tv_sec, .tv_nsec = tv->tv_usec * 1000}));
-}
-
 // TODO:
 int utimes(const char *filename, const struct timeval times[2])
 {
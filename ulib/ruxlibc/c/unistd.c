@@ -12,8 +12,10 @@
 #include <fcntl.h>
 #include <stdio.h>
 #include <stdlib.h>
+#include <sys/ioctl.h>
 #include <sys/types.h>
 #include <sys/utsname.h>
+#include <termios.h>
 #include <time.h>
 #include <unistd.h>
 
@@ -59,11 +61,18 @@ int setgid(gid_t gid)
     return 0;
 }
 
-// TODO
 int isatty(int fd)
 {
-    unimplemented();
+#ifdef RUX_CONFIG_FD
+    struct winsize wsz;
+    return ioctl(fd, TIOCGWINSZ, &wsz) == 0;
+#else
+    // only the console is there without file descriptors
+    if (fd >= 0 && fd <= 2)
+        return 1;
+    errno = EBADF;
     return 0;
+#endif
 }
 
 // TODO
@@ -103,13 +112,6 @@ int gethostname(char *name, size_t len)
     return 0;
 }
 
-// TODO
-int chown(const char *path, uid_t owner, gid_t group)
-{
-    unimplemented();
-    return 0;
-}
-
 unsigned int sleep(unsigned int seconds)
 {
     struct timespec ts;
@@ -137,55 +139,6 @@ int access(const char *pathname, int mode)
     return 0;
 }
 
-// TODO:
-ssize_t readlink(const char *path, char *buf, size_t bufsiz)
-{
-    unimplemented();
-    return 0;
-}
-
-// TODO:
-int fsync(int fd)
-{
-    unimplemented();
-    return 0;
-}
-
-// TODO
-int fdatasync(int __fildes)
-{
-    unimplemented();
-    return 0;
-}
-
-// TODO:
-int fchown(int fd, uid_t owner, gid_t group)
-{
-    unimplemented("owner: %x group: %x", owner, group);
-    return 0;
-}
-
-// TODO:
-int ftruncate(int fd, off_t length)
-{
-    unimplemented();
-    return 0;
-}
-
-// TODO
-int chdir(const char *__path)
-{
-    unimplemented();
-    return 0;
-}
-
-// TODO
-int truncate(const char *path, off_t length)
-{
-    unimplemented();
-    return 0;
-}
-
 #endif // RUX_CONFIG_FS
 
 #ifdef RUX_CONFIG_PIPE
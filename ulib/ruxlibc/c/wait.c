@@ -7,20 +7,21 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-#include <stdio.h>
+#include <stddef.h>
 #include <sys/resource.h>
 #include <sys/wait.h>
 
-// TODO
+pid_t wait(int *status)
+{
+    return wait4(-1, status, 0, NULL);
+}
+
 pid_t waitpid(pid_t pid, int *status, int options)
 {
-    unimplemented();
-    return 0;
+    return wait4(pid, status, options, NULL);
 }
 
-// TODO
-pid_t wait3(int *status, int _options, struct rusage *usage)
+pid_t wait3(int *status, int options, struct rusage *usage)
 {
-    unimplemented();
-    return 0;
+    return wait4(-1, status, options, usage);
 }
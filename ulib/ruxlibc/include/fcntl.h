@@ -23,16 +23,24 @@
 #define O_DSYNC     010000
 #define O_SYNC      04010000
 #define O_RSYNC     04010000
-#define O_DIRECTORY 040000
-#define O_NOFOLLOW  0400000
 #define O_CLOEXEC   02000000
 
-#define O_ASYNC     020000
+#if defined(__aarch64__)
+#define O_DIRECTORY 040000
+#define O_NOFOLLOW  0100000
+#define O_DIRECT    0200000
+#define O_LARGEFILE 0400000
+#else
+#define O_DIRECTORY 0200000
+#define O_NOFOLLOW  0400000
 #define O_DIRECT    040000
 #define O_LARGEFILE 0100000
+#endif
+
+#define O_ASYNC     020000
 #define O_NOATIME   01000000
 #define O_PATH      010000000
-#define O_TMPFILE   020200000
+#define O_TMPFILE   (020000000 | O_DIRECTORY)
 #define O_NDELAY    O_NONBLOCK
 
 #define O_SEARCH   O_PATH
@@ -111,6 +119,10 @@
 #define AT_FDCWD      (-100)
 #define AT_EMPTY_PATH 0x1000
 #define AT_REMOVEDIR 0x200
+#define AT_SYMLINK_FOLLOW 0x400
+
+#define FALLOC_FL_KEEP_SIZE  1
+#define FALLOC_FL_PUNCH_HOLE 2
 
 #define SYNC_FILE_RANGE_WAIT_BEFORE 1
 #define SYNC_FILE_RANGE_WRITE       2
@@ -118,6 +130,19 @@
 
 #define loff_t off_t
 
+#define RESOLVE_NO_XDEV       0x01
+#define RESOLVE_NO_MAGICLINKS 0x02
+#define RESOLVE_NO_SYMLINKS   0x04
+#define RESOLVE_BENEATH       0x08
+#define RESOLVE_IN_ROOT       0x10
+#define RESOLVE_CACHED        0x20
+
+struct open_how {
+    uint64_t flags;
+    uint64_t mode;
+    uint64_t resolve;
+};
+
 struct flock {
     short l_type;
     short l_whence;
@@ -129,8 +154,11 @@ struct flock {
 int fcntl(int fd, int cmd, ... /* arg */);
 int posix_fadvise(int __fd, unsigned long __offset, unsigned long __len, int __advise);
 int sync_file_range(int, off_t, off_t, unsigned);
+int fallocate(int, int, off_t, off_t);
+int posix_fallocate(int, off_t, off_t);
 
 int open(const char *filename, int flags, ...);
 int openat(int, const char *, int, ...);
+int openat2(int, const char *, struct open_how *, size_t);
 
 #endif
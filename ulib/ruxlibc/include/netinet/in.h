@@ -61,6 +61,22 @@ uint16_t ntohs(uint16_t);
 #define IPPROTO_MPTCP    262
 #define IPPROTO_MAX      263
 
+#define IP_TOS          1
+#define IP_TTL          2
+#define IP_HDRINCL      3
+#define IP_OPTIONS      4
+#define IP_ROUTER_ALERT 5
+#define IP_RECVOPTS     6
+#define IP_RETOPTS      7
+#define IP_PKTINFO      8
+#define IP_PKTOPTIONS   9
+#define IP_PMTUDISC     10
+#define IP_MTU_DISCOVER 10
+#define IP_RECVERR      11
+#define IP_RECVTTL      12
+#define IP_RECVTOS      13
+#define IP_MTU          14
+
 #define IPV6_ADDRFORM             1
 #define IPV6_2292PKTINFO          2
 #define IPV6_2292HOPOPTS          3
@@ -186,6 +186,7 @@ int sigaction(int, const struct sigaction *__restrict, struct sigaction *__restr
 int sigemptyset(sigset_t *);
 int raise(int);
 int sigaddset(sigset_t *, int);
+int sigismember(const sigset_t *, int);
 int pthread_sigmask(int, const sigset_t *__restrict, sigset_t *__restrict);
 
 int kill(pid_t, int);
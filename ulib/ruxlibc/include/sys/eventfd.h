@@ -20,4 +20,8 @@ typedef uint64_t eventfd_t;
 #define EFD_CLOEXEC   O_CLOEXEC
 #define EFD_NONBLOCK  O_NONBLOCK
 
+int eventfd(unsigned int, int);
+int eventfd_read(int, eventfd_t *);
+int eventfd_write(int, eventfd_t);
+
 #endif /* sys/eventfd.h */
@@ -20,6 +20,10 @@ struct rlimit {
     rlim_t rlim_max;
 };
 
+#define RLIM_INFINITY  (~0ULL)
+#define RLIM_SAVED_CUR RLIM_INFINITY
+#define RLIM_SAVED_MAX RLIM_INFINITY
+
 #define PRIO_PROCESS 0
 #define PRIO_PGRP    1
 #define PRIO_USER    2
@@ -46,6 +50,7 @@ struct rlimit {
 
 #define RUSAGE_SELF     0
 #define RUSAGE_CHILDREN -1
+#define RUSAGE_THREAD   1
 
 struct rusage {
     struct timeval ru_utime;
@@ -64,6 +64,7 @@ struct sockaddr_storage {
 
 int socket(int, int, int);
 int shutdown(int, int);
+int sockatmark(int);
 
 int bind(int, const struct sockaddr *, socklen_t);
 int connect(int, const struct sockaddr *, socklen_t);
@@ -96,6 +97,14 @@ int socketpair (int, int, int, int [2]);
 #define MSG_CTRUNC    0x0008
 #define MSG_PROXY     0x0010
 #define MSG_TRUNC     0x0020
+#define MSG_DONTWAIT  0x0040
+#define MSG_EOR       0x0080
+#define MSG_WAITALL   0x0100
+#define MSG_FIN       0x0200
+#define MSG_SYN       0x0400
+#define MSG_CONFIRM   0x0800
+#define MSG_RST       0x1000
+#define MSG_ERRQUEUE  0x2000
 
 #define SO_BINDTODEVICE            25
 #define SO_ATTACH_FILTER           26
@@ -137,6 +146,7 @@ int socketpair (int, int, int, int [2]);
 #define SO_BUSY_POLL_BUDGET        70
 
 #define MSG_NOSIGNAL 0x4000
+#define MSG_CMSG_CLOEXEC 0x40000000
 
 #define SHUT_RD   0
 #define SHUT_WR   1
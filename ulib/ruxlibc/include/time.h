@@ -14,12 +14,23 @@
 #include <stddef.h>
 #include <sys/time.h>
 
-#define CLOCK_REALTIME  0
-#define CLOCK_MONOTONIC 1
-#define CLOCKS_PER_SEC  1000000L
+#define CLOCK_REALTIME           0
+#define CLOCK_MONOTONIC          1
+#define CLOCK_PROCESS_CPUTIME_ID 2
+#define CLOCK_THREAD_CPUTIME_ID  3
+#define CLOCK_MONOTONIC_RAW      4
+#define CLOCK_REALTIME_COARSE    5
+#define CLOCK_MONOTONIC_COARSE   6
+#define CLOCK_BOOTTIME           7
+#define CLOCKS_PER_SEC           1000000L
 
 #define __tm_gmtoff tm_gmtoff
 
+struct itimerspec {
+    struct timespec it_interval;
+    struct timespec it_value;
+};
+
 struct tm {
     int tm_sec;   /* seconds of minute */
     int tm_min;   /* minutes of hour */
@@ -51,6 +62,7 @@ void tzset(void);
 
 int nanosleep(const struct timespec *requested_time, struct timespec *remaining);
 int clock_gettime(clockid_t _clk, struct timespec *ts);
+int clock_getres(clockid_t, struct timespec *);
 int clock_settime(clockid_t, const struct timespec *);
 
 #endif // __TIME_H__
@@ -89,6 +89,7 @@ pid_t getpgid(pid_t);
 int setpgid(pid_t, pid_t);
 pid_t setsid(void);
 pid_t getsid(pid_t);
+pid_t gettid(void);
 char *ttyname(int);
 int ttyname_r(int, char *, size_t);
 int isatty(int);
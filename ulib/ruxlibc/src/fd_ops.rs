@@ -10,7 +10,10 @@
 use crate::{ctypes, utils::e};
 use axerrno::LinuxError;
 use core::ffi::c_int;
-use ruxos_posix_api::{sys_close, sys_dup, sys_dup2, sys_fcntl};
+use ruxos_posix_api::{
+    sys_close, sys_dup, sys_dup2, sys_eventfd2, sys_fallocate, sys_fcntl, sys_ftruncate,
+    sys_timerfd_create, sys_timerfd_gettime, sys_timerfd_settime,
+};
 
 /// Close a file by `fd`.
 #[no_mangle]
@@ -30,6 +33,68 @@ pub unsafe extern "C" fn dup2(old_fd: c_int, new_fd: c_int) -> c_int {
     e(sys_dup2(old_fd, new_fd))
 }
 
+/// Create a file descriptor for event notification.
+#[no_mangle]
+pub unsafe extern "C" fn eventfd(initval: core::ffi::c_uint, flags: c_int) -> c_int {
+    e(sys_eventfd2(initval, flags))
+}
+
+/// Create a timer that notifies via a file descriptor.
+#[no_mangle]
+pub unsafe extern "C" fn timerfd_create(clockid: c_int, flags: c_int) -> c_int {
+    e(sys_timerfd_create(clockid, flags))
+}
+
+/// Arm or disarm the timer referred to by `fd`.
+#[no_mangle]
+pub unsafe extern "C" fn timerfd_settime(
+    fd: c_int,
+    flags: c_int,
+    new_value: *const ctypes::itimerspec,
+    old_value: *mut ctypes::itimerspec,
+) -> c_int {
+    e(sys_timerfd_settime(fd, flags, new_value, old_value))
+}
+
+/// Get the current setting of the timer referred to by `fd`.
+#[no_mangle]
+pub unsafe extern "C" fn timerfd_gettime(fd: c_int, curr_value: *mut ctypes::itimerspec) -> c_int {
+    e(sys_timerfd_gettime(fd, curr_value))
+}
+
+/// Truncate or extend a file to `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ftruncate(fd: c_int, length: ctypes::off_t) -> c_int {
+    e(sys_ftruncate(fd, length))
+}
+
+/// Allocate the storage of the `len` bytes at `offset` of a file.
+#[no_mangle]
+pub unsafe extern "C" fn fallocate(
+    fd: c_int,
+    mode: c_int,
+    offset: ctypes::off_t,
+    len: ctypes::off_t,
+) -> c_int {
+    e(sys_fallocate(fd, mode, offset, len))
+}
+
+/// Allocate the storage of the `len` bytes at `offset` of a file, extending
+/// it if needed.
+///
+/// Unlike `fallocate`, it returns the error number instead of setting `errno`.
+#[no_mangle]
+pub unsafe extern "C" fn posix_fallocate(
+    fd: c_int,
+    offset: ctypes::off_t,
+    len: ctypes::off_t,
+) -> c_int {
+    match sys_fallocate(fd, 0, offset, len) {
+        ret if ret < 0 => -ret,
+        _ => 0,
+    }
+}
+
 /// Duplicate a file descriptor, the caller can force the close-on-exec flag to
 /// be set for the new file descriptor by specifying `O_CLOEXEC` in flags.
 ///
@@ -55,8 +120,6 @@ pub unsafe extern "C" fn dup3(old_fd: c_int, new_fd: c_int, flags: c_int) -> c_i
 }
 
 /// Manipulate file descriptor.
-///
-/// TODO: `SET/GET` command is ignored
 #[no_mangle]
 pub unsafe extern "C" fn ax_fcntl(fd: c_int, cmd: c_int, arg: usize) -> c_int {
     e(sys_fcntl(fd, cmd, arg))
@@ -10,8 +10,10 @@
 use core::ffi::{c_char, c_int};
 
 use ruxos_posix_api::{
-    sys_fstat, sys_getcwd, sys_lseek, sys_lstat, sys_mkdir, sys_open, sys_rename, sys_rmdir,
-    sys_stat, sys_unlink,
+    sys_chdir, sys_chmod, sys_chown, sys_fchdir, sys_fchmod, sys_fchown, sys_fdatasync, sys_fstat,
+    sys_fstatat, sys_fsync, sys_getcwd, sys_lchown, sys_link, sys_linkat, sys_lseek, sys_lstat,
+    sys_mkdir, sys_mount, sys_open, sys_openat, sys_openat2, sys_readlinkat, sys_rename, sys_rmdir,
+    sys_stat, sys_statfs, sys_symlinkat, sys_truncate, sys_umount2, sys_unlink,
 };
 
 use crate::{ctypes, utils::e};
@@ -29,6 +31,32 @@ pub unsafe extern "C" fn ax_open(
     e(sys_open(filename, flags, mode))
 }
 
+/// Open a file by `pathname` relative to the directory `dirfd`, and insert it
+/// into the file descriptor table.
+///
+/// Return its index in the file table (`fd`).
+#[no_mangle]
+pub unsafe extern "C" fn ax_openat(
+    dirfd: c_int,
+    pathname: *const c_char,
+    flags: c_int,
+    mode: ctypes::mode_t,
+) -> c_int {
+    e(sys_openat(dirfd as usize, pathname, flags, mode))
+}
+
+/// Open a file under `dirfd` with the flags, the mode and the restrictions on
+/// the resolution of the path in `how`.
+#[no_mangle]
+pub unsafe extern "C" fn openat2(
+    dirfd: c_int,
+    pathname: *const c_char,
+    how: *const ctypes::open_how,
+    size: ctypes::size_t,
+) -> c_int {
+    e(sys_openat2(dirfd, pathname, how, size))
+}
+
 /// Set the position of the file indicated by `fd`.
 ///
 /// Return its position after seek.
@@ -61,6 +89,122 @@ pub unsafe extern "C" fn lstat(path: *const c_char, buf: *mut ctypes::stat) -> c
     e(sys_lstat(path, buf) as _)
 }
 
+/// Get the attributes of the filesystem which contains `path`, e.g. its free
+/// space, and write them into `buf`.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn statfs(path: *const c_char, buf: *mut ctypes::statfs) -> c_int {
+    e(sys_statfs(path, buf))
+}
+
+/// Get the metadata of the file at `path` relative to `fd` and write into
+/// `buf`, without following a symbolic link if `flag` has
+/// `AT_SYMLINK_NOFOLLOW`.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn fstatat(
+    fd: c_int,
+    path: *const c_char,
+    buf: *mut ctypes::stat,
+    flag: c_int,
+) -> c_int {
+    e(sys_fstatat(fd, path, buf, flag))
+}
+
+/// Changes the permission mode of the file at `path`.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn chmod(path: *const c_char, mode: ctypes::mode_t) -> c_int {
+    e(sys_chmod(path, mode))
+}
+
+/// Changes the permission mode of the file referred to by `fd`.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn fchmod(fd: c_int, mode: ctypes::mode_t) -> c_int {
+    e(sys_fchmod(fd, mode))
+}
+
+/// Changes the owner and group of the file at `path`.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn chown(
+    path: *const c_char,
+    owner: ctypes::uid_t,
+    group: ctypes::gid_t,
+) -> c_int {
+    e(sys_chown(path, owner, group))
+}
+
+/// Changes the owner and group of the file at `path`, without following it
+/// if it is a symbolic link.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn lchown(
+    path: *const c_char,
+    owner: ctypes::uid_t,
+    group: ctypes::gid_t,
+) -> c_int {
+    e(sys_lchown(path, owner, group))
+}
+
+/// Changes the owner and group of the file referred to by `fd`.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn fchown(fd: c_int, owner: ctypes::uid_t, group: ctypes::gid_t) -> c_int {
+    e(sys_fchown(fd, owner, group))
+}
+
+/// Creates a symbolic link `linkpath` which points to `target`.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn symlink(target: *const c_char, linkpath: *const c_char) -> c_int {
+    e(sys_symlinkat(target, ctypes::AT_FDCWD, linkpath))
+}
+
+/// Create a hard link `newpath` to the file at `oldpath`.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn link(oldpath: *const c_char, newpath: *const c_char) -> c_int {
+    e(sys_link(oldpath, newpath))
+}
+
+/// Create a hard link `newpath` to the file at `oldpath`, or to the file
+/// `olddirfd` if `oldpath` is empty and `flags` has `AT_EMPTY_PATH`.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn linkat(
+    olddirfd: c_int,
+    oldpath: *const c_char,
+    newdirfd: c_int,
+    newpath: *const c_char,
+    flags: c_int,
+) -> c_int {
+    e(sys_linkat(olddirfd, oldpath, newdirfd, newpath, flags))
+}
+
+/// Read the target of the symbolic link `path` into `buf`.
+///
+/// Return the number of bytes placed in `buf`.
+#[no_mangle]
+pub unsafe extern "C" fn readlink(
+    path: *const c_char,
+    buf: *mut c_char,
+    bufsize: usize,
+) -> ctypes::ssize_t {
+    e(sys_readlinkat(ctypes::AT_FDCWD, path, buf, bufsize) as _) as _
+}
+
 /// Get the path of the current directory.
 #[no_mangle]
 pub unsafe extern "C" fn getcwd(buf: *mut c_char, size: usize) -> *mut c_char {
@@ -79,6 +223,22 @@ pub unsafe extern "C" fn getcwd(buf: *mut c_char, size: usize) -> *mut c_char {
     buf
 }
 
+/// Change the current directory to `path`.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn chdir(path: *const c_char) -> c_int {
+    e(sys_chdir(path))
+}
+
+/// Change the current directory to the directory referred to by `fd`.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn fchdir(fd: c_int) -> c_int {
+    e(sys_fchdir(fd))
+}
+
 /// Rename `old` to `new`
 /// If new exists, it is first removed.
 ///
@@ -102,6 +262,48 @@ pub unsafe extern "C" fn unlink(pathname: *const c_char) -> c_int {
     e(sys_unlink(pathname))
 }
 
+/// Write the data and the metadata of the file `fd` back to the disk.
+#[no_mangle]
+pub unsafe extern "C" fn fsync(fd: c_int) -> c_int {
+    e(sys_fsync(fd))
+}
+
+/// Write the data of the file `fd` back to the disk.
+#[no_mangle]
+pub unsafe extern "C" fn fdatasync(fd: c_int) -> c_int {
+    e(sys_fdatasync(fd))
+}
+
+/// Truncate or extend a file to `length` bytes, given its path.
+#[no_mangle]
+pub unsafe extern "C" fn truncate(path: *const c_char, length: ctypes::off_t) -> c_int {
+    e(sys_truncate(path, length))
+}
+
+/// Mounts a new filesystem of the type `fstype` on the directory `target`.
+#[no_mangle]
+pub unsafe extern "C" fn mount(
+    source: *const c_char,
+    target: *const c_char,
+    fstype: *const c_char,
+    flags: core::ffi::c_ulong,
+    data: *const core::ffi::c_void,
+) -> c_int {
+    e(sys_mount(source, target, fstype, flags, data))
+}
+
+/// Unmounts the filesystem mounted on `target`.
+#[no_mangle]
+pub unsafe extern "C" fn umount(target: *const c_char) -> c_int {
+    e(sys_umount2(target, 0))
+}
+
+/// Unmounts the filesystem mounted on `target`, with `flags`.
+#[no_mangle]
+pub unsafe extern "C" fn umount2(target: *const c_char, flags: c_int) -> c_int {
+    e(sys_umount2(target, flags))
+}
+
 /// Creates a new directory
 #[no_mangle]
 pub unsafe extern "C" fn mkdir(pathname: *const c_char, mode: ctypes::mode_t) -> c_int {
@@ -86,6 +86,7 @@ mod io;
 mod mktime;
 mod rand;
 mod resource;
+mod sched;
 mod setjmp;
 mod signal;
 mod string;
@@ -100,19 +101,27 @@ pub use self::io::{read, writev};
 pub use self::errno::strerror;
 pub use self::mktime::mktime;
 pub use self::rand::{getrandom, rand, random, srand};
-pub use self::resource::{getrlimit, setrlimit};
+pub use self::resource::{getrlimit, getrusage, setrlimit};
+pub use self::sched::sched_yield;
 pub use self::setjmp::{longjmp, setjmp};
 pub use self::string::{strlen, strnlen};
 pub use self::sys::sysconf;
-pub use self::time::{clock_gettime, nanosleep};
-pub use self::unistd::{abort, exit, getpid};
+pub use self::time::{clock_gettime, gettimeofday, nanosleep, settimeofday};
+pub use self::unistd::{abort, ax_prctl, exit, getpid, umask, wait4};
 
 #[cfg(feature = "alloc")]
 pub use self::env::{getenv, setenv, unsetenv};
 #[cfg(feature = "fd")]
-pub use self::fd_ops::{ax_fcntl, close, dup, dup2, dup3};
+pub use self::fd_ops::{
+    ax_fcntl, close, dup, dup2, dup3, eventfd, fallocate, ftruncate, posix_fallocate,
+    timerfd_create, timerfd_gettime, timerfd_settime,
+};
 #[cfg(feature = "fs")]
-pub use self::fs::{ax_open, fstat, getcwd, lseek, lstat, mkdir, rename, rmdir, stat, unlink};
+pub use self::fs::{
+    ax_open, ax_openat, chdir, chmod, chown, fchdir, fchmod, fchown, fdatasync, fstat, fstatat,
+    fsync, getcwd, lchown, link, linkat, lseek, lstat, mkdir, mount, openat2, readlink, rename,
+    rmdir, stat, statfs, symlink, truncate, umount, umount2, unlink,
+};
 #[cfg(feature = "fd")]
 pub use self::io::rux_ioctl;
 #[cfg(feature = "poll")]
@@ -127,8 +136,9 @@ pub use self::malloc::{free, malloc};
 pub use self::mmap::{mmap, munmap};
 #[cfg(feature = "net")]
 pub use self::net::{
-    accept, ax_sendmsg, bind, connect, freeaddrinfo, getaddrinfo, getpeername, getsockname, listen,
-    recv, recvfrom, send, sendto, shutdown, socket,
+    accept, accept4, ax_sendmsg, bind, connect, freeaddrinfo, getaddrinfo, getpeername,
+    getsockname, getsockopt, listen, recv, recvfrom, recvmsg, send, sendto, setsockopt, shutdown,
+    sockatmark, socket,
 };
 #[cfg(feature = "pipe")]
 pub use self::pipe::pipe;
@@ -137,11 +147,24 @@ pub use self::pthread::{
     pthread_cond_broadcast, pthread_cond_init, pthread_cond_signal, pthread_cond_wait,
 };
 #[cfg(feature = "multitask")]
-pub use self::pthread::{pthread_create, pthread_exit, pthread_join, pthread_self};
+pub use self::pthread::{
+    pthread_create, pthread_exit, pthread_getname_np, pthread_join, pthread_self,
+    pthread_setname_np,
+};
 #[cfg(feature = "multitask")]
 pub use self::pthread::{
     pthread_mutex_init, pthread_mutex_lock, pthread_mutex_trylock, pthread_mutex_unlock,
 };
+#[cfg(feature = "multitask")]
+pub use self::pthread::{
+    pthread_rwlock_init, pthread_rwlock_rdlock, pthread_rwlock_tryrdlock, pthread_rwlock_trywrlock,
+    pthread_rwlock_unlock, pthread_rwlock_wrlock,
+};
+#[cfg(feature = "multitask")]
+pub use self::sched::{
+    sched_get_priority_max, sched_get_priority_min, sched_getaffinity, sched_getparam,
+    sched_getscheduler, sched_setaffinity, sched_setscheduler,
+};
 #[cfg(feature = "alloc")]
 pub use self::strftime::strftime;
 #[cfg(feature = "fp_simd")]
@@ -7,7 +7,10 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use crate::ctypes;
+use crate::{
+    ctypes,
+    utils::{e, e_ptr},
+};
 use core::ffi::{c_int, c_void};
 
 use ruxos_posix_api::{sys_madvise, sys_mmap, sys_mprotect, sys_mremap, sys_msync, sys_munmap};
@@ -22,7 +25,7 @@ pub unsafe extern "C" fn mmap(
     fid: c_int,
     offset: ctypes::off_t,
 ) -> *mut c_void {
-    sys_mmap(addr, len, prot, flags, fid, offset)
+    e_ptr(sys_mmap(addr, len, prot, flags, fid, offset))
 }
 
 /// Unmap a range address of memory.
@@ -46,19 +49,17 @@ pub unsafe extern "C" fn mremap(
     flags: c_int,
     new_addr: *mut c_void,
 ) -> *mut c_void {
-    sys_mremap(old_addr, old_size, new_size, flags, new_addr)
+    e_ptr(sys_mremap(old_addr, old_size, new_size, flags, new_addr))
 }
 
 /// Change the accessiblity for already mapped memory.
 #[no_mangle]
 pub unsafe extern "C" fn mprotect(addr: *mut c_void, len: ctypes::size_t, flags: c_int) -> c_int {
-    sys_mprotect(addr, len, flags)
+    e(sys_mprotect(addr, len, flags))
 }
 
 /// Advise the operating system about the expected behavior of a specific region of memory.
-///
-/// Note: Unimplement yet.
 #[no_mangle]
 pub unsafe extern "C" fn madvise(addr: *mut c_void, len: ctypes::size_t, advice: c_int) -> c_int {
-    sys_madvise(addr, len, advice)
+    e(sys_madvise(addr, len, advice))
 }
@@ -7,6 +7,7 @@
  *   See the Mulan PSL v2 for more details.
  */
 
+use axerrno::LinuxError;
 use core::ffi::{c_char, c_int, c_void};
 use ruxos_posix_api as api;
 
@@ -20,6 +21,20 @@ pub unsafe extern "C" fn socket(domain: c_int, socktype: c_int, protocol: c_int)
     e(api::sys_socket(domain, socktype, protocol))
 }
 
+/// Create a pair of connected sockets, only of `AF_UNIX`.
+///
+/// Return 0 if success, the sockets are stored in `sv`.
+#[no_mangle]
+pub unsafe extern "C" fn socketpair(
+    domain: c_int,
+    socktype: c_int,
+    protocol: c_int,
+    sv: *mut c_int,
+) -> c_int {
+    let sv = unsafe { core::slice::from_raw_parts_mut(sv, 2) };
+    e(api::sys_socketpair(domain, socktype, protocol, sv))
+}
+
 /// Bind a address to a socket.
 ///
 /// Return 0 if success.
@@ -110,10 +125,7 @@ pub unsafe extern "C" fn recv(
 ///
 /// Return 0 if success.
 #[no_mangle]
-pub unsafe extern "C" fn listen(
-    socket_fd: c_int,
-    backlog: c_int, // currently not used
-) -> c_int {
+pub unsafe extern "C" fn listen(socket_fd: c_int, backlog: c_int) -> c_int {
     e(api::sys_listen(socket_fd, backlog))
 }
 
@@ -129,6 +141,20 @@ pub unsafe extern "C" fn accept(
     e(api::sys_accept(socket_fd, socket_addr, socket_len))
 }
 
+/// Accept for connections on a socket, with `SOCK_NONBLOCK` and `SOCK_CLOEXEC`
+/// applied to the accepted socket.
+///
+/// Return file descriptor for the accepted socket if success.
+#[no_mangle]
+pub unsafe extern "C" fn accept4(
+    socket_fd: c_int,
+    socket_addr: *mut ctypes::sockaddr,
+    socket_len: *mut ctypes::socklen_t,
+    flags: c_int,
+) -> c_int {
+    e(api::sys_accept4(socket_fd, socket_addr, socket_len, flags))
+}
+
 /// Shut down a full-duplex connection.
 ///
 /// Return 0 if success.
@@ -140,9 +166,17 @@ pub unsafe extern "C" fn shutdown(
     e(api::sys_shutdown(socket_fd, flag))
 }
 
+/// Tell whether the next byte to read on a TCP socket is the urgent byte.
+///
+/// Return 1 at the mark, 0 otherwise.
+#[no_mangle]
+pub unsafe extern "C" fn sockatmark(socket_fd: c_int) -> c_int {
+    e(api::sys_sockatmark(socket_fd))
+}
+
 /// Query addresses for a domain name.
 ///
-/// Return address number if success.
+/// Return 0 if success, or an `EAI_*` error code. `errno` is not changed.
 #[no_mangle]
 pub unsafe extern "C" fn getaddrinfo(
     nodename: *const c_char,
@@ -150,11 +184,28 @@ pub unsafe extern "C" fn getaddrinfo(
     hints: *const ctypes::addrinfo,
     res: *mut *mut ctypes::addrinfo,
 ) -> c_int {
-    let ret = e(api::sys_getaddrinfo(nodename, servname, hints, res));
-    match ret {
-        r if r < 0 => ctypes::EAI_FAIL,
-        0 => ctypes::EAI_NONAME,
-        _ => 0,
+    let ret = api::sys_getaddrinfo(nodename, servname, hints, res);
+    if ret > 0 {
+        return 0;
+    }
+    let is = |err: LinuxError| ret == -(err as c_int);
+    if ret == 0 || is(LinuxError::ENOENT) || is(LinuxError::ECONNREFUSED) || is(LinuxError::EINVAL)
+    {
+        // no address found, or the name does not exist
+        ctypes::EAI_NONAME
+    } else if is(LinuxError::ESRCH) {
+        ctypes::EAI_SERVICE
+    } else if is(LinuxError::EAFNOSUPPORT) {
+        ctypes::EAI_FAMILY
+    } else if is(LinuxError::ESOCKTNOSUPPORT) {
+        ctypes::EAI_SOCKTYPE
+    } else if is(LinuxError::EAGAIN) || is(LinuxError::EBUSY) || is(LinuxError::ETIMEDOUT) {
+        // no nameserver answered
+        ctypes::EAI_AGAIN
+    } else if is(LinuxError::ENOMEM) {
+        ctypes::EAI_MEMORY
+    } else {
+        ctypes::EAI_FAIL
     }
 }
 
@@ -164,6 +215,34 @@ pub unsafe extern "C" fn freeaddrinfo(res: *mut ctypes::addrinfo) {
     api::sys_freeaddrinfo(res);
 }
 
+/// Set the option `optname` at `level` of a socket.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn setsockopt(
+    sock_fd: c_int,
+    level: c_int,
+    optname: c_int,
+    optval: *const c_void,
+    optlen: ctypes::socklen_t,
+) -> c_int {
+    e(api::sys_setsockopt(sock_fd, level, optname, optval, optlen))
+}
+
+/// Get the option `optname` at `level` of a socket.
+///
+/// Return 0 if success.
+#[no_mangle]
+pub unsafe extern "C" fn getsockopt(
+    sock_fd: c_int,
+    level: c_int,
+    optname: c_int,
+    optval: *mut c_void,
+    optlen: *mut ctypes::socklen_t,
+) -> c_int {
+    e(api::sys_getsockopt(sock_fd, level, optname, optval, optlen))
+}
+
 /// Get current address to which the socket sockfd is bound.
 #[no_mangle]
 pub unsafe extern "C" fn getsockname(
@@ -196,3 +275,16 @@ pub unsafe extern "C" fn ax_sendmsg(
 ) -> ctypes::ssize_t {
     e(api::sys_sendmsg(socket_fd, msg, flags) as _) as _
 }
+
+/// Receive a message on a socket.
+/// The data received is scattered over the buffers of msg.msg_iov.
+///
+/// Return the number of bytes received if success.
+#[no_mangle]
+pub unsafe extern "C" fn recvmsg(
+    socket_fd: c_int,
+    msg: *mut ctypes::msghdr,
+    flags: c_int,
+) -> ctypes::ssize_t {
+    e(api::sys_recvmsg(socket_fd, msg, flags) as _) as _
+}
@@ -8,7 +8,7 @@
  */
 
 use crate::{ctypes, utils::e};
-use core::ffi::{c_int, c_void};
+use core::ffi::{c_char, c_int, c_uint, c_void};
 use ruxos_posix_api as api;
 
 /// Returns the `pthread` struct of current thread.
@@ -46,6 +46,25 @@ pub unsafe extern "C" fn pthread_join(
     e(api::sys_pthread_join(thread, retval))
 }
 
+/// Set the name of the given thread, truncated to 15 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_setname_np(
+    thread: ctypes::pthread_t,
+    name: *const c_char,
+) -> c_int {
+    e(api::sys_pthread_setname_np(thread, name))
+}
+
+/// Get the name of the given thread.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_getname_np(
+    thread: ctypes::pthread_t,
+    buf: *mut c_char,
+    len: ctypes::size_t,
+) -> c_int {
+    e(api::sys_pthread_getname_np(thread, buf, len))
+}
+
 /// Initialize a mutex.
 #[no_mangle]
 pub unsafe extern "C" fn pthread_mutex_init(
@@ -80,6 +99,80 @@ pub unsafe extern "C" fn pthread_mutex_unlock(mutex: *mut ctypes::pthread_mutex_
     e(api::sys_pthread_mutex_unlock(mutex))
 }
 
+/// Initialize a read/write lock.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_init(
+    rwlock: *mut ctypes::pthread_rwlock_t,
+    attr: *const ctypes::pthread_rwlockattr_t,
+) -> c_int {
+    e(api::sys_pthread_rwlock_init(rwlock, attr))
+}
+
+/// Destroy a read/write lock.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_destroy(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    e(api::sys_pthread_rwlock_destroy(rwlock))
+}
+
+/// Lock the given read/write lock for reading.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_rdlock(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    e(api::sys_pthread_rwlock_rdlock(rwlock))
+}
+
+/// Lock the given read/write lock for reading. If it is held by a writer, or a writer is
+/// waiting for it, it returns immediatly with the error code EBUSY.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_tryrdlock(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    e(api::sys_pthread_rwlock_tryrdlock(rwlock))
+}
+
+/// Lock the given read/write lock for writing.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_wrlock(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    e(api::sys_pthread_rwlock_wrlock(rwlock))
+}
+
+/// Lock the given read/write lock for writing. If it is already locked, it returns
+/// immediatly with the error code EBUSY.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_trywrlock(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    e(api::sys_pthread_rwlock_trywrlock(rwlock))
+}
+
+/// Unlock the given read/write lock.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_unlock(rwlock: *mut ctypes::pthread_rwlock_t) -> c_int {
+    e(api::sys_pthread_rwlock_unlock(rwlock))
+}
+
+/// Initialize a barrier for `count` threads.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_barrier_init(
+    barrier: *mut ctypes::pthread_barrier_t,
+    attr: *const ctypes::pthread_barrierattr_t,
+    count: c_uint,
+) -> c_int {
+    e(api::sys_pthread_barrier_init(barrier, attr, count))
+}
+
+/// Destroy a barrier.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_barrier_destroy(barrier: *mut ctypes::pthread_barrier_t) -> c_int {
+    e(api::sys_pthread_barrier_destroy(barrier))
+}
+
+/// Wait until the required number of threads have reached the barrier.
+///
+/// `PTHREAD_BARRIER_SERIAL_THREAD` is returned to one of the released threads.
+#[no_mangle]
+pub unsafe extern "C" fn pthread_barrier_wait(barrier: *mut ctypes::pthread_barrier_t) -> c_int {
+    match api::sys_pthread_barrier_wait(barrier) {
+        ctypes::PTHREAD_BARRIER_SERIAL_THREAD => ctypes::PTHREAD_BARRIER_SERIAL_THREAD,
+        ret => e(ret),
+    }
+}
+
 /// Initialize a condition variable
 #[no_mangle]
 pub unsafe extern "C" fn pthread_cond_init(
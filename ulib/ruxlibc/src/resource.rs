@@ -9,7 +9,7 @@
 
 use core::ffi::c_int;
 
-use ruxos_posix_api::{sys_getrlimit, sys_setrlimit};
+use ruxos_posix_api::{sys_getrlimit, sys_getrusage, sys_setrlimit};
 
 use crate::utils::e;
 
@@ -27,3 +27,9 @@ pub unsafe extern "C" fn setrlimit(
 ) -> c_int {
     e(sys_setrlimit(resource, rlimits))
 }
+
+/// Get resource usage
+#[no_mangle]
+pub unsafe extern "C" fn getrusage(who: c_int, usage: *mut crate::ctypes::rusage) -> c_int {
+    e(sys_getrusage(who, usage))
+}
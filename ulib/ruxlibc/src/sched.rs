@@ -0,0 +1,85 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use core::ffi::c_int;
+use ruxos_posix_api as api;
+#[cfg(feature = "multitask")]
+use {crate::ctypes, crate::utils::e};
+
+/// Relinquish the CPU, and switches to another task.
+#[no_mangle]
+pub unsafe extern "C" fn sched_yield() -> c_int {
+    api::sys_sched_yield()
+}
+
+/// Set the CPU affinity mask of the thread `pid`, 0 for the calling thread.
+#[cfg(feature = "multitask")]
+#[no_mangle]
+pub unsafe extern "C" fn sched_setaffinity(
+    pid: ctypes::pid_t,
+    cpusetsize: ctypes::size_t,
+    mask: *const ctypes::cpu_set_t,
+) -> c_int {
+    e(api::sys_sched_setaffinity(pid, cpusetsize, mask))
+}
+
+/// Get the CPU affinity mask of the thread `pid`, 0 for the calling thread.
+#[cfg(feature = "multitask")]
+#[no_mangle]
+pub unsafe extern "C" fn sched_getaffinity(
+    pid: ctypes::pid_t,
+    cpusetsize: ctypes::size_t,
+    mask: *mut ctypes::cpu_set_t,
+) -> c_int {
+    // The system call returns the size of the stored mask.
+    e(api::sys_sched_getaffinity(pid, cpusetsize, mask)).min(0)
+}
+
+/// Set the scheduling policy and the static priority of the thread `pid`, 0
+/// for the calling thread.
+#[cfg(feature = "multitask")]
+#[no_mangle]
+pub unsafe extern "C" fn sched_setscheduler(
+    pid: ctypes::pid_t,
+    policy: c_int,
+    param: *const ctypes::sched_param,
+) -> c_int {
+    e(api::sys_sched_setscheduler(pid, policy, param))
+}
+
+/// Get the scheduling policy of the thread `pid`, 0 for the calling thread.
+#[cfg(feature = "multitask")]
+#[no_mangle]
+pub unsafe extern "C" fn sched_getscheduler(pid: ctypes::pid_t) -> c_int {
+    e(api::sys_sched_getscheduler(pid))
+}
+
+/// Get the static priority of the thread `pid`, 0 for the calling thread.
+#[cfg(feature = "multitask")]
+#[no_mangle]
+pub unsafe extern "C" fn sched_getparam(
+    pid: ctypes::pid_t,
+    param: *mut ctypes::sched_param,
+) -> c_int {
+    e(api::sys_sched_getparam(pid, param))
+}
+
+/// Get the highest static priority of the scheduling policy `policy`.
+#[cfg(feature = "multitask")]
+#[no_mangle]
+pub unsafe extern "C" fn sched_get_priority_max(policy: c_int) -> c_int {
+    e(api::sys_sched_get_priority_max(policy))
+}
+
+/// Get the lowest static priority of the scheduling policy `policy`.
+#[cfg(feature = "multitask")]
+#[no_mangle]
+pub unsafe extern "C" fn sched_get_priority_min(policy: c_int) -> c_int {
+    e(api::sys_sched_get_priority_min(policy))
+}
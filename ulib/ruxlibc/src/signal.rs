@@ -13,7 +13,45 @@ use core::ffi::c_int;
 use crate::ctypes::k_sigaction;
 use crate::ctypes::{sigaction, EINVAL, SIGKILL, SIGSTOP};
 #[cfg(feature = "signal")]
-use ruxos_posix_api::sys_sigaction;
+use crate::utils::e;
+use ruxos_posix_api::sys_rt_sigprocmask;
+#[cfg(all(feature = "signal", feature = "fd"))]
+use ruxos_posix_api::sys_signalfd4;
+#[cfg(feature = "signal")]
+use ruxos_posix_api::{sys_kill, sys_sigaction};
+
+/// Examine and change blocked signals, returns a negative error number on failure.
+#[no_mangle]
+pub unsafe extern "C" fn rux_sigprocmask(
+    how: c_int,
+    set: *const crate::ctypes::sigset_t,
+    oldset: *mut crate::ctypes::sigset_t,
+) -> c_int {
+    sys_rt_sigprocmask(
+        how,
+        set as *const usize,
+        oldset as *mut usize,
+        core::mem::size_of::<usize>(),
+    )
+}
+
+/// Send a signal to a process.
+#[cfg(feature = "signal")]
+#[no_mangle]
+pub unsafe extern "C" fn kill(pid: crate::ctypes::pid_t, sig: c_int) -> c_int {
+    e(sys_kill(pid, sig))
+}
+
+/// Create a file descriptor for accepting signals in `mask`.
+#[cfg(all(feature = "signal", feature = "fd"))]
+#[no_mangle]
+pub unsafe extern "C" fn signalfd(
+    fd: c_int,
+    mask: *const crate::ctypes::sigset_t,
+    flags: c_int,
+) -> c_int {
+    e(sys_signalfd4(fd, mask, core::mem::size_of::<u64>(), flags))
+}
 
 #[cfg(feature = "signal")]
 unsafe extern "C" fn ignore_handler(_: c_int) {}
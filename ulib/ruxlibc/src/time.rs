@@ -7,8 +7,11 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use core::ffi::c_int;
-use ruxos_posix_api::{sys_clock_gettime, sys_clock_settime, sys_nanosleep};
+use core::ffi::{c_int, c_void};
+use ruxos_posix_api::{
+    sys_clock_getres, sys_clock_gettime, sys_clock_settime, sys_gettimeofday, sys_nanosleep,
+    sys_settimeofday,
+};
 #[cfg(feature = "signal")]
 use ruxos_posix_api::{sys_getitimer, sys_setitimer};
 
@@ -20,12 +23,30 @@ pub unsafe extern "C" fn clock_gettime(clk: ctypes::clockid_t, ts: *mut ctypes::
     e(sys_clock_gettime(clk, ts))
 }
 
+/// Get the resolution of a clock
+#[no_mangle]
+pub unsafe extern "C" fn clock_getres(clk: ctypes::clockid_t, res: *mut ctypes::timespec) -> c_int {
+    e(sys_clock_getres(clk, res))
+}
+
 /// Set clock time since booting
 #[no_mangle]
 pub unsafe extern "C" fn clock_settime(clk: ctypes::clockid_t, ts: *mut ctypes::timespec) -> c_int {
     e(sys_clock_settime(clk, ts))
 }
 
+/// Get the wall-clock time, the time zone is ignored
+#[no_mangle]
+pub unsafe extern "C" fn gettimeofday(tv: *mut ctypes::timeval, tz: *mut c_void) -> c_int {
+    e(sys_gettimeofday(tv, tz))
+}
+
+/// Set the wall-clock time, the time zone is ignored
+#[no_mangle]
+pub unsafe extern "C" fn settimeofday(tv: *const ctypes::timeval, tz: *const c_void) -> c_int {
+    e(sys_settimeofday(tv, tz))
+}
+
 /// Sleep some nanoseconds
 ///
 /// TODO: should be woken by signals, and set errno
@@ -7,15 +7,11 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use core::ffi::c_int;
-use ruxos_posix_api::{sys_exit, sys_getpid, sys_gettid};
+use crate::{ctypes, utils::e};
+use core::ffi::{c_int, c_ulong};
+use ruxos_posix_api::{sys_exit_group, sys_getpid, sys_gettid, sys_prctl, sys_umask, sys_wait4};
 #[cfg(feature = "signal")]
-use {
-    crate::getitimer,
-    crate::{ctypes, utils::e},
-    core::ffi::c_uint,
-    ruxos_posix_api::sys_setitimer,
-};
+use {crate::getitimer, core::ffi::c_uint, ruxos_posix_api::sys_setitimer};
 
 /// Get current thread ID.
 #[no_mangle]
@@ -35,10 +31,41 @@ pub unsafe extern "C" fn abort() -> ! {
     panic!()
 }
 
-/// Exits the current thread.
+/// Exits the program with all its threads.
 #[no_mangle]
 pub unsafe extern "C" fn exit(exit_code: c_int) -> ! {
-    sys_exit(exit_code)
+    sys_exit_group(exit_code)
+}
+
+/// Wait for the child `pid` to exit, and store its exit status in `wstatus`.
+#[no_mangle]
+pub unsafe extern "C" fn wait4(
+    pid: ctypes::pid_t,
+    wstatus: *mut c_int,
+    options: c_int,
+    rusage: *mut ctypes::rusage,
+) -> ctypes::pid_t {
+    e(sys_wait4(pid, wstatus, options, rusage))
+}
+
+/// Set the file mode creation mask, and return the previous one.
+#[no_mangle]
+pub unsafe extern "C" fn umask(mask: ctypes::mode_t) -> ctypes::mode_t {
+    sys_umask(mask)
+}
+
+/// Operations on the calling thread, called by `prctl`.
+///
+/// TODO: remove this function in future work
+#[no_mangle]
+pub unsafe extern "C" fn ax_prctl(
+    option: c_int,
+    arg2: c_ulong,
+    arg3: c_ulong,
+    arg4: c_ulong,
+    arg5: c_ulong,
+) -> c_int {
+    e(sys_prctl(option, arg2, arg3, arg4, arg5))
 }
 
 /// Set an alarm clock for delivery of a signal
@@ -7,7 +7,7 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use core::ffi::c_int;
+use core::ffi::{c_int, c_void};
 
 pub fn e(ret: c_int) -> c_int {
     if ret < 0 {
@@ -17,3 +17,15 @@ pub fn e(ret: c_int) -> c_int {
         ret as _
     }
 }
+
+/// Same as [`e`], but for the functions returning an address, which is
+/// `MAP_FAILED` (`-1`) on error.
+pub fn e_ptr(ret: *mut c_void) -> *mut c_void {
+    let ret_val = ret as isize;
+    if (-4095..0).contains(&ret_val) {
+        crate::errno::set_errno(-ret_val as c_int);
+        -1isize as *mut c_void
+    } else {
+        ret
+    }
+}
@@ -1,6 +1,6 @@
 pub mod syscall_id;
 
-use core::ffi::{c_char, c_int};
+use core::ffi::{c_char, c_int, c_ulong};
 use ruxos_posix_api::ctypes::{self, gid_t, pid_t, uid_t};
 use syscall_id::SyscallId;
 
@@ -14,6 +14,10 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             SyscallId::GETCWD => {
                 ruxos_posix_api::sys_getcwd(args[0] as *mut core::ffi::c_char, args[1]) as _
             }
+            #[cfg(feature = "fd")]
+            SyscallId::EVENTFD2 => {
+                ruxos_posix_api::sys_eventfd2(args[0] as core::ffi::c_uint, args[1] as c_int) as _
+            }
             #[cfg(feature = "epoll")]
             SyscallId::EPOLL_CREATE1 => ruxos_posix_api::sys_epoll_create(args[0] as c_int) as _,
             #[cfg(feature = "epoll")]
@@ -57,6 +61,31 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[2] as c_int,
             ) as _,
             #[cfg(feature = "fs")]
+            SyscallId::SYMLINKAT => ruxos_posix_api::sys_symlinkat(
+                args[0] as *const core::ffi::c_char,
+                args[1] as c_int,
+                args[2] as *const core::ffi::c_char,
+            ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::LINKAT => ruxos_posix_api::sys_linkat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as c_int,
+                args[3] as *const core::ffi::c_char,
+                args[4] as c_int,
+            ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::FCHMOD => {
+                ruxos_posix_api::sys_fchmod(args[0] as c_int, args[1] as ctypes::mode_t) as _
+            }
+            #[cfg(feature = "fs")]
+            SyscallId::FCHMODAT => ruxos_posix_api::sys_fchmodat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as ctypes::mode_t,
+                0,
+            ) as _,
+            #[cfg(feature = "fs")]
             SyscallId::FCHOWNAT => ruxos_posix_api::sys_fchownat(
                 args[0] as c_int,
                 args[1] as *const core::ffi::c_char,
@@ -65,6 +94,12 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[4] as c_int,
             ) as _,
             #[cfg(feature = "fs")]
+            SyscallId::FCHOWN => ruxos_posix_api::sys_fchown(
+                args[0] as c_int,
+                args[1] as ctypes::uid_t,
+                args[2] as ctypes::gid_t,
+            ) as _,
+            #[cfg(feature = "fs")]
             SyscallId::RENAMEAT => ruxos_posix_api::sys_renameat(
                 args[0] as c_int,
                 args[1] as *const core::ffi::c_char,
@@ -72,6 +107,23 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[3] as *const core::ffi::c_char,
             ) as _,
             #[cfg(feature = "fs")]
+            SyscallId::UMOUNT2 => {
+                ruxos_posix_api::sys_umount2(args[0] as *const c_char, args[1] as c_int) as _
+            }
+            #[cfg(feature = "fs")]
+            SyscallId::MOUNT => ruxos_posix_api::sys_mount(
+                args[0] as *const core::ffi::c_char,
+                args[1] as *const core::ffi::c_char,
+                args[2] as *const core::ffi::c_char,
+                args[3] as core::ffi::c_ulong,
+                args[4] as *const core::ffi::c_void,
+            ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::STATFS => ruxos_posix_api::sys_statfs(
+                args[0] as *const core::ffi::c_char,
+                args[1] as *mut ctypes::statfs,
+            ) as _,
+            #[cfg(feature = "fs")]
             SyscallId::FACCESSAT => ruxos_posix_api::sys_faccessat(
                 args[0] as c_int,
                 args[1] as *const c_char,
@@ -81,6 +133,8 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             #[cfg(feature = "fs")]
             SyscallId::CHDIR => ruxos_posix_api::sys_chdir(args[0] as *const c_char) as _,
             #[cfg(feature = "fs")]
+            SyscallId::FCHDIR => ruxos_posix_api::sys_fchdir(args[0] as c_int) as _,
+            #[cfg(feature = "fs")]
             SyscallId::OPENAT => ruxos_posix_api::sys_openat(
                 args[0],
                 args[1] as *const core::ffi::c_char,
@@ -158,6 +212,13 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[4] as *mut ctypes::timeval,
                 args[5] as *const core::ffi::c_void,
             ) as _,
+            #[cfg(all(feature = "signal", feature = "fd"))]
+            SyscallId::SIGNALFD4 => ruxos_posix_api::sys_signalfd4(
+                args[0] as c_int,
+                args[1] as *const ctypes::sigset_t,
+                args[2],
+                args[3] as c_int,
+            ) as _,
             #[cfg(feature = "poll")]
             SyscallId::PPOLL => ruxos_posix_api::sys_ppoll(
                 args[0] as *mut ctypes::pollfd,
@@ -192,6 +253,38 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             SyscallId::GETTID => ruxos_posix_api::sys_gettid() as _,
             #[cfg(feature = "fs")]
             SyscallId::FDATASYNC => ruxos_posix_api::sys_fdatasync(args[0] as c_int) as _,
+            #[cfg(feature = "fd")]
+            SyscallId::TIMERFD_CREATE => {
+                ruxos_posix_api::sys_timerfd_create(args[0] as c_int, args[1] as c_int) as _
+            }
+            #[cfg(feature = "fd")]
+            SyscallId::TIMERFD_SETTIME => ruxos_posix_api::sys_timerfd_settime(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as *const ctypes::itimerspec,
+                args[3] as *mut ctypes::itimerspec,
+            ) as _,
+            #[cfg(feature = "fd")]
+            SyscallId::TIMERFD_GETTIME => ruxos_posix_api::sys_timerfd_gettime(
+                args[0] as c_int,
+                args[1] as *mut ctypes::itimerspec,
+            ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::TRUNCATE => ruxos_posix_api::sys_truncate(
+                args[0] as *const core::ffi::c_char,
+                args[1] as ctypes::off_t,
+            ) as _,
+            #[cfg(feature = "fd")]
+            SyscallId::FTRUNCATE => {
+                ruxos_posix_api::sys_ftruncate(args[0] as c_int, args[1] as ctypes::off_t) as _
+            }
+            #[cfg(feature = "fd")]
+            SyscallId::FALLOCATE => ruxos_posix_api::sys_fallocate(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as ctypes::off_t,
+                args[3] as ctypes::off_t,
+            ) as _,
             SyscallId::CAP_GET => ruxos_posix_api::sys_cap_get(args[0], args[1]) as _,
             #[allow(unreachable_code)]
             #[cfg(not(feature = "multitask"))]
@@ -201,8 +294,12 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             SyscallId::EXIT => {
                 ruxos_posix_api::sys_pthread_exit(args[0] as *mut core::ffi::c_void) as _
             }
+            #[allow(unreachable_code)]
+            SyscallId::EXIT_GROUP => ruxos_posix_api::sys_exit_group(args[0] as c_int) as _,
             #[cfg(feature = "multitask")]
             SyscallId::SET_TID_ADDRESS => ruxos_posix_api::sys_set_tid_address(args[0]) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::UNSHARE => ruxos_posix_api::sys_unshare(args[0] as c_int) as _,
             #[cfg(feature = "multitask")]
             SyscallId::FUTEX => ruxos_posix_api::sys_futex(
                 args[0],
@@ -224,7 +321,46 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[0] as ctypes::clockid_t,
                 args[1] as *mut ctypes::timespec,
             ) as _,
+            SyscallId::CLOCK_GETRES => ruxos_posix_api::sys_clock_getres(
+                args[0] as ctypes::clockid_t,
+                args[1] as *mut ctypes::timespec,
+            ) as _,
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_SETSCHEDULER => ruxos_posix_api::sys_sched_setscheduler(
+                args[0] as ctypes::pid_t,
+                args[1] as c_int,
+                args[2] as *const ctypes::sched_param,
+            ) as _,
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GETSCHEDULER => {
+                ruxos_posix_api::sys_sched_getscheduler(args[0] as ctypes::pid_t) as _
+            }
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GETPARAM => ruxos_posix_api::sys_sched_getparam(
+                args[0] as ctypes::pid_t,
+                args[1] as *mut ctypes::sched_param,
+            ) as _,
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_SETAFFINITY => ruxos_posix_api::sys_sched_setaffinity(
+                args[0] as ctypes::pid_t,
+                args[1] as ctypes::size_t,
+                args[2] as *const ctypes::cpu_set_t,
+            ) as _,
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GETAFFINITY => ruxos_posix_api::sys_sched_getaffinity(
+                args[0] as ctypes::pid_t,
+                args[1] as ctypes::size_t,
+                args[2] as *mut ctypes::cpu_set_t,
+            ) as _,
             SyscallId::SCHED_YIELD => ruxos_posix_api::sys_sched_yield() as _,
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GET_PRIORITY_MAX => {
+                ruxos_posix_api::sys_sched_get_priority_max(args[0] as c_int) as _
+            }
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GET_PRIORITY_MIN => {
+                ruxos_posix_api::sys_sched_get_priority_min(args[0] as c_int) as _
+            }
             #[cfg(feature = "signal")]
             SyscallId::KILL => ruxos_posix_api::sys_kill(args[0] as pid_t, args[1] as c_int) as _,
             #[cfg(feature = "signal")]
@@ -262,7 +398,18 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 ruxos_posix_api::sys_setrlimit(args[0] as c_int, args[1] as *const ctypes::rlimit)
                     as _
             }
+            SyscallId::GETRUSAGE => {
+                ruxos_posix_api::sys_getrusage(args[0] as c_int, args[1] as *mut ctypes::rusage)
+                    as _
+            }
             SyscallId::UMASK => ruxos_posix_api::sys_umask(args[0] as ctypes::mode_t) as _,
+            SyscallId::PRCTL => ruxos_posix_api::sys_prctl(
+                args[0] as c_int,
+                args[1] as c_ulong,
+                args[2] as c_ulong,
+                args[3] as c_ulong,
+                args[4] as c_ulong,
+            ) as _,
             #[cfg(feature = "multitask")]
             SyscallId::GETPID => ruxos_posix_api::sys_getpid() as _,
             SyscallId::GETPPID => ruxos_posix_api::sys_getppid() as _,
@@ -276,6 +423,13 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                     as _
             }
             #[cfg(feature = "net")]
+            SyscallId::SOCKETPAIR => ruxos_posix_api::sys_socketpair(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as c_int,
+                core::slice::from_raw_parts_mut(args[3] as *mut c_int, 2),
+            ) as _,
+            #[cfg(feature = "net")]
             SyscallId::BIND => ruxos_posix_api::sys_bind(
                 args[0] as c_int,
                 args[1] as *const ctypes::sockaddr,
@@ -292,6 +446,13 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[2] as *mut ctypes::socklen_t,
             ) as _,
             #[cfg(feature = "net")]
+            SyscallId::ACCEPT4 => ruxos_posix_api::sys_accept4(
+                args[0] as c_int,
+                args[1] as *mut ctypes::sockaddr,
+                args[2] as *mut ctypes::socklen_t,
+                args[3] as c_int,
+            ) as _,
+            #[cfg(feature = "net")]
             SyscallId::CONNECT => ruxos_posix_api::sys_connect(
                 args[0] as c_int,
                 args[1] as *const ctypes::sockaddr,
@@ -336,6 +497,14 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[4] as ctypes::socklen_t,
             ) as _,
             #[cfg(feature = "net")]
+            SyscallId::GETSOCKOPT => ruxos_posix_api::sys_getsockopt(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as c_int,
+                args[3] as *mut core::ffi::c_void,
+                args[4] as *mut ctypes::socklen_t,
+            ) as _,
+            #[cfg(feature = "net")]
             SyscallId::SHUTDOWN => {
                 ruxos_posix_api::sys_shutdown(args[0] as c_int, args[1] as c_int) as _
             }
@@ -345,6 +514,12 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[1] as *const ctypes::msghdr,
                 args[2] as c_int,
             ) as _,
+            #[cfg(feature = "net")]
+            SyscallId::RECVMSG => ruxos_posix_api::sys_recvmsg(
+                args[0] as c_int,
+                args[1] as *mut ctypes::msghdr,
+                args[2] as c_int,
+            ) as _,
             #[cfg(feature = "alloc")]
             SyscallId::MUNMAP => ruxos_posix_api::sys_munmap(
                 args[0] as *mut core::ffi::c_void,
@@ -398,6 +573,12 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[1] as ctypes::size_t,
                 args[2] as c_int,
             ) as _,
+            SyscallId::WAIT4 => ruxos_posix_api::sys_wait4(
+                args[0] as pid_t,
+                args[1] as *mut c_int,
+                args[2] as c_int,
+                args[3] as *mut ctypes::rusage,
+            ) as _,
             SyscallId::PRLIMIT64 => ruxos_posix_api::sys_prlimit64(
                 args[0] as ctypes::pid_t,
                 args[1] as c_int,
@@ -409,6 +590,20 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[1] as ctypes::size_t,
                 args[2] as c_int,
             ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::OPENAT2 => ruxos_posix_api::sys_openat2(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as *const ctypes::open_how,
+                args[3] as ctypes::size_t,
+            ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::FACCESSAT2 => ruxos_posix_api::sys_faccessat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as c_int,
+                args[3] as c_int,
+            ) as _,
         }
     }
 }
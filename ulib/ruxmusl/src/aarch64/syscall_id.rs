@@ -9,6 +9,8 @@ pub enum SyscallId {
     INVALID = 999,
     #[cfg(feature = "fs")]
     GETCWD = 17,
+    #[cfg(feature = "fd")]
+    EVENTFD2 = 19,
     #[cfg(feature = "epoll")]
     EPOLL_CREATE1 = 20,
     #[cfg(feature = "epoll")]
@@ -28,14 +30,38 @@ pub enum SyscallId {
     #[cfg(feature = "fs")]
     UNLINKAT = 35,
     #[cfg(feature = "fs")]
+    SYMLINKAT = 36,
+    #[cfg(feature = "fs")]
+    LINKAT = 37,
+    #[cfg(feature = "fs")]
     RENAMEAT = 38,
     #[cfg(feature = "fs")]
+    UMOUNT2 = 39,
+    #[cfg(feature = "fs")]
+    MOUNT = 40,
+    #[cfg(feature = "fs")]
+    STATFS = 43,
+    #[cfg(feature = "fs")]
+    TRUNCATE = 45,
+    #[cfg(feature = "fd")]
+    FTRUNCATE = 46,
+    #[cfg(feature = "fd")]
+    FALLOCATE = 47,
+    #[cfg(feature = "fs")]
     FACCESSAT = 48,
     #[cfg(feature = "fs")]
     CHDIR = 49,
     #[cfg(feature = "fs")]
+    FCHDIR = 50,
+    #[cfg(feature = "fs")]
+    FCHMOD = 52,
+    #[cfg(feature = "fs")]
+    FCHMODAT = 53,
+    #[cfg(feature = "fs")]
     FCHOWNAT = 54,
     #[cfg(feature = "fs")]
+    FCHOWN = 55,
+    #[cfg(feature = "fs")]
     OPENAT = 56,
     #[cfg(feature = "fd")]
     CLOSE = 57,
@@ -61,6 +87,8 @@ pub enum SyscallId {
     PSELECT6 = 72,
     #[cfg(feature = "poll")]
     PPOLL = 73,
+    #[cfg(all(feature = "signal", feature = "fd"))]
+    SIGNALFD4 = 74,
     #[cfg(feature = "fs")]
     READLINKAT = 78,
     #[cfg(feature = "fs")]
@@ -71,16 +99,40 @@ pub enum SyscallId {
     FSYNC = 82,
     #[cfg(feature = "fs")]
     FDATASYNC = 83,
+    #[cfg(feature = "fd")]
+    TIMERFD_CREATE = 85,
+    #[cfg(feature = "fd")]
+    TIMERFD_SETTIME = 86,
+    #[cfg(feature = "fd")]
+    TIMERFD_GETTIME = 87,
     CAP_GET = 90,
     EXIT = 93,
+    EXIT_GROUP = 94,
     #[cfg(feature = "multitask")]
     SET_TID_ADDRESS = 96,
+    #[cfg(feature = "fs")]
+    UNSHARE = 97,
     #[cfg(feature = "multitask")]
     FUTEX = 98,
     NANO_SLEEP = 101,
     CLOCK_SETTIME = 112,
     CLOCK_GETTIME = 113,
+    CLOCK_GETRES = 114,
+    #[cfg(feature = "multitask")]
+    SCHED_SETSCHEDULER = 119,
+    #[cfg(feature = "multitask")]
+    SCHED_GETSCHEDULER = 120,
+    #[cfg(feature = "multitask")]
+    SCHED_GETPARAM = 121,
+    #[cfg(feature = "multitask")]
+    SCHED_SETAFFINITY = 122,
+    #[cfg(feature = "multitask")]
+    SCHED_GETAFFINITY = 123,
     SCHED_YIELD = 124,
+    #[cfg(feature = "multitask")]
+    SCHED_GET_PRIORITY_MAX = 125,
+    #[cfg(feature = "multitask")]
+    SCHED_GET_PRIORITY_MIN = 126,
     #[cfg(feature = "signal")]
     KILL = 129,
     #[cfg(feature = "signal")]
@@ -97,7 +149,9 @@ pub enum SyscallId {
     UNAME = 160,
     GETRLIMIT = 163,
     SETRLIMIT = 164,
+    GETRUSAGE = 165,
     UMASK = 166,
+    PRCTL = 167,
     #[cfg(feature = "multitask")]
     GETPID = 172,
     GETPPID = 173,
@@ -110,6 +164,8 @@ pub enum SyscallId {
     #[cfg(feature = "net")]
     SOCKET = 198,
     #[cfg(feature = "net")]
+    SOCKETPAIR = 199,
+    #[cfg(feature = "net")]
     BIND = 200,
     #[cfg(feature = "net")]
     LISTEN = 201,
@@ -128,9 +184,13 @@ pub enum SyscallId {
     #[cfg(feature = "net")]
     SETSOCKOPT = 208,
     #[cfg(feature = "net")]
+    GETSOCKOPT = 209,
+    #[cfg(feature = "net")]
     SHUTDOWN = 210,
     #[cfg(feature = "net")]
     SENDMSG = 211,
+    #[cfg(feature = "net")]
+    RECVMSG = 212,
     #[cfg(feature = "alloc")]
     MUNMAP = 215,
     #[cfg(feature = "alloc")]
@@ -147,6 +207,13 @@ pub enum SyscallId {
     MSYNC = 227,
     #[cfg(feature = "alloc")]
     MADVISE = 233,
+    #[cfg(feature = "net")]
+    ACCEPT4 = 242,
+    WAIT4 = 260,
     PRLIMIT64 = 261,
     GETRANDOM = 278,
+    #[cfg(feature = "fs")]
+    OPENAT2 = 437,
+    #[cfg(feature = "fs")]
+    FACCESSAT2 = 439,
 }
@@ -1,6 +1,6 @@
 pub mod syscall_id;
 
-use core::ffi::c_int;
+use core::ffi::{c_int, c_ulong};
 use ruxos_posix_api::ctypes;
 use syscall_id::SyscallId;
 
@@ -14,6 +14,10 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             SyscallId::GETCWD => {
                 ruxos_posix_api::sys_getcwd(args[0] as *mut core::ffi::c_char, args[1]) as _
             }
+            #[cfg(feature = "fd")]
+            SyscallId::EVENTFD2 => {
+                ruxos_posix_api::sys_eventfd2(args[0] as core::ffi::c_uint, args[1] as c_int) as _
+            }
             #[cfg(feature = "epoll")]
             SyscallId::EPOLL_CREATE1 => ruxos_posix_api::sys_epoll_create(args[0] as c_int) as _,
             #[cfg(feature = "epoll")]
@@ -57,6 +61,37 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[2] as c_int,
             ) as _,
             #[cfg(feature = "fs")]
+            SyscallId::SYMLINKAT => ruxos_posix_api::sys_symlinkat(
+                args[0] as *const core::ffi::c_char,
+                args[1] as c_int,
+                args[2] as *const core::ffi::c_char,
+            ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::LINKAT => ruxos_posix_api::sys_linkat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as c_int,
+                args[3] as *const core::ffi::c_char,
+                args[4] as c_int,
+            ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::CHDIR => {
+                ruxos_posix_api::sys_chdir(args[0] as *const core::ffi::c_char) as _
+            }
+            #[cfg(feature = "fs")]
+            SyscallId::FCHDIR => ruxos_posix_api::sys_fchdir(args[0] as c_int) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::FCHMOD => {
+                ruxos_posix_api::sys_fchmod(args[0] as c_int, args[1] as ctypes::mode_t) as _
+            }
+            #[cfg(feature = "fs")]
+            SyscallId::FCHMODAT => ruxos_posix_api::sys_fchmodat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as ctypes::mode_t,
+                0,
+            ) as _,
+            #[cfg(feature = "fs")]
             SyscallId::FCHOWNAT => ruxos_posix_api::sys_fchownat(
                 args[0] as c_int,
                 args[1] as *const core::ffi::c_char,
@@ -65,6 +100,12 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[4] as c_int,
             ) as _,
             #[cfg(feature = "fs")]
+            SyscallId::FCHOWN => ruxos_posix_api::sys_fchown(
+                args[0] as c_int,
+                args[1] as ctypes::uid_t,
+                args[2] as ctypes::gid_t,
+            ) as _,
+            #[cfg(feature = "fs")]
             SyscallId::RENAMEAT => ruxos_posix_api::sys_renameat(
                 args[0] as c_int,
                 args[1] as *const core::ffi::c_char,
@@ -72,6 +113,24 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[3] as *const core::ffi::c_char,
             ) as _,
             #[cfg(feature = "fs")]
+            SyscallId::UMOUNT2 => {
+                ruxos_posix_api::sys_umount2(args[0] as *const core::ffi::c_char, args[1] as c_int)
+                    as _
+            }
+            #[cfg(feature = "fs")]
+            SyscallId::MOUNT => ruxos_posix_api::sys_mount(
+                args[0] as *const core::ffi::c_char,
+                args[1] as *const core::ffi::c_char,
+                args[2] as *const core::ffi::c_char,
+                args[3] as core::ffi::c_ulong,
+                args[4] as *const core::ffi::c_void,
+            ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::STATFS => ruxos_posix_api::sys_statfs(
+                args[0] as *const core::ffi::c_char,
+                args[1] as *mut ctypes::statfs,
+            ) as _,
+            #[cfg(feature = "fs")]
             SyscallId::OPENAT => ruxos_posix_api::sys_openat(
                 args[0],
                 args[1] as *const core::ffi::c_char,
@@ -128,6 +187,13 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[4] as *mut ctypes::timeval,
                 args[5] as *const core::ffi::c_void,
             ) as _,
+            #[cfg(all(feature = "signal", feature = "fd"))]
+            SyscallId::SIGNALFD4 => ruxos_posix_api::sys_signalfd4(
+                args[0] as c_int,
+                args[1] as *const ctypes::sigset_t,
+                args[2],
+                args[3] as c_int,
+            ) as _,
             #[cfg(feature = "poll")]
             SyscallId::PPOLL => ruxos_posix_api::sys_ppoll(
                 args[0] as *mut ctypes::pollfd,
@@ -160,6 +226,38 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             SyscallId::GETEGID => ruxos_posix_api::sys_getegid() as _,
             #[cfg(feature = "fs")]
             SyscallId::FDATASYNC => ruxos_posix_api::sys_fdatasync(args[0] as c_int) as _,
+            #[cfg(feature = "fd")]
+            SyscallId::TIMERFD_CREATE => {
+                ruxos_posix_api::sys_timerfd_create(args[0] as c_int, args[1] as c_int) as _
+            }
+            #[cfg(feature = "fd")]
+            SyscallId::TIMERFD_SETTIME => ruxos_posix_api::sys_timerfd_settime(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as *const ctypes::itimerspec,
+                args[3] as *mut ctypes::itimerspec,
+            ) as _,
+            #[cfg(feature = "fd")]
+            SyscallId::TIMERFD_GETTIME => ruxos_posix_api::sys_timerfd_gettime(
+                args[0] as c_int,
+                args[1] as *mut ctypes::itimerspec,
+            ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::TRUNCATE => ruxos_posix_api::sys_truncate(
+                args[0] as *const core::ffi::c_char,
+                args[1] as ctypes::off_t,
+            ) as _,
+            #[cfg(feature = "fd")]
+            SyscallId::FTRUNCATE => {
+                ruxos_posix_api::sys_ftruncate(args[0] as c_int, args[1] as ctypes::off_t) as _
+            }
+            #[cfg(feature = "fd")]
+            SyscallId::FALLOCATE => ruxos_posix_api::sys_fallocate(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as ctypes::off_t,
+                args[3] as ctypes::off_t,
+            ) as _,
             #[allow(unreachable_code)]
             #[cfg(not(feature = "multitask"))]
             SyscallId::EXIT => ruxos_posix_api::sys_exit(args[0] as c_int) as _,
@@ -168,8 +266,12 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             SyscallId::EXIT => {
                 ruxos_posix_api::sys_pthread_exit(args[0] as *mut core::ffi::c_void) as _
             }
+            #[allow(unreachable_code)]
+            SyscallId::EXIT_GROUP => ruxos_posix_api::sys_exit_group(args[0] as c_int) as _,
             #[cfg(feature = "multitask")]
             SyscallId::SET_TID_ADDRESS => ruxos_posix_api::sys_set_tid_address(args[0]) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::UNSHARE => ruxos_posix_api::sys_unshare(args[0] as c_int) as _,
             #[cfg(feature = "multitask")]
             SyscallId::FUTEX => ruxos_posix_api::sys_futex(
                 args[0],
@@ -191,7 +293,46 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[0] as ctypes::clockid_t,
                 args[1] as *mut ctypes::timespec,
             ) as _,
+            SyscallId::CLOCK_GETRES => ruxos_posix_api::sys_clock_getres(
+                args[0] as ctypes::clockid_t,
+                args[1] as *mut ctypes::timespec,
+            ) as _,
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_SETSCHEDULER => ruxos_posix_api::sys_sched_setscheduler(
+                args[0] as ctypes::pid_t,
+                args[1] as c_int,
+                args[2] as *const ctypes::sched_param,
+            ) as _,
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GETSCHEDULER => {
+                ruxos_posix_api::sys_sched_getscheduler(args[0] as ctypes::pid_t) as _
+            }
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GETPARAM => ruxos_posix_api::sys_sched_getparam(
+                args[0] as ctypes::pid_t,
+                args[1] as *mut ctypes::sched_param,
+            ) as _,
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_SETAFFINITY => ruxos_posix_api::sys_sched_setaffinity(
+                args[0] as ctypes::pid_t,
+                args[1] as ctypes::size_t,
+                args[2] as *const ctypes::cpu_set_t,
+            ) as _,
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GETAFFINITY => ruxos_posix_api::sys_sched_getaffinity(
+                args[0] as ctypes::pid_t,
+                args[1] as ctypes::size_t,
+                args[2] as *mut ctypes::cpu_set_t,
+            ) as _,
             SyscallId::SCHED_YIELD => ruxos_posix_api::sys_sched_yield() as _,
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GET_PRIORITY_MAX => {
+                ruxos_posix_api::sys_sched_get_priority_max(args[0] as c_int) as _
+            }
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GET_PRIORITY_MIN => {
+                ruxos_posix_api::sys_sched_get_priority_min(args[0] as c_int) as _
+            }
             #[cfg(feature = "signal")]
             SyscallId::SIGALTSTACK => ruxos_posix_api::sys_sigaltstack(
                 args[0] as *const core::ffi::c_void,
@@ -220,7 +361,18 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 ruxos_posix_api::sys_setrlimit(args[0] as c_int, args[1] as *const ctypes::rlimit)
                     as _
             }
+            SyscallId::GETRUSAGE => {
+                ruxos_posix_api::sys_getrusage(args[0] as c_int, args[1] as *mut ctypes::rusage)
+                    as _
+            }
             SyscallId::UMASK => ruxos_posix_api::sys_umask(args[0] as ctypes::mode_t) as _,
+            SyscallId::PRCTL => ruxos_posix_api::sys_prctl(
+                args[0] as c_int,
+                args[1] as c_ulong,
+                args[2] as c_ulong,
+                args[3] as c_ulong,
+                args[4] as c_ulong,
+            ) as _,
             #[cfg(feature = "multitask")]
             SyscallId::GETPID => ruxos_posix_api::sys_getpid() as _,
             SyscallId::SYSINFO => {
@@ -232,6 +384,13 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                     as _
             }
             #[cfg(feature = "net")]
+            SyscallId::SOCKETPAIR => ruxos_posix_api::sys_socketpair(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as c_int,
+                core::slice::from_raw_parts_mut(args[3] as *mut c_int, 2),
+            ) as _,
+            #[cfg(feature = "net")]
             SyscallId::BIND => ruxos_posix_api::sys_bind(
                 args[0] as c_int,
                 args[1] as *const ctypes::sockaddr,
@@ -248,6 +407,13 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[2] as *mut ctypes::socklen_t,
             ) as _,
             #[cfg(feature = "net")]
+            SyscallId::ACCEPT4 => ruxos_posix_api::sys_accept4(
+                args[0] as c_int,
+                args[1] as *mut ctypes::sockaddr,
+                args[2] as *mut ctypes::socklen_t,
+                args[3] as c_int,
+            ) as _,
+            #[cfg(feature = "net")]
             SyscallId::CONNECT => ruxos_posix_api::sys_connect(
                 args[0] as c_int,
                 args[1] as *const ctypes::sockaddr,
@@ -292,6 +458,14 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[4] as ctypes::socklen_t,
             ) as _,
             #[cfg(feature = "net")]
+            SyscallId::GETSOCKOPT => ruxos_posix_api::sys_getsockopt(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as c_int,
+                args[3] as *mut core::ffi::c_void,
+                args[4] as *mut ctypes::socklen_t,
+            ) as _,
+            #[cfg(feature = "net")]
             SyscallId::SHUTDOWN => {
                 ruxos_posix_api::sys_shutdown(args[0] as c_int, args[1] as c_int) as _
             }
@@ -301,6 +475,12 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[1] as *const ctypes::msghdr,
                 args[2] as c_int,
             ) as _,
+            #[cfg(feature = "net")]
+            SyscallId::RECVMSG => ruxos_posix_api::sys_recvmsg(
+                args[0] as c_int,
+                args[1] as *mut ctypes::msghdr,
+                args[2] as c_int,
+            ) as _,
             #[cfg(feature = "alloc")]
             SyscallId::MUNMAP => ruxos_posix_api::sys_munmap(
                 args[0] as *mut core::ffi::c_void,
@@ -343,12 +523,25 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[1] as ctypes::size_t,
                 args[2] as c_int,
             ) as _,
+            SyscallId::WAIT4 => ruxos_posix_api::sys_wait4(
+                args[0] as ctypes::pid_t,
+                args[1] as *mut c_int,
+                args[2] as c_int,
+                args[3] as *mut ctypes::rusage,
+            ) as _,
             SyscallId::PRLIMIT64 => ruxos_posix_api::sys_prlimit64(
                 args[0] as ctypes::pid_t,
                 args[1] as c_int,
                 args[2] as *const ctypes::rlimit,
                 args[3] as *mut ctypes::rlimit,
             ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::OPENAT2 => ruxos_posix_api::sys_openat2(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as *const ctypes::open_how,
+                args[3] as ctypes::size_t,
+            ) as _,
         }
     }
 }
@@ -21,6 +21,9 @@ impl ruxhal::trap::TrapHandler for TrapHandlerImpl {
     #[cfg(feature = "musl")]
     fn handle_syscall(syscall_id: usize, args: [usize; 6]) -> isize {
         let id = SyscallId::try_from(syscall_id).unwrap_or(SyscallId::INVALID);
-        crate::syscall(id, args)
+        let ret = crate::syscall(id, args);
+        // back from the system call, where no lock is held
+        ruxos_posix_api::exit_if_group_exiting();
+        ret
     }
 }
@@ -42,12 +42,12 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 ruxos_posix_api::sys_fstat(args[0] as c_int, args[1] as *mut c_void) as _
             }
 
-            // TODO: lstat is identical to stat(), except that if pathname is a symbolic link,
-            // then it returns information about the link itself, not the file that the link refers to.
             #[cfg(feature = "fs")]
-            SyscallId::LSTAT => ruxos_posix_api::sys_stat(
+            SyscallId::LSTAT => ruxos_posix_api::sys_newfstatat(
+                ctypes::AT_FDCWD as c_int,
                 args[0] as *const core::ffi::c_char,
-                args[1] as *mut c_void,
+                args[1] as *mut ctypes::kstat,
+                ctypes::AT_SYMLINK_NOFOLLOW as c_int,
             ) as _,
 
             #[cfg(feature = "poll")]
@@ -193,6 +193,14 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                     as _
             }
 
+            #[cfg(feature = "net")]
+            SyscallId::SOCKETPAIR => ruxos_posix_api::sys_socketpair(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as c_int,
+                core::slice::from_raw_parts_mut(args[3] as *mut c_int, 2),
+            ) as _,
+
             #[cfg(feature = "net")]
             SyscallId::CONNECT => ruxos_posix_api::sys_connect(
                 args[0] as c_int,
@@ -207,6 +215,14 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[2] as *mut ctypes::socklen_t,
             ) as _,
 
+            #[cfg(feature = "net")]
+            SyscallId::ACCEPT4 => ruxos_posix_api::sys_accept4(
+                args[0] as c_int,
+                args[1] as *mut ctypes::sockaddr,
+                args[2] as *mut ctypes::socklen_t,
+                args[3] as c_int,
+            ) as _,
+
             #[cfg(feature = "net")]
             SyscallId::SENDTO => ruxos_posix_api::sys_sendto(
                 args[0] as c_int,
@@ -234,6 +250,13 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[2] as c_int,
             ) as _,
 
+            #[cfg(feature = "net")]
+            SyscallId::RECVMSG => ruxos_posix_api::sys_recvmsg(
+                args[0] as c_int,
+                args[1] as *mut ctypes::msghdr,
+                args[2] as c_int,
+            ) as _,
+
             #[cfg(feature = "net")]
             SyscallId::SHUTDOWN => {
                 ruxos_posix_api::sys_shutdown(args[0] as c_int, args[1] as c_int) as _
@@ -274,6 +297,15 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[4] as ctypes::socklen_t,
             ) as _,
 
+            #[cfg(feature = "net")]
+            SyscallId::GETSOCKOPT => ruxos_posix_api::sys_getsockopt(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as c_int,
+                args[3] as *mut c_void,
+                args[4] as *mut ctypes::socklen_t,
+            ) as _,
+
             #[cfg(feature = "multitask")]
             SyscallId::CLONE => ruxos_posix_api::sys_clone(
                 args[0] as c_int,
@@ -298,6 +330,13 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             #[cfg(feature = "multitask")]
             SyscallId::EXIT => ruxos_posix_api::sys_pthread_exit(args[0] as *mut c_void) as _,
 
+            SyscallId::WAIT4 => ruxos_posix_api::sys_wait4(
+                args[0] as pid_t,
+                args[1] as *mut c_int,
+                args[2] as c_int,
+                args[3] as *mut ctypes::rusage,
+            ) as _,
+
             #[cfg(feature = "signal")]
             SyscallId::KILL => ruxos_posix_api::sys_kill(args[0] as pid_t, args[1] as c_int) as _,
 
@@ -314,6 +353,25 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             #[cfg(feature = "fs")]
             SyscallId::FDATASYNC => ruxos_posix_api::sys_fdatasync(args[0] as c_int) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::TRUNCATE => ruxos_posix_api::sys_truncate(
+                args[0] as *const core::ffi::c_char,
+                args[1] as ctypes::off_t,
+            ) as _,
+
+            #[cfg(feature = "fd")]
+            SyscallId::FTRUNCATE => {
+                ruxos_posix_api::sys_ftruncate(args[0] as c_int, args[1] as ctypes::off_t) as _
+            }
+
+            #[cfg(feature = "fd")]
+            SyscallId::FALLOCATE => ruxos_posix_api::sys_fallocate(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as ctypes::off_t,
+                args[3] as ctypes::off_t,
+            ) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::GETDENTS => ruxos_posix_api::sys_getdents64(
                 args[0] as core::ffi::c_int,
@@ -329,6 +387,9 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             #[cfg(feature = "fs")]
             SyscallId::CHDIR => ruxos_posix_api::sys_chdir(args[0] as *const c_char) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::FCHDIR => ruxos_posix_api::sys_fchdir(args[0] as c_int) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::RENAME => ruxos_posix_api::sys_rename(
                 args[0] as *const core::ffi::c_char,
@@ -346,11 +407,24 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 ruxos_posix_api::sys_rmdir(args[0] as *const core::ffi::c_char) as _
             }
 
+            #[cfg(feature = "fs")]
+            SyscallId::LINK => ruxos_posix_api::sys_link(
+                args[0] as *const core::ffi::c_char,
+                args[1] as *const core::ffi::c_char,
+            ) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::UNLINK => {
                 ruxos_posix_api::sys_unlink(args[0] as *const core::ffi::c_char) as _
             }
 
+            #[cfg(feature = "fs")]
+            SyscallId::SYMLINK => ruxos_posix_api::sys_symlinkat(
+                args[0] as *const core::ffi::c_char,
+                ctypes::AT_FDCWD as c_int,
+                args[1] as *const core::ffi::c_char,
+            ) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::READLINK => ruxos_posix_api::sys_readlinkat(
                 ctypes::AT_FDCWD as c_int,
@@ -359,11 +433,43 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[2],
             ) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::CHMOD => ruxos_posix_api::sys_chmod(
+                args[0] as *const core::ffi::c_char,
+                args[1] as ctypes::mode_t,
+            ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::FCHMOD => {
+                ruxos_posix_api::sys_fchmod(args[0] as c_int, args[1] as ctypes::mode_t) as _
+            }
+
+            #[cfg(feature = "fs")]
+            SyscallId::CHOWN => ruxos_posix_api::sys_chown(
+                args[0] as *const core::ffi::c_char,
+                args[1] as ctypes::uid_t,
+                args[2] as ctypes::gid_t,
+            ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::FCHOWN => ruxos_posix_api::sys_fchown(
+                args[0] as c_int,
+                args[1] as ctypes::uid_t,
+                args[2] as ctypes::gid_t,
+            ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::LCHOWN => ruxos_posix_api::sys_lchown(
+                args[0] as *const core::ffi::c_char,
+                args[1] as ctypes::uid_t,
+                args[2] as ctypes::gid_t,
+            ) as _,
+
             SyscallId::UMASK => ruxos_posix_api::sys_umask(args[0] as ctypes::mode_t) as _,
 
             SyscallId::GETTIMEOFDAY => ruxos_posix_api::sys_gettimeofday(
-                args[0] as *mut ctypes::timespec,
-                args[1] as c_int,
+                args[0] as *mut ctypes::timeval,
+                args[1] as *mut c_void,
             ) as _,
 
             SyscallId::GETRLIMIT => {
@@ -371,6 +477,11 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                     as _
             }
 
+            SyscallId::GETRUSAGE => {
+                ruxos_posix_api::sys_getrusage(args[0] as c_int, args[1] as *mut ctypes::rusage)
+                    as _
+            }
+
             SyscallId::SYSINFO => {
                 ruxos_posix_api::sys_sysinfo(args[0] as *mut ctypes::sysinfo) as _
             }
@@ -397,6 +508,40 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                     as _
             }
 
+            #[cfg(feature = "fs")]
+            SyscallId::STATFS => ruxos_posix_api::sys_statfs(
+                args[0] as *const core::ffi::c_char,
+                args[1] as *mut ctypes::statfs,
+            ) as _,
+
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GETPARAM => ruxos_posix_api::sys_sched_getparam(
+                args[0] as ctypes::pid_t,
+                args[1] as *mut ctypes::sched_param,
+            ) as _,
+
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_SETSCHEDULER => ruxos_posix_api::sys_sched_setscheduler(
+                args[0] as ctypes::pid_t,
+                args[1] as c_int,
+                args[2] as *const ctypes::sched_param,
+            ) as _,
+
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GETSCHEDULER => {
+                ruxos_posix_api::sys_sched_getscheduler(args[0] as ctypes::pid_t) as _
+            }
+
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GET_PRIORITY_MAX => {
+                ruxos_posix_api::sys_sched_get_priority_max(args[0] as c_int) as _
+            }
+
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GET_PRIORITY_MIN => {
+                ruxos_posix_api::sys_sched_get_priority_min(args[0] as c_int) as _
+            }
+
             SyscallId::PRCTL => ruxos_posix_api::sys_prctl(
                 args[0] as c_int,
                 args[1] as c_ulong,
@@ -409,6 +554,16 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 ruxos_posix_api::sys_arch_prctl(args[0] as c_int, args[1] as c_ulong) as _
             }
 
+            SyscallId::SETRLIMIT => {
+                ruxos_posix_api::sys_setrlimit(args[0] as c_int, args[1] as *const ctypes::rlimit)
+                    as _
+            }
+
+            SyscallId::SETTIMEOFDAY => ruxos_posix_api::sys_settimeofday(
+                args[0] as *const ctypes::timeval,
+                args[1] as *const c_void,
+            ) as _,
+
             #[cfg(feature = "multitask")]
             SyscallId::GETTID => ruxos_posix_api::sys_gettid() as _,
 
@@ -422,6 +577,20 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[5] as _,
             ) as _,
 
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_SETAFFINITY => ruxos_posix_api::sys_sched_setaffinity(
+                args[0] as ctypes::pid_t,
+                args[1] as ctypes::size_t,
+                args[2] as *const ctypes::cpu_set_t,
+            ) as _,
+
+            #[cfg(feature = "multitask")]
+            SyscallId::SCHED_GETAFFINITY => ruxos_posix_api::sys_sched_getaffinity(
+                args[0] as ctypes::pid_t,
+                args[1] as ctypes::size_t,
+                args[2] as *mut ctypes::cpu_set_t,
+            ) as _,
+
             #[cfg(feature = "epoll")]
             SyscallId::EPOLL_CREATE => ruxos_posix_api::sys_epoll_create(args[0] as c_int) as _,
 
@@ -445,6 +614,14 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[1] as *mut ctypes::timespec,
             ) as _,
 
+            SyscallId::CLOCK_GETRES => ruxos_posix_api::sys_clock_getres(
+                args[0] as c_int,
+                args[1] as *mut ctypes::timespec,
+            ) as _,
+
+            #[allow(unreachable_code)]
+            SyscallId::EXIT_GROUP => ruxos_posix_api::sys_exit_group(args[0] as c_int) as _,
+
             #[cfg(feature = "epoll")]
             SyscallId::EPOLL_WAIT => ruxos_posix_api::sys_epoll_wait(
                 args[0] as c_int,
@@ -476,6 +653,15 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[2] as ctypes::mode_t,
             ) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::FCHOWNAT => ruxos_posix_api::sys_fchownat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as ctypes::uid_t,
+                args[3] as ctypes::gid_t,
+                args[4] as c_int,
+            ) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::NEWFSTATAT => ruxos_posix_api::sys_newfstatat(
                 args[0] as c_int,
@@ -499,6 +685,22 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[3] as *const core::ffi::c_char,
             ) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::LINKAT => ruxos_posix_api::sys_linkat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as c_int,
+                args[3] as *const core::ffi::c_char,
+                args[4] as c_int,
+            ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::SYMLINKAT => ruxos_posix_api::sys_symlinkat(
+                args[0] as *const core::ffi::c_char,
+                args[1] as c_int,
+                args[2] as *const core::ffi::c_char,
+            ) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::READLINKAT => ruxos_posix_api::sys_readlinkat(
                 args[0] as c_int,
@@ -507,6 +709,14 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[3],
             ) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::FCHMODAT => ruxos_posix_api::sys_fchmodat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as ctypes::mode_t,
+                0,
+            ) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::FACCESSAT => ruxos_posix_api::sys_faccessat(
                 args[0] as c_int,
@@ -534,6 +744,23 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[4] as ctypes::size_t,
             ) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::UNSHARE => ruxos_posix_api::sys_unshare(args[0] as c_int) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::MOUNT => ruxos_posix_api::sys_mount(
+                args[0] as *const c_char,
+                args[1] as *const c_char,
+                args[2] as *const c_char,
+                args[3] as c_ulong,
+                args[4] as *const c_void,
+            ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::UMOUNT2 => {
+                ruxos_posix_api::sys_umount2(args[0] as *const c_char, args[1] as c_int) as _
+            }
+
             #[cfg(feature = "epoll")]
             SyscallId::EPOLL_PWAIT => ruxos_posix_api::sys_epoll_pwait(
                 args[0] as c_int,
@@ -544,6 +771,50 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[5] as *const ctypes::size_t,
             ) as _,
 
+            #[cfg(all(feature = "signal", feature = "fd"))]
+            SyscallId::SIGNALFD => ruxos_posix_api::sys_signalfd(
+                args[0] as c_int,
+                args[1] as *const ctypes::sigset_t,
+                args[2],
+            ) as _,
+
+            #[cfg(all(feature = "signal", feature = "fd"))]
+            SyscallId::SIGNALFD4 => ruxos_posix_api::sys_signalfd4(
+                args[0] as c_int,
+                args[1] as *const ctypes::sigset_t,
+                args[2],
+                args[3] as c_int,
+            ) as _,
+
+            #[cfg(feature = "fd")]
+            SyscallId::TIMERFD_CREATE => {
+                ruxos_posix_api::sys_timerfd_create(args[0] as c_int, args[1] as c_int) as _
+            }
+
+            #[cfg(feature = "fd")]
+            SyscallId::TIMERFD_SETTIME => ruxos_posix_api::sys_timerfd_settime(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as *const ctypes::itimerspec,
+                args[3] as *mut ctypes::itimerspec,
+            ) as _,
+
+            #[cfg(feature = "fd")]
+            SyscallId::TIMERFD_GETTIME => ruxos_posix_api::sys_timerfd_gettime(
+                args[0] as c_int,
+                args[1] as *mut ctypes::itimerspec,
+            ) as _,
+
+            #[cfg(feature = "fd")]
+            SyscallId::EVENTFD => {
+                ruxos_posix_api::sys_eventfd(args[0] as core::ffi::c_uint, 0) as _
+            }
+
+            #[cfg(feature = "fd")]
+            SyscallId::EVENTFD2 => {
+                ruxos_posix_api::sys_eventfd2(args[0] as core::ffi::c_uint, args[1] as c_int) as _
+            }
+
             #[cfg(feature = "epoll")]
             SyscallId::EPOLL_CREATE1 => ruxos_posix_api::sys_epoll_create(args[0] as c_int) as _,
 
@@ -578,6 +849,22 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[1] as ctypes::size_t,
                 args[2] as c_int,
             ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::OPENAT2 => ruxos_posix_api::sys_openat2(
+                args[0] as c_int,
+                args[1] as *const c_char,
+                args[2] as *const ctypes::open_how,
+                args[3] as ctypes::size_t,
+            ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::FACCESSAT2 => ruxos_posix_api::sys_faccessat(
+                args[0] as c_int,
+                args[1] as *const c_char,
+                args[2] as c_int,
+                args[3] as c_int,
+            ) as _,
         }
     }
 }
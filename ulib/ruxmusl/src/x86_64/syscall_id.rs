@@ -108,6 +108,9 @@ pub enum SyscallId {
     #[cfg(feature = "net")]
     SENDMSG = 46,
 
+    #[cfg(feature = "net")]
+    RECVMSG = 47,
+
     #[cfg(feature = "net")]
     SHUTDOWN = 48,
 
@@ -123,9 +126,15 @@ pub enum SyscallId {
     #[cfg(feature = "net")]
     GETPEERNAME = 52,
 
+    #[cfg(feature = "net")]
+    SOCKETPAIR = 53,
+
     #[cfg(feature = "net")]
     SETSOCKOPT = 54,
 
+    #[cfg(feature = "net")]
+    GETSOCKOPT = 55,
+
     // TODO: check clone
     #[cfg(feature = "multitask")]
     CLONE = 56,
@@ -135,6 +144,8 @@ pub enum SyscallId {
 
     EXIT = 60,
 
+    WAIT4 = 61,
+
     #[cfg(feature = "signal")]
     KILL = 62,
 
@@ -149,6 +160,12 @@ pub enum SyscallId {
     #[cfg(feature = "fs")]
     FDATASYNC = 75,
 
+    #[cfg(feature = "fs")]
+    TRUNCATE = 76,
+
+    #[cfg(feature = "fd")]
+    FTRUNCATE = 77,
+
     #[cfg(feature = "fs")]
     GETDENTS = 78,
 
@@ -158,6 +175,9 @@ pub enum SyscallId {
     #[cfg(feature = "fs")]
     CHDIR = 80,
 
+    #[cfg(feature = "fs")]
+    FCHDIR = 81,
+
     #[cfg(feature = "fs")]
     RENAME = 82,
 
@@ -167,18 +187,41 @@ pub enum SyscallId {
     #[cfg(feature = "fs")]
     RMDIR = 84,
 
+    #[cfg(feature = "fs")]
+    LINK = 86,
+
     #[cfg(feature = "fs")]
     UNLINK = 87,
 
+    #[cfg(feature = "fs")]
+    SYMLINK = 88,
+
     #[cfg(feature = "fs")]
     READLINK = 89,
 
+    #[cfg(feature = "fs")]
+    CHMOD = 90,
+
+    #[cfg(feature = "fs")]
+    FCHMOD = 91,
+
+    #[cfg(feature = "fs")]
+    CHOWN = 92,
+
+    #[cfg(feature = "fs")]
+    FCHOWN = 93,
+
+    #[cfg(feature = "fs")]
+    LCHOWN = 94,
+
     UMASK = 95,
 
     GETTIMEOFDAY = 96,
 
     GETRLIMIT = 97,
 
+    GETRUSAGE = 98,
+
     SYSINFO = 99,
 
     TIMES = 100,
@@ -200,16 +243,44 @@ pub enum SyscallId {
     #[cfg(feature = "signal")]
     SIGALTSTACK = 131,
 
+    #[cfg(feature = "fs")]
+    STATFS = 137,
+
+    #[cfg(feature = "multitask")]
+    SCHED_GETPARAM = 143,
+
+    #[cfg(feature = "multitask")]
+    SCHED_SETSCHEDULER = 144,
+
+    #[cfg(feature = "multitask")]
+    SCHED_GETSCHEDULER = 145,
+
+    #[cfg(feature = "multitask")]
+    SCHED_GET_PRIORITY_MAX = 146,
+
+    #[cfg(feature = "multitask")]
+    SCHED_GET_PRIORITY_MIN = 147,
+
     PRCTL = 157,
 
     ARCH_PRCTL = 158,
 
+    SETRLIMIT = 160,
+
+    SETTIMEOFDAY = 164,
+
     #[cfg(feature = "multitask")]
     GETTID = 186,
 
     #[cfg(feature = "multitask")]
     FUTEX = 202,
 
+    #[cfg(feature = "multitask")]
+    SCHED_SETAFFINITY = 203,
+
+    #[cfg(feature = "multitask")]
+    SCHED_GETAFFINITY = 204,
+
     #[cfg(feature = "epoll")]
     EPOLL_CREATE = 213,
 
@@ -223,6 +294,10 @@ pub enum SyscallId {
 
     CLOCK_GETTIME = 228,
 
+    CLOCK_GETRES = 229,
+
+    EXIT_GROUP = 231,
+
     #[cfg(feature = "epoll")]
     EPOLL_WAIT = 232,
 
@@ -235,6 +310,9 @@ pub enum SyscallId {
     #[cfg(feature = "fs")]
     MKDIRAT = 258,
 
+    #[cfg(feature = "fs")]
+    FCHOWNAT = 260,
+
     #[cfg(feature = "fs")]
     NEWFSTATAT = 262,
 
@@ -244,9 +322,18 @@ pub enum SyscallId {
     #[cfg(feature = "fs")]
     RENAMEAT = 264,
 
+    #[cfg(feature = "fs")]
+    LINKAT = 265,
+
+    #[cfg(feature = "fs")]
+    SYMLINKAT = 266,
+
     #[cfg(feature = "fs")]
     READLINKAT = 267,
 
+    #[cfg(feature = "fs")]
+    FCHMODAT = 268,
+
     #[cfg(feature = "fs")]
     FACCESSAT = 269,
 
@@ -256,9 +343,45 @@ pub enum SyscallId {
     #[cfg(feature = "poll")]
     PPOLL = 271,
 
+    #[cfg(feature = "fs")]
+    UNSHARE = 272,
+
+    #[cfg(feature = "fs")]
+    MOUNT = 165,
+
+    #[cfg(feature = "fs")]
+    UMOUNT2 = 166,
+
     #[cfg(feature = "epoll")]
     EPOLL_PWAIT = 281,
 
+    #[cfg(all(feature = "signal", feature = "fd"))]
+    SIGNALFD = 282,
+
+    #[cfg(feature = "fd")]
+    TIMERFD_CREATE = 283,
+
+    #[cfg(feature = "fd")]
+    EVENTFD = 284,
+
+    #[cfg(feature = "fd")]
+    FALLOCATE = 285,
+
+    #[cfg(feature = "fd")]
+    TIMERFD_SETTIME = 286,
+
+    #[cfg(feature = "fd")]
+    TIMERFD_GETTIME = 287,
+
+    #[cfg(feature = "net")]
+    ACCEPT4 = 288,
+
+    #[cfg(all(feature = "signal", feature = "fd"))]
+    SIGNALFD4 = 289,
+
+    #[cfg(feature = "fd")]
+    EVENTFD2 = 290,
+
     #[cfg(feature = "epoll")]
     EPOLL_CREATE1 = 291,
 
@@ -274,4 +397,10 @@ pub enum SyscallId {
     PRLIMIT64 = 302,
 
     GETRANDOM = 318,
+
+    #[cfg(feature = "fs")]
+    OPENAT2 = 437,
+
+    #[cfg(feature = "fs")]
+    FACCESSAT2 = 439,
 }